@@ -23,27 +23,28 @@ export const controllers = {
   },
   home: {
     index: (): RouteConfig => ({ url: '/', method: 'get' }),
-    protected: (): RouteConfig => ({ url: '/protected', method: 'get' })
+    home: (): RouteConfig => ({ url: '/protected', method: 'get' })
   },
   todo: {
     list: (): RouteConfig => ({ url: '/todos', method: 'get' }),
     create_random: (): RouteConfig => ({ url: '/todos/random', method: 'post' })
   },
   user: {
+    redirect_example: (): RouteConfig => ({ url: '/redirect-example', method: 'get' }),
     index: (): RouteConfig => ({ url: '/users', method: 'get' }),
     show: (params: UserShowParams): RouteConfig => ({ url: `/users/${params.id}`, method: 'get' }),
-    store: (): RouteConfig => ({ url: '/users', method: 'post' }),
-    redirect_example: (): RouteConfig => ({ url: '/redirect-example', method: 'get' })
+    store: (): RouteConfig => ({ url: '/users', method: 'post' })
   }
 } as const;
 
 // Named routes lookup
 export const routes = {
   'home': controllers.home.index,
+  'config.show': controllers.config_example.show,
   'users.index': controllers.user.index,
   'users.show': controllers.user.show,
   'users.store': controllers.user.store,
-  'config.show': controllers.config_example.show,
+  'protected.home': controllers.home.index,
   'todos.index': controllers.todo.list,
   'todos.create_random': controllers.todo.create_random
 } as const;
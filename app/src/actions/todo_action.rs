@@ -1,7 +1,7 @@
 //! Todo actions
 
 use kit::database::{Model, ModelMut};
-use kit::injectable;
+use kit::{async_trait, injectable, Action, FrameworkError};
 use sea_orm::Set;
 
 use crate::models::todos;
@@ -9,8 +9,12 @@ use crate::models::todos;
 #[injectable]
 pub struct CreateRandomTodoAction;
 
-impl CreateRandomTodoAction {
-    pub async fn execute(&self) -> Result<todos::Model, kit::error::FrameworkError> {
+#[async_trait]
+impl Action for CreateRandomTodoAction {
+    type Input = ();
+    type Output = todos::Model;
+
+    async fn handle(&self, _input: ()) -> Result<todos::Model, FrameworkError> {
         let random_num = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -33,8 +37,12 @@ impl CreateRandomTodoAction {
 #[injectable]
 pub struct ListTodosAction;
 
-impl ListTodosAction {
-    pub async fn execute(&self) -> Result<Vec<todos::Model>, kit::error::FrameworkError> {
+#[async_trait]
+impl Action for ListTodosAction {
+    type Input = ();
+    type Output = Vec<todos::Model>;
+
+    async fn handle(&self, _input: ()) -> Result<Vec<todos::Model>, FrameworkError> {
         todos::Entity::all().await
     }
 }
@@ -13,7 +13,7 @@
 //! // For services needing runtime config, register here:
 //! pub async fn register() {
 //!     // Initialize database
-//!     DB::init().await.expect("Failed to connect to database");
+//!     DB::init().await.expect("Failed to configure database");
 //!
 //!     // Global middleware
 //!     global_middleware!(middleware::LoggingMiddleware);
@@ -34,8 +34,8 @@ use crate::providers::DatabaseUserProvider;
 /// Called from cmd/main.rs before `Server::from_config()`.
 /// Middleware and services registered here can use environment variables, config files, etc.
 pub async fn register() {
-    // Initialize database connection
-    DB::init().await.expect("Failed to connect to database");
+    // Register the database connection (connects on first query)
+    DB::init().await.expect("Failed to configure database");
 
     // Global middleware (runs on every request in registration order)
     global_middleware!(middleware::LoggingMiddleware);
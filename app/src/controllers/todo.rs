@@ -1,11 +1,9 @@
-use kit::{json_response, App, Request, Response, ResponseExt};
+use kit::{json_response, Action, Request, Response, ResponseExt};
 
 use crate::actions::todo_action::{CreateRandomTodoAction, ListTodosAction};
 
 pub async fn create_random(_req: Request) -> Response {
-    let action = App::resolve::<CreateRandomTodoAction>()?;
-
-    match action.execute().await {
+    match CreateRandomTodoAction::dispatch(()).await {
         Ok(todo) => json_response!({
             "success": true,
             "todo": todo
@@ -20,9 +18,7 @@ pub async fn create_random(_req: Request) -> Response {
 }
 
 pub async fn list(_req: Request) -> Response {
-    let action = App::resolve::<ListTodosAction>()?;
-
-    match action.execute().await {
+    match ListTodosAction::dispatch(()).await {
         Ok(todos) => json_response!({
             "success": true,
             "todos": todos
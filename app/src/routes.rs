@@ -10,10 +10,10 @@ routes! {
 
     // User routes group
     group!("/users", {
-        get!("/", controllers::user::index).name("users.index"),
-        get!("/{id}", controllers::user::show).name("users.show"),
-        post!("/", controllers::user::store).name("users.store"),
-    }),
+        get!("/", controllers::user::index).name("index"),
+        get!("/{id}", controllers::user::show).name("show"),
+        post!("/", controllers::user::store).name("store"),
+    }).name_prefix("users."),
 
     // Protected routes - requires Authorization header
     group!("/protected", {
@@ -0,0 +1,84 @@
+//! Contended `App::make`: the `ArcSwap` snapshot the global container uses
+//! against the `RwLock` it replaced
+//!
+//! All threads resolve the same trait binding. `App::make` loads it from the
+//! one global container; the baseline reads it through one shared `RwLock`,
+//! as `App::make` did before. `App::make` also checks the thread-local test
+//! overrides first, which the baseline skips. Run in release mode on a
+//! multi-core machine; a single core has no contention to remove.
+//!
+//! ```bash
+//! cargo bench -p kit-rs --bench container
+//! ```
+
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use kit_rs::{App, Container};
+
+const RESOLUTIONS_PER_THREAD: usize = 1_000_000;
+
+trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        42
+    }
+}
+
+/// Time `threads` threads each running `resolve` `per_thread` times
+fn contended(threads: usize, per_thread: usize, resolve: impl Fn() -> u64 + Sync) -> Duration {
+    let started = Instant::now();
+    std::thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..per_thread {
+                    std::hint::black_box(resolve());
+                }
+            });
+        }
+    });
+    started.elapsed()
+}
+
+fn main() {
+    App::bind::<dyn Clock>(Arc::new(FixedClock));
+    let mut container = Container::new();
+    container.bind::<dyn Clock>(Arc::new(FixedClock));
+    let locked = RwLock::new(container);
+
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut thread_counts = vec![1, 2, 4, 8, cores];
+    thread_counts.retain(|threads| *threads <= cores.max(2));
+    thread_counts.sort_unstable();
+    thread_counts.dedup();
+
+    println!(
+        "{} resolutions per thread, {} core(s)",
+        RESOLUTIONS_PER_THREAD, cores
+    );
+    println!("{:>8} {:>14} {:>14}", "threads", "App::make", "RwLock");
+    for threads in thread_counts {
+        let lock_free = contended(threads, RESOLUTIONS_PER_THREAD, || {
+            App::make::<dyn Clock>().unwrap().now()
+        });
+        let rw_lock = contended(threads, RESOLUTIONS_PER_THREAD, || {
+            locked.read().unwrap().make::<dyn Clock>().unwrap().now()
+        });
+        println!(
+            "{:>8} {:>12.1}ns {:>12.1}ns",
+            threads,
+            per_resolution(lock_free),
+            per_resolution(rw_lock)
+        );
+    }
+}
+
+/// Wall-clock nanoseconds per resolution on each thread
+fn per_resolution(elapsed: Duration) -> f64 {
+    elapsed.as_nanos() as f64 / RESOLUTIONS_PER_THREAD as f64
+}
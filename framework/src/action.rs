@@ -0,0 +1,155 @@
+//! Actions: single-purpose units of business logic
+//!
+//! An action is an `#[injectable]` struct implementing `Action`. Dispatching
+//! it resolves the action from the container, checks `authorize` and
+//! `validate`, runs `handle`, and records how long it took (see
+//! `metrics::action_timings`).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{async_trait, injectable, Action, FrameworkError};
+//!
+//! #[injectable]
+//! pub struct CreateTodoAction;
+//!
+//! #[async_trait]
+//! impl Action for CreateTodoAction {
+//!     type Input = String;
+//!     type Output = todos::Model;
+//!
+//!     fn validate(&self, title: &String) -> Result<(), FrameworkError> {
+//!         if title.is_empty() {
+//!             return Err(FrameworkError::validation("title", "The title is required."));
+//!         }
+//!         Ok(())
+//!     }
+//!
+//!     async fn handle(&self, title: String) -> Result<todos::Model, FrameworkError> {
+//!         todos::Model::create(title).await
+//!     }
+//! }
+//!
+//! // In a controller
+//! let todo = CreateTodoAction::dispatch(form.title).await?;
+//!
+//! // In the background
+//! CreateTodoAction::dispatch_async("Water the plants".to_string());
+//! ```
+
+use crate::container::App;
+use crate::error::FrameworkError;
+use crate::metrics;
+use async_trait::async_trait;
+use std::time::Instant;
+use tokio::task::JoinHandle;
+
+/// A unit of business logic dispatched through the container
+#[async_trait]
+pub trait Action: Clone + Send + Sync + 'static {
+    /// What the action is dispatched with
+    type Input: Send + 'static;
+    /// What the action returns
+    type Output: Send + 'static;
+
+    /// Whether the current user may run the action
+    ///
+    /// Dispatching fails with `FrameworkError::Unauthorized` (403) when this
+    /// returns `false`. Allows everyone by default.
+    async fn authorize(&self, _input: &Self::Input) -> Result<bool, FrameworkError> {
+        Ok(true)
+    }
+
+    /// Check the input before `handle` runs
+    fn validate(&self, _input: &Self::Input) -> Result<(), FrameworkError> {
+        Ok(())
+    }
+
+    /// Run the action
+    async fn handle(&self, input: Self::Input) -> Result<Self::Output, FrameworkError>;
+
+    /// Resolve the action from the container and run it
+    async fn dispatch(input: Self::Input) -> Result<Self::Output, FrameworkError> {
+        App::resolve::<Self>()?.run(input).await
+    }
+
+    /// Dispatch the action on a background task
+    ///
+    /// Returns immediately; errors are logged. Await the handle to get the
+    /// result.
+    fn dispatch_async(input: Self::Input) -> JoinHandle<Result<Self::Output, FrameworkError>> {
+        tokio::spawn(async move {
+            let result = Self::dispatch(input).await;
+            if let Err(err) = &result {
+                eprintln!("Action {} failed: {}", std::any::type_name::<Self>(), err);
+            }
+            result
+        })
+    }
+
+    /// Authorize, validate and handle `input` with this instance
+    ///
+    /// Use this when the action isn't registered in the container, e.g. in
+    /// tests.
+    async fn run(&self, input: Self::Input) -> Result<Self::Output, FrameworkError> {
+        let started = Instant::now();
+        let result = async {
+            if !self.authorize(&input).await? {
+                return Err(FrameworkError::Unauthorized);
+            }
+            self.validate(&input)?;
+            self.handle(input).await
+        }
+        .await;
+        metrics::record_action(std::any::type_name::<Self>(), started.elapsed());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct DoubleAction;
+
+    #[async_trait]
+    impl Action for DoubleAction {
+        type Input = i64;
+        type Output = i64;
+
+        async fn authorize(&self, input: &i64) -> Result<bool, FrameworkError> {
+            Ok(*input != 13)
+        }
+
+        fn validate(&self, input: &i64) -> Result<(), FrameworkError> {
+            if *input < 0 {
+                return Err(FrameworkError::validation("input", "Must be positive"));
+            }
+            Ok(())
+        }
+
+        async fn handle(&self, input: i64) -> Result<i64, FrameworkError> {
+            Ok(input * 2)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_checks_authorize_and_validate() {
+        assert_eq!(DoubleAction.run(21).await.unwrap(), 42);
+        assert!(matches!(
+            DoubleAction.run(13).await,
+            Err(FrameworkError::Unauthorized)
+        ));
+        assert!(matches!(
+            DoubleAction.run(-1).await,
+            Err(FrameworkError::ValidationError { .. })
+        ));
+
+        let timing = metrics::action_timings()
+            .into_iter()
+            .find(|timing| timing.name.ends_with("DoubleAction"))
+            .unwrap();
+        assert!(timing.calls >= 3);
+    }
+}
@@ -0,0 +1,83 @@
+//! Activity feed configuration for Kit framework
+
+use crate::config::env;
+
+/// Activity feed configuration
+///
+/// # Environment Variables
+///
+/// - `ACTIVITY_RETENTION_DAYS` - Age after which `Activity::prune` deletes
+///   activities, 0 to keep them forever (default: 365)
+/// - `ACTIVITY_FEED_LIMIT` - Most activities returned by a feed (default: 50)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{ActivityConfig, Config};
+///
+/// // Register from environment
+/// Config::register(ActivityConfig::from_env());
+///
+/// // Or build manually
+/// Config::register(ActivityConfig::builder()
+///     .retention_days(90)
+///     .build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ActivityConfig {
+    /// Age in days after which activities are pruned, 0 to keep them
+    pub retention_days: u64,
+    /// Most activities returned by a feed
+    pub feed_limit: u64,
+}
+
+impl ActivityConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            retention_days: env("ACTIVITY_RETENTION_DAYS", 365),
+            feed_limit: env("ACTIVITY_FEED_LIMIT", 50),
+        }
+    }
+
+    /// Create a builder for manual configuration
+    pub fn builder() -> ActivityConfigBuilder {
+        ActivityConfigBuilder::default()
+    }
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Builder for ActivityConfig
+#[derive(Debug, Default)]
+pub struct ActivityConfigBuilder {
+    retention_days: Option<u64>,
+    feed_limit: Option<u64>,
+}
+
+impl ActivityConfigBuilder {
+    /// Set the age in days after which activities are pruned
+    pub fn retention_days(mut self, days: u64) -> Self {
+        self.retention_days = Some(days);
+        self
+    }
+
+    /// Set the most activities returned by a feed
+    pub fn feed_limit(mut self, limit: u64) -> Self {
+        self.feed_limit = Some(limit);
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> ActivityConfig {
+        let defaults = ActivityConfig::from_env();
+        ActivityConfig {
+            retention_days: self.retention_days.unwrap_or(defaults.retention_days),
+            feed_limit: self.feed_limit.unwrap_or(defaults.feed_limit),
+        }
+    }
+}
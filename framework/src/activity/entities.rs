@@ -0,0 +1,31 @@
+//! SeaORM entity for the activity feed
+//!
+//! The table is created by the migration from `kit activity:install`.
+
+/// What users did to models, for their feeds
+pub mod activities {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "activities")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        /// User who did it, if anyone was logged in
+        pub actor_id: Option<i64>,
+        /// What happened, e.g. "created"
+        pub action: String,
+        /// Table of the model it happened to, e.g. "posts"
+        pub subject_type: String,
+        /// Primary key of the model it happened to
+        pub subject_id: String,
+        /// JSON object with details for the feed
+        pub properties: String,
+        pub created_at: chrono::NaiveDateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
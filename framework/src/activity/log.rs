@@ -0,0 +1,362 @@
+//! Recording activities and reading feeds
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityName, EntityTrait, Iterable, ModelTrait,
+    PrimaryKeyToColumn, QueryFilter, QueryOrder, QuerySelect, Set, Value,
+};
+use serde::Serialize;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+
+use super::config::ActivityConfig;
+use super::entities::activities;
+use crate::auth::{Auth, Authenticatable};
+use crate::config::Config;
+use crate::database::DB;
+use crate::error::FrameworkError;
+use crate::schedule::{Task, TaskResult};
+
+/// Activity facade - records what users do and reads it back as feeds
+pub struct Activity;
+
+impl Activity {
+    /// Start recording that `action` happened to `subject`
+    ///
+    /// The logged in user, if any, is recorded as the actor.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// Activity::log("created", &post).await?;
+    ///
+    /// Activity::log("published", &post)
+    ///     .with_properties(json!({ "title": post.title }))
+    ///     .await?;
+    /// ```
+    pub fn log<M: ModelTrait>(action: impl Into<String>, subject: &M) -> PendingActivity {
+        PendingActivity {
+            action: action.into(),
+            subject: subject_of(subject),
+            actor_id: Auth::id(),
+            properties: serde_json::Value::Object(Default::default()),
+        }
+    }
+
+    /// The newest activities caused by `user`, up to `ACTIVITY_FEED_LIMIT`
+    pub async fn feed_for<U: Authenticatable>(
+        user: &U,
+    ) -> Result<Vec<ActivityItem>, FrameworkError> {
+        Self::feed(activities::Column::ActorId.eq(user.auth_identifier())).await
+    }
+
+    /// The newest activities on `subject`, up to `ACTIVITY_FEED_LIMIT`
+    pub async fn for_subject<M: ModelTrait>(
+        subject: &M,
+    ) -> Result<Vec<ActivityItem>, FrameworkError> {
+        let (subject_type, subject_id) = subject_of(subject)?;
+        Self::feed(
+            activities::Column::SubjectType
+                .eq(subject_type)
+                .and(activities::Column::SubjectId.eq(subject_id)),
+        )
+        .await
+    }
+
+    async fn feed(
+        condition: sea_orm::sea_query::SimpleExpr,
+    ) -> Result<Vec<ActivityItem>, FrameworkError> {
+        let config = Config::get::<ActivityConfig>().unwrap_or_default();
+        let db = DB::connection()?;
+        let activities = activities::Entity::find()
+            .filter(condition)
+            .order_by_desc(activities::Column::CreatedAt)
+            .order_by_desc(activities::Column::Id)
+            .limit(config.feed_limit)
+            .all(db.inner())
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+        Ok(activities.into_iter().map(ActivityItem::from).collect())
+    }
+
+    /// Delete activities older than `ACTIVITY_RETENTION_DAYS`
+    ///
+    /// Returns the number deleted. Schedule `PruneActivities` to run this
+    /// daily.
+    pub async fn prune() -> Result<u64, FrameworkError> {
+        let config = Config::get::<ActivityConfig>().unwrap_or_default();
+        if config.retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = Utc::now().naive_utc() - Duration::days(config.retention_days as i64);
+
+        let db = DB::connection()?;
+        let result = activities::Entity::delete_many()
+            .filter(activities::Column::CreatedAt.lt(cutoff))
+            .exec(db.inner())
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+        Ok(result.rows_affected)
+    }
+}
+
+/// An activity about to be recorded
+///
+/// Await it (or call `save()`) to insert the row.
+#[must_use = "activities are only recorded when awaited"]
+pub struct PendingActivity {
+    action: String,
+    subject: Result<(String, String), FrameworkError>,
+    actor_id: Option<i64>,
+    properties: serde_json::Value,
+}
+
+impl PendingActivity {
+    /// Record `user_id` as the actor instead of the logged in user
+    pub fn by(mut self, user_id: i64) -> Self {
+        self.actor_id = Some(user_id);
+        self
+    }
+
+    /// Attach details for the feed, such as the title at the time
+    pub fn with_properties(mut self, properties: serde_json::Value) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Insert the activity
+    pub async fn save(self) -> Result<activities::Model, FrameworkError> {
+        let (subject_type, subject_id) = self.subject?;
+        let db = DB::connection()?;
+        activities::ActiveModel {
+            actor_id: Set(self.actor_id),
+            action: Set(self.action),
+            subject_type: Set(subject_type),
+            subject_id: Set(subject_id),
+            properties: Set(self.properties.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .insert(db.inner())
+        .await
+        .map_err(|e| FrameworkError::database(e.to_string()))
+    }
+}
+
+impl IntoFuture for PendingActivity {
+    type Output = Result<activities::Model, FrameworkError>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.save())
+    }
+}
+
+/// An activity as sent to the frontend
+///
+/// `kit generate-types` emits a matching `ActivityItem` interface for props
+/// that use it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActivityItem {
+    pub id: i64,
+    pub actor_id: Option<i64>,
+    pub action: String,
+    pub subject_type: String,
+    pub subject_id: String,
+    pub properties: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<activities::Model> for ActivityItem {
+    fn from(activity: activities::Model) -> Self {
+        Self {
+            id: activity.id,
+            actor_id: activity.actor_id,
+            action: activity.action,
+            subject_type: activity.subject_type,
+            subject_id: activity.subject_id,
+            properties: serde_json::from_str(&activity.properties).unwrap_or_default(),
+            created_at: activity.created_at,
+        }
+    }
+}
+
+/// Scheduled task running `Activity::prune`
+///
+/// ```rust,ignore
+/// schedule.add(schedule.task(PruneActivities).daily().name("activity:prune"));
+/// ```
+pub struct PruneActivities;
+
+#[async_trait]
+impl Task for PruneActivities {
+    async fn handle(&self) -> TaskResult {
+        Activity::prune().await.map(|_| ())
+    }
+}
+
+/// The table and primary key of a model
+fn subject_of<M: ModelTrait>(subject: &M) -> Result<(String, String), FrameworkError> {
+    let table = M::Entity::default().table_name().to_string();
+    let key = <M::Entity as EntityTrait>::PrimaryKey::iter()
+        .next()
+        .ok_or_else(|| FrameworkError::internal(format!("{} has no primary key", table)))?;
+
+    let id = match subject.get(key.into_column()) {
+        Value::TinyInt(Some(id)) => id.to_string(),
+        Value::SmallInt(Some(id)) => id.to_string(),
+        Value::Int(Some(id)) => id.to_string(),
+        Value::BigInt(Some(id)) => id.to_string(),
+        Value::TinyUnsigned(Some(id)) => id.to_string(),
+        Value::SmallUnsigned(Some(id)) => id.to_string(),
+        Value::Unsigned(Some(id)) => id.to_string(),
+        Value::BigUnsigned(Some(id)) => id.to_string(),
+        Value::String(Some(id)) => id.to_string(),
+        other => {
+            return Err(FrameworkError::internal(format!(
+                "Unsupported primary key {:?} on {}",
+                other, table
+            )))
+        }
+    };
+    Ok((table, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDatabase;
+    use sea_orm::ConnectionTrait;
+    use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager};
+    use serde_json::json;
+    use std::any::Any;
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreateActivitiesTable)]
+        }
+    }
+
+    struct CreateActivitiesTable;
+
+    impl MigrationName for CreateActivitiesTable {
+        fn name(&self) -> &str {
+            "create_activities_table"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreateActivitiesTable {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), sea_orm::DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "CREATE TABLE activities (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        actor_id INTEGER NULL,
+                        action TEXT NOT NULL,
+                        subject_type TEXT NOT NULL,
+                        subject_id TEXT NOT NULL,
+                        properties TEXT NOT NULL,
+                        created_at TIMESTAMP NOT NULL
+                    )",
+                )
+                .await?;
+            Ok(())
+        }
+    }
+
+    mod post {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "posts")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i64,
+            pub title: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    struct User(i64);
+
+    impl Authenticatable for User {
+        fn auth_identifier(&self) -> i64 {
+            self.0
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn post(id: i64) -> post::Model {
+        post::Model {
+            id,
+            title: format!("Post {}", id),
+        }
+    }
+
+    #[tokio::test]
+    async fn activities_are_logged_and_read_back_as_feeds() {
+        let _db = TestDatabase::fresh::<Migrator>().await.unwrap();
+
+        let created = Activity::log("created", &post(1))
+            .by(7)
+            .with_properties(json!({ "title": "Post 1" }))
+            .await
+            .unwrap();
+        assert_eq!(created.subject_type, "posts");
+        assert_eq!(created.subject_id, "1");
+        Activity::log("published", &post(1)).by(7).await.unwrap();
+        Activity::log("created", &post(2)).by(8).await.unwrap();
+        let anonymous = Activity::log("viewed", &post(2)).await.unwrap();
+        assert_eq!(anonymous.actor_id, None);
+
+        let feed = Activity::feed_for(&User(7)).await.unwrap();
+        assert_eq!(
+            feed.iter().map(|a| a.action.as_str()).collect::<Vec<_>>(),
+            ["published", "created"]
+        );
+        assert_eq!(feed[1].properties, json!({ "title": "Post 1" }));
+        assert_eq!(feed[0].properties, json!({}));
+
+        let on_post = Activity::for_subject(&post(2)).await.unwrap();
+        assert_eq!(
+            on_post
+                .iter()
+                .map(|a| a.action.as_str())
+                .collect::<Vec<_>>(),
+            ["viewed", "created"]
+        );
+    }
+
+    #[tokio::test]
+    async fn prune_deletes_activities_past_retention() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+
+        Activity::log("created", &post(1)).by(7).await.unwrap();
+        db.conn()
+            .execute_unprepared(
+                "INSERT INTO activities
+                    (actor_id, action, subject_type, subject_id, properties, created_at)
+                 VALUES (7, 'created', 'posts', '2', '{}', '2020-01-01 00:00:00')",
+            )
+            .await
+            .unwrap();
+
+        assert!(PruneActivities.handle().await.is_ok());
+        let feed = Activity::feed_for(&User(7)).await.unwrap();
+        assert_eq!(feed.len(), 1);
+        assert_eq!(feed[0].subject_id, "1");
+    }
+}
@@ -0,0 +1,31 @@
+//! User-facing activity feeds
+//!
+//! Opt in with `kit activity:install`, which adds the `activities` migration.
+//! Each activity records who did what to which model, with optional
+//! properties to show in the feed, so a page can list "Ada published
+//! 'Hello'" without joining every table.
+//!
+//! # Quick Start
+//!
+//! ```rust,ignore
+//! use kit::Activity;
+//!
+//! // The logged in user is recorded as the actor
+//! Activity::log("created", &post).await?;
+//!
+//! // A user's timeline, newest first
+//! let feed = Activity::feed_for(&user).await?;
+//!
+//! // Everything that happened to a post
+//! let history = Activity::for_subject(&post).await?;
+//! ```
+//!
+//! Activities older than `ACTIVITY_RETENTION_DAYS` are deleted by
+//! `Activity::prune`; schedule the `PruneActivities` task to run it.
+
+pub mod config;
+pub mod entities;
+pub mod log;
+
+pub use config::{ActivityConfig, ActivityConfigBuilder};
+pub use log::{Activity, ActivityItem, PendingActivity, PruneActivities};
@@ -19,13 +19,20 @@
 //! }
 //! ```
 
-use crate::{Config, Router, Server};
-use clap::{Parser, Subcommand};
+use crate::bench::{Bench, BenchOptions};
+use crate::daemon::{Daemon, DaemonOptions};
+use crate::startup::{Boot, BootPhases};
+use crate::{App, Config, Router, Schedule, Server, Supervisor, SupervisorConfig};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use sea_orm_migration::prelude::*;
 use std::env;
 use std::future::Future;
 use std::path::Path;
 use std::pin::Pin;
+use std::time::Instant;
+
+/// Where `route:cache` writes the route manifest read by `redirect!`
+const ROUTE_MANIFEST: &str = ".kit/routes.json";
 
 /// CLI structure for Kit applications
 #[derive(Parser)]
@@ -43,6 +50,9 @@ enum Commands {
         /// Skip running migrations on startup
         #[arg(long)]
         no_migrate: bool,
+        /// Print only the address instead of the startup summary
+        #[arg(long)]
+        quiet: bool,
     },
     /// Run the web server (alias for serve)
     #[command(name = "web:run")]
@@ -50,6 +60,9 @@ enum Commands {
         /// Skip running migrations on startup
         #[arg(long)]
         no_migrate: bool,
+        /// Print only the address instead of the startup summary
+        #[arg(long)]
+        quiet: bool,
     },
     /// Run pending database migrations
     Migrate,
@@ -68,18 +81,68 @@ enum Commands {
     MigrateFresh,
     /// Run the scheduler daemon (checks every minute)
     #[command(name = "schedule:work")]
-    ScheduleWork,
+    ScheduleWork {
+        #[command(flatten)]
+        daemon: DaemonOptions,
+    },
     /// Run all due scheduled tasks once
     #[command(name = "schedule:run")]
     ScheduleRun,
     /// List all registered scheduled tasks
     #[command(name = "schedule:list")]
     ScheduleList,
+    /// List all registered routes
+    #[command(name = "routes:list")]
+    RoutesList,
+    /// Write the route manifest used by compile-time route checks
+    #[command(name = "route:cache")]
+    RouteCache {
+        /// Where to write the manifest
+        #[arg(long, default_value = ROUTE_MANIFEST)]
+        path: String,
+    },
+    /// Run the scheduler and workflow worker pools from supervisor.toml
+    Work {
+        /// Restart crashed workers and show a live status dashboard
+        #[arg(long)]
+        supervise: bool,
+        /// Print status as JSON lines instead of the dashboard
+        #[arg(long)]
+        json: bool,
+        /// Path to the supervisor configuration file
+        #[arg(long, default_value = crate::supervisor::DEFAULT_CONFIG_FILE)]
+        config: String,
+        #[command(flatten)]
+        daemon: DaemonOptions,
+    },
     /// Run the workflow worker daemon
     #[command(name = "workflow:work")]
-    WorkflowWork,
+    WorkflowWork {
+        #[command(flatten)]
+        daemon: DaemonOptions,
+    },
+    /// Run the queue worker daemon
+    #[command(name = "queue:work")]
+    QueueWork {
+        /// Queues to work, highest priority first (default: QUEUE_NAME)
+        #[arg(long, value_delimiter = ',')]
+        queue: Vec<String>,
+        #[command(flatten)]
+        daemon: DaemonOptions,
+    },
+    /// Benchmark a route in-process and report latency percentiles
+    Bench {
+        #[command(flatten)]
+        options: BenchOptions,
+    },
 }
 
+/// Boxed async bootstrap function registered via `.bootstrap()`
+type BootstrapFn = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
+/// Boxed schedule registration function registered via `.schedule()`
+type ScheduleFn = Box<dyn FnOnce(&mut Schedule)>;
+
 /// Application builder for Kit framework
 ///
 /// Use this to configure and run your Kit application with a fluent API.
@@ -88,8 +151,9 @@ where
     M: MigratorTrait,
 {
     config_fn: Option<Box<dyn FnOnce()>>,
-    bootstrap_fn: Option<Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>>,
+    bootstrap_fn: Option<BootstrapFn>,
     routes_fn: Option<Box<dyn FnOnce() -> Router + Send>>,
+    schedule_fn: Option<ScheduleFn>,
     _migrator: std::marker::PhantomData<M>,
 }
 
@@ -109,6 +173,7 @@ impl Application<NoMigrator> {
             config_fn: None,
             bootstrap_fn: None,
             routes_fn: None,
+            schedule_fn: None,
             _migrator: std::marker::PhantomData,
         }
     }
@@ -181,6 +246,25 @@ where
         self
     }
 
+    /// Register the scheduled tasks function
+    ///
+    /// This function is used by the `schedule:*` commands to build the
+    /// task schedule.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// Application::new()
+    ///     .schedule(schedule::register)
+    /// ```
+    pub fn schedule<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut Schedule) + 'static,
+    {
+        self.schedule_fn = Some(Box::new(f));
+        self
+    }
+
     /// Configure the migrator type for database migrations
     ///
     /// # Example
@@ -197,6 +281,7 @@ where
             config_fn: self.config_fn,
             bootstrap_fn: self.bootstrap_fn,
             routes_fn: self.routes_fn,
+            schedule_fn: self.schedule_fn,
             _migrator: std::marker::PhantomData,
         }
     }
@@ -211,10 +296,16 @@ where
     /// - `migrate:rollback`: Rollback migrations
     /// - `migrate:fresh`: Drop and re-run all migrations
     /// - `schedule:*`: Scheduler commands
+    ///
+    /// Commands registered with `#[console_command]` are also available and
+    /// run after config and bootstrap.
     pub async fn run(self) {
-        let cli = Cli::parse();
+        let matches = crate::console::commands()
+            .fold(Cli::command(), |cli, entry| cli.subcommand(entry.to_clap()))
+            .get_matches();
 
         // Initialize framework configuration (loads .env files)
+        let started = Instant::now();
         Config::init(Path::new("."));
 
         // Destructure self to avoid partial move issues
@@ -222,26 +313,52 @@ where
             config_fn,
             bootstrap_fn,
             routes_fn,
+            schedule_fn,
             _migrator,
         } = self;
 
+        // Benchmarks run against a throwaway database unless told otherwise
+        if let Some(("bench", args)) = matches.subcommand() {
+            if !args.get_flag("env_db") {
+                Self::use_in_memory_database();
+            }
+        }
+
         // Run user's config registration
         if let Some(config_fn) = config_fn {
             config_fn();
         }
+        let mut phases = BootPhases::new();
+        phases.record("config", started);
+
+        // Application-defined console commands
+        if let Some((name, args)) = matches.subcommand() {
+            if let Some(entry) = crate::console::find(name) {
+                Self::run_console_command_internal(entry, args, bootstrap_fn).await;
+                return;
+            }
+        }
+
+        let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
         match cli.command {
-            None
-            | Some(Commands::Serve { no_migrate: false })
-            | Some(Commands::WebRun { no_migrate: false }) => {
+            None => {
                 // Default: run server with auto-migrate
-                Self::run_migrations_silent::<M>().await;
-                Self::run_server_internal(bootstrap_fn, routes_fn).await;
+                phases
+                    .time_async("migrations", Self::run_migrations_silent::<M>())
+                    .await;
+                Self::run_server_internal(bootstrap_fn, routes_fn, schedule_fn, phases, false)
+                    .await;
             }
-            Some(Commands::Serve { no_migrate: true })
-            | Some(Commands::WebRun { no_migrate: true }) => {
-                // Run server without migrations
-                Self::run_server_internal(bootstrap_fn, routes_fn).await;
+            Some(Commands::Serve { no_migrate, quiet })
+            | Some(Commands::WebRun { no_migrate, quiet }) => {
+                if !no_migrate {
+                    phases
+                        .time_async("migrations", Self::run_migrations_silent::<M>())
+                        .await;
+                }
+                Self::run_server_internal(bootstrap_fn, routes_fn, schedule_fn, phases, quiet)
+                    .await;
             }
             Some(Commands::Migrate) => {
                 Self::run_migrations::<M>().await;
@@ -255,42 +372,106 @@ where
             Some(Commands::MigrateFresh) => {
                 Self::fresh_migrations::<M>().await;
             }
-            Some(Commands::ScheduleWork) => {
-                Self::run_scheduler_daemon_internal(bootstrap_fn).await;
+            Some(Commands::ScheduleWork { daemon }) => {
+                Self::run_scheduler_daemon_internal(bootstrap_fn, schedule_fn, daemon).await;
             }
             Some(Commands::ScheduleRun) => {
-                Self::run_scheduled_tasks_internal(bootstrap_fn).await;
+                Self::run_scheduled_tasks_internal(bootstrap_fn, schedule_fn).await;
             }
             Some(Commands::ScheduleList) => {
-                Self::list_scheduled_tasks().await;
+                Self::list_scheduled_tasks(schedule_fn).await;
+            }
+            Some(Commands::RoutesList) => {
+                Self::list_routes(routes_fn);
+            }
+            Some(Commands::RouteCache { path }) => {
+                Self::cache_routes(routes_fn, Path::new(&path));
+            }
+            Some(Commands::Work {
+                supervise,
+                json,
+                config,
+                daemon,
+            }) => {
+                Self::run_supervisor_internal(bootstrap_fn, schedule_fn, supervise, json, config, daemon)
+                    .await;
+            }
+            Some(Commands::WorkflowWork { daemon }) => {
+                Self::run_workflow_worker_internal(bootstrap_fn, daemon).await;
+            }
+            Some(Commands::QueueWork { queue, daemon }) => {
+                Self::run_queue_worker_internal(bootstrap_fn, queue, daemon).await;
             }
-            Some(Commands::WorkflowWork) => {
-                Self::run_workflow_worker_internal(bootstrap_fn).await;
+            Some(Commands::Bench { options }) => {
+                Self::run_bench_internal::<M>(bootstrap_fn, routes_fn, options).await;
             }
         }
     }
 
     async fn run_server_internal(
-        bootstrap_fn: Option<Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>>,
+        bootstrap_fn: Option<BootstrapFn>,
         routes_fn: Option<Box<dyn FnOnce() -> Router + Send>>,
+        schedule_fn: Option<ScheduleFn>,
+        mut phases: BootPhases,
+        quiet: bool,
     ) {
         // Run bootstrap
         if let Some(bootstrap_fn) = bootstrap_fn {
-            bootstrap_fn().await;
+            phases.time_async("bootstrap", bootstrap_fn()).await;
         }
 
-        // Get router
-        let router = if let Some(routes_fn) = routes_fn {
-            routes_fn()
+        // Other commands connect on first query; the server fails fast instead
+        if crate::DB::is_connected() {
+            if let Err(e) = phases.time_async("database", crate::DB::warm_up()).await {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+
+        // Get router, refusing to start with routes that couldn't be registered
+        let router = phases.time("routes", || match routes_fn {
+            Some(routes_fn) => routes_fn(),
+            None => Router::new(),
+        });
+        let router = router.try_build().unwrap_or_else(|errors| {
+            eprintln!("{}", errors);
+            std::process::exit(1);
+        });
+
+        // Create server with configuration from environment
+        let server = phases.time("services", || Server::from_config(router));
+        let server = if quiet {
+            server
         } else {
-            Router::new()
+            // The scheduler runs in its own process; count what it would run
+            let mut schedule = Schedule::new();
+            if let Some(schedule_fn) = schedule_fn {
+                schedule_fn(&mut schedule);
+            }
+            server.startup(Boot {
+                phases,
+                scheduled_tasks: (schedule.len(), schedule.errors().len()),
+            })
         };
+        server.run().await.expect("Failed to start server");
+    }
 
-        // Create server with configuration from environment
-        Server::from_config(router)
-            .run()
-            .await
-            .expect("Failed to start server");
+    async fn run_console_command_internal(
+        entry: &'static crate::console::CommandEntry,
+        args: &clap::ArgMatches,
+        bootstrap_fn: Option<BootstrapFn>,
+    ) {
+        App::init();
+        App::boot_services();
+
+        if let Some(bootstrap_fn) = bootstrap_fn {
+            bootstrap_fn().await;
+        }
+
+        if let Err(e) = (entry.run)(args).await {
+            eprintln!("Command '{}' failed: {}", entry.name, e);
+            std::process::exit(1);
+        }
     }
 
     async fn get_database_connection() -> sea_orm::DatabaseConnection {
@@ -363,51 +544,224 @@ where
         println!("Database refreshed successfully!");
     }
 
+    fn build_schedule(schedule_fn: Option<ScheduleFn>) -> Schedule {
+        let mut schedule = Schedule::new();
+        if let Some(schedule_fn) = schedule_fn {
+            schedule_fn(&mut schedule);
+        }
+        let errors = schedule.errors();
+        if !errors.is_empty() {
+            let plural = if errors.len() == 1 { "" } else { "s" };
+            eprintln!("Found {} invalid scheduled task{}:", errors.len(), plural);
+            for error in errors {
+                eprintln!("  - {}", error);
+            }
+            std::process::exit(1);
+        }
+        schedule
+    }
+
     async fn run_scheduler_daemon_internal(
-        bootstrap_fn: Option<Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>>,
+        bootstrap_fn: Option<BootstrapFn>,
+        schedule_fn: Option<ScheduleFn>,
+        options: DaemonOptions,
     ) {
         // Run bootstrap for scheduler context
         if let Some(bootstrap_fn) = bootstrap_fn {
             bootstrap_fn().await;
         }
 
+        let schedule = Self::build_schedule(schedule_fn);
+
         println!("==============================================");
         println!("  Kit Scheduler Daemon");
         println!("==============================================");
         println!();
-        println!("  Note: Create tasks with `kit make:task <name>`");
+        println!("  Tasks registered: {}", schedule.len());
         println!("  Press Ctrl+C to stop");
         println!();
         println!("==============================================");
 
-        eprintln!("Scheduler daemon is not yet configured.");
-        eprintln!("Create a scheduled task with: kit make:task <name>");
-        eprintln!("Then register it in src/schedule.rs");
+        if schedule.is_empty() {
+            eprintln!("No scheduled tasks registered.");
+            eprintln!("Create a scheduled task with: kit make:task <name>");
+            eprintln!("Then register it in src/schedule.rs");
+            return;
+        }
+
+        let reason = schedule
+            .work(Daemon::new("Scheduler", options), Default::default())
+            .await;
+
+        std::process::exit(reason.exit_code());
     }
 
     async fn run_scheduled_tasks_internal(
-        bootstrap_fn: Option<Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>>,
+        bootstrap_fn: Option<BootstrapFn>,
+        schedule_fn: Option<ScheduleFn>,
     ) {
         // Run bootstrap for scheduler context
         if let Some(bootstrap_fn) = bootstrap_fn {
             bootstrap_fn().await;
         }
 
+        let schedule = Self::build_schedule(schedule_fn);
+
+        if schedule.is_empty() {
+            eprintln!("No scheduled tasks registered.");
+            eprintln!("Create a scheduled task with: kit make:task <name>");
+            return;
+        }
+
         println!("Running scheduled tasks...");
-        eprintln!("Scheduler is not yet configured.");
-        eprintln!("Create a scheduled task with: kit make:task <name>");
+        let results = schedule.run_due_tasks().await;
+        if results.is_empty() {
+            println!("No tasks are due.");
+        }
+        for (name, result) in results {
+            match result {
+                Ok(()) => println!("  ✓ {}", name),
+                Err(e) => eprintln!("  ✗ {}: {}", name, e),
+            }
+        }
     }
 
-    async fn list_scheduled_tasks() {
+    fn list_routes(routes_fn: Option<Box<dyn FnOnce() -> Router + Send>>) {
+        let router = routes_fn.map(|routes_fn| routes_fn()).unwrap_or_default();
+        let routes = router.routes();
+
+        if routes.is_empty() {
+            eprintln!("No routes registered.");
+            return;
+        }
+
+        let path_width = routes.iter().map(|r| r.pattern.len()).max().unwrap_or(0).max(4);
+        let name_width = routes
+            .iter()
+            .filter_map(|r| r.name.as_ref().map(String::len))
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        println!(
+            "  {:<7} {:<path_width$}  {:<name_width$}  MIDDLEWARE  DESCRIPTION",
+            "METHOD", "PATH", "NAME"
+        );
+        for route in &routes {
+            let mut description = route.description.clone().unwrap_or_default();
+            if !route.tags.is_empty() {
+                description = format!("{} [{}]", description, route.tags.join(", "));
+            }
+            if route.deprecated {
+                description = format!("{} (deprecated)", description);
+            }
+            println!(
+                "  {:<7} {:<path_width$}  {:<name_width$}  {:<10}  {}",
+                route.method,
+                route.pattern,
+                route.name.as_deref().unwrap_or(""),
+                route.middleware,
+                description.trim_start()
+            );
+        }
+        println!();
+        println!("{} route(s)", routes.len());
+    }
+
+    fn cache_routes(routes_fn: Option<Box<dyn FnOnce() -> Router + Send>>, path: &Path) {
+        let router = routes_fn.map(|routes_fn| routes_fn()).unwrap_or_default();
+        let routes: Vec<_> = router
+            .routes()
+            .into_iter()
+            .map(|route| {
+                serde_json::json!({
+                    "method": route.method,
+                    "path": route.pattern,
+                    "name": route.name,
+                    "description": route.description,
+                    "tags": route.tags,
+                    "deprecated": route.deprecated,
+                })
+            })
+            .collect();
+        let manifest = serde_json::json!({ "routes": routes });
+
+        let result = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| {
+                std::fs::write(
+                    path,
+                    serde_json::to_string_pretty(&manifest).unwrap_or_default(),
+                )
+            });
+        match result {
+            Ok(()) => println!("Cached {} route(s) in {}", routes.len(), path.display()),
+            Err(e) => {
+                eprintln!("Failed to write {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    async fn list_scheduled_tasks(schedule_fn: Option<ScheduleFn>) {
+        let schedule = Self::build_schedule(schedule_fn);
+
         println!("Registered scheduled tasks:");
         println!();
-        eprintln!("No scheduled tasks registered.");
-        eprintln!("Create a scheduled task with: kit make:task <name>");
+
+        if schedule.is_empty() {
+            eprintln!("No scheduled tasks registered.");
+            eprintln!("Create a scheduled task with: kit make:task <name>");
+            return;
+        }
+
+        for task in schedule.tasks() {
+            match &task.description {
+                Some(description) => println!(
+                    "  {:<15} {}  {}",
+                    task.schedule_description(),
+                    task.name,
+                    description
+                ),
+                None => println!("  {:<15} {}", task.schedule_description(), task.name),
+            }
+        }
     }
 
-    async fn run_workflow_worker_internal(
-        bootstrap_fn: Option<Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>>,
+    async fn run_supervisor_internal(
+        bootstrap_fn: Option<BootstrapFn>,
+        schedule_fn: Option<ScheduleFn>,
+        supervise: bool,
+        json: bool,
+        config_path: String,
+        options: DaemonOptions,
     ) {
+        let config = match SupervisorConfig::load(Path::new(&config_path)) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+
+        if let Some(bootstrap_fn) = bootstrap_fn {
+            bootstrap_fn().await;
+        }
+
+        let supervisor = Supervisor::new(config, Self::build_schedule(schedule_fn))
+            .daemon_options(options)
+            .supervise(supervise)
+            .json(json);
+
+        if let Err(e) = supervisor.run().await {
+            eprintln!("Supervisor error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    async fn run_workflow_worker_internal(bootstrap_fn: Option<BootstrapFn>, options: DaemonOptions) {
         if let Some(bootstrap_fn) = bootstrap_fn {
             bootstrap_fn().await;
         }
@@ -420,9 +774,91 @@ where
         println!();
         println!("==============================================");
 
-        if let Err(e) = crate::workflow::WorkflowWorker::work_loop().await {
-            eprintln!("Workflow worker error: {}", e);
-            std::process::exit(1);
+        match crate::workflow::WorkflowWorker::work_loop_with(options).await {
+            Ok(reason) => std::process::exit(reason.exit_code()),
+            Err(e) => {
+                eprintln!("Workflow worker error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    async fn run_queue_worker_internal(
+        bootstrap_fn: Option<BootstrapFn>,
+        queues: Vec<String>,
+        options: DaemonOptions,
+    ) {
+        if let Some(bootstrap_fn) = bootstrap_fn {
+            bootstrap_fn().await;
+        }
+
+        println!("==============================================");
+        println!("  Kit Queue Worker");
+        println!("==============================================");
+        println!();
+        if !queues.is_empty() {
+            println!("  Queues: {}", queues.join(", "));
+            println!();
+        }
+        println!("  Press Ctrl+C to stop");
+        println!();
+        println!("==============================================");
+
+        match crate::queue::QueueWorker::work_loop_with(options, queues).await {
+            Ok(reason) => std::process::exit(reason.exit_code()),
+            Err(e) => {
+                eprintln!("Queue worker error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Point DATABASE_URL at a single-connection in-memory SQLite database
+    fn use_in_memory_database() {
+        env::set_var("DATABASE_URL", "sqlite::memory:");
+        env::set_var("DB_MAX_CONNECTIONS", "1");
+        env::set_var("DB_MIN_CONNECTIONS", "1");
+    }
+
+    async fn run_bench_internal<Migrator: MigratorTrait>(
+        bootstrap_fn: Option<BootstrapFn>,
+        routes_fn: Option<Box<dyn FnOnce() -> Router + Send>>,
+        options: BenchOptions,
+    ) {
+        if let Some(bootstrap_fn) = bootstrap_fn {
+            bootstrap_fn().await;
+        }
+
+        if !options.env_db && crate::DB::is_connected() {
+            if let Ok(db) = crate::DB::connection() {
+                if let Err(e) = Migrator::up(db.inner(), None).await {
+                    eprintln!("Warning: Migration failed: {}", e);
+                }
+            }
+        }
+
+        let router = if let Some(routes_fn) = routes_fn {
+            routes_fn()
+        } else {
+            Router::new()
+        };
+
+        crate::Cache::bootstrap().await;
+
+        let report = match Bench::from_options(Server::from_config(router), &options) {
+            Ok(bench) => bench.run().await,
+            Err(e) => Err(e),
+        };
+
+        match report {
+            Ok(report) if options.json => {
+                println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+            }
+            Ok(report) => report.print(),
+            Err(e) => {
+                eprintln!("Benchmark failed: {}", e);
+                std::process::exit(1);
+            }
         }
     }
 }
@@ -19,6 +19,10 @@ use std::any::Any;
 ///         self.id as i64
 ///     }
 ///
+///     fn auth_password(&self) -> Option<&str> {
+///         Some(&self.password)
+///     }
+///
 ///     fn as_any(&self) -> &dyn Any {
 ///         self
 ///     }
@@ -35,6 +39,13 @@ pub trait Authenticatable: Send + Sync + 'static {
         "id"
     }
 
+    /// Get the hashed password, for `Auth::verify_password`
+    ///
+    /// Override this if the user logs in with a password.
+    fn auth_password(&self) -> Option<&str> {
+        None
+    }
+
     /// Allow downcasting to concrete type
     ///
     /// This is used by `Auth::user_as::<T>()` to cast the trait object
@@ -0,0 +1,263 @@
+//! Gate facade for authorization checks
+//!
+//! Checks either go to a `Policy` about a model or to a named ability
+//! defined with a closure, for permissions that aren't about a model.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{can, Gate};
+//!
+//! // In bootstrap.rs
+//! Gate::policy(PostPolicy);
+//! Gate::define("admin-only", |user: &User| user.is_admin);
+//!
+//! // In a controller or middleware
+//! if Gate::allows("admin-only", &user) {
+//!     // ...
+//! }
+//! if can!("admin-only").await? {
+//!     // the logged in user is an admin
+//! }
+//! Gate::authorize::<PostPolicy>(Ability::Update(&post)).await?;
+//! ```
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::authenticatable::Authenticatable;
+use super::guard::Auth;
+use super::policy::{Ability, Policy};
+use super::provider::UserProvider;
+use crate::container::testing::TestContainer;
+use crate::container::App;
+use crate::error::FrameworkError;
+
+/// Gate facade - registers policies and abilities and checks them
+pub struct Gate;
+
+impl Gate {
+    /// Register `policy` in the container, replacing any of the same type
+    pub fn policy<P: Policy>(policy: P) {
+        App::bind::<P>(Arc::new(policy));
+    }
+
+    /// The registered policy `P`
+    pub fn resolve<P: Policy>() -> Result<Arc<P>, FrameworkError> {
+        App::make::<P>().ok_or_else(|| {
+            FrameworkError::internal(format!(
+                "Policy {} is not registered, add Gate::policy(...) to bootstrap.rs",
+                std::any::type_name::<P>()
+            ))
+        })
+    }
+
+    /// Whether `user` may do `ability`
+    pub async fn permits_for<P: Policy>(
+        user: &P::User,
+        ability: Ability<'_, P::Model>,
+    ) -> Result<bool, FrameworkError> {
+        let policy = Self::resolve::<P>()?;
+        if let Some(answer) = policy.before(user).await {
+            return Ok(answer);
+        }
+        Ok(match ability {
+            Ability::ViewAny => policy.view_any(user).await,
+            Ability::View(model) => policy.view(user, model).await,
+            Ability::Create => policy.create(user).await,
+            Ability::Update(model) => policy.update(user, model).await,
+            Ability::Delete(model) => policy.delete(user, model).await,
+        })
+    }
+
+    /// Whether the logged in user may do `ability`; guests may not
+    pub async fn permits<P: Policy>(
+        ability: Ability<'_, P::Model>,
+    ) -> Result<bool, FrameworkError> {
+        match Auth::id() {
+            Some(id) => Self::permits_id::<P>(id, ability).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Fail with a 403 unless the logged in user may do `ability`
+    pub async fn authorize<P: Policy>(
+        ability: Ability<'_, P::Model>,
+    ) -> Result<(), FrameworkError> {
+        if Self::permits::<P>(ability).await? {
+            Ok(())
+        } else {
+            Err(FrameworkError::Unauthorized)
+        }
+    }
+
+    /// Whether user `id` may do `ability`
+    pub(crate) async fn permits_id<P: Policy>(
+        id: i64,
+        ability: Ability<'_, P::Model>,
+    ) -> Result<bool, FrameworkError> {
+        let Some(user) = user(id).await? else {
+            return Ok(false);
+        };
+        let user = user.as_any().downcast_ref::<P::User>().ok_or_else(|| {
+            FrameworkError::internal(format!(
+                "The UserProvider does not return {} users",
+                std::any::type_name::<P::User>()
+            ))
+        })?;
+        Self::permits_for::<P>(user, ability).await
+    }
+
+    /// Define a named ability, replacing any with that name
+    ///
+    /// `check` gets users of type `U`; users of other types are denied.
+    /// Inside a `TestContainer::fake()` the ability is only defined for the
+    /// test.
+    pub fn define<U, F>(name: impl Into<String>, check: F)
+    where
+        U: Authenticatable,
+        F: Fn(&U) -> bool + Send + Sync + 'static,
+    {
+        let check: AbilityCheck =
+            Arc::new(move |user: &dyn Any| user.downcast_ref::<U>().is_some_and(&check));
+        let mut abilities = App::get::<Abilities>().unwrap_or_default();
+        abilities.0.insert(name.into(), check);
+        if TestContainer::is_active() {
+            TestContainer::singleton(abilities);
+        } else {
+            App::singleton(abilities);
+        }
+    }
+
+    /// Whether `user` has the named ability; undefined abilities are denied
+    pub fn allows<U: Authenticatable + ?Sized>(name: &str, user: &U) -> bool {
+        App::get::<Abilities>()
+            .and_then(|abilities| abilities.0.get(name).cloned())
+            .is_some_and(|check| check(user.as_any()))
+    }
+
+    /// Whether `user` lacks the named ability
+    pub fn denies<U: Authenticatable + ?Sized>(name: &str, user: &U) -> bool {
+        !Self::allows(name, user)
+    }
+
+    /// Whether the logged in user has the named ability; guests don't
+    pub async fn check(name: &str) -> Result<bool, FrameworkError> {
+        let Some(id) = Auth::id() else {
+            return Ok(false);
+        };
+        Ok(user(id)
+            .await?
+            .is_some_and(|user| Self::allows(name, user.as_ref())))
+    }
+}
+
+/// A named ability's check, given the user as `Any`
+type AbilityCheck = Arc<dyn Fn(&dyn Any) -> bool + Send + Sync>;
+
+/// Registry of named abilities, kept in the container
+#[derive(Clone, Default)]
+struct Abilities(HashMap<String, AbilityCheck>);
+
+/// Load user `id` with the registered `UserProvider`
+async fn user(id: i64) -> Result<Option<Arc<dyn Authenticatable>>, FrameworkError> {
+    let provider = App::make::<dyn UserProvider>().ok_or_else(|| {
+        FrameworkError::internal(
+            "No UserProvider registered. Register one in bootstrap.rs with: \
+             bind!(dyn UserProvider, YourUserProvider)",
+        )
+    })?;
+    provider.retrieve_by_id(id).await
+}
+
+/// Check a named ability, see `Gate::define`
+///
+/// With a user it is `Gate::allows` and returns a `bool`; without one it
+/// checks the logged in user with `Gate::check`, a future to await.
+///
+/// ```rust,ignore
+/// if can!("admin-only", &user) {
+///     // ...
+/// }
+///
+/// if !can!("admin-only").await? {
+///     return Err(FrameworkError::Unauthorized.into());
+/// }
+/// ```
+#[macro_export]
+macro_rules! can {
+    ($ability:expr, $user:expr $(,)?) => {
+        $crate::auth::Gate::allows($ability, $user)
+    };
+    ($ability:expr $(,)?) => {
+        $crate::auth::Gate::check($ability)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::{Session, SessionData};
+    use async_trait::async_trait;
+
+    struct User {
+        id: i64,
+        is_admin: bool,
+    }
+
+    impl Authenticatable for User {
+        fn auth_identifier(&self) -> i64 {
+            self.id
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    struct Users;
+
+    #[async_trait]
+    impl UserProvider for Users {
+        async fn retrieve_by_id(
+            &self,
+            id: i64,
+        ) -> Result<Option<Arc<dyn Authenticatable>>, FrameworkError> {
+            Ok(Some(Arc::new(User {
+                id,
+                is_admin: id == 1,
+            })))
+        }
+    }
+
+    fn logged_in(id: i64) -> Session {
+        let mut data = SessionData::new("test".to_string(), "token".to_string());
+        data.user_id = Some(id);
+        Session::new(data)
+    }
+
+    #[tokio::test]
+    async fn named_abilities_check_users() {
+        let _container = TestContainer::fake();
+        TestContainer::bind::<dyn UserProvider>(Arc::new(Users));
+        Gate::define("admin-only", |user: &User| user.is_admin);
+
+        let admin = User {
+            id: 1,
+            is_admin: true,
+        };
+        let member = User {
+            id: 2,
+            is_admin: false,
+        };
+        assert!(Gate::allows("admin-only", &admin));
+        assert!(Gate::denies("admin-only", &member));
+        assert!(crate::can!("admin-only", &admin));
+        assert!(!crate::can!("undefined", &admin));
+
+        assert!(logged_in(1).scope(crate::can!("admin-only")).await.unwrap());
+        assert!(!logged_in(2).scope(crate::can!("admin-only")).await.unwrap());
+        assert!(!Gate::check("admin-only").await.unwrap());
+    }
+}
@@ -3,6 +3,9 @@
 use std::sync::Arc;
 
 use crate::container::App;
+use crate::error::FrameworkError;
+use crate::hashing;
+use crate::http::Request;
 use crate::session::{
     auth_user_id, clear_auth_user, generate_csrf_token, regenerate_session_id, session_mut,
     set_auth_user,
@@ -156,16 +159,34 @@ impl Auth {
         validator().await
     }
 
-    /// Get the currently authenticated user
+    /// Hash a password for storage
+    pub fn hash_password(password: &str) -> Result<String, FrameworkError> {
+        hashing::hash(password)
+    }
+
+    /// Check a password against the user's stored hash
+    ///
+    /// Returns `false` for users without a password.
+    pub fn verify_password(
+        user: &dyn Authenticatable,
+        password: &str,
+    ) -> Result<bool, FrameworkError> {
+        match user.auth_password() {
+            Some(hash) => hashing::verify(password, hash),
+            None => Ok(false),
+        }
+    }
+
+    /// Get the user authenticated in the request's session
     ///
-    /// Returns `None` if not authenticated or if no `UserProvider` is registered.
+    /// Returns `None` for guests.
     ///
     /// # Example
     ///
     /// ```rust,ignore
     /// use kit::Auth;
     ///
-    /// if let Some(user) = Auth::user().await? {
+    /// if let Some(user) = Auth::user(&req).await? {
     ///     println!("Logged in as user {}", user.auth_identifier());
     /// }
     /// ```
@@ -178,8 +199,10 @@ impl Auth {
     /// ```rust,ignore
     /// bind!(dyn UserProvider, DatabaseUserProvider);
     /// ```
-    pub async fn user() -> Result<Option<Arc<dyn Authenticatable>>, crate::error::FrameworkError> {
-        let user_id = match Self::id() {
+    pub async fn user(
+        req: &Request,
+    ) -> Result<Option<Arc<dyn Authenticatable>>, crate::error::FrameworkError> {
+        let user_id = match req.try_session().and_then(|session| session.user_id()) {
             Some(id) => id,
             None => return Ok(None),
         };
@@ -206,7 +229,7 @@ impl Auth {
     /// use kit::Auth;
     /// use crate::models::users::User;
     ///
-    /// if let Some(user) = Auth::user_as::<User>().await? {
+    /// if let Some(user) = Auth::user_as::<User>(&req).await? {
     ///     println!("Welcome, user #{}!", user.id);
     /// }
     /// ```
@@ -215,8 +238,48 @@ impl Auth {
     ///
     /// * `T` - The concrete user type that implements `Authenticatable` and `Clone`
     pub async fn user_as<T: Authenticatable + Clone>(
+        req: &Request,
     ) -> Result<Option<T>, crate::error::FrameworkError> {
-        let user = Self::user().await?;
+        let user = Self::user(req).await?;
         Ok(user.and_then(|u| u.as_any().downcast_ref::<T>().cloned()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::Any;
+
+    struct User {
+        password: Option<String>,
+    }
+
+    impl Authenticatable for User {
+        fn auth_identifier(&self) -> i64 {
+            1
+        }
+
+        fn auth_password(&self) -> Option<&str> {
+            self.password.as_deref()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn test_passwords_and_guests() {
+        let user = User {
+            password: Some(hashing::hash_with_cost("secret", 4).unwrap()),
+        };
+        assert!(Auth::verify_password(&user, "secret").unwrap());
+        assert!(!Auth::verify_password(&user, "wrong").unwrap());
+        assert!(!Auth::verify_password(&User { password: None }, "secret").unwrap());
+
+        assert!(Auth::user(&Request::fake().build())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -97,6 +97,24 @@ impl Middleware for AuthMiddleware {
     }
 }
 
+/// Auth middleware that redirects guests to `/login`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::auth::auth;
+///
+/// group!("/dashboard").middleware(auth()).routes([...]);
+/// ```
+pub fn auth() -> AuthMiddleware {
+    AuthMiddleware::redirect_to("/login")
+}
+
+/// Guest middleware that redirects authenticated users to `/dashboard`
+pub fn guest() -> GuestMiddleware {
+    GuestMiddleware::redirect_to("/dashboard")
+}
+
 /// Guest middleware
 ///
 /// Protects routes that should only be accessible to guests (non-authenticated users).
@@ -6,16 +6,18 @@
 //!
 //! Kit provides a simple, session-based authentication system:
 //!
-//! - `Auth` facade for login/logout operations
-//! - `AuthMiddleware` for protecting routes
-//! - `GuestMiddleware` for guest-only routes
+//! - `Auth` facade for login/logout operations and password hashing
+//! - `AuthMiddleware` for protecting routes, `auth()` for the usual setup
+//! - `GuestMiddleware` for guest-only routes, `guest()` for the usual setup
 //! - `Authenticatable` trait for user models
 //! - `UserProvider` trait for user retrieval
+//! - `Gate` facade for authorization, with `Policy` traits and named abilities
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use kit::{Auth, AuthMiddleware, GuestMiddleware};
+//! use kit::auth::{auth, guest};
+//! use kit::Auth;
 //!
 //! // In a controller
 //! if Auth::check() {
@@ -23,39 +25,45 @@
 //! }
 //!
 //! // Get the currently authenticated user
-//! if let Some(user) = Auth::user().await? {
+//! if let Some(user) = Auth::user(&req).await? {
 //!     println!("User ID: {}", user.auth_identifier());
 //! }
 //!
 //! // Get as concrete User type
-//! if let Some(user) = Auth::user_as::<User>().await? {
+//! if let Some(user) = Auth::user_as::<User>(&req).await? {
 //!     println!("Welcome, user #{}!", user.id);
 //! }
 //!
 //! // Login
-//! Auth::login(user.id);
+//! if Auth::verify_password(&user, &form.password)? {
+//!     Auth::login(user.id);
+//! }
 //!
 //! // Logout
 //! Auth::logout();
 //!
 //! // In routes
 //! group!("/dashboard")
-//!     .middleware(AuthMiddleware::redirect_to("/login"))
+//!     .middleware(auth())
 //!     .routes([...]);
 //!
 //! group!("/")
-//!     .middleware(GuestMiddleware::redirect_to("/dashboard"))
+//!     .middleware(guest())
 //!     .routes([
 //!         get!("/login", auth::show_login),
 //!     ]);
 //! ```
 
 pub mod authenticatable;
+pub mod gate;
 pub mod guard;
 pub mod middleware;
+pub mod policy;
 pub mod provider;
 
 pub use authenticatable::Authenticatable;
+pub use gate::Gate;
 pub use guard::Auth;
-pub use middleware::{AuthMiddleware, GuestMiddleware};
+pub use middleware::{auth, guest, AuthMiddleware, GuestMiddleware};
+pub use policy::{Ability, Policy};
 pub use provider::UserProvider;
@@ -0,0 +1,222 @@
+//! Authorization policies
+//!
+//! A policy decides what a user may do with one kind of model. Register it
+//! with `Gate::policy`, then check it with
+//! `#[authorize(...)]` on a handler, `req.authorize(...)` or `Gate`.
+//! Denied checks, and checks for guests, fail with a 403.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{async_trait, authorize, handler, Ability, Gate, Policy};
+//!
+//! pub struct PostPolicy;
+//!
+//! #[async_trait]
+//! impl Policy for PostPolicy {
+//!     type User = User;
+//!     type Model = Post;
+//!
+//!     async fn update(&self, user: &User, post: &Post) -> bool {
+//!         post.author_id == user.id
+//!     }
+//! }
+//!
+//! // In bootstrap.rs
+//! Gate::policy(PostPolicy);
+//!
+//! // The model is the handler's first bound parameter
+//! #[handler]
+//! #[authorize(PostPolicy::update)]
+//! pub async fn update(post: Post, form: UpdatePostRequest) -> Response { ... }
+//!
+//! // Or check in the body
+//! req.authorize::<PostPolicy>(Ability::Delete(&post)).await?;
+//! ```
+
+use async_trait::async_trait;
+
+use super::authenticatable::Authenticatable;
+
+/// What a user may do with a model
+///
+/// Every ability is denied unless the method is overridden.
+#[async_trait]
+pub trait Policy: Send + Sync + 'static {
+    /// The user model, as returned by the `UserProvider`
+    type User: Authenticatable + Clone;
+    /// The model the policy is about
+    type Model: Send + Sync + 'static;
+
+    /// Checked before every ability; `Some` answers for it, e.g. for admins
+    async fn before(&self, _user: &Self::User) -> Option<bool> {
+        None
+    }
+
+    /// Whether the user may list models
+    async fn view_any(&self, _user: &Self::User) -> bool {
+        false
+    }
+
+    /// Whether the user may see `model`
+    async fn view(&self, _user: &Self::User, _model: &Self::Model) -> bool {
+        false
+    }
+
+    /// Whether the user may create models
+    async fn create(&self, _user: &Self::User) -> bool {
+        false
+    }
+
+    /// Whether the user may change `model`
+    async fn update(&self, _user: &Self::User, _model: &Self::Model) -> bool {
+        false
+    }
+
+    /// Whether the user may delete `model`
+    async fn delete(&self, _user: &Self::User, _model: &Self::Model) -> bool {
+        false
+    }
+}
+
+/// An ability to check, with the model it is about
+#[derive(Debug)]
+pub enum Ability<'a, M> {
+    ViewAny,
+    View(&'a M),
+    Create,
+    Update(&'a M),
+    Delete(&'a M),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{Gate, UserProvider};
+    use crate::error::FrameworkError;
+    use crate::session::{Session, SessionData};
+    use crate::testing::{TestContainer, TestResponse};
+    use std::any::Any;
+    use std::sync::Arc;
+
+    #[derive(Clone)]
+    struct User {
+        id: i64,
+        admin: bool,
+    }
+
+    impl Authenticatable for User {
+        fn auth_identifier(&self) -> i64 {
+            self.id
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    struct Users;
+
+    #[async_trait]
+    impl UserProvider for Users {
+        async fn retrieve_by_id(
+            &self,
+            id: i64,
+        ) -> Result<Option<Arc<dyn Authenticatable>>, FrameworkError> {
+            Ok(Some(Arc::new(User { id, admin: id == 1 })))
+        }
+    }
+
+    struct Post {
+        author_id: i64,
+    }
+
+    #[async_trait]
+    impl crate::database::RouteBinding for Post {
+        fn param_name() -> &'static str {
+            "post"
+        }
+
+        async fn from_route_param(value: &str) -> Result<Self, FrameworkError> {
+            let author_id = value
+                .parse()
+                .map_err(|_| FrameworkError::param_parse(value, "i64"))?;
+            Ok(Post { author_id })
+        }
+    }
+
+    #[crate::handler]
+    #[crate::authorize(PostPolicy::update)]
+    async fn update(post: Post) -> crate::Response {
+        crate::text(format!("updated {}", post.author_id))
+    }
+
+    struct PostPolicy;
+
+    #[async_trait]
+    impl Policy for PostPolicy {
+        type User = User;
+        type Model = Post;
+
+        async fn before(&self, user: &User) -> Option<bool> {
+            user.admin.then_some(true)
+        }
+
+        async fn update(&self, user: &User, post: &Post) -> bool {
+            post.author_id == user.id
+        }
+    }
+
+    fn session(user_id: Option<i64>) -> Session {
+        let mut data = SessionData::new("test".to_string(), "token".to_string());
+        data.user_id = user_id;
+        Session::new(data)
+    }
+
+    #[tokio::test]
+    async fn policies_decide_for_the_logged_in_user() {
+        let _container = TestContainer::fake();
+        TestContainer::bind::<dyn UserProvider>(Arc::new(Users));
+        let post = Post { author_id: 2 };
+
+        // Not registered yet
+        let check = Gate::authorize::<PostPolicy>(Ability::Update(&post));
+        assert!(session(Some(2)).scope(check).await.is_err());
+
+        TestContainer::bind::<PostPolicy>(Arc::new(PostPolicy));
+        let permits =
+            |user_id, ability| session(user_id).scope(Gate::permits::<PostPolicy>(ability));
+        assert!(permits(Some(2), Ability::Update(&post)).await.unwrap());
+        assert!(!permits(Some(3), Ability::Update(&post)).await.unwrap());
+        assert!(!permits(Some(2), Ability::Delete(&post)).await.unwrap());
+        assert!(!permits(None, Ability::Update(&post)).await.unwrap());
+        // Admins pass `before`
+        assert!(permits(Some(1), Ability::Delete(&post)).await.unwrap());
+
+        let denied = session(Some(3))
+            .scope(Gate::authorize::<PostPolicy>(Ability::Update(&post)))
+            .await;
+        assert!(matches!(denied, Err(FrameworkError::Unauthorized)));
+
+        let req = crate::http::Request::fake().build();
+        let check = req.authorize::<PostPolicy>(Ability::Update(&post));
+        assert!(session(Some(2)).scope(check).await.is_ok());
+        let check = req.authorize::<PostPolicy>(Ability::Update(&post));
+        assert!(check.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn authorize_attribute_checks_the_bound_model() {
+        let _container = TestContainer::fake();
+        TestContainer::bind::<dyn UserProvider>(Arc::new(Users));
+        TestContainer::bind::<PostPolicy>(Arc::new(PostPolicy));
+        let request = || crate::http::Request::fake().param("post", "2").build();
+
+        let response = TestResponse::from(session(Some(2)).scope(update(request())).await);
+        response.assert_status(200);
+        assert_eq!(response.text(), "updated 2");
+
+        TestResponse::from(session(Some(3)).scope(update(request())).await).assert_status(403);
+        TestResponse::from(update(request()).await).assert_status(403);
+    }
+}
@@ -0,0 +1,444 @@
+//! Batch endpoint: several requests in one round trip
+//!
+//! When enabled with `Server::batch`, a `POST` to the batch path accepts a
+//! JSON array of sub-requests, dispatches them through the router, and
+//! responds with an array of their responses in the same order:
+//!
+//! ```text
+//! POST /batch
+//! [
+//!     { "method": "GET", "path": "/users/1" },
+//!     { "method": "POST", "path": "/todos", "body": { "title": "Ship it" } }
+//! ]
+//!
+//! 200 OK
+//! [
+//!     { "status": 200, "headers": { "content-type": "application/json" }, "body": { ... } },
+//!     { "status": 201, "headers": { "content-type": "application/json" }, "body": { ... } }
+//! ]
+//! ```
+//!
+//! `method` defaults to `GET`; `headers` and `body` are optional. Each
+//! sub-request inherits the batch request's headers (cookies,
+//! `Authorization`, ...) and runs through the global and route middleware
+//! like any other request, so authentication applies to each one. JSON
+//! response bodies are embedded as JSON, anything else as a string.
+//!
+//! Sub-requests run concurrently, at most `concurrency` at a time, and a
+//! batch may contain at most `max_requests` of them.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! Server::from_config(router)
+//!     .batch(BatchEndpoint::new("/batch").max_requests(50).concurrency(8))
+//!     .run()
+//!     .await?;
+//! ```
+
+use crate::error::FrameworkError;
+use crate::http::{
+    BodyLimits, ConnectionScheme, Disconnect, HttpResponse, RemoteAddr, RequestBody, ResponseBody,
+};
+use crate::middleware::MiddlewareRegistry;
+use crate::routing::Router;
+use crate::server::handle_request;
+use bytes::Bytes;
+use futures_util::stream::{self, StreamExt};
+use http_body_util::BodyExt;
+use hyper::header::{HeaderName, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::Method;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Headers describing the batch request's own body or connection, which
+/// sub-requests don't inherit
+const UNINHERITED_HEADERS: [&str; 5] = [
+    "content-length",
+    "content-type",
+    "transfer-encoding",
+    "connection",
+    "upgrade",
+];
+
+/// Configuration for the batch endpoint
+#[derive(Debug, Clone)]
+pub struct BatchEndpoint {
+    path: String,
+    max_requests: usize,
+    concurrency: usize,
+}
+
+impl BatchEndpoint {
+    /// Serve batches at `path`, up to 20 sub-requests with 5 at a time
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            max_requests: 20,
+            concurrency: 5,
+        }
+    }
+
+    /// Maximum number of sub-requests in one batch
+    pub fn max_requests(mut self, max: usize) -> Self {
+        self.max_requests = max;
+        self
+    }
+
+    /// Maximum number of sub-requests dispatched at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Whether `req` is a batch request
+    pub(crate) fn matches<B>(&self, req: &hyper::Request<B>) -> bool {
+        req.method() == Method::POST && req.uri().path() == self.path
+    }
+
+    /// Dispatch a batch request's sub-requests and collect their responses
+    pub(crate) async fn handle(
+        &self,
+        router: Arc<Router>,
+        middleware: Arc<MiddlewareRegistry>,
+        req: hyper::Request<RequestBody>,
+    ) -> hyper::Response<ResponseBody> {
+        match self.dispatch(router, middleware, req).await {
+            Ok(responses) => HttpResponse::json(serde_json::json!(responses)).into_hyper(),
+            Err(err) => HttpResponse::from(err).into_hyper(),
+        }
+    }
+
+    async fn dispatch(
+        &self,
+        router: Arc<Router>,
+        middleware: Arc<MiddlewareRegistry>,
+        req: hyper::Request<RequestBody>,
+    ) -> Result<Vec<BatchResponse>, FrameworkError> {
+        BodyLimits::from_config().check_content_length(req.headers())?;
+
+        let (parts, body) = req.into_parts();
+        let bytes = body.collect().await?;
+        let requests: Vec<BatchRequest> = serde_json::from_slice(&bytes).map_err(|_| {
+            FrameworkError::domain("Batch body must be a JSON array of requests", 400)
+        })?;
+        if requests.len() > self.max_requests {
+            return Err(FrameworkError::domain(
+                format!("A batch may contain at most {} requests", self.max_requests),
+                422,
+            ));
+        }
+
+        let parent = Arc::new(parts);
+        let responses = stream::iter(requests)
+            .map(|sub| {
+                let router = router.clone();
+                let middleware = middleware.clone();
+                let parent = parent.clone();
+                async move {
+                    let response = match sub.into_hyper(&parent) {
+                        Ok(req) => tokio::spawn(handle_request(router, middleware, req))
+                            .await
+                            .unwrap_or_else(|_| {
+                                HttpResponse::from(FrameworkError::internal(
+                                    "Batch sub-request panicked",
+                                ))
+                                .into_hyper()
+                            }),
+                        Err(err) => HttpResponse::from(err).into_hyper(),
+                    };
+                    BatchResponse::from_hyper(response).await
+                }
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await;
+        Ok(responses)
+    }
+}
+
+impl From<&str> for BatchEndpoint {
+    fn from(path: &str) -> Self {
+        Self::new(path)
+    }
+}
+
+/// One sub-request in a batch
+#[derive(Debug, Deserialize)]
+struct BatchRequest {
+    #[serde(default = "default_method")]
+    method: String,
+    path: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: Option<serde_json::Value>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+impl BatchRequest {
+    /// Build the hyper request, inheriting headers from the batch request
+    fn into_hyper(
+        self,
+        parent: &hyper::http::request::Parts,
+    ) -> Result<hyper::Request<RequestBody>, FrameworkError> {
+        let method = Method::from_bytes(self.method.to_uppercase().as_bytes()).map_err(|_| {
+            FrameworkError::domain(format!("Invalid method '{}'", self.method), 400)
+        })?;
+        if !self.path.starts_with('/') {
+            return Err(FrameworkError::domain(
+                format!(
+                    "Invalid path '{}', expected it to start with '/'",
+                    self.path
+                ),
+                400,
+            ));
+        }
+
+        let mut req = hyper::Request::builder()
+            .method(method)
+            .uri(&self.path)
+            .body(RequestBody::Full(Bytes::new()))
+            .map_err(|_| FrameworkError::domain(format!("Invalid path '{}'", self.path), 400))?;
+
+        let headers = req.headers_mut();
+        for (name, value) in &parent.headers {
+            if !UNINHERITED_HEADERS.contains(&name.as_str()) {
+                headers.append(name.clone(), value.clone());
+            }
+        }
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| FrameworkError::domain(format!("Invalid header '{}'", name), 400))?;
+            let value = HeaderValue::from_str(value).map_err(|_| {
+                FrameworkError::domain(format!("Invalid value for header '{}'", name), 400)
+            })?;
+            headers.insert(name, value);
+        }
+
+        if let Some(body) = self.body {
+            let bytes = match body {
+                // Strings are sent as-is when the sub-request sets its own
+                // content type, e.g. a form-encoded body
+                serde_json::Value::String(text) if headers.contains_key(CONTENT_TYPE) => {
+                    Bytes::from(text)
+                }
+                value => {
+                    headers
+                        .entry(CONTENT_TYPE)
+                        .or_insert(HeaderValue::from_static("application/json"));
+                    Bytes::from(value.to_string())
+                }
+            };
+            headers.insert(CONTENT_LENGTH, HeaderValue::from(bytes.len()));
+            *req.body_mut() = RequestBody::Full(bytes);
+        }
+
+        if let Some(remote_addr) = parent.extensions.get::<RemoteAddr>() {
+            req.extensions_mut().insert(*remote_addr);
+        }
+        if let Some(scheme) = parent.extensions.get::<ConnectionScheme>() {
+            req.extensions_mut().insert(*scheme);
+        }
+        if let Some(disconnect) = parent.extensions.get::<Disconnect>() {
+            req.extensions_mut().insert(disconnect.clone());
+        }
+        Ok(req)
+    }
+}
+
+/// The response to one sub-request
+#[derive(Debug, Serialize)]
+struct BatchResponse {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: serde_json::Value,
+}
+
+impl BatchResponse {
+    async fn from_hyper(response: hyper::Response<ResponseBody>) -> Self {
+        let (parts, body) = response.into_parts();
+        let bytes = body
+            .collect()
+            .await
+            .map(|collected| collected.to_bytes())
+            .unwrap_or_default();
+
+        let mut headers = BTreeMap::new();
+        for (name, value) in &parts.headers {
+            if let Ok(value) = value.to_str() {
+                headers
+                    .entry(name.to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+        }
+
+        let is_json = headers
+            .get("content-type")
+            .is_some_and(|content_type| content_type.contains("json"));
+        let body = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else if is_json {
+            serde_json::from_slice(&bytes).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned())
+            })
+        } else {
+            serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned())
+        };
+
+        Self {
+            status: parts.status.as_u16(),
+            headers,
+            body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{Request, Response};
+    use crate::middleware::{Middleware, Next};
+    use serde_json::{json, Value};
+
+    async fn show(req: Request) -> Response {
+        let id = req.param("id")?.to_string();
+        Ok(HttpResponse::json(json!({ "id": id })))
+    }
+
+    async fn store(req: Request) -> Response {
+        let body: Value = req.json().await?;
+        Ok(HttpResponse::json(json!({ "created": body["title"] })).status(201))
+    }
+
+    async fn slow(_req: Request) -> Response {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        Ok(HttpResponse::text("done"))
+    }
+
+    struct RequireToken;
+
+    #[async_trait::async_trait]
+    impl Middleware for RequireToken {
+        async fn handle(&self, request: Request, next: Next) -> Response {
+            if request.header("Authorization") != Some("Bearer secret") {
+                return Err(HttpResponse::text("Unauthorized").status(401));
+            }
+            next(request).await
+        }
+    }
+
+    fn router() -> Arc<Router> {
+        let mut router = Router::new();
+        router = crate::get!("/todos/{id}", show).register(router);
+        router = crate::post!("/todos", store).register(router);
+        router = crate::get!("/slow", slow).register(router);
+        router = crate::get!("/private", slow)
+            .middleware(RequireToken)
+            .register(router);
+        Arc::new(router)
+    }
+
+    async fn send(endpoint: &BatchEndpoint, body: Value, token: Option<&str>) -> (u16, Value) {
+        let mut builder = hyper::Request::builder()
+            .method("POST")
+            .uri("/batch")
+            .header("Content-Type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("Authorization", token);
+        }
+        let req = builder
+            .body(RequestBody::Full(Bytes::from(body.to_string())))
+            .unwrap();
+
+        let response = endpoint
+            .handle(router(), Arc::new(MiddlewareRegistry::new()), req)
+            .await;
+        let status = response.status().as_u16();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn dispatches_sub_requests_in_order() {
+        let (status, body) = send(
+            &BatchEndpoint::new("/batch"),
+            json!([
+                { "path": "/todos/7" },
+                { "method": "post", "path": "/todos", "body": { "title": "Ship it" } },
+                { "path": "/missing" },
+                { "path": "/slow" }
+            ]),
+            None,
+        )
+        .await;
+
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["status"], 200);
+        assert_eq!(body[0]["body"], json!({ "id": "7" }));
+        assert_eq!(body[1]["status"], 201);
+        assert_eq!(body[1]["body"], json!({ "created": "Ship it" }));
+        assert_eq!(body[2]["status"], 404);
+        assert_eq!(body[3]["status"], 200);
+        assert_eq!(body[3]["body"], "done");
+    }
+
+    #[tokio::test]
+    async fn sub_requests_inherit_batch_headers() {
+        let batch = json!([
+            { "path": "/private" },
+            { "path": "/private", "headers": { "Authorization": "Bearer wrong" } }
+        ]);
+        let (_, body) = send(&BatchEndpoint::new("/batch"), batch, Some("Bearer secret")).await;
+
+        assert_eq!(body[0]["status"], 200);
+        assert_eq!(body[1]["status"], 401);
+    }
+
+    #[tokio::test]
+    async fn runs_sub_requests_concurrently_up_to_the_cap() {
+        let batch = json!([{ "path": "/slow" }, { "path": "/slow" }, { "path": "/slow" }, { "path": "/slow" }]);
+
+        let started = std::time::Instant::now();
+        send(
+            &BatchEndpoint::new("/batch").concurrency(4),
+            batch.clone(),
+            None,
+        )
+        .await;
+        let concurrent = started.elapsed();
+
+        let started = std::time::Instant::now();
+        send(&BatchEndpoint::new("/batch").concurrency(1), batch, None).await;
+        let sequential = started.elapsed();
+
+        assert!(concurrent < std::time::Duration::from_millis(150));
+        assert!(sequential >= std::time::Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_batches() {
+        let endpoint = BatchEndpoint::new("/batch").max_requests(2);
+
+        let (status, _) = send(&endpoint, json!({ "path": "/todos/1" }), None).await;
+        assert_eq!(status, 400);
+
+        let too_many = json!([{ "path": "/slow" }, { "path": "/slow" }, { "path": "/slow" }]);
+        let (status, _) = send(&endpoint, too_many, None).await;
+        assert_eq!(status, 422);
+
+        let (status, body) = send(
+            &endpoint,
+            json!([{ "method": "GET BAD", "path": "/slow" }, { "path": "todos" }]),
+            None,
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["status"], 400);
+        assert_eq!(body[1]["status"], 400);
+    }
+}
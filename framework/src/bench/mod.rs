@@ -0,0 +1,465 @@
+//! In-process benchmark harness for `bench`
+//!
+//! Fires concurrent requests at the application's router and middleware
+//! over in-memory connections, so no sockets or network stack are involved,
+//! and reports latency percentiles, throughput, and allocations per request.
+//!
+//! ```bash
+//! kit bench /users
+//! kit bench users.index --requests=5000 --concurrency=50
+//! kit bench /api/todos --method=POST --header="Content-Type: application/json" --body='{"title":"x"}'
+//! ```
+//!
+//! Allocation stats are reported when [`CountingAllocator`] is installed as
+//! the binary's global allocator.
+
+mod alloc;
+
+pub use alloc::{AllocSnapshot, CountingAllocator};
+
+use crate::error::FrameworkError;
+use crate::http::RequestBody;
+use crate::middleware::MiddlewareRegistry;
+use crate::routing::{route, Router};
+use crate::server::{handle_request, Server};
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::client::conn::http1::SendRequest;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::Method;
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+/// Command-line options for `bench`
+///
+/// Flatten into a clap command with `#[command(flatten)]`.
+#[derive(Debug, Clone, clap::Args)]
+pub struct BenchOptions {
+    /// Route path (e.g. /users/1) or route name (e.g. users.index)
+    pub route: String,
+    /// HTTP method
+    #[arg(long, default_value = "GET")]
+    pub method: String,
+    /// Number of measured requests
+    #[arg(long, short = 'n', default_value_t = 1000)]
+    pub requests: usize,
+    /// Number of requests in flight at once
+    #[arg(long, short = 'c', default_value_t = 10)]
+    pub concurrency: usize,
+    /// Unmeasured requests sent before the run
+    #[arg(long, default_value_t = 10)]
+    pub warmup: usize,
+    /// Request header as "Name: value" (repeatable)
+    #[arg(long = "header", short = 'H')]
+    pub headers: Vec<String>,
+    /// Request body
+    #[arg(long)]
+    pub body: Option<String>,
+    /// Use the configured DATABASE_URL instead of an in-memory SQLite database
+    #[arg(long)]
+    pub env_db: bool,
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Benchmark of a single route
+pub struct Bench {
+    router: Arc<Router>,
+    middleware: Arc<MiddlewareRegistry>,
+    method: Method,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+    requests: usize,
+    concurrency: usize,
+    warmup: usize,
+}
+
+impl Bench {
+    /// Benchmark `path` against the server's router and global middleware
+    pub fn new(server: Server, path: impl Into<String>) -> Self {
+        let (router, middleware) = server.into_parts();
+        Self {
+            router,
+            middleware,
+            method: Method::GET,
+            path: path.into(),
+            headers: Vec::new(),
+            body: Bytes::new(),
+            requests: 1000,
+            concurrency: 10,
+            warmup: 10,
+        }
+    }
+
+    /// Benchmark configured from command-line options
+    ///
+    /// Route names are resolved to paths, so the routes must already be
+    /// registered.
+    pub fn from_options(server: Server, options: &BenchOptions) -> Result<Self, FrameworkError> {
+        let path = if options.route.starts_with('/') {
+            options.route.clone()
+        } else {
+            route(&options.route, &[]).ok_or_else(|| {
+                FrameworkError::internal(format!("Route '{}' not found", options.route))
+            })?
+        };
+        let method =
+            Method::from_bytes(options.method.to_uppercase().as_bytes()).map_err(|_| {
+                FrameworkError::internal(format!("Invalid method '{}'", options.method))
+            })?;
+
+        let mut bench = Self::new(server, path)
+            .method(method)
+            .requests(options.requests)
+            .concurrency(options.concurrency)
+            .warmup(options.warmup);
+        for header in &options.headers {
+            let (name, value) = header.split_once(':').ok_or_else(|| {
+                FrameworkError::internal(format!(
+                    "Invalid header '{}', expected \"Name: value\"",
+                    header
+                ))
+            })?;
+            bench = bench.header(name.trim(), value.trim());
+        }
+        if let Some(body) = &options.body {
+            bench = bench.body(body.clone());
+        }
+        Ok(bench)
+    }
+
+    /// HTTP method to use (default: GET)
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Add a header to every request
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Request body sent with every request
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Total number of measured requests (default: 1000)
+    pub fn requests(mut self, requests: usize) -> Self {
+        self.requests = requests.max(1);
+        self
+    }
+
+    /// Number of requests in flight at once (default: 10)
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Unmeasured requests sent before the run (default: 10)
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Run the benchmark
+    pub async fn run(self) -> Result<BenchReport, FrameworkError> {
+        let bench = Arc::new(self);
+
+        if bench.warmup > 0 {
+            let mut sender = bench.connect().await?;
+            for _ in 0..bench.warmup {
+                bench.send(&mut sender).await?;
+            }
+        }
+
+        let next = Arc::new(AtomicUsize::new(0));
+        let allocs_before = AllocSnapshot::now();
+        let started = Instant::now();
+
+        let mut workers = JoinSet::new();
+        for _ in 0..bench.concurrency.min(bench.requests) {
+            let bench = bench.clone();
+            let next = next.clone();
+            workers.spawn(async move {
+                let mut sender = bench.connect().await?;
+                let mut samples = Vec::new();
+                while next.fetch_add(1, Ordering::Relaxed) < bench.requests {
+                    let sent = Instant::now();
+                    let status = bench.send(&mut sender).await?;
+                    samples.push((sent.elapsed(), status));
+                }
+                Ok::<_, FrameworkError>(samples)
+            });
+        }
+
+        let mut latencies = Vec::with_capacity(bench.requests);
+        let mut status_codes = BTreeMap::new();
+        while let Some(joined) = workers.join_next().await {
+            let samples = joined.map_err(|e| {
+                FrameworkError::internal(format!("Benchmark worker failed: {}", e))
+            })??;
+            for (latency, status) in samples {
+                latencies.push(latency);
+                *status_codes.entry(status).or_insert(0) += 1;
+            }
+        }
+
+        let elapsed = started.elapsed();
+        let allocations = match (allocs_before, AllocSnapshot::now()) {
+            (Some(before), Some(after)) => {
+                Some(AllocStats::new(after.since(&before), latencies.len()))
+            }
+            _ => None,
+        };
+
+        Ok(BenchReport {
+            method: bench.method.to_string(),
+            path: bench.path.clone(),
+            requests: latencies.len(),
+            concurrency: bench.concurrency,
+            elapsed_ms: elapsed.as_secs_f64() * 1000.0,
+            requests_per_sec: latencies.len() as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            latency: LatencyStats::from_samples(&mut latencies),
+            status_codes,
+            allocations,
+        })
+    }
+
+    /// Open an in-memory HTTP/1 connection served by the router
+    async fn connect(&self) -> Result<SendRequest<Full<Bytes>>, FrameworkError> {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let router = self.router.clone();
+        let middleware = self.middleware.clone();
+
+        tokio::spawn(async move {
+            let service = service_fn(move |req: hyper::Request<hyper::body::Incoming>| {
+                let router = router.clone();
+                let middleware = middleware.clone();
+                let req = req.map(RequestBody::Incoming);
+                async move { Ok::<_, Infallible>(handle_request(router, middleware, req).await) }
+            });
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(server), service)
+                .await;
+        });
+
+        let (sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(client))
+            .await
+            .map_err(|e| FrameworkError::internal(format!("Benchmark connection failed: {}", e)))?;
+        tokio::spawn(connection);
+
+        Ok(sender)
+    }
+
+    /// Send one request and read the full response, returning its status
+    async fn send(&self, sender: &mut SendRequest<Full<Bytes>>) -> Result<u16, FrameworkError> {
+        let mut request = hyper::Request::builder()
+            .method(self.method.clone())
+            .uri(self.path.as_str())
+            .header("Host", "localhost");
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let request = request
+            .body(Full::new(self.body.clone()))
+            .map_err(|e| FrameworkError::internal(format!("Invalid benchmark request: {}", e)))?;
+
+        sender
+            .ready()
+            .await
+            .map_err(|e| FrameworkError::internal(format!("Benchmark connection closed: {}", e)))?;
+        let response = sender
+            .send_request(request)
+            .await
+            .map_err(|e| FrameworkError::internal(format!("Benchmark request failed: {}", e)))?;
+        let status = response.status().as_u16();
+        response
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| FrameworkError::internal(format!("Failed to read response: {}", e)))?;
+
+        Ok(status)
+    }
+}
+
+/// Result of a benchmark run
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub method: String,
+    pub path: String,
+    pub requests: usize,
+    pub concurrency: usize,
+    pub elapsed_ms: f64,
+    pub requests_per_sec: f64,
+    pub latency: LatencyStats,
+    /// Number of responses per HTTP status code
+    pub status_codes: BTreeMap<u16, u64>,
+    /// `None` unless `CountingAllocator` is the global allocator
+    pub allocations: Option<AllocStats>,
+}
+
+impl BenchReport {
+    /// Print the report as a human-readable summary
+    pub fn print(&self) {
+        println!("{} {}", self.method, self.path);
+        println!(
+            "  {} requests, concurrency {}, {:.1} ms total",
+            self.requests, self.concurrency, self.elapsed_ms
+        );
+        println!("  {:.0} requests/sec", self.requests_per_sec);
+        println!();
+        println!("Latency");
+        for (label, value) in [
+            ("min", self.latency.min_us),
+            ("mean", self.latency.mean_us),
+            ("p50", self.latency.p50_us),
+            ("p90", self.latency.p90_us),
+            ("p99", self.latency.p99_us),
+            ("max", self.latency.max_us),
+        ] {
+            println!("  {:<5} {:>10}", label, format_micros(value));
+        }
+        println!();
+        println!("Status codes");
+        for (status, count) in &self.status_codes {
+            println!("  {:<5} {:>10}", status, count);
+        }
+        println!();
+        match &self.allocations {
+            Some(allocs) => {
+                println!("Allocations");
+                println!("  {:.1} allocs/request", allocs.per_request);
+                println!("  {:.0} bytes/request", allocs.bytes_per_request);
+            }
+            None => {
+                println!("Allocations: n/a (install kit::bench::CountingAllocator as the global allocator)");
+            }
+        }
+    }
+}
+
+/// Latency distribution in microseconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub min_us: f64,
+    pub mean_us: f64,
+    pub p50_us: f64,
+    pub p90_us: f64,
+    pub p99_us: f64,
+    pub max_us: f64,
+}
+
+impl LatencyStats {
+    /// Compute the distribution from unsorted samples
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+
+        let micros = |d: Duration| d.as_secs_f64() * 1_000_000.0;
+        let total: Duration = samples.iter().sum();
+
+        Self {
+            min_us: micros(samples[0]),
+            mean_us: micros(total) / samples.len() as f64,
+            p50_us: micros(percentile(samples, 50.0)),
+            p90_us: micros(percentile(samples, 90.0)),
+            p99_us: micros(percentile(samples, 99.0)),
+            max_us: micros(samples[samples.len() - 1]),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Allocation totals and per-request averages
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+    pub per_request: f64,
+    pub bytes_per_request: f64,
+}
+
+impl AllocStats {
+    fn new(delta: AllocSnapshot, requests: usize) -> Self {
+        let requests = requests.max(1) as f64;
+        Self {
+            allocations: delta.allocations,
+            bytes: delta.bytes,
+            per_request: delta.allocations as f64 / requests,
+            bytes_per_request: delta.bytes as f64 / requests,
+        }
+    }
+}
+
+fn format_micros(us: f64) -> String {
+    if us >= 1000.0 {
+        format!("{:.2} ms", us / 1000.0)
+    } else {
+        format!("{:.0} µs", us)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{HttpResponse, Request, Response};
+
+    async fn hello(_req: Request) -> Response {
+        Ok(HttpResponse::text("hello"))
+    }
+
+    #[test]
+    fn test_percentiles_use_nearest_rank() {
+        let mut samples: Vec<Duration> = (1..=100).rev().map(Duration::from_micros).collect();
+        let stats = LatencyStats::from_samples(&mut samples);
+
+        assert_eq!(stats.min_us, 1.0);
+        assert_eq!(stats.p50_us, 50.0);
+        assert_eq!(stats.p90_us, 90.0);
+        assert_eq!(stats.p99_us, 99.0);
+        assert_eq!(stats.max_us, 100.0);
+        assert_eq!(stats.mean_us, 50.5);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bench_dispatches_through_router() {
+        let server = Server::new(Router::new().get("/hello", hello));
+        let report = Bench::new(server, "/hello")
+            .requests(20)
+            .concurrency(4)
+            .warmup(2)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(report.requests, 20);
+        assert_eq!(report.status_codes.get(&200), Some(&20));
+
+        let report = Bench::new(Server::new(Router::new()), "/missing")
+            .requests(5)
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(report.status_codes.get(&404), Some(&5));
+    }
+}
@@ -0,0 +1,139 @@
+//! Billable trait for models that can subscribe
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use super::entities::{billing_customers, subscriptions};
+use super::stripe::Stripe;
+use super::webhook::sync_subscription;
+use crate::database::DB;
+use crate::error::FrameworkError;
+
+/// Trait for models that can have subscriptions, usually the User model
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::billing::Billable;
+///
+/// impl Billable for User {
+///     fn billable_id(&self) -> i64 {
+///         self.id
+///     }
+///
+///     fn billing_email(&self) -> Option<String> {
+///         Some(self.email.clone())
+///     }
+/// }
+///
+/// if user.subscribed("pro").await? {
+///     // ...
+/// }
+///
+/// let url = user
+///     .checkout("pro", "price_123", "https://example.com/billing", "https://example.com/pricing")
+///     .await?;
+/// Ok(Redirect::to(url).into())
+/// ```
+#[async_trait]
+pub trait Billable: Send + Sync {
+    /// The id subscriptions are stored under (typically the primary key)
+    fn billable_id(&self) -> i64;
+
+    /// The email given to Stripe when the customer is created
+    fn billing_email(&self) -> Option<String> {
+        None
+    }
+
+    /// The Stripe customer id, if the customer was created
+    async fn stripe_id(&self) -> Result<Option<String>, FrameworkError> {
+        let db = DB::connection()?;
+        let customer = billing_customers::Entity::find()
+            .filter(billing_customers::Column::UserId.eq(self.billable_id()))
+            .one(db.inner())
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+        Ok(customer.map(|customer| customer.stripe_id))
+    }
+
+    /// The Stripe customer id, creating the customer on first use
+    async fn create_or_get_stripe_id(&self) -> Result<String, FrameworkError> {
+        if let Some(stripe_id) = self.stripe_id().await? {
+            return Ok(stripe_id);
+        }
+        let email = self.billing_email();
+        let stripe_id = Stripe::new()
+            .create_customer(self.billable_id(), email.as_deref())
+            .await?;
+
+        let db = DB::connection()?;
+        billing_customers::Entity::insert(billing_customers::ActiveModel {
+            user_id: Set(self.billable_id()),
+            stripe_id: Set(stripe_id.clone()),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        })
+        .exec(db.inner())
+        .await
+        .map_err(|e| FrameworkError::database(e.to_string()))?;
+        Ok(stripe_id)
+    }
+
+    /// The latest subscription with the given name
+    async fn subscription(
+        &self,
+        name: &str,
+    ) -> Result<Option<subscriptions::Model>, FrameworkError> {
+        let db = DB::connection()?;
+        subscriptions::Entity::find()
+            .filter(subscriptions::Column::UserId.eq(self.billable_id()))
+            .filter(subscriptions::Column::Name.eq(name))
+            .order_by_desc(subscriptions::Column::CreatedAt)
+            .order_by_desc(subscriptions::Column::Id)
+            .one(db.inner())
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))
+    }
+
+    /// Check if the subscription with the given name gives access
+    ///
+    /// True while it is active or trialing, and after cancellation until the
+    /// paid period ends.
+    async fn subscribed(&self, name: &str) -> Result<bool, FrameworkError> {
+        Ok(self
+            .subscription(name)
+            .await?
+            .is_some_and(|subscription| subscription.is_valid()))
+    }
+
+    /// Start a Stripe Checkout for a subscription, returning the URL to
+    /// redirect to
+    ///
+    /// The subscription is stored when Stripe sends the webhook for it.
+    async fn checkout(
+        &self,
+        name: &str,
+        price: &str,
+        success_url: &str,
+        cancel_url: &str,
+    ) -> Result<String, FrameworkError> {
+        let customer = self.create_or_get_stripe_id().await?;
+        Stripe::new()
+            .checkout(&customer, price, name, success_url, cancel_url)
+            .await
+    }
+
+    /// Cancel the subscription with the given name at the end of the paid
+    /// period
+    async fn cancel_subscription(&self, name: &str) -> Result<(), FrameworkError> {
+        let subscription = self
+            .subscription(name)
+            .await?
+            .ok_or_else(|| FrameworkError::domain(format!("No '{}' subscription", name), 404))?;
+        let object = Stripe::new()
+            .cancel_subscription(&subscription.stripe_id)
+            .await?;
+        sync_subscription(&object, false).await
+    }
+}
@@ -0,0 +1,108 @@
+//! Billing configuration for Kit framework
+
+use crate::config::env;
+
+/// Billing configuration
+///
+/// # Environment Variables
+///
+/// - `STRIPE_SECRET` - Secret API key
+/// - `STRIPE_WEBHOOK_SECRET` - Signing secret of the webhook endpoint
+/// - `STRIPE_API_BASE` - API URL, e.g. for stripe-mock (default: "https://api.stripe.com")
+/// - `STRIPE_WEBHOOK_TOLERANCE` - Oldest webhook signature accepted, in
+///   seconds (default: 300)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{BillingConfig, Config};
+///
+/// // Register from environment
+/// Config::register(BillingConfig::from_env());
+///
+/// // Or build manually
+/// Config::register(BillingConfig::builder()
+///     .secret("sk_test_...")
+///     .webhook_secret("whsec_...")
+///     .build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BillingConfig {
+    /// Secret API key
+    pub secret: String,
+    /// Signing secret of the webhook endpoint
+    pub webhook_secret: String,
+    /// Base URL of the Stripe API
+    pub api_base: String,
+    /// Oldest webhook signature accepted, in seconds
+    pub webhook_tolerance: u64,
+}
+
+impl BillingConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            secret: env("STRIPE_SECRET", String::new()),
+            webhook_secret: env("STRIPE_WEBHOOK_SECRET", String::new()),
+            api_base: env("STRIPE_API_BASE", "https://api.stripe.com".to_string()),
+            webhook_tolerance: env("STRIPE_WEBHOOK_TOLERANCE", 300),
+        }
+    }
+
+    /// Create a builder for manual configuration
+    pub fn builder() -> BillingConfigBuilder {
+        BillingConfigBuilder::default()
+    }
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Builder for BillingConfig
+#[derive(Debug, Default)]
+pub struct BillingConfigBuilder {
+    secret: Option<String>,
+    webhook_secret: Option<String>,
+    api_base: Option<String>,
+    webhook_tolerance: Option<u64>,
+}
+
+impl BillingConfigBuilder {
+    /// Set the secret API key
+    pub fn secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Set the signing secret of the webhook endpoint
+    pub fn webhook_secret(mut self, secret: impl Into<String>) -> Self {
+        self.webhook_secret = Some(secret.into());
+        self
+    }
+
+    /// Set the base URL of the Stripe API
+    pub fn api_base(mut self, url: impl Into<String>) -> Self {
+        self.api_base = Some(url.into());
+        self
+    }
+
+    /// Set the oldest webhook signature accepted, in seconds
+    pub fn webhook_tolerance(mut self, seconds: u64) -> Self {
+        self.webhook_tolerance = Some(seconds);
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> BillingConfig {
+        let defaults = BillingConfig::from_env();
+        BillingConfig {
+            secret: self.secret.unwrap_or(defaults.secret),
+            webhook_secret: self.webhook_secret.unwrap_or(defaults.webhook_secret),
+            api_base: self.api_base.unwrap_or(defaults.api_base),
+            webhook_tolerance: self.webhook_tolerance.unwrap_or(defaults.webhook_tolerance),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! SeaORM entities for billing
+//!
+//! The tables are created by the migration from `kit make:billing`.
+
+/// Stripe customers of the app's users
+pub mod billing_customers {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "billing_customers")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        #[sea_orm(unique)]
+        pub user_id: i64,
+        #[sea_orm(unique)]
+        pub stripe_id: String,
+        pub created_at: chrono::NaiveDateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Stripe subscriptions, kept in sync by the webhook
+pub mod subscriptions {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "subscriptions")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        pub user_id: i64,
+        /// Name the app gave the subscription, e.g. "pro"
+        pub name: String,
+        #[sea_orm(unique)]
+        pub stripe_id: String,
+        pub stripe_status: String,
+        pub stripe_price: Option<String>,
+        pub trial_ends_at: Option<chrono::NaiveDateTime>,
+        /// When a canceled subscription stops, or stopped
+        pub ends_at: Option<chrono::NaiveDateTime>,
+        pub created_at: chrono::NaiveDateTime,
+        pub updated_at: chrono::NaiveDateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    impl Model {
+        /// Check if the subscription gives access: active, trialing, or
+        /// canceled but not yet ended
+        pub fn is_valid(&self) -> bool {
+            self.on_grace_period()
+                || (self.ends_at.is_none()
+                    && matches!(self.stripe_status.as_str(), "active" | "trialing"))
+        }
+
+        /// Check if the subscription was canceled
+        pub fn is_canceled(&self) -> bool {
+            self.ends_at.is_some()
+        }
+
+        /// Check if the subscription was canceled but runs until the end of
+        /// the paid period
+        pub fn on_grace_period(&self) -> bool {
+            self.ends_at
+                .is_some_and(|ends_at| ends_at > chrono::Utc::now().naive_utc())
+        }
+
+        /// Check if the subscription is in its trial
+        pub fn on_trial(&self) -> bool {
+            self.trial_ends_at
+                .is_some_and(|trial_ends_at| trial_ends_at > chrono::Utc::now().naive_utc())
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Subscription billing with Stripe
+//!
+//! Opt in with `kit make:billing`, which adds the `billing_customers` and
+//! `subscriptions` migration, a webhook route at `/stripe/webhook` and the
+//! `STRIPE_*` settings. Stripe Checkout takes the payment; the webhook keeps
+//! the `subscriptions` table in sync, so checking a subscription is a
+//! database query.
+//!
+//! # Quick Start
+//!
+//! ```rust,ignore
+//! use kit::billing::Billable;
+//!
+//! impl Billable for User {
+//!     fn billable_id(&self) -> i64 {
+//!         self.id
+//!     }
+//! }
+//!
+//! // Send the user to Stripe Checkout
+//! let url = user.checkout("pro", "price_123", success_url, cancel_url).await?;
+//!
+//! // Once the webhook has arrived
+//! if user.subscribed("pro").await? {
+//!     // ...
+//! }
+//! ```
+//!
+//! Every webhook is dispatched as a `StripeEvent`, so other events, such as
+//! `invoice.payment_failed`, can be handled with a `#[listener]`.
+
+pub mod billable;
+pub mod config;
+pub mod entities;
+pub mod stripe;
+pub mod webhook;
+
+pub use billable::Billable;
+pub use config::{BillingConfig, BillingConfigBuilder};
+pub use stripe::Stripe;
+pub use webhook::{handle_event, handle_webhook, verify_signature, StripeEvent, StripeEventData};
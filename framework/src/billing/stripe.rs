@@ -0,0 +1,140 @@
+//! Stripe API client
+
+use serde_json::Value;
+
+use super::config::BillingConfig;
+use crate::config::Config;
+use crate::error::FrameworkError;
+
+/// Client for the parts of the Stripe API billing needs
+///
+/// Requests are form-encoded as the API expects; nested parameters use
+/// Stripe's bracket syntax, e.g. `("metadata[user_id]", "7")`.
+pub struct Stripe {
+    config: BillingConfig,
+    client: reqwest::Client,
+}
+
+impl Stripe {
+    /// Client using the registered `BillingConfig`
+    pub fn new() -> Self {
+        Self::with_config(Config::get::<BillingConfig>().unwrap_or_default())
+    }
+
+    /// Client with a custom config
+    pub fn with_config(config: BillingConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Create a customer, returning its Stripe id
+    pub async fn create_customer(
+        &self,
+        user_id: i64,
+        email: Option<&str>,
+    ) -> Result<String, FrameworkError> {
+        let mut params = vec![("metadata[user_id]", user_id.to_string())];
+        if let Some(email) = email {
+            params.push(("email", email.to_string()));
+        }
+        let customer = self.post("/v1/customers", &params).await?;
+        string_field(&customer, "id")
+    }
+
+    /// Start a Checkout session subscribing a customer to `price`, returning
+    /// the URL to redirect the user to
+    ///
+    /// The subscription is created under `name` once the customer pays.
+    pub async fn checkout(
+        &self,
+        customer: &str,
+        price: &str,
+        name: &str,
+        success_url: &str,
+        cancel_url: &str,
+    ) -> Result<String, FrameworkError> {
+        let params = [
+            ("mode", "subscription".to_string()),
+            ("customer", customer.to_string()),
+            ("line_items[0][price]", price.to_string()),
+            ("line_items[0][quantity]", "1".to_string()),
+            ("subscription_data[metadata][name]", name.to_string()),
+            ("success_url", success_url.to_string()),
+            ("cancel_url", cancel_url.to_string()),
+        ];
+        let session = self.post("/v1/checkout/sessions", &params).await?;
+        string_field(&session, "url")
+    }
+
+    /// Cancel a subscription at the end of the paid period
+    pub async fn cancel_subscription(&self, stripe_id: &str) -> Result<Value, FrameworkError> {
+        self.post(
+            &format!("/v1/subscriptions/{}", stripe_id),
+            &[("cancel_at_period_end", "true".to_string())],
+        )
+        .await
+    }
+
+    /// Resume a subscription canceled with `cancel_subscription` before it ends
+    pub async fn resume_subscription(&self, stripe_id: &str) -> Result<Value, FrameworkError> {
+        self.post(
+            &format!("/v1/subscriptions/{}", stripe_id),
+            &[("cancel_at_period_end", "false".to_string())],
+        )
+        .await
+    }
+
+    /// Send a POST request to the API
+    pub async fn post(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<Value, FrameworkError> {
+        if self.config.secret.is_empty() {
+            return Err(FrameworkError::internal(
+                "Billing is not configured: set STRIPE_SECRET",
+            ));
+        }
+        let url = format!("{}{}", self.config.api_base.trim_end_matches('/'), path);
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(&self.config.secret)
+            .form(params)
+            .send()
+            .await
+            .map_err(|e| FrameworkError::internal(format!("Stripe request failed: {}", e)))?;
+
+        let status = response.status();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| FrameworkError::internal(format!("Stripe request failed: {}", e)))?;
+        let body: Value = serde_json::from_slice(&body)
+            .map_err(|e| FrameworkError::internal(format!("Stripe response error: {}", e)))?;
+        if !status.is_success() {
+            let message = body["error"]["message"].as_str().unwrap_or("unknown error");
+            return Err(FrameworkError::internal(format!(
+                "Stripe error ({}): {}",
+                status.as_u16(),
+                message
+            )));
+        }
+        Ok(body)
+    }
+}
+
+impl Default for Stripe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn string_field(object: &Value, field: &str) -> Result<String, FrameworkError> {
+    object[field]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| FrameworkError::internal(format!("Stripe response has no `{}`", field)))
+}
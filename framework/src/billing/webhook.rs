@@ -0,0 +1,382 @@
+//! Stripe webhooks
+//!
+//! `handle_webhook` checks the `Stripe-Signature` header, keeps the
+//! `subscriptions` table in sync and dispatches every event as a
+//! `StripeEvent`, so apps can react to the rest with listeners.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use hmac::{Hmac, Mac};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::time::Duration;
+
+use super::config::BillingConfig;
+use super::entities::{billing_customers, subscriptions};
+use crate::config::Config;
+use crate::database::DB;
+use crate::error::FrameworkError;
+use crate::events::Event;
+use crate::http::{HttpResponse, Request, Response};
+
+/// A webhook event from Stripe, dispatched after the subscriptions are synced
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{listener, StripeEvent};
+///
+/// #[listener]
+/// async fn send_receipt(event: &StripeEvent) -> Result<(), FrameworkError> {
+///     if event.kind == "invoice.paid" {
+///         dispatch!(SendReceipt { invoice: event.object()["id"].to_string() }).await?;
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripeEvent {
+    pub id: String,
+    /// Event type, e.g. "customer.subscription.updated"
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub data: StripeEventData,
+}
+
+/// The object a `StripeEvent` is about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripeEventData {
+    pub object: Value,
+}
+
+impl StripeEvent {
+    /// The object the event is about, e.g. the subscription
+    pub fn object(&self) -> &Value {
+        &self.data.object
+    }
+}
+
+/// Route handler for the Stripe webhook endpoint
+///
+/// Register it as a POST route and exempt it from CSRF protection; `kit
+/// make:billing` does both. Invalid signatures get a 400, and errors from
+/// syncing or listeners a 500 so Stripe retries the event.
+pub async fn handle_webhook(req: Request) -> Response {
+    let config = Config::get::<BillingConfig>().unwrap_or_default();
+    let signature = req
+        .header("Stripe-Signature")
+        .unwrap_or_default()
+        .to_string();
+    let payload = req.bytes().await?;
+
+    verify_signature(
+        &payload,
+        &signature,
+        &config.webhook_secret,
+        Duration::from_secs(config.webhook_tolerance),
+    )?;
+    let event: StripeEvent = serde_json::from_slice(&payload)
+        .map_err(|e| FrameworkError::domain(format!("Invalid webhook payload: {}", e), 400))?;
+
+    handle_event(event).await?;
+    Ok(HttpResponse::json(serde_json::json!({ "received": true })))
+}
+
+/// Sync the subscriptions for an event and dispatch it to listeners
+pub async fn handle_event(event: StripeEvent) -> Result<(), FrameworkError> {
+    match event.kind.as_str() {
+        "customer.subscription.created" | "customer.subscription.updated" => {
+            sync_subscription(event.object(), false).await?
+        }
+        "customer.subscription.deleted" => sync_subscription(event.object(), true).await?,
+        _ => {}
+    }
+    Event::dispatch(event).await
+}
+
+/// Check a `Stripe-Signature` header against the raw request body
+///
+/// The header carries a timestamp and one or more HMAC-SHA256 signatures of
+/// `"{timestamp}.{body}"`; one must match and the timestamp must be within
+/// `tolerance`, so captured requests can't be replayed later.
+pub fn verify_signature(
+    payload: &[u8],
+    header: &str,
+    secret: &str,
+    tolerance: Duration,
+) -> Result<(), FrameworkError> {
+    if secret.is_empty() {
+        return Err(FrameworkError::internal(
+            "Billing webhooks are not configured: set STRIPE_WEBHOOK_SECRET",
+        ));
+    }
+    let invalid = || FrameworkError::domain("Invalid Stripe signature", 400);
+
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.extend(hex::decode(value).ok()),
+            _ => {}
+        }
+    }
+    let timestamp = timestamp.ok_or_else(invalid)?;
+    if Utc::now().timestamp().abs_diff(timestamp) > tolerance.as_secs() {
+        return Err(invalid());
+    }
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(payload);
+    let valid = signatures
+        .iter()
+        .any(|signature| mac.clone().verify_slice(signature).is_ok());
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Create or update the row for a Stripe subscription object
+///
+/// Subscriptions of customers the app doesn't know are ignored.
+pub(crate) async fn sync_subscription(object: &Value, deleted: bool) -> Result<(), FrameworkError> {
+    let db = DB::connection()?;
+    let stripe_id = object["id"]
+        .as_str()
+        .ok_or_else(|| FrameworkError::domain("Subscription without an id", 400))?;
+    let customer = object["customer"].as_str().unwrap_or_default();
+
+    let Some(customer) = billing_customers::Entity::find()
+        .filter(billing_customers::Column::StripeId.eq(customer))
+        .one(db.inner())
+        .await
+        .map_err(|e| FrameworkError::database(e.to_string()))?
+    else {
+        return Ok(());
+    };
+
+    let now = Utc::now().naive_utc();
+    let ends_at = if deleted {
+        timestamp(&object["ended_at"]).or(Some(now))
+    } else {
+        timestamp(&object["cancel_at"])
+    };
+    let existing = subscriptions::Entity::find()
+        .filter(subscriptions::Column::StripeId.eq(stripe_id))
+        .one(db.inner())
+        .await
+        .map_err(|e| FrameworkError::database(e.to_string()))?;
+
+    let mut model = match existing {
+        Some(subscription) => subscription.into_active_model(),
+        None => subscriptions::ActiveModel {
+            user_id: Set(customer.user_id),
+            name: Set(object["metadata"]["name"]
+                .as_str()
+                .unwrap_or("default")
+                .to_string()),
+            stripe_id: Set(stripe_id.to_string()),
+            created_at: Set(now),
+            ..Default::default()
+        },
+    };
+    model.stripe_status = Set(object["status"].as_str().unwrap_or_default().to_string());
+    model.stripe_price = Set(object["items"]["data"][0]["price"]["id"]
+        .as_str()
+        .map(str::to_string));
+    model.trial_ends_at = Set(timestamp(&object["trial_end"]));
+    model.ends_at = Set(ends_at);
+    model.updated_at = Set(now);
+    model
+        .save(db.inner())
+        .await
+        .map_err(|e| FrameworkError::database(e.to_string()))?;
+    Ok(())
+}
+
+fn timestamp(value: &Value) -> Option<NaiveDateTime> {
+    value
+        .as_i64()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|time| time.naive_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::billing::Billable;
+    use crate::testing::TestDatabase;
+    use sea_orm::ConnectionTrait;
+    use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager};
+
+    fn sign(payload: &str, secret: &str, timestamp: i64) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+        format!(
+            "t={},v1={}",
+            timestamp,
+            hex::encode(mac.finalize().into_bytes())
+        )
+    }
+
+    #[test]
+    fn verifies_stripe_signatures() {
+        let payload = r#"{"id":"evt_1"}"#;
+        let now = Utc::now().timestamp();
+        let tolerance = Duration::from_secs(300);
+        let check = |header: &str| {
+            verify_signature(payload.as_bytes(), header, "whsec_test", tolerance).is_ok()
+        };
+
+        assert!(check(&sign(payload, "whsec_test", now)));
+        // Stripe sends one signature per active secret while rolling them
+        assert!(check(&format!(
+            "{},v1=00ff",
+            sign(payload, "whsec_test", now)
+        )));
+        assert!(!check(&sign(payload, "whsec_other", now)));
+        assert!(!check(&sign(r#"{"id":"evt_2"}"#, "whsec_test", now)));
+        assert!(!check(&sign(payload, "whsec_test", now - 600)));
+        assert!(!check("v1=00ff"));
+        assert!(
+            verify_signature(payload.as_bytes(), &sign(payload, "", now), "", tolerance).is_err()
+        );
+    }
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreateBillingTables)]
+        }
+    }
+
+    struct CreateBillingTables;
+
+    impl MigrationName for CreateBillingTables {
+        fn name(&self) -> &str {
+            "create_billing_tables"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreateBillingTables {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), sea_orm::DbErr> {
+            let db = manager.get_connection();
+            db.execute_unprepared(
+                "CREATE TABLE billing_customers (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    user_id INTEGER NOT NULL UNIQUE,
+                    stripe_id TEXT NOT NULL UNIQUE,
+                    created_at TIMESTAMP NOT NULL
+                )",
+            )
+            .await?;
+            db.execute_unprepared(
+                "CREATE TABLE subscriptions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    user_id INTEGER NOT NULL,
+                    name TEXT NOT NULL,
+                    stripe_id TEXT NOT NULL UNIQUE,
+                    stripe_status TEXT NOT NULL,
+                    stripe_price TEXT NULL,
+                    trial_ends_at TIMESTAMP NULL,
+                    ends_at TIMESTAMP NULL,
+                    created_at TIMESTAMP NOT NULL,
+                    updated_at TIMESTAMP NOT NULL
+                )",
+            )
+            .await?;
+            Ok(())
+        }
+    }
+
+    struct User(i64);
+
+    impl Billable for User {
+        fn billable_id(&self) -> i64 {
+            self.0
+        }
+    }
+
+    fn subscription_event(kind: &str, status: &str, cancel_at: Option<i64>) -> StripeEvent {
+        serde_json::from_value(serde_json::json!({
+            "id": "evt_1",
+            "type": kind,
+            "data": { "object": {
+                "id": "sub_1",
+                "customer": "cus_1",
+                "status": status,
+                "cancel_at": cancel_at,
+                "metadata": { "name": "pro" },
+                "items": { "data": [{ "price": { "id": "price_pro" } }] }
+            } }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscription_webhooks_keep_subscriptions_in_sync() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        let events = Event::fake();
+        db.conn()
+            .execute_unprepared(
+                "INSERT INTO billing_customers (user_id, stripe_id, created_at)
+                 VALUES (7, 'cus_1', CURRENT_TIMESTAMP)",
+            )
+            .await
+            .unwrap();
+        let user = User(7);
+        assert!(!user.subscribed("pro").await.unwrap());
+
+        handle_event(subscription_event(
+            "customer.subscription.created",
+            "active",
+            None,
+        ))
+        .await
+        .unwrap();
+        assert!(user.subscribed("pro").await.unwrap());
+        assert!(!user.subscribed("team").await.unwrap());
+        assert!(!User(8).subscribed("pro").await.unwrap());
+        let subscription = user.subscription("pro").await.unwrap().unwrap();
+        assert_eq!(subscription.stripe_price.as_deref(), Some("price_pro"));
+
+        // Canceled at the end of the period: still subscribed until then
+        let period_end = Utc::now().timestamp() + 3600;
+        handle_event(subscription_event(
+            "customer.subscription.updated",
+            "active",
+            Some(period_end),
+        ))
+        .await
+        .unwrap();
+        let subscription = user.subscription("pro").await.unwrap().unwrap();
+        assert!(subscription.on_grace_period());
+        assert!(user.subscribed("pro").await.unwrap());
+
+        handle_event(subscription_event(
+            "customer.subscription.deleted",
+            "canceled",
+            Some(period_end),
+        ))
+        .await
+        .unwrap();
+        assert!(!user.subscribed("pro").await.unwrap());
+        assert_eq!(
+            subscriptions::Entity::find()
+                .all(db.conn())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(events.dispatched::<StripeEvent>().len(), 3);
+    }
+}
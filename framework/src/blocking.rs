@@ -0,0 +1,93 @@
+//! Running blocking work off the async runtime
+//!
+//! CPU-heavy or blocking code (image resizing, `std::fs`, password hashing,
+//! synchronous clients) stalls every request sharing the runtime thread.
+//! `blocking` moves it to tokio's blocking pool and records the time under
+//! `blocking` in `Server-Timing`. Strict mode reports requests that block
+//! the runtime without it, see `kit::strict`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::blocking;
+//!
+//! let thumbnail = blocking(move || resize(&bytes, 200, 200)).await?;
+//!
+//! // Or run a whole handler on the blocking pool
+//! #[blocking]
+//! #[handler]
+//! pub fn thumbnail(upload: UploadedFile) -> Response {
+//!     let image = image::load_from_memory(&upload.bytes())?;
+//!     // ...
+//! }
+//! ```
+
+use crate::error::FrameworkError;
+use crate::profile::{self, Phase};
+
+/// Run `f` on the blocking thread pool and wait for its result
+///
+/// A panic in `f` is returned as an internal error rather than unwinding
+/// into the caller.
+pub async fn blocking<F, T>(f: F) -> Result<T, FrameworkError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    profile::timed(Phase::Blocking, tokio::task::spawn_blocking(f))
+        .await
+        .map_err(|e| {
+            let reason = if e.is_panic() {
+                "panicked"
+            } else {
+                "was cancelled"
+            };
+            FrameworkError::internal(format!("Blocking task {}", reason))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{HttpResponse, Request, Response};
+    use crate::testing::TestResponse;
+
+    #[crate::blocking]
+    fn thread_id() -> Result<std::thread::ThreadId, FrameworkError> {
+        Ok(std::thread::current().id())
+    }
+
+    #[crate::blocking]
+    #[crate::handler]
+    fn sum(req: Request) -> Response {
+        let total: i64 = req
+            .param("numbers")?
+            .split(',')
+            .filter_map(|n| n.parse::<i64>().ok())
+            .sum();
+        Ok(HttpResponse::text(total.to_string()))
+    }
+
+    #[tokio::test]
+    async fn runs_off_the_runtime_and_reports_panics() {
+        let runtime_thread = std::thread::current().id();
+        let thread = blocking(|| std::thread::current().id()).await.unwrap();
+        assert_ne!(thread, runtime_thread);
+
+        let err = blocking(|| -> u32 { panic!("boom") }).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Internal server error: Blocking task panicked"
+        );
+    }
+
+    #[tokio::test]
+    async fn blocking_functions_and_handlers_become_async() {
+        assert_ne!(thread_id().await.unwrap(), std::thread::current().id());
+
+        let request = Request::fake().param("numbers", "1,2,39").build();
+        let response = TestResponse::from(sum(request).await);
+        response.assert_status(200);
+        assert_eq!(response.text(), "42");
+    }
+}
@@ -9,6 +9,9 @@ use crate::config::{env, env_optional};
 /// - `REDIS_URL` - Redis connection URL (default: redis://127.0.0.1:6379)
 /// - `REDIS_PREFIX` - Key prefix for cache entries (default: "kit_cache:")
 /// - `CACHE_DEFAULT_TTL` - Default TTL in seconds, 0 = no expiration (default: 3600)
+/// - `REDIS_RECONNECT_INTERVAL` - Seconds between reconnection attempts while
+///   Redis is down and the cache is in memory (default: 30)
+/// - `CACHE_ENCRYPT` - Encrypt cached values with `APP_KEY` (default: false)
 ///
 /// # Example
 ///
@@ -32,6 +35,10 @@ pub struct CacheConfig {
     pub prefix: String,
     /// Default TTL in seconds (0 = no expiration)
     pub default_ttl: u64,
+    /// Seconds between attempts to reach Redis again after losing it
+    pub reconnect_interval: u64,
+    /// Encrypt cached values with `APP_KEY`
+    pub encrypt: bool,
 }
 
 impl CacheConfig {
@@ -41,6 +48,8 @@ impl CacheConfig {
             url: env_optional("REDIS_URL").unwrap_or_else(|| "redis://127.0.0.1:6379".to_string()),
             prefix: env("REDIS_PREFIX", "kit_cache:".to_string()),
             default_ttl: env("CACHE_DEFAULT_TTL", 3600),
+            reconnect_interval: env("REDIS_RECONNECT_INTERVAL", 30),
+            encrypt: env("CACHE_ENCRYPT", false),
         }
     }
 
@@ -62,6 +71,8 @@ pub struct CacheConfigBuilder {
     url: Option<String>,
     prefix: Option<String>,
     default_ttl: Option<u64>,
+    reconnect_interval: Option<u64>,
+    encrypt: Option<bool>,
 }
 
 impl CacheConfigBuilder {
@@ -83,6 +94,18 @@ impl CacheConfigBuilder {
         self
     }
 
+    /// Set the seconds between reconnection attempts while Redis is down
+    pub fn reconnect_interval(mut self, seconds: u64) -> Self {
+        self.reconnect_interval = Some(seconds);
+        self
+    }
+
+    /// Encrypt cached values with `APP_KEY`
+    pub fn encrypt(mut self, encrypt: bool) -> Self {
+        self.encrypt = Some(encrypt);
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> CacheConfig {
         let defaults = CacheConfig::from_env();
@@ -90,6 +113,10 @@ impl CacheConfigBuilder {
             url: self.url.unwrap_or(defaults.url),
             prefix: self.prefix.unwrap_or(defaults.prefix),
             default_ttl: self.default_ttl.unwrap_or(defaults.default_ttl),
+            reconnect_interval: self
+                .reconnect_interval
+                .unwrap_or(defaults.reconnect_interval),
+            encrypt: self.encrypt.unwrap_or(defaults.encrypt),
         }
     }
 }
@@ -0,0 +1,99 @@
+//! Cache store wrapper that encrypts values at rest
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::store::CacheStore;
+use crate::crypt::Encrypter;
+use crate::error::FrameworkError;
+
+/// Encrypts values before they reach another store
+///
+/// Bound by `Cache::bootstrap` when `CACHE_ENCRYPT` is set, so values on a
+/// shared Redis can't be read without `APP_KEY`. Keys aren't encrypted, and
+/// neither are counters from `increment`/`decrement`, which Redis has to do
+/// arithmetic on. Values cached before encryption was turned on are still
+/// read.
+pub struct EncryptedCache {
+    inner: Arc<dyn CacheStore>,
+    encrypter: Encrypter,
+}
+
+impl EncryptedCache {
+    /// Wrap a store, encrypting with the given encrypter
+    pub fn new(inner: Arc<dyn CacheStore>, encrypter: Encrypter) -> Self {
+        Self { inner, encrypter }
+    }
+}
+
+#[async_trait]
+impl CacheStore for EncryptedCache {
+    async fn get_raw(&self, key: &str) -> Result<Option<String>, FrameworkError> {
+        match self.inner.get_raw(key).await? {
+            Some(payload) => self.encrypter.open_json(payload, true).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn put_raw(
+        &self,
+        key: &str,
+        value: &str,
+        ttl: Option<Duration>,
+    ) -> Result<(), FrameworkError> {
+        let payload = self.encrypter.encrypt_string(value)?;
+        self.inner.put_raw(key, &payload, ttl).await
+    }
+
+    async fn has(&self, key: &str) -> Result<bool, FrameworkError> {
+        self.inner.has(key).await
+    }
+
+    async fn forget(&self, key: &str) -> Result<bool, FrameworkError> {
+        self.inner.forget(key).await
+    }
+
+    async fn flush(&self) -> Result<(), FrameworkError> {
+        self.inner.flush().await
+    }
+
+    async fn increment(&self, key: &str, amount: i64) -> Result<i64, FrameworkError> {
+        self.inner.increment(key, amount).await
+    }
+
+    async fn decrement(&self, key: &str, amount: i64) -> Result<i64, FrameworkError> {
+        self.inner.decrement(key, amount).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::InMemoryCache;
+
+    #[tokio::test]
+    async fn values_are_encrypted_in_the_inner_store() {
+        let inner = Arc::new(InMemoryCache::new());
+        inner.put_raw("legacy", r#""plain""#, None).await.unwrap();
+        let cache = EncryptedCache::new(inner.clone(), Encrypter::new("secret"));
+
+        cache
+            .put_raw("user:1", r#"{"email":"ada@example.com"}"#, None)
+            .await
+            .unwrap();
+        let stored = inner.get_raw("user:1").await.unwrap().unwrap();
+        assert!(!stored.contains("ada@example.com"));
+        assert_eq!(
+            cache.get_raw("user:1").await.unwrap().as_deref(),
+            Some(r#"{"email":"ada@example.com"}"#)
+        );
+
+        assert_eq!(
+            cache.get_raw("legacy").await.unwrap().as_deref(),
+            Some(r#""plain""#)
+        );
+        assert_eq!(cache.increment("visits", 3).await.unwrap(), 3);
+        assert_eq!(cache.get_raw("visits").await.unwrap().as_deref(), Some("3"));
+    }
+}
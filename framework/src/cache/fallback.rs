@@ -0,0 +1,332 @@
+//! Redis cache that keeps working in memory while Redis is down
+//!
+//! `Cache::bootstrap` binds a `FallbackCache`, so sessions and everything
+//! else on the `Cache` store survive a Redis restart or network blip. When a
+//! Redis call fails because Redis can't be reached (an I/O error, a dropped
+//! or refused connection, or a timeout) the cache switches to memory, dispatches
+//! `CacheHealth::Degraded` and tries to reconnect every
+//! `CacheConfig::reconnect_interval` seconds. Once Redis answers again it is
+//! used from then on and `CacheHealth::Recovered` is dispatched. Any other
+//! Redis error, such as a command on a key of the wrong type, is returned to
+//! the caller and leaves the cache on Redis.
+//!
+//! Entries written while degraded stay in memory and are dropped on
+//! recovery, so users may have to sign in again after an outage.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{listener, CacheHealth};
+//!
+//! #[listener]
+//! fn alert_on_redis_outage(event: &CacheHealth) {
+//!     if let CacheHealth::Degraded { error } = event {
+//!         tracing::error!(%error, "Redis is down, caching in memory");
+//!     }
+//! }
+//! ```
+
+use async_trait::async_trait;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use super::config::CacheConfig;
+use super::memory::InMemoryCache;
+use super::redis::{RedisCache, UNAVAILABLE};
+use super::store::CacheStore;
+use crate::error::FrameworkError;
+use crate::events::Event;
+
+/// How long to wait for Redis to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Event dispatched when the cache loses or regains Redis
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheHealth {
+    /// A Redis call failed; the cache is in memory until it reconnects
+    Degraded { error: String },
+    /// Redis is reachable again and the cache is back on it
+    Recovered,
+}
+
+/// Cache store that uses Redis when reachable and memory otherwise
+pub struct FallbackCache {
+    redis: Arc<RwLock<Option<Arc<dyn CacheStore>>>>,
+    memory: Arc<InMemoryCache>,
+    reconnecting: Arc<AtomicBool>,
+    config: CacheConfig,
+}
+
+impl FallbackCache {
+    /// Connect to Redis, starting in memory if it can't be reached
+    ///
+    /// A failed first connection doesn't dispatch `CacheHealth::Degraded`;
+    /// apps without Redis just use memory, as they always have.
+    pub async fn connect(config: &CacheConfig) -> Self {
+        let redis = connect_redis(config).await.ok();
+        let cache = Self::new(redis, config);
+        if cache.is_degraded() {
+            cache.reconnect_in_background();
+        }
+        cache
+    }
+
+    fn new(redis: Option<Arc<dyn CacheStore>>, config: &CacheConfig) -> Self {
+        Self {
+            redis: Arc::new(RwLock::new(redis)),
+            memory: Arc::new(InMemoryCache::with_prefix(&config.prefix)),
+            reconnecting: Arc::new(AtomicBool::new(false)),
+            config: config.clone(),
+        }
+    }
+
+    /// Check if the cache is in memory because Redis is unreachable
+    pub fn is_degraded(&self) -> bool {
+        self.redis.read().unwrap().is_none()
+    }
+
+    /// Run `op` against Redis, or memory if Redis is down or can't be reached
+    async fn run<T, F, Fut>(&self, op: F) -> Result<T, FrameworkError>
+    where
+        F: Fn(Arc<dyn CacheStore>) -> Fut + Send,
+        Fut: Future<Output = Result<T, FrameworkError>> + Send,
+        T: Send,
+    {
+        let redis = self.redis.read().unwrap().clone();
+        if let Some(redis) = redis {
+            match op(redis).await {
+                Ok(value) => return Ok(value),
+                Err(e) if is_unavailable(&e) => self.degrade(e).await,
+                Err(e) => return Err(e),
+            }
+        }
+        op(self.memory.clone()).await
+    }
+
+    async fn degrade(&self, error: FrameworkError) {
+        if self.redis.write().unwrap().take().is_none() {
+            // Another request got here first
+            return;
+        }
+        eprintln!(
+            "Warning: Redis is unavailable, caching in memory until it reconnects: {}",
+            error
+        );
+        self.reconnect_in_background();
+        let _ = Event::dispatch(CacheHealth::Degraded {
+            error: error.to_string(),
+        })
+        .await;
+    }
+
+    fn reconnect_in_background(&self) {
+        if self.reconnecting.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let redis = Arc::clone(&self.redis);
+        let memory = Arc::clone(&self.memory);
+        let reconnecting = Arc::clone(&self.reconnecting);
+        let config = self.config.clone();
+        let interval = Duration::from_secs(config.reconnect_interval.max(1));
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Ok(store) = connect_redis(&config).await else {
+                    continue;
+                };
+                // Entries from the outage would be stale by the next one
+                let _ = memory.flush().await;
+                *redis.write().unwrap() = Some(store);
+                reconnecting.store(false, Ordering::SeqCst);
+                let _ = Event::dispatch(CacheHealth::Recovered).await;
+                return;
+            }
+        });
+    }
+}
+
+/// Whether a Redis error means Redis can't be reached
+fn is_unavailable(error: &FrameworkError) -> bool {
+    matches!(error, FrameworkError::Internal { message } if message.starts_with(UNAVAILABLE))
+}
+
+async fn connect_redis(config: &CacheConfig) -> Result<Arc<dyn CacheStore>, FrameworkError> {
+    let cache = tokio::time::timeout(CONNECT_TIMEOUT, RedisCache::connect(config))
+        .await
+        .map_err(|_| FrameworkError::internal("Redis connection timed out"))??;
+    Ok(Arc::new(cache))
+}
+
+#[async_trait]
+impl CacheStore for FallbackCache {
+    async fn get_raw(&self, key: &str) -> Result<Option<String>, FrameworkError> {
+        self.run(|store| async move { store.get_raw(key).await })
+            .await
+    }
+
+    async fn put_raw(
+        &self,
+        key: &str,
+        value: &str,
+        ttl: Option<Duration>,
+    ) -> Result<(), FrameworkError> {
+        self.run(|store| async move { store.put_raw(key, value, ttl).await })
+            .await
+    }
+
+    async fn has(&self, key: &str) -> Result<bool, FrameworkError> {
+        self.run(|store| async move { store.has(key).await }).await
+    }
+
+    async fn forget(&self, key: &str) -> Result<bool, FrameworkError> {
+        self.run(|store| async move { store.forget(key).await })
+            .await
+    }
+
+    async fn flush(&self) -> Result<(), FrameworkError> {
+        self.run(|store| async move { store.flush().await }).await
+    }
+
+    async fn increment(&self, key: &str, amount: i64) -> Result<i64, FrameworkError> {
+        self.run(|store| async move { store.increment(key, amount).await })
+            .await
+    }
+
+    async fn decrement(&self, key: &str, amount: i64) -> Result<i64, FrameworkError> {
+        self.run(|store| async move { store.decrement(key, amount).await })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refused() -> FrameworkError {
+        FrameworkError::internal(format!("{}: Connection refused", UNAVAILABLE))
+    }
+
+    /// A Redis that has gone away
+    struct Unreachable;
+
+    #[async_trait]
+    impl CacheStore for Unreachable {
+        async fn get_raw(&self, _: &str) -> Result<Option<String>, FrameworkError> {
+            Err(refused())
+        }
+
+        async fn put_raw(
+            &self,
+            _: &str,
+            _: &str,
+            _: Option<Duration>,
+        ) -> Result<(), FrameworkError> {
+            Err(refused())
+        }
+
+        async fn has(&self, _: &str) -> Result<bool, FrameworkError> {
+            Err(refused())
+        }
+
+        async fn forget(&self, _: &str) -> Result<bool, FrameworkError> {
+            Err(refused())
+        }
+
+        async fn flush(&self) -> Result<(), FrameworkError> {
+            Err(refused())
+        }
+
+        async fn increment(&self, _: &str, _: i64) -> Result<i64, FrameworkError> {
+            Err(refused())
+        }
+
+        async fn decrement(&self, _: &str, _: i64) -> Result<i64, FrameworkError> {
+            Err(refused())
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_redis_calls_fall_back_to_memory() {
+        let events = Event::fake();
+        let config = CacheConfig::builder().reconnect_interval(3600).build();
+        let cache = FallbackCache::new(Some(Arc::new(Unreachable)), &config);
+        assert!(!cache.is_degraded());
+
+        cache.put_raw("greeting", "\"hi\"", None).await.unwrap();
+        assert!(cache.is_degraded());
+        assert_eq!(
+            cache.get_raw("greeting").await.unwrap().as_deref(),
+            Some("\"hi\"")
+        );
+        assert_eq!(cache.increment("visits", 2).await.unwrap(), 2);
+
+        let dispatched = events.dispatched::<CacheHealth>();
+        assert_eq!(dispatched.len(), 1);
+        assert_eq!(
+            *dispatched[0],
+            CacheHealth::Degraded {
+                error: refused().to_string()
+            }
+        );
+    }
+
+    /// A Redis that is up but rejects increments on a string key
+    struct WrongType;
+
+    #[async_trait]
+    impl CacheStore for WrongType {
+        async fn get_raw(&self, _: &str) -> Result<Option<String>, FrameworkError> {
+            Ok(Some("\"hi\"".to_string()))
+        }
+
+        async fn put_raw(
+            &self,
+            _: &str,
+            _: &str,
+            _: Option<Duration>,
+        ) -> Result<(), FrameworkError> {
+            Ok(())
+        }
+
+        async fn has(&self, _: &str) -> Result<bool, FrameworkError> {
+            Ok(true)
+        }
+
+        async fn forget(&self, _: &str) -> Result<bool, FrameworkError> {
+            Ok(true)
+        }
+
+        async fn flush(&self) -> Result<(), FrameworkError> {
+            Ok(())
+        }
+
+        async fn increment(&self, _: &str, _: i64) -> Result<i64, FrameworkError> {
+            Err(FrameworkError::internal(
+                "Cache increment error: WRONGTYPE Operation against a key of the wrong type",
+            ))
+        }
+
+        async fn decrement(&self, _: &str, _: i64) -> Result<i64, FrameworkError> {
+            self.increment("", 0).await
+        }
+    }
+
+    #[tokio::test]
+    async fn application_errors_are_returned_without_falling_back() {
+        let events = Event::fake();
+        let config = CacheConfig::builder().reconnect_interval(3600).build();
+        let cache = FallbackCache::new(Some(Arc::new(WrongType)), &config);
+
+        let error = cache.increment("greeting", 1).await.unwrap_err();
+        assert!(error.to_string().contains("WRONGTYPE"));
+        assert!(!cache.is_degraded());
+        assert_eq!(
+            cache.get_raw("greeting").await.unwrap().as_deref(),
+            Some("\"hi\"")
+        );
+        assert!(events.dispatched::<CacheHealth>().is_empty());
+    }
+}
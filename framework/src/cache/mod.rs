@@ -1,6 +1,8 @@
 //! Cache module for Kit framework
 //!
-//! Provides a Redis-backed cache with automatic in-memory fallback.
+//! Provides a Redis-backed cache with automatic in-memory fallback. If Redis
+//! goes away while the app is running, the cache keeps working in memory and
+//! reconnects in the background; see [`FallbackCache`].
 //!
 //! # Quick Start
 //!
@@ -31,18 +33,24 @@
 //! ```
 
 pub mod config;
+pub mod encrypted;
+pub mod fallback;
 pub mod memory;
 pub mod redis;
 pub mod store;
 
 pub use config::{CacheConfig, CacheConfigBuilder};
+pub use encrypted::EncryptedCache;
+pub use fallback::{CacheHealth, FallbackCache};
 pub use memory::InMemoryCache;
 pub use redis::RedisCache;
 pub use store::CacheStore;
 
 use crate::config::Config;
 use crate::container::App;
+use crate::crypt::Crypt;
 use crate::error::FrameworkError;
+use crate::profile::{self, Phase};
 use serde::{de::DeserializeOwned, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -78,23 +86,24 @@ impl Cache {
     /// Bootstrap the cache system
     ///
     /// Tries to connect to Redis first. If Redis is unavailable,
-    /// falls back to in-memory cache automatically.
+    /// falls back to in-memory cache automatically, switching to Redis
+    /// once it can be reached. With `CACHE_ENCRYPT` values are encrypted
+    /// with `APP_KEY`.
     ///
     /// This is called automatically by `Server::run()`.
+    ///
+    /// # Panics
+    ///
+    /// If `CACHE_ENCRYPT` is set without an `APP_KEY`, rather than caching
+    /// in plain text.
     pub(crate) async fn bootstrap() {
         let config = Config::get::<CacheConfig>().unwrap_or_default();
-
-        // Try Redis first
-        match RedisCache::connect(&config).await {
-            Ok(redis_cache) => {
-                App::bind::<dyn CacheStore>(Arc::new(redis_cache));
-            }
-            Err(_) => {
-                // Fallback to in-memory
-                let memory_cache = InMemoryCache::with_prefix(&config.prefix);
-                App::bind::<dyn CacheStore>(Arc::new(memory_cache));
-            }
+        let mut store: Arc<dyn CacheStore> = Arc::new(FallbackCache::connect(&config).await);
+        if config.encrypt {
+            let encrypter = Crypt::encrypter().expect("CACHE_ENCRYPT needs APP_KEY to be set");
+            store = Arc::new(EncryptedCache::new(store, encrypter));
         }
+        App::bind::<dyn CacheStore>(store);
     }
 
     /// Get the underlying cache store
@@ -122,7 +131,7 @@ impl Cache {
     /// ```
     pub async fn get<T: DeserializeOwned>(key: &str) -> Result<Option<T>, FrameworkError> {
         let store = Self::store()?;
-        match store.get_raw(key).await? {
+        match profile::timed(Phase::Cache, store.get_raw(key)).await? {
             Some(json) => {
                 let value = serde_json::from_str(&json).map_err(|e| {
                     FrameworkError::internal(format!("Cache deserialize error: {}", e))
@@ -148,10 +157,9 @@ impl Cache {
         ttl: Option<Duration>,
     ) -> Result<(), FrameworkError> {
         let store = Self::store()?;
-        let json = serde_json::to_string(value).map_err(|e| {
-            FrameworkError::internal(format!("Cache serialize error: {}", e))
-        })?;
-        store.put_raw(key, &json, ttl).await
+        let json = serde_json::to_string(value)
+            .map_err(|e| FrameworkError::internal(format!("Cache serialize error: {}", e)))?;
+        profile::timed(Phase::Cache, store.put_raw(key, &json, ttl)).await
     }
 
     /// Store an item forever (no expiration)
@@ -176,7 +184,7 @@ impl Cache {
     /// ```
     pub async fn has(key: &str) -> Result<bool, FrameworkError> {
         let store = Self::store()?;
-        store.has(key).await
+        profile::timed(Phase::Cache, store.has(key)).await
     }
 
     /// Remove an item from the cache
@@ -190,7 +198,7 @@ impl Cache {
     /// ```
     pub async fn forget(key: &str) -> Result<bool, FrameworkError> {
         let store = Self::store()?;
-        store.forget(key).await
+        profile::timed(Phase::Cache, store.forget(key)).await
     }
 
     /// Remove all items from the cache
@@ -202,7 +210,7 @@ impl Cache {
     /// ```
     pub async fn flush() -> Result<(), FrameworkError> {
         let store = Self::store()?;
-        store.flush().await
+        profile::timed(Phase::Cache, store.flush()).await
     }
 
     /// Increment a numeric value
@@ -217,7 +225,7 @@ impl Cache {
     /// ```
     pub async fn increment(key: &str, amount: i64) -> Result<i64, FrameworkError> {
         let store = Self::store()?;
-        store.increment(key, amount).await
+        profile::timed(Phase::Cache, store.increment(key, amount)).await
     }
 
     /// Decrement a numeric value
@@ -232,7 +240,7 @@ impl Cache {
     /// ```
     pub async fn decrement(key: &str, amount: i64) -> Result<i64, FrameworkError> {
         let store = Self::store()?;
-        store.decrement(key, amount).await
+        profile::timed(Phase::Cache, store.decrement(key, amount)).await
     }
 
     /// Get an item or store a default value if it doesn't exist
@@ -1,13 +1,26 @@
 //! Redis-backed cache implementation
 
 use async_trait::async_trait;
-use redis::{aio::ConnectionManager, AsyncCommands, Client};
+use redis::{aio::ConnectionManager, AsyncCommands, Client, RedisError};
 use std::time::Duration;
 
 use super::config::CacheConfig;
 use super::store::CacheStore;
 use crate::error::FrameworkError;
 
+/// Start of the message of errors from Redis being unreachable, which
+/// `FallbackCache` switches to memory for
+pub(crate) const UNAVAILABLE: &str = "Redis unavailable";
+
+/// A Redis error as a `FrameworkError`, marking connectivity failures
+fn redis_error(context: &str, e: RedisError) -> FrameworkError {
+    if e.is_io_error() || e.is_connection_dropped() || e.is_connection_refusal() || e.is_timeout() {
+        FrameworkError::internal(format!("{}: {}: {}", UNAVAILABLE, context, e))
+    } else {
+        FrameworkError::internal(format!("{}: {}", context, e))
+    }
+}
+
 /// Redis cache implementation
 ///
 /// Uses redis-rs with async/tokio runtime for high-performance caching.
@@ -52,9 +65,10 @@ impl CacheStore for RedisCache {
         let mut conn = self.conn.clone();
         let key = self.prefixed_key(key);
 
-        let value: Option<String> = conn.get(&key).await.map_err(|e| {
-            FrameworkError::internal(format!("Cache get error: {}", e))
-        })?;
+        let value: Option<String> = conn
+            .get(&key)
+            .await
+            .map_err(|e| redis_error("Cache get error", e))?;
 
         Ok(value)
     }
@@ -73,11 +87,11 @@ impl CacheStore for RedisCache {
         if let Some(duration) = effective_ttl {
             conn.set_ex::<_, _, ()>(&key, value, duration.as_secs())
                 .await
-                .map_err(|e| FrameworkError::internal(format!("Cache set error: {}", e)))?;
+                .map_err(|e| redis_error("Cache set error", e))?;
         } else {
             conn.set::<_, _, ()>(&key, value)
                 .await
-                .map_err(|e| FrameworkError::internal(format!("Cache set error: {}", e)))?;
+                .map_err(|e| redis_error("Cache set error", e))?;
         }
 
         Ok(())
@@ -87,9 +101,10 @@ impl CacheStore for RedisCache {
         let mut conn = self.conn.clone();
         let key = self.prefixed_key(key);
 
-        let exists: bool = conn.exists(&key).await.map_err(|e| {
-            FrameworkError::internal(format!("Cache exists error: {}", e))
-        })?;
+        let exists: bool = conn
+            .exists(&key)
+            .await
+            .map_err(|e| redis_error("Cache exists error", e))?;
 
         Ok(exists)
     }
@@ -98,9 +113,10 @@ impl CacheStore for RedisCache {
         let mut conn = self.conn.clone();
         let key = self.prefixed_key(key);
 
-        let deleted: i64 = conn.del(&key).await.map_err(|e| {
-            FrameworkError::internal(format!("Cache delete error: {}", e))
-        })?;
+        let deleted: i64 = conn
+            .del(&key)
+            .await
+            .map_err(|e| redis_error("Cache delete error", e))?;
 
         Ok(deleted > 0)
     }
@@ -115,12 +131,12 @@ impl CacheStore for RedisCache {
             .arg(&pattern)
             .query_async(&mut conn)
             .await
-            .map_err(|e| FrameworkError::internal(format!("Cache flush scan error: {}", e)))?;
+            .map_err(|e| redis_error("Cache flush scan error", e))?;
 
         if !keys.is_empty() {
             conn.del::<_, ()>(keys)
                 .await
-                .map_err(|e| FrameworkError::internal(format!("Cache flush delete error: {}", e)))?;
+                .map_err(|e| redis_error("Cache flush delete error", e))?;
         }
 
         Ok(())
@@ -130,9 +146,10 @@ impl CacheStore for RedisCache {
         let mut conn = self.conn.clone();
         let key = self.prefixed_key(key);
 
-        let value: i64 = conn.incr(&key, amount).await.map_err(|e| {
-            FrameworkError::internal(format!("Cache increment error: {}", e))
-        })?;
+        let value: i64 = conn
+            .incr(&key, amount)
+            .await
+            .map_err(|e| redis_error("Cache increment error", e))?;
 
         Ok(value)
     }
@@ -141,9 +158,10 @@ impl CacheStore for RedisCache {
         let mut conn = self.conn.clone();
         let key = self.prefixed_key(key);
 
-        let value: i64 = conn.decr(&key, amount).await.map_err(|e| {
-            FrameworkError::internal(format!("Cache decrement error: {}", e))
-        })?;
+        let value: i64 = conn
+            .decr(&key, amount)
+            .await
+            .map_err(|e| redis_error("Cache decrement error", e))?;
 
         Ok(value)
     }
@@ -64,6 +64,8 @@ impl Config {
         // Register default configs
         repository::register(AppConfig::from_env());
         repository::register(ServerConfig::from_env());
+        repository::register(crate::http::TrustedProxies::from_env());
+        repository::register(crate::logging::Redaction::from_env());
 
         env
     }
@@ -1,4 +1,5 @@
 use crate::config::env::{env, Environment};
+use crate::strict::StrictMode;
 
 /// Application configuration
 #[derive(Debug, Clone)]
@@ -11,16 +12,25 @@ pub struct AppConfig {
     pub debug: bool,
     /// Application URL
     pub url: String,
+    /// Development checks for common mistakes, see `kit::strict`
+    pub strict_mode: StrictMode,
 }
 
 impl AppConfig {
     /// Build config from environment variables
     pub fn from_env() -> Self {
+        let environment = Environment::detect();
+        let strict_default = if environment == Environment::Local {
+            StrictMode::Log
+        } else {
+            StrictMode::Off
+        };
         Self {
             name: env("APP_NAME", "Kit Application".to_string()),
-            environment: Environment::detect(),
+            environment,
             debug: env("APP_DEBUG", true),
             url: env("APP_URL", "http://localhost:8080".to_string()),
+            strict_mode: env("APP_STRICT", strict_default),
         }
     }
 
@@ -58,6 +68,7 @@ pub struct AppConfigBuilder {
     environment: Option<Environment>,
     debug: Option<bool>,
     url: Option<String>,
+    strict_mode: Option<StrictMode>,
 }
 
 impl AppConfigBuilder {
@@ -85,6 +96,12 @@ impl AppConfigBuilder {
         self
     }
 
+    /// Set the strict mode
+    pub fn strict_mode(mut self, mode: StrictMode) -> Self {
+        self.strict_mode = Some(mode);
+        self
+    }
+
     /// Build the AppConfig
     pub fn build(self) -> AppConfig {
         let default = AppConfig::from_env();
@@ -93,6 +110,7 @@ impl AppConfigBuilder {
             environment: self.environment.unwrap_or(default.environment),
             debug: self.debug.unwrap_or(default.debug),
             url: self.url.unwrap_or(default.url),
+            strict_mode: self.strict_mode.unwrap_or(default.strict_mode),
         }
     }
 }
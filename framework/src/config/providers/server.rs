@@ -9,6 +9,24 @@ pub struct ServerConfig {
     pub port: u16,
     /// Maximum request body size in bytes (default: 10MB)
     pub max_body_size: usize,
+    /// Seconds allowed for reading a request body (0 disables)
+    pub body_timeout_secs: u64,
+    /// Log requests slower than this many milliseconds (0 disables)
+    pub slow_request_ms: u64,
+    /// Minutes between slowest-request summaries in development (0 disables)
+    pub slow_summary_minutes: u64,
+    /// Fraction of requests outside development given a `Server-Timing`
+    /// header, from 0.0 to 1.0
+    pub timing_sample_rate: f64,
+    /// Directory of `{status}.tsx` Inertia error pages; empty keeps JSON
+    /// errors for every request
+    pub error_pages: String,
+    /// PEM certificate chain to serve HTTPS with; empty serves plain HTTP
+    pub tls_cert: String,
+    /// PEM private key for `tls_cert`
+    pub tls_key: String,
+    /// Port that redirects plain HTTP to HTTPS when TLS is on (0 disables)
+    pub https_redirect_port: u16,
 }
 
 impl ServerConfig {
@@ -18,6 +36,17 @@ impl ServerConfig {
             host: env("SERVER_HOST", "127.0.0.1".to_string()),
             port: env("SERVER_PORT", 8080),
             max_body_size: env("SERVER_MAX_BODY_SIZE", 10 * 1024 * 1024), // 10MB
+            body_timeout_secs: env("SERVER_BODY_TIMEOUT", 30),
+            slow_request_ms: env("SERVER_SLOW_REQUEST_MS", 1000),
+            slow_summary_minutes: env("SERVER_SLOW_SUMMARY_MINUTES", 5),
+            timing_sample_rate: env("SERVER_TIMING_SAMPLE_RATE", 0.0),
+            error_pages: env(
+                "SERVER_ERROR_PAGES",
+                "frontend/src/pages/errors".to_string(),
+            ),
+            tls_cert: env("SERVER_TLS_CERT", String::new()),
+            tls_key: env("SERVER_TLS_KEY", String::new()),
+            https_redirect_port: env("SERVER_HTTPS_REDIRECT_PORT", 0),
         }
     }
 
@@ -39,6 +68,14 @@ pub struct ServerConfigBuilder {
     host: Option<String>,
     port: Option<u16>,
     max_body_size: Option<usize>,
+    body_timeout_secs: Option<u64>,
+    slow_request_ms: Option<u64>,
+    slow_summary_minutes: Option<u64>,
+    timing_sample_rate: Option<f64>,
+    error_pages: Option<String>,
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+    https_redirect_port: Option<u16>,
 }
 
 impl ServerConfigBuilder {
@@ -60,6 +97,51 @@ impl ServerConfigBuilder {
         self
     }
 
+    /// Set the seconds allowed for reading a request body (0 disables)
+    pub fn body_timeout_secs(mut self, secs: u64) -> Self {
+        self.body_timeout_secs = Some(secs);
+        self
+    }
+
+    /// Set the slow request threshold in milliseconds (0 disables)
+    pub fn slow_request_ms(mut self, ms: u64) -> Self {
+        self.slow_request_ms = Some(ms);
+        self
+    }
+
+    /// Set the minutes between slowest-request summaries (0 disables)
+    pub fn slow_summary_minutes(mut self, minutes: u64) -> Self {
+        self.slow_summary_minutes = Some(minutes);
+        self
+    }
+
+    /// Set the fraction of requests outside development given a
+    /// `Server-Timing` header
+    pub fn timing_sample_rate(mut self, rate: f64) -> Self {
+        self.timing_sample_rate = Some(rate);
+        self
+    }
+
+    /// Set the directory of Inertia error pages, empty to keep JSON errors
+    /// for every request
+    pub fn error_pages(mut self, dir: impl Into<String>) -> Self {
+        self.error_pages = Some(dir.into());
+        self
+    }
+
+    /// Serve HTTPS with a PEM certificate chain and private key
+    pub fn tls(mut self, cert: impl Into<String>, key: impl Into<String>) -> Self {
+        self.tls_cert = Some(cert.into());
+        self.tls_key = Some(key.into());
+        self
+    }
+
+    /// Set the port that redirects plain HTTP to HTTPS (0 disables)
+    pub fn https_redirect_port(mut self, port: u16) -> Self {
+        self.https_redirect_port = Some(port);
+        self
+    }
+
     /// Build the ServerConfig
     pub fn build(self) -> ServerConfig {
         let default = ServerConfig::from_env();
@@ -67,6 +149,20 @@ impl ServerConfigBuilder {
             host: self.host.unwrap_or(default.host),
             port: self.port.unwrap_or(default.port),
             max_body_size: self.max_body_size.unwrap_or(default.max_body_size),
+            body_timeout_secs: self.body_timeout_secs.unwrap_or(default.body_timeout_secs),
+            slow_request_ms: self.slow_request_ms.unwrap_or(default.slow_request_ms),
+            slow_summary_minutes: self
+                .slow_summary_minutes
+                .unwrap_or(default.slow_summary_minutes),
+            timing_sample_rate: self
+                .timing_sample_rate
+                .unwrap_or(default.timing_sample_rate),
+            error_pages: self.error_pages.unwrap_or(default.error_pages),
+            tls_cert: self.tls_cert.unwrap_or(default.tls_cert),
+            tls_key: self.tls_key.unwrap_or(default.tls_key),
+            https_redirect_port: self
+                .https_redirect_port
+                .unwrap_or(default.https_redirect_port),
         }
     }
 }
@@ -0,0 +1,109 @@
+//! Console commands defined by the application
+//!
+//! Applications can add their own commands to the `app` binary alongside the
+//! built-in ones (`serve`, `migrate`, `schedule:work`, ...). Commands are
+//! registered at compile time with the `#[console_command]` attribute and run
+//! after configuration and bootstrap, so the App container is fully booted.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{async_trait, console_command, ConsoleCommand, FrameworkError};
+//!
+//! #[console_command(name = "reports:send", description = "Email the daily reports")]
+//! #[derive(clap::Args)]
+//! pub struct SendReports {
+//!     /// Only include reports created after this date
+//!     #[arg(long)]
+//!     since: Option<String>,
+//! }
+//!
+//! #[async_trait]
+//! impl ConsoleCommand for SendReports {
+//!     async fn handle(&self) -> Result<(), FrameworkError> {
+//!         let mailer = App::make::<dyn Mailer>().unwrap();
+//!         // ...
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! ```bash
+//! cargo run -- reports:send --since=2024-01-01
+//! ```
+
+#[doc(hidden)]
+pub mod registry;
+
+use crate::error::FrameworkError;
+use async_trait::async_trait;
+
+pub use registry::CommandEntry;
+
+/// A console command provided by the application
+///
+/// The command's arguments are the fields of the implementing type, parsed
+/// by clap. Derive `clap::Args` on the type and register it with
+/// `#[console_command(name = "...")]`.
+#[async_trait]
+pub trait ConsoleCommand: Send + Sync + 'static {
+    /// Execute the command
+    ///
+    /// Returning an error prints it to stderr and exits with status 1.
+    async fn handle(&self) -> Result<(), FrameworkError>;
+}
+
+/// Iterate over all registered console commands
+pub fn commands() -> impl Iterator<Item = &'static CommandEntry> {
+    inventory::iter::<CommandEntry>.into_iter()
+}
+
+/// Find a registered console command by name
+pub fn find(name: &str) -> Option<&'static CommandEntry> {
+    registry::find(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static GREETED: AtomicUsize = AtomicUsize::new(0);
+
+    #[crate::console_command(name = "test:greet", description = "Greet someone")]
+    #[derive(clap::Args)]
+    struct GreetCommand {
+        #[arg(long, default_value = "1")]
+        times: usize,
+    }
+
+    #[async_trait]
+    impl ConsoleCommand for GreetCommand {
+        async fn handle(&self) -> Result<(), FrameworkError> {
+            GREETED.fetch_add(self.times, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_command_is_registered() {
+        let entry = find("test:greet").expect("command registered");
+        assert_eq!(entry.description, "Greet someone");
+        assert!(commands().any(|c| c.name == "test:greet"));
+        assert!(find("test:missing").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_command_runs_with_parsed_args() {
+        let entry = find("test:greet").unwrap();
+        let cli = clap::Command::new("app").subcommand(entry.to_clap());
+        let matches = cli
+            .try_get_matches_from(["app", "test:greet", "--times", "3"])
+            .unwrap();
+        let (name, args) = matches.subcommand().unwrap();
+        assert_eq!(name, "test:greet");
+
+        (entry.run)(args).await.unwrap();
+        assert_eq!(GREETED.load(Ordering::SeqCst), 3);
+    }
+}
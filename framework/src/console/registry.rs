@@ -0,0 +1,37 @@
+//! Console command registry via inventory
+
+use crate::error::FrameworkError;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Boxed console command runner
+pub type CommandRunner =
+    fn(&clap::ArgMatches) -> Pin<Box<dyn Future<Output = Result<(), FrameworkError>> + Send>>;
+
+/// Inventory entry for a console command
+pub struct CommandEntry {
+    /// Command name as typed on the command line (e.g. `reports:send`)
+    pub name: &'static str,
+    /// One-line description shown in `--help`
+    pub description: &'static str,
+    /// Adds the command's arguments to a clap command
+    pub augment: fn(clap::Command) -> clap::Command,
+    /// Parses the matched arguments and runs the command
+    pub run: CommandRunner,
+}
+
+inventory::collect!(CommandEntry);
+
+impl CommandEntry {
+    /// Build the clap subcommand for this entry
+    pub fn to_clap(&self) -> clap::Command {
+        (self.augment)(clap::Command::new(self.name).about(self.description))
+    }
+}
+
+/// Find a command entry by name
+pub fn find(name: &str) -> Option<&'static CommandEntry> {
+    inventory::iter::<CommandEntry>
+        .into_iter()
+        .find(|entry| entry.name == name)
+}
@@ -27,15 +27,24 @@
 //! ```
 
 pub mod provider;
+mod spy;
 pub mod testing;
 
 use std::any::{Any, TypeId};
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::sync::{Arc, OnceLock, RwLock};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use arc_swap::ArcSwap;
 
 /// Global application container
-static APP_CONTAINER: OnceLock<RwLock<Container>> = OnceLock::new();
+///
+/// Resolutions load the current snapshot without locking. Registrations are
+/// rare, so they copy the bindings, add theirs and swap the copy in.
+static APP_CONTAINER: OnceLock<ArcSwap<Container>> = OnceLock::new();
+
+/// Serializes registrations so concurrent copies don't drop each other's bindings
+static REGISTRATION: Mutex<()> = Mutex::new(());
 
 // Thread-local test overrides for isolated testing
 thread_local! {
@@ -56,6 +65,7 @@ enum Binding {
 ///
 /// Stores type-erased bindings keyed by TypeId. Supports both concrete types
 /// and trait objects (via Arc<dyn Trait>).
+#[derive(Clone)]
 pub struct Container {
     /// Type bindings: TypeId -> Binding
     bindings: HashMap<TypeId, Binding>,
@@ -209,7 +219,7 @@ impl App {
     /// Should be called once at application startup. This is automatically
     /// called by `Server::from_config()`.
     pub fn init() {
-        APP_CONTAINER.get_or_init(|| RwLock::new(Container::new()));
+        global();
     }
 
     /// Register a singleton instance (shared across all resolutions)
@@ -219,10 +229,7 @@ impl App {
     /// App::singleton(DatabaseConnection::new(&url));
     /// ```
     pub fn singleton<T: Any + Send + Sync + 'static>(instance: T) {
-        let container = APP_CONTAINER.get_or_init(|| RwLock::new(Container::new()));
-        if let Ok(mut c) = container.write() {
-            c.singleton(instance);
-        }
+        register(|c| c.singleton(instance));
     }
 
     /// Register a factory binding (new instance per resolution)
@@ -236,10 +243,7 @@ impl App {
         T: Any + Send + Sync + 'static,
         F: Fn() -> T + Send + Sync + 'static,
     {
-        let container = APP_CONTAINER.get_or_init(|| RwLock::new(Container::new()));
-        if let Ok(mut c) = container.write() {
-            c.factory(factory);
-        }
+        register(|c| c.factory(factory));
     }
 
     /// Bind a trait object to a concrete implementation (as singleton)
@@ -249,10 +253,7 @@ impl App {
     /// App::bind::<dyn HttpClient>(Arc::new(RealHttpClient::new()));
     /// ```
     pub fn bind<T: ?Sized + Send + Sync + 'static>(instance: Arc<T>) {
-        let container = APP_CONTAINER.get_or_init(|| RwLock::new(Container::new()));
-        if let Ok(mut c) = container.write() {
-            c.bind(instance);
-        }
+        register(|c| c.bind(instance));
     }
 
     /// Bind a trait object to a factory
@@ -265,10 +266,7 @@ impl App {
     where
         F: Fn() -> Arc<T> + Send + Sync + 'static,
     {
-        let container = APP_CONTAINER.get_or_init(|| RwLock::new(Container::new()));
-        if let Ok(mut c) = container.write() {
-            c.bind_factory(factory);
-        }
+        register(|c| c.bind_factory(factory));
     }
 
     /// Resolve a concrete type
@@ -292,8 +290,7 @@ impl App {
         }
 
         // Fall back to global container
-        let container = APP_CONTAINER.get()?;
-        container.read().ok()?.get::<T>()
+        APP_CONTAINER.get()?.load().get::<T>()
     }
 
     /// Resolve a trait binding - returns Arc<T>
@@ -317,8 +314,7 @@ impl App {
         }
 
         // Fall back to global container
-        let container = APP_CONTAINER.get()?;
-        container.read().ok()?.make::<T>()
+        APP_CONTAINER.get()?.load().make::<T>()
     }
 
     /// Resolve a concrete type, returning an error if not found
@@ -365,11 +361,7 @@ impl App {
             return true;
         }
 
-        APP_CONTAINER
-            .get()
-            .and_then(|c| c.read().ok())
-            .map(|c| c.has::<T>())
-            .unwrap_or(false)
+        APP_CONTAINER.get().is_some_and(|c| c.load().has::<T>())
     }
 
     /// Check if a trait binding is registered
@@ -388,9 +380,7 @@ impl App {
 
         APP_CONTAINER
             .get()
-            .and_then(|c| c.read().ok())
-            .map(|c| c.has_binding::<T>())
-            .unwrap_or(false)
+            .is_some_and(|c| c.load().has_binding::<T>())
     }
 
     /// Boot all auto-registered services
@@ -402,6 +392,19 @@ impl App {
     }
 }
 
+fn global() -> &'static ArcSwap<Container> {
+    APP_CONTAINER.get_or_init(|| ArcSwap::from_pointee(Container::new()))
+}
+
+/// Apply `f` to a copy of the global bindings and publish the copy
+fn register(f: impl FnOnce(&mut Container)) {
+    let _guard = REGISTRATION.lock().unwrap_or_else(|e| e.into_inner());
+    let container = global();
+    let mut updated = Container::clone(&container.load());
+    f(&mut updated);
+    container.store(Arc::new(updated));
+}
+
 /// Bind a trait to a singleton implementation (auto-wraps in Arc)
 ///
 /// # Example
@@ -457,3 +460,22 @@ macro_rules! factory {
         $crate::App::factory($factory)
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registration_keeps_earlier_bindings() {
+        struct First;
+        struct Second;
+
+        std::thread::scope(|s| {
+            s.spawn(|| App::singleton(Arc::new(First)));
+            s.spawn(|| App::singleton(Arc::new(Second)));
+        });
+
+        assert!(App::has::<Arc<First>>());
+        assert!(App::has::<Arc<Second>>());
+    }
+}
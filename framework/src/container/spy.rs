@@ -0,0 +1,271 @@
+//! Call recording for container bindings
+//!
+//! `TestContainer::spy::<dyn Trait>()` wraps the current binding of a
+//! `#[service]` trait in a proxy that records every call before delegating
+//! to the wrapped implementation.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::testing::TestContainer;
+//!
+//! #[tokio::test]
+//! async fn sends_the_welcome_email() {
+//!     let _guard = TestContainer::fake();
+//!     TestContainer::bind::<dyn Mailer>(Arc::new(FakeMailer::default()));
+//!     let mailer = TestContainer::spy::<dyn Mailer>();
+//!
+//!     RegisterUserAction::new().execute("ada@example.com").await.unwrap();
+//!
+//!     mailer
+//!         .assert_called("send")
+//!         .times(1)
+//!         .with(&["\"ada@example.com\"", "\"Welcome!\""]);
+//! }
+//! ```
+
+use super::testing::TestContainer;
+use super::App;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+/// A trait object that can be wrapped in a recording proxy
+///
+/// Implemented by `#[service]` for `dyn Trait`.
+pub trait Spyable: Send + Sync + 'static {
+    /// Wrap `inner` in a proxy that reports calls to `recorder`
+    fn spy(inner: Arc<Self>, recorder: SpyRecorder) -> Arc<Self>;
+}
+
+/// A recorded method call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpyCall {
+    /// The method name
+    pub method: &'static str,
+    /// The arguments, `Debug`-formatted (`_` for types without `Debug`)
+    pub args: Vec<String>,
+}
+
+/// Shared log of the calls made through a spy proxy
+#[derive(Clone, Default)]
+pub struct SpyRecorder {
+    calls: Arc<Mutex<Vec<SpyCall>>>,
+}
+
+impl SpyRecorder {
+    /// Record a call (used by the generated proxies)
+    pub fn record(&self, method: &'static str, args: Vec<String>) {
+        self.calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(SpyCall { method, args });
+    }
+
+    fn calls(&self) -> Vec<SpyCall> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Handle to the calls made through a spied binding
+pub struct Spy<T: ?Sized> {
+    recorder: SpyRecorder,
+    _marker: PhantomData<fn() -> Arc<T>>,
+}
+
+impl<T: ?Sized> Spy<T> {
+    /// All recorded calls, in order
+    pub fn calls(&self) -> Vec<SpyCall> {
+        self.recorder.calls()
+    }
+
+    /// The recorded calls to one method, in order
+    pub fn calls_to(&self, method: &str) -> Vec<SpyCall> {
+        self.calls()
+            .into_iter()
+            .filter(|call| call.method == method)
+            .collect()
+    }
+
+    /// Assert `method` was called at least once
+    #[track_caller]
+    pub fn assert_called(&self, method: &str) -> CallAssertion {
+        let calls = self.calls_to(method);
+        assert!(
+            !calls.is_empty(),
+            "Expected {} to be called, but it wasn't. Calls: {:?}",
+            method,
+            self.calls()
+        );
+        CallAssertion {
+            method: method.to_string(),
+            calls,
+        }
+    }
+
+    /// Assert `method` was never called
+    #[track_caller]
+    pub fn assert_not_called(&self, method: &str) {
+        let calls = self.calls_to(method);
+        assert!(
+            calls.is_empty(),
+            "Expected {} not to be called, but it was called {} time(s): {:?}",
+            method,
+            calls.len(),
+            calls
+        );
+    }
+}
+
+/// Further checks on the calls to a method, from `Spy::assert_called`
+pub struct CallAssertion {
+    method: String,
+    calls: Vec<SpyCall>,
+}
+
+impl CallAssertion {
+    /// Assert the method was called exactly `expected` times
+    #[track_caller]
+    pub fn times(self, expected: usize) -> Self {
+        assert_eq!(
+            self.calls.len(),
+            expected,
+            "Expected {} to be called {} time(s), but it was called {} time(s)",
+            self.method,
+            expected,
+            self.calls.len()
+        );
+        self
+    }
+
+    /// Assert one of the calls had these `Debug`-formatted arguments
+    #[track_caller]
+    pub fn with(self, args: &[&str]) -> Self {
+        assert!(
+            self.calls.iter().any(|call| call.args == args),
+            "Expected {} to be called with {:?}. Calls: {:?}",
+            self.method,
+            args,
+            self.calls
+        );
+        self
+    }
+}
+
+impl TestContainer {
+    /// Wrap the current binding of `T` in a recording proxy
+    ///
+    /// The binding is resolved like `App::make` (test container first), and the
+    /// proxy is bound in the test container, so later `App::make::<T>()` calls
+    /// go through it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no test container is active (see `TestContainer::fake`) or
+    /// if `T` has no binding.
+    pub fn spy<T: ?Sized + Spyable>() -> Spy<T> {
+        assert!(
+            TestContainer::is_active(),
+            "TestContainer::spy() needs a test container, call TestContainer::fake() first"
+        );
+        let inner = App::make::<T>().unwrap_or_else(|| {
+            panic!(
+                "TestContainer::spy(): {} has no binding to spy on",
+                std::any::type_name::<T>()
+            )
+        });
+
+        let recorder = SpyRecorder::default();
+        TestContainer::bind::<T>(T::spy(inner, recorder.clone()));
+        Spy {
+            recorder,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Formats proxy arguments, falling back to `_` for types without `Debug`
+///
+/// The generated proxies call `(&SpyArg(&arg)).spy_format()` with both
+/// `SpyDebug` and `SpyOpaque` in scope; method resolution prefers `SpyDebug`.
+#[doc(hidden)]
+pub struct SpyArg<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait SpyDebug {
+    fn spy_format(&self) -> String;
+}
+
+impl<T: Debug + ?Sized> SpyDebug for SpyArg<'_, T> {
+    fn spy_format(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait SpyOpaque {
+    fn spy_format(&self) -> String;
+}
+
+impl<T: ?Sized> SpyOpaque for &SpyArg<'_, T> {
+    fn spy_format(&self) -> String {
+        "_".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service;
+
+    struct Secret;
+
+    #[service]
+    trait Greeter {
+        fn greet(&self, name: &str, times: usize) -> String;
+        fn whisper(&self, secret: Secret) -> &'static str;
+    }
+
+    struct EnglishGreeter;
+
+    impl Greeter for EnglishGreeter {
+        fn greet(&self, name: &str, times: usize) -> String {
+            format!("Hello {}", name).repeat(times)
+        }
+
+        fn whisper(&self, _secret: Secret) -> &'static str {
+            "psst"
+        }
+    }
+
+    #[test]
+    fn test_spy_records_calls_and_delegates() {
+        let _guard = TestContainer::fake();
+        TestContainer::bind::<dyn Greeter>(Arc::new(EnglishGreeter));
+        let spy = TestContainer::spy::<dyn Greeter>();
+
+        let greeter = App::make::<dyn Greeter>().unwrap();
+        assert_eq!(greeter.greet("Ada", 1), "Hello Ada");
+        greeter.greet("Grace", 2);
+        assert_eq!(greeter.whisper(Secret), "psst");
+
+        spy.assert_called("greet")
+            .times(2)
+            .with(&["\"Grace\"", "2"]);
+        spy.assert_called("whisper").with(&["_"]);
+        spy.assert_not_called("shout");
+        assert_eq!(spy.calls()[0].method, "greet");
+    }
+
+    #[test]
+    #[should_panic(expected = "to be called 3 time(s)")]
+    fn test_times_mismatch_panics() {
+        let _guard = TestContainer::fake();
+        TestContainer::bind::<dyn Greeter>(Arc::new(EnglishGreeter));
+        let spy = TestContainer::spy::<dyn Greeter>();
+
+        App::make::<dyn Greeter>().unwrap().greet("Ada", 1);
+
+        spy.assert_called("greet").times(3);
+    }
+}
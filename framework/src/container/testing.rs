@@ -20,6 +20,10 @@
 //! }
 //! ```
 
+pub use super::spy::{CallAssertion, Spy, SpyCall, SpyRecorder, Spyable};
+#[doc(hidden)]
+pub use super::spy::{SpyArg, SpyDebug, SpyOpaque};
+
 use super::{Container, TEST_CONTAINER};
 use std::any::Any;
 use std::sync::Arc;
@@ -51,6 +55,11 @@ impl TestContainer {
         TestContainerGuard
     }
 
+    /// Whether a test container is active on this thread
+    pub fn is_active() -> bool {
+        TEST_CONTAINER.with(|c| c.borrow().is_some())
+    }
+
     /// Register a fake singleton for testing
     ///
     /// # Example
@@ -128,3 +137,64 @@ impl Drop for TestContainerGuard {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{service, App};
+
+    #[service(mock)]
+    trait Clock {
+        fn now(&self) -> u64;
+        fn format(&self, timestamp: u64, pattern: &str) -> String;
+        fn label(&self) -> &'static str {
+            "clock"
+        }
+    }
+
+    #[test]
+    fn test_mock_returns_configured_values() {
+        let _guard = TestContainer::fake();
+        MockClock::new()
+            .on_now(|| 42)
+            .on_format(|timestamp, pattern| format!("{}@{}", pattern, timestamp))
+            .bind();
+
+        let clock = App::make::<dyn Clock>().unwrap();
+        assert_eq!(clock.now(), 42);
+        assert_eq!(clock.format(7, "iso"), "iso@7");
+        assert_eq!(clock.label(), "clock");
+        assert_eq!(MockClock::new().on_label(|| "mock").label(), "mock");
+    }
+
+    // No #[async_trait]: #[service] adds it for async methods
+    #[service(mock)]
+    trait Fetcher {
+        async fn fetch(&self, id: u32) -> String;
+        async fn fetch_all(&self, ids: &[u32]) -> Vec<String> {
+            let mut all = Vec::new();
+            for id in ids {
+                all.push(self.fetch(*id).await);
+            }
+            all
+        }
+        fn cached() -> Self
+        where
+            Self: Sized;
+    }
+
+    #[tokio::test]
+    async fn test_async_service_methods() {
+        let _guard = TestContainer::fake();
+        MockFetcher::new().on_fetch(|id| format!("#{}", id)).bind();
+
+        let fetcher = App::make::<dyn Fetcher>().unwrap();
+        assert_eq!(fetcher.fetch_all(&[1, 2]).await, ["#1", "#2"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "MockClock::now was called, but no return was configured")]
+    fn test_unconfigured_mock_method_panics() {
+        MockClock::new().now();
+    }
+}
@@ -0,0 +1,194 @@
+//! Encryption for Kit framework
+//!
+//! Encrypts values with AES-256-GCM under a key derived from `APP_KEY`, so
+//! they can be stored somewhere shared and can't be read or altered without
+//! the key. Sessions and the cache use it for `SESSION_ENCRYPT` and
+//! `CACHE_ENCRYPT`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::Crypt;
+//!
+//! let token = Crypt::encrypt_string("4111 1111 1111 1111")?;
+//! let card = Crypt::decrypt_string(&token)?;
+//!
+//! // Any serde type
+//! let token = Crypt::encrypt(&profile)?;
+//! let profile: Profile = Crypt::decrypt(&token)?;
+//! ```
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use rand::RngCore;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::env_optional;
+use crate::error::FrameworkError;
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// Crypt facade - encrypts with the application key
+pub struct Crypt;
+
+impl Crypt {
+    /// An encrypter for `APP_KEY`
+    ///
+    /// Fails if `APP_KEY` isn't set.
+    pub fn encrypter() -> Result<Encrypter, FrameworkError> {
+        env_optional::<String>("APP_KEY")
+            .filter(|key| !key.is_empty())
+            .map(|key| Encrypter::new(&key))
+            .ok_or_else(|| FrameworkError::internal("Encryption needs APP_KEY to be set"))
+    }
+
+    /// Encrypt a string
+    pub fn encrypt_string(value: &str) -> Result<String, FrameworkError> {
+        Self::encrypter()?.encrypt_string(value)
+    }
+
+    /// Decrypt a string from `encrypt_string`
+    pub fn decrypt_string(payload: &str) -> Result<String, FrameworkError> {
+        Self::encrypter()?.decrypt_string(payload)
+    }
+
+    /// Serialize a value to JSON and encrypt it
+    pub fn encrypt<T: Serialize>(value: &T) -> Result<String, FrameworkError> {
+        let json = serde_json::to_string(value)
+            .map_err(|e| FrameworkError::internal(format!("Encrypt serialize error: {}", e)))?;
+        Self::encrypt_string(&json)
+    }
+
+    /// Decrypt a value from `encrypt`
+    pub fn decrypt<T: DeserializeOwned>(payload: &str) -> Result<T, FrameworkError> {
+        let json = Self::decrypt_string(payload)?;
+        serde_json::from_str(&json)
+            .map_err(|e| FrameworkError::internal(format!("Decrypt deserialize error: {}", e)))
+    }
+}
+
+/// Encrypts and decrypts with one key
+///
+/// Payloads are base64 of the nonce, the authentication tag and the
+/// ciphertext. Each encryption uses a fresh random nonce, so encrypting the
+/// same value twice gives different payloads.
+#[derive(Clone)]
+pub struct Encrypter {
+    key: [u8; 32],
+}
+
+impl Encrypter {
+    /// Create an encrypter, deriving the AES key from `key` with SHA-256
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: Sha256::digest(key.as_bytes()).into(),
+        }
+    }
+
+    /// Encrypt a string
+    pub fn encrypt_string(&self, value: &str) -> Result<String, FrameworkError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let mut tag = [0u8; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(&nonce),
+            &[],
+            value.as_bytes(),
+            &mut tag,
+        )
+        .map_err(|e| FrameworkError::internal(format!("Encryption error: {}", e)))?;
+
+        let mut payload = Vec::with_capacity(NONCE_LEN + TAG_LEN + ciphertext.len());
+        payload.extend_from_slice(&nonce);
+        payload.extend_from_slice(&tag);
+        payload.extend_from_slice(&ciphertext);
+        Ok(STANDARD.encode(payload))
+    }
+
+    /// Decrypt a string from `encrypt_string`
+    ///
+    /// Fails if the payload was encrypted with another key or altered.
+    pub fn decrypt_string(&self, payload: &str) -> Result<String, FrameworkError> {
+        let invalid = || FrameworkError::internal("The encrypted payload is invalid");
+        let bytes = STANDARD.decode(payload).map_err(|_| invalid())?;
+        if bytes.len() < NONCE_LEN + TAG_LEN {
+            return Err(invalid());
+        }
+        let (nonce, rest) = bytes.split_at(NONCE_LEN);
+        let (tag, ciphertext) = rest.split_at(TAG_LEN);
+        let plaintext = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(nonce),
+            &[],
+            ciphertext,
+            tag,
+        )
+        .map_err(|_| invalid())?;
+        String::from_utf8(plaintext).map_err(|_| invalid())
+    }
+
+    /// Decrypt a stored JSON payload, passing through payloads that are
+    /// already JSON if `allow_plaintext` is set
+    ///
+    /// Lets stores read values written before encryption was turned on.
+    /// Plaintext carries no integrity check, so anyone who can write to the
+    /// store can plant it; only allow it while migrating.
+    pub(crate) fn open_json(
+        &self,
+        payload: String,
+        allow_plaintext: bool,
+    ) -> Result<String, FrameworkError> {
+        if allow_plaintext && serde_json::from_str::<IgnoredAny>(&payload).is_ok() {
+            Ok(payload)
+        } else {
+            self.decrypt_string(&payload)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_other_keys() {
+        let encrypter = Encrypter::new("base64:c2VjcmV0");
+        let payload = encrypter.encrypt_string("ada@example.com").unwrap();
+        assert!(!payload.contains("ada"));
+        assert_ne!(
+            payload,
+            encrypter.encrypt_string("ada@example.com").unwrap()
+        );
+        assert_eq!(
+            encrypter.decrypt_string(&payload).unwrap(),
+            "ada@example.com"
+        );
+
+        assert!(Encrypter::new("other").decrypt_string(&payload).is_err());
+        let mut tampered = STANDARD.decode(&payload).unwrap();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(encrypter
+            .decrypt_string(&STANDARD.encode(tampered))
+            .is_err());
+        assert!(encrypter.decrypt_string("not base64!").is_err());
+    }
+
+    #[test]
+    fn plain_json_passes_through_only_when_allowed() {
+        let encrypter = Encrypter::new("secret");
+        let payload = encrypter.encrypt_string(r#"{"name":"Ada"}"#).unwrap();
+        assert_eq!(
+            encrypter.open_json(payload, false).unwrap(),
+            r#"{"name":"Ada"}"#
+        );
+        assert_eq!(encrypter.open_json("42".to_string(), true).unwrap(), "42");
+        assert!(encrypter.open_json("42".to_string(), false).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 //! CSRF protection middleware
 
-use crate::http::{HttpResponse, Response};
+use crate::http::{Cookie, HttpResponse, Response};
 use crate::middleware::{Middleware, Next};
 use crate::session::get_csrf_token;
 use crate::Request;
@@ -17,6 +17,10 @@ use async_trait::async_trait;
 /// 2. `X-XSRF-TOKEN` header (Laravel convention)
 /// 3. `_token` form field (traditional forms)
 ///
+/// Responses also carry the token in an `XSRF-TOKEN` cookie, which Axios
+/// (and so Inertia) sends back as `X-XSRF-TOKEN`. It uses the `CookieConfig`
+/// defaults but isn't HttpOnly, so scripts can read it.
+///
 /// # Usage
 ///
 /// ```rust,ignore
@@ -29,6 +33,8 @@ pub struct CsrfMiddleware {
     protected_methods: Vec<&'static str>,
     /// Paths to exclude from CSRF validation (e.g., webhooks)
     except: Vec<String>,
+    /// Whether to set the `XSRF-TOKEN` cookie
+    xsrf_cookie: bool,
 }
 
 impl CsrfMiddleware {
@@ -39,9 +45,17 @@ impl CsrfMiddleware {
         Self {
             protected_methods: vec!["POST", "PUT", "PATCH", "DELETE"],
             except: Vec::new(),
+            xsrf_cookie: true,
         }
     }
 
+    /// Don't set the `XSRF-TOKEN` cookie, e.g. when the token is only read
+    /// from the meta tag
+    pub fn without_xsrf_cookie(mut self) -> Self {
+        self.xsrf_cookie = false;
+        self
+    }
+
     /// Add paths to exclude from CSRF validation
     ///
     /// Useful for webhooks or API endpoints that use other authentication.
@@ -57,31 +71,8 @@ impl CsrfMiddleware {
         self
     }
 
-    /// Check if a path should be excluded from CSRF validation
-    fn is_excluded(&self, path: &str) -> bool {
-        for pattern in &self.except {
-            if pattern.ends_with('*') {
-                let prefix = &pattern[..pattern.len() - 1];
-                if path.starts_with(prefix) {
-                    return true;
-                }
-            } else if pattern == path {
-                return true;
-            }
-        }
-        false
-    }
-}
-
-impl Default for CsrfMiddleware {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[async_trait]
-impl Middleware for CsrfMiddleware {
-    async fn handle(&self, request: Request, next: Next) -> Response {
+    /// Validate the token, then run the rest of the pipeline
+    async fn verify(&self, request: Request, next: Next) -> Response {
         let method = request.method().as_str();
 
         // Only validate state-changing requests
@@ -127,6 +118,46 @@ impl Middleware for CsrfMiddleware {
             }
         }
     }
+
+    /// Check if a path should be excluded from CSRF validation
+    fn is_excluded(&self, path: &str) -> bool {
+        for pattern in &self.except {
+            if pattern.ends_with('*') {
+                let prefix = &pattern[..pattern.len() - 1];
+                if path.starts_with(prefix) {
+                    return true;
+                }
+            } else if pattern == path {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Default for CsrfMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Middleware for CsrfMiddleware {
+    async fn handle(&self, request: Request, next: Next) -> Response {
+        let response = self.verify(request, next).await;
+
+        // Read afterwards, as logging in may have changed the token
+        match get_csrf_token().filter(|_| self.xsrf_cookie) {
+            Some(token) => {
+                let cookie = Cookie::new("XSRF-TOKEN", token).http_only(false);
+                match response {
+                    Ok(res) => Ok(res.cookie(cookie)),
+                    Err(res) => Err(res.cookie(cookie)),
+                }
+            }
+            None => response,
+        }
+    }
 }
 
 /// Constant-time string comparison to prevent timing attacks
@@ -0,0 +1,482 @@
+//! Long-running daemon helper for worker commands
+//!
+//! `Daemon` wraps the main loop of worker processes (`schedule:work`,
+//! `workflow:work`, or application console commands) and decides when the
+//! loop should stop:
+//!
+//! - SIGTERM / Ctrl+C (the current iteration is allowed to finish)
+//! - `--max-jobs`: after processing a number of jobs
+//! - `--max-time`: after running for a number of seconds
+//! - `--memory`: when resident memory exceeds a limit, so a process
+//!   manager can restart the worker with a fresh heap
+//! - a stop file written by `kit daemon:stop`
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::daemon::{Daemon, DaemonOptions};
+//!
+//! let daemon = Daemon::new("mailer", options);
+//! let signal = daemon.signal();
+//!
+//! let reason = daemon
+//!     .run(|| {
+//!         let signal = signal.clone();
+//!         async move {
+//!             let sent = send_pending_emails().await?;
+//!             if sent == 0 {
+//!                 signal.sleep(Duration::from_secs(5)).await;
+//!             }
+//!             Ok(sent)
+//!         }
+//!     })
+//!     .await;
+//!
+//! std::process::exit(reason.exit_code());
+//! ```
+
+use crate::error::FrameworkError;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+
+/// Default location of the stop file written by `kit daemon:stop`
+pub const DEFAULT_STOP_FILE: &str = ".kit/daemon.stop";
+
+/// Exit code used when a daemon stops because of its memory limit
+pub const MEMORY_LIMIT_EXIT_CODE: i32 = 12;
+
+/// Command line options shared by daemon commands
+///
+/// Flatten into a clap command with `#[command(flatten)]`.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct DaemonOptions {
+    /// Stop after processing this many jobs
+    #[arg(long)]
+    pub max_jobs: Option<u64>,
+    /// Stop after running for this many seconds
+    #[arg(long)]
+    pub max_time: Option<u64>,
+    /// Stop when resident memory exceeds this many megabytes
+    #[arg(long)]
+    pub memory: Option<u64>,
+}
+
+/// Why a daemon loop stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// SIGTERM or Ctrl+C was received
+    Signal,
+    /// `--max-jobs` was reached
+    MaxJobs,
+    /// `--max-time` was reached
+    MaxTime,
+    /// `--memory` was exceeded
+    MemoryLimit,
+    /// The stop file was touched after the daemon started
+    StopFile,
+}
+
+impl StopReason {
+    /// Process exit code for this stop reason
+    ///
+    /// Memory limit stops exit with a non-zero code so process managers
+    /// restart the worker; all other reasons are a clean exit.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            StopReason::MemoryLimit => MEMORY_LIMIT_EXIT_CODE,
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            StopReason::Signal => "shutdown signal received",
+            StopReason::MaxJobs => "job limit reached",
+            StopReason::MaxTime => "time limit reached",
+            StopReason::MemoryLimit => "memory limit exceeded",
+            StopReason::StopFile => "stop requested via daemon:stop",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// Shared job counters for a daemon
+///
+/// Attach with `Daemon::stats()` to observe a worker from elsewhere in the
+/// process (e.g. the `work --supervise` dashboard).
+#[derive(Debug, Default)]
+pub struct WorkerStats {
+    processed: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl WorkerStats {
+    /// Record processed jobs
+    pub fn record_processed(&self, count: u64) {
+        self.processed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Record a failed job
+    pub fn record_failure(&self) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Total jobs processed
+    pub fn processed(&self) -> u64 {
+        self.processed.load(Ordering::Relaxed)
+    }
+
+    /// Total jobs failed
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+}
+
+/// Shutdown notification handed to daemon iterations
+///
+/// Use `sleep()` instead of `tokio::time::sleep()` while idle so the daemon
+/// reacts to SIGTERM without waiting for the full poll interval.
+#[derive(Clone)]
+pub struct ShutdownSignal {
+    rx: watch::Receiver<bool>,
+    deadline: Option<Instant>,
+}
+
+impl ShutdownSignal {
+    /// Whether a shutdown has been requested
+    pub fn is_requested(&self) -> bool {
+        *self.rx.borrow()
+    }
+
+    /// Sleep for `duration`, returning early if a shutdown is requested
+    /// or the daemon's `--max-time` runs out
+    pub async fn sleep(&self, duration: Duration) {
+        let mut rx = self.rx.clone();
+        if *rx.borrow_and_update() {
+            return;
+        }
+        let duration = match self.deadline {
+            Some(deadline) => duration.min(deadline.saturating_duration_since(Instant::now())),
+            None => duration,
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => {}
+            _ = rx.changed() => {}
+        }
+    }
+}
+
+/// Long-running worker loop with standard stop conditions
+pub struct Daemon {
+    name: String,
+    options: DaemonOptions,
+    stop_file: PathBuf,
+    stats: Option<Arc<WorkerStats>>,
+    started: Instant,
+    started_at: SystemTime,
+    shutdown_tx: watch::Sender<bool>,
+    shutdown_rx: watch::Receiver<bool>,
+}
+
+impl Daemon {
+    /// Create a daemon with the given display name and options
+    ///
+    /// `--max-time` and the stop file are measured from this point.
+    pub fn new(name: &str, options: DaemonOptions) -> Self {
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        Self {
+            name: name.to_string(),
+            options,
+            stop_file: PathBuf::from(DEFAULT_STOP_FILE),
+            stats: None,
+            started: Instant::now(),
+            started_at: SystemTime::now(),
+            shutdown_tx,
+            shutdown_rx,
+        }
+    }
+
+    /// Use a custom stop file path
+    pub fn stop_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.stop_file = path.into();
+        self
+    }
+
+    /// Record processed jobs and iteration errors into `stats`
+    pub fn stats(mut self, stats: Arc<WorkerStats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Get a shutdown signal for use inside iterations
+    pub fn signal(&self) -> ShutdownSignal {
+        ShutdownSignal {
+            rx: self.shutdown_rx.clone(),
+            deadline: self
+                .options
+                .max_time
+                .map(|secs| self.started + Duration::from_secs(secs)),
+        }
+    }
+
+    /// Request a graceful shutdown from within the process
+    pub fn shutdown(&self) {
+        let _ = self.shutdown_tx.send(true);
+    }
+
+    /// Run `iteration` until a stop condition is met
+    ///
+    /// Each iteration returns the number of jobs it processed. Errors are
+    /// logged and the loop continues. Iterations are never interrupted;
+    /// stop conditions are checked between them.
+    pub async fn run<F, Fut>(self, mut iteration: F) -> StopReason
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<u64, FrameworkError>>,
+    {
+        let mut jobs: u64 = 0;
+
+        let tx = self.shutdown_tx.clone();
+        let listener = tokio::spawn(async move {
+            shutdown_signal().await;
+            let _ = tx.send(true);
+        });
+
+        let reason = loop {
+            if let Some(reason) = self.check_stop(jobs) {
+                break reason;
+            }
+
+            match iteration().await {
+                Ok(processed) => {
+                    jobs += processed;
+                    if let Some(stats) = &self.stats {
+                        stats.record_processed(processed);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{} error: {}", self.name, e);
+                    if let Some(stats) = &self.stats {
+                        stats.record_failure();
+                    }
+                }
+            }
+        };
+
+        listener.abort();
+        println!("{} stopping: {}", self.name, reason);
+        reason
+    }
+
+    fn check_stop(&self, jobs: u64) -> Option<StopReason> {
+        if *self.shutdown_rx.borrow() {
+            return Some(StopReason::Signal);
+        }
+
+        if let Some(max_jobs) = self.options.max_jobs {
+            if jobs >= max_jobs {
+                return Some(StopReason::MaxJobs);
+            }
+        }
+
+        if let Some(max_time) = self.options.max_time {
+            if self.started.elapsed() >= Duration::from_secs(max_time) {
+                return Some(StopReason::MaxTime);
+            }
+        }
+
+        if let Some(limit) = self.options.memory {
+            if resident_memory_mb().is_some_and(|used| used >= limit) {
+                return Some(StopReason::MemoryLimit);
+            }
+        }
+
+        if stop_requested_since(&self.stop_file, self.started_at) {
+            return Some(StopReason::StopFile);
+        }
+
+        None
+    }
+}
+
+/// Ask all daemons using `path` as their stop file to stop
+///
+/// Daemons started before this call stop after their current iteration.
+/// Daemons started afterwards are unaffected.
+pub fn request_stop(path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    std::fs::write(path, now.to_string())
+}
+
+/// Whether the stop file holds a timestamp later than `since`
+fn stop_requested_since(path: &Path, since: SystemTime) -> bool {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let Ok(requested_ms) = contents.trim().parse::<u128>() else {
+        return false;
+    };
+    let since_ms = since
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    requested_ms >= since_ms
+}
+
+/// Resident memory of the current process in megabytes (Linux only)
+pub fn resident_memory_mb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
+/// Wait for SIGTERM or Ctrl+C
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stops_after_max_jobs() {
+        let options = DaemonOptions {
+            max_jobs: Some(5),
+            ..Default::default()
+        };
+        let mut iterations = 0;
+        let reason = Daemon::new("test", options)
+            .stop_file(std::env::temp_dir().join("kit-test-daemon-max-jobs.stop"))
+            .run(|| {
+                iterations += 1;
+                async { Ok(2) }
+            })
+            .await;
+
+        assert_eq!(reason, StopReason::MaxJobs);
+        assert_eq!(iterations, 3);
+    }
+
+    #[tokio::test]
+    async fn test_stops_after_max_time() {
+        let options = DaemonOptions {
+            max_time: Some(0),
+            ..Default::default()
+        };
+        let reason = Daemon::new("test", options)
+            .stop_file(std::env::temp_dir().join("kit-test-daemon-max-time.stop"))
+            .run(|| async { Ok(0) })
+            .await;
+
+        assert_eq!(reason, StopReason::MaxTime);
+    }
+
+    #[tokio::test]
+    async fn test_stops_on_shutdown_request() {
+        let daemon = Daemon::new("test", DaemonOptions::default())
+            .stop_file(std::env::temp_dir().join("kit-test-daemon-shutdown.stop"));
+        daemon.shutdown();
+
+        let reason = daemon.run(|| async { Ok(1) }).await;
+        assert_eq!(reason, StopReason::Signal);
+    }
+
+    #[tokio::test]
+    async fn test_stop_file_only_affects_running_daemons() {
+        let path = std::env::temp_dir().join("kit-test-daemon-stop-file.stop");
+        request_stop(&path).unwrap();
+
+        // Written before the daemon started: ignored
+        assert!(!stop_requested_since(
+            &path,
+            SystemTime::now() + Duration::from_secs(1)
+        ));
+        assert!(stop_requested_since(&path, UNIX_EPOCH));
+
+        let mut requested = false;
+        let stop_path = path.clone();
+        let reason = Daemon::new("test", DaemonOptions::default())
+            .stop_file(&path)
+            .run(|| {
+                if !requested {
+                    requested = true;
+                    request_stop(&stop_path).unwrap();
+                }
+                async { Ok(0) }
+            })
+            .await;
+
+        assert_eq!(reason, StopReason::StopFile);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_records_stats() {
+        let stats = Arc::new(WorkerStats::default());
+        let options = DaemonOptions {
+            max_jobs: Some(4),
+            ..Default::default()
+        };
+        let mut fail = true;
+        Daemon::new("test", options)
+            .stop_file(std::env::temp_dir().join("kit-test-daemon-stats.stop"))
+            .stats(stats.clone())
+            .run(|| {
+                fail = !fail;
+                let result = if fail {
+                    Err(FrameworkError::internal("boom"))
+                } else {
+                    Ok(2)
+                };
+                async move { result }
+            })
+            .await;
+
+        assert_eq!(stats.processed(), 4);
+        assert_eq!(stats.failed(), 1);
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(StopReason::MemoryLimit.exit_code(), MEMORY_LIMIT_EXIT_CODE);
+        assert_eq!(StopReason::Signal.exit_code(), 0);
+        assert_eq!(StopReason::MaxJobs.exit_code(), 0);
+    }
+}
@@ -1,6 +1,7 @@
 //! Database configuration for Kit framework
 
 use crate::config::{env, env_optional};
+use std::collections::HashMap;
 
 /// Database type enumeration
 #[derive(Debug, Clone, PartialEq)]
@@ -19,6 +20,11 @@ pub enum DatabaseType {
 /// - `DB_MIN_CONNECTIONS` - Minimum pool connections (default: 1)
 /// - `DB_CONNECT_TIMEOUT` - Connection timeout in seconds (default: 30)
 /// - `DB_LOGGING` - Enable SQL logging (default: false)
+/// - `DB_CONNECTIONS` - Comma-separated names of extra connections, e.g.
+///   `analytics,legacy`. Each reads `DB_<NAME>_URL` and optionally
+///   `DB_<NAME>_MAX_CONNECTIONS`, `DB_<NAME>_MIN_CONNECTIONS`,
+///   `DB_<NAME>_CONNECT_TIMEOUT` and `DB_<NAME>_LOGGING`, falling back to
+///   the default connection's pool settings
 ///
 /// # Example
 ///
@@ -32,6 +38,9 @@ pub enum DatabaseType {
 /// Config::register(DatabaseConfig::builder()
 ///     .url("postgres://localhost/mydb")
 ///     .max_connections(20)
+///     .connection("analytics", DatabaseConfig::builder()
+///         .url("postgres://replica/reporting")
+///         .build())
 ///     .build());
 /// ```
 #[derive(Debug, Clone)]
@@ -46,18 +55,41 @@ pub struct DatabaseConfig {
     pub connect_timeout: u64,
     /// Enable SQL query logging
     pub logging: bool,
+    /// Named connections besides the default, see `DB::connection_named`
+    pub connections: HashMap<String, DatabaseConfig>,
 }
 
 impl DatabaseConfig {
     /// Create configuration from environment variables
     pub fn from_env() -> Self {
-        Self {
+        let mut config = Self {
             url: env_optional("DATABASE_URL")
                 .unwrap_or_else(|| "sqlite://./database.db".to_string()),
             max_connections: env("DB_MAX_CONNECTIONS", 10),
             min_connections: env("DB_MIN_CONNECTIONS", 1),
             connect_timeout: env("DB_CONNECT_TIMEOUT", 30),
             logging: env("DB_LOGGING", false),
+            connections: HashMap::new(),
+        };
+
+        let names: String = env_optional("DB_CONNECTIONS").unwrap_or_default();
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let named = config.named_from_env(name);
+            config.connections.insert(name.to_string(), named);
+        }
+        config
+    }
+
+    /// Read the `DB_<NAME>_*` variables of a named connection
+    fn named_from_env(&self, name: &str) -> Self {
+        let prefix = format!("DB_{}", name.to_uppercase());
+        Self {
+            url: env_optional(&format!("{}_URL", prefix)).unwrap_or_default(),
+            max_connections: env(&format!("{}_MAX_CONNECTIONS", prefix), self.max_connections),
+            min_connections: env(&format!("{}_MIN_CONNECTIONS", prefix), self.min_connections),
+            connect_timeout: env(&format!("{}_CONNECT_TIMEOUT", prefix), self.connect_timeout),
+            logging: env(&format!("{}_LOGGING", prefix), self.logging),
+            connections: HashMap::new(),
         }
     }
 
@@ -97,6 +129,7 @@ pub struct DatabaseConfigBuilder {
     min_connections: Option<u32>,
     connect_timeout: Option<u64>,
     logging: Option<bool>,
+    connections: HashMap<String, DatabaseConfig>,
 }
 
 impl DatabaseConfigBuilder {
@@ -130,6 +163,12 @@ impl DatabaseConfigBuilder {
         self
     }
 
+    /// Add a named connection, see `DB::connection_named`
+    pub fn connection(mut self, name: impl Into<String>, config: DatabaseConfig) -> Self {
+        self.connections.insert(name.into(), config);
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> DatabaseConfig {
         let defaults = DatabaseConfig::from_env();
@@ -139,6 +178,11 @@ impl DatabaseConfigBuilder {
             min_connections: self.min_connections.unwrap_or(defaults.min_connections),
             connect_timeout: self.connect_timeout.unwrap_or(defaults.connect_timeout),
             logging: self.logging.unwrap_or(defaults.logging),
+            connections: if self.connections.is_empty() {
+                defaults.connections
+            } else {
+                self.connections
+            },
         }
     }
 }
@@ -1,12 +1,30 @@
 //! Database connection management
 
-use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use sea_orm::{
+    ConnectOptions, Database, DatabaseConnection, DatabaseTransaction, TransactionTrait,
+};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::database::config::DatabaseConfig;
 use crate::error::FrameworkError;
 
+/// The name of the connection configured by `DATABASE_URL`
+pub const DEFAULT_CONNECTION: &str = "default";
+
+/// The transaction opened by `DbConnection::transaction` on this task
+struct OpenTransaction {
+    connection: Arc<str>,
+    warned: Cell<bool>,
+}
+
+tokio::task_local! {
+    static OPEN_TRANSACTION: OpenTransaction;
+}
+
 /// Wrapper around SeaORM's DatabaseConnection
 ///
 /// This provides a clonable, thread-safe connection that can be stored
@@ -23,6 +41,7 @@ use crate::error::FrameworkError;
 #[derive(Clone)]
 pub struct DbConnection {
     inner: Arc<DatabaseConnection>,
+    name: Arc<str>,
 }
 
 impl DbConnection {
@@ -32,6 +51,36 @@ impl DbConnection {
     /// For SQLite databases, this will automatically create the database file
     /// if it doesn't exist.
     pub async fn connect(config: &DatabaseConfig) -> Result<Self, FrameworkError> {
+        Self::connect_named(DEFAULT_CONNECTION, config).await
+    }
+
+    /// Create a connection registered under `name`, see `DB::connection_named`
+    pub async fn connect_named(
+        name: &str,
+        config: &DatabaseConfig,
+    ) -> Result<Self, FrameworkError> {
+        Self::open(name, config, false).await
+    }
+
+    /// Create a connection pool that connects on first use
+    ///
+    /// Nothing is sent to the database until the first query, so commands
+    /// that never query work while the database is down. Until the server
+    /// accepts connections, queries wait and retry with exponential backoff
+    /// for up to `connect_timeout` seconds. `warm_up` connects eagerly.
+    pub async fn connect_lazy(name: &str, config: &DatabaseConfig) -> Result<Self, FrameworkError> {
+        Self::open(name, config, true).await
+    }
+
+    async fn open(name: &str, config: &DatabaseConfig, lazy: bool) -> Result<Self, FrameworkError> {
+        if config.url.is_empty() {
+            return Err(FrameworkError::internal(format!(
+                "Database connection '{}' has no URL. Set DB_{}_URL.",
+                name,
+                name.to_uppercase()
+            )));
+        }
+
         // For SQLite, ensure the database file can be created
         let url = if config.url.starts_with("sqlite://") {
             // Extract the file path from the URL
@@ -64,16 +113,113 @@ impl DbConnection {
             .min_connections(config.min_connections)
             .connect_timeout(Duration::from_secs(config.connect_timeout))
             .sqlx_logging(config.logging);
+        if lazy {
+            opt.connect_lazy(true)
+                .acquire_timeout(Duration::from_secs(config.connect_timeout));
+        }
 
-        let conn = Database::connect(opt)
+        let mut conn = Database::connect(opt)
             .await
             .map_err(|e| FrameworkError::database(e.to_string()))?;
 
+        // Count queries against the current request for the slow request log
+        // and strict mode, time them for `Server-Timing`, and trace them
+        conn.set_metric_callback(|info| {
+            crate::metrics::record_query();
+            crate::strict::record_query(&info.statement.sql);
+            crate::profile::record_duration(crate::profile::Phase::Database, info.elapsed);
+            crate::telemetry::record_query(&info.statement.sql, info.elapsed, info.failed);
+        });
+
         Ok(Self {
             inner: Arc::new(conn),
+            name: name.into(),
         })
     }
 
+    /// Connect now, failing if the database can't be reached
+    ///
+    /// For lazy connections this waits for the database like the first
+    /// query would.
+    pub async fn warm_up(&self) -> Result<(), FrameworkError> {
+        self.inner.ping().await.map_err(|e| {
+            FrameworkError::database(format!(
+                "Failed to connect to database '{}': {}",
+                self.name, e
+            ))
+        })
+    }
+
+    /// The name the connection is registered under (`"default"` for `DATABASE_URL`)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Run `f` in a transaction, committed if it returns `Ok`
+    ///
+    /// The transaction only covers queries on this connection that are run
+    /// on `txn`. Resolving a different named connection inside `f` logs a
+    /// warning: its queries are committed on their own and are not rolled
+    /// back with the transaction.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let conn = DB::connection()?;
+    /// conn.transaction(|txn| {
+    ///     Box::pin(async move {
+    ///         order.insert(txn).await?;
+    ///         stock.update(txn).await?;
+    ///         Ok(())
+    ///     })
+    /// })
+    /// .await?;
+    /// ```
+    pub async fn transaction<F, T>(&self, f: F) -> Result<T, FrameworkError>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            )
+                -> Pin<Box<dyn Future<Output = Result<T, FrameworkError>> + Send + 'c>>
+            + Send,
+        T: Send,
+    {
+        let txn = self
+            .inner
+            .begin()
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+        let open = OpenTransaction {
+            connection: self.name.clone(),
+            warned: Cell::new(false),
+        };
+        match OPEN_TRANSACTION.scope(open, f(&txn)).await {
+            Ok(value) => {
+                txn.commit()
+                    .await
+                    .map_err(|e| FrameworkError::database(e.to_string()))?;
+                Ok(value)
+            }
+            Err(e) => {
+                txn.rollback().await.ok();
+                Err(e)
+            }
+        }
+    }
+
+    /// Warn when `name` is used inside a transaction on another connection
+    pub(crate) fn warn_outside_transaction(name: &str) {
+        let _ = OPEN_TRANSACTION.try_with(|open| {
+            if &*open.connection != name && !open.warned.replace(true) {
+                eprintln!(
+                    "Warning: database connection '{}' used inside a transaction on '{}'. \
+                     Its queries are not part of the transaction and won't be rolled back with it.",
+                    name, open.connection
+                );
+            }
+        });
+    }
+
     /// Get a reference to the underlying SeaORM connection
     ///
     /// Use this when you need to execute raw SeaORM queries.
@@ -0,0 +1,441 @@
+//! Cursor-based pagination
+//!
+//! `QueryBuilder::cursor_paginate` pages by the values of the sort columns
+//! instead of an offset, so pages stay stable while rows are inserted or
+//! deleted and deep pages are as cheap as the first one. This suits
+//! infinite-scroll UIs and large tables.
+//!
+//! Cursors are opaque URL-safe base64 tokens. Pass a page's `next_cursor`
+//! or `prev_cursor` back to fetch the neighbouring page.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::database::CursorPage;
+//!
+//! let page: CursorPage<todos::Model> = Todo::query()
+//!     .order_by_desc(Column::CreatedAt)
+//!     .cursor_paginate(20, cursor.as_deref())
+//!     .await?;
+//!
+//! // { "data": [...], "next_cursor": "WyJu...", "prev_cursor": null }
+//! json_response!(page)
+//! ```
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sea_orm::prelude::{Decimal, Uuid};
+use sea_orm::sea_query::{Condition, IntoCondition};
+use sea_orm::{
+    ColumnTrait, EntityTrait, IdenStatic, Iterable, ModelTrait, Order, PrimaryKeyToColumn, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FrameworkError;
+
+/// One page of cursor-paginated results
+///
+/// Serializes to `{ data, next_cursor, prev_cursor }`; a cursor is `null`
+/// when there is no page in that direction.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CursorPage<T> {
+    /// Rows on this page, in query order
+    pub data: Vec<T>,
+    /// Token for the page after this one
+    pub next_cursor: Option<String>,
+    /// Token for the page before this one
+    pub prev_cursor: Option<String>,
+}
+
+impl<T> CursorPage<T> {
+    /// Convert every row, keeping the cursors
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let page = page.map(TodoResource::from);
+    /// ```
+    pub fn map<U, F>(self, f: F) -> CursorPage<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        CursorPage {
+            data: self.data.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            prev_cursor: self.prev_cursor,
+        }
+    }
+}
+
+/// Which way a cursor pages from its row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Direction {
+    #[serde(rename = "n")]
+    Next,
+    #[serde(rename = "p")]
+    Prev,
+}
+
+/// Decoded cursor token: direction plus `(column, type, value)` per sort key
+#[derive(Serialize, Deserialize)]
+struct Token(Direction, Vec<(String, String, String)>);
+
+/// Sort keys for a cursor query: the requested order columns followed by
+/// the primary key, which breaks ties so every row has a unique position
+pub(crate) fn sort_keys<E>(
+    orders: &[(String, Order)],
+) -> Result<Vec<(E::Column, Order)>, FrameworkError>
+where
+    E: EntityTrait,
+{
+    let mut keys = Vec::with_capacity(orders.len() + 1);
+    for (name, order) in orders {
+        let column = name.parse::<E::Column>().map_err(|_| {
+            FrameworkError::internal(format!(
+                "cursor_paginate can only order by columns of {}, got '{}'",
+                std::any::type_name::<E>(),
+                name
+            ))
+        })?;
+        if matches!(order, Order::Field(_)) {
+            return Err(FrameworkError::internal(
+                "cursor_paginate does not support ordering by field values",
+            ));
+        }
+        keys.push((column, order.clone()));
+    }
+
+    for key in E::PrimaryKey::iter() {
+        let column = key.into_column();
+        if !keys.iter().any(|(c, _)| c.as_str() == column.as_str()) {
+            keys.push((column, Order::Asc));
+        }
+    }
+    Ok(keys)
+}
+
+/// Encode a cursor pointing at `model`
+pub(crate) fn encode<E>(
+    model: &E::Model,
+    keys: &[(E::Column, Order)],
+    direction: Direction,
+) -> Result<String, FrameworkError>
+where
+    E: EntityTrait,
+{
+    let values = keys
+        .iter()
+        .map(|(column, _)| {
+            let (kind, value) = value_to_parts(model.get(*column)).ok_or_else(|| {
+                FrameworkError::internal(format!(
+                    "cursor_paginate cannot sort by column '{}': values must be non-null scalars",
+                    column.as_str()
+                ))
+            })?;
+            Ok((column.as_str().to_string(), kind.to_string(), value))
+        })
+        .collect::<Result<Vec<_>, FrameworkError>>()?;
+
+    let json = serde_json::to_vec(&Token(direction, values))
+        .map_err(|e| FrameworkError::internal(format!("Cursor encode error: {}", e)))?;
+    Ok(URL_SAFE_NO_PAD.encode(json))
+}
+
+/// Decode a cursor for a query sorted by `keys`
+///
+/// Malformed tokens, or tokens issued for a different sort order, are a
+/// 400 Bad Request.
+pub(crate) fn decode<C>(
+    token: &str,
+    keys: &[(C, Order)],
+) -> Result<(Direction, Vec<Value>), FrameworkError>
+where
+    C: ColumnTrait,
+{
+    let invalid = || FrameworkError::domain("Invalid pagination cursor", 400);
+
+    let json = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+    let Token(direction, parts) = serde_json::from_slice(&json).map_err(|_| invalid())?;
+    if parts.len() != keys.len() {
+        return Err(invalid());
+    }
+
+    let values = keys
+        .iter()
+        .zip(parts)
+        .map(|((column, _), (name, kind, value))| {
+            if name != column.as_str() {
+                return None;
+            }
+            value_from_parts(&kind, &value)
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    Ok((direction, values))
+}
+
+/// Rows strictly after `values` in `keys` order (before, when `backward`)
+///
+/// For keys `(a, b)` ascending this is `a > x OR (a = x AND b > y)`.
+pub(crate) fn keyset_condition<C>(
+    keys: &[(C, Order)],
+    values: &[Value],
+    backward: bool,
+) -> Condition
+where
+    C: ColumnTrait,
+{
+    let mut any = Condition::any();
+    for i in 0..keys.len() {
+        let mut all = Condition::all();
+        for ((column, _), value) in keys[..i].iter().zip(values) {
+            all = all.add(column.eq(value.clone()));
+        }
+
+        let (column, order) = &keys[i];
+        let value = values[i].clone();
+        let ascending = matches!(order, Order::Asc) != backward;
+        all = all.add(if ascending {
+            column.gt(value)
+        } else {
+            column.lt(value)
+        });
+        any = any.add(all.into_condition());
+    }
+    any
+}
+
+/// Reverse an order, for fetching the page before a cursor
+pub(crate) fn reverse(order: &Order) -> Order {
+    match order {
+        Order::Asc => Order::Desc,
+        _ => Order::Asc,
+    }
+}
+
+fn value_to_parts(value: Value) -> Option<(&'static str, String)> {
+    Some(match value {
+        Value::Bool(Some(v)) => ("bool", v.to_string()),
+        Value::TinyInt(Some(v)) => ("i8", v.to_string()),
+        Value::SmallInt(Some(v)) => ("i16", v.to_string()),
+        Value::Int(Some(v)) => ("i32", v.to_string()),
+        Value::BigInt(Some(v)) => ("i64", v.to_string()),
+        Value::TinyUnsigned(Some(v)) => ("u8", v.to_string()),
+        Value::SmallUnsigned(Some(v)) => ("u16", v.to_string()),
+        Value::Unsigned(Some(v)) => ("u32", v.to_string()),
+        Value::BigUnsigned(Some(v)) => ("u64", v.to_string()),
+        Value::Float(Some(v)) => ("f32", v.to_string()),
+        Value::Double(Some(v)) => ("f64", v.to_string()),
+        Value::String(Some(v)) => ("str", *v),
+        Value::Char(Some(v)) => ("char", v.to_string()),
+        Value::ChronoDate(Some(v)) => ("date", v.to_string()),
+        Value::ChronoTime(Some(v)) => ("time", v.to_string()),
+        Value::ChronoDateTime(Some(v)) => {
+            ("datetime", v.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+        }
+        Value::ChronoDateTimeUtc(Some(v)) => ("datetime_utc", v.to_rfc3339()),
+        Value::ChronoDateTimeLocal(Some(v)) => ("datetime_tz", v.fixed_offset().to_rfc3339()),
+        Value::ChronoDateTimeWithTimeZone(Some(v)) => ("datetime_tz", v.to_rfc3339()),
+        Value::Uuid(Some(v)) => ("uuid", v.to_string()),
+        Value::Decimal(Some(v)) => ("decimal", v.to_string()),
+        _ => return None,
+    })
+}
+
+fn value_from_parts(kind: &str, value: &str) -> Option<Value> {
+    Some(match kind {
+        "bool" => value.parse::<bool>().ok()?.into(),
+        "i8" => value.parse::<i8>().ok()?.into(),
+        "i16" => value.parse::<i16>().ok()?.into(),
+        "i32" => value.parse::<i32>().ok()?.into(),
+        "i64" => value.parse::<i64>().ok()?.into(),
+        "u8" => value.parse::<u8>().ok()?.into(),
+        "u16" => value.parse::<u16>().ok()?.into(),
+        "u32" => value.parse::<u32>().ok()?.into(),
+        "u64" => value.parse::<u64>().ok()?.into(),
+        "f32" => value.parse::<f32>().ok()?.into(),
+        "f64" => value.parse::<f64>().ok()?.into(),
+        "str" => value.to_string().into(),
+        "char" => value.parse::<char>().ok()?.into(),
+        "date" => value.parse::<chrono::NaiveDate>().ok()?.into(),
+        "time" => value.parse::<chrono::NaiveTime>().ok()?.into(),
+        "datetime" => value.parse::<chrono::NaiveDateTime>().ok()?.into(),
+        "datetime_utc" => chrono::DateTime::parse_from_rfc3339(value)
+            .ok()?
+            .to_utc()
+            .into(),
+        "datetime_tz" => chrono::DateTime::parse_from_rfc3339(value).ok()?.into(),
+        "uuid" => value.parse::<Uuid>().ok()?.into(),
+        "decimal" => value.parse::<Decimal>().ok()?.into(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::QueryBuilder;
+    use crate::testing::TestDatabase;
+    use sea_orm::{ActiveModelTrait, ConnectionTrait, DbErr, Set};
+    use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager};
+
+    mod posts {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "posts")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub title: String,
+            pub score: i32,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreatePosts)]
+        }
+    }
+
+    struct CreatePosts;
+
+    impl MigrationName for CreatePosts {
+        fn name(&self) -> &str {
+            "create_posts"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreatePosts {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "CREATE TABLE posts (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        title TEXT NOT NULL,
+                        score INTEGER NOT NULL
+                    )",
+                )
+                .await
+                .map(|_| ())
+        }
+    }
+
+    async fn seed(db: &TestDatabase, scores: &[i32]) {
+        for (i, score) in scores.iter().enumerate() {
+            posts::ActiveModel {
+                title: Set(format!("Post {}", i + 1)),
+                score: Set(*score),
+                ..Default::default()
+            }
+            .insert(db.conn())
+            .await
+            .unwrap();
+        }
+    }
+
+    fn ids(page: &CursorPage<posts::Model>) -> Vec<i32> {
+        page.data.iter().map(|post| post.id).collect()
+    }
+
+    #[tokio::test]
+    async fn pages_forward_and_back_by_primary_key() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        seed(&db, &[0, 0, 0, 0, 0]).await;
+        let query = QueryBuilder::<posts::Entity>::new;
+
+        let first = query().cursor_paginate(2, None).await.unwrap();
+        assert_eq!(ids(&first), vec![1, 2]);
+        assert!(first.prev_cursor.is_none());
+
+        let second = query()
+            .cursor_paginate(2, first.next_cursor.as_deref())
+            .await
+            .unwrap();
+        assert_eq!(ids(&second), vec![3, 4]);
+
+        let last = query()
+            .cursor_paginate(2, second.next_cursor.as_deref())
+            .await
+            .unwrap();
+        assert_eq!(ids(&last), vec![5]);
+        assert!(last.next_cursor.is_none());
+
+        let back = query()
+            .cursor_paginate(2, last.prev_cursor.as_deref())
+            .await
+            .unwrap();
+        assert_eq!(ids(&back), vec![3, 4]);
+
+        let start = query()
+            .cursor_paginate(2, back.prev_cursor.as_deref())
+            .await
+            .unwrap();
+        assert_eq!(ids(&start), vec![1, 2]);
+        assert!(start.prev_cursor.is_none());
+        assert!(start.next_cursor.is_some());
+    }
+
+    #[tokio::test]
+    async fn ties_in_the_sort_column_are_broken_by_primary_key() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        seed(&db, &[10, 30, 20, 30, 10]).await;
+        let query = || QueryBuilder::<posts::Entity>::new().order_by_desc(posts::Column::Score);
+
+        let first = query().cursor_paginate(3, None).await.unwrap();
+        assert_eq!(ids(&first), vec![2, 4, 3]);
+
+        let second = query()
+            .cursor_paginate(3, first.next_cursor.as_deref())
+            .await
+            .unwrap();
+        assert_eq!(ids(&second), vec![1, 5]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_or_foreign_cursors() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        seed(&db, &[1, 2, 3]).await;
+
+        let error = QueryBuilder::<posts::Entity>::new()
+            .cursor_paginate(2, Some("not a cursor"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), 400);
+
+        let by_id = QueryBuilder::<posts::Entity>::new()
+            .cursor_paginate(1, None)
+            .await
+            .unwrap();
+        let error = QueryBuilder::<posts::Entity>::new()
+            .order_by_asc(posts::Column::Score)
+            .cursor_paginate(1, by_id.next_cursor.as_deref())
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), 400);
+    }
+
+    #[test]
+    fn serializes_as_a_page_envelope() {
+        let page = CursorPage {
+            data: vec![1, 2],
+            next_cursor: Some("abc".to_string()),
+            prev_cursor: None,
+        };
+        assert_eq!(
+            serde_json::to_value(page.map(|n| n * 10)).unwrap(),
+            serde_json::json!({ "data": [10, 20], "next_cursor": "abc", "prev_cursor": null })
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Test fixtures loaded from YAML or JSON files
+//!
+//! A fixture file maps table names to the rows to insert, in order:
+//!
+//! ```yaml
+//! users:
+//!   - id: 1
+//!     name: Ada
+//!     email: ada@example.com
+//! posts:
+//!   - id: 1
+//!     user_id: 1
+//!     title: Hello
+//! ```
+//!
+//! JSON files use the same shape. Tables are inserted in the order they
+//! appear, so parents can come before the rows that reference them.
+
+use super::connection::DbConnection;
+use super::DB;
+use crate::error::FrameworkError;
+use sea_orm::sea_query::{Alias, Keyword, Query, SimpleExpr};
+use sea_orm::ConnectionTrait;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Rows inserted from a fixture file
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{fixtures, kit_test};
+/// use kit::testing::TestDatabase;
+///
+/// #[kit_test]
+/// async fn shows_a_user(db: TestDatabase) {
+///     // Loads tests/fixtures/users.yaml into the test database
+///     let fixtures = fixtures!("users.yaml");
+///
+///     let ada: user::Model = fixtures.get("users", 0);
+///     assert_eq!(ada.name, "Ada");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fixtures {
+    tables: Vec<(String, Vec<serde_json::Value>)>,
+}
+
+impl Fixtures {
+    /// Insert a fixture file into the current database (`DB::connection()`)
+    ///
+    /// In tests this is the `TestDatabase`.
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self, FrameworkError> {
+        Self::load_into(path, &DB::connection()?).await
+    }
+
+    /// Insert a fixture file into `db`
+    pub async fn load_into(
+        path: impl AsRef<Path>,
+        db: &DbConnection,
+    ) -> Result<Self, FrameworkError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            FrameworkError::internal(format!(
+                "Failed to read fixtures {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let fixtures = Self::parse(&content).map_err(|e| {
+            FrameworkError::internal(format!("Invalid fixtures {}: {}", path.display(), e))
+        })?;
+
+        fixtures.insert(db).await?;
+        Ok(fixtures)
+    }
+
+    /// Parse fixtures from YAML (JSON is valid YAML)
+    fn parse(content: &str) -> Result<Self, String> {
+        let document: serde_yaml::Mapping =
+            serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+
+        let mut tables = Vec::new();
+        for (table, rows) in document {
+            let table = table
+                .as_str()
+                .ok_or("table names must be strings")?
+                .to_string();
+            let rows: Vec<serde_json::Value> =
+                serde_yaml::from_value(rows).map_err(|e| format!("table {}: {}", table, e))?;
+            if let Some(row) = rows.iter().find(|row| !row.is_object()) {
+                return Err(format!(
+                    "table {}: rows must be maps of column to value, got {}",
+                    table, row
+                ));
+            }
+            tables.push((table, rows));
+        }
+        Ok(Self { tables })
+    }
+
+    async fn insert(&self, db: &DbConnection) -> Result<(), FrameworkError> {
+        let conn = db.inner();
+        let backend = conn.get_database_backend();
+
+        for (table, rows) in &self.tables {
+            for row in rows {
+                let Some(columns) = row.as_object() else {
+                    continue;
+                };
+                let mut insert = Query::insert();
+                insert
+                    .into_table(Alias::new(table))
+                    .columns(columns.keys().map(Alias::new))
+                    .values_panic(columns.values().map(to_expr));
+                conn.execute(backend.build(&insert)).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The rows of a table, as they appear in the file
+    pub fn rows(&self, table: &str) -> &[serde_json::Value] {
+        self.tables
+            .iter()
+            .find(|(name, _)| name == table)
+            .map_or(&[], |(_, rows)| rows.as_slice())
+    }
+
+    /// A row of a table, deserialized into `T` (e.g. a SeaORM `Model`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row doesn't exist or doesn't deserialize into `T`.
+    pub fn get<T: DeserializeOwned>(&self, table: &str, index: usize) -> T {
+        let row = self.rows(table).get(index).unwrap_or_else(|| {
+            panic!("Fixture table '{}' has no row {}", table, index)
+        });
+        serde_json::from_value(row.clone()).unwrap_or_else(|e| {
+            panic!("Fixture row {} of '{}' doesn't match the type: {}", index, table, e)
+        })
+    }
+
+    /// All rows of a table, deserialized into `T`
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row doesn't deserialize into `T`.
+    pub fn all<T: DeserializeOwned>(&self, table: &str) -> Vec<T> {
+        (0..self.rows(table).len())
+            .map(|index| self.get(table, index))
+            .collect()
+    }
+}
+
+fn to_expr(value: &serde_json::Value) -> SimpleExpr {
+    match value {
+        serde_json::Value::Null => SimpleExpr::Keyword(Keyword::Null),
+        serde_json::Value::Bool(b) => (*b).into(),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        serde_json::Value::String(s) => s.as_str().into(),
+        // Nested values are stored as JSON text
+        other => other.to_string().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_table_order() {
+        let fixtures = Fixtures::parse(
+            "users:\n  - id: 1\n    name: Ada\nposts:\n  - id: 1\n    user_id: 1\n",
+        )
+        .unwrap();
+
+        let tables: Vec<&str> = fixtures.tables.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(tables, ["users", "posts"]);
+        assert_eq!(fixtures.rows("users")[0]["name"], "Ada");
+        assert!(fixtures.rows("comments").is_empty());
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let fixtures = Fixtures::parse(r#"{"users": [{"id": 1, "name": "Ada"}]}"#).unwrap();
+
+        #[derive(serde::Deserialize)]
+        struct User {
+            id: i64,
+            name: String,
+        }
+        let user: User = fixtures.get("users", 0);
+        assert_eq!((user.id, user.name.as_str()), (1, "Ada"));
+    }
+
+    #[test]
+    fn test_parse_rejects_rows_that_are_not_maps() {
+        let err = Fixtures::parse("users:\n  - Ada\n").unwrap_err();
+        assert!(err.contains("rows must be maps"), "{}", err);
+    }
+}
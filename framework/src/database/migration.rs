@@ -0,0 +1,177 @@
+//! Migration helpers for schema changes on live databases
+//!
+//! `kit migrate --check-safety` flags operations that lock or fail on a
+//! busy production table; the helpers here are the safe replacements.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::database::migration::prelude::*;
+//!
+//! #[async_trait::async_trait]
+//! impl MigrationTrait for Migration {
+//!     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+//!         manager
+//!             .create_index_concurrently(
+//!                 Index::create()
+//!                     .name("idx_posts_slug")
+//!                     .table(Posts::Table)
+//!                     .col(Posts::Slug)
+//!                     .to_owned(),
+//!             )
+//!             .await
+//!     }
+//! }
+//! ```
+
+use async_trait::async_trait;
+use sea_orm::{ConnectionTrait, DbBackend, DbErr};
+use sea_orm_migration::prelude::{
+    IndexCreateStatement, IndexDropStatement, PostgresQueryBuilder, SchemaManager,
+};
+use sea_orm_migration::SchemaManagerConnection;
+
+/// Everything a migration needs: SeaORM's prelude plus Kit's helpers
+pub mod prelude {
+    pub use super::SafeMigration;
+    pub use sea_orm_migration::prelude::*;
+}
+
+/// Zero-downtime variants of `SchemaManager` operations
+///
+/// On Postgres these build indexes with `CONCURRENTLY`, which doesn't block
+/// writes to the table while the index is built. Other databases use the
+/// plain statements.
+///
+/// Postgres can't run `CONCURRENTLY` inside a transaction, and SeaORM runs
+/// all pending Postgres migrations in a single one. The helpers commit it,
+/// run the statement and open a new transaction, so everything before them
+/// is already applied if they fail. Keep them in a migration of their own.
+#[async_trait]
+pub trait SafeMigration {
+    /// Create an index without locking the table against writes
+    async fn create_index_concurrently(&self, index: IndexCreateStatement) -> Result<(), DbErr>;
+
+    /// Drop an index without locking the table against writes
+    async fn drop_index_concurrently(&self, index: IndexDropStatement) -> Result<(), DbErr>;
+}
+
+#[async_trait]
+impl SafeMigration for SchemaManager<'_> {
+    async fn create_index_concurrently(&self, index: IndexCreateStatement) -> Result<(), DbErr> {
+        if self.get_database_backend() != DbBackend::Postgres {
+            return self.create_index(index).await;
+        }
+        run_outside_transaction(self, &concurrently(&index.to_string(PostgresQueryBuilder))).await
+    }
+
+    async fn drop_index_concurrently(&self, index: IndexDropStatement) -> Result<(), DbErr> {
+        if self.get_database_backend() != DbBackend::Postgres {
+            return self.drop_index(index).await;
+        }
+        run_outside_transaction(self, &concurrently(&index.to_string(PostgresQueryBuilder))).await
+    }
+}
+
+/// `CREATE [UNIQUE] INDEX ...` / `DROP INDEX ...` with `CONCURRENTLY` added
+fn concurrently(sql: &str) -> String {
+    sql.replacen("INDEX", "INDEX CONCURRENTLY", 1)
+}
+
+async fn run_outside_transaction(manager: &SchemaManager<'_>, sql: &str) -> Result<(), DbErr> {
+    let conn = manager.get_connection();
+    let in_transaction = matches!(conn, SchemaManagerConnection::Transaction(_));
+    if in_transaction {
+        conn.execute_unprepared("COMMIT").await?;
+    }
+    let result = conn.execute_unprepared(sql).await;
+    if in_transaction {
+        conn.execute_unprepared("BEGIN").await?;
+    }
+    result.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prelude::*;
+    use super::*;
+    use crate::testing::TestDatabase;
+    use sea_orm::{FromQueryResult, Statement};
+
+    struct Migrator;
+
+    #[async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(IndexPosts)]
+        }
+    }
+
+    struct IndexPosts;
+
+    impl MigrationName for IndexPosts {
+        fn name(&self) -> &str {
+            "index_posts"
+        }
+    }
+
+    #[async_trait]
+    impl MigrationTrait for IndexPosts {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared("CREATE TABLE posts (id INTEGER PRIMARY KEY, slug TEXT)")
+                .await?;
+            manager
+                .create_index_concurrently(
+                    Index::create()
+                        .name("idx_posts_slug")
+                        .table(Alias::new("posts"))
+                        .col(Alias::new("slug"))
+                        .unique()
+                        .to_owned(),
+                )
+                .await
+        }
+    }
+
+    #[test]
+    fn postgres_index_statements_are_concurrent() {
+        let create = Index::create()
+            .name("idx_posts_slug")
+            .table(Alias::new("posts"))
+            .col(Alias::new("slug"))
+            .unique()
+            .if_not_exists()
+            .to_owned();
+        assert_eq!(
+            concurrently(&create.to_string(PostgresQueryBuilder)),
+            r#"CREATE UNIQUE INDEX CONCURRENTLY IF NOT EXISTS "idx_posts_slug" ON "posts" ("slug")"#
+        );
+
+        let drop = Index::drop().name("idx_posts_slug").to_owned();
+        assert_eq!(
+            concurrently(&drop.to_string(PostgresQueryBuilder)),
+            r#"DROP INDEX CONCURRENTLY "idx_posts_slug""#
+        );
+    }
+
+    #[tokio::test]
+    async fn other_databases_create_the_index_normally() {
+        #[derive(FromQueryResult)]
+        struct IndexRow {
+            name: String,
+        }
+
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        let indexes = IndexRow::find_by_statement(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT name FROM pragma_index_list('posts')",
+        ))
+        .all(db.conn())
+        .await
+        .unwrap();
+        assert_eq!(indexes.len(), 1);
+        assert_eq!(indexes[0].name, "idx_posts_slug");
+    }
+}
@@ -8,7 +8,7 @@ use sea_orm::{
     PaginatorTrait, PrimaryKeyTrait, TryIntoModel,
 };
 
-use crate::database::DB;
+use crate::database::{DbConnection, DB, DEFAULT_CONNECTION};
 use crate::error::FrameworkError;
 
 /// Trait providing Laravel-like read operations on SeaORM entities
@@ -43,11 +43,31 @@ use crate::error::FrameworkError;
 /// let users = Entity::all().await?;
 /// let user = Entity::find_by_pk(1).await?;
 /// ```
+///
+/// A model stored in another database overrides `connection_name`:
+///
+/// ```rust,ignore
+/// impl kit::database::Model for Entity {
+///     fn connection_name() -> &'static str {
+///         "analytics"
+///     }
+/// }
+/// ```
 #[async_trait]
 pub trait Model: EntityTrait + Sized
 where
     Self::Model: ModelTrait<Entity = Self> + Send + Sync,
 {
+    /// The named connection the model's queries run on
+    fn connection_name() -> &'static str {
+        DEFAULT_CONNECTION
+    }
+
+    /// The model's connection, see `DB::connection_named`
+    fn connection() -> Result<DbConnection, FrameworkError> {
+        DB::connection_named(Self::connection_name())
+    }
+
     /// Find all records
     ///
     /// # Example
@@ -55,7 +75,7 @@ where
     /// let users = user::Entity::all().await?;
     /// ```
     async fn all() -> Result<Vec<Self::Model>, FrameworkError> {
-        let db = DB::connection()?;
+        let db = Self::connection()?;
         Self::find()
             .all(db.inner())
             .await
@@ -72,7 +92,7 @@ where
     where
         K: Into<<Self::PrimaryKey as PrimaryKeyTrait>::ValueType> + Send,
     {
-        let db = DB::connection()?;
+        let db = Self::connection()?;
         Self::find_by_id(id)
             .one(db.inner())
             .await
@@ -105,7 +125,7 @@ where
     /// let count = user::Entity::count_all().await?;
     /// ```
     async fn count_all() -> Result<u64, FrameworkError> {
-        let db = DB::connection()?;
+        let db = Self::connection()?;
         Self::find()
             .count(db.inner())
             .await
@@ -131,7 +151,7 @@ where
     /// let first_user = user::Entity::first().await?;
     /// ```
     async fn first() -> Result<Option<Self::Model>, FrameworkError> {
-        let db = DB::connection()?;
+        let db = Self::connection()?;
         Self::find()
             .one(db.inner())
             .await
@@ -182,7 +202,7 @@ where
     /// let user = user::Entity::insert_one(new_user).await?;
     /// ```
     async fn insert_one(model: Self::ActiveModel) -> Result<Self::Model, FrameworkError> {
-        let db = DB::connection()?;
+        let db = Self::connection()?;
         model
             .insert(db.inner())
             .await
@@ -198,7 +218,7 @@ where
     /// let updated = user::Entity::update_one(user).await?;
     /// ```
     async fn update_one(model: Self::ActiveModel) -> Result<Self::Model, FrameworkError> {
-        let db = DB::connection()?;
+        let db = Self::connection()?;
         model
             .update(db.inner())
             .await
@@ -215,7 +235,7 @@ where
     where
         K: Into<<Self::PrimaryKey as PrimaryKeyTrait>::ValueType> + Send,
     {
-        let db = DB::connection()?;
+        let db = Self::connection()?;
         let result = Self::delete_by_id(id)
             .exec(db.inner())
             .await
@@ -237,7 +257,7 @@ where
     where
         Self::ActiveModel: TryIntoModel<Self::Model>,
     {
-        let db = DB::connection()?;
+        let db = Self::connection()?;
         let saved = model
             .save(db.inner())
             .await
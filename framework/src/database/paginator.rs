@@ -0,0 +1,77 @@
+//! Offset pagination
+//!
+//! `QueryBuilder::paginate` fetches one numbered page plus the total row
+//! count, which is what tables with page links need. For infinite scroll
+//! over large tables prefer `cursor_paginate`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // `page` comes from the query string, 1 for the first page
+//! let users = User::query()
+//!     .order_by_asc(Column::Name)
+//!     .paginate(25, page)
+//!     .await?;
+//!
+//! inertia_response!("Users/Index", UsersProps {
+//!     users: users.map(UserProps::from).into_inertia(),
+//! })
+//! ```
+
+use serde::Serialize;
+
+use crate::inertia::{InertiaContext, InertiaPaginated};
+
+/// One page of offset-paginated results
+///
+/// Serializes to `{ data, total, per_page, current_page }`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Paginator<T> {
+    /// Rows on this page, in query order
+    pub data: Vec<T>,
+    /// Matching rows across all pages
+    pub total: u64,
+    pub per_page: u64,
+    /// 1-based page number
+    pub current_page: u64,
+}
+
+impl<T> Paginator<T> {
+    /// The number of the last page, at least 1
+    pub fn last_page(&self) -> u64 {
+        self.total.div_ceil(self.per_page.max(1)).max(1)
+    }
+
+    /// Whether there is a page after this one
+    pub fn has_more(&self) -> bool {
+        self.current_page < self.last_page()
+    }
+
+    /// Convert every row, keeping the page numbers
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let page = page.map(UserProps::from);
+    /// ```
+    pub fn map<U, F>(self, f: F) -> Paginator<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Paginator {
+            data: self.data.into_iter().map(f).collect(),
+            total: self.total,
+            per_page: self.per_page,
+            current_page: self.current_page,
+        }
+    }
+
+    /// Props for a paginated Inertia table, with links for the current URL
+    ///
+    /// Links keep the request's query string (filters, sorting) and only
+    /// change `page`.
+    pub fn into_inertia(self) -> InertiaPaginated<T> {
+        let context = InertiaContext::get().unwrap_or_default();
+        InertiaPaginated::new(self, &context.path, context.query.as_deref())
+    }
+}
@@ -24,14 +24,33 @@
 //!     .offset(20)
 //!     .all()
 //!     .await?;
+//!
+//! // Weak ETag for conditional GETs on index routes
+//! let etag = Todo::query().etag(Column::UpdatedAt).await?;
+//!
+//! // Numbered pages with a total count
+//! let page = Todo::query()
+//!     .order_by_desc(Column::CreatedAt)
+//!     .paginate(20, 2)
+//!     .await?;
+//!
+//! // Cursor pagination for infinite scroll
+//! let page = Todo::query()
+//!     .order_by_desc(Column::CreatedAt)
+//!     .cursor_paginate(20, cursor.as_deref())
+//!     .await?;
 //! ```
 
 use sea_orm::{
-    ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+    ColumnTrait, ConnectionTrait, DbBackend, EntityTrait, Order, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, QueryTrait, Select, Statement,
 };
 
-use crate::database::DB;
+use crate::database::cursor::{self, CursorPage, Direction};
+use crate::database::paginator::Paginator;
+use crate::database::{DB, DEFAULT_CONNECTION};
 use crate::error::FrameworkError;
+use crate::http::ETag;
 
 /// Fluent query builder wrapper
 ///
@@ -52,6 +71,8 @@ where
     E: EntityTrait,
 {
     select: Select<E>,
+    orders: Vec<(String, Order)>,
+    connection: &'static str,
 }
 
 impl<E> QueryBuilder<E>
@@ -63,9 +84,23 @@ where
     pub fn new() -> Self {
         Self {
             select: E::find(),
+            orders: Vec::new(),
+            connection: DEFAULT_CONNECTION,
         }
     }
 
+    /// Run the query on a named connection, see `DB::connection_named`
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let views = PageView::query().on_connection("analytics").all().await?;
+    /// ```
+    pub fn on_connection(mut self, name: &'static str) -> Self {
+        self.connection = name;
+        self
+    }
+
     /// Add a filter condition
     ///
     /// # Example
@@ -99,6 +134,7 @@ where
     where
         C: ColumnTrait,
     {
+        self.orders.push((col.as_str().to_string(), Order::Asc));
         self.select = self.select.order_by(col, Order::Asc);
         self
     }
@@ -117,6 +153,7 @@ where
     where
         C: ColumnTrait,
     {
+        self.orders.push((col.as_str().to_string(), Order::Desc));
         self.select = self.select.order_by(col, Order::Desc);
         self
     }
@@ -136,6 +173,7 @@ where
     where
         C: ColumnTrait,
     {
+        self.orders.push((col.as_str().to_string(), order.clone()));
         self.select = self.select.order_by(col, order);
         self
     }
@@ -173,7 +211,7 @@ where
     /// let todos = Todo::query().all().await?;
     /// ```
     pub async fn all(self) -> Result<Vec<E::Model>, FrameworkError> {
-        let db = DB::connection()?;
+        let db = DB::connection_named(self.connection)?;
         self.select
             .all(db.inner())
             .await
@@ -193,7 +231,7 @@ where
     ///     .await?;
     /// ```
     pub async fn first(self) -> Result<Option<E::Model>, FrameworkError> {
-        let db = DB::connection()?;
+        let db = DB::connection_named(self.connection)?;
         self.select
             .one(db.inner())
             .await
@@ -229,7 +267,7 @@ where
     ///     .await?;
     /// ```
     pub async fn count(self) -> Result<u64, FrameworkError> {
-        let db = DB::connection()?;
+        let db = DB::connection_named(self.connection)?;
         self.select
             .count(db.inner())
             .await
@@ -250,6 +288,196 @@ where
         Ok(self.count().await? > 0)
     }
 
+    /// A weak ETag for the rows this query returns
+    ///
+    /// Hashes the query's SQL with the row count and the latest value of
+    /// `updated_at`, fetched in one aggregate query, so the tag changes when
+    /// rows are added, removed or updated, or the filters change. Pair with
+    /// `ETag::respond` to skip loading unchanged collections.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let query = Todo::query().filter(Column::Done.eq(false));
+    /// let etag = query.etag(Column::UpdatedAt).await?;
+    /// etag.respond(&req, || async { json_response!({ "data": query.all().await? }) }).await
+    /// ```
+    pub async fn etag<C>(&self, updated_at: C) -> Result<ETag, FrameworkError>
+    where
+        C: ColumnTrait,
+    {
+        let db = DB::connection_named(self.connection)?;
+        let backend = db.inner().get_database_backend();
+        let inner = self.select.build(backend);
+        let signature = inner.to_string();
+
+        let (quote, text) = match backend {
+            DbBackend::MySql => ('`', "CHAR"),
+            _ => ('"', "TEXT"),
+        };
+        let sql = format!(
+            "SELECT COUNT(*) AS row_count, CAST(MAX({q}{column}{q}) AS {text}) AS latest FROM ({inner}) AS etag_source",
+            q = quote,
+            column = updated_at.as_str(),
+            text = text,
+            inner = inner.sql,
+        );
+        let statement = match inner.values {
+            Some(values) => Statement::from_sql_and_values(backend, sql, values),
+            None => Statement::from_string(backend, sql),
+        };
+
+        let row = db
+            .inner()
+            .query_one(statement)
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?
+            .ok_or_else(|| FrameworkError::database("ETag query returned no rows"))?;
+        let count: i64 = row
+            .try_get("", "row_count")
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+        let latest: Option<String> = row
+            .try_get("", "latest")
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+
+        Ok(ETag::from_parts([
+            signature,
+            count.to_string(),
+            latest.unwrap_or_default(),
+        ]))
+    }
+
+    /// Fetch page `page` (1-based) of `per_page` rows, with the total count
+    ///
+    /// Pages past the end come back empty with the real total, so the
+    /// page links still work.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let page = Todo::query()
+    ///     .order_by_desc(Column::CreatedAt)
+    ///     .paginate(20, 1)
+    ///     .await?;
+    ///
+    /// // Inertia table props with links and meta
+    /// let todos = page.into_inertia();
+    /// ```
+    pub async fn paginate(
+        self,
+        per_page: u64,
+        page: u64,
+    ) -> Result<Paginator<E::Model>, FrameworkError> {
+        let per_page = per_page.max(1);
+        let current_page = page.max(1);
+        let db = DB::connection_named(self.connection)?;
+
+        let total = self
+            .select
+            .clone()
+            .count(db.inner())
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+        let data = self
+            .select
+            .limit(per_page)
+            .offset((current_page - 1) * per_page)
+            .all(db.inner())
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+
+        Ok(Paginator {
+            data,
+            total,
+            per_page,
+            current_page,
+        })
+    }
+
+    /// Fetch one page of results using an opaque cursor
+    ///
+    /// Pages are ordered by the query's `order_by` columns with the primary
+    /// key as a tie-breaker (just the primary key when there is no order).
+    /// Pass `None` for the first page, then a previous page's `next_cursor`
+    /// or `prev_cursor`. Sort columns must not be nullable. Malformed
+    /// cursors, or cursors from a differently ordered query, return 400.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let page = Todo::query()
+    ///     .order_by_desc(Column::CreatedAt)
+    ///     .cursor_paginate(20, None)
+    ///     .await?;
+    ///
+    /// let next = Todo::query()
+    ///     .order_by_desc(Column::CreatedAt)
+    ///     .cursor_paginate(20, page.next_cursor.as_deref())
+    ///     .await?;
+    /// ```
+    pub async fn cursor_paginate(
+        self,
+        limit: u64,
+        cursor: Option<&str>,
+    ) -> Result<CursorPage<E::Model>, FrameworkError> {
+        let limit = limit.max(1);
+        let keys = cursor::sort_keys::<E>(&self.orders)?;
+        let position = cursor
+            .map(|token| cursor::decode(token, &keys))
+            .transpose()?;
+        let backward = matches!(position, Some((Direction::Prev, _)));
+
+        let mut select = self.select;
+        QueryTrait::query(&mut select).clear_order_by();
+        for (column, order) in &keys {
+            let order = if backward {
+                cursor::reverse(order)
+            } else {
+                order.clone()
+            };
+            select = select.order_by(*column, order);
+        }
+        if let Some((_, values)) = &position {
+            select = select.filter(cursor::keyset_condition(&keys, values, backward));
+        }
+
+        let db = DB::connection_named(self.connection)?;
+        let mut data = select
+            .limit(limit + 1)
+            .offset(None)
+            .all(db.inner())
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+
+        let has_more = data.len() as u64 > limit;
+        data.truncate(limit as usize);
+        if backward {
+            data.reverse();
+        }
+
+        // Going forward there are earlier rows whenever we started from a
+        // cursor; going backward there are later rows by the same logic.
+        let (more_after, more_before) = if backward {
+            (true, has_more)
+        } else {
+            (has_more, position.is_some())
+        };
+        let next_cursor = match data.last() {
+            Some(row) if more_after => Some(cursor::encode::<E>(row, &keys, Direction::Next)?),
+            _ => None,
+        };
+        let prev_cursor = match data.first() {
+            Some(row) if more_before => Some(cursor::encode::<E>(row, &keys, Direction::Prev)?),
+            _ => None,
+        };
+
+        Ok(CursorPage {
+            data,
+            next_cursor,
+            prev_cursor,
+        })
+    }
+
     /// Get access to the underlying SeaORM Select for advanced queries
     ///
     /// Use this when you need SeaORM features not exposed by QueryBuilder.
@@ -278,3 +506,145 @@ where
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDatabase;
+    use sea_orm::{ActiveModelTrait, DbErr, Set};
+    use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager};
+
+    mod notes {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "notes")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub pinned: bool,
+            pub updated_at: DateTime,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    use notes::Column;
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreateNotes)]
+        }
+    }
+
+    struct CreateNotes;
+
+    impl MigrationName for CreateNotes {
+        fn name(&self) -> &str {
+            "create_notes"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreateNotes {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "CREATE TABLE notes (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        pinned BOOLEAN NOT NULL,
+                        updated_at TIMESTAMP NOT NULL
+                    )",
+                )
+                .await
+                .map(|_| ())
+        }
+    }
+
+    fn at(seconds: i64) -> chrono::NaiveDateTime {
+        chrono::DateTime::from_timestamp(1_800_000_000 + seconds, 0)
+            .unwrap()
+            .naive_utc()
+    }
+
+    async fn insert(
+        db: &TestDatabase,
+        pinned: bool,
+        updated_at: chrono::NaiveDateTime,
+    ) -> notes::Model {
+        notes::ActiveModel {
+            pinned: Set(pinned),
+            updated_at: Set(updated_at),
+            ..Default::default()
+        }
+        .insert(db.conn())
+        .await
+        .unwrap()
+    }
+
+    async fn etag(pinned: Option<bool>) -> ETag {
+        let mut query = QueryBuilder::<notes::Entity>::new();
+        if let Some(pinned) = pinned {
+            query = query.filter(Column::Pinned.eq(pinned));
+        }
+        query.etag(Column::UpdatedAt).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn etag_changes_with_rows_and_filters() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        let empty = etag(None).await;
+
+        let note = insert(&db, false, at(0)).await;
+        let one = etag(None).await;
+        assert_ne!(one, empty);
+        assert_eq!(etag(None).await, one);
+        assert_ne!(etag(Some(true)).await, one);
+
+        let mut touched: notes::ActiveModel = note.into();
+        touched.updated_at = Set(at(60));
+        touched.update(db.conn()).await.unwrap();
+        let updated = etag(None).await;
+        assert_ne!(updated, one);
+
+        insert(&db, true, at(30)).await;
+        assert_ne!(etag(None).await, updated);
+    }
+
+    #[tokio::test]
+    async fn paginate_counts_and_offsets() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        for seconds in 0..5 {
+            insert(&db, seconds % 2 == 0, at(seconds)).await;
+        }
+        let page = |page| {
+            QueryBuilder::<notes::Entity>::new()
+                .order_by_asc(Column::Id)
+                .paginate(2, page)
+        };
+
+        let second = page(2).await.unwrap();
+        assert_eq!(second.data.iter().map(|n| n.id).collect::<Vec<_>>(), [3, 4]);
+        assert_eq!((second.total, second.last_page()), (5, 3));
+        assert!(second.has_more());
+
+        let past_end = page(9).await.unwrap();
+        assert!(past_end.data.is_empty());
+        assert_eq!(past_end.total, 5);
+
+        let pinned = QueryBuilder::<notes::Entity>::new()
+            .filter(Column::Pinned.eq(true))
+            .paginate(10, 0)
+            .await
+            .unwrap();
+        assert_eq!((pinned.total, pinned.current_page), (3, 1));
+        assert!(!pinned.has_more());
+    }
+}
@@ -0,0 +1,386 @@
+//! Filtering and sorting index endpoints from the query string
+//!
+//! `QueryFilter` maps allow-listed query string parameters onto a
+//! `QueryBuilder`:
+//!
+//! - `?filter[status]=active` adds `status = 'active'`
+//! - `?filter[status]=active,pending` adds `status IN ('active', 'pending')`
+//! - `?sort=-created_at,title` orders by `created_at DESC, title ASC`
+//!
+//! Only the names passed to `allow_filter`/`allow_sort` are accepted; any
+//! other `filter[...]` or sort field is a 422 validation error, as is a
+//! value that doesn't fit the column's type. Other parameters (`page`,
+//! `cursor`, ...) are ignored.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::database::QueryFilter;
+//!
+//! fn todo_filters() -> QueryFilter<todos::Entity> {
+//!     QueryFilter::new()
+//!         .allow_filter("status", Column::Status)
+//!         .allow_filter("user_id", Column::UserId)
+//!         .allow_sort("created_at", Column::CreatedAt)
+//!         .allow_sort("title", Column::Title)
+//!         .default_sort(Column::CreatedAt, Order::Desc)
+//! }
+//!
+//! #[handler]
+//! pub async fn index(req: Request) -> Response {
+//!     let todos = todo_filters().apply(Todo::query(), &req)?.all().await?;
+//!     json_response!({ "data": todos })
+//! }
+//! ```
+
+use sea_orm::prelude::Decimal;
+use sea_orm::{ColumnTrait, ColumnType, EntityTrait, Order, Value};
+
+use crate::database::QueryBuilder;
+use crate::error::{FrameworkError, ValidationErrors};
+use crate::http::Request;
+
+/// Allow-list of query string filters and sorts for one entity
+pub struct QueryFilter<E>
+where
+    E: EntityTrait,
+{
+    filters: Vec<(String, E::Column)>,
+    sorts: Vec<(String, E::Column)>,
+    default_sort: Vec<(E::Column, Order)>,
+}
+
+impl<E> QueryFilter<E>
+where
+    E: EntityTrait,
+    E::Model: Send + Sync,
+{
+    /// An empty allow-list: no filters or sorts accepted
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+            sorts: Vec::new(),
+            default_sort: Vec::new(),
+        }
+    }
+
+    /// Accept `filter[name]=value` for `column`
+    pub fn allow_filter(mut self, name: impl Into<String>, column: E::Column) -> Self {
+        self.filters.push((name.into(), column));
+        self
+    }
+
+    /// Accept `name` (ascending) and `-name` (descending) in `sort`
+    pub fn allow_sort(mut self, name: impl Into<String>, column: E::Column) -> Self {
+        self.sorts.push((name.into(), column));
+        self
+    }
+
+    /// Order to use when the request has no `sort` parameter
+    ///
+    /// Call repeatedly to sort by several columns.
+    pub fn default_sort(mut self, column: E::Column, order: Order) -> Self {
+        self.default_sort.push((column, order));
+        self
+    }
+
+    /// Apply the request's filters and sorts to `query`
+    pub fn apply(
+        &self,
+        query: QueryBuilder<E>,
+        req: &Request,
+    ) -> Result<QueryBuilder<E>, FrameworkError> {
+        self.apply_query_string(query, req.query_string().unwrap_or(""))
+    }
+
+    /// Apply filters and sorts from a raw query string
+    pub fn apply_query_string(
+        &self,
+        mut query: QueryBuilder<E>,
+        query_string: &str,
+    ) -> Result<QueryBuilder<E>, FrameworkError> {
+        let params: Vec<(String, String)> = serde_urlencoded::from_str(query_string)
+            .map_err(|_| FrameworkError::domain("Invalid query string", 400))?;
+
+        let mut errors = ValidationErrors::new();
+        let mut sorts = None;
+
+        for (key, value) in &params {
+            if key == "sort" {
+                sorts = Some(self.parse_sort(value, &mut errors));
+                continue;
+            }
+            let Some(name) = key
+                .strip_prefix("filter[")
+                .and_then(|rest| rest.strip_suffix(']'))
+            else {
+                continue;
+            };
+
+            let Some((_, column)) = self.filters.iter().find(|(allowed, _)| allowed == name) else {
+                errors.add(key, format!("Filtering by '{}' is not allowed", name));
+                continue;
+            };
+            let values = value
+                .split(',')
+                .map(|raw| parse_value(*column, raw.trim()))
+                .collect::<Result<Vec<_>, _>>();
+            match values {
+                Ok(mut values) if values.len() == 1 => {
+                    query = query.filter(column.eq(values.remove(0)));
+                }
+                Ok(values) => query = query.filter(column.is_in(values)),
+                Err(expected) => {
+                    errors.add(key, format!("The {} filter must be {}", name, expected))
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(FrameworkError::validation_errors(errors));
+        }
+
+        for (column, order) in sorts.unwrap_or_else(|| self.default_sort.clone()) {
+            query = query.order_by(column, order);
+        }
+        Ok(query)
+    }
+
+    fn parse_sort(&self, value: &str, errors: &mut ValidationErrors) -> Vec<(E::Column, Order)> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .filter_map(|field| {
+                let (name, order) = match field.strip_prefix('-') {
+                    Some(name) => (name, Order::Desc),
+                    None => (field, Order::Asc),
+                };
+                match self.sorts.iter().find(|(allowed, _)| allowed == name) {
+                    Some((_, column)) => Some((*column, order)),
+                    None => {
+                        errors.add("sort", format!("Sorting by '{}' is not allowed", name));
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl<E> Default for QueryFilter<E>
+where
+    E: EntityTrait,
+    E::Model: Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert a query string value to the column's type
+///
+/// Returns a description of the expected value on failure.
+fn parse_value<C: ColumnTrait>(column: C, raw: &str) -> Result<Value, &'static str> {
+    match column.def().get_column_type() {
+        ColumnType::TinyInteger
+        | ColumnType::SmallInteger
+        | ColumnType::Integer
+        | ColumnType::BigInteger
+        | ColumnType::TinyUnsigned
+        | ColumnType::SmallUnsigned
+        | ColumnType::Unsigned
+        | ColumnType::BigUnsigned => raw
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| "an integer"),
+        ColumnType::Float | ColumnType::Double => {
+            raw.parse::<f64>().map(Value::from).map_err(|_| "a number")
+        }
+        ColumnType::Decimal(_) | ColumnType::Money(_) => raw
+            .parse::<Decimal>()
+            .map(Value::from)
+            .map_err(|_| "a number"),
+        ColumnType::Boolean => match raw {
+            "true" | "1" => Ok(true.into()),
+            "false" | "0" => Ok(false.into()),
+            _ => Err("true or false"),
+        },
+        _ => Ok(raw.to_string().into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDatabase;
+    use sea_orm::{ActiveModelTrait, ConnectionTrait, DbErr, Set};
+    use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager};
+
+    mod tasks {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "tasks")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub title: String,
+            pub status: String,
+            pub priority: i32,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    use tasks::Column;
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreateTasks)]
+        }
+    }
+
+    struct CreateTasks;
+
+    impl MigrationName for CreateTasks {
+        fn name(&self) -> &str {
+            "create_tasks"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreateTasks {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "CREATE TABLE tasks (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        title TEXT NOT NULL,
+                        status TEXT NOT NULL,
+                        priority INTEGER NOT NULL
+                    )",
+                )
+                .await
+                .map(|_| ())
+        }
+    }
+
+    async fn seed(db: &TestDatabase) {
+        let rows = [
+            ("Write docs", "active", 2),
+            ("Fix bug", "pending", 3),
+            ("Ship release", "active", 1),
+            ("Plan sprint", "done", 2),
+        ];
+        for (title, status, priority) in rows {
+            tasks::ActiveModel {
+                title: Set(title.to_string()),
+                status: Set(status.to_string()),
+                priority: Set(priority),
+                ..Default::default()
+            }
+            .insert(db.conn())
+            .await
+            .unwrap();
+        }
+    }
+
+    fn filters() -> QueryFilter<tasks::Entity> {
+        QueryFilter::new()
+            .allow_filter("status", Column::Status)
+            .allow_filter("priority", Column::Priority)
+            .allow_sort("priority", Column::Priority)
+            .allow_sort("title", Column::Title)
+            .default_sort(Column::Id, Order::Desc)
+    }
+
+    async fn titles(query_string: &str) -> Result<Vec<String>, FrameworkError> {
+        let tasks = filters()
+            .apply_query_string(QueryBuilder::new(), query_string)?
+            .all()
+            .await?;
+        Ok(tasks.into_iter().map(|task| task.title).collect())
+    }
+
+    #[tokio::test]
+    async fn filters_and_sorts_by_allowed_fields() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        seed(&db).await;
+
+        assert_eq!(
+            titles("filter[status]=active&sort=priority").await.unwrap(),
+            ["Ship release", "Write docs"]
+        );
+        assert_eq!(
+            titles("filter%5Bstatus%5D=active,done&sort=-priority,title&page=2")
+                .await
+                .unwrap(),
+            ["Plan sprint", "Write docs", "Ship release"]
+        );
+        assert_eq!(
+            titles("filter[priority]=2").await.unwrap(),
+            ["Plan sprint", "Write docs"]
+        );
+    }
+
+    #[tokio::test]
+    async fn uses_default_sort_without_a_sort_param() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        seed(&db).await;
+
+        assert_eq!(
+            titles("").await.unwrap(),
+            ["Plan sprint", "Ship release", "Fix bug", "Write docs"]
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_the_request_query_string() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        seed(&db).await;
+
+        let req = Request::fake()
+            .path("/tasks?filter%5Bstatus%5D=pending")
+            .build();
+        let tasks = filters()
+            .apply(QueryBuilder::new(), &req)
+            .unwrap()
+            .all()
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "Fix bug");
+    }
+
+    #[test]
+    fn unknown_fields_and_bad_values_are_validation_errors() {
+        let query = QueryBuilder::<tasks::Entity>::new();
+        let Err(FrameworkError::Validation(errors)) = filters().apply_query_string(
+            query,
+            "filter[secret]=1&filter[priority]=high&sort=-created_at,title",
+        ) else {
+            panic!("expected validation errors");
+        };
+
+        assert_eq!(
+            errors.errors["filter[secret]"],
+            ["Filtering by 'secret' is not allowed"]
+        );
+        assert_eq!(
+            errors.errors["filter[priority]"],
+            ["The priority filter must be an integer"]
+        );
+        assert_eq!(
+            errors.errors["sort"],
+            ["Sorting by 'created_at' is not allowed"]
+        );
+    }
+}
@@ -20,13 +20,21 @@
 //!
 //! The parameter name (`user`) is used as the route parameter key. So for a route
 //! defined as `/users/{user}`, the `user` parameter will be automatically resolved.
+//! Routes using `{id}` work too, and type aliases like `pub type User = Model`
+//! can be used in place of `user::Model`.
 //!
 //! If the model is not found, a 404 Not Found response is returned.
 //! If the parameter cannot be parsed, a 400 Bad Request response is returned.
+//!
+//! To look a model up by another column, name it in the route:
+//! `/posts/{post:slug}` resolves `post` by its `slug` column.
 
 use crate::error::FrameworkError;
 use async_trait::async_trait;
-use sea_orm::{EntityTrait, ModelTrait as SeaModelTrait, PrimaryKeyTrait};
+use sea_orm::{
+    ColumnTrait, EntityTrait, FromQueryResult, ModelTrait as SeaModelTrait, PrimaryKeyTrait,
+    QueryFilter,
+};
 
 /// Trait for models that can be automatically resolved from route parameters
 ///
@@ -109,6 +117,11 @@ pub trait AutoRouteBinding: Sized + Send {
     /// - `Err(FrameworkError::ModelNotFound)` - Model not found (returns 404)
     /// - `Err(FrameworkError::ParamParse)` - Parameter could not be parsed (returns 400)
     async fn from_route_param(value: &str) -> Result<Self, FrameworkError>;
+
+    /// Fetch the model whose `column` equals the route parameter value
+    ///
+    /// Used for routes like `/posts/{post:slug}`.
+    async fn from_route_key(column: &str, value: &str) -> Result<Self, FrameworkError>;
 }
 
 /// Blanket implementation of AutoRouteBinding for all SeaORM models
@@ -119,7 +132,7 @@ pub trait AutoRouteBinding: Sized + Send {
 #[async_trait]
 impl<M, E> AutoRouteBinding for M
 where
-    M: SeaModelTrait<Entity = E> + Send + Sync,
+    M: SeaModelTrait<Entity = E> + FromQueryResult + Send + Sync,
     E: EntityTrait<Model = M> + crate::database::Model + Sync,
     E::PrimaryKey: PrimaryKeyTrait,
     <E::PrimaryKey as PrimaryKeyTrait>::ValueType: std::str::FromStr + Send,
@@ -134,13 +147,33 @@ where
 
         <E as crate::database::Model>::find_by_pk(id)
             .await?
-            .ok_or_else(|| {
-                // Extract a cleaner model name from the full type name
-                let full_name = std::any::type_name::<M>();
-                let model_name = full_name.rsplit("::").nth(1).unwrap_or(full_name);
-                FrameworkError::model_not_found(model_name)
-            })
+            .ok_or_else(model_not_found::<M>)
     }
+
+    async fn from_route_key(column: &str, value: &str) -> Result<Self, FrameworkError> {
+        let column: E::Column = column.parse().map_err(|_| {
+            FrameworkError::internal(format!(
+                "Route binding column '{}' doesn't exist on {}",
+                column,
+                std::any::type_name::<E>()
+            ))
+        })?;
+
+        let db = <E as crate::database::Model>::connection()?;
+        E::find()
+            .filter(column.eq(value))
+            .one(db.inner())
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?
+            .ok_or_else(model_not_found::<M>)
+    }
+}
+
+fn model_not_found<M>() -> FrameworkError {
+    // Extract a cleaner model name from the full type name
+    let full_name = std::any::type_name::<M>();
+    let model_name = full_name.rsplit("::").nth(1).unwrap_or(full_name);
+    FrameworkError::model_not_found(model_name)
 }
 
 /// Convenience macro to implement RouteBinding for a SeaORM model
@@ -0,0 +1,42 @@
+//! Database seeders
+//!
+//! A seeder inserts a known set of rows, for example the users every test in
+//! a suite expects. Tests run them with `#[kit_test(seed = ...)]`.
+
+use super::connection::DbConnection;
+use crate::error::FrameworkError;
+use async_trait::async_trait;
+
+/// Inserts seed data into a database
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{async_trait, DbConnection, FrameworkError, Seeder};
+/// use sea_orm::{ActiveModelTrait, Set};
+///
+/// pub struct BasicUsers;
+///
+/// #[async_trait]
+/// impl Seeder for BasicUsers {
+///     async fn run(&self, db: &DbConnection) -> Result<(), FrameworkError> {
+///         user::ActiveModel {
+///             name: Set("Ada".to_string()),
+///             ..Default::default()
+///         }
+///         .insert(db.inner())
+///         .await?;
+///         Ok(())
+///     }
+/// }
+///
+/// #[kit_test(seed = seeders::BasicUsers)]
+/// async fn lists_users(db: TestDatabase) {
+///     // BasicUsers ran before the test body
+/// }
+/// ```
+#[async_trait]
+pub trait Seeder: Send + Sync {
+    /// Insert the seed data
+    async fn run(&self, db: &DbConnection) -> Result<(), FrameworkError>;
+}
@@ -3,6 +3,10 @@
 //! Provides `TestDatabase` for setting up isolated test environments with
 //! in-memory SQLite databases and automatic migration support.
 //!
+//! `TestDatabase::fresh` migrates a new database for every test.
+//! `TestDatabase::transactional` migrates once per process and rolls each
+//! test back instead, which is much faster for apps with many migrations.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -17,8 +21,14 @@
 //! }
 //! ```
 
-use sea_orm::DatabaseConnection;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 use sea_orm_migration::MigratorTrait;
+use std::any::TypeId;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::{OnceCell, OwnedMutexGuard};
 
 use super::config::DatabaseConfig;
 use super::connection::DbConnection;
@@ -59,9 +69,23 @@ use crate::error::FrameworkError;
 /// ```
 pub struct TestDatabase {
     conn: DbConnection,
+    transactional: bool,
     _guard: TestContainerGuard,
+    /// Held by transactional tests, released after the connection is dropped
+    _lock: Option<OwnedMutexGuard<()>>,
 }
 
+/// Migrated databases shared by transactional tests, one per migrator
+///
+/// Each value keeps a connection open, so the in-memory database lives for
+/// the whole test process.
+type SharedDatabases = Mutex<HashMap<TypeId, Arc<OnceCell<(String, DbConnection)>>>>;
+
+static SHARED_DATABASES: OnceLock<SharedDatabases> = OnceLock::new();
+
+/// Serializes transactional tests, which share one database
+static TRANSACTION_LOCK: OnceLock<Arc<tokio::sync::Mutex<()>>> = OnceLock::new();
+
 impl TestDatabase {
     /// Create a fresh test database with migrations applied
     ///
@@ -115,7 +139,90 @@ impl TestDatabase {
         // will now get this test database
         TestContainer::singleton(conn.clone());
 
-        Ok(Self { conn, _guard: guard })
+        Ok(Self {
+            conn,
+            transactional: false,
+            _guard: guard,
+            _lock: None,
+        })
+    }
+
+    /// Open a transaction on a database shared by all tests in the process
+    ///
+    /// Migrations run once, the first time a test asks for a database with
+    /// migrator `M`. Every test then works inside a transaction that is
+    /// rolled back when it finishes, so tests still see a clean database.
+    ///
+    /// Transactional tests run one at a time, because they share the
+    /// database. Code that opens its own transactions (`begin()`) fails
+    /// inside the test transaction; use `fresh` for those tests.
+    ///
+    /// Prefer `#[kit_test(transactional)]`, which also rolls back. When
+    /// calling this directly, call `rollback()` at the end of the test.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// #[tokio::test]
+    /// async fn test_example() {
+    ///     let db = TestDatabase::transactional::<Migrator>().await.unwrap();
+    ///     // ...
+    ///     db.rollback().await.unwrap();
+    /// }
+    /// ```
+    pub async fn transactional<M: MigratorTrait + 'static>() -> Result<Self, FrameworkError> {
+        let lock = TRANSACTION_LOCK
+            .get_or_init(|| Arc::new(tokio::sync::Mutex::new(())))
+            .clone()
+            .lock_owned()
+            .await;
+
+        let cell = SHARED_DATABASES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(TypeId::of::<M>())
+            .or_default()
+            .clone();
+        let (url, _) = cell.get_or_try_init(migrate_shared::<M>).await?;
+
+        let guard = TestContainer::fake();
+        let config = DatabaseConfig::builder()
+            .url(url.as_str())
+            .max_connections(1)
+            .min_connections(1)
+            .logging(false)
+            .build();
+        let conn = DbConnection::connect(&config).await?;
+        conn.inner()
+            .execute_unprepared("BEGIN")
+            .await
+            .map_err(|e| FrameworkError::database(e.to_string()))?;
+
+        TestContainer::singleton(conn.clone());
+
+        Ok(Self {
+            conn,
+            transactional: true,
+            _guard: guard,
+            _lock: Some(lock),
+        })
+    }
+
+    /// Undo everything the test wrote (transactional databases only)
+    ///
+    /// Does nothing for databases created with `fresh`. If a test panics
+    /// before rolling back, closing the connection rolls back instead.
+    pub async fn rollback(&self) -> Result<(), FrameworkError> {
+        if !self.transactional {
+            return Ok(());
+        }
+        self.conn
+            .inner()
+            .execute_unprepared("ROLLBACK")
+            .await
+            .map(|_| ())
+            .map_err(|e| FrameworkError::database(e.to_string()))
     }
 
     /// Get a reference to the underlying database connection
@@ -140,6 +247,30 @@ impl TestDatabase {
     }
 }
 
+/// Create the in-memory database shared by transactional tests and migrate it
+async fn migrate_shared<M: MigratorTrait + 'static>() -> Result<(String, DbConnection), FrameworkError> {
+    let mut hasher = DefaultHasher::new();
+    TypeId::of::<M>().hash(&mut hasher);
+    let url = format!(
+        "sqlite:file:kit-test-{}-{:x}?mode=memory&cache=shared",
+        std::process::id(),
+        hasher.finish()
+    );
+    let config = DatabaseConfig::builder()
+        .url(url.as_str())
+        .max_connections(1)
+        .min_connections(1)
+        .logging(false)
+        .build();
+    let conn = DbConnection::connect(&config).await?;
+
+    M::up(conn.inner(), None)
+        .await
+        .map_err(|e| FrameworkError::database(format!("Migration failed: {}", e)))?;
+
+    Ok((url, conn))
+}
+
 /// Create a test database with default migrator
 ///
 /// This macro creates a `TestDatabase` using `crate::migrations::Migrator` as the
@@ -179,3 +310,144 @@ macro_rules! test_database {
             .expect("Failed to set up test database")
     };
 }
+
+/// Load a fixture file into the test database
+///
+/// The path is relative to the crate's `tests/fixtures/` directory. Returns
+/// the inserted rows as `Fixtures`, with typed accessors.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[kit_test]
+/// async fn test_user_profile(db: TestDatabase) {
+///     let fixtures = fixtures!("users.yaml");
+///     let ada: user::Model = fixtures.get("users", 0);
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! fixtures {
+    ($path:literal) => {
+        $crate::testing::Fixtures::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/",
+            $path
+        ))
+        .await
+        .expect("Failed to load fixtures")
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::Statement;
+    use sea_orm_migration::{MigrationName, MigrationTrait, SchemaManager};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static MIGRATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreateNotes)]
+        }
+    }
+
+    struct CreateNotes;
+
+    impl MigrationName for CreateNotes {
+        fn name(&self) -> &str {
+            "create_notes"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreateNotes {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), sea_orm::DbErr> {
+            MIGRATIONS.fetch_add(1, Ordering::SeqCst);
+            manager
+                .get_connection()
+                .execute_unprepared("CREATE TABLE notes (body TEXT NOT NULL)")
+                .await
+                .map(|_| ())
+        }
+    }
+
+    async fn insert_and_count(db: &TestDatabase) -> i64 {
+        db.conn()
+            .execute_unprepared("INSERT INTO notes (body) VALUES ('hello')")
+            .await
+            .unwrap();
+        let row = db
+            .conn()
+            .query_one(Statement::from_string(
+                db.conn().get_database_backend(),
+                "SELECT COUNT(*) AS count FROM notes",
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        row.try_get("", "count").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_transactional_tests_start_empty() {
+        for i in 0..4 {
+            let db = TestDatabase::transactional::<Migrator>().await.unwrap();
+            assert_eq!(insert_and_count(&db).await, 1);
+            // Dropping without rolling back (as after a panic) also undoes the insert
+            if i % 2 == 0 {
+                db.rollback().await.unwrap();
+            }
+        }
+    }
+
+    #[crate::kit_test(transactional, migrator = Migrator)]
+    async fn test_kit_test_transactional(db: TestDatabase) {
+        assert_eq!(insert_and_count(&db).await, 1);
+    }
+
+    struct OneNote;
+
+    #[async_trait::async_trait]
+    impl crate::Seeder for OneNote {
+        async fn run(&self, db: &DbConnection) -> Result<(), FrameworkError> {
+            db.inner()
+                .execute_unprepared("INSERT INTO notes (body) VALUES ('seeded')")
+                .await?;
+            Ok(())
+        }
+    }
+
+    #[crate::kit_test(transactional, migrator = Migrator, seed = OneNote, seed = OneNote)]
+    async fn test_kit_test_runs_seeders(db: TestDatabase) {
+        assert_eq!(insert_and_count(&db).await, 3);
+    }
+
+    #[crate::kit_test(transactional, migrator = Migrator)]
+    async fn test_fixtures_are_inserted(db: TestDatabase) {
+        #[derive(serde::Deserialize)]
+        struct Note {
+            body: String,
+        }
+
+        let fixtures = crate::fixtures!("notes.yaml");
+
+        let notes: Vec<Note> = fixtures.all("notes");
+        assert_eq!(notes[1].body, "second");
+        assert_eq!(insert_and_count(&db).await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_transactional_migrates_once() {
+        let db = TestDatabase::transactional::<Migrator>().await.unwrap();
+        assert_eq!(insert_and_count(&db).await, 1);
+        db.rollback().await.unwrap();
+
+        assert_eq!(MIGRATIONS.load(Ordering::SeqCst), 1);
+    }
+}
@@ -185,19 +185,39 @@ impl ValidationErrors {
     }
 
     /// Convert from validator crate's ValidationErrors
+    ///
+    /// Errors of `#[validate(nested)]` structs and lists get dotted keys,
+    /// e.g. `address.city` and `items.2.price`.
     pub fn from_validator(errors: validator::ValidationErrors) -> Self {
         let mut result = Self::new();
-        for (field, field_errors) in errors.field_errors() {
-            for error in field_errors {
-                let message = error
-                    .message
-                    .as_ref()
-                    .map(|m| m.to_string())
-                    .unwrap_or_else(|| format!("Validation failed for field '{}'", field));
-                result.add(field.to_string(), message);
+        result.add_validator_errors("", &errors);
+        result
+    }
+
+    fn add_validator_errors(&mut self, prefix: &str, errors: &validator::ValidationErrors) {
+        for (field, kind) in errors.errors() {
+            let key = format!("{}{}", prefix, field);
+            match kind {
+                validator::ValidationErrorsKind::Field(field_errors) => {
+                    for error in field_errors {
+                        let message = error
+                            .message
+                            .as_ref()
+                            .map(|m| m.to_string())
+                            .unwrap_or_else(|| format!("Validation failed for field '{}'", key));
+                        self.add(key.clone(), message);
+                    }
+                }
+                validator::ValidationErrorsKind::Struct(nested) => {
+                    self.add_validator_errors(&format!("{}.", key), nested);
+                }
+                validator::ValidationErrorsKind::List(items) => {
+                    for (index, nested) in items {
+                        self.add_validator_errors(&format!("{}.{}.", key, index), nested);
+                    }
+                }
             }
         }
-        result
     }
 
     /// Convert to JSON Value for response
@@ -332,6 +352,19 @@ pub enum FrameworkError {
         /// The expected type (e.g., "i32", "uuid")
         expected_type: &'static str,
     },
+
+    /// Request body isn't valid JSON for the expected type (400 Bad Request)
+    ///
+    /// Used by the `Json<T>` extractor.
+    #[error("Malformed JSON body: {message}")]
+    MalformedJson {
+        /// What serde_json reported
+        message: String,
+        /// 1-based line of the error, 0 if unknown
+        line: usize,
+        /// 1-based column of the error, 0 if unknown
+        column: usize,
+    },
 }
 
 impl FrameworkError {
@@ -390,6 +423,26 @@ impl FrameworkError {
             Self::Unauthorized => 403,
             Self::ModelNotFound { .. } => 404,
             Self::ParamParse { .. } => 400,
+            Self::MalformedJson { .. } => 400,
+        }
+    }
+
+    /// Name of this error's variant, e.g. "ModelNotFound"
+    ///
+    /// Used by `ExceptionHandler::dont_report()` to list kinds of errors.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ServiceNotFound { .. } => "ServiceNotFound",
+            Self::ParamError { .. } => "ParamError",
+            Self::ValidationError { .. } => "ValidationError",
+            Self::Database(_) => "Database",
+            Self::Internal { .. } => "Internal",
+            Self::Domain { .. } => "Domain",
+            Self::Validation(_) => "Validation",
+            Self::Unauthorized => "Unauthorized",
+            Self::ModelNotFound { .. } => "ModelNotFound",
+            Self::ParamParse { .. } => "ParamParse",
+            Self::MalformedJson { .. } => "MalformedJson",
         }
     }
 
@@ -412,6 +465,15 @@ impl FrameworkError {
             expected_type,
         }
     }
+
+    /// Create a MalformedJson error (400) from a serde_json error
+    pub fn malformed_json(err: &serde_json::Error) -> Self {
+        Self::MalformedJson {
+            message: err.to_string(),
+            line: err.line(),
+            column: err.column(),
+        }
+    }
 }
 
 // Implement From<DbErr> for automatic error conversion with ?
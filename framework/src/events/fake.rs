@@ -0,0 +1,125 @@
+//! Recording event dispatcher for tests
+
+use std::any::{type_name, Any, TypeId};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A dispatched event, as recorded by `EventFake`
+struct Recorded {
+    type_id: TypeId,
+    name: &'static str,
+    event: Arc<dyn Any + Send + Sync>,
+}
+
+/// Records events instead of running listeners, returned by `Event::fake()`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let events = Event::fake();
+///
+/// register_user("ada@example.com").await?;
+///
+/// events.assert_dispatched::<UserRegistered>();
+/// events.assert_not_dispatched::<UserDeleted>();
+/// ```
+#[derive(Default)]
+pub struct EventFake {
+    events: Mutex<Vec<Recorded>>,
+}
+
+impl EventFake {
+    /// A fake with nothing dispatched
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Recorded>> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn record<E: Send + Sync + 'static>(&self, event: E) {
+        self.lock().push(Recorded {
+            type_id: TypeId::of::<E>(),
+            name: type_name::<E>(),
+            event: Arc::new(event),
+        });
+    }
+
+    /// Events of type `E` dispatched so far, oldest first
+    pub fn dispatched<E: Send + Sync + 'static>(&self) -> Vec<Arc<E>> {
+        self.lock()
+            .iter()
+            .filter(|recorded| recorded.type_id == TypeId::of::<E>())
+            .filter_map(|recorded| recorded.event.clone().downcast::<E>().ok())
+            .collect()
+    }
+
+    /// Type names of all dispatched events, oldest first
+    pub fn dispatched_names(&self) -> Vec<&'static str> {
+        self.lock().iter().map(|recorded| recorded.name).collect()
+    }
+
+    /// Panic unless an event of type `E` was dispatched
+    #[track_caller]
+    pub fn assert_dispatched<E: Send + Sync + 'static>(&self) {
+        assert!(
+            !self.dispatched::<E>().is_empty(),
+            "Expected {} to be dispatched, dispatched: {:?}",
+            type_name::<E>(),
+            self.dispatched_names()
+        );
+    }
+
+    /// Panic unless an event of type `E` matching `predicate` was dispatched
+    #[track_caller]
+    pub fn assert_dispatched_where<E, F>(&self, predicate: F)
+    where
+        E: Send + Sync + 'static,
+        F: Fn(&E) -> bool,
+    {
+        let events = self.dispatched::<E>();
+        assert!(
+            events.iter().any(|event| predicate(event)),
+            "Expected a matching {} to be dispatched, {} dispatched did not match",
+            type_name::<E>(),
+            events.len()
+        );
+    }
+
+    /// Panic unless exactly `times` events of type `E` were dispatched
+    #[track_caller]
+    pub fn assert_dispatched_times<E: Send + Sync + 'static>(&self, times: usize) {
+        let count = self.dispatched::<E>().len();
+        assert_eq!(
+            count,
+            times,
+            "Expected {} to be dispatched {} times, dispatched {} times",
+            type_name::<E>(),
+            times,
+            count
+        );
+    }
+
+    /// Panic if an event of type `E` was dispatched
+    #[track_caller]
+    pub fn assert_not_dispatched<E: Send + Sync + 'static>(&self) {
+        let count = self.dispatched::<E>().len();
+        assert!(
+            count == 0,
+            "Expected {} not to be dispatched, dispatched {} times",
+            type_name::<E>(),
+            count
+        );
+    }
+
+    /// Panic if any event was dispatched
+    #[track_caller]
+    pub fn assert_nothing_dispatched(&self) {
+        let names = self.dispatched_names();
+        assert!(
+            names.is_empty(),
+            "Expected no events to be dispatched, dispatched: {:?}",
+            names
+        );
+    }
+}
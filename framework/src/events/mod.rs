@@ -0,0 +1,227 @@
+//! Events and listeners
+//!
+//! An event is any struct. Functions marked `#[listener]` take the event by
+//! reference and are registered automatically; `Event::dispatch` runs every
+//! listener for the event's type. Listeners may be sync or async and return
+//! `()` or `Result<(), FrameworkError>`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{listener, Event, FrameworkError};
+//!
+//! pub struct UserRegistered {
+//!     pub user_id: i64,
+//! }
+//!
+//! #[listener]
+//! async fn send_welcome_email(event: &UserRegistered) -> Result<(), FrameworkError> {
+//!     dispatch!(SendWelcomeEmail { user_id: event.user_id }).await?;
+//!     Ok(())
+//! }
+//!
+//! #[listener]
+//! fn log_registration(event: &UserRegistered) {
+//!     tracing::info!(user_id = event.user_id, "user registered");
+//! }
+//!
+//! Event::dispatch(UserRegistered { user_id: user.id }).await?;
+//! ```
+//!
+//! # Testing
+//!
+//! ```rust,ignore
+//! let events = Event::fake();
+//!
+//! register_user("ada@example.com").await?;
+//!
+//! expect_event_dispatched!(UserRegistered);
+//! expect_event_dispatched!(UserRegistered, |e| e.user_id == 1);
+//! events.assert_not_dispatched::<UserDeleted>();
+//! ```
+
+pub mod fake;
+#[doc(hidden)]
+pub mod registry;
+
+pub use fake::EventFake;
+
+use crate::error::FrameworkError;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+thread_local! {
+    /// Fake installed by `Event::fake()` on this thread
+    static FAKE: RefCell<Option<Arc<EventFake>>> = const { RefCell::new(None) };
+}
+
+/// Event facade - dispatches events to their listeners
+pub struct Event;
+
+impl Event {
+    /// Run every listener registered for the event's type, in turn
+    ///
+    /// Stops at and returns the first listener error. While `Event::fake()`
+    /// is active on this thread the event is only recorded.
+    pub async fn dispatch<E>(event: E) -> Result<(), FrameworkError>
+    where
+        E: Send + Sync + 'static,
+    {
+        if let Some(fake) = Self::faked() {
+            fake.record(event);
+            return Ok(());
+        }
+
+        let event: Arc<dyn Any + Send + Sync> = Arc::new(event);
+        for entry in registry::listeners_for(TypeId::of::<E>()) {
+            (entry.handle)(Arc::clone(&event)).await?;
+        }
+        Ok(())
+    }
+
+    /// Record events instead of running listeners, for this thread
+    ///
+    /// Returns the fake to assert on. Fakes last until the thread ends, so
+    /// each `#[test]`/`#[tokio::test]` starts with real listeners.
+    pub fn fake() -> Arc<EventFake> {
+        let fake = Arc::new(EventFake::new());
+        FAKE.with(|current| *current.borrow_mut() = Some(Arc::clone(&fake)));
+        fake
+    }
+
+    /// The fake installed on this thread, if any
+    pub fn faked() -> Option<Arc<EventFake>> {
+        FAKE.with(|current| current.borrow().clone())
+    }
+
+    /// Names of the listeners registered for events of type `E`
+    pub fn listeners<E: 'static>() -> Vec<&'static str> {
+        registry::listeners_for(TypeId::of::<E>())
+            .map(|entry| entry.name)
+            .collect()
+    }
+}
+
+/// Assert that an event was dispatched while `Event::fake()` is active
+///
+/// Pass a predicate to require a matching event.
+///
+/// Example:
+/// ```rust,ignore
+/// Event::fake();
+/// register_user("ada@example.com").await?;
+///
+/// expect_event_dispatched!(UserRegistered);
+/// expect_event_dispatched!(UserRegistered, |e| e.email == "ada@example.com");
+/// ```
+#[macro_export]
+macro_rules! expect_event_dispatched {
+    ($event:ty) => {
+        $crate::events::Event::faked()
+            .expect("expect_event_dispatched! needs Event::fake() to be called first")
+            .assert_dispatched::<$event>()
+    };
+    ($event:ty, $predicate:expr $(,)?) => {
+        $crate::events::Event::faked()
+            .expect("expect_event_dispatched! needs Event::fake() to be called first")
+            .assert_dispatched_where::<$event, _>($predicate)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static WELCOMED: AtomicUsize = AtomicUsize::new(0);
+    static LOGGED: AtomicUsize = AtomicUsize::new(0);
+    static PAID: AtomicUsize = AtomicUsize::new(0);
+
+    struct UserRegistered {
+        user_id: usize,
+    }
+
+    struct OrderShipped;
+
+    struct PaymentFailed;
+
+    struct InvoicePaid {
+        amount: usize,
+    }
+
+    #[crate::listener]
+    async fn welcome(event: &UserRegistered) -> Result<(), FrameworkError> {
+        tokio::task::yield_now().await;
+        WELCOMED.fetch_add(event.user_id, Ordering::SeqCst);
+        Ok(())
+    }
+
+    #[crate::listener]
+    fn log_registration(_event: &UserRegistered) {
+        LOGGED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[crate::listener]
+    async fn refuse(_event: &PaymentFailed) -> Result<(), FrameworkError> {
+        Err(FrameworkError::internal("listener failed"))
+    }
+
+    #[crate::listener]
+    fn record_payment(event: &InvoicePaid) {
+        PAID.fetch_add(event.amount, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn listeners_are_registered_by_event_type() {
+        let mut names = Event::listeners::<UserRegistered>();
+        names.sort();
+        assert_eq!(names.len(), 2);
+        assert!(names[0].ends_with("::events::tests::log_registration"));
+        assert!(names[1].ends_with("::events::tests::welcome"));
+        assert!(Event::listeners::<OrderShipped>().is_empty());
+    }
+
+    #[tokio::test]
+    async fn dispatch_runs_sync_and_async_listeners() {
+        let welcomed = WELCOMED.load(Ordering::SeqCst);
+        let logged = LOGGED.load(Ordering::SeqCst);
+
+        Event::dispatch(UserRegistered { user_id: 3 })
+            .await
+            .unwrap();
+        Event::dispatch(OrderShipped).await.unwrap();
+
+        assert_eq!(WELCOMED.load(Ordering::SeqCst), welcomed + 3);
+        assert_eq!(LOGGED.load(Ordering::SeqCst), logged + 1);
+    }
+
+    #[tokio::test]
+    async fn listener_errors_are_returned() {
+        let error = Event::dispatch(PaymentFailed).await.unwrap_err();
+        assert!(error.to_string().contains("listener failed"));
+    }
+
+    #[tokio::test]
+    async fn fake_records_events_without_running_listeners() {
+        let events = Event::fake();
+
+        Event::dispatch(InvoicePaid { amount: 7 }).await.unwrap();
+        Event::dispatch(InvoicePaid { amount: 8 }).await.unwrap();
+        Event::dispatch(PaymentFailed).await.unwrap();
+
+        assert_eq!(PAID.load(Ordering::SeqCst), 0);
+        crate::expect_event_dispatched!(InvoicePaid);
+        crate::expect_event_dispatched!(InvoicePaid, |e| e.amount == 8);
+        events.assert_dispatched_times::<InvoicePaid>(2);
+        events.assert_not_dispatched::<OrderShipped>();
+    }
+
+    #[test]
+    #[should_panic(expected = "not to be dispatched")]
+    fn fake_assertions_fail_with_a_message() {
+        let events = Event::fake();
+        events.record(OrderShipped);
+        events.assert_not_dispatched::<OrderShipped>();
+    }
+}
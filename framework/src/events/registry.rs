@@ -0,0 +1,47 @@
+//! Listener registry via inventory
+
+use crate::error::FrameworkError;
+use std::any::{Any, TypeId};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Boxed listener, given the dispatched event
+pub type ListenerFn = fn(
+    Arc<dyn Any + Send + Sync>,
+) -> Pin<Box<dyn Future<Output = Result<(), FrameworkError>> + Send>>;
+
+/// Inventory entry for a listener
+pub struct ListenerEntry {
+    /// Type of event the listener handles
+    pub event: fn() -> TypeId,
+    /// `module::path::function`
+    pub name: &'static str,
+    pub handle: ListenerFn,
+}
+
+inventory::collect!(ListenerEntry);
+
+/// Listeners registered for events of type `event`
+pub fn listeners_for(event: TypeId) -> impl Iterator<Item = &'static ListenerEntry> {
+    inventory::iter::<ListenerEntry>
+        .into_iter()
+        .filter(move |entry| (entry.event)() == event)
+}
+
+/// What a listener function may return: `()` or `Result<(), FrameworkError>`
+pub trait ListenerResult {
+    fn into_result(self) -> Result<(), FrameworkError>;
+}
+
+impl ListenerResult for () {
+    fn into_result(self) -> Result<(), FrameworkError> {
+        Ok(())
+    }
+}
+
+impl ListenerResult for Result<(), FrameworkError> {
+    fn into_result(self) -> Result<(), FrameworkError> {
+        self
+    }
+}
@@ -0,0 +1,150 @@
+//! Geolocation configuration for Kit framework
+
+use crate::config::env;
+
+/// Geolocation configuration
+///
+/// # Environment Variables
+///
+/// - `GEO_DRIVER` - "ip-api" or "maxmind" (default: "ip-api")
+/// - `GEO_IP_API_URL` - ip-api.com endpoint, e.g. "https://pro.ip-api.com"
+///   with a key (default: "http://ip-api.com")
+/// - `MAXMIND_ACCOUNT_ID` - Account ID for the MaxMind GeoIP2 web service
+/// - `MAXMIND_LICENSE_KEY` - License key for the MaxMind GeoIP2 web service
+/// - `GEO_MAXMIND_URL` - MaxMind endpoint, e.g. "https://geolite.info" for
+///   GeoLite2 (default: "https://geoip.maxmind.com")
+/// - `GEO_CACHE_TTL` - Seconds a lookup is cached, 0 to disable (default: 86400)
+/// - `GEO_TIMEOUT` - Seconds to wait for a lookup (default: 2)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Config, GeoConfig};
+///
+/// // Register from environment
+/// Config::register(GeoConfig::from_env());
+///
+/// // Or build manually
+/// Config::register(GeoConfig::builder()
+///     .driver("maxmind")
+///     .maxmind_account_id("123456")
+///     .maxmind_license_key("...")
+///     .build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct GeoConfig {
+    /// Lookup driver, "ip-api" or "maxmind"
+    pub driver: String,
+    /// Base URL of ip-api.com
+    pub ip_api_url: String,
+    /// MaxMind account ID
+    pub maxmind_account_id: String,
+    /// MaxMind license key
+    pub maxmind_license_key: String,
+    /// Base URL of the MaxMind web service
+    pub maxmind_url: String,
+    /// Seconds a lookup is cached, 0 to disable
+    pub cache_ttl: u64,
+    /// Seconds to wait for a lookup
+    pub timeout: u64,
+}
+
+impl GeoConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            driver: env("GEO_DRIVER", "ip-api".to_string()),
+            ip_api_url: env("GEO_IP_API_URL", "http://ip-api.com".to_string()),
+            maxmind_account_id: env("MAXMIND_ACCOUNT_ID", String::new()),
+            maxmind_license_key: env("MAXMIND_LICENSE_KEY", String::new()),
+            maxmind_url: env("GEO_MAXMIND_URL", "https://geoip.maxmind.com".to_string()),
+            cache_ttl: env("GEO_CACHE_TTL", 86400),
+            timeout: env("GEO_TIMEOUT", 2),
+        }
+    }
+
+    /// Create a builder for manual configuration
+    pub fn builder() -> GeoConfigBuilder {
+        GeoConfigBuilder::default()
+    }
+}
+
+impl Default for GeoConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Builder for GeoConfig
+#[derive(Debug, Default)]
+pub struct GeoConfigBuilder {
+    driver: Option<String>,
+    ip_api_url: Option<String>,
+    maxmind_account_id: Option<String>,
+    maxmind_license_key: Option<String>,
+    maxmind_url: Option<String>,
+    cache_ttl: Option<u64>,
+    timeout: Option<u64>,
+}
+
+impl GeoConfigBuilder {
+    /// Set the lookup driver, "ip-api" or "maxmind"
+    pub fn driver(mut self, driver: impl Into<String>) -> Self {
+        self.driver = Some(driver.into());
+        self
+    }
+
+    /// Set the base URL of ip-api.com
+    pub fn ip_api_url(mut self, url: impl Into<String>) -> Self {
+        self.ip_api_url = Some(url.into());
+        self
+    }
+
+    /// Set the MaxMind account ID
+    pub fn maxmind_account_id(mut self, id: impl Into<String>) -> Self {
+        self.maxmind_account_id = Some(id.into());
+        self
+    }
+
+    /// Set the MaxMind license key
+    pub fn maxmind_license_key(mut self, key: impl Into<String>) -> Self {
+        self.maxmind_license_key = Some(key.into());
+        self
+    }
+
+    /// Set the base URL of the MaxMind web service
+    pub fn maxmind_url(mut self, url: impl Into<String>) -> Self {
+        self.maxmind_url = Some(url.into());
+        self
+    }
+
+    /// Set how many seconds a lookup is cached, 0 to disable
+    pub fn cache_ttl(mut self, seconds: u64) -> Self {
+        self.cache_ttl = Some(seconds);
+        self
+    }
+
+    /// Set how many seconds to wait for a lookup
+    pub fn timeout(mut self, seconds: u64) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> GeoConfig {
+        let defaults = GeoConfig::from_env();
+        GeoConfig {
+            driver: self.driver.unwrap_or(defaults.driver),
+            ip_api_url: self.ip_api_url.unwrap_or(defaults.ip_api_url),
+            maxmind_account_id: self
+                .maxmind_account_id
+                .unwrap_or(defaults.maxmind_account_id),
+            maxmind_license_key: self
+                .maxmind_license_key
+                .unwrap_or(defaults.maxmind_license_key),
+            maxmind_url: self.maxmind_url.unwrap_or(defaults.maxmind_url),
+            cache_ttl: self.cache_ttl.unwrap_or(defaults.cache_ttl),
+            timeout: self.timeout.unwrap_or(defaults.timeout),
+        }
+    }
+}
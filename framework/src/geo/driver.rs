@@ -0,0 +1,135 @@
+//! Geolocation lookup drivers
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use super::config::GeoConfig;
+use super::Location;
+use crate::error::FrameworkError;
+
+/// Looks up where an IP address is
+///
+/// Bind an implementation with `bind!(dyn GeoDriver, ...)` to replace the
+/// configured driver, e.g. to read a local database.
+#[async_trait]
+pub trait GeoDriver: Send + Sync {
+    /// The location of `ip`, or `None` if the service doesn't know it
+    async fn locate(&self, ip: IpAddr) -> Result<Option<Location>, FrameworkError>;
+}
+
+/// Driver for the ip-api.com JSON API
+pub struct IpApiDriver {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl IpApiDriver {
+    pub fn new(config: &GeoConfig) -> Self {
+        Self {
+            url: config.ip_api_url.trim_end_matches('/').to_string(),
+            client: client(config),
+        }
+    }
+}
+
+#[async_trait]
+impl GeoDriver for IpApiDriver {
+    async fn locate(&self, ip: IpAddr) -> Result<Option<Location>, FrameworkError> {
+        let url = format!(
+            "{}/json/{}?fields=status,message,countryCode,country,regionName,city,lat,lon,timezone",
+            self.url, ip
+        );
+        let body = get_json(self.client.get(url)).await?;
+        // Private and reserved ranges come back as "fail"
+        if body["status"] != "success" {
+            return Ok(None);
+        }
+        Ok(Some(Location {
+            country: string(&body["countryCode"]).unwrap_or_default(),
+            country_name: string(&body["country"]),
+            region: string(&body["regionName"]),
+            city: string(&body["city"]),
+            latitude: body["lat"].as_f64(),
+            longitude: body["lon"].as_f64(),
+            timezone: string(&body["timezone"]),
+        }))
+    }
+}
+
+/// Driver for the MaxMind GeoIP2 (or GeoLite2) City web service
+pub struct MaxMindDriver {
+    url: String,
+    account_id: String,
+    license_key: String,
+    client: reqwest::Client,
+}
+
+impl MaxMindDriver {
+    pub fn new(config: &GeoConfig) -> Self {
+        Self {
+            url: config.maxmind_url.trim_end_matches('/').to_string(),
+            account_id: config.maxmind_account_id.clone(),
+            license_key: config.maxmind_license_key.clone(),
+            client: client(config),
+        }
+    }
+}
+
+#[async_trait]
+impl GeoDriver for MaxMindDriver {
+    async fn locate(&self, ip: IpAddr) -> Result<Option<Location>, FrameworkError> {
+        let request = self
+            .client
+            .get(format!("{}/geoip/v2.1/city/{}", self.url, ip))
+            .basic_auth(&self.account_id, Some(&self.license_key));
+        let body = get_json(request).await?;
+        if let Some(code) = body["code"].as_str() {
+            return match code {
+                "IP_ADDRESS_NOT_FOUND" | "IP_ADDRESS_RESERVED" => Ok(None),
+                _ => Err(FrameworkError::internal(format!(
+                    "MaxMind lookup failed: {}",
+                    body["error"].as_str().unwrap_or(code)
+                ))),
+            };
+        }
+        let Some(country) = string(&body["country"]["iso_code"]) else {
+            return Ok(None);
+        };
+        Ok(Some(Location {
+            country,
+            country_name: string(&body["country"]["names"]["en"]),
+            region: string(&body["subdivisions"][0]["names"]["en"]),
+            city: string(&body["city"]["names"]["en"]),
+            latitude: body["location"]["latitude"].as_f64(),
+            longitude: body["location"]["longitude"].as_f64(),
+            timezone: string(&body["location"]["time_zone"]),
+        }))
+    }
+}
+
+fn client(config: &GeoConfig) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout))
+        .build()
+        .unwrap_or_default()
+}
+
+/// The JSON body of a lookup; error statuses carry JSON too
+async fn get_json(request: reqwest::RequestBuilder) -> Result<Value, FrameworkError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| FrameworkError::internal(format!("Geo lookup failed: {}", e)))?;
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| FrameworkError::internal(format!("Geo lookup failed: {}", e)))?;
+    serde_json::from_slice(&body)
+        .map_err(|e| FrameworkError::internal(format!("Invalid geo lookup response: {}", e)))
+}
+
+fn string(value: &Value) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(str::to_string)
+}
@@ -0,0 +1,94 @@
+//! Picking a locale for a request
+
+/// The supported locale that best fits an `Accept-Language` header, falling
+/// back to one for `country`, then to the first supported locale
+pub fn negotiate<'a>(
+    accept_language: Option<&str>,
+    country: Option<&str>,
+    supported: &[&'a str],
+) -> Option<&'a str> {
+    accept_language
+        .and_then(|header| from_header(header, supported))
+        .or_else(|| country.and_then(|country| for_country(country, supported)))
+        .or(supported.first().copied())
+}
+
+/// The supported locale that best fits an `Accept-Language` header
+///
+/// Tags match exactly or by language ("de-AT" picks "de", "de" picks
+/// "de-DE"), in order of preference.
+pub fn from_header<'a>(accept_language: &str, supported: &[&'a str]) -> Option<&'a str> {
+    let mut tags: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|part| {
+            let mut pieces = part.split(';');
+            let tag = pieces.next()?.trim();
+            let quality = pieces
+                .find_map(|piece| piece.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse().ok())?;
+            (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    // Stable, so equal weights keep the client's order
+    tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    tags.iter().find_map(|(tag, _)| {
+        let exact = supported
+            .iter()
+            .find(|locale| normalize(locale) == normalize(tag));
+        exact
+            .or_else(|| {
+                supported
+                    .iter()
+                    .find(|locale| language(locale) == language(tag))
+            })
+            .copied()
+    })
+}
+
+/// The supported locale whose region is `country`, so "CH" picks "de-CH"
+pub fn for_country<'a>(country: &str, supported: &[&'a str]) -> Option<&'a str> {
+    supported
+        .iter()
+        .find(|locale| region(locale).is_some_and(|region| region.eq_ignore_ascii_case(country)))
+        .copied()
+}
+
+fn normalize(tag: &str) -> String {
+    tag.replace('_', "-").to_ascii_lowercase()
+}
+
+fn language(tag: &str) -> String {
+    normalize(tag)
+        .split('-')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn region(tag: &str) -> Option<&str> {
+    tag.split(['-', '_']).nth(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUPPORTED: &[&str] = &["en", "de-DE", "de-CH", "fr_FR"];
+
+    #[test]
+    fn negotiates_by_header_then_country() {
+        assert_eq!(negotiate(Some("de-ch"), None, SUPPORTED), Some("de-CH"));
+        assert_eq!(negotiate(Some("de-AT"), None, SUPPORTED), Some("de-DE"));
+        assert_eq!(
+            negotiate(Some("es;q=0.9, fr-CA;q=0.8, en;q=0.5"), None, SUPPORTED),
+            Some("fr_FR")
+        );
+        assert_eq!(negotiate(Some("fr;q=0, *"), None, SUPPORTED), Some("en"));
+
+        assert_eq!(negotiate(Some("es"), Some("ch"), SUPPORTED), Some("de-CH"));
+        assert_eq!(negotiate(None, Some("FR"), SUPPORTED), Some("fr_FR"));
+        assert_eq!(negotiate(None, Some("US"), SUPPORTED), Some("en"));
+        assert_eq!(negotiate(None, None, &[]), None);
+    }
+}
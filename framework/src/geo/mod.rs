@@ -0,0 +1,190 @@
+//! IP geolocation
+//!
+//! Enabled with the `geo` feature. `Geo` looks addresses up with ip-api.com
+//! or the MaxMind GeoIP2 web service (see `GeoConfig`) and caches the answer;
+//! `Request::country()` and `Request::locale()` use it for the client.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::Geo;
+//!
+//! // In a handler
+//! if req.country().await.as_deref() == Some("DE") {
+//!     // ...
+//! }
+//!
+//! // Pick one of the app's locales from Accept-Language, then the country
+//! let locale = req.locale(&["en", "de-DE", "fr-FR"]).await;
+//!
+//! // Any address
+//! let location = Geo::locate("81.2.69.142".parse()?).await?;
+//! ```
+
+pub mod config;
+pub mod driver;
+pub mod locale;
+
+pub use config::{GeoConfig, GeoConfigBuilder};
+pub use driver::{GeoDriver, IpApiDriver, MaxMindDriver};
+
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::container::App;
+use crate::error::FrameworkError;
+
+/// Where an IP address is
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    /// ISO 3166-1 alpha-2 country code, e.g. "DE"
+    pub country: String,
+    pub country_name: Option<String>,
+    /// Region or state
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// IANA time zone, e.g. "Europe/Berlin"
+    pub timezone: Option<String>,
+}
+
+/// Geo facade - looks up where IP addresses are
+pub struct Geo;
+
+impl Geo {
+    /// The bound `dyn GeoDriver`, or the one named by `GEO_DRIVER`
+    pub fn driver() -> Result<Arc<dyn GeoDriver>, FrameworkError> {
+        if let Some(driver) = App::make::<dyn GeoDriver>() {
+            return Ok(driver);
+        }
+        let config = Config::get::<GeoConfig>().unwrap_or_default();
+        match config.driver.as_str() {
+            "ip-api" => Ok(Arc::new(IpApiDriver::new(&config))),
+            "maxmind" => Ok(Arc::new(MaxMindDriver::new(&config))),
+            other => Err(FrameworkError::internal(format!(
+                "Unknown geo driver '{}'",
+                other
+            ))),
+        }
+    }
+
+    /// The location of `ip`
+    ///
+    /// Private, loopback and other local addresses are `None` without a
+    /// lookup. Answers are cached for `GEO_CACHE_TTL` seconds.
+    pub async fn locate(ip: IpAddr) -> Result<Option<Location>, FrameworkError> {
+        if is_local(ip) {
+            return Ok(None);
+        }
+        let ttl = Config::get::<GeoConfig>().unwrap_or_default().cache_ttl;
+        if ttl == 0 || !Cache::is_initialized() {
+            return Self::driver()?.locate(ip).await;
+        }
+        Cache::remember(
+            &format!("geo:{}", ip),
+            Some(Duration::from_secs(ttl)),
+            || async { Self::driver()?.locate(ip).await },
+        )
+        .await
+    }
+
+    /// The country code of `ip`, e.g. "DE"
+    pub async fn country(ip: IpAddr) -> Result<Option<String>, FrameworkError> {
+        Ok(Self::locate(ip).await?.map(|location| location.country))
+    }
+}
+
+fn is_local(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => {
+            let segment = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                || (segment & 0xfe00) == 0xfc00
+                || (segment & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{CacheStore, InMemoryCache};
+    use crate::http::Request;
+    use crate::testing::TestContainer;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// An ip-api server answering every request with `body`, counting them
+    async fn ip_api(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let n = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                assert!(request.starts_with("GET /json/81.2.69.142?fields="));
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url, hits)
+    }
+
+    #[tokio::test]
+    async fn lookups_are_cached_and_used_by_requests() {
+        let (url, hits) = ip_api(
+            r#"{"status":"success","countryCode":"GB","country":"United Kingdom","regionName":"England","city":"London","lat":51.5,"lon":-0.1,"timezone":"Europe/London"}"#,
+        )
+        .await;
+        let _container = TestContainer::fake();
+        TestContainer::bind::<dyn CacheStore>(Arc::new(InMemoryCache::new()));
+        TestContainer::bind::<dyn GeoDriver>(Arc::new(IpApiDriver::new(
+            &GeoConfig::builder().ip_api_url(url).build(),
+        )));
+
+        let ip: IpAddr = "81.2.69.142".parse().unwrap();
+        let location = Geo::locate(ip).await.unwrap().unwrap();
+        assert_eq!(location.country, "GB");
+        assert_eq!(location.city.as_deref(), Some("London"));
+        assert_eq!(location.timezone.as_deref(), Some("Europe/London"));
+
+        let req = Request::fake()
+            .remote_addr("81.2.69.142:4000")
+            .header("Accept-Language", "es")
+            .build();
+        assert_eq!(req.country().await.as_deref(), Some("GB"));
+        assert_eq!(req.locale(&["de", "en-US", "en-GB"]).await, Some("en-GB"));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        // Local addresses are never looked up
+        assert_eq!(
+            Geo::locate("192.168.1.1".parse().unwrap()).await.unwrap(),
+            None
+        );
+        assert_eq!(Geo::locate("::1".parse().unwrap()).await.unwrap(), None);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
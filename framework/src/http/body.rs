@@ -1,19 +1,123 @@
 //! Body parsing utilities for HTTP requests
 //!
-//! Provides async body collection and parsing for JSON and form-urlencoded data.
+//! Provides async body collection and parsing for JSON, form-urlencoded and
+//! multipart form data.
+//!
+//! Bodies are read within the limits from `ServerConfig`: a body larger than
+//! `max_body_size` is rejected with 413, and one that takes longer than
+//! `body_timeout_secs` to arrive with 408, so slow uploads can't hold a
+//! connection open indefinitely.
 
+use super::upload::{deserialize_form, UploadedFile};
+use crate::config::{Config, ServerConfig};
 use crate::error::FrameworkError;
 use bytes::Bytes;
-use http_body_util::BodyExt;
-use hyper::body::Incoming;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use hyper::body::{Body, Incoming};
+use hyper::HeaderMap;
 use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// Size and time limits for reading a request body
+#[derive(Debug, Clone, Copy)]
+pub struct BodyLimits {
+    /// Maximum body size in bytes
+    pub max_size: usize,
+    /// Time allowed for reading the whole body
+    pub timeout: Option<Duration>,
+}
+
+impl BodyLimits {
+    /// The limits from the registered `ServerConfig`
+    pub fn from_config() -> Self {
+        let config = Config::get::<ServerConfig>().unwrap_or_default();
+        Self {
+            max_size: config.max_body_size,
+            timeout: (config.body_timeout_secs > 0)
+                .then(|| Duration::from_secs(config.body_timeout_secs)),
+        }
+    }
+
+    /// Reject a request whose `Content-Length` is over the size limit
+    ///
+    /// Lets the server answer 413 before reading any of the body.
+    pub fn check_content_length(&self, headers: &HeaderMap) -> Result<(), FrameworkError> {
+        let length = headers
+            .get(hyper::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        match length {
+            Some(length) if length > self.max_size as u64 => Err(self.too_large()),
+            _ => Ok(()),
+        }
+    }
 
-/// Collect the full body from an Incoming stream
+    fn too_large(&self) -> FrameworkError {
+        FrameworkError::domain(
+            format!(
+                "Request body is larger than the limit of {} bytes",
+                self.max_size
+            ),
+            413,
+        )
+    }
+}
+
+/// Collect the full body from an Incoming stream, within `BodyLimits::from_config()`
 pub async fn collect_body(body: Incoming) -> Result<Bytes, FrameworkError> {
-    body.collect()
-        .await
+    collect_body_with_limits(body, BodyLimits::from_config()).await
+}
+
+/// Collect the full body of any `Body`, within `limits`
+///
+/// Fails with 413 once the body grows past `max_size` (whatever the
+/// `Content-Length` said) and with 408 if it isn't complete in time.
+pub async fn collect_body_with_limits<B>(
+    body: B,
+    limits: BodyLimits,
+) -> Result<Bytes, FrameworkError>
+where
+    B: Body,
+    B::Error: std::error::Error + Send + Sync + 'static,
+{
+    let collect = Limited::new(body, limits.max_size).collect();
+    let collected = match limits.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, collect)
+            .await
+            .map_err(|_| FrameworkError::domain("Timed out reading the request body", 408))?,
+        None => collect.await,
+    };
+
+    collected
         .map(|collected| collected.to_bytes())
-        .map_err(|e| FrameworkError::internal(format!("Failed to read request body: {}", e)))
+        .map_err(|e| {
+            if e.is::<LengthLimitError>() {
+                limits.too_large()
+            } else {
+                FrameworkError::internal(format!("Failed to read request body: {}", e))
+            }
+        })
+}
+
+/// Body of a `Request`
+///
+/// Streamed from the connection for real requests, or held in memory for
+/// requests built with `Request::fake()`.
+pub enum RequestBody {
+    /// Body streamed from the client
+    Incoming(Incoming),
+    /// Body already in memory
+    Full(Bytes),
+}
+
+impl RequestBody {
+    /// Collect the full body
+    pub async fn collect(self) -> Result<Bytes, FrameworkError> {
+        match self {
+            RequestBody::Incoming(body) => collect_body(body).await,
+            RequestBody::Full(bytes) => Ok(bytes),
+        }
+    }
 }
 
 /// Parse bytes as JSON into the target type
@@ -27,3 +131,159 @@ pub fn parse_form<T: DeserializeOwned>(bytes: &Bytes) -> Result<T, FrameworkErro
     serde_urlencoded::from_bytes(bytes)
         .map_err(|e| FrameworkError::internal(format!("Failed to parse form body: {}", e)))
 }
+
+/// A parsed `multipart/form-data` body
+#[derive(Debug, Default)]
+pub struct MultipartForm {
+    /// Text fields, in the order they were sent
+    pub fields: Vec<(String, String)>,
+    /// Uploaded files with their field names
+    pub files: Vec<(String, UploadedFile)>,
+}
+
+impl MultipartForm {
+    /// The first text field named `name`
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The first file uploaded as `name`
+    pub fn file(&self, name: &str) -> Option<&UploadedFile> {
+        self.files
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, file)| file)
+    }
+
+    /// Deserialize the fields and files into the target type
+    ///
+    /// Text fields are parsed like form-urlencoded fields; `UploadedFile`
+    /// and `Option<UploadedFile>` fields receive the files.
+    pub fn deserialize<T: DeserializeOwned>(self) -> Result<T, FrameworkError> {
+        deserialize_form(self)
+    }
+}
+
+/// Parse a `multipart/form-data` body
+///
+/// `content_type` is the request's `Content-Type`, which names the boundary.
+/// File inputs left empty by the browser are skipped.
+pub async fn parse_multipart(
+    content_type: &str,
+    bytes: Bytes,
+) -> Result<MultipartForm, FrameworkError> {
+    let error = |e: multer::Error| {
+        FrameworkError::internal(format!("Failed to parse multipart body: {}", e))
+    };
+    let boundary = multer::parse_boundary(content_type).map_err(error)?;
+    let mut multipart = multer::Multipart::with_reader(std::io::Cursor::new(bytes), boundary);
+
+    let mut form = MultipartForm::default();
+    while let Some(field) = multipart.next_field().await.map_err(error)? {
+        let name = field.name().unwrap_or_default().to_string();
+        match field.file_name().map(str::to_string) {
+            Some(file_name) => {
+                let content_type = field
+                    .content_type()
+                    .map_or("application/octet-stream".to_string(), |mime| {
+                        mime.to_string()
+                    });
+                let data = field.bytes().await.map_err(error)?;
+                if file_name.is_empty() && data.is_empty() {
+                    continue;
+                }
+                form.files
+                    .push((name, UploadedFile::new(file_name, content_type, data)));
+            }
+            None => form.fields.push((name, field.text().await.map_err(error)?)),
+        }
+    }
+    Ok(form)
+}
+
+/// Parse a body by its `Content-Type`: form-urlencoded, multipart or JSON
+pub(crate) async fn parse_input<T: DeserializeOwned>(
+    content_type: Option<&str>,
+    bytes: Bytes,
+) -> Result<T, FrameworkError> {
+    match content_type {
+        Some(ct) if ct.starts_with("application/x-www-form-urlencoded") => parse_form(&bytes),
+        Some(ct) if ct.starts_with("multipart/form-data") => {
+            parse_multipart(ct, bytes).await?.deserialize()
+        }
+        _ => parse_json(&bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::Full;
+    use hyper::body::Frame;
+
+    fn limits(max_size: usize, timeout_ms: u64) -> BodyLimits {
+        BodyLimits {
+            max_size,
+            timeout: Some(Duration::from_millis(timeout_ms)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collects_bodies_within_the_limits() {
+        let body = Full::new(Bytes::from_static(b"hello"));
+        let bytes = collect_body_with_limits(body, limits(5, 1000))
+            .await
+            .unwrap();
+        assert_eq!(bytes, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bodies_over_the_size_limit() {
+        let body = Full::new(Bytes::from_static(b"hello world"));
+        let err = collect_body_with_limits(body, limits(5, 1000))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code(), 413);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(hyper::header::CONTENT_LENGTH, "6".parse().unwrap());
+        let err = limits(5, 1000).check_content_length(&headers).unwrap_err();
+        assert_eq!(err.status_code(), 413);
+        headers.insert(hyper::header::CONTENT_LENGTH, "5".parse().unwrap());
+        assert!(limits(5, 1000).check_content_length(&headers).is_ok());
+    }
+
+    /// A client that sends one chunk and then stalls
+    struct StalledBody {
+        sent: bool,
+    }
+
+    impl Body for StalledBody {
+        type Data = Bytes;
+        type Error = std::io::Error;
+
+        fn poll_frame(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+            if self.sent {
+                return std::task::Poll::Pending;
+            }
+            self.sent = true;
+            std::task::Poll::Ready(Some(Ok(Frame::data(Bytes::from_static(b"he")))))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_times_out_slow_bodies() {
+        let body = StalledBody { sent: false };
+
+        let err = collect_body_with_limits(body, limits(1024, 20))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code(), 408);
+    }
+}
@@ -1,7 +1,9 @@
 //! Cookie handling for Kit framework
 //!
-//! Provides Laravel-like cookie API with secure defaults.
+//! Provides Laravel-like cookie API with secure defaults. The defaults
+//! depend on the environment and are set in [`CookieConfig`].
 
+use crate::config::{env_optional, AppConfig, Config, Environment};
 use std::collections::HashMap;
 use std::time::Duration;
 
@@ -19,6 +21,141 @@ impl Default for SameSite {
     }
 }
 
+impl SameSite {
+    /// Parse `strict`, `lax` or `none`, ignoring case; anything else is `Lax`
+    pub fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "strict" => Self::Strict,
+            "none" => Self::None,
+            _ => Self::Lax,
+        }
+    }
+
+    /// The attribute value, e.g. `Lax`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Strict => "Strict",
+            Self::Lax => "Lax",
+            Self::None => "None",
+        }
+    }
+}
+
+/// Cookie defaults for the current environment
+///
+/// Cookies are `Secure` and `SameSite=Lax` everywhere except local,
+/// development and testing environments, which are usually served over
+/// plain HTTP and get non-secure cookies. `Cookie::new`, the session
+/// cookie and the `XSRF-TOKEN` cookie start from these; builder methods on
+/// a cookie and the `SESSION_*` variables override them.
+///
+/// # Environment Variables
+///
+/// - `COOKIE_SECURE` - Send cookies over HTTPS only (default: by environment)
+/// - `COOKIE_SAME_SITE` - `lax`, `strict` or `none` (default: lax)
+/// - `COOKIE_DOMAIN` - Domain cookies are set for (default: the request's host)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Config, CookieConfig, SameSite};
+///
+/// Config::register(CookieConfig::builder()
+///     .same_site(SameSite::Strict)
+///     .domain(".example.com")
+///     .build());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CookieConfig {
+    /// Set the `Secure` flag
+    pub secure: bool,
+    /// `SameSite` attribute
+    pub same_site: SameSite,
+    /// `Domain` attribute
+    pub domain: Option<String>,
+}
+
+impl CookieConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        let environment = Config::get::<AppConfig>()
+            .map(|app| app.environment)
+            .unwrap_or_else(Environment::detect);
+        let defaults = Self::for_environment(&environment);
+        Self {
+            secure: env_optional::<String>("COOKIE_SECURE")
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_lowercase() == "true" || s == "1")
+                .unwrap_or(defaults.secure),
+            same_site: env_optional::<String>("COOKIE_SAME_SITE")
+                .map(|s| SameSite::parse(&s))
+                .unwrap_or(defaults.same_site),
+            domain: env_optional::<String>("COOKIE_DOMAIN").filter(|s| !s.is_empty()),
+        }
+    }
+
+    /// The defaults for an environment, ignoring environment variables
+    pub fn for_environment(environment: &Environment) -> Self {
+        Self {
+            secure: !matches!(
+                environment,
+                Environment::Local | Environment::Development | Environment::Testing
+            ),
+            same_site: SameSite::Lax,
+            domain: None,
+        }
+    }
+
+    /// Create a builder for manual configuration
+    pub fn builder() -> CookieConfigBuilder {
+        CookieConfigBuilder::default()
+    }
+}
+
+impl Default for CookieConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Builder for CookieConfig
+#[derive(Debug, Default)]
+pub struct CookieConfigBuilder {
+    secure: Option<bool>,
+    same_site: Option<SameSite>,
+    domain: Option<String>,
+}
+
+impl CookieConfigBuilder {
+    /// Set whether cookies are sent over HTTPS only
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = Some(secure);
+        self
+    }
+
+    /// Set the SameSite attribute
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Set the domain cookies are set for
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> CookieConfig {
+        let defaults = CookieConfig::from_env();
+        CookieConfig {
+            secure: self.secure.unwrap_or(defaults.secure),
+            same_site: self.same_site.unwrap_or(defaults.same_site),
+            domain: self.domain.or(defaults.domain),
+        }
+    }
+}
+
 /// Cookie options with secure defaults
 #[derive(Clone, Debug)]
 pub struct CookieOptions {
@@ -31,13 +168,15 @@ pub struct CookieOptions {
 }
 
 impl Default for CookieOptions {
+    /// HttpOnly on path `/`, with the rest from `CookieConfig`
     fn default() -> Self {
+        let config = Config::get::<CookieConfig>().unwrap_or_default();
         Self {
             http_only: true,
-            secure: true,
-            same_site: SameSite::Lax,
+            secure: config.secure,
+            same_site: config.same_site,
             path: "/".to_string(),
-            domain: None,
+            domain: config.domain,
             max_age: None,
         }
     }
@@ -68,9 +207,11 @@ impl Cookie {
     ///
     /// Default options:
     /// - HttpOnly: true
-    /// - Secure: true
+    /// - Secure: true, except in local, development and testing environments
     /// - SameSite: Lax
     /// - Path: "/"
+    ///
+    /// See [`CookieConfig`] to change them.
     pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
         Self {
             name: name.into(),
@@ -97,7 +238,7 @@ impl Cookie {
         self
     }
 
-    /// Set the Secure flag (default: true)
+    /// Set the Secure flag (default: from `CookieConfig`)
     ///
     /// Secure cookies are only sent over HTTPS connections.
     pub fn secure(mut self, value: bool) -> Self {
@@ -105,7 +246,7 @@ impl Cookie {
         self
     }
 
-    /// Set the SameSite attribute (default: Lax)
+    /// Set the SameSite attribute (default: from `CookieConfig`)
     ///
     /// Controls when the cookie is sent with cross-site requests.
     pub fn same_site(mut self, value: SameSite) -> Self {
@@ -151,11 +292,7 @@ impl Cookie {
             parts.push("Secure".to_string());
         }
 
-        match self.options.same_site {
-            SameSite::Strict => parts.push("SameSite=Strict".to_string()),
-            SameSite::Lax => parts.push("SameSite=Lax".to_string()),
-            SameSite::None => parts.push("SameSite=None".to_string()),
-        }
+        parts.push(format!("SameSite={}", self.options.same_site.as_str()));
 
         if let Some(ref domain) = self.options.domain {
             parts.push(format!("Domain={}", domain));
@@ -180,7 +317,6 @@ impl Cookie {
         Self::new(name, "")
             .max_age(Duration::from_secs(0))
             .http_only(true)
-            .secure(true)
     }
 
     /// Create a permanent cookie (5 years)
@@ -296,6 +432,21 @@ mod tests {
         assert!(header.contains("Max-Age=3600"));
     }
 
+    #[test]
+    fn test_defaults_by_environment() {
+        let production = CookieConfig::for_environment(&Environment::Production);
+        assert!(production.secure);
+        assert_eq!(production.same_site, SameSite::Lax);
+
+        let local = CookieConfig::for_environment(&Environment::Local);
+        assert!(!local.secure);
+        assert_eq!(local.same_site, SameSite::Lax);
+        assert!(CookieConfig::for_environment(&Environment::Staging).secure);
+
+        assert_eq!(SameSite::parse("STRICT"), SameSite::Strict);
+        assert_eq!(SameSite::parse("bogus"), SameSite::Lax);
+    }
+
     #[test]
     fn test_parse_cookies() {
         let cookies = parse_cookies("session=abc123; user_id=42; empty=");
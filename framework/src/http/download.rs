@@ -0,0 +1,201 @@
+//! File downloads and byte ranges for `HttpResponse::download`
+
+use bytes::Bytes;
+use futures_util::future::ready;
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+/// A response body read from a file, kept so a `Range` request can be
+/// answered with part of it
+#[derive(Debug, Clone)]
+pub(crate) struct FileBody {
+    pub(crate) path: PathBuf,
+    pub(crate) len: u64,
+}
+
+/// The part of a file a `Range` header asks for
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ByteRange {
+    /// First and last byte, inclusive
+    Satisfiable(u64, u64),
+    /// Outside the file, answered with 416
+    Unsatisfiable,
+}
+
+/// Stream `len` bytes of `path` starting at `offset`, in chunks
+///
+/// A read error ends the body early; the status has already been sent.
+pub(crate) fn read(path: PathBuf, offset: u64, len: u64) -> impl Stream<Item = Bytes> + Send {
+    stream::once(async move {
+        let mut file = tokio::fs::File::open(&path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        Ok::<_, std::io::Error>(ReaderStream::new(file.take(len)))
+    })
+    .try_flatten()
+    .take_while(|chunk| ready(chunk.is_ok()))
+    .filter_map(|chunk| ready(chunk.ok()))
+}
+
+/// Parse a single `bytes=` range against a file of `len` bytes
+///
+/// Returns `None` for headers that should be ignored, such as other units
+/// or multiple ranges, in which case the whole file is sent.
+pub(crate) fn parse_range(header: &str, len: u64) -> Option<ByteRange> {
+    let spec = header.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            if suffix == 0 {
+                return Some(ByteRange::Unsatisfiable);
+            }
+            (len.saturating_sub(suffix), len.checked_sub(1))
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)),
+        (start, end) => {
+            let end: u64 = end.parse().ok()?;
+            (
+                start.parse().ok()?,
+                len.checked_sub(1).map(|last| end.min(last)),
+            )
+        }
+    };
+    match end {
+        Some(end) if start <= end => Some(ByteRange::Satisfiable(start, end)),
+        _ => Some(ByteRange::Unsatisfiable),
+    }
+}
+
+/// A `Content-Disposition` value; non-ASCII names use the RFC 6266
+/// `filename*` form with a plain fallback for old clients
+pub(crate) fn content_disposition(kind: &str, filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect();
+    if fallback == filename {
+        format!("{}; filename=\"{}\"", kind, filename)
+    } else {
+        format!(
+            "{}; filename=\"{}\"; filename*=UTF-8''{}",
+            kind,
+            fallback,
+            utf8_percent_encode(filename, NON_ALPHANUMERIC)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HttpResponse;
+    use http_body_util::BodyExt;
+
+    #[test]
+    fn parses_single_byte_ranges() {
+        assert_eq!(
+            parse_range("bytes=0-4", 10),
+            Some(ByteRange::Satisfiable(0, 4))
+        );
+        assert_eq!(
+            parse_range("bytes=6-", 10),
+            Some(ByteRange::Satisfiable(6, 9))
+        );
+        assert_eq!(
+            parse_range("bytes=-3", 10),
+            Some(ByteRange::Satisfiable(7, 9))
+        );
+        assert_eq!(
+            parse_range("bytes=5-100", 10),
+            Some(ByteRange::Satisfiable(5, 9))
+        );
+        assert_eq!(parse_range("bytes=10-", 10), Some(ByteRange::Unsatisfiable));
+        assert_eq!(parse_range("bytes=0-", 0), Some(ByteRange::Unsatisfiable));
+        assert_eq!(parse_range("bytes=0-1,4-5", 10), None);
+        assert_eq!(parse_range("items=0-1", 10), None);
+    }
+
+    #[test]
+    fn non_ascii_filenames_get_an_encoded_form() {
+        assert_eq!(
+            content_disposition("attachment", "report.csv"),
+            "attachment; filename=\"report.csv\""
+        );
+        assert_eq!(
+            content_disposition("attachment", "résumé.pdf"),
+            "attachment; filename=\"r_sum_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9%2Epdf"
+        );
+    }
+
+    fn fixture() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("kit-download-{}.csv", std::process::id()));
+        std::fs::write(&path, "id,name\n1,Ada\n").unwrap();
+        path
+    }
+
+    fn header(response: &hyper::Response<crate::http::ResponseBody>, name: &str) -> String {
+        response.headers()[name].to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn downloads_stream_the_file() {
+        let response = HttpResponse::download(fixture(), "export.csv").into_hyper();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(header(&response, "Content-Type"), "text/csv; charset=utf-8");
+        assert_eq!(
+            header(&response, "Content-Disposition"),
+            "attachment; filename=\"export.csv\""
+        );
+        assert_eq!(header(&response, "Content-Length"), "14");
+        assert_eq!(header(&response, "Accept-Ranges"), "bytes");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "id,name\n1,Ada\n");
+
+        let missing = HttpResponse::download("/nonexistent/export.csv", "export.csv");
+        assert_eq!(missing.into_hyper().status(), 404);
+    }
+
+    #[tokio::test]
+    async fn range_requests_get_part_of_the_file() {
+        let response = HttpResponse::inline_file(fixture())
+            .with_range(Some("bytes=8-"))
+            .into_hyper();
+
+        assert_eq!(response.status(), 206);
+        assert_eq!(header(&response, "Content-Range"), "bytes 8-13/14");
+        assert_eq!(header(&response, "Content-Length"), "6");
+        assert!(header(&response, "Content-Disposition").starts_with("inline;"));
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "1,Ada\n");
+
+        let response = HttpResponse::inline_file(fixture())
+            .with_range(Some("bytes=20-30"))
+            .into_hyper();
+        assert_eq!(response.status(), 416);
+        assert_eq!(header(&response, "Content-Range"), "bytes */14");
+    }
+
+    #[tokio::test]
+    async fn streams_are_sent_chunk_by_chunk() {
+        let rows = stream::iter(["id\n", "1\n", "2\n"].map(Bytes::from));
+        let response = HttpResponse::stream(rows)
+            .content_type("text/csv")
+            .into_hyper();
+
+        assert_eq!(response.headers().get_all("Content-Type").iter().count(), 1);
+        assert_eq!(header(&response, "Content-Type"), "text/csv");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "id\n1\n2\n");
+    }
+}
@@ -0,0 +1,200 @@
+//! JSON bodies for framework errors
+//!
+//! Every `FrameworkError` returned from a handler, including `#[domain_error]`
+//! types, `AppError` and validation errors, is rendered here. Bodies always
+//! carry the request id, which is also sent as `X-Request-Id`. With
+//! `APP_DEBUG=true` they gain a `debug` object with the error's source chain,
+//! the matched route and a backtrace; without it, server errors (5xx) only
+//! say `Server Error`, so internals never reach clients in production.
+//!
+//! ```json
+//! {
+//!     "error": "Database error: connection refused",
+//!     "request_id": "4f1c2a9be07d3358",
+//!     "debug": {
+//!         "source": ["Database error: connection refused"],
+//!         "route": "/users/{id}",
+//!         "backtrace": ["..."]
+//!     }
+//! }
+//! ```
+
+use crate::config::Config;
+use crate::error::FrameworkError;
+use rand::Rng;
+use serde_json::{json, Value};
+use std::backtrace::Backtrace;
+use std::future::Future;
+
+/// Message that replaces server error details outside of debug mode
+const SERVER_ERROR: &str = "Server Error";
+
+/// Longest client-supplied `X-Request-Id` that is reused
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static ERROR_CONTEXT: ErrorContext;
+}
+
+/// The request an error is raised in
+#[derive(Debug, Clone)]
+pub(crate) struct ErrorContext {
+    pub(crate) request_id: String,
+    /// Pattern of the matched route, `None` for the fallback
+    pub(crate) route: Option<String>,
+}
+
+impl ErrorContext {
+    /// Context for a request, reusing a well-formed `X-Request-Id` header
+    pub(crate) fn new(headers: &hyper::HeaderMap, route: Option<&str>) -> Self {
+        let request_id = headers
+            .get("X-Request-Id")
+            .and_then(|value| value.to_str().ok())
+            .filter(|id| is_valid_request_id(id))
+            .map(str::to_string)
+            .unwrap_or_else(generate_request_id);
+        Self {
+            request_id,
+            route: route.map(str::to_string),
+        }
+    }
+
+    /// Run `future` with this context for the errors it raises
+    pub(crate) async fn scope<F: Future>(self, future: F) -> F::Output {
+        ERROR_CONTEXT.scope(self, future).await
+    }
+
+    pub(crate) fn current() -> Option<Self> {
+        ERROR_CONTEXT.try_with(Self::clone).ok()
+    }
+}
+
+fn generate_request_id() -> String {
+    format!("{:016x}", rand::thread_rng().gen::<u64>())
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LENGTH
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// The response body for `err`, and the request id it mentions
+pub(crate) fn render(err: &FrameworkError) -> (Value, Option<String>) {
+    render_with(err, Config::is_debug())
+}
+
+fn render_with(err: &FrameworkError, debug: bool) -> (Value, Option<String>) {
+    let context = ErrorContext::current();
+    let mut body = base_body(err);
+
+    if !debug && err.status_code() >= 500 {
+        body = json!({ "error": SERVER_ERROR });
+    }
+    if let Some(context) = &context {
+        body["request_id"] = json!(context.request_id);
+    }
+    if debug {
+        body["debug"] = json!({
+            "source": source_chain(err),
+            "route": context.as_ref().and_then(|c| c.route.clone()),
+            "backtrace": Backtrace::force_capture()
+                .to_string()
+                .lines()
+                .map(str::trim)
+                .collect::<Vec<_>>(),
+        });
+    }
+
+    (body, context.map(|context| context.request_id))
+}
+
+/// The body every environment starts from
+fn base_body(err: &FrameworkError) -> Value {
+    match err {
+        FrameworkError::ParamError { param_name } => json!({
+            "error": format!("Missing required parameter: {}", param_name)
+        }),
+        FrameworkError::ValidationError { field, message } => json!({
+            "error": "Validation failed",
+            "field": field,
+            "message": message
+        }),
+        // Laravel/Inertia-compatible validation error format
+        FrameworkError::Validation(errors) => errors.to_json(),
+        FrameworkError::Unauthorized => json!({
+            "message": "This action is unauthorized."
+        }),
+        FrameworkError::MalformedJson {
+            message,
+            line,
+            column,
+        } => json!({
+            "error": "Malformed JSON body",
+            "message": message,
+            "line": line,
+            "column": column,
+        }),
+        _ => json!({ "error": err.to_string() }),
+    }
+}
+
+fn source_chain(err: &FrameworkError) -> Vec<String> {
+    let mut chain = vec![err.to_string()];
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        chain.push(err.to_string());
+        source = err.source();
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> ErrorContext {
+        ErrorContext {
+            request_id: "req-1".to_string(),
+            route: Some("/users/{id}".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debug_and_production_bodies() {
+        let err = FrameworkError::database("connection refused");
+
+        let (body, request_id) = context().scope(async { render_with(&err, true) }).await;
+        assert_eq!(request_id.as_deref(), Some("req-1"));
+        assert_eq!(body["error"], "Database error: connection refused");
+        assert_eq!(body["debug"]["route"], "/users/{id}");
+        assert!(body["debug"]["backtrace"].is_array());
+
+        let (body, _) = context().scope(async { render_with(&err, false) }).await;
+        assert_eq!(
+            body,
+            json!({ "error": SERVER_ERROR, "request_id": "req-1" })
+        );
+
+        // Client errors keep their message and shape
+        let (body, _) = context()
+            .scope(async { render_with(&FrameworkError::domain("User not found", 404), false) })
+            .await;
+        assert_eq!(
+            body,
+            json!({ "error": "User not found", "request_id": "req-1" })
+        );
+    }
+
+    #[test]
+    fn test_request_id_header() {
+        let mut headers = hyper::HeaderMap::new();
+        headers.insert("X-Request-Id", "abc-123".parse().unwrap());
+        assert_eq!(ErrorContext::new(&headers, None).request_id, "abc-123");
+
+        headers.insert("X-Request-Id", "<script>".parse().unwrap());
+        assert_eq!(ErrorContext::new(&headers, None).request_id.len(), 16);
+    }
+}
@@ -0,0 +1,126 @@
+//! Per-group rendering of framework errors
+//!
+//! Errors returned with `?` become JSON responses by default. A route group
+//! can choose another format with `.error_format()`, so an `/api` group keeps
+//! JSON errors while web routes render an Inertia error page.
+
+use super::{HttpResponse, Response};
+use crate::inertia::{InertiaContext, InertiaResponse};
+use crate::middleware::{Middleware, Next};
+use crate::Request;
+use async_trait::async_trait;
+
+/// How errors raised by a group's routes are rendered
+///
+/// # Example
+///
+/// ```rust,ignore
+/// routes! {
+///     group!("/api", {
+///         get!("/users", controllers::api::users::index),
+///     }).error_format(ErrorFormat::Json),
+///
+///     group!("/", {
+///         get!("/dashboard", controllers::dashboard::index),
+///     }).error_format(ErrorFormat::Inertia),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// JSON body such as `{"error": "..."}` (the default)
+    #[default]
+    Json,
+    /// The `Error` Inertia page, with `status` and `message` props
+    ///
+    /// Expects a `frontend/src/pages/Error.tsx` component.
+    Inertia,
+}
+
+impl ErrorFormat {
+    /// Render a response built from a `FrameworkError` in this format
+    ///
+    /// Other responses are returned unchanged. The error is marked as
+    /// rendered, so the innermost group's format wins over its parents'.
+    pub fn render(self, mut response: HttpResponse) -> HttpResponse {
+        let Some(message) = response.take_error() else {
+            return response;
+        };
+
+        match self {
+            ErrorFormat::Json => response,
+            ErrorFormat::Inertia => {
+                let status = response.status_code();
+                let page = InertiaResponse::new(
+                    "Error",
+                    serde_json::json!({ "status": status, "message": message }),
+                    InertiaContext::current_path(),
+                );
+                let rendered = if InertiaContext::is_inertia_request() {
+                    page.to_json_response()
+                } else {
+                    page.to_html_response()
+                };
+                rendered.status(status)
+            }
+        }
+    }
+}
+
+/// Middleware installed by `GroupDef::error_format()`
+pub(crate) struct ErrorFormatMiddleware(pub(crate) ErrorFormat);
+
+#[async_trait]
+impl Middleware for ErrorFormatMiddleware {
+    async fn handle(&self, request: Request, next: Next) -> Response {
+        match next(request).await {
+            Ok(response) => Ok(self.0.render(response)),
+            Err(response) => Err(self.0.render(response)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FrameworkError;
+
+    fn not_found() -> HttpResponse {
+        FrameworkError::domain("Not found", 404).into()
+    }
+
+    fn is_inertia_page(response: HttpResponse) -> bool {
+        response.into_hyper().headers().contains_key("Vary")
+    }
+
+    #[test]
+    fn test_json_format_keeps_the_error_body() {
+        let rendered = ErrorFormat::Json.render(not_found());
+
+        assert_eq!(rendered.status_code(), 404);
+        assert!(!is_inertia_page(rendered));
+    }
+
+    #[test]
+    fn test_inertia_format_renders_error_page() {
+        let rendered = ErrorFormat::Inertia.render(not_found());
+
+        assert_eq!(rendered.status_code(), 404);
+        assert!(is_inertia_page(rendered));
+    }
+
+    #[test]
+    fn test_innermost_format_wins() {
+        let rendered = ErrorFormat::Json.render(not_found());
+        let rendered = ErrorFormat::Inertia.render(rendered);
+
+        assert!(!is_inertia_page(rendered));
+    }
+
+    #[test]
+    fn test_plain_responses_are_unchanged() {
+        let rendered = ErrorFormat::Inertia.render(HttpResponse::text("Forbidden").status(403));
+
+        assert_eq!(rendered.status_code(), 403);
+        assert!(!is_inertia_page(rendered));
+    }
+}
@@ -0,0 +1,211 @@
+//! Error pages for browser requests
+//!
+//! Errors are JSON by default, which a browser shows as raw text. Requests
+//! that don't ask for JSON (see `Request::wants_json`) get a page instead:
+//! the Inertia component `errors/{status}` when
+//! `frontend/src/pages/errors/{status}.tsx` exists, otherwise a minimal HTML
+//! page. Pages get `status` and `message` props:
+//!
+//! ```tsx
+//! // frontend/src/pages/errors/404.tsx
+//! export default function NotFound({ status, message }: { status: number; message: string }) {
+//!     return <h1>{status}: {message}</h1>
+//! }
+//! ```
+//!
+//! Set `SERVER_ERROR_PAGES` to another directory, or to an empty string to
+//! keep JSON errors for every request. Groups with an explicit
+//! `.error_format()` keep their format, and validation errors (422) always
+//! stay JSON for the form that sent them.
+
+use std::path::Path;
+
+use super::HttpResponse;
+use crate::config::{Config, ServerConfig};
+use crate::inertia::{InertiaContext, InertiaResponse};
+
+/// Render a response built from a `FrameworkError` as an error page
+///
+/// Other responses, and requests that want JSON, are returned unchanged.
+pub(crate) fn render(response: HttpResponse) -> HttpResponse {
+    let dir = Config::get::<ServerConfig>()
+        .unwrap_or_default()
+        .error_pages;
+    render_with(response, &dir, Config::is_debug())
+}
+
+/// The response for a path no route matches
+pub(crate) fn not_found() -> HttpResponse {
+    let dir = Config::get::<ServerConfig>()
+        .unwrap_or_default()
+        .error_pages;
+    if !wants_page(&dir) {
+        return HttpResponse::text("404 Not Found").status(404);
+    }
+    page(&dir, 404, "Not Found")
+}
+
+fn render_with(mut response: HttpResponse, dir: &str, debug: bool) -> HttpResponse {
+    let status = response.status_code();
+    if !wants_page(dir) || status == 422 || response.error().is_none() {
+        return response;
+    }
+    let message = response.take_error().unwrap_or_default();
+    // Like JSON bodies, server errors only show their details when debugging
+    let message = if status >= 500 && !debug {
+        reason(status).to_string()
+    } else {
+        message
+    };
+
+    let mut rendered = page(dir, status, &message);
+    for (name, value) in response.headers() {
+        if name.eq_ignore_ascii_case("X-Request-Id") {
+            rendered = rendered.header(name.clone(), value.clone());
+        }
+    }
+    rendered
+}
+
+fn wants_page(dir: &str) -> bool {
+    !dir.is_empty() && !InertiaContext::wants_json_request()
+}
+
+/// The Inertia page for `status` if the app has one, otherwise plain HTML
+fn page(dir: &str, status: u16, message: &str) -> HttpResponse {
+    if Path::new(dir).join(format!("{}.tsx", status)).is_file() {
+        let page = InertiaResponse::new(
+            format!("errors/{}", status),
+            serde_json::json!({ "status": status, "message": message }),
+            InertiaContext::current_path(),
+        );
+        let response = if InertiaContext::is_inertia_request() {
+            page.to_json_response()
+        } else {
+            page.to_html_response()
+        };
+        return response.status(status);
+    }
+
+    let title = format!("{} {}", status, reason(status));
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{title}</title>
+    <style>
+        body {{ font-family: system-ui, sans-serif; display: flex; align-items: center; justify-content: center; min-height: 100vh; margin: 0; color: #374151; }}
+        h1 {{ font-size: 1.25rem; font-weight: 600; margin: 0 0 0.5rem; }}
+        p {{ margin: 0; color: #6b7280; }}
+    </style>
+</head>
+<body>
+    <main>
+        <h1>{title}</h1>
+        <p>{message}</p>
+    </main>
+</body>
+</html>"#,
+        title = escape(&title),
+        message = escape(message),
+    );
+    HttpResponse::from_bytes("text/html; charset=utf-8", html).status(status)
+}
+
+fn reason(status: u16) -> &'static str {
+    hyper::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or("Error")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FrameworkError;
+    use crate::testing::TestResponse;
+
+    fn context(is_inertia: bool, wants_json: bool) {
+        InertiaContext::set(InertiaContext {
+            path: "/posts/9".to_string(),
+            is_inertia,
+            wants_json,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn browser_requests_get_error_pages() {
+        let dir = std::env::temp_dir().join(format!("kit-error-pages-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("404.tsx"), "").unwrap();
+        let dir = dir.to_str().unwrap();
+
+        // Inertia visit with a page for the status
+        context(true, false);
+        let response = TestResponse::from(Ok(render_with(
+            FrameworkError::model_not_found("Post").into(),
+            dir,
+            false,
+        )));
+        response
+            .assert_status(404)
+            .assert_header("X-Inertia", "true");
+        let page: serde_json::Value = response.json();
+        assert_eq!(page["component"], "errors/404");
+        assert_eq!(page["props"]["message"], "Post not found");
+
+        // Plain browser request without a page hides server error details
+        context(false, false);
+        let response = TestResponse::from(Ok(render_with(
+            FrameworkError::internal("connection refused <db>").into(),
+            dir,
+            false,
+        )));
+        response
+            .assert_status(500)
+            .assert_header("Content-Type", "text/html; charset=utf-8");
+        assert!(response
+            .text()
+            .contains("<h1>500 Internal Server Error</h1>"));
+        assert!(!response.text().contains("connection refused"));
+
+        // API clients and validation errors keep JSON
+        context(false, true);
+        let response = TestResponse::from(Ok(render_with(
+            FrameworkError::model_not_found("Post").into(),
+            dir,
+            false,
+        )));
+        response.assert_header("Content-Type", "application/json");
+        context(false, false);
+        let response = TestResponse::from(Ok(render_with(
+            FrameworkError::validation("title", "is required").into(),
+            dir,
+            false,
+        )));
+        response
+            .assert_status(422)
+            .assert_header("Content-Type", "application/json");
+
+        InertiaContext::clear();
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
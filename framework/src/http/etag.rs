@@ -0,0 +1,170 @@
+//! Conditional GET with weak ETags
+//!
+//! Index routes can skip fetching and serializing a collection that hasn't
+//! changed since the client last saw it. `QueryBuilder::etag` derives a tag
+//! from the query itself plus the row count and latest `updated_at`, which is
+//! one cheap aggregate query; `ETag::respond` answers `304 Not Modified` when
+//! the request's `If-None-Match` matches and only renders the body otherwise.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[handler]
+//! pub async fn index(req: Request) -> Response {
+//!     let query = Todo::query().filter(Column::UserId.eq(user_id));
+//!     let etag = query.etag(Column::UpdatedAt).await?;
+//!
+//!     etag.respond(&req, || async {
+//!         let todos = query.all().await?;
+//!         json_response!({ "data": todos })
+//!     })
+//!     .await
+//! }
+//! ```
+
+use super::{HttpResponse, Request, Response};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::future::Future;
+
+/// A weak entity tag, `W/"..."`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ETag(String);
+
+impl ETag {
+    /// A weak tag hashed from `parts`
+    ///
+    /// Parts are separated before hashing, so `["ab", "c"]` and `["a", "bc"]`
+    /// give different tags.
+    pub fn from_parts<I, P>(parts: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            let part = part.as_ref();
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        let digest = hex::encode(hasher.finalize());
+        Self(format!("W/\"{}\"", &digest[..32]))
+    }
+
+    /// The tag as sent in the `ETag` header
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the request's `If-None-Match` header matches this tag
+    ///
+    /// Uses weak comparison, so `"abc"` matches `W/"abc"`.
+    pub fn matches(&self, req: &Request) -> bool {
+        req.header("If-None-Match")
+            .is_some_and(|header| self.matches_header(header))
+    }
+
+    fn matches_header(&self, header: &str) -> bool {
+        let ours = opaque(&self.0);
+        header
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || opaque(tag) == ours)
+    }
+
+    /// A `304 Not Modified` response carrying this tag
+    pub fn not_modified(&self) -> HttpResponse {
+        HttpResponse::new()
+            .status(304)
+            .header("ETag", self.as_str())
+    }
+
+    /// Answer `304` if the request matches, otherwise render the response
+    ///
+    /// `render` only runs when the client's copy is stale; successful
+    /// responses get the `ETag` header so the next request can match.
+    pub async fn respond<F, Fut>(&self, req: &Request, render: F) -> Response
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Response>,
+    {
+        if self.matches(req) {
+            return Ok(self.not_modified());
+        }
+        render()
+            .await
+            .map(|response| response.header("ETag", self.as_str()))
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The quoted part of a tag, without the weak prefix
+fn opaque(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(if_none_match: Option<&str>) -> Request {
+        let mut fake = Request::fake().path("/todos");
+        if let Some(value) = if_none_match {
+            fake = fake.header("If-None-Match", value);
+        }
+        fake.build()
+    }
+
+    #[test]
+    fn tags_are_weak_and_depend_on_every_part() {
+        let tag = ETag::from_parts(["select", "3", "2026-01-01"]);
+        assert!(tag.as_str().starts_with("W/\""));
+        assert_eq!(tag, ETag::from_parts(["select", "3", "2026-01-01"]));
+        assert_ne!(tag, ETag::from_parts(["select", "4", "2026-01-01"]));
+        assert_ne!(ETag::from_parts(["ab", "c"]), ETag::from_parts(["a", "bc"]));
+    }
+
+    #[test]
+    fn matches_if_none_match_with_weak_comparison() {
+        let tag = ETag::from_parts(["todos"]);
+        let strong = tag.as_str().trim_start_matches("W/").to_string();
+
+        assert!(tag.matches(&request(Some(tag.as_str()))));
+        assert!(tag.matches(&request(Some(&strong))));
+        assert!(tag.matches(&request(Some(&format!("\"other\", {}", tag)))));
+        assert!(tag.matches(&request(Some("*"))));
+        assert!(!tag.matches(&request(Some("W/\"other\""))));
+        assert!(!tag.matches(&request(None)));
+    }
+
+    #[tokio::test]
+    async fn respond_skips_rendering_when_fresh() {
+        let tag = ETag::from_parts(["todos"]);
+
+        let Ok(response) = tag
+            .respond(&request(Some(tag.as_str())), || async {
+                panic!("rendered a fresh response")
+            })
+            .await
+        else {
+            panic!("expected a 304");
+        };
+        assert_eq!(response.status_code(), 304);
+
+        let Ok(response) = tag
+            .respond(&request(None), || async { Ok(HttpResponse::text("[]")) })
+            .await
+        else {
+            panic!("expected a response");
+        };
+        assert_eq!(response.status_code(), 200);
+        assert!(response
+            .headers()
+            .contains(&("ETag".to_string(), tag.to_string())));
+    }
+}
@@ -0,0 +1,150 @@
+//! Application-wide handling of framework errors
+//!
+//! Bind an `ExceptionHandler` to see every `FrameworkError` before it becomes
+//! a response: report it to an error tracker, and optionally render it
+//! yourself. Without one, errors are rendered by the default JSON body.
+
+use super::error_body::ErrorContext;
+use super::HttpResponse;
+use crate::container::App;
+use crate::error::FrameworkError;
+
+/// Kinds of errors that are not reported by default
+///
+/// These are caused by the client rather than the application, see
+/// `FrameworkError::kind()`.
+pub const DONT_REPORT: &[&str] = &[
+    "ParamError",
+    "ValidationError",
+    "Validation",
+    "Unauthorized",
+    "ModelNotFound",
+    "ParamParse",
+    "MalformedJson",
+];
+
+/// Receives every `FrameworkError` before it is rendered
+///
+/// `report()` is called for each error whose kind isn't in `dont_report()`,
+/// then `render()` may replace the default response.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{bind, ExceptionHandler, FrameworkError, HttpResponse};
+///
+/// struct Handler;
+///
+/// impl ExceptionHandler for Handler {
+///     fn report(&self, err: &FrameworkError) {
+///         sentry::capture_error(err);
+///     }
+///
+///     fn render(&self, err: &FrameworkError) -> Option<HttpResponse> {
+///         match err {
+///             FrameworkError::ModelNotFound { .. } => {
+///                 Some(HttpResponse::text("Nothing here").status(404))
+///             }
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// bind!(dyn ExceptionHandler, Handler);
+/// ```
+pub trait ExceptionHandler: Send + Sync {
+    /// Send `err` to a log or error tracker
+    fn report(&self, _err: &FrameworkError) {}
+
+    /// The response for `err`, or `None` for the default one
+    fn render(&self, _err: &FrameworkError) -> Option<HttpResponse> {
+        None
+    }
+
+    /// Kinds of errors `report()` is not called for
+    fn dont_report(&self) -> &[&'static str] {
+        DONT_REPORT
+    }
+
+    /// Whether `report()` is called for `err`
+    fn should_report(&self, err: &FrameworkError) -> bool {
+        !self.dont_report().contains(&err.kind())
+    }
+}
+
+/// Report `err` to the bound handler and return its response, if any
+pub(crate) fn handle(err: &FrameworkError) -> Option<HttpResponse> {
+    let handler = App::make::<dyn ExceptionHandler>()?;
+    if handler.should_report(err) {
+        handler.report(err);
+    }
+    let mut response = handler.render(err)?;
+    let has_request_id = response
+        .headers()
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("X-Request-Id"));
+    if let (false, Some(context)) = (has_request_id, ErrorContext::current()) {
+        response = response.header("X-Request-Id", context.request_id);
+    }
+    Some(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TestContainer, TestResponse};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder {
+        reported: Mutex<Vec<String>>,
+    }
+
+    impl ExceptionHandler for Arc<Recorder> {
+        fn report(&self, err: &FrameworkError) {
+            self.reported.lock().unwrap().push(err.to_string());
+        }
+
+        fn render(&self, err: &FrameworkError) -> Option<HttpResponse> {
+            match err {
+                FrameworkError::ModelNotFound { model_name } => {
+                    Some(HttpResponse::text(format!("No such {}", model_name)).status(404))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn handler_reports_and_renders_errors() {
+        let _container = TestContainer::fake();
+        let recorder = Arc::new(Recorder::default());
+        TestContainer::bind::<dyn ExceptionHandler>(Arc::new(recorder.clone()));
+
+        let context = ErrorContext {
+            request_id: "abc123".to_string(),
+            route: None,
+        };
+        let (custom, default) = context
+            .scope(async {
+                (
+                    HttpResponse::from(FrameworkError::model_not_found("Post")),
+                    HttpResponse::from(FrameworkError::internal("boom")),
+                )
+            })
+            .await;
+
+        let custom = TestResponse::from(Err(custom));
+        custom
+            .assert_status(404)
+            .assert_header("X-Request-Id", "abc123");
+        assert_eq!(custom.text(), "No such Post");
+        TestResponse::from(Err(default)).assert_status(500);
+
+        // Not-found is in DONT_REPORT
+        assert_eq!(
+            *recorder.reported.lock().unwrap(),
+            vec!["Internal server error: boom".to_string()]
+        );
+    }
+}
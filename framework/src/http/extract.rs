@@ -3,9 +3,10 @@
 //! This module provides the `FromRequest` trait which enables the `#[handler]`
 //! macro to automatically extract typed parameters from incoming requests.
 
-use super::Request;
+use super::{Json, Request};
 use crate::error::FrameworkError;
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 
 /// Trait for types that can be extracted from an HTTP request
 ///
@@ -16,6 +17,7 @@ use async_trait::async_trait;
 ///
 /// - `Request` - passes the request through unchanged
 /// - Any type implementing `FormRequest` - automatically parses and validates
+/// - `Json<T>` - deserializes a JSON body, without validation
 ///
 /// # Example
 ///
@@ -53,6 +55,38 @@ impl FromRequest for Request {
     }
 }
 
+/// The JSON body, deserialized without validation rules
+///
+/// For webhooks and internal APIs where a `FormRequest` is more than needed.
+/// A body that isn't valid JSON for `T` is a 400 with the parse error's
+/// position; the `Content-Type` isn't checked.
+///
+/// ```rust,ignore
+/// #[handler]
+/// pub async fn stripe(Json(event): Json<StripeEvent>) -> Response {
+///     // ...
+/// }
+/// ```
+#[async_trait]
+impl<T: DeserializeOwned + Send> FromRequest for Json<T> {
+    async fn from_request(req: Request) -> Result<Self, FrameworkError> {
+        let (_, bytes) = req.body_bytes().await?;
+        serde_json::from_slice(&bytes)
+            .map(Json)
+            .map_err(|e| FrameworkError::malformed_json(&e))
+    }
+}
+
+/// Types extracted from a borrowed request
+///
+/// Unlike `FromRequest`, which consumes the request, these only read it
+/// (the URL, headers), so `#[handler]` can still pass the request to
+/// another parameter. `Query<T>` is the built-in implementation.
+pub trait FromRequestRef: Sized {
+    /// Extract Self from the incoming request
+    fn from_request_ref(req: &Request) -> Result<Self, FrameworkError>;
+}
+
 /// Trait for types that can be extracted from a single path parameter
 ///
 /// This trait enables automatic extraction of typed values from route parameters
@@ -84,42 +118,255 @@ impl FromParam for String {
     }
 }
 
-impl FromParam for i32 {
-    fn from_param(value: &str) -> Result<Self, FrameworkError> {
-        value
-            .parse()
-            .map_err(|_| FrameworkError::param_parse(value, "i32"))
-    }
+/// `FromParam` through `FromStr`, naming the type in the 400 error
+macro_rules! from_param_via_parse {
+    ($($ty:ty => $name:literal),* $(,)?) => {
+        $(
+            impl FromParam for $ty {
+                fn from_param(value: &str) -> Result<Self, FrameworkError> {
+                    value
+                        .parse()
+                        .map_err(|_| FrameworkError::param_parse(value, $name))
+                }
+            }
+        )*
+    };
 }
 
-impl FromParam for i64 {
-    fn from_param(value: &str) -> Result<Self, FrameworkError> {
-        value
-            .parse()
-            .map_err(|_| FrameworkError::param_parse(value, "i64"))
-    }
+from_param_via_parse! {
+    i8 => "i8",
+    i16 => "i16",
+    i32 => "i32",
+    i64 => "i64",
+    i128 => "i128",
+    isize => "isize",
+    u8 => "u8",
+    u16 => "u16",
+    u32 => "u32",
+    u64 => "u64",
+    u128 => "u128",
+    usize => "usize",
+    bool => "bool",
+    sea_orm::prelude::Uuid => "uuid",
 }
 
-impl FromParam for u32 {
-    fn from_param(value: &str) -> Result<Self, FrameworkError> {
-        value
-            .parse()
-            .map_err(|_| FrameworkError::param_parse(value, "u32"))
+/// Parameter resolution for `#[handler]`
+///
+/// From a type name alone the macro can't tell whether `User` is a model or
+/// a form request, so it calls `__kit_resolve` on `&&&Resolve<T>` and lets
+/// method resolution pick the most specific impl: a custom `RouteBinding`,
+/// then `AutoRouteBinding`, then `FromRequestRef`, then `FromRequest`.
+#[doc(hidden)]
+pub mod __resolve {
+    use super::{FromRequest, FromRequestRef, Request};
+    use crate::database::{AutoRouteBinding, RouteBinding};
+    use crate::error::FrameworkError;
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::marker::PhantomData;
+    use std::pin::Pin;
+
+    type Resolving<'a, T> = Pin<Box<dyn Future<Output = Result<T, FrameworkError>> + Send + 'a>>;
+
+    pub struct Resolve<T>(PhantomData<T>);
+
+    impl<T> Resolve<T> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
     }
-}
 
-impl FromParam for u64 {
-    fn from_param(value: &str) -> Result<Self, FrameworkError> {
-        value
-            .parse()
-            .map_err(|_| FrameworkError::param_parse(value, "u64"))
+    /// What a handler parameter can be resolved from
+    pub struct Source<'a> {
+        /// The parameter's name in the handler signature
+        pub name: &'static str,
+        pub params: &'a HashMap<String, String>,
+        pub binding_keys: &'a HashMap<String, String>,
+        /// The request, until a parameter consumes it
+        pub request: &'a mut Option<Request>,
+    }
+
+    impl<'a> Source<'a> {
+        /// The first of `names` present in the route, with its binding column
+        ///
+        /// Falls back to `{id}`, so `show(user: User)` also works on
+        /// `/users/{id}`.
+        fn route_value(
+            &self,
+            names: &[&str],
+        ) -> Result<(&'a str, Option<&'a str>), FrameworkError> {
+            let (params, binding_keys) = (self.params, self.binding_keys);
+            names
+                .iter()
+                .chain(&["id"])
+                .find_map(|name| {
+                    let value = params.get(*name)?;
+                    Some((value.as_str(), binding_keys.get(*name).map(String::as_str)))
+                })
+                .ok_or_else(|| FrameworkError::param(self.name))
+        }
+    }
+
+    pub trait ViaRouteBinding {
+        type Output;
+        fn __kit_resolve<'a>(&self, source: Source<'a>) -> Resolving<'a, Self::Output>;
+    }
+
+    impl<T: RouteBinding + 'static> ViaRouteBinding for &&&Resolve<T> {
+        type Output = T;
+        fn __kit_resolve<'a>(&self, source: Source<'a>) -> Resolving<'a, T> {
+            let value = source.route_value(&[T::param_name(), source.name]);
+            Box::pin(async move { T::from_route_param(value?.0).await })
+        }
+    }
+
+    pub trait ViaAutoRouteBinding {
+        type Output;
+        fn __kit_resolve<'a>(&self, source: Source<'a>) -> Resolving<'a, Self::Output>;
+    }
+
+    impl<T: AutoRouteBinding + 'static> ViaAutoRouteBinding for &&Resolve<T> {
+        type Output = T;
+        fn __kit_resolve<'a>(&self, source: Source<'a>) -> Resolving<'a, T> {
+            let value = source.route_value(&[source.name]);
+            Box::pin(async move {
+                match value? {
+                    (value, Some(column)) => T::from_route_key(column, value).await,
+                    (value, None) => T::from_route_param(value).await,
+                }
+            })
+        }
+    }
+
+    pub trait ViaFromRequestRef {
+        type Output;
+        fn __kit_resolve<'a>(&self, source: Source<'a>) -> Resolving<'a, Self::Output>;
+    }
+
+    impl<T: FromRequestRef + Send + 'static> ViaFromRequestRef for &Resolve<T> {
+        type Output = T;
+        fn __kit_resolve<'a>(&self, source: Source<'a>) -> Resolving<'a, T> {
+            let value = match source.request.as_ref() {
+                Some(request) => T::from_request_ref(request),
+                None => Err(FrameworkError::internal(format!(
+                    "Handler parameter `{}` reads the request, but an earlier parameter already consumed it; declare `{}` first",
+                    source.name, source.name
+                ))),
+            };
+            Box::pin(async move { value })
+        }
+    }
+
+    pub trait ViaFromRequest {
+        type Output;
+        fn __kit_resolve<'a>(&self, source: Source<'a>) -> Resolving<'a, Self::Output>;
+    }
+
+    impl<T: FromRequest + 'static> ViaFromRequest for Resolve<T> {
+        type Output = T;
+        fn __kit_resolve<'a>(&self, source: Source<'a>) -> Resolving<'a, T> {
+            let request = take_request(source.request, source.name);
+            Box::pin(async move { T::from_request(request?).await })
+        }
+    }
+
+    /// Hand the request to the parameter that consumes it
+    pub fn take_request(
+        request: &mut Option<Request>,
+        name: &str,
+    ) -> Result<Request, FrameworkError> {
+        request.take().ok_or_else(|| {
+            FrameworkError::internal(format!(
+                "Handler parameter `{}` needs the request, but an earlier parameter already consumed it",
+                name
+            ))
+        })
     }
 }
 
-impl FromParam for usize {
-    fn from_param(value: &str) -> Result<Self, FrameworkError> {
-        value
-            .parse()
-            .map_err(|_| FrameworkError::param_parse(value, "usize"))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestResponse;
+    use crate::Response;
+
+    #[derive(Debug, PartialEq)]
+    enum Status {
+        Open,
+        Closed,
+    }
+
+    impl FromParam for Status {
+        fn from_param(value: &str) -> Result<Self, FrameworkError> {
+            match value {
+                "open" => Ok(Status::Open),
+                "closed" => Ok(Status::Closed),
+                _ => Err(FrameworkError::param_parse(value, "Status")),
+            }
+        }
+    }
+
+    #[crate::handler]
+    async fn show(id: i64, req: Request, #[param] status: Status, active: bool) -> Response {
+        assert_eq!(req.path(), "/orders");
+        crate::text(format!("{} {:?} {}", id, status, active))
+    }
+
+    fn request(id: &str, status: &str) -> Request {
+        Request::fake()
+            .path("/orders")
+            .param("id", id)
+            .param("status", status)
+            .param("active", "true")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn handler_extracts_typed_path_params() {
+        let response = TestResponse::from(show(request("42", "closed")).await);
+        response.assert_status(200);
+        assert_eq!(response.text(), "42 Closed true");
+        assert_eq!(Status::from_param("open").unwrap(), Status::Open);
+    }
+
+    #[tokio::test]
+    async fn unparseable_params_are_bad_requests() {
+        TestResponse::from(show(request("forty-two", "open")).await).assert_status(400);
+        TestResponse::from(show(request("42", "archived")).await).assert_status(400);
+        assert!(i8::from_param("300").is_err());
+        assert!(sea_orm::prelude::Uuid::from_param("67e55044-10b1-426f-9247-bb680e5fe0c8").is_ok());
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Event {
+        kind: String,
+        attempts: u32,
+    }
+
+    #[crate::handler]
+    async fn webhook(Json(event): Json<Event>) -> Response {
+        crate::text(format!("{} {}", event.kind, event.attempts))
+    }
+
+    #[tokio::test]
+    async fn json_bodies_are_deserialized_without_validation() {
+        let req = Request::fake()
+            .json(serde_json::json!({ "kind": "invoice.paid", "attempts": 2 }))
+            .build();
+        let response = TestResponse::from(webhook(req).await);
+        response.assert_status(200);
+        assert_eq!(response.text(), "invoice.paid 2");
+
+        let req = Request::fake()
+            .body("{\"kind\": \"invoice.paid\",\n  \"attempts\": -1}")
+            .build();
+        let response = TestResponse::from(webhook(req).await);
+        response.assert_status(400);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"], "Malformed JSON body");
+        assert_eq!(body["line"], 2);
+        assert!(body["column"].as_u64().unwrap() > 0);
+        assert!(body["message"].as_str().unwrap().contains("invalid value"));
     }
 }
@@ -0,0 +1,446 @@
+//! RSS and Atom feeds
+//!
+//! Build a `Feed` and send it with `HttpResponse::atom` or
+//! `HttpResponse::rss`; text is escaped, dates are formatted for the format,
+//! and the response gets `Cache-Control` and `Last-Modified` headers.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{handler, Feed, FeedEntry, HttpResponse, Response};
+//!
+//! #[handler]
+//! pub async fn feed() -> Response {
+//!     let posts = Post::latest(20).await?;
+//!     let feed = Feed::new("Kit Blog", "https://example.com/blog")
+//!         .feed_url("https://example.com/blog/feed.atom")
+//!         .description("News from the Kit team")
+//!         .entries(posts.iter().map(|post| {
+//!             FeedEntry::new(&post.title, format!("https://example.com/blog/{}", post.slug))
+//!                 .summary(&post.excerpt)
+//!                 .published(post.published_at)
+//!                 .updated(post.updated_at)
+//!         }));
+//!
+//!     Ok(HttpResponse::atom(&feed))
+//! }
+//! ```
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::time::Duration;
+
+use super::HttpResponse;
+
+/// Format of the `Last-Modified` header
+const HTTP_DATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// A feed of entries, rendered as Atom or RSS 2.0
+#[derive(Debug, Clone)]
+pub struct Feed {
+    title: String,
+    link: String,
+    feed_url: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    language: Option<String>,
+    updated: Option<DateTime<Utc>>,
+    ttl: Duration,
+    entries: Vec<FeedEntry>,
+}
+
+impl Feed {
+    /// A feed for the site page at `link`, cached for an hour
+    pub fn new(title: impl Into<String>, link: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            link: link.into(),
+            feed_url: None,
+            description: None,
+            author: None,
+            language: None,
+            updated: None,
+            ttl: Duration::from_secs(3600),
+            entries: Vec::new(),
+        }
+    }
+
+    /// URL the feed itself is served from, used as the Atom id
+    pub fn feed_url(mut self, url: impl Into<String>) -> Self {
+        self.feed_url = Some(url.into());
+        self
+    }
+
+    /// Short description, the Atom subtitle
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Author of entries without their own
+    pub fn author(mut self, name: impl Into<String>) -> Self {
+        self.author = Some(name.into());
+        self
+    }
+
+    /// Language of the feed, e.g. "en-us"
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// When the feed last changed; defaults to the newest entry
+    pub fn updated(mut self, updated: DateTime<Utc>) -> Self {
+        self.updated = Some(updated);
+        self
+    }
+
+    /// How long clients and caches may keep the feed
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Add an entry
+    pub fn entry(mut self, entry: FeedEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Add entries
+    pub fn entries(mut self, entries: impl IntoIterator<Item = FeedEntry>) -> Self {
+        self.entries.extend(entries);
+        self
+    }
+
+    /// When the feed last changed, if known
+    pub fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.updated.or_else(|| {
+            self.entries
+                .iter()
+                .filter_map(FeedEntry::last_modified)
+                .max()
+        })
+    }
+
+    /// The feed as an Atom document
+    pub fn to_atom(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\"",
+        );
+        if let Some(language) = &self.language {
+            xml.push_str(&format!(" xml:lang=\"{}\"", escape(language)));
+        }
+        xml.push_str(">\n");
+        element(&mut xml, 1, "title", &self.title);
+        element(
+            &mut xml,
+            1,
+            "id",
+            self.feed_url.as_deref().unwrap_or(&self.link),
+        );
+        xml.push_str(&format!(
+            "  <link rel=\"alternate\" href=\"{}\"/>\n",
+            escape(&self.link)
+        ));
+        if let Some(url) = &self.feed_url {
+            xml.push_str(&format!(
+                "  <link rel=\"self\" href=\"{}\"/>\n",
+                escape(url)
+            ));
+        }
+        if let Some(description) = &self.description {
+            element(&mut xml, 1, "subtitle", description);
+        }
+        // Atom requires <updated>; an empty feed without one has changed now
+        let updated = self.last_modified().unwrap_or_else(Utc::now);
+        element(&mut xml, 1, "updated", &atom_date(updated));
+        if let Some(author) = &self.author {
+            author_element(&mut xml, 1, author);
+        }
+
+        for entry in &self.entries {
+            xml.push_str("  <entry>\n");
+            element(&mut xml, 2, "title", &entry.title);
+            element(
+                &mut xml,
+                2,
+                "id",
+                entry.id.as_deref().unwrap_or(&entry.link),
+            );
+            xml.push_str(&format!(
+                "    <link rel=\"alternate\" href=\"{}\"/>\n",
+                escape(&entry.link)
+            ));
+            let updated = entry.last_modified().unwrap_or(updated);
+            element(&mut xml, 2, "updated", &atom_date(updated));
+            if let Some(published) = entry.published {
+                element(&mut xml, 2, "published", &atom_date(published));
+            }
+            if let Some(author) = &entry.author {
+                author_element(&mut xml, 2, author);
+            }
+            if let Some(summary) = &entry.summary {
+                element(&mut xml, 2, "summary", summary);
+            }
+            if let Some(content) = &entry.content {
+                xml.push_str(&format!(
+                    "    <content type=\"html\">{}</content>\n",
+                    escape(content)
+                ));
+            }
+            xml.push_str("  </entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    /// The feed as an RSS 2.0 document
+    pub fn to_rss(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n",
+        );
+        element(&mut xml, 1, "title", &self.title);
+        element(&mut xml, 1, "link", &self.link);
+        // RSS requires a description
+        element(
+            &mut xml,
+            1,
+            "description",
+            self.description.as_deref().unwrap_or(&self.title),
+        );
+        if let Some(url) = &self.feed_url {
+            xml.push_str(&format!(
+                "  <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+                escape(url)
+            ));
+        }
+        if let Some(language) = &self.language {
+            element(&mut xml, 1, "language", language);
+        }
+        if let Some(updated) = self.last_modified() {
+            element(&mut xml, 1, "lastBuildDate", &updated.to_rfc2822());
+        }
+        element(&mut xml, 1, "ttl", &(self.ttl.as_secs() / 60).to_string());
+
+        for entry in &self.entries {
+            xml.push_str("  <item>\n");
+            element(&mut xml, 2, "title", &entry.title);
+            element(&mut xml, 2, "link", &entry.link);
+            let guid = entry.id.as_deref().unwrap_or(&entry.link);
+            xml.push_str(&format!(
+                "    <guid isPermaLink=\"{}\">{}</guid>\n",
+                entry.id.is_none(),
+                escape(guid)
+            ));
+            if let Some(date) = entry.published.or(entry.updated) {
+                element(&mut xml, 2, "pubDate", &date.to_rfc2822());
+            }
+            if let Some(author) = entry.author.as_ref().or(self.author.as_ref()) {
+                // RSS <author> must be an email address
+                element(&mut xml, 2, "dc:creator", author);
+            }
+            if let Some(summary) = &entry.summary {
+                element(&mut xml, 2, "description", summary);
+            }
+            if let Some(content) = &entry.content {
+                element(&mut xml, 2, "content:encoded", content);
+            }
+            xml.push_str("  </item>\n");
+        }
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+
+    fn respond(&self, content_type: &str, body: String) -> HttpResponse {
+        let mut response = HttpResponse::from_bytes(content_type, body).header(
+            "Cache-Control",
+            format!("public, max-age={}", self.ttl.as_secs()),
+        );
+        if let Some(updated) = self.last_modified() {
+            response = response.header("Last-Modified", updated.format(HTTP_DATE).to_string());
+        }
+        response
+    }
+}
+
+/// An entry of a `Feed`
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    title: String,
+    link: String,
+    id: Option<String>,
+    summary: Option<String>,
+    content: Option<String>,
+    author: Option<String>,
+    published: Option<DateTime<Utc>>,
+    updated: Option<DateTime<Utc>>,
+}
+
+impl FeedEntry {
+    /// An entry for the page at `link`
+    pub fn new(title: impl Into<String>, link: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            link: link.into(),
+            id: None,
+            summary: None,
+            content: None,
+            author: None,
+            published: None,
+            updated: None,
+        }
+    }
+
+    /// Permanent id, if the link may change; defaults to the link
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Plain text summary
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Full content as HTML
+    pub fn content(mut self, html: impl Into<String>) -> Self {
+        self.content = Some(html.into());
+        self
+    }
+
+    /// Author name
+    pub fn author(mut self, name: impl Into<String>) -> Self {
+        self.author = Some(name.into());
+        self
+    }
+
+    /// When the entry was first published
+    pub fn published(mut self, published: DateTime<Utc>) -> Self {
+        self.published = Some(published);
+        self
+    }
+
+    /// When the entry last changed
+    pub fn updated(mut self, updated: DateTime<Utc>) -> Self {
+        self.updated = Some(updated);
+        self
+    }
+
+    fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.updated.or(self.published)
+    }
+}
+
+impl HttpResponse {
+    /// Send `feed` as Atom (`application/atom+xml`)
+    pub fn atom(feed: &Feed) -> Self {
+        feed.respond("application/atom+xml; charset=utf-8", feed.to_atom())
+    }
+
+    /// Send `feed` as RSS 2.0 (`application/rss+xml`)
+    pub fn rss(feed: &Feed) -> Self {
+        feed.respond("application/rss+xml; charset=utf-8", feed.to_rss())
+    }
+}
+
+fn element(xml: &mut String, depth: usize, name: &str, text: &str) {
+    xml.push_str(&format!(
+        "{}<{}>{}</{}>\n",
+        "  ".repeat(depth),
+        name,
+        escape(text),
+        name
+    ));
+}
+
+fn author_element(xml: &mut String, depth: usize, name: &str) {
+    let indent = "  ".repeat(depth);
+    xml.push_str(&format!("{}<author>\n", indent));
+    element(xml, depth + 1, "name", name);
+    xml.push_str(&format!("{}</author>\n", indent));
+}
+
+fn atom_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn feed() -> Feed {
+        let date = |day| Utc.with_ymd_and_hms(2024, 5, day, 9, 30, 0).unwrap();
+        Feed::new("Kit & Co", "https://example.com/blog")
+            .feed_url("https://example.com/blog/feed")
+            .author("Ada")
+            .ttl(Duration::from_secs(600))
+            .entry(
+                FeedEntry::new("Hello <world>", "https://example.com/blog/hello")
+                    .summary("First post")
+                    .content("<p>Hi</p>")
+                    .published(date(1)),
+            )
+            .entry(
+                FeedEntry::new("Second", "https://example.com/blog/second")
+                    .published(date(2))
+                    .updated(date(3)),
+            )
+    }
+
+    #[test]
+    fn feeds_render_as_atom_and_rss() {
+        let atom = feed().to_atom();
+        assert!(atom.contains("<title>Kit &amp; Co</title>"));
+        assert!(atom.contains("<id>https://example.com/blog/feed</id>"));
+        assert!(atom.contains("<updated>2024-05-03T09:30:00Z</updated>"));
+        assert!(atom.contains("<title>Hello &lt;world&gt;</title>"));
+        assert!(atom.contains("<content type=\"html\">&lt;p&gt;Hi&lt;/p&gt;</content>"));
+        assert!(atom.contains("<published>2024-05-01T09:30:00Z</published>"));
+
+        let rss = feed().to_rss();
+        assert!(rss.contains("<description>Kit &amp; Co</description>"));
+        assert!(rss.contains("<lastBuildDate>Fri, 3 May 2024 09:30:00 +0000</lastBuildDate>"));
+        assert!(rss.contains("<ttl>10</ttl>"));
+        assert!(rss.contains("<dc:creator>Ada</dc:creator>"));
+        assert!(rss.contains("<guid isPermaLink=\"true\">https://example.com/blog/hello</guid>"));
+        assert!(rss.contains("<pubDate>Wed, 1 May 2024 09:30:00 +0000</pubDate>"));
+        assert!(rss.contains("<content:encoded>&lt;p&gt;Hi&lt;/p&gt;</content:encoded>"));
+    }
+
+    #[test]
+    fn feed_responses_have_content_type_and_caching_headers() {
+        let response = HttpResponse::atom(&feed()).into_hyper();
+        assert_eq!(
+            response.headers()["Content-Type"],
+            "application/atom+xml; charset=utf-8"
+        );
+        assert_eq!(response.headers()["Cache-Control"], "public, max-age=600");
+        assert_eq!(
+            response.headers()["Last-Modified"],
+            "Fri, 03 May 2024 09:30:00 GMT"
+        );
+
+        let response = HttpResponse::rss(&feed()).into_hyper();
+        assert_eq!(
+            response.headers()["Content-Type"],
+            "application/rss+xml; charset=utf-8"
+        );
+    }
+}
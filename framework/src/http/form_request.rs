@@ -3,8 +3,9 @@
 //! Provides Laravel-like FormRequest pattern with automatic body parsing,
 //! validation, and authorization.
 
-use super::body::{parse_form, parse_json};
+use super::body::parse_input;
 use super::extract::FromRequest;
+use super::rules::{RuleCheck, Rules};
 use super::Request;
 use crate::error::{FrameworkError, ValidationErrors};
 use async_trait::async_trait;
@@ -14,7 +15,7 @@ use validator::Validate;
 /// Trait for validated form/JSON request data
 ///
 /// Implement this trait on request structs to enable automatic:
-/// - Body parsing (JSON or form-urlencoded based on Content-Type)
+/// - Body parsing (JSON, form-urlencoded or multipart based on Content-Type)
 /// - Validation using the `validator` crate
 /// - Authorization checks
 ///
@@ -42,6 +43,26 @@ use validator::Validate;
 /// }
 /// ```
 ///
+/// # Nested Data
+///
+/// Structs and lists are validated when the field is marked
+/// `#[validate(nested)]`; their errors use dotted keys such as
+/// `address.city` and `items.2.price`:
+///
+/// ```rust,ignore
+/// #[derive(Deserialize, Validate)]
+/// pub struct LineItem {
+///     #[validate(range(min = 1))]
+///     pub quantity: u32,
+/// }
+///
+/// #[request]
+/// pub struct CreateOrderRequest {
+///     #[validate(nested)]
+///     pub items: Vec<LineItem>,
+/// }
+/// ```
+///
 /// # Authorization
 ///
 /// Override `authorize()` to add authorization logic:
@@ -66,12 +87,70 @@ pub trait FormRequest: Sized + DeserializeOwned + Validate + Send {
         true
     }
 
+    /// Clean up the parsed data before it is validated
+    ///
+    /// `#[request]` and `#[derive(FormRequest)]` generate this from
+    /// `#[transform(...)]` field attributes:
+    ///
+    /// ```rust,ignore
+    /// #[request]
+    /// pub struct CreatePostRequest {
+    ///     pub title: String,
+    ///
+    ///     // Basic formatting and links only (`strict` and `relaxed` also exist)
+    ///     #[transform(sanitize_html)]
+    ///     pub body: String,
+    ///
+    ///     #[transform(sanitize_html(strict))]
+    ///     pub summary: Option<String>,
+    /// }
+    /// ```
+    fn transform(&mut self) {}
+
+    /// Check uploaded files, alongside `Validate`
+    ///
+    /// `#[request]` and `#[derive(FormRequest)]` generate this from
+    /// `#[file(...)]` attributes on `UploadedFile` fields:
+    ///
+    /// ```rust,ignore
+    /// #[request]
+    /// pub struct UploadRequest {
+    ///     // Sizes are bytes, or strings such as "512KB" and "2MB"
+    ///     #[file(max_size = "2MB", mimes("image/png", "image/jpeg"))]
+    ///     pub avatar: UploadedFile,
+    ///
+    ///     #[file(mimes("application/pdf"))]
+    ///     pub resume: Option<UploadedFile>,
+    /// }
+    /// ```
+    fn validate_uploads(&self) -> ValidationErrors {
+        ValidationErrors::new()
+    }
+
+    /// Values to check with registered async rules, alongside `Validate`
+    ///
+    /// `#[request]` generates this from `#[validate(custom = "...")]`; see
+    /// `Rule` for registering the names:
+    ///
+    /// ```rust,ignore
+    /// #[request]
+    /// pub struct RegisterRequest {
+    ///     #[validate(email, custom = "unique_email")]
+    ///     pub email: String,
+    /// }
+    /// ```
+    fn rule_checks(&self) -> Vec<RuleCheck> {
+        Vec::new()
+    }
+
     /// Extract and validate data from the request
     ///
     /// This method:
     /// 1. Checks authorization
-    /// 2. Parses the request body (JSON or form based on Content-Type)
-    /// 3. Validates the parsed data
+    /// 2. Parses the request body (JSON, form or multipart based on Content-Type)
+    /// 3. Applies `transform()`
+    /// 4. Validates the parsed data and its uploaded files
+    /// 5. Runs the async rules on fields that are valid so far
     ///
     /// Returns `Err(FrameworkError)` on authorization failure, parse error,
     /// or validation failure.
@@ -87,16 +166,23 @@ pub trait FormRequest: Sized + DeserializeOwned + Validate + Send {
         // Collect and parse body
         let (_, bytes) = req.body_bytes().await?;
 
-        let data: Self = match content_type.as_deref() {
-            Some(ct) if ct.starts_with("application/x-www-form-urlencoded") => parse_form(&bytes)?,
-            _ => parse_json(&bytes)?,
-        };
+        let mut data: Self = parse_input(content_type.as_deref(), bytes).await?;
+
+        data.transform();
 
         // Validate the parsed data
-        if let Err(errors) = data.validate() {
-            return Err(FrameworkError::Validation(
-                ValidationErrors::from_validator(errors),
-            ));
+        let mut errors = match data.validate() {
+            Ok(()) => ValidationErrors::new(),
+            Err(errors) => ValidationErrors::from_validator(errors),
+        };
+        for (field, messages) in data.validate_uploads().errors {
+            for message in messages {
+                errors.add(field.clone(), message);
+            }
+        }
+        Rules::check(data.rule_checks(), &mut errors).await?;
+        if !errors.is_empty() {
+            return Err(FrameworkError::Validation(errors));
         }
 
         Ok(data)
@@ -110,3 +196,196 @@ impl<T: FormRequest> FromRequest for T {
         T::extract(req).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Validate, crate::FormRequestDerive)]
+    struct CreatePostRequest {
+        #[validate(length(min = 1))]
+        #[transform(sanitize_html)]
+        body: String,
+        #[transform(sanitize_html(strict))]
+        summary: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn test_transform_runs_before_validation() {
+        let req = Request::fake()
+            .json(serde_json::json!({
+                "body": "<p onclick=\"x()\">Hi <b>there</b></p>",
+                "summary": "<i>Short</i>",
+            }))
+            .build();
+        let post = CreatePostRequest::extract(req).await.unwrap();
+        assert_eq!(post.body, "<p>Hi <b>there</b></p>");
+        assert_eq!(post.summary.as_deref(), Some("Short"));
+
+        // Nothing is left once the script is removed
+        let req = Request::fake()
+            .json(serde_json::json!({ "body": "<script>alert(1)</script>" }))
+            .build();
+        assert!(matches!(
+            CreatePostRequest::extract(req).await,
+            Err(FrameworkError::Validation(_))
+        ));
+    }
+
+    #[crate::request]
+    struct RegisterRequest {
+        #[validate(email, custom = "unique_email")]
+        email: String,
+        #[validate(custom = "unique_email")]
+        backup_email: Option<String>,
+    }
+
+    struct UniqueEmail;
+
+    #[async_trait]
+    impl crate::Rule for UniqueEmail {
+        async fn passes(&self, value: &serde_json::Value) -> Result<bool, FrameworkError> {
+            Ok(value != "taken@example.com")
+        }
+
+        fn message(&self, field: &str) -> String {
+            format!("The {} has already been taken.", field)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_rules_merge_into_validation_errors() {
+        let _container = crate::testing::TestContainer::fake();
+        let register =
+            |body: serde_json::Value| RegisterRequest::extract(Request::fake().json(body).build());
+
+        let Err(FrameworkError::Internal { .. }) =
+            register(serde_json::json!({ "email": "ada@example.com" })).await
+        else {
+            panic!("expected an error for the unregistered rule");
+        };
+
+        Rules::register("unique_email", UniqueEmail);
+        let form = register(serde_json::json!({ "email": "ada@example.com" }))
+            .await
+            .unwrap();
+        assert_eq!(form.email, "ada@example.com");
+        assert_eq!(form.backup_email, None);
+
+        let Err(FrameworkError::Validation(errors)) = register(serde_json::json!({
+            "email": "taken@example.com",
+            "backup_email": "taken@example.com",
+        }))
+        .await
+        else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(
+            errors.errors["email"],
+            ["The email has already been taken."]
+        );
+        assert_eq!(
+            errors.errors["backup_email"],
+            ["The backup_email has already been taken."]
+        );
+
+        // Fields that fail the validator rules aren't checked again
+        let Err(FrameworkError::Validation(errors)) =
+            register(serde_json::json!({ "email": "not-an-email" })).await
+        else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(errors.errors["email"].len(), 1);
+        assert!(!errors.errors["email"][0].contains("taken"));
+    }
+
+    #[derive(Deserialize, Validate)]
+    struct Address {
+        #[validate(length(min = 1, message = "The city is required."))]
+        city: String,
+    }
+
+    #[derive(Deserialize, Validate)]
+    struct LineItem {
+        #[validate(range(min = 1, message = "The price must be positive."))]
+        price: u32,
+    }
+
+    #[crate::request]
+    struct CreateOrderRequest {
+        #[validate(nested)]
+        address: Address,
+        #[validate(nested)]
+        items: Vec<LineItem>,
+        #[validate(nested)]
+        gift: Option<LineItem>,
+    }
+
+    #[tokio::test]
+    async fn test_nested_errors_use_dotted_keys() {
+        let order = |body: serde_json::Value| {
+            CreateOrderRequest::extract(Request::fake().json(body).build())
+        };
+
+        let form = order(serde_json::json!({
+            "address": { "city": "Lahore" },
+            "items": [{ "price": 5 }],
+        }))
+        .await
+        .unwrap();
+        assert_eq!(form.items.len(), 1);
+
+        let Err(FrameworkError::Validation(errors)) = order(serde_json::json!({
+            "address": { "city": "" },
+            "items": [{ "price": 5 }, { "price": 3 }, { "price": 0 }],
+            "gift": { "price": 0 },
+        }))
+        .await
+        else {
+            panic!("expected validation errors");
+        };
+        let mut keys: Vec<_> = errors.errors.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["address.city", "gift.price", "items.2.price"]);
+        assert_eq!(
+            errors.errors["items.2.price"],
+            ["The price must be positive."]
+        );
+        assert_eq!(errors.errors["address.city"], ["The city is required."]);
+    }
+
+    #[derive(Deserialize, Validate, crate::FormRequestDerive)]
+    struct AvatarRequest {
+        #[validate(length(min = 1))]
+        name: String,
+        #[file(max_size = 16, mimes("image/*"))]
+        avatar: crate::UploadedFile,
+    }
+
+    #[tokio::test]
+    async fn test_multipart_files_are_extracted_and_validated() {
+        let request = |file: crate::UploadedFile| {
+            Request::fake()
+                .method("POST")
+                .multipart(&[("name", "Ada")], &[("avatar", file)])
+                .build()
+        };
+
+        let png = crate::UploadedFile::new("me.png", "image/png", &b"\x89PNG"[..]);
+        let form = AvatarRequest::extract(request(png)).await.unwrap();
+        assert_eq!(form.name, "Ada");
+        assert_eq!(form.avatar.client_name(), Some("me.png"));
+        assert_eq!(form.avatar.bytes().as_ref(), b"\x89PNG");
+
+        let pdf = crate::UploadedFile::new("cv.pdf", "application/pdf", vec![0; 32]);
+        let Err(FrameworkError::Validation(errors)) = AvatarRequest::extract(request(pdf)).await
+        else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(
+            errors.errors["avatar"],
+            ["The avatar may not be greater than 16 bytes."]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Conversion of handler return values into responses
+//!
+//! `#[handler]` functions may return anything implementing `IntoResponse`,
+//! not only `Response`. The macro converts the value after the body runs.
+
+use super::{HttpResponse, Json, Redirect, RedirectRouteBuilder, Response, SseResponse};
+use crate::error::FrameworkError;
+use crate::inertia::{InertiaContext, InertiaResponse};
+use crate::profile::{self, Phase};
+use serde::Serialize;
+use std::future::Future;
+
+/// A value a handler can return
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{handler, FrameworkError, Redirect};
+///
+/// #[handler]
+/// pub async fn show(id: i32) -> Result<User, FrameworkError> {
+///     // Serialized as JSON; errors become error responses
+///     User::find(id).await
+/// }
+///
+/// #[handler]
+/// pub async fn logout(req: Request) -> Redirect {
+///     Auth::logout(&req);
+///     Redirect::to("/")
+/// }
+/// ```
+pub trait IntoResponse {
+    /// Convert into a `Response`
+    fn into_response(self) -> Response;
+}
+
+impl IntoResponse for Response {
+    fn into_response(self) -> Response {
+        self
+    }
+}
+
+impl IntoResponse for HttpResponse {
+    fn into_response(self) -> Response {
+        Ok(self)
+    }
+}
+
+/// `Ok` values are serialized as JSON, errors rendered as usual
+impl<T: Serialize> IntoResponse for Result<T, FrameworkError> {
+    fn into_response(self) -> Response {
+        Json(self?).into_response()
+    }
+}
+
+impl IntoResponse for SseResponse {
+    fn into_response(self) -> Response {
+        Ok(self.into())
+    }
+}
+
+impl IntoResponse for FrameworkError {
+    fn into_response(self) -> Response {
+        Err(self.into())
+    }
+}
+
+impl IntoResponse for Redirect {
+    fn into_response(self) -> Response {
+        self.into()
+    }
+}
+
+impl IntoResponse for RedirectRouteBuilder {
+    fn into_response(self) -> Response {
+        self.into()
+    }
+}
+
+/// Rendered as JSON for Inertia visits, as the HTML shell otherwise
+impl IntoResponse for InertiaResponse {
+    fn into_response(self) -> Response {
+        if InertiaContext::is_inertia_request() {
+            Ok(self.to_json_response())
+        } else {
+            Ok(self.to_html_response())
+        }
+    }
+}
+
+impl IntoResponse for String {
+    fn into_response(self) -> Response {
+        Ok(HttpResponse::text(self))
+    }
+}
+
+impl IntoResponse for &'static str {
+    fn into_response(self) -> Response {
+        Ok(HttpResponse::text(self))
+    }
+}
+
+/// A response with the status code replaced, e.g. `(201, Json(user))`
+impl<R: IntoResponse> IntoResponse for (u16, R) {
+    fn into_response(self) -> Response {
+        let (status, response) = self;
+        match response.into_response() {
+            Ok(response) => Ok(response.status(status)),
+            Err(response) => Err(response.status(status)),
+        }
+    }
+}
+
+/// Run a `#[handler]` body and convert its value
+///
+/// Taking the body as a future whose output is `T` lets `?` and `return`
+/// in the body infer the handler's declared return type.
+#[doc(hidden)]
+pub async fn __handler_response<T, F>(body: F) -> Response
+where
+    T: IntoResponse,
+    F: Future<Output = T>,
+{
+    let started = profile::start();
+    let value = body.await;
+    profile::record(Phase::Handler, started);
+
+    let started = profile::start();
+    let response = value.into_response();
+    profile::record(Phase::Serialization, started);
+    response
+}
+
+/// Run a synchronous `#[handler]` body and convert its value
+#[doc(hidden)]
+pub fn __handler_response_sync<T, F>(body: F) -> Response
+where
+    T: IntoResponse,
+    F: FnOnce() -> T,
+{
+    let started = profile::start();
+    let value = body();
+    profile::record(Phase::Handler, started);
+
+    let started = profile::start();
+    let response = value.into_response();
+    profile::record(Phase::Serialization, started);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Request;
+    use crate::testing::TestResponse;
+
+    #[derive(Serialize)]
+    struct User {
+        name: &'static str,
+    }
+
+    #[test]
+    fn test_result_serializes_ok_values() {
+        let ok: Result<User, FrameworkError> = Ok(User { name: "Ada" });
+        let response = TestResponse::from(ok.into_response());
+        response.assert_status(200);
+        assert_eq!(response.json::<serde_json::Value>()["name"], "Ada");
+
+        let err: Result<User, FrameworkError> = Err(FrameworkError::model_not_found("User"));
+        let response = TestResponse::from(err.into_response());
+        assert!(response.is_err());
+        response.assert_status(404);
+    }
+
+    #[test]
+    fn test_status_tuple() {
+        let response = TestResponse::from((201, Json(User { name: "Ada" })).into_response());
+        response
+            .assert_status(201)
+            .assert_header("Content-Type", "application/json");
+    }
+
+    #[crate::handler]
+    async fn show(id: i32) -> Result<User, FrameworkError> {
+        if id != 1 {
+            return Err(FrameworkError::model_not_found("User"));
+        }
+        let name =
+            std::str::from_utf8(b"Ada").map_err(|e| FrameworkError::internal(e.to_string()))?;
+        Ok(User { name })
+    }
+
+    #[crate::handler]
+    async fn store() -> impl IntoResponse {
+        (201, Json(User { name: "Grace" }))
+    }
+
+    #[tokio::test]
+    async fn test_handler_converts_return_values() {
+        let response = TestResponse::from(show(Request::fake().param("id", "1").build()).await);
+        assert_eq!(response.json::<serde_json::Value>()["name"], "Ada");
+
+        let response = TestResponse::from(show(Request::fake().param("id", "2").build()).await);
+        response.assert_status(404);
+
+        TestResponse::from(store(Request::fake().build()).await).assert_status(201);
+    }
+
+    #[test]
+    fn test_redirect_and_text() {
+        TestResponse::from(Redirect::to("/home").into_response()).assert_redirect("/home");
+        let response = TestResponse::from("hello".into_response());
+        assert_eq!(response.text(), "hello");
+    }
+}
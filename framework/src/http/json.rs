@@ -0,0 +1,54 @@
+//! Typed JSON responses
+
+use super::{HttpResponse, IntoResponse, Response};
+use crate::error::FrameworkError;
+use serde::Serialize;
+use std::ops::{Deref, DerefMut};
+
+/// A JSON body serialized from any `Serialize` value
+///
+/// As a handler parameter it deserializes the request body instead, see
+/// `FromRequest`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{handler, Json};
+///
+/// #[handler]
+/// pub async fn store(form: CreateUserRequest) -> (u16, Json<User>) {
+///     (201, Json(User::create(form).await))
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    /// The wrapped value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        let body = serde_json::to_value(&self.0).map_err(|e| {
+            FrameworkError::internal(format!("Failed to serialize JSON response: {}", e))
+        })?;
+        Ok(HttpResponse::json(body))
+    }
+}
@@ -0,0 +1,169 @@
+//! camelCase JSON responses
+//!
+//! Rust structs serialize with snake_case keys while JavaScript frontends
+//! usually expect camelCase. `CamelCaseJson` rewrites the keys of JSON
+//! response bodies, recursively, so handlers keep their snake_case structs:
+//!
+//! ```rust,ignore
+//! // Every route
+//! global_middleware!(CamelCaseJson);
+//!
+//! // Or one group
+//! group!("/api", {
+//!     get!("/users", controllers::api::users::index),
+//! }).middleware(CamelCaseJson)
+//! ```
+//!
+//! Keys are converted with serde's `rename_all = "camelCase"` rule, so
+//! `created_at` becomes `createdAt` and `address_line_1` becomes
+//! `addressLine1`. Map keys are converted too, not just struct fields.
+//!
+//! Inertia responses are left alone: their props are also embedded in the
+//! initial HTML page, which this middleware can't rewrite. Give props structs
+//! `#[inertia(rename_all = "camelCase")]` instead; `kit generate-types`
+//! applies the same rule to the generated TypeScript interfaces.
+
+use super::{HttpResponse, Response};
+use crate::middleware::{Middleware, Next};
+use crate::Request;
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+/// Middleware converting JSON response keys to camelCase
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CamelCaseJson;
+
+#[async_trait]
+impl Middleware for CamelCaseJson {
+    async fn handle(&self, request: Request, next: Next) -> Response {
+        match next(request).await {
+            Ok(response) => Ok(camel_case_response(response)),
+            Err(response) => Err(camel_case_response(response)),
+        }
+    }
+}
+
+/// Rewrite the keys of a JSON response body, leaving other responses as-is
+fn camel_case_response(response: HttpResponse) -> HttpResponse {
+    let header = |name: &str| {
+        response
+            .headers()
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    };
+    let is_json = header("Content-Type").is_some_and(|content_type| content_type.contains("json"));
+    if !is_json || header("X-Inertia").is_some() {
+        return response;
+    }
+
+    match serde_json::from_slice::<Value>(response.body()) {
+        Ok(value) => {
+            let body = camel_case_keys(value).to_string();
+            response.with_body(body)
+        }
+        Err(_) => response,
+    }
+}
+
+/// Convert every object key in `value` to camelCase, recursively
+pub fn camel_case_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| (to_camel_case(&key), camel_case_keys(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(camel_case_keys).collect()),
+        other => other,
+    }
+}
+
+/// `snake_case` to `camelCase`, matching serde's `rename_all = "camelCase"`
+pub fn to_camel_case(key: &str) -> String {
+    let mut camel = String::with_capacity(key.len());
+    let mut capitalize = false;
+    for ch in key.chars() {
+        if ch == '_' {
+            capitalize = !camel.is_empty();
+        } else if capitalize {
+            camel.push(ch.to_ascii_uppercase());
+            capitalize = false;
+        } else if camel.is_empty() {
+            camel.push(ch.to_ascii_lowercase());
+        } else {
+            camel.push(ch);
+        }
+    }
+    camel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inertia::InertiaResponse;
+    use serde_json::json;
+
+    fn body(response: &HttpResponse) -> Value {
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    #[test]
+    fn converts_like_serde() {
+        assert_eq!(to_camel_case("created_at"), "createdAt");
+        assert_eq!(to_camel_case("address_line_1"), "addressLine1");
+        assert_eq!(to_camel_case("id"), "id");
+        assert_eq!(to_camel_case("_private"), "private");
+        assert_eq!(to_camel_case("alreadyCamel"), "alreadyCamel");
+    }
+
+    #[test]
+    fn rewrites_nested_json_keys() {
+        let response = camel_case_response(
+            HttpResponse::json(json!({
+                "next_cursor": null,
+                "data": [{ "user_id": 1, "tags": [{ "tag_name": "x" }] }],
+            }))
+            .status(201),
+        );
+
+        assert_eq!(response.status_code(), 201);
+        assert_eq!(
+            body(&response),
+            json!({
+                "nextCursor": null,
+                "data": [{ "userId": 1, "tags": [{ "tagName": "x" }] }],
+            })
+        );
+    }
+
+    #[derive(crate::InertiaProps)]
+    #[inertia(rename_all = "camelCase")]
+    struct ProfileProps {
+        current_user: i32,
+        address_line_1: String,
+    }
+
+    #[test]
+    fn inertia_props_rename_matches_the_middleware() {
+        let props = ProfileProps {
+            current_user: 1,
+            address_line_1: "Main St".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&props).unwrap(),
+            camel_case_keys(json!({ "current_user": 1, "address_line_1": "Main St" }))
+        );
+    }
+
+    #[test]
+    fn leaves_other_responses_alone() {
+        let text = camel_case_response(HttpResponse::text("user_id"));
+        assert_eq!(text.body().as_ref(), b"user_id");
+
+        let page = InertiaResponse::new("Users", json!({ "user_id": 1 }), "/users".to_string());
+        let inertia = camel_case_response(page.to_json_response());
+        assert_eq!(body(&inertia)["props"], json!({ "user_id": 1 }));
+    }
+}
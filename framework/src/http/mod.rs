@@ -1,16 +1,58 @@
 mod body;
 pub mod cookie;
+pub(crate) mod download;
+mod error_body;
+mod error_format;
+pub(crate) mod error_page;
+mod etag;
+mod exception;
 mod extract;
+mod feed;
 mod form_request;
+mod into_response;
+mod json;
+mod json_case;
+mod proxies;
+mod query;
 mod request;
 mod response;
+mod rules;
+mod sanitize;
+pub(crate) mod tls;
+mod upload;
 
-pub use body::{collect_body, parse_form, parse_json};
-pub use cookie::{parse_cookies, Cookie, CookieOptions, SameSite};
-pub use extract::{FromParam, FromRequest};
+pub use body::{
+    collect_body, collect_body_with_limits, parse_form, parse_json, parse_multipart, BodyLimits,
+    MultipartForm, RequestBody,
+};
+pub use cookie::{parse_cookies, Cookie, CookieConfig, CookieOptions, SameSite};
+pub(crate) use error_body::ErrorContext;
+pub use error_format::ErrorFormat;
+pub(crate) use error_format::ErrorFormatMiddleware;
+pub use etag::ETag;
+pub use exception::{ExceptionHandler, DONT_REPORT};
+#[doc(hidden)]
+pub use extract::__resolve;
+pub use extract::{FromParam, FromRequest, FromRequestRef};
+pub use feed::{Feed, FeedEntry};
 pub use form_request::FormRequest;
+pub use into_response::IntoResponse;
+#[doc(hidden)]
+pub use into_response::{__handler_response, __handler_response_sync};
+pub use json::Json;
+pub use json_case::{camel_case_keys, to_camel_case, CamelCaseJson};
+pub(crate) use proxies::{ConnectionScheme, RemoteAddr};
+pub use proxies::{ForwardedHeader, TrustedProxies, TrustedProxyConfig};
+pub use query::Query;
+pub(crate) use request::{wants_json, Disconnect};
 pub use request::{Request, RequestParts};
-pub use response::{HttpResponse, Redirect, RedirectRouteBuilder, Response, ResponseExt};
+pub use response::{
+    HttpResponse, Redirect, RedirectRouteBuilder, Response, ResponseBody, ResponseExt, SseEvent,
+    SseResponse,
+};
+pub use rules::{Rule, RuleCheck, Rules};
+pub use sanitize::{sanitize_html, HtmlPolicy, SanitizeHtml};
+pub use upload::{UploadRules, UploadedFile, ValidateUpload};
 
 /// Error type for missing route parameters
 ///
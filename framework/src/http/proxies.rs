@@ -0,0 +1,329 @@
+//! Trusted reverse proxies
+//!
+//! Behind a load balancer the connection comes from the proxy, and the
+//! client's address, scheme and host arrive in `X-Forwarded-*` headers, or
+//! the client's address in a standard `Forwarded` header (RFC 7239). Those
+//! headers are only believed when the connection comes from a proxy listed
+//! in `TrustedProxies`, since any client can send them. Only the header the
+//! proxy sets is read for the address: most proxies pass a client's own
+//! `Forwarded` header through untouched while appending to `X-Forwarded-For`.
+
+use crate::config::{env_optional, Config};
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
+
+/// The proxies whose `X-Forwarded-*` headers are trusted
+///
+/// Used by `Request::client_ip()`, `Request::scheme()` and `Request::host()`,
+/// and through them by `Request::url_for()` and absolute redirects.
+///
+/// # Environment Variables
+///
+/// - `TRUSTED_PROXIES` - Comma-separated IPs or CIDR ranges, or `*` to trust
+///   every connection (default: none)
+/// - `TRUSTED_PROXY_HEADER` - Header the client address is read from, `xff`
+///   for `X-Forwarded-For` or `forwarded` for `Forwarded` (default: xff)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Config, TrustedProxies};
+///
+/// // Register from environment (Config::init does this)
+/// Config::register(TrustedProxies::from_env());
+///
+/// // Or list the proxies
+/// Config::register(TrustedProxies::none().trust("10.0.0.0/8").trust("127.0.0.1"));
+///
+/// // Behind a proxy that sets the standard header
+/// Config::register(TrustedProxies::all().header(ForwardedHeader::Forwarded));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    all: bool,
+    networks: Vec<IpNet>,
+    header: ForwardedHeader,
+}
+
+/// The header trusted proxies put the client's address in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ForwardedHeader {
+    /// `X-Forwarded-For`, set by nginx, Cloudflare and most load balancers
+    #[default]
+    XForwardedFor,
+    /// The standard `Forwarded` header (RFC 7239)
+    Forwarded,
+}
+
+impl ForwardedHeader {
+    /// Parse `xff`, `x-forwarded-for` or `forwarded`, ignoring case
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "xff" | "x-forwarded-for" => Some(Self::XForwardedFor),
+            "forwarded" => Some(Self::Forwarded),
+            _ => None,
+        }
+    }
+
+    /// The header name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::XForwardedFor => "X-Forwarded-For",
+            Self::Forwarded => "Forwarded",
+        }
+    }
+}
+
+/// `TrustedProxies` under the name the other configuration types use
+pub type TrustedProxyConfig = TrustedProxies;
+
+impl TrustedProxies {
+    /// Create configuration from environment variables
+    ///
+    /// Invalid entries in `TRUSTED_PROXIES` are reported and skipped.
+    pub fn from_env() -> Self {
+        let mut proxies = Self::none();
+        for entry in env_optional::<String>("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            match parse_proxy(entry) {
+                Some(Proxy::All) => proxies.all = true,
+                Some(Proxy::Network(network)) => proxies.networks.push(network),
+                None => eprintln!("Ignoring invalid TRUSTED_PROXIES entry: {}", entry),
+            }
+        }
+        if let Some(header) = env_optional::<String>("TRUSTED_PROXY_HEADER") {
+            match ForwardedHeader::parse(&header) {
+                Some(header) => proxies.header = header,
+                None => eprintln!("Ignoring invalid TRUSTED_PROXY_HEADER: {}", header),
+            }
+        }
+        proxies
+    }
+
+    /// Trust no proxies; forwarded headers are ignored
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Trust every connection, e.g. when the app is only reachable through
+    /// the load balancer
+    pub fn all() -> Self {
+        Self {
+            all: true,
+            ..Self::default()
+        }
+    }
+
+    /// Trust a proxy IP or CIDR range, e.g. `"10.0.0.0/8"`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `proxy` is not an IP address, a CIDR range or `*`.
+    pub fn trust(mut self, proxy: &str) -> Self {
+        match parse_proxy(proxy) {
+            Some(Proxy::All) => self.all = true,
+            Some(Proxy::Network(network)) => self.networks.push(network),
+            None => panic!("Invalid trusted proxy: {}", proxy),
+        }
+        self
+    }
+
+    /// Read the client address from `header` (default: `X-Forwarded-For`)
+    ///
+    /// The other header is ignored, so set this to the one the proxy sets.
+    pub fn header(mut self, header: ForwardedHeader) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// The header the client address is read from
+    pub fn forwarded_header(&self) -> ForwardedHeader {
+        self.header
+    }
+
+    /// Whether connections from `ip` are trusted
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.all || self.networks.iter().any(|network| network.contains(&ip))
+    }
+
+    /// The registered configuration, or the one from the environment
+    pub(crate) fn current() -> Self {
+        Config::get::<TrustedProxies>().unwrap_or_else(Self::from_env)
+    }
+
+    /// The client IP for a connection from `peer`, given the value of the
+    /// configured forwarded header
+    ///
+    /// The hops are read right to left, skipping trusted proxies, so a
+    /// client can't pick its address by sending the header itself.
+    pub(crate) fn client_ip(&self, peer: IpAddr, forwarded: Option<&str>) -> IpAddr {
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+        let Some(forwarded) = forwarded else {
+            return peer;
+        };
+
+        let hops: Vec<IpAddr> = match self.header {
+            ForwardedHeader::XForwardedFor => forwarded
+                .split(',')
+                .filter_map(|hop| hop.trim().parse().ok())
+                .collect(),
+            ForwardedHeader::Forwarded => forwarded_hops(forwarded),
+        };
+        hops.iter()
+            .rev()
+            .find(|ip| !self.is_trusted(**ip))
+            .or_else(|| hops.first())
+            .copied()
+            .unwrap_or(peer)
+    }
+}
+
+/// The `for=` addresses of a `Forwarded` header, e.g.
+/// `for=198.51.100.7, for="[2001:db8::1]:4711";proto=https`
+///
+/// Obfuscated identifiers and `unknown` are skipped, like unparseable
+/// `X-Forwarded-For` hops.
+fn forwarded_hops(forwarded: &str) -> Vec<IpAddr> {
+    forwarded
+        .split(',')
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                if !key.trim().eq_ignore_ascii_case("for") {
+                    return None;
+                }
+                let node = value.trim().trim_matches('"');
+                match node.strip_prefix('[') {
+                    // IPv6 is bracketed, with an optional port after the `]`
+                    Some(v6) => v6.split_once(']')?.0.parse().ok(),
+                    None => node
+                        .parse()
+                        .ok()
+                        .or_else(|| node.split_once(':')?.0.parse().ok()),
+                }
+            })
+        })
+        .collect()
+}
+
+enum Proxy {
+    All,
+    Network(IpNet),
+}
+
+fn parse_proxy(proxy: &str) -> Option<Proxy> {
+    if proxy == "*" {
+        return Some(Proxy::All);
+    }
+    proxy
+        .parse::<IpNet>()
+        .ok()
+        .or_else(|| proxy.parse::<IpAddr>().ok().map(IpNet::from))
+        .map(Proxy::Network)
+}
+
+/// The address of the connection a request came in on
+///
+/// Set by the server as a request extension.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RemoteAddr(pub SocketAddr);
+
+/// Whether the connection a request came in on is TLS
+///
+/// Set by the server as a request extension, since request URIs on a
+/// server connection never carry a scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConnectionScheme {
+    Http,
+    Https,
+}
+
+impl ConnectionScheme {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Http => "http",
+            Self::Https => "https",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn test_trust() {
+        let proxies = TrustedProxies::none().trust("10.0.0.0/8").trust("192.168.1.1");
+
+        assert!(proxies.is_trusted(ip("10.1.2.3")));
+        assert!(proxies.is_trusted(ip("192.168.1.1")));
+        assert!(proxies.is_trusted(ip("::ffff:10.0.0.1")));
+        assert!(!proxies.is_trusted(ip("192.168.1.2")));
+        assert!(TrustedProxies::all().is_trusted(ip("203.0.113.9")));
+        assert!(!TrustedProxies::none().is_trusted(ip("127.0.0.1")));
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let proxies = TrustedProxies::none().trust("10.0.0.0/8");
+        let forwarded = Some("198.51.100.7, 203.0.113.9, 10.0.0.2");
+
+        // The rightmost untrusted hop is the client as seen by our proxies
+        assert_eq!(
+            proxies.client_ip(ip("10.0.0.1"), forwarded),
+            ip("203.0.113.9")
+        );
+        // Headers from untrusted connections are ignored
+        assert_eq!(
+            proxies.client_ip(ip("203.0.113.50"), forwarded),
+            ip("203.0.113.50")
+        );
+        assert_eq!(
+            proxies.client_ip(ip("10.0.0.1"), Some("10.0.0.3")),
+            ip("10.0.0.3")
+        );
+        assert_eq!(proxies.client_ip(ip("10.0.0.1"), None), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_client_ip_from_forwarded() {
+        let proxies = TrustedProxies::none()
+            .trust("10.0.0.0/8")
+            .header(ForwardedHeader::Forwarded);
+        let forwarded =
+            Some("for=198.51.100.7, For=\"[2001:db8::1]:4711\";proto=https, for=10.0.0.2:80");
+
+        assert_eq!(
+            proxies.client_ip(ip("10.0.0.1"), forwarded),
+            ip("2001:db8::1")
+        );
+        assert_eq!(
+            proxies.client_ip(ip("203.0.113.50"), forwarded),
+            ip("203.0.113.50")
+        );
+        assert_eq!(
+            proxies.client_ip(ip("10.0.0.1"), Some("for=unknown;by=_proxy")),
+            ip("10.0.0.1")
+        );
+        assert_eq!(
+            ForwardedHeader::parse("XFF"),
+            Some(ForwardedHeader::XForwardedFor)
+        );
+        assert_eq!(ForwardedHeader::parse("bogus"), None);
+        assert_eq!(
+            forwarded_hops("for=192.0.2.43:8080;proto=http, for=_hidden, for=\"[::1]\""),
+            vec![ip("192.0.2.43"), ip("::1")]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Typed query string extraction
+
+use super::extract::{FromRequest, FromRequestRef};
+use super::Request;
+use crate::error::{FrameworkError, ValidationErrors};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use std::ops::{Deref, DerefMut};
+use validator::Validate;
+
+/// The URL query string, deserialized and validated
+///
+/// A query string that doesn't match `T` is a 400 Bad Request, failed
+/// validation a 422 like a `FormRequest`. Use `Option` or
+/// `#[serde(default)]` for parameters that may be left out.
+///
+/// `Query` only reads the request, so a handler can also take the
+/// `Request` or, declared after the `Query`, a form request.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{handler, Query, Response};
+/// use serde::Deserialize;
+/// use validator::Validate;
+///
+/// #[derive(Deserialize, Validate)]
+/// pub struct PaginationParams {
+///     #[serde(default = "first_page")]
+///     #[validate(range(min = 1))]
+///     pub page: u64,
+///     #[validate(range(min = 1, max = 100))]
+///     pub per_page: Option<u64>,
+/// }
+///
+/// fn first_page() -> u64 {
+///     1
+/// }
+///
+/// #[handler]
+/// pub async fn index(params: Query<PaginationParams>) -> Response {
+///     let users = User::query()
+///         .paginate(params.per_page.unwrap_or(25), params.page)
+///         .await?;
+///     // ...
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+impl<T> Query<T> {
+    /// The deserialized parameters
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Query<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Query<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: DeserializeOwned + Validate> FromRequestRef for Query<T> {
+    fn from_request_ref(req: &Request) -> Result<Self, FrameworkError> {
+        let query = req.query_string().unwrap_or_default();
+        let params: T = serde_urlencoded::from_str(query)
+            .map_err(|e| FrameworkError::domain(format!("Invalid query string: {}", e), 400))?;
+        params.validate().map_err(|errors| {
+            FrameworkError::Validation(ValidationErrors::from_validator(errors))
+        })?;
+        Ok(Query(params))
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned + Validate + Send> FromRequest for Query<T> {
+    async fn from_request(req: Request) -> Result<Self, FrameworkError> {
+        Self::from_request_ref(&req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestResponse;
+    use crate::Response;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct PaginationParams {
+        #[serde(default = "first_page")]
+        #[validate(range(min = 1))]
+        page: u64,
+        search: Option<String>,
+    }
+
+    fn first_page() -> u64 {
+        1
+    }
+
+    #[crate::handler]
+    async fn index(req: Request, params: Query<PaginationParams>) -> Response {
+        crate::text(format!(
+            "{} {} {:?}",
+            req.path(),
+            params.page,
+            params.search
+        ))
+    }
+
+    async fn get(path: &str) -> TestResponse {
+        TestResponse::from(index(Request::fake().path(path).build()).await)
+    }
+
+    #[tokio::test]
+    async fn handlers_take_typed_query_params_alongside_the_request() {
+        let response = get("/users?page=3&search=ada+l").await;
+        response.assert_status(200);
+        assert_eq!(response.text(), "/users 3 Some(\"ada l\")");
+
+        assert_eq!(get("/users").await.text(), "/users 1 None");
+    }
+
+    #[tokio::test]
+    async fn invalid_query_strings_are_rejected() {
+        get("/users?page=abc").await.assert_status(400);
+        get("/users?page=0").await.assert_status(422);
+    }
+}
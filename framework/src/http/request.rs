@@ -1,19 +1,41 @@
-use super::body::{collect_body, parse_form, parse_json};
+use super::body::{
+    parse_form, parse_input, parse_json, parse_multipart, MultipartForm, RequestBody,
+};
 use super::cookie::parse_cookies;
+use super::proxies::{ConnectionScheme, RemoteAddr, TrustedProxies};
 use super::ParamError;
+use crate::auth::{Ability, Gate, Policy};
 use crate::error::FrameworkError;
+use crate::session::Session;
 use bytes::Bytes;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// Route binding columns of the matched route
+#[derive(Clone)]
+struct BindingKeys(Arc<[(&'static str, &'static str)]>);
+
+/// Cancelled by the server when the request's connection closes
+#[derive(Debug, Clone)]
+pub(crate) struct Disconnect(pub CancellationToken);
 
 /// HTTP Request wrapper providing Laravel-like access to request data
 pub struct Request {
-    inner: hyper::Request<hyper::body::Incoming>,
+    inner: hyper::Request<RequestBody>,
     params: HashMap<String, String>,
 }
 
 impl Request {
     pub fn new(inner: hyper::Request<hyper::body::Incoming>) -> Self {
+        Self::from_hyper(inner.map(RequestBody::Incoming))
+    }
+
+    /// Wrap a hyper request whose body may already be in memory
+    pub(crate) fn from_hyper(inner: hyper::Request<RequestBody>) -> Self {
         Self {
             inner,
             params: HashMap::new(),
@@ -25,6 +47,104 @@ impl Request {
         self
     }
 
+    /// Set the columns route parameters bind by (see `route_key`)
+    pub fn with_binding_keys(
+        mut self,
+        keys: impl Into<Arc<[(&'static str, &'static str)]>>,
+    ) -> Self {
+        let keys = keys.into();
+        if !keys.is_empty() {
+            self.insert_extension(BindingKeys(keys));
+        }
+        self
+    }
+
+    /// Attach a value for later middleware and the handler
+    pub(crate) fn insert_extension<T: Clone + Send + Sync + 'static>(&mut self, value: T) {
+        self.inner.extensions_mut().insert(value);
+    }
+
+    /// Values attached to this request, keyed by type
+    pub fn extensions(&self) -> &hyper::http::Extensions {
+        self.inner.extensions()
+    }
+
+    /// Values attached to this request, for middleware to pass data on to
+    /// later middleware and the handler
+    ///
+    /// ```rust,ignore
+    /// // In middleware
+    /// let user = Auth::user_as::<User>(&request).await?;
+    /// request.extensions_mut().insert(CurrentUser(user));
+    /// next(request).await
+    ///
+    /// // In the handler
+    /// let user = req.extension::<CurrentUser>();
+    /// ```
+    pub fn extensions_mut(&mut self) -> &mut hyper::http::Extensions {
+        self.inner.extensions_mut()
+    }
+
+    /// The value of type `T` attached with `extensions_mut`
+    pub fn extension<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.inner.extensions().get::<T>()
+    }
+
+    /// The session of this request
+    ///
+    /// # Panics
+    ///
+    /// Panics if `SessionMiddleware` didn't run for this request.
+    pub fn session(&self) -> Session {
+        self.try_session()
+            .expect("No session for this request, register SessionMiddleware")
+    }
+
+    /// The session of this request, if `SessionMiddleware` ran
+    pub fn try_session(&self) -> Option<Session> {
+        self.inner
+            .extensions()
+            .get::<Session>()
+            .cloned()
+            .or_else(Session::current)
+    }
+
+    /// Values flashed by the previous request, such as a message set with
+    /// `Redirect::with_flash`
+    ///
+    /// Empty without `SessionMiddleware`.
+    ///
+    /// ```rust,ignore
+    /// let status = req.flash().get("success").cloned();
+    /// ```
+    pub fn flash(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.try_session()
+            .map(|session| session.flashed())
+            .unwrap_or_default()
+    }
+
+    /// Fail with a 403 unless the user of this request's session may do
+    /// `ability`, see `Policy`
+    ///
+    /// ```rust,ignore
+    /// req.authorize::<PostPolicy>(Ability::Update(&post)).await?;
+    /// ```
+    pub async fn authorize<P: Policy>(
+        &self,
+        ability: Ability<'_, P::Model>,
+    ) -> Result<(), FrameworkError> {
+        let id = self.try_session().and_then(|session| session.user_id());
+        let allowed = match id {
+            Some(id) => Gate::permits_id::<P>(id, ability).await?,
+            None => false,
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(FrameworkError::Unauthorized)
+        }
+    }
+
     /// Get the request method
     pub fn method(&self) -> &hyper::Method {
         self.inner.method()
@@ -35,6 +155,11 @@ impl Request {
         self.inner.uri().path()
     }
 
+    /// Get the raw query string, without the leading `?`
+    pub fn query_string(&self) -> Option<&str> {
+        self.inner.uri().query()
+    }
+
     /// Get a route parameter by name (e.g., /users/{id})
     /// Returns Err(ParamError) if the parameter is missing, enabling use of `?` operator
     pub fn param(&self, name: &str) -> Result<&str, ParamError> {
@@ -51,11 +176,30 @@ impl Request {
         &self.params
     }
 
+    /// Column a route parameter binds by, e.g. `slug` for `/posts/{post:slug}`
+    ///
+    /// `None` means the model is looked up by primary key.
+    pub fn route_key(&self, name: &str) -> Option<&str> {
+        self.inner
+            .extensions()
+            .get::<BindingKeys>()?
+            .0
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, column)| *column)
+    }
+
     /// Get the inner hyper request
-    pub fn inner(&self) -> &hyper::Request<hyper::body::Incoming> {
+    pub fn inner(&self) -> &hyper::Request<RequestBody> {
         &self.inner
     }
 
+    /// Take over the connection once the response has been sent, e.g. for
+    /// WebSockets
+    pub(crate) fn on_upgrade(&mut self) -> hyper::upgrade::OnUpgrade {
+        hyper::upgrade::on(&mut self.inner)
+    }
+
     /// Get a header value by name
     pub fn header(&self, name: &str) -> Option<&str> {
         self.inner.headers().get(name).and_then(|v| v.to_str().ok())
@@ -66,6 +210,164 @@ impl Request {
         self.header("content-type")
     }
 
+    /// The client's IP address
+    ///
+    /// Behind a trusted proxy (see `TrustedProxies`) this is read from
+    /// `X-Forwarded-For`, or `Forwarded` if configured, otherwise it's the
+    /// connection's address. `None` for requests that didn't come through the server, such
+    /// as fake requests. Use this rather than the connection's address for
+    /// rate limiting and logging.
+    pub fn client_ip(&self) -> Option<IpAddr> {
+        let RemoteAddr(peer) = *self.inner.extensions().get::<RemoteAddr>()?;
+        let proxies = TrustedProxies::current();
+        let forwarded = self.header(proxies.forwarded_header().name());
+        Some(proxies.client_ip(peer.ip(), forwarded))
+    }
+
+    /// The client's IP address, same as `client_ip()`
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.client_ip()
+    }
+
+    /// The client's country code, e.g. "DE", see `Geo`
+    ///
+    /// `None` for local addresses, or if the lookup fails.
+    #[cfg(feature = "geo")]
+    pub async fn country(&self) -> Option<String> {
+        crate::geo::Geo::country(self.client_ip()?).await.ok().flatten()
+    }
+
+    /// The locale in `supported` that best fits the client
+    ///
+    /// Picked from `Accept-Language`, then by the client's country, then the
+    /// first supported locale; the country is only looked up when the
+    /// header doesn't decide.
+    #[cfg(feature = "geo")]
+    pub async fn locale<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        use crate::geo::locale::{for_country, from_header};
+
+        if let Some(locale) = self
+            .header("Accept-Language")
+            .and_then(|header| from_header(header, supported))
+        {
+            return Some(locale);
+        }
+        let country = self.country().await;
+        country
+            .and_then(|country| for_country(&country, supported))
+            .or(supported.first().copied())
+    }
+
+    /// A future that completes when the client disconnects
+    ///
+    /// Handlers are dropped as soon as their client goes away (unless the
+    /// server is built with `.cancel_on_disconnect(false)`), so this is
+    /// mostly for work a handler hands off, such as a task producing events
+    /// for an `SseResponse`. Never completes for requests that didn't come
+    /// through the server, such as fake requests.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let disconnected = req.on_disconnect();
+    /// tokio::spawn(async move {
+    ///     tokio::select! {
+    ///         _ = export.run(tx) => {}
+    ///         _ = disconnected => {} // stop exporting for a client that left
+    ///     }
+    /// });
+    /// ```
+    pub fn on_disconnect(&self) -> impl Future<Output = ()> + Send + 'static {
+        let token = self
+            .inner
+            .extensions()
+            .get::<Disconnect>()
+            .map(|disconnect| disconnect.0.clone());
+        async move {
+            match token {
+                Some(token) => token.cancelled_owned().await,
+                None => std::future::pending().await,
+            }
+        }
+    }
+
+    /// Whether the client has already disconnected
+    pub fn is_disconnected(&self) -> bool {
+        self.inner
+            .extensions()
+            .get::<Disconnect>()
+            .is_some_and(|disconnect| disconnect.0.is_cancelled())
+    }
+
+    /// The scheme the client used, `"http"` or `"https"`
+    ///
+    /// Behind a trusted proxy this is read from `X-Forwarded-Proto`.
+    pub fn scheme(&self) -> &str {
+        if let Some(proto) = self.forwarded_header("X-Forwarded-Proto") {
+            return proto;
+        }
+        if let Some(scheme) = self.inner.extensions().get::<ConnectionScheme>() {
+            return scheme.as_str();
+        }
+        self.inner.uri().scheme_str().unwrap_or("http")
+    }
+
+    /// The host the client requested, including any port
+    ///
+    /// Behind a trusted proxy this is read from `X-Forwarded-Host`, otherwise
+    /// from the `Host` header.
+    pub fn host(&self) -> Option<&str> {
+        self.forwarded_header("X-Forwarded-Host")
+            .or_else(|| self.header("Host"))
+            .or_else(|| self.inner.uri().authority().map(|a| a.as_str()))
+    }
+
+    /// The absolute URL of this request, including the query string
+    pub fn url(&self) -> String {
+        let path_and_query = self
+            .inner
+            .uri()
+            .path_and_query()
+            .map_or("/", |p| p.as_str());
+        format!("{}{}", self.base_url(), path_and_query)
+    }
+
+    /// The absolute URL of a named route, e.g. for links in emails
+    ///
+    /// Returns `None` if the route doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let url = req.url_for("users.show", &[("id", "5")]);
+    /// // Some("https://example.com/users/5") behind a TLS-terminating proxy
+    /// ```
+    pub fn url_for(&self, name: &str, params: &[(&str, &str)]) -> Option<String> {
+        let path = crate::routing::route(name, params)?;
+        Some(format!("{}{}", self.base_url(), path))
+    }
+
+    /// `scheme://host`, or an empty string if the host is unknown
+    pub(crate) fn base_url(&self) -> String {
+        match self.host() {
+            Some(host) => format!("{}://{}", self.scheme(), host),
+            None => String::new(),
+        }
+    }
+
+    /// The first value of a forwarded header, if the connection is from a
+    /// trusted proxy
+    fn forwarded_header(&self, name: &str) -> Option<&str> {
+        let RemoteAddr(peer) = *self.inner.extensions().get::<RemoteAddr>()?;
+        if !TrustedProxies::current().is_trusted(peer.ip()) {
+            return None;
+        }
+        self.header(name)
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
     /// Check if this is an Inertia XHR request
     pub fn is_inertia(&self) -> bool {
         self.header("X-Inertia")
@@ -73,6 +375,22 @@ impl Request {
             .unwrap_or(false)
     }
 
+    /// Check if the client asked for JSON rather than an HTML page
+    ///
+    /// True for an `Accept` header naming a JSON type or a plain XHR
+    /// (`X-Requested-With: XMLHttpRequest`). Inertia visits are XHRs too but
+    /// expect a page, so they never count. `respond_with!` uses this to pick
+    /// between the two.
+    pub fn wants_json(&self) -> bool {
+        wants_json(self.inner.headers())
+    }
+
+    /// Check if the request should get an Inertia page, the opposite of
+    /// [`Request::wants_json`]
+    pub fn expects_inertia(&self) -> bool {
+        !self.wants_json()
+    }
+
     /// Get all cookies from the request
     ///
     /// Parses the Cookie header and returns a HashMap of cookie names to values.
@@ -120,6 +438,22 @@ impl Request {
             .map(|v| v.split(',').collect())
     }
 
+    /// Consume the request and return the raw body
+    ///
+    /// The body is returned as received, without copying or UTF-8 decoding.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// pub async fn upload(req: Request) -> Response {
+    ///     let bytes = req.bytes().await?;
+    ///     Ok(HttpResponse::from_bytes("application/octet-stream", bytes))
+    /// }
+    /// ```
+    pub async fn bytes(self) -> Result<Bytes, FrameworkError> {
+        self.inner.into_body().collect().await
+    }
+
     /// Consume the request and collect the body as bytes
     pub async fn body_bytes(self) -> Result<(RequestParts, Bytes), FrameworkError> {
         let content_type = self
@@ -130,7 +464,7 @@ impl Request {
             .map(|s| s.to_string());
 
         let params = self.params;
-        let bytes = collect_body(self.inner.into_body()).await?;
+        let bytes = self.inner.into_body().collect().await?;
 
         Ok((
             RequestParts {
@@ -141,6 +475,20 @@ impl Request {
         ))
     }
 
+    /// Read the body into memory, keeping it readable by later handlers
+    pub(crate) async fn buffer_body(self) -> Result<(Self, Bytes), FrameworkError> {
+        let (parts, body) = self.inner.into_parts();
+        let bytes = body.collect().await?;
+        let inner = hyper::Request::from_parts(parts, RequestBody::Full(bytes.clone()));
+        Ok((
+            Self {
+                inner,
+                params: self.params,
+            },
+            bytes,
+        ))
+    }
+
     /// Parse the request body as JSON
     ///
     /// Consumes the request since the body can only be read once.
@@ -181,26 +529,44 @@ impl Request {
         parse_form(&bytes)
     }
 
+    /// Parse the request body as `multipart/form-data`
+    ///
+    /// Consumes the request since the body can only be read once.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// pub async fn upload(req: Request) -> Response {
+    ///     let form = req.multipart().await?;
+    ///     let Some(file) = form.file("document") else {
+    ///         return Err(FrameworkError::validation("document", "A file is required").into());
+    ///     };
+    ///     let path = file.store("documents").await?;
+    ///     // ...
+    /// }
+    /// ```
+    pub async fn multipart(self) -> Result<MultipartForm, FrameworkError> {
+        let (parts, bytes) = self.body_bytes().await?;
+        parse_multipart(parts.content_type.as_deref().unwrap_or_default(), bytes).await
+    }
+
     /// Parse the request body based on Content-Type header
     ///
     /// - `application/json` -> JSON parsing
     /// - `application/x-www-form-urlencoded` -> Form parsing
+    /// - `multipart/form-data` -> Form parsing, with `UploadedFile` fields
     /// - Otherwise -> JSON parsing (default)
     ///
     /// Consumes the request since the body can only be read once.
     pub async fn input<T: DeserializeOwned>(self) -> Result<T, FrameworkError> {
         let (parts, bytes) = self.body_bytes().await?;
-
-        match parts.content_type.as_deref() {
-            Some(ct) if ct.starts_with("application/x-www-form-urlencoded") => parse_form(&bytes),
-            _ => parse_json(&bytes),
-        }
+        parse_input(parts.content_type.as_deref(), bytes).await
     }
 
     /// Consume the request and return its parts along with the inner hyper request body
     ///
     /// This is used internally by the handler macro for FormRequest extraction.
-    pub fn into_parts(self) -> (RequestParts, hyper::body::Incoming) {
+    pub fn into_parts(self) -> (RequestParts, RequestBody) {
         let content_type = self
             .inner
             .headers()
@@ -221,6 +587,17 @@ impl Request {
     }
 }
 
+/// See [`Request::wants_json`]; the server also records it for
+/// `respond_with!`, which has no request to ask
+pub(crate) fn wants_json(headers: &hyper::HeaderMap) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    if header("X-Inertia") == Some("true") {
+        return false;
+    }
+    header("Accept").is_some_and(|accept| accept.contains("/json") || accept.contains("+json"))
+        || header("X-Requested-With").is_some_and(|v| v.eq_ignore_ascii_case("XMLHttpRequest"))
+}
+
 /// Request parts after body has been separated
 ///
 /// Contains metadata needed for body parsing without the body itself.
@@ -229,3 +606,51 @@ pub struct RequestParts {
     pub params: HashMap<String, String>,
     pub content_type: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn middleware_values_are_read_by_type() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct CurrentUser(i64);
+
+        let mut req = Request::fake().build();
+        assert_eq!(req.extension::<CurrentUser>(), None);
+        req.extensions_mut().insert(CurrentUser(7));
+        req.extensions_mut().insert(42u8);
+        assert_eq!(req.extension::<CurrentUser>(), Some(&CurrentUser(7)));
+        assert_eq!(req.extensions().get::<u8>(), Some(&42));
+    }
+
+    #[test]
+    fn api_clients_want_json_and_inertia_visits_do_not() {
+        let browser = Request::fake()
+            .header("Accept", "text/html,application/xhtml+xml")
+            .build();
+        assert!(!browser.wants_json());
+        assert!(browser.expects_inertia());
+
+        let api = Request::fake().header("Accept", "application/json").build();
+        assert!(api.wants_json());
+        let problem = Request::fake()
+            .header("Accept", "application/problem+json")
+            .build();
+        assert!(problem.wants_json());
+        let xhr = Request::fake()
+            .header("X-Requested-With", "XMLHttpRequest")
+            .build();
+        assert!(xhr.wants_json());
+
+        // Inertia sends an XHR with `Accept: text/html, application/xhtml+xml`
+        // but some clients add JSON; the X-Inertia header wins either way
+        let inertia = Request::fake()
+            .header("X-Inertia", "true")
+            .header("X-Requested-With", "XMLHttpRequest")
+            .header("Accept", "application/json")
+            .build();
+        assert!(!inertia.wants_json());
+        assert!(inertia.expects_inertia());
+    }
+}
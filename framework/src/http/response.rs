@@ -1,12 +1,53 @@
 use super::cookie::Cookie;
+use super::download::{self, ByteRange, FileBody};
+use super::Request;
+use crate::error::FrameworkError;
+use crate::session::Session;
 use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
 use http_body_util::Full;
+use hyper::body::{Body, Frame, SizeHint};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{interval_at, Instant, Interval};
+
+type ChunkStream = Pin<Box<dyn Stream<Item = Bytes> + Send>>;
 
 /// HTTP Response builder providing Laravel-like response creation
 pub struct HttpResponse {
     status: u16,
-    body: String,
+    body: Bytes,
+    /// Chunks sent in place of `body` as they become available
+    stream: Option<Box<StreamBody>>,
     headers: Vec<(String, String)>,
+    /// Message of the `FrameworkError` this response was built from, kept so
+    /// a group's `ErrorFormat` can render the error again
+    error: Option<String>,
+}
+
+/// A streamed response body
+struct StreamBody {
+    /// Behind a mutex so `HttpResponse` stays `Sync` for middleware
+    chunks: Mutex<ChunkStream>,
+    /// The file the chunks are read from, for answering `Range` requests
+    file: Option<FileBody>,
+}
+
+impl StreamBody {
+    fn new<S>(stream: S, file: Option<FileBody>) -> Box<Self>
+    where
+        S: Stream<Item = Bytes> + Send + 'static,
+    {
+        Box::new(Self {
+            chunks: Mutex::new(Box::pin(stream)),
+            file,
+        })
+    }
 }
 
 /// Response type alias - allows using `?` operator for early returns
@@ -16,8 +57,10 @@ impl HttpResponse {
     pub fn new() -> Self {
         Self {
             status: 200,
-            body: String::new(),
+            body: Bytes::new(),
             headers: Vec::new(),
+            stream: None,
+            error: None,
         }
     }
 
@@ -25,8 +68,10 @@ impl HttpResponse {
     pub fn text(body: impl Into<String>) -> Self {
         Self {
             status: 200,
-            body: body.into(),
+            body: Bytes::from(body.into()),
             headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+            stream: None,
+            error: None,
         }
     }
 
@@ -34,11 +79,152 @@ impl HttpResponse {
     pub fn json(body: serde_json::Value) -> Self {
         Self {
             status: 200,
-            body: body.to_string(),
+            body: Bytes::from(body.to_string()),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            stream: None,
+            error: None,
         }
     }
 
+    /// Create a response with a binary body
+    ///
+    /// The bytes are passed to hyper as-is, so images, PDFs, and proxied
+    /// bodies are never copied or decoded as UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pdf = std::fs::read("invoice.pdf")?;
+    /// HttpResponse::from_bytes("application/pdf", pdf)
+    /// ```
+    pub fn from_bytes(content_type: impl Into<String>, body: impl Into<Bytes>) -> Self {
+        Self {
+            status: 200,
+            body: body.into(),
+            headers: vec![("Content-Type".to_string(), content_type.into())],
+            stream: None,
+            error: None,
+        }
+    }
+
+    /// Create a response whose body is streamed from `stream`
+    pub(crate) fn from_stream<S>(content_type: impl Into<String>, stream: S) -> Self
+    where
+        S: Stream<Item = Bytes> + Send + 'static,
+    {
+        Self {
+            status: 200,
+            body: Bytes::new(),
+            stream: Some(StreamBody::new(stream, None)),
+            headers: vec![("Content-Type".to_string(), content_type.into())],
+            error: None,
+        }
+    }
+
+    /// Create a response that sends chunks from `stream` as they arrive
+    ///
+    /// The body uses chunked transfer encoding and is never held in memory
+    /// as a whole. The Content-Type defaults to `application/octet-stream`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use futures_util::StreamExt;
+    ///
+    /// let rows = Order::stream_all().await?.map(|order| Bytes::from(order.to_csv_line()));
+    /// HttpResponse::stream(rows).content_type("text/csv")
+    /// ```
+    pub fn stream<S>(stream: S) -> Self
+    where
+        S: Stream<Item = Bytes> + Send + 'static,
+    {
+        Self::from_stream("application/octet-stream", stream)
+    }
+
+    /// Send the file at `path` as an attachment saved as `filename`
+    ///
+    /// The file is streamed from disk and `Range` requests are answered
+    /// with `206 Partial Content`, so large exports can be resumed. A
+    /// missing file is a 404.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// HttpResponse::download(export.path(), "orders-2024.csv")
+    /// ```
+    pub fn download(path: impl AsRef<Path>, filename: &str) -> Self {
+        Self::file(
+            path.as_ref(),
+            download::content_disposition("attachment", filename),
+        )
+    }
+
+    /// Send the file at `path` for the browser to display, e.g. a PDF or video
+    ///
+    /// Streams and supports `Range` requests like `download`.
+    pub fn inline_file(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self::file(path, download::content_disposition("inline", &filename))
+    }
+
+    fn file(path: &Path, disposition: String) -> Self {
+        let len = match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            _ => return FrameworkError::domain("File not found", 404).into(),
+        };
+        let file = FileBody {
+            path: path.to_path_buf(),
+            len,
+        };
+        let mut response = Self::new()
+            .header(
+                "Content-Type",
+                crate::routing::static_files::content_type(path),
+            )
+            .header("Content-Disposition", disposition)
+            .header("Content-Length", len.to_string())
+            .header("Accept-Ranges", "bytes");
+        response.stream = Some(StreamBody::new(
+            download::read(file.path.clone(), 0, len),
+            Some(file),
+        ));
+        response
+    }
+
+    /// Answer a `Range` header for a file response, see `download`
+    pub(crate) fn with_range(mut self, range: Option<&str>) -> Self {
+        let file = self.stream.as_ref().and_then(|stream| stream.file.clone());
+        let (Some(file), Some(range)) = (file, range) else {
+            return self;
+        };
+        if self.status != 200 {
+            return self;
+        }
+        match download::parse_range(range, file.len) {
+            Some(ByteRange::Satisfiable(start, end)) => {
+                let stream = download::read(file.path.clone(), start, end - start + 1);
+                let content_range = format!("bytes {}-{}/{}", start, end, file.len);
+                self.stream = Some(StreamBody::new(stream, Some(file)));
+                self.status = 206;
+                self.set_header("Content-Length", (end - start + 1).to_string());
+                self.set_header("Content-Range", content_range);
+            }
+            Some(ByteRange::Unsatisfiable) => {
+                let content_range = format!("bytes */{}", file.len);
+                self.stream = None;
+                self.status = 416;
+                self.set_header("Content-Length", "0");
+                self.set_header("Content-Range", content_range);
+            }
+            None => {}
+        }
+        self
+    }
+
     /// Set the HTTP status code
     pub fn status(mut self, status: u16) -> Self {
         self.status = status;
@@ -51,6 +237,18 @@ impl HttpResponse {
         self
     }
 
+    /// Set the Content-Type, replacing the one set by the constructor
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.set_header("Content-Type", content_type);
+        self
+    }
+
+    fn set_header(&mut self, name: &str, value: impl Into<String>) {
+        self.headers
+            .retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.into()));
+    }
+
     /// Add a Set-Cookie header to the response
     ///
     /// # Example
@@ -71,15 +269,104 @@ impl HttpResponse {
         Ok(self)
     }
 
+    /// The error message this response was built from, if any
+    pub(crate) fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Take the error message this response was built from, if any
+    pub(crate) fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    /// The HTTP status code
+    pub(crate) fn status_code(&self) -> u16 {
+        self.status
+    }
+
+    /// The response body
+    pub(crate) fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Replace the body, keeping the status and headers
+    pub(crate) fn with_body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self.stream = None;
+        self
+    }
+
+    /// The response headers, in the order they were added
+    pub(crate) fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
     /// Convert to hyper response
-    pub fn into_hyper(self) -> hyper::Response<Full<Bytes>> {
+    pub fn into_hyper(self) -> hyper::Response<ResponseBody> {
         let mut builder = hyper::Response::builder().status(self.status);
 
         for (name, value) in self.headers {
             builder = builder.header(name, value);
         }
 
-        builder.body(Full::new(Bytes::from(self.body))).unwrap()
+        let body = match self.stream {
+            Some(stream) => ResponseBody::Stream(
+                stream
+                    .chunks
+                    .into_inner()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            ),
+            None => ResponseBody::Full(Full::new(self.body)),
+        };
+        builder.body(body).unwrap()
+    }
+}
+
+/// Body of a response sent by the server
+///
+/// Either the whole body at once or, for streamed responses such as
+/// `SseResponse`, chunks as they become available.
+pub enum ResponseBody {
+    /// Body already in memory
+    Full(Full<Bytes>),
+    /// Body produced chunk by chunk
+    Stream(Pin<Box<dyn Stream<Item = Bytes> + Send>>),
+}
+
+impl From<Full<Bytes>> for ResponseBody {
+    fn from(body: Full<Bytes>) -> Self {
+        ResponseBody::Full(body)
+    }
+}
+
+impl Body for ResponseBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        match self.get_mut() {
+            ResponseBody::Full(body) => Pin::new(body).poll_frame(cx),
+            ResponseBody::Stream(stream) => stream
+                .poll_next_unpin(cx)
+                .map(|chunk| chunk.map(|bytes| Ok(Frame::data(bytes)))),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match self {
+            ResponseBody::Full(body) => body.is_end_stream(),
+            ResponseBody::Stream(_) => false,
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match self {
+            ResponseBody::Full(body) => body.size_hint(),
+            ResponseBody::Stream(_) => SizeHint::default(),
+        }
     }
 }
 
@@ -110,15 +397,19 @@ pub struct Redirect {
     location: String,
     query_params: Vec<(String, String)>,
     status: u16,
+    base_url: Option<String>,
 }
 
 impl Redirect {
     /// Create a redirect to a specific URL/path
+    ///
+    /// External URLs work too: `Redirect::to("https://example.com")`.
     pub fn to(path: impl Into<String>) -> Self {
         Self {
             location: path.into(),
             query_params: Vec::new(),
             status: 302,
+            base_url: None,
         }
     }
 
@@ -129,33 +420,67 @@ impl Redirect {
             params: std::collections::HashMap::new(),
             query_params: Vec::new(),
             status: 302,
+            base_url: None,
         }
     }
 
     /// Add a query parameter
+    ///
+    /// Replaces a parameter of the same name already in the URL; other
+    /// parameters in the URL are kept.
     pub fn query(mut self, key: &str, value: impl Into<String>) -> Self {
         self.query_params.push((key.to_string(), value.into()));
         self
     }
 
+    /// Add several query parameters, e.g. from a `HashMap`
+    pub fn query_map<K, V>(mut self, params: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.query_params
+            .extend(params.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Flash a value, such as a status message, for the page redirected to
+    ///
+    /// It's read there with `req.flash()` and is shared with Inertia pages
+    /// as the `flash` prop. Needs `SessionMiddleware`.
+    pub fn with_flash(self, key: &str, value: impl Serialize) -> Self {
+        flash(key, value);
+        self
+    }
+
     /// Set status to 301 (Moved Permanently)
-    pub fn permanent(mut self) -> Self {
-        self.status = 301;
+    pub fn permanent(self) -> Self {
+        self.status(301)
+    }
+
+    /// Set the redirect status code (e.g. 303 or 307)
+    pub fn status(mut self, status: u16) -> Self {
+        debug_assert!(
+            (300..400).contains(&status),
+            "Redirect status must be 3xx, got {}",
+            status
+        );
+        self.status = status;
+        self
+    }
+
+    /// Make a path absolute using the request's scheme and host
+    ///
+    /// Behind a trusted proxy these come from the forwarded headers (see
+    /// `TrustedProxies`). URLs that are already absolute are left alone.
+    pub fn absolute(mut self, req: &Request) -> Self {
+        self.base_url = Some(req.base_url());
         self
     }
 
     fn build_url(&self) -> String {
-        if self.query_params.is_empty() {
-            self.location.clone()
-        } else {
-            let query = self
-                .query_params
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<_>>()
-                .join("&");
-            format!("{}?{}", self.location, query)
-        }
+        let url = merge_query(&self.location, &self.query_params);
+        with_base_url(self.base_url.as_deref(), url)
     }
 }
 
@@ -174,6 +499,7 @@ pub struct RedirectRouteBuilder {
     params: std::collections::HashMap<String, String>,
     query_params: Vec<(String, String)>,
     status: u16,
+    base_url: Option<String>,
 }
 
 impl RedirectRouteBuilder {
@@ -189,26 +515,51 @@ impl RedirectRouteBuilder {
         self
     }
 
+    /// Add several query parameters, e.g. from a `HashMap`
+    pub fn query_map<K, V>(mut self, params: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.query_params
+            .extend(params.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Flash a value for the page redirected to (see `Redirect::with_flash`)
+    pub fn with_flash(self, key: &str, value: impl Serialize) -> Self {
+        flash(key, value);
+        self
+    }
+
     /// Set status to 301 (Moved Permanently)
-    pub fn permanent(mut self) -> Self {
-        self.status = 301;
+    pub fn permanent(self) -> Self {
+        self.status(301)
+    }
+
+    /// Set the redirect status code (e.g. 303 or 307)
+    pub fn status(mut self, status: u16) -> Self {
+        debug_assert!(
+            (300..400).contains(&status),
+            "Redirect status must be 3xx, got {}",
+            status
+        );
+        self.status = status;
+        self
+    }
+
+    /// Redirect to the absolute URL of the route (see `Redirect::absolute`)
+    pub fn absolute(mut self, req: &Request) -> Self {
+        self.base_url = Some(req.base_url());
         self
     }
 
     fn build_url(&self) -> Option<String> {
         use crate::routing::route_with_params;
 
-        let mut url = route_with_params(&self.name, &self.params)?;
-        if !self.query_params.is_empty() {
-            let query = self
-                .query_params
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<_>>()
-                .join("&");
-            url = format!("{}?{}", url, query);
-        }
-        Some(url)
+        let url = route_with_params(&self.name, &self.params)?;
+        let url = merge_query(&url, &self.query_params);
+        Some(with_base_url(self.base_url.as_deref(), url))
     }
 }
 
@@ -224,42 +575,235 @@ impl From<RedirectRouteBuilder> for Response {
     }
 }
 
+/// Flash a value into the current session, if there is one
+fn flash(key: &str, value: impl Serialize) {
+    if let Some(session) = Session::current() {
+        session.flash(key, value);
+    }
+}
+
+/// Prefix a path with `scheme://host`
+fn with_base_url(base_url: Option<&str>, url: String) -> String {
+    match base_url {
+        Some(base_url) if url.starts_with('/') && !url.starts_with("//") => {
+            format!("{}{}", base_url, url)
+        }
+        _ => url,
+    }
+}
+
+/// Add query parameters to a URL, keeping its existing query and fragment
+///
+/// A parameter replaces any parameter of the same name already in the URL.
+fn merge_query(url: &str, params: &[(String, String)]) -> String {
+    if params.is_empty() {
+        return url.to_string();
+    }
+
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+    let (path, existing) = url.split_once('?').unwrap_or((url, ""));
+
+    let mut query: Vec<(String, String)> = serde_urlencoded::from_str(existing).unwrap_or_default();
+    query.retain(|(key, _)| !params.iter().any(|(k, _)| k == key));
+    query.extend(params.iter().cloned());
+
+    let mut merged = format!(
+        "{}?{}",
+        path,
+        serde_urlencoded::to_string(&query).unwrap_or_default()
+    );
+    if let Some(fragment) = fragment {
+        merged.push('#');
+        merged.push_str(fragment);
+    }
+    merged
+}
+
+/// One Server-Sent Event
+///
+/// # Example
+///
+/// ```rust,ignore
+/// SseEvent::json(&notification).event("notification").id(notification.id.to_string())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SseEvent {
+    event: Option<String>,
+    data: String,
+    id: Option<String>,
+    retry: Option<u64>,
+}
+
+impl SseEvent {
+    /// An event carrying `data`; multi-line data is split across `data:` lines
+    pub fn new(data: impl Into<String>) -> Self {
+        Self {
+            data: data.into(),
+            ..Self::default()
+        }
+    }
+
+    /// An event carrying `value` serialized as JSON
+    pub fn json<T: Serialize>(value: &T) -> Self {
+        Self::new(serde_json::to_string(value).unwrap_or_default())
+    }
+
+    /// Set the event name, for `EventSource.addEventListener(name, ...)`
+    pub fn event(mut self, name: impl Into<String>) -> Self {
+        self.event = Some(name.into());
+        self
+    }
+
+    /// Set the event id, sent back by the browser as `Last-Event-ID` on reconnect
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Tell the browser how many milliseconds to wait before reconnecting
+    pub fn retry(mut self, millis: u64) -> Self {
+        self.retry = Some(millis);
+        self
+    }
+
+    /// The event in the `text/event-stream` wire format
+    pub fn to_bytes(&self) -> Bytes {
+        let mut out = String::new();
+        if let Some(event) = &self.event {
+            out.push_str(&format!("event: {}\n", single_line(event)));
+        }
+        if let Some(id) = &self.id {
+            out.push_str(&format!("id: {}\n", single_line(id)));
+        }
+        if let Some(retry) = self.retry {
+            out.push_str(&format!("retry: {}\n", retry));
+        }
+        for line in self.data.split('\n') {
+            out.push_str(&format!("data: {}\n", line.trim_end_matches('\r')));
+        }
+        out.push('\n');
+        Bytes::from(out)
+    }
+}
+
+impl From<String> for SseEvent {
+    fn from(data: String) -> Self {
+        Self::new(data)
+    }
+}
+
+impl From<&str> for SseEvent {
+    fn from(data: &str) -> Self {
+        Self::new(data)
+    }
+}
+
+/// Newlines would end the field early, so they're replaced with spaces
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// A `text/event-stream` response streaming events to an `EventSource`
+///
+/// A keep-alive comment is sent every 15 seconds without events so proxies
+/// don't close the idle connection. The response ends when the stream does.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use futures_util::StreamExt;
+/// use kit::{sse_response, SseEvent};
+///
+/// #[handler]
+/// pub async fn notifications(req: Request) -> Response {
+///     let updates = Notifications::subscribe(req.user_id()?)
+///         .map(|notification| SseEvent::json(&notification).event("notification"));
+///     sse_response!(updates)
+/// }
+/// ```
+pub struct SseResponse {
+    events: Pin<Box<dyn Stream<Item = SseEvent> + Send>>,
+    keep_alive: Option<Duration>,
+}
+
+impl SseResponse {
+    /// Stream events from `events`
+    pub fn new<S, E>(events: S) -> Self
+    where
+        S: Stream<Item = E> + Send + 'static,
+        E: Into<SseEvent> + 'static,
+    {
+        Self {
+            events: Box::pin(events.map(Into::into)),
+            keep_alive: Some(Duration::from_secs(15)),
+        }
+    }
+
+    /// Interval between keep-alive comments (`None` disables them)
+    pub fn keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.keep_alive = interval;
+        self
+    }
+}
+
+impl From<SseResponse> for HttpResponse {
+    fn from(sse: SseResponse) -> HttpResponse {
+        let chunks = KeepAlive {
+            events: sse.events,
+            ping: sse
+                .keep_alive
+                .map(|period| interval_at(Instant::now() + period, period)),
+        };
+        HttpResponse::from_stream("text/event-stream", chunks)
+            .header("Cache-Control", "no-cache")
+            .header("X-Accel-Buffering", "no")
+    }
+}
+
+/// Encoded events, with a keep-alive comment whenever none arrive in time
+struct KeepAlive {
+    events: Pin<Box<dyn Stream<Item = SseEvent> + Send>>,
+    ping: Option<Interval>,
+}
+
+impl Stream for KeepAlive {
+    type Item = Bytes;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Bytes>> {
+        let this = self.get_mut();
+        if let Poll::Ready(event) = this.events.poll_next_unpin(cx) {
+            if let Some(ping) = &mut this.ping {
+                ping.reset();
+            }
+            return Poll::Ready(event.map(|event| event.to_bytes()));
+        }
+        match this.ping.as_mut().map(|ping| ping.poll_tick(cx)) {
+            Some(Poll::Ready(_)) => Poll::Ready(Some(Bytes::from_static(b": keep-alive\n\n"))),
+            _ => Poll::Pending,
+        }
+    }
+}
+
 /// Auto-convert FrameworkError to HttpResponse
 ///
 /// This enables using the `?` operator in controller handlers to propagate
-/// framework errors as appropriate HTTP responses.
+/// framework errors as appropriate HTTP responses. The body depends on
+/// `APP_DEBUG`, see `error_body`.
 impl From<crate::error::FrameworkError> for HttpResponse {
     fn from(err: crate::error::FrameworkError) -> HttpResponse {
-        let status = err.status_code();
-        let body = match &err {
-            crate::error::FrameworkError::ParamError { param_name } => {
-                serde_json::json!({
-                    "error": format!("Missing required parameter: {}", param_name)
-                })
-            }
-            crate::error::FrameworkError::ValidationError { field, message } => {
-                serde_json::json!({
-                    "error": "Validation failed",
-                    "field": field,
-                    "message": message
-                })
-            }
-            crate::error::FrameworkError::Validation(errors) => {
-                // Laravel/Inertia-compatible validation error format
-                errors.to_json()
-            }
-            crate::error::FrameworkError::Unauthorized => {
-                serde_json::json!({
-                    "message": "This action is unauthorized."
-                })
-            }
-            _ => {
-                serde_json::json!({
-                    "error": err.to_string()
-                })
-            }
-        };
-        HttpResponse::json(body).status(status)
+        if let Some(response) = super::exception::handle(&err) {
+            return response;
+        }
+        let (body, request_id) = super::error_body::render(&err);
+        let mut response = HttpResponse::json(body).status(err.status_code());
+        if let Some(request_id) = request_id {
+            response = response.header("X-Request-Id", request_id);
+        }
+        response.error = Some(err.to_string());
+        response
     }
 }
 
@@ -273,3 +817,121 @@ impl From<crate::error::AppError> for HttpResponse {
         framework_err.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    #[tokio::test]
+    async fn test_from_bytes_keeps_binary_body() {
+        let png = Bytes::from_static(&[0x89, b'P', b'N', b'G', 0xff, 0x00]);
+        let response = HttpResponse::from_bytes("image/png", png.clone()).into_hyper();
+
+        assert_eq!(response.headers()["Content-Type"], "image/png");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, png);
+    }
+
+    fn location(response: Response) -> (u16, String) {
+        let Ok(response) = response else {
+            panic!("expected a redirect");
+        };
+        let location = response
+            .headers()
+            .iter()
+            .find(|(name, _)| name == "Location")
+            .map(|(_, value)| value.clone())
+            .unwrap();
+        (response.status_code(), location)
+    }
+
+    #[test]
+    fn test_redirect_merges_query() {
+        let redirect = Redirect::to("https://example.com/search?q=kit&page=1#results")
+            .query("page", "2")
+            .query_map([("sort", "new & hot")]);
+
+        assert_eq!(
+            location(redirect.into()),
+            (
+                302,
+                "https://example.com/search?q=kit&page=2&sort=new+%26+hot#results".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_redirect_status() {
+        assert_eq!(location(Redirect::to("/").permanent().into()).0, 301);
+        assert_eq!(location(Redirect::to("/").status(307).into()).0, 307);
+        assert_eq!(location(Redirect::to("/").into()), (302, "/".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_with_flash_reaches_the_next_page() {
+        use crate::inertia::InertiaResponse;
+        use crate::session::{session_mut, SessionData};
+
+        let session = Session::new(SessionData::new("abc".to_string(), "token".to_string()));
+        let next_page = session.clone().scope(async {
+            let response = Redirect::to("/users").with_flash("success", "User created");
+            assert_eq!(location(response.into()).1, "/users");
+            assert!(Request::fake().build().flash().is_empty());
+
+            // The next request
+            session_mut(|data| data.age_flash_data());
+            let flash = Request::fake().build().flash();
+            let page = InertiaResponse::new("Users/Index", serde_json::json!({}), "/users".into());
+            (flash, page.to_json_response())
+        });
+        let (flash, page) = next_page.await;
+
+        assert_eq!(flash["success"], "User created");
+        let page: serde_json::Value = serde_json::from_slice(page.body()).unwrap();
+        assert_eq!(
+            page["props"]["flash"],
+            serde_json::json!({ "success": "User created" })
+        );
+    }
+
+    #[test]
+    fn test_sse_event_format() {
+        let event = SseEvent::new("line one\nline two")
+            .event("update")
+            .id("7")
+            .retry(3000);
+        assert_eq!(
+            event.to_bytes(),
+            "event: update\nid: 7\nretry: 3000\ndata: line one\ndata: line two\n\n"
+        );
+        assert_eq!(
+            SseEvent::json(&serde_json::json!({ "count": 1 })).to_bytes(),
+            "data: {\"count\":1}\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sse_response_streams_events() {
+        let events = futures_util::stream::iter(["first", "second"]);
+        let response = HttpResponse::from(SseResponse::new(events)).into_hyper();
+
+        assert_eq!(response.headers()["Content-Type"], "text/event-stream");
+        assert_eq!(response.headers()["Cache-Control"], "no-cache");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "data: first\n\ndata: second\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_sse_response_sends_keep_alive_while_idle() {
+        let events = futures_util::stream::pending::<SseEvent>();
+        let response = HttpResponse::from(
+            SseResponse::new(events).keep_alive(Some(Duration::from_millis(10))),
+        )
+        .into_hyper();
+
+        let mut body = response.into_body();
+        let frame = body.frame().await.unwrap().unwrap();
+        assert_eq!(frame.into_data().unwrap(), ": keep-alive\n\n");
+    }
+}
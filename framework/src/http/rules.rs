@@ -0,0 +1,128 @@
+//! Async validation rules for `#[request]` structs
+
+use crate::container::testing::TestContainer;
+use crate::container::App;
+use crate::error::{FrameworkError, ValidationErrors};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A validation rule that may do I/O, such as a uniqueness check
+///
+/// Register it under a name with `Rules::register` and use the name in
+/// `#[validate(custom = "...")]` on `#[request]` fields. The rule runs after
+/// the `validator` rules, and only for fields that passed them, so an
+/// invalid email never reaches the database.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{async_trait, request, FrameworkError, Rule, Rules};
+///
+/// pub struct UniqueEmail;
+///
+/// #[async_trait]
+/// impl Rule for UniqueEmail {
+///     async fn passes(&self, value: &serde_json::Value) -> Result<bool, FrameworkError> {
+///         let email = value.as_str().unwrap_or_default();
+///         Ok(User::find_by_email(email).await?.is_none())
+///     }
+///
+///     fn message(&self, field: &str) -> String {
+///         format!("The {} has already been taken.", field)
+///     }
+/// }
+///
+/// // In bootstrap.rs
+/// Rules::register("unique_email", UniqueEmail);
+///
+/// #[request]
+/// pub struct RegisterRequest {
+///     #[validate(email, custom = "unique_email")]
+///     pub email: String,
+/// }
+/// ```
+#[async_trait]
+pub trait Rule: Send + Sync + 'static {
+    /// Whether `value`, the field serialized to JSON, is valid
+    ///
+    /// Errors fail the request rather than the field, e.g. when the
+    /// database can't be reached.
+    async fn passes(&self, value: &Value) -> Result<bool, FrameworkError>;
+
+    /// The error message for `field` when the value is invalid
+    fn message(&self, field: &str) -> String {
+        format!("The {} is invalid.", field)
+    }
+}
+
+/// Registry of named rules, kept in the container
+#[derive(Clone, Default)]
+struct NamedRules(HashMap<String, Arc<dyn Rule>>);
+
+/// Rules facade - registers the rules used by `#[validate(custom = "...")]`
+pub struct Rules;
+
+impl Rules {
+    /// Register `rule` under `name`, replacing any rule with that name
+    ///
+    /// Inside a `TestContainer::fake()` the rule is only registered for the
+    /// test.
+    pub fn register(name: impl Into<String>, rule: impl Rule) {
+        let mut rules = App::get::<NamedRules>().unwrap_or_default();
+        rules.0.insert(name.into(), Arc::new(rule));
+        if TestContainer::is_active() {
+            TestContainer::singleton(rules);
+        } else {
+            App::singleton(rules);
+        }
+    }
+
+    /// The rule registered under `name`
+    pub fn get(name: &str) -> Option<Arc<dyn Rule>> {
+        App::get::<NamedRules>().and_then(|rules| rules.0.get(name).cloned())
+    }
+
+    /// Run `checks`, adding messages for the values that fail
+    ///
+    /// Fields that already have errors are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rule isn't registered or fails to run.
+    pub async fn check(
+        checks: Vec<RuleCheck>,
+        errors: &mut ValidationErrors,
+    ) -> Result<(), FrameworkError> {
+        for check in checks {
+            if check.value.is_null() || errors.errors.contains_key(check.field) {
+                continue;
+            }
+            let rule = Self::get(check.rule).ok_or_else(|| {
+                FrameworkError::internal(format!(
+                    "Validation rule '{}' is not registered",
+                    check.rule
+                ))
+            })?;
+            if !rule.passes(&check.value).await? {
+                errors.add(check.field, rule.message(check.field));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A field value to check with a named rule
+///
+/// `#[request]` builds these from `#[validate(custom = "...")]`; missing
+/// `Option` values are serialized as `null` and skipped.
+#[derive(Debug, Clone)]
+pub struct RuleCheck {
+    /// The field name used in error messages
+    pub field: &'static str,
+    /// The name the rule was registered under
+    pub rule: &'static str,
+    /// The field serialized to JSON
+    pub value: Value,
+}
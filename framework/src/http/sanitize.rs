@@ -0,0 +1,622 @@
+//! Allowlist-based HTML sanitization for user-generated rich text
+//!
+//! Tags and attributes not allowed by the policy are removed; the text inside
+//! removed tags is kept, except for elements like `<script>` and `<style>`,
+//! which are dropped with their content. Comments are always removed, URL
+//! attributes only keep `http`, `https`, `mailto` and relative URLs, and open
+//! tags are closed so the output can't break the surrounding page.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{sanitize_html, HtmlPolicy};
+//!
+//! let html = sanitize_html(
+//!     r#"<p onclick="steal()">Hi <script>alert(1)</script><b>there</b>"#,
+//!     &HtmlPolicy::basic(),
+//! );
+//! assert_eq!(html, "<p>Hi <b>there</b></p>");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+/// Elements removed together with everything inside them
+const DROP_WITH_CONTENT: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "noscript", "template", "textarea", "select",
+    "title", "head", "svg", "math",
+];
+
+/// Elements without a closing tag
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "wbr"];
+
+/// Attributes holding URLs, checked against the allowed schemes
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "cite"];
+
+const BASIC_TAGS: &[(&str, &[&str])] = &[
+    ("a", &["href", "title"]),
+    ("b", &[]),
+    ("blockquote", &[]),
+    ("br", &[]),
+    ("code", &[]),
+    ("em", &[]),
+    ("i", &[]),
+    ("li", &[]),
+    ("ol", &[]),
+    ("p", &[]),
+    ("pre", &[]),
+    ("s", &[]),
+    ("strong", &[]),
+    ("u", &[]),
+    ("ul", &[]),
+];
+
+const RELAXED_TAGS: &[(&str, &[&str])] = &[
+    ("abbr", &["title"]),
+    ("caption", &[]),
+    ("dd", &[]),
+    ("del", &[]),
+    ("div", &[]),
+    ("dl", &[]),
+    ("dt", &[]),
+    ("figcaption", &[]),
+    ("figure", &[]),
+    ("h1", &[]),
+    ("h2", &[]),
+    ("h3", &[]),
+    ("h4", &[]),
+    ("h5", &[]),
+    ("h6", &[]),
+    ("hr", &[]),
+    ("img", &["src", "alt", "title", "width", "height"]),
+    ("ins", &[]),
+    ("mark", &[]),
+    ("small", &[]),
+    ("span", &[]),
+    ("sub", &[]),
+    ("sup", &[]),
+    ("table", &[]),
+    ("tbody", &[]),
+    ("td", &["colspan", "rowspan"]),
+    ("tfoot", &[]),
+    ("th", &["colspan", "rowspan"]),
+    ("thead", &[]),
+    ("tr", &[]),
+];
+
+/// The tags and attributes `sanitize_html` keeps
+///
+/// - `strict()` - no tags, only the text
+/// - `basic()` - inline formatting, paragraphs, lists, code, quotes and links
+/// - `relaxed()` - `basic()` plus headings, images, tables and layout tags
+///
+/// Presets can be extended:
+///
+/// ```rust,ignore
+/// use kit::HtmlPolicy;
+///
+/// let policy = HtmlPolicy::basic()
+///     .allow_tag("h2", &[])
+///     .allow_tag("a", &["href", "title", "rel"])
+///     .allow_scheme("tel");
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmlPolicy {
+    tags: HashMap<String, HashSet<String>>,
+    schemes: HashSet<String>,
+}
+
+impl HtmlPolicy {
+    /// Strip every tag, keeping the text
+    pub fn strict() -> Self {
+        Self {
+            tags: HashMap::new(),
+            schemes: ["http", "https", "mailto"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+
+    /// Allow inline formatting, paragraphs, lists, code, quotes and links
+    pub fn basic() -> Self {
+        BASIC_TAGS
+            .iter()
+            .fold(Self::strict(), |policy, (tag, attributes)| {
+                policy.allow_tag(tag, attributes)
+            })
+    }
+
+    /// Allow `basic()` plus headings, images, tables and layout tags
+    pub fn relaxed() -> Self {
+        RELAXED_TAGS
+            .iter()
+            .fold(Self::basic(), |policy, (tag, attributes)| {
+                policy.allow_tag(tag, attributes)
+            })
+    }
+
+    /// Allow a tag and the given attributes on it
+    ///
+    /// Attributes add to the ones already allowed on the tag.
+    pub fn allow_tag(mut self, tag: &str, attributes: &[&str]) -> Self {
+        self.tags
+            .entry(tag.to_ascii_lowercase())
+            .or_default()
+            .extend(attributes.iter().map(|a| a.to_ascii_lowercase()));
+        self
+    }
+
+    /// Allow URLs with this scheme in `href` and `src`, e.g. `"tel"`
+    pub fn allow_scheme(mut self, scheme: &str) -> Self {
+        self.schemes.insert(scheme.to_ascii_lowercase());
+        self
+    }
+
+    fn allows_tag(&self, tag: &str) -> bool {
+        self.tags.contains_key(tag)
+    }
+
+    fn allows_attribute(&self, tag: &str, attribute: &str, value: &str) -> bool {
+        let allowed = self
+            .tags
+            .get(tag)
+            .is_some_and(|attributes| attributes.contains(attribute));
+        allowed && (!URL_ATTRIBUTES.contains(&attribute) || self.allows_url(value))
+    }
+
+    /// Relative URLs are allowed; absolute ones need an allowed scheme
+    fn allows_url(&self, url: &str) -> bool {
+        // Browsers ignore whitespace and control characters in the scheme,
+        // so "java\tscript:" must not slip through
+        let url: String = decode_entities(url)
+            .chars()
+            .filter(|c| !c.is_whitespace() && !c.is_control())
+            .collect();
+        match url.find([':', '/', '?', '#']) {
+            Some(i) if url[i..].starts_with(':') => {
+                self.schemes.contains(&url[..i].to_ascii_lowercase())
+            }
+            _ => true,
+        }
+    }
+}
+
+impl Default for HtmlPolicy {
+    fn default() -> Self {
+        Self::basic()
+    }
+}
+
+/// Remove the tags and attributes `policy` doesn't allow from `input`
+///
+/// See the module docs for what is removed.
+pub fn sanitize_html(input: &str, policy: &HtmlPolicy) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut open: Vec<String> = Vec::new();
+    let mut rest = input;
+
+    while let Some(start) = rest.find('<') {
+        push_text(&mut output, &rest[..start]);
+        rest = &rest[start..];
+
+        let Some(tag) = parse_tag(rest) else {
+            output.push_str("&lt;");
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.len..];
+
+        match tag.kind {
+            TagKind::Other => {}
+            TagKind::Start {
+                name,
+                attributes,
+                self_closing,
+            } => {
+                if DROP_WITH_CONTENT.contains(&name.as_str()) {
+                    if !self_closing {
+                        rest = skip_element(rest, &name);
+                    }
+                } else if policy.allows_tag(&name) {
+                    output.push('<');
+                    output.push_str(&name);
+                    for (attribute, value) in attributes {
+                        if policy.allows_attribute(&name, &attribute, &value) {
+                            output.push(' ');
+                            output.push_str(&attribute);
+                            output.push_str("=\"");
+                            push_attribute_value(&mut output, &value);
+                            output.push('"');
+                        }
+                    }
+                    output.push('>');
+                    if !VOID_ELEMENTS.contains(&name.as_str()) {
+                        open.push(name);
+                    }
+                }
+            }
+            TagKind::End { name } => {
+                // Close the matching tag and anything left open inside it;
+                // stray closing tags are dropped
+                if let Some(i) = open.iter().rposition(|tag| *tag == name) {
+                    for tag in open.drain(i..).rev() {
+                        push_end_tag(&mut output, &tag);
+                    }
+                }
+            }
+        }
+    }
+    push_text(&mut output, rest);
+
+    for tag in open.iter().rev() {
+        push_end_tag(&mut output, tag);
+    }
+    output
+}
+
+/// Fields the `#[transform(sanitize_html)]` request attribute can clean
+pub trait SanitizeHtml {
+    /// Sanitize the field in place
+    fn sanitize_html(&mut self, policy: &HtmlPolicy);
+}
+
+impl SanitizeHtml for String {
+    fn sanitize_html(&mut self, policy: &HtmlPolicy) {
+        *self = sanitize_html(self, policy);
+    }
+}
+
+impl<T: SanitizeHtml> SanitizeHtml for Option<T> {
+    fn sanitize_html(&mut self, policy: &HtmlPolicy) {
+        if let Some(value) = self {
+            value.sanitize_html(policy);
+        }
+    }
+}
+
+impl<T: SanitizeHtml> SanitizeHtml for Vec<T> {
+    fn sanitize_html(&mut self, policy: &HtmlPolicy) {
+        for value in self {
+            value.sanitize_html(policy);
+        }
+    }
+}
+
+/// Attribute names and values, in source order
+type Attributes = Vec<(String, String)>;
+
+struct Tag {
+    kind: TagKind,
+    /// Bytes of input the tag spans, including `<` and `>`
+    len: usize,
+}
+
+enum TagKind {
+    Start {
+        name: String,
+        attributes: Attributes,
+        self_closing: bool,
+    },
+    End {
+        name: String,
+    },
+    /// Comments, doctypes and processing instructions
+    Other,
+}
+
+/// Parse the tag at the start of `input`, which begins with `<`
+///
+/// Returns `None` when the `<` doesn't start a tag and is plain text.
+fn parse_tag(input: &str) -> Option<Tag> {
+    let after = &input[1..];
+
+    if let Some(comment) = after.strip_prefix("!--") {
+        let len = comment.find("-->").map_or(input.len(), |end| 4 + end + 3);
+        return Some(Tag {
+            kind: TagKind::Other,
+            len,
+        });
+    }
+    if after.starts_with(['!', '?']) {
+        let len = after.find('>').map_or(input.len(), |end| end + 2);
+        return Some(Tag {
+            kind: TagKind::Other,
+            len,
+        });
+    }
+
+    let (is_end, after) = match after.strip_prefix('/') {
+        Some(after) => (true, after),
+        None => (false, after),
+    };
+    if !after.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let name_len = after
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .unwrap_or(after.len());
+    let name = after[..name_len].to_ascii_lowercase();
+    // Browsers drop a tag left unterminated at the end of the input
+    let Some((attributes, self_closing, body_len)) = parse_attributes(&after[name_len..]) else {
+        return Some(Tag {
+            kind: TagKind::Other,
+            len: input.len(),
+        });
+    };
+    let len = input.len() - after.len() + name_len + body_len;
+
+    let kind = if is_end {
+        TagKind::End { name }
+    } else {
+        TagKind::Start {
+            name,
+            attributes,
+            self_closing,
+        }
+    };
+    Some(Tag { kind, len })
+}
+
+/// Parse attributes up to and including the closing `>`
+///
+/// Returns the attributes, whether the tag ends in `/>`, and the bytes
+/// consumed, or `None` if the input ends before the `>`.
+fn parse_attributes(input: &str) -> Option<(Attributes, bool, usize)> {
+    let mut attributes = Vec::new();
+    let mut self_closing = false;
+    let mut pos = 0;
+
+    loop {
+        let rest = &input[pos..];
+        let c = rest.chars().next()?;
+        if c == '>' {
+            return Some((attributes, self_closing, pos + 1));
+        }
+        if c.is_whitespace() || c == '/' {
+            self_closing = c == '/';
+            pos += c.len_utf8();
+            continue;
+        }
+        self_closing = false;
+
+        let name_len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(rest.len());
+        let name = rest[..name_len].to_ascii_lowercase();
+        pos += name_len;
+
+        let after_name = &input[pos..];
+        let trimmed = after_name.trim_start();
+        let mut value = String::new();
+        if let Some(after_eq) = trimmed.strip_prefix('=') {
+            let value_start = after_eq.trim_start();
+            pos += after_name.len() - value_start.len();
+            let (raw, consumed) = match value_start.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &value_start[1..];
+                    let end = inner.find(quote)?;
+                    (&inner[..end], end + 2)
+                }
+                _ => {
+                    let end = value_start
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(value_start.len());
+                    (&value_start[..end], end)
+                }
+            };
+            value = raw.to_string();
+            pos += consumed;
+        }
+
+        if !attributes.iter().any(|(existing, _)| *existing == name) {
+            attributes.push((name, value));
+        }
+    }
+}
+
+/// Skip past the closing tag of `name`, or to the end of the input
+fn skip_element<'a>(input: &'a str, name: &str) -> &'a str {
+    let lower = input.to_ascii_lowercase();
+    let closing = format!("</{}", name);
+    let mut from = 0;
+    while let Some(i) = lower[from..].find(&closing) {
+        let start = from + i;
+        let after = &lower[start + closing.len()..];
+        if after.starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+            return match after.find('>') {
+                Some(end) => &input[start + closing.len() + end + 1..],
+                None => "",
+            };
+        }
+        from = start + closing.len();
+    }
+    ""
+}
+
+fn push_end_tag(output: &mut String, tag: &str) {
+    output.push_str("</");
+    output.push_str(tag);
+    output.push('>');
+}
+
+/// Escape text, keeping character references the input already has
+fn push_text(output: &mut String, text: &str) {
+    push_escaped(output, text, false);
+}
+
+fn push_attribute_value(output: &mut String, value: &str) {
+    push_escaped(output, value, true);
+}
+
+fn push_escaped(output: &mut String, text: &str, quotes: bool) {
+    for (i, c) in text.char_indices() {
+        match c {
+            '&' if !starts_with_entity(&text[i..]) => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' if quotes => output.push_str("&quot;"),
+            c => output.push(c),
+        }
+    }
+}
+
+/// Whether `text` starts with a character reference like `&amp;` or `&#39;`
+fn starts_with_entity(text: &str) -> bool {
+    let Some(end) = text.find(';') else {
+        return false;
+    };
+    let body = &text[1..end];
+    if let Some(number) = body.strip_prefix('#') {
+        match number.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()),
+        }
+    } else {
+        !body.is_empty() && body.len() <= 32 && body.chars().all(|c| c.is_ascii_alphanumeric())
+    }
+}
+
+/// Decode numeric and common named character references, for URL checks
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // The terminating `;` is optional in browsers
+        let body_len = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+            .unwrap_or(rest.len() - 1);
+        let body = &rest[1..1 + body_len];
+        let character = match body.strip_prefix('#') {
+            Some(number) => match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => number.parse().ok(),
+            }
+            .and_then(char::from_u32),
+            None => match body.to_ascii_lowercase().as_str() {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "colon" => Some(':'),
+                "tab" => Some('\t'),
+                "newline" => Some('\n'),
+                _ => None,
+            },
+        };
+
+        match character {
+            Some(c) => {
+                decoded.push(c);
+                let len = 1 + body_len;
+                rest = rest[len..].strip_prefix(';').unwrap_or(&rest[len..]);
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies() {
+        let input = r#"<h1>Title</h1><p>Some <b>bold</b> <a href="/docs" target="_blank">docs</a></p><img src="/a.png" alt="A">"#;
+
+        assert_eq!(
+            sanitize_html(input, &HtmlPolicy::strict()),
+            "TitleSome bold docs"
+        );
+        assert_eq!(
+            sanitize_html(input, &HtmlPolicy::basic()),
+            r#"Title<p>Some <b>bold</b> <a href="/docs">docs</a></p>"#
+        );
+        assert_eq!(
+            sanitize_html(input, &HtmlPolicy::relaxed()),
+            r#"<h1>Title</h1><p>Some <b>bold</b> <a href="/docs">docs</a></p><img src="/a.png" alt="A">"#
+        );
+    }
+
+    #[test]
+    fn test_removes_scripts_handlers_and_comments() {
+        let policy = HtmlPolicy::basic();
+
+        assert_eq!(
+            sanitize_html(
+                "<p onclick='x()'>a<!-- note --><SCRIPT>alert('</p>')</script >b</p>",
+                &policy
+            ),
+            "<p>ab</p>"
+        );
+        assert_eq!(sanitize_html("a<style>p{}", &policy), "a");
+        assert_eq!(
+            sanitize_html("<svg><a href='/x'>y</a></svg>z", &policy),
+            "z"
+        );
+    }
+
+    #[test]
+    fn test_rejects_dangerous_urls() {
+        let policy = HtmlPolicy::basic();
+
+        for href in [
+            "javascript:alert(1)",
+            " JavaScript:alert(1)",
+            "java\tscript:alert(1)",
+            "javascript&#58;alert(1)",
+            "&#x6A;avascript:alert(1)",
+            "data:text/html,x",
+        ] {
+            let html = format!(r#"<a href="{}">x</a>"#, href);
+            assert_eq!(sanitize_html(&html, &policy), "<a>x</a>", "{}", href);
+        }
+        for href in [
+            "https://example.com/a?b=c:d",
+            "/path:with:colons",
+            "#top",
+            "mailto:a@b.c",
+        ] {
+            let html = format!(r#"<a href="{}">x</a>"#, href);
+            assert_eq!(
+                sanitize_html(&html, &policy),
+                format!(r#"<a href="{}">x</a>"#, href)
+            );
+        }
+        assert_eq!(
+            sanitize_html(
+                r#"<a href="tel:123">x</a>"#,
+                &HtmlPolicy::basic().allow_scheme("tel")
+            ),
+            r#"<a href="tel:123">x</a>"#
+        );
+    }
+
+    #[test]
+    fn test_output_is_escaped_and_balanced() {
+        let policy = HtmlPolicy::basic();
+
+        assert_eq!(
+            sanitize_html("1 < 2 & 3 > 2 &amp; <b>bold", &policy),
+            "1 &lt; 2 &amp; 3 &gt; 2 &amp; <b>bold</b>"
+        );
+        assert_eq!(
+            sanitize_html(r#"<a title='say "hi"'>x</a></p></b>"#, &policy),
+            r#"<a title="say &quot;hi&quot;">x</a>"#
+        );
+        assert_eq!(
+            sanitize_html("<ul><li><em>one</li></ul>", &policy),
+            "<ul><li><em>one</em></li></ul>"
+        );
+        assert_eq!(sanitize_html("<br/>a<b", &policy), "<br>a");
+    }
+}
@@ -0,0 +1,171 @@
+//! HTTPS for the built-in server
+//!
+//! `Server::with_tls(cert, key)`, or `SERVER_TLS_CERT` and `SERVER_TLS_KEY`,
+//! serves HTTPS with rustls from PEM files: the certificate chain (leaf
+//! first, as Let's Encrypt's `fullchain.pem`) and its private key.
+//! `SERVER_HTTPS_REDIRECT_PORT` also listens for plain HTTP on that port
+//! and redirects every request to HTTPS.
+
+use std::convert::Infallible;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use super::{HttpResponse, ResponseBody};
+use crate::error::FrameworkError;
+
+/// How long a client gets to finish the TLS handshake before it's dropped
+pub(crate) const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Build a TLS acceptor from PEM certificate chain and key files
+pub(crate) fn acceptor(cert: &Path, key: &Path) -> Result<TlsAcceptor, FrameworkError> {
+    let pem_error = |path: &Path, err: &dyn std::fmt::Display| {
+        FrameworkError::internal(format!("Failed to read {}: {}", path.display(), err))
+    };
+    let certs = CertificateDer::pem_file_iter(cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| pem_error(cert, &e))?;
+    if certs.is_empty() {
+        return Err(pem_error(cert, &"no certificates found"));
+    }
+    let key = PrivateKeyDer::from_pem_file(key).map_err(|e| pem_error(key, &e))?;
+
+    let provider = Arc::new(tokio_rustls::rustls::crypto::ring::default_provider());
+    let mut config = ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(certs, key))
+        .map_err(|e| FrameworkError::internal(format!("Invalid TLS certificate: {}", e)))?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Answer every request on `listener` with a redirect to HTTPS on `https_port`
+pub(crate) async fn redirect_http(listener: TcpListener, https_port: u16) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                // Errors like EMFILE persist for a while, so don't spin on them
+                eprintln!("HTTPS redirect listener failed to accept: {}", e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        tokio::spawn(async move {
+            let service = service_fn(
+                move |req: hyper::Request<hyper::body::Incoming>| async move {
+                    Ok::<_, Infallible>(redirect(&req, https_port))
+                },
+            );
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+fn redirect<B>(req: &hyper::Request<B>, https_port: u16) -> hyper::Response<ResponseBody> {
+    let host = req
+        .headers()
+        .get(hyper::header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .or_else(|| req.uri().host())
+        .unwrap_or("localhost");
+    let location = https_location(host, req.uri(), https_port);
+    // 308 keeps the method and body, so form posts aren't turned into GETs
+    HttpResponse::text("")
+        .status(308)
+        .header("Location", location)
+        .into_hyper()
+}
+
+/// The HTTPS URL for a request to `host` and `uri`
+fn https_location(host: &str, uri: &hyper::Uri, https_port: u16) -> String {
+    // Drop the port the plain HTTP request was sent to, keeping IPv6 brackets
+    let hostname = match host.rfind(':') {
+        Some(colon) if !host[colon..].contains(']') => &host[..colon],
+        _ => host,
+    };
+    let path = uri.path_and_query().map_or("/", |path| path.as_str());
+    if https_port == 443 {
+        format!("https://{}{}", hostname, path)
+    } else {
+        format!("https://{}:{}{}", hostname, https_port, path)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+    use openssl::x509::{X509NameBuilder, X509};
+    use std::path::PathBuf;
+
+    /// Write a self-signed certificate for `localhost` and its key
+    pub(crate) fn self_signed(name: &str) -> (PathBuf, PathBuf) {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut subject = X509NameBuilder::new().unwrap();
+        subject.append_entry_by_text("CN", "localhost").unwrap();
+        let subject = subject.build();
+
+        let mut cert = X509::builder().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_subject_name(&subject).unwrap();
+        cert.set_issuer_name(&subject).unwrap();
+        cert.set_pubkey(&key).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        cert.sign(&key, MessageDigest::sha256()).unwrap();
+
+        let dir = std::env::temp_dir();
+        let cert_path = dir.join(format!("kit-{}-{}.crt", name, std::process::id()));
+        let key_path = dir.join(format!("kit-{}-{}.key", name, std::process::id()));
+        std::fs::write(&cert_path, cert.build().to_pem().unwrap()).unwrap();
+        std::fs::write(&key_path, key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        (cert_path, key_path)
+    }
+
+    #[test]
+    fn loads_pem_files_and_reports_bad_ones() {
+        let (cert, key) = self_signed("tls-load");
+        assert!(acceptor(&cert, &key).is_ok());
+
+        let err = acceptor(&key, &key).err().unwrap();
+        assert!(err.to_string().contains("no certificates found"), "{}", err);
+        assert!(acceptor(&cert, Path::new("/nonexistent/key.pem")).is_err());
+
+        std::fs::remove_file(cert).ok();
+        std::fs::remove_file(key).ok();
+    }
+
+    #[test]
+    fn redirects_keep_host_and_path() {
+        let uri: hyper::Uri = "/orders?page=2".parse().unwrap();
+        assert_eq!(
+            https_location("shop.test:8080", &uri, 443),
+            "https://shop.test/orders?page=2"
+        );
+        assert_eq!(
+            https_location("[::1]:80", &uri, 8443),
+            "https://[::1]:8443/orders?page=2"
+        );
+        assert_eq!(
+            https_location("[::1]", &"/".parse().unwrap(), 443),
+            "https://[::1]/"
+        );
+    }
+}
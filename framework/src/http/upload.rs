@@ -0,0 +1,366 @@
+//! Uploaded files from `multipart/form-data` requests
+
+use super::body::{parse_form, MultipartForm};
+use crate::error::FrameworkError;
+use crate::storage::{disk::normalize_path, Storage};
+use bytes::Bytes;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use std::cell::RefCell;
+
+/// Length of the random names given by `store()`
+const STORED_NAME_LENGTH: usize = 40;
+
+/// Marks form values that stand for an uploaded file while deserializing
+const MARKER_PREFIX: &str = "\u{0}kit-upload:";
+
+thread_local! {
+    /// Files of the multipart form being deserialized
+    static PENDING: RefCell<Option<PendingUploads>> = const { RefCell::new(None) };
+}
+
+struct PendingUploads {
+    nonce: String,
+    files: Vec<Option<UploadedFile>>,
+}
+
+/// A file uploaded with a `multipart/form-data` request
+///
+/// Use it as a field type in `#[request]` structs (also as
+/// `Option<UploadedFile>`), or get it from `req.multipart()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{request, UploadedFile};
+///
+/// #[request]
+/// pub struct UpdateAvatarRequest {
+///     #[file(max_size = "2MB", mimes("image/png", "image/jpeg"))]
+///     pub avatar: UploadedFile,
+/// }
+///
+/// #[handler]
+/// pub async fn update(form: UpdateAvatarRequest) -> Response {
+///     // e.g. "avatars/3kq8...x1.png" on the default disk
+///     let path = form.avatar.store("avatars").await?;
+///     json_response!({ "path": path })
+/// }
+/// ```
+#[derive(Clone)]
+pub struct UploadedFile {
+    client_name: Option<String>,
+    content_type: String,
+    data: Bytes,
+}
+
+impl UploadedFile {
+    /// A file with the given client name, content type and contents
+    ///
+    /// Useful for building fake uploads in tests.
+    pub fn new(
+        client_name: impl Into<String>,
+        content_type: impl Into<String>,
+        data: impl Into<Bytes>,
+    ) -> Self {
+        let client_name = client_name.into();
+        Self {
+            client_name: (!client_name.is_empty()).then_some(client_name),
+            content_type: content_type.into(),
+            data: data.into(),
+        }
+    }
+
+    /// The file name sent by the client
+    ///
+    /// Don't use it as a path: it's chosen by the client.
+    pub fn client_name(&self) -> Option<&str> {
+        self.client_name.as_deref()
+    }
+
+    /// The MIME type sent by the client, e.g. `image/png`
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// The lowercase extension of the client name, if it is alphanumeric
+    pub fn extension(&self) -> Option<String> {
+        let (_, extension) = self.client_name.as_deref()?.rsplit_once('.')?;
+        (!extension.is_empty() && extension.chars().all(|c| c.is_ascii_alphanumeric()))
+            .then(|| extension.to_ascii_lowercase())
+    }
+
+    /// Size in bytes
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The file contents
+    pub fn bytes(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Take the file contents
+    pub fn into_bytes(self) -> Bytes {
+        self.data
+    }
+
+    /// Save the file in `dir` under a random name, keeping its extension
+    ///
+    /// Returns the path on the default disk, e.g. `avatars/3kq8...x1.png`.
+    pub async fn store(&self, dir: &str) -> Result<String, FrameworkError> {
+        let name: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(STORED_NAME_LENGTH)
+            .map(|c| char::from(c).to_ascii_lowercase())
+            .collect();
+        let name = match self.extension() {
+            Some(extension) => format!("{}.{}", name, extension),
+            None => name,
+        };
+        self.store_as(dir, &name).await
+    }
+
+    /// Save the file as `dir/name` on the default disk, replacing any existing file
+    ///
+    /// Returns the path on the disk. Fails for paths that would leave it,
+    /// such as `../config`.
+    pub async fn store_as(&self, dir: &str, name: &str) -> Result<String, FrameworkError> {
+        if name.is_empty() || name.contains(['/', '\\']) {
+            return Err(FrameworkError::internal(format!(
+                "Invalid upload name: {}",
+                name
+            )));
+        }
+        let path = normalize_path(&format!("{}/{}", dir, name))?;
+        Storage::put(&path, self.data.clone()).await?;
+        Ok(path)
+    }
+}
+
+impl std::fmt::Debug for UploadedFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadedFile")
+            .field("client_name", &self.client_name)
+            .field("content_type", &self.content_type)
+            .field("size", &self.data.len())
+            .finish()
+    }
+}
+
+/// Files are only deserialized from multipart forms, see `deserialize_form`
+impl<'de> Deserialize<'de> for UploadedFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let marker = String::deserialize(deserializer)?;
+        take_pending(&marker).ok_or_else(|| D::Error::custom("expected an uploaded file"))
+    }
+}
+
+fn take_pending(marker: &str) -> Option<UploadedFile> {
+    let (nonce, index) = marker.strip_prefix(MARKER_PREFIX)?.split_once(':')?;
+    let index: usize = index.parse().ok()?;
+    PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        let pending = pending.as_mut().filter(|pending| pending.nonce == nonce)?;
+        pending.files.get_mut(index)?.take()
+    })
+}
+
+/// Deserialize a multipart form like a urlencoded one, plus its files
+///
+/// Text fields follow the rules of `parse_form`. Each file is passed as a
+/// marker value that `UploadedFile`'s `Deserialize` swaps for the file.
+pub(crate) fn deserialize_form<T: DeserializeOwned>(
+    form: MultipartForm,
+) -> Result<T, FrameworkError> {
+    let nonce: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect();
+
+    let mut pairs = form.fields;
+    let mut files = Vec::with_capacity(form.files.len());
+    for (name, file) in form.files {
+        pairs.push((name, format!("{}{}:{}", MARKER_PREFIX, nonce, files.len())));
+        files.push(Some(file));
+    }
+    let encoded = serde_urlencoded::to_string(&pairs)
+        .map_err(|e| FrameworkError::internal(format!("Failed to parse form body: {}", e)))?;
+
+    PENDING.with(|pending| *pending.borrow_mut() = Some(PendingUploads { nonce, files }));
+    let result = parse_form(&Bytes::from(encoded));
+    PENDING.with(|pending| pending.borrow_mut().take());
+    result
+}
+
+/// Size and type limits for an uploaded file
+///
+/// `#[file(...)]` attributes on `#[request]` fields build these.
+#[derive(Debug, Clone, Default)]
+pub struct UploadRules {
+    max_size: Option<usize>,
+    mimes: Vec<&'static str>,
+}
+
+impl UploadRules {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject files larger than `bytes`
+    pub fn max_size(mut self, bytes: usize) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Only accept these MIME types; `image/*` accepts any image
+    pub fn mimes(mut self, mimes: &[&'static str]) -> Self {
+        self.mimes.extend_from_slice(mimes);
+        self
+    }
+
+    /// The validation message for `file`, if it breaks a rule
+    pub fn check(&self, field: &str, file: &UploadedFile) -> Option<String> {
+        if let Some(max_size) = self.max_size.filter(|max| file.size() > *max) {
+            let limit = if max_size >= 1024 {
+                format!("{} kilobytes", max_size / 1024)
+            } else {
+                format!("{} bytes", max_size)
+            };
+            return Some(format!(
+                "The {} may not be greater than {}.",
+                field.replace('_', " "),
+                limit
+            ));
+        }
+        if !self.mimes.is_empty() && !self.mimes.iter().any(|mime| mime_matches(mime, file)) {
+            return Some(format!(
+                "The {} must be a file of type: {}.",
+                field.replace('_', " "),
+                self.mimes.join(", ")
+            ));
+        }
+        None
+    }
+}
+
+fn mime_matches(rule: &str, file: &UploadedFile) -> bool {
+    let content_type = file
+        .content_type()
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim();
+    match rule.strip_suffix("/*") {
+        Some(kind) => content_type
+            .split_once('/')
+            .is_some_and(|(file_kind, _)| file_kind.eq_ignore_ascii_case(kind)),
+        None => content_type.eq_ignore_ascii_case(rule),
+    }
+}
+
+/// Fields that `#[file(...)]` rules can be checked on
+pub trait ValidateUpload {
+    /// The first validation message for the field, if any
+    fn validate_upload(&self, field: &str, rules: &UploadRules) -> Option<String>;
+}
+
+impl ValidateUpload for UploadedFile {
+    fn validate_upload(&self, field: &str, rules: &UploadRules) -> Option<String> {
+        rules.check(field, self)
+    }
+}
+
+impl ValidateUpload for Option<UploadedFile> {
+    fn validate_upload(&self, field: &str, rules: &UploadRules) -> Option<String> {
+        self.as_ref().and_then(|file| rules.check(field, file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Profile {
+        name: String,
+        age: u32,
+        avatar: UploadedFile,
+        banner: Option<UploadedFile>,
+    }
+
+    #[test]
+    fn test_deserializes_fields_and_files() {
+        let form = MultipartForm {
+            fields: vec![
+                ("name".to_string(), "Ada".to_string()),
+                ("age".to_string(), "36".to_string()),
+            ],
+            files: vec![(
+                "avatar".to_string(),
+                UploadedFile::new("Me.PNG", "image/png", &b"\x89PNG"[..]),
+            )],
+        };
+        let profile: Profile = deserialize_form(form).unwrap();
+
+        assert_eq!(profile.name, "Ada");
+        assert_eq!(profile.age, 36);
+        assert_eq!(profile.avatar.extension().as_deref(), Some("png"));
+        assert_eq!(profile.avatar.size(), 4);
+        assert!(profile.banner.is_none());
+
+        // A text value can't stand in for a file
+        let form = MultipartForm {
+            fields: vec![
+                ("name".to_string(), "Ada".to_string()),
+                ("age".to_string(), "36".to_string()),
+                ("avatar".to_string(), format!("{}abc:0", MARKER_PREFIX)),
+            ],
+            files: Vec::new(),
+        };
+        assert!(deserialize_form::<Profile>(form).is_err());
+    }
+
+    #[test]
+    fn test_rules() {
+        let png = UploadedFile::new("a.png", "image/png", vec![0; 4096]);
+        let rules = UploadRules::new().max_size(2048).mimes(&["image/*"]);
+        assert_eq!(
+            png.validate_upload("avatar", &rules).as_deref(),
+            Some("The avatar may not be greater than 2 kilobytes.")
+        );
+
+        let rules = UploadRules::new().mimes(&["image/jpeg", "application/pdf"]);
+        assert_eq!(
+            Some(png.clone())
+                .validate_upload("profile_photo", &rules)
+                .as_deref(),
+            Some("The profile photo must be a file of type: image/jpeg, application/pdf.")
+        );
+        assert!(png
+            .validate_upload("avatar", &UploadRules::new().mimes(&["image/*"]))
+            .is_none());
+        assert!(None::<UploadedFile>
+            .validate_upload("avatar", &rules)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_store_as_stays_on_the_disk() {
+        let disk = Storage::fake();
+        let file = UploadedFile::new("notes.txt", "text/plain", "hi");
+
+        assert!(file.store_as("../config", "x.txt").await.is_err());
+        assert!(file.store_as("notes", "/etc/passwd").await.is_err());
+        assert!(file.store_as("notes", "").await.is_err());
+
+        let path = file.store("notes").await.unwrap();
+        assert!(path.starts_with("notes/") && path.ends_with(".txt"));
+        disk.assert_exists(&path);
+    }
+}
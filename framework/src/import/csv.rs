@@ -0,0 +1,151 @@
+//! Minimal CSV reading and writing (RFC 4180)
+//!
+//! Fields are separated by commas; a field in double quotes may contain
+//! commas, line breaks and `""` for a quote. Records end with `\n` or
+//! `\r\n`, and a leading byte order mark is skipped.
+
+use crate::error::FrameworkError;
+
+/// Iterator over the records of a CSV document
+#[derive(Clone)]
+pub struct CsvReader<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> CsvReader<'a> {
+    /// Read records from `input`
+    pub fn new(input: &'a str) -> Self {
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        Self { input, pos: 0 }
+    }
+
+    fn read_record(&mut self) -> Result<Vec<String>, FrameworkError> {
+        let bytes = self.input.as_bytes();
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut start = self.pos;
+
+        loop {
+            if self.pos < bytes.len() && bytes[self.pos] == b'"' && self.pos == start {
+                // Quoted field
+                self.pos += 1;
+                loop {
+                    let Some(offset) = self.input[self.pos..].find('"') else {
+                        self.pos = self.input.len();
+                        return Err(FrameworkError::domain(
+                            "The CSV file has an unterminated quoted field",
+                            422,
+                        ));
+                    };
+                    field.push_str(&self.input[self.pos..self.pos + offset]);
+                    self.pos += offset + 1;
+                    if bytes.get(self.pos) == Some(&b'"') {
+                        field.push('"');
+                        self.pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                // Anything between the closing quote and the separator is kept
+                start = self.pos;
+            }
+
+            match bytes.get(self.pos) {
+                Some(b',') => {
+                    field.push_str(&self.input[start..self.pos]);
+                    fields.push(std::mem::take(&mut field));
+                    self.pos += 1;
+                    start = self.pos;
+                }
+                Some(b'\n') | None => {
+                    let end = if self.pos > start && bytes[self.pos - 1] == b'\r' {
+                        self.pos - 1
+                    } else {
+                        self.pos
+                    };
+                    field.push_str(&self.input[start..end]);
+                    fields.push(field);
+                    self.pos += 1;
+                    return Ok(fields);
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+}
+
+impl Iterator for CsvReader<'_> {
+    type Item = Result<Vec<String>, FrameworkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip blank lines, including the one after the last record
+        loop {
+            let rest = self.input.get(self.pos..)?;
+            if rest.is_empty() {
+                return None;
+            }
+            if rest.starts_with("\r\n") {
+                self.pos += 2;
+            } else if rest.starts_with('\n') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        Some(self.read_record())
+    }
+}
+
+/// Append a record to `out`, quoting fields where needed
+pub fn write_record<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let field = field.as_ref();
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &str) -> Vec<Vec<String>> {
+        CsvReader::new(input).collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn reads_quoted_fields_and_line_endings() {
+        let records = read(
+            "\u{feff}name,note\r\nAda,\"likes \"\"math\"\", and tea\"\r\n\nGrace,\"multi\nline\"\n,\n",
+        );
+        assert_eq!(
+            records,
+            vec![
+                vec!["name", "note"],
+                vec!["Ada", "likes \"math\", and tea"],
+                vec!["Grace", "multi\nline"],
+                vec!["", ""],
+            ]
+        );
+        assert!(CsvReader::new("a,\"b").next().unwrap().is_err());
+    }
+
+    #[test]
+    fn written_records_read_back() {
+        let fields = ["plain", "with, comma", "with \"quotes\"", "two\nlines"];
+        let mut out = String::new();
+        write_record(&mut out, &fields);
+        write_record(&mut out, &["", "x"]);
+        assert_eq!(read(&out), vec![fields.to_vec(), vec!["", "x"]]);
+    }
+}
@@ -0,0 +1,483 @@
+//! CSV imports run as durable workflows
+//!
+//! An `Import` turns each row of an uploaded CSV file into a `Row` struct,
+//! validated like a `FormRequest`, and handles the valid rows a chunk at a
+//! time. Run inside a `#[workflow]`, every chunk is a workflow step, so a
+//! retried import resumes after the last finished chunk and
+//! `ImportStatus::find` can report progress while it runs.
+//!
+//! Rows that fail to parse or validate are skipped and written, with their
+//! errors, to a failure report next to the upload on the default storage
+//! disk.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{import, workflow, start_workflow, FrameworkError, Import, ImportStatus};
+//!
+//! #[derive(Deserialize, Validate)]
+//! pub struct UserRow {
+//!     #[validate(length(min = 1))]
+//!     pub name: String,
+//!     #[validate(email)]
+//!     pub email: String,
+//! }
+//!
+//! pub struct UsersImport;
+//!
+//! #[async_trait]
+//! impl Import for UsersImport {
+//!     type Row = UserRow;
+//!
+//!     async fn handle(&self, rows: Vec<UserRow>) -> Result<(), FrameworkError> {
+//!         for row in rows {
+//!             User::create(&row.name, &row.email).await?;
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[workflow]
+//! async fn import_users(path: String) -> Result<import::ImportSummary, FrameworkError> {
+//!     import::run(UsersImport, &path).await
+//! }
+//!
+//! // In the upload controller
+//! let path = form.file.store("imports").await?;
+//! let handle = start_workflow!(import_users, path)?;
+//!
+//! // In the progress page
+//! let status = ImportStatus::find(workflow_id).await?;
+//! ```
+
+pub mod csv;
+pub mod status;
+
+pub use status::{ImportStatus, ImportSummary, RowFailure};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use validator::Validate;
+
+use self::csv::{write_record, CsvReader};
+use self::status::ChunkResult;
+use crate::error::{FrameworkError, ValidationErrors};
+use crate::storage::Storage;
+use crate::workflow::WorkflowContext;
+
+/// Step names, also read back by `ImportStatus`
+pub(crate) const COUNT_STEP: &str = "import:count";
+pub(crate) const CHUNK_STEP: &str = "import:chunk";
+pub(crate) const REPORT_STEP: &str = "import:report";
+
+/// An import of CSV rows
+///
+/// The header row names the fields of `Row`. Values are parsed like form
+/// fields, so numbers, booleans and `Option`s work as in a `FormRequest`;
+/// empty cells are left out, making `Option` fields `None`.
+#[async_trait]
+pub trait Import: Send + Sync + 'static {
+    /// A row of the file
+    type Row: DeserializeOwned + Validate + Send + 'static;
+
+    /// Rows validated and handled at a time (default: 500)
+    fn chunk_size(&self) -> usize {
+        500
+    }
+
+    /// Handle the valid rows of a chunk
+    ///
+    /// An error fails the chunk, and the workflow retries it; don't keep
+    /// partial writes of a failed chunk.
+    async fn handle(&self, rows: Vec<Self::Row>) -> Result<(), FrameworkError>;
+}
+
+/// Import the CSV file at `path` on the default storage disk
+///
+/// Inside a workflow, counting the rows, each chunk and writing the failure
+/// report are workflow steps; elsewhere the import just runs.
+pub async fn run<I: Import>(import: I, path: &str) -> Result<ImportSummary, FrameworkError> {
+    let import = Arc::new(import);
+    let contents = Storage::get(path).await?;
+    let contents = std::str::from_utf8(&contents)
+        .map_err(|_| FrameworkError::domain("The CSV file must be UTF-8 encoded", 422))?;
+
+    let mut records = CsvReader::new(contents);
+    let headers: Arc<Vec<String>> = match records.next().transpose()? {
+        Some(headers) => Arc::new(headers.iter().map(|h| h.trim().to_string()).collect()),
+        None => return Err(FrameworkError::domain("The CSV file is empty", 422)),
+    };
+
+    let rows = records.clone().count();
+    let total = step(
+        COUNT_STEP,
+        path.to_string(),
+        move || async move { Ok(rows) },
+    )
+    .await?;
+
+    let mut summary = ImportSummary {
+        total,
+        ..Default::default()
+    };
+    let mut failures = Vec::new();
+    let mut row = 1;
+    loop {
+        let mut chunk = Vec::new();
+        for values in records.by_ref().take(import.chunk_size().max(1)) {
+            row += 1;
+            chunk.push((row, values?));
+        }
+        let Some(&(first_row, _)) = chunk.first() else {
+            break;
+        };
+
+        let import = import.clone();
+        let headers = headers.clone();
+        let result: ChunkResult = step(CHUNK_STEP, first_row.to_string(), move || {
+            handle_chunk(import, headers, chunk)
+        })
+        .await?;
+        summary.imported += result.imported;
+        failures.extend(result.failures);
+    }
+
+    summary.failed = failures.len();
+    if !failures.is_empty() {
+        let report_path = report_path(path);
+        let report = failure_report(&headers, &failures);
+        summary.report = Some(
+            step(REPORT_STEP, report_path.clone(), move || async move {
+                Storage::put(&report_path, report).await?;
+                Ok(report_path)
+            })
+            .await?,
+        );
+    }
+    Ok(summary)
+}
+
+/// Run `f` as a workflow step when in a workflow
+async fn step<T, F, Fut>(name: &str, input: String, f: F) -> Result<T, FrameworkError>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, FrameworkError>> + Send + 'static,
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    match WorkflowContext::current() {
+        Some(ctx) => {
+            let input = serde_json::to_string(&input).map_err(|e| {
+                FrameworkError::internal(format!("Import step input serialize error: {}", e))
+            })?;
+            ctx.run_step_with_input(name, input, f).await
+        }
+        None => f().await,
+    }
+}
+
+async fn handle_chunk<I: Import>(
+    import: Arc<I>,
+    headers: Arc<Vec<String>>,
+    chunk: Vec<(usize, Vec<String>)>,
+) -> Result<ChunkResult, FrameworkError> {
+    let mut rows = Vec::new();
+    let mut failures = Vec::new();
+    for (row, values) in chunk {
+        match parse_row::<I::Row>(&headers, &values) {
+            Ok(parsed) => rows.push(parsed),
+            Err(errors) => failures.push(RowFailure {
+                row,
+                values,
+                errors,
+            }),
+        }
+    }
+
+    let imported = rows.len();
+    if !rows.is_empty() {
+        import.handle(rows).await?;
+    }
+    Ok(ChunkResult {
+        rows: imported + failures.len(),
+        imported,
+        failures,
+    })
+}
+
+/// Deserialize and validate a row, or return its errors by field
+fn parse_row<T: DeserializeOwned + Validate>(
+    headers: &[String],
+    values: &[String],
+) -> Result<T, BTreeMap<String, Vec<String>>> {
+    let fields: Vec<(&str, &str)> = headers
+        .iter()
+        .zip(values)
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(header, value)| (header.as_str(), value.as_str()))
+        .collect();
+    let encoded = serde_urlencoded::to_string(&fields).unwrap_or_default();
+
+    let row: T = serde_urlencoded::from_str(&encoded).map_err(|e| {
+        let e = e.to_string();
+        match e
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.strip_suffix('`'))
+        {
+            Some(field) => BTreeMap::from([(
+                field.to_string(),
+                vec![format!("The {} field is required.", field)],
+            )]),
+            None => BTreeMap::from([(
+                "row".to_string(),
+                vec![format!("The row is invalid: {}", e)],
+            )]),
+        }
+    })?;
+    match row.validate() {
+        Ok(()) => Ok(row),
+        Err(errors) => Err(ValidationErrors::from_validator(errors)
+            .errors
+            .into_iter()
+            .collect()),
+    }
+}
+
+/// `imports/users.csv` -> `imports/users.failures.csv`
+fn report_path(path: &str) -> String {
+    let stem = path.strip_suffix(".csv").unwrap_or(path);
+    format!("{}.failures.csv", stem)
+}
+
+/// The failed rows as CSV, with `row` and `errors` columns added
+fn failure_report(headers: &[String], failures: &[RowFailure]) -> String {
+    let mut report = String::new();
+    let mut header = vec!["row".to_string()];
+    header.extend(headers.iter().cloned());
+    header.push("errors".to_string());
+    write_record(&mut report, &header);
+
+    for failure in failures {
+        let mut record = vec![failure.row.to_string()];
+        record.extend(failure.values.iter().cloned());
+        record.resize(headers.len() + 1, String::new());
+        record.push(failure.message());
+        write_record(&mut report, &record);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestDatabase;
+    use crate::workflow::store;
+    use sea_orm::ConnectionTrait;
+    use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager};
+    use serde::Deserialize;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreateWorkflowTables)]
+        }
+    }
+
+    struct CreateWorkflowTables;
+
+    impl MigrationName for CreateWorkflowTables {
+        fn name(&self) -> &str {
+            "create_workflow_tables"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreateWorkflowTables {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), sea_orm::DbErr> {
+            let db = manager.get_connection();
+            db.execute_unprepared(
+                "CREATE TABLE workflows (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    status TEXT NOT NULL,
+                    input TEXT NOT NULL,
+                    output TEXT NULL,
+                    error TEXT NULL,
+                    attempts INTEGER NOT NULL,
+                    max_attempts INTEGER NOT NULL,
+                    next_run_at TIMESTAMP NULL,
+                    locked_until TIMESTAMP NULL,
+                    worker_id TEXT NULL,
+                    created_at TIMESTAMP NOT NULL,
+                    updated_at TIMESTAMP NOT NULL,
+                    started_at TIMESTAMP NULL,
+                    completed_at TIMESTAMP NULL
+                )",
+            )
+            .await?;
+            db.execute_unprepared(
+                "CREATE TABLE workflow_steps (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    workflow_id INTEGER NOT NULL,
+                    step_index INTEGER NOT NULL,
+                    step_name TEXT NOT NULL,
+                    status TEXT NOT NULL,
+                    input TEXT NOT NULL,
+                    output TEXT NULL,
+                    error TEXT NULL,
+                    attempts INTEGER NOT NULL,
+                    created_at TIMESTAMP NOT NULL,
+                    updated_at TIMESTAMP NOT NULL,
+                    started_at TIMESTAMP NULL,
+                    completed_at TIMESTAMP NULL
+                )",
+            )
+            .await?;
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct UserRow {
+        name: String,
+        #[validate(email(message = "The email is invalid."))]
+        email: String,
+        age: Option<u32>,
+    }
+
+    /// Records handled rows; fails the rows starting with `fail_at` once
+    #[derive(Default)]
+    struct UsersImport {
+        handled: Mutex<Vec<String>>,
+        fail_at: Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl Import for Arc<UsersImport> {
+        type Row = UserRow;
+
+        fn chunk_size(&self) -> usize {
+            2
+        }
+
+        async fn handle(&self, rows: Vec<UserRow>) -> Result<(), FrameworkError> {
+            let mut fail_at = self.fail_at.lock().unwrap();
+            if fail_at.as_deref() == Some(rows[0].email.as_str()) {
+                *fail_at = None;
+                return Err(FrameworkError::internal("database is down"));
+            }
+            let mut handled = self.handled.lock().unwrap();
+            handled.extend(rows.iter().map(|row| format!("{}:{:?}", row.name, row.age)));
+            Ok(())
+        }
+    }
+
+    const USERS: &str = "name,email,age\n\
+        Ada,ada@example.com,36\n\
+        Grace,not-an-email,\n\
+        Linus,linus@example.com,\n\
+        ,nameless@example.com,7\n\
+        Alan,alan@example.com,forty\n\
+        Edsger,edsger@example.com,72\n";
+
+    #[tokio::test]
+    async fn imports_valid_rows_and_reports_failures() {
+        let disk = Storage::fake();
+        Storage::put("imports/users.csv", USERS).await.unwrap();
+        let import = Arc::new(UsersImport::default());
+
+        let summary = run(import.clone(), "imports/users.csv").await.unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                total: 6,
+                imported: 3,
+                failed: 3,
+                report: Some("imports/users.failures.csv".to_string()),
+            }
+        );
+        assert_eq!(
+            *import.handled.lock().unwrap(),
+            ["Ada:Some(36)", "Linus:None", "Edsger:Some(72)"]
+        );
+
+        disk.assert_exists("imports/users.failures.csv");
+        let report = Storage::get("imports/users.failures.csv").await.unwrap();
+        let report: Vec<Vec<String>> = CsvReader::new(std::str::from_utf8(&report).unwrap())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(report[0], ["row", "name", "email", "age", "errors"]);
+        assert_eq!(
+            report[1],
+            [
+                "3",
+                "Grace",
+                "not-an-email",
+                "",
+                "email: The email is invalid."
+            ]
+        );
+        assert_eq!(report[2][4], "name: The name field is required.");
+        assert_eq!(report[3][0], "6");
+        assert!(report[3][4].starts_with("row: The row is invalid"));
+    }
+
+    #[tokio::test]
+    async fn workflow_imports_resume_and_report_progress() {
+        let _db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        Storage::fake();
+        Storage::put("imports/users.csv", USERS).await.unwrap();
+        let import = Arc::new(UsersImport::default());
+        *import.fail_at.lock().unwrap() = Some("linus@example.com".to_string());
+
+        let handle = store::insert_workflow("import_users", "[]", 3)
+            .await
+            .unwrap();
+        let attempt = || {
+            WorkflowContext::new(handle.id(), Duration::from_secs(30))
+                .enter(run(import.clone(), "imports/users.csv"))
+        };
+
+        // The second chunk fails after the first was handled
+        assert!(attempt().await.is_err());
+        let status = ImportStatus::find(handle.id()).await.unwrap();
+        assert_eq!(
+            (
+                status.total,
+                status.processed,
+                status.imported,
+                status.failed
+            ),
+            (6, 2, 1, 1)
+        );
+        assert_eq!(status.progress, 33);
+        assert_eq!(status.report_url, None);
+
+        // The retry skips the finished chunk
+        let summary = attempt().await.unwrap();
+        assert_eq!((summary.imported, summary.failed), (3, 3));
+        assert_eq!(import.handled.lock().unwrap().len(), 3);
+
+        store::mark_succeeded(handle.id(), &serde_json::to_string(&summary).unwrap())
+            .await
+            .unwrap();
+        let status = ImportStatus::find(handle.id()).await.unwrap();
+        assert_eq!(status.status, "succeeded");
+        assert_eq!(
+            (status.processed, status.imported, status.failed),
+            (6, 3, 3)
+        );
+        assert_eq!(status.progress, 100);
+        assert_eq!(
+            status.report_url.as_deref(),
+            Some("/storage/imports/users.failures.csv?expires_in=3600")
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Import results and progress
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::{CHUNK_STEP, COUNT_STEP, REPORT_STEP};
+use crate::error::FrameworkError;
+use crate::storage::Storage;
+use crate::workflow::store;
+use crate::workflow::types::StepStatus;
+
+/// How long the failure report link in `ImportStatus` works
+const REPORT_LINK_TTL: Duration = Duration::from_secs(3600);
+
+/// What an import did, returned by `import::run`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// Data rows in the file
+    pub total: usize,
+    /// Rows handed to `Import::handle`
+    pub imported: usize,
+    /// Rows skipped for errors
+    pub failed: usize,
+    /// Storage path of the failure report, if rows failed
+    pub report: Option<String>,
+}
+
+/// A row skipped for errors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowFailure {
+    /// Record number in the file, the header being 1
+    pub row: usize,
+    /// The row as it was in the file
+    pub values: Vec<String>,
+    /// Error messages by field
+    pub errors: BTreeMap<String, Vec<String>>,
+}
+
+impl RowFailure {
+    /// The errors on one line, e.g. "email: The email is invalid."
+    pub fn message(&self) -> String {
+        self.errors
+            .iter()
+            .flat_map(|(field, messages)| {
+                messages
+                    .iter()
+                    .map(move |message| format!("{}: {}", field, message))
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Output of a chunk step
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ChunkResult {
+    pub rows: usize,
+    pub imported: usize,
+    pub failures: Vec<RowFailure>,
+}
+
+/// Progress of an import workflow, for a progress page
+///
+/// Send it as a prop and poll with a partial reload; `kit generate-types`
+/// emits a matching `ImportStatus` interface.
+///
+/// ```rust,ignore
+/// #[handler]
+/// pub async fn show(req: Request) -> Response {
+///     let id: i64 = req.param("id")?.parse()?;
+///     inertia_response!("Imports/Show", { "import": ImportStatus::find(id).await? })
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportStatus {
+    /// Workflow status: "pending", "running", "succeeded" or "failed"
+    pub status: String,
+    /// Data rows in the file, 0 until counted
+    pub total: usize,
+    /// Rows checked so far
+    pub processed: usize,
+    /// Rows handed to `Import::handle` so far
+    pub imported: usize,
+    /// Rows skipped for errors so far
+    pub failed: usize,
+    /// Percentage of rows processed
+    pub progress: u8,
+    /// Link to the failure report once it's written
+    pub report_url: Option<String>,
+    /// Why the import failed, if it did
+    pub error: Option<String>,
+}
+
+impl ImportStatus {
+    /// Read the progress of the import run by workflow `workflow_id`
+    pub async fn find(workflow_id: i64) -> Result<Self, FrameworkError> {
+        let workflow = store::get_workflow_record(workflow_id).await?;
+        let mut status = Self {
+            error: (workflow.status == "failed")
+                .then_some(workflow.error)
+                .flatten(),
+            status: workflow.status,
+            total: 0,
+            processed: 0,
+            imported: 0,
+            failed: 0,
+            progress: 0,
+            report_url: None,
+        };
+
+        let steps = store::list_steps(workflow_id).await?;
+        let finished = steps
+            .iter()
+            .filter(|step| step.status == StepStatus::Succeeded.as_str());
+        for step in finished {
+            let Some(output) = step.output.as_deref() else {
+                continue;
+            };
+            match step.step_name.as_str() {
+                COUNT_STEP => status.total = serde_json::from_str(output).unwrap_or(0),
+                CHUNK_STEP => {
+                    if let Ok(chunk) = serde_json::from_str::<ChunkResult>(output) {
+                        status.processed += chunk.rows;
+                        status.imported += chunk.imported;
+                        status.failed += chunk.failures.len();
+                    }
+                }
+                REPORT_STEP => {
+                    if let Ok(path) = serde_json::from_str::<String>(output) {
+                        status.report_url = Storage::temporary_url(&path, REPORT_LINK_TTL)
+                            .or_else(|_| Storage::url(&path))
+                            .ok();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        status.progress = match status.total {
+            0 if status.status == "succeeded" => 100,
+            0 => 0,
+            total => (status.processed * 100 / total).min(100) as u8,
+        };
+        Ok(status)
+    }
+}
@@ -1,15 +1,66 @@
+use hyper::HeaderMap;
+use serde::Serialize;
+use serde_json::{Map, Value};
 use std::cell::RefCell;
 
 /// Request context for Inertia - stored in thread-local storage
 #[derive(Clone, Default)]
 pub struct InertiaContext {
     pub path: String,
+    /// The request's query string, without the `?`
+    pub query: Option<String>,
     pub is_inertia: bool,
+    /// The client asked for JSON rather than a page, see `Request::wants_json`
+    pub wants_json: bool,
     pub version: Option<String>,
+    /// Set when an Inertia request reloads only some props
+    pub partial: Option<PartialReload>,
+}
+
+/// A partial reload, from the `X-Inertia-Partial-*` headers
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartialReload {
+    /// The page component the client has loaded
+    pub component: String,
+    /// Props to send; all of them when empty
+    pub only: Vec<String>,
+    /// Props to leave out
+    pub except: Vec<String>,
+}
+
+impl PartialReload {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let list = |name: &str| {
+            header(name)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|key| !key.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Some(Self {
+            component: header("X-Inertia-Partial-Component")?.to_string(),
+            only: list("X-Inertia-Partial-Data"),
+            except: list("X-Inertia-Partial-Except"),
+        })
+    }
+
+    /// Check if the prop with this top-level key should be sent
+    pub fn includes(&self, key: &str) -> bool {
+        (self.only.is_empty() || self.only.iter().any(|only| only == key))
+            && !self.except.iter().any(|except| except == key)
+    }
 }
 
 thread_local! {
     static CONTEXT: RefCell<Option<InertiaContext>> = RefCell::new(None);
+    /// Props shared with every page of the current request
+    static SHARED: RefCell<Map<String, Value>> = RefCell::new(Map::new());
 }
 
 impl InertiaContext {
@@ -30,6 +81,47 @@ impl InertiaContext {
         CONTEXT.with(|c| {
             *c.borrow_mut() = None;
         });
+        SHARED.with(|s| s.borrow_mut().clear());
+    }
+
+    /// Share a prop with every Inertia page rendered for this request
+    ///
+    /// Meant for middleware, e.g. the signed-in user; flash messages are
+    /// shared as `flash` already. Dots in the key nest the value, so
+    /// `"auth.user"` becomes `{ auth: { user } }`. Page props with the same
+    /// top-level key win.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// InertiaContext::share("auth.user", Auth::user(&req).await?);
+    /// InertiaContext::share("app.version", env!("CARGO_PKG_VERSION"));
+    /// ```
+    pub fn share(key: &str, value: impl Serialize) {
+        let value = serde_json::to_value(value).expect("Failed to serialize shared Inertia prop");
+        SHARED.with(|s| {
+            let mut shared = s.borrow_mut();
+            let mut segments = key.split('.').peekable();
+            let mut target = &mut *shared;
+            while let Some(segment) = segments.next() {
+                if segments.peek().is_none() {
+                    target.insert(segment.to_string(), value);
+                    return;
+                }
+                let entry = target
+                    .entry(segment.to_string())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if !entry.is_object() {
+                    *entry = Value::Object(Map::new());
+                }
+                target = entry.as_object_mut().unwrap();
+            }
+        });
+    }
+
+    /// Props shared so far for this request
+    pub fn shared() -> Map<String, Value> {
+        SHARED.with(|s| s.borrow().clone())
     }
 
     /// Get current path or empty string
@@ -41,4 +133,61 @@ impl InertiaContext {
     pub fn is_inertia_request() -> bool {
         Self::get().map(|c| c.is_inertia).unwrap_or(false)
     }
+
+    /// The partial reload the current request asks for, if any
+    pub fn partial_reload() -> Option<PartialReload> {
+        Self::get().and_then(|c| c.partial)
+    }
+
+    /// Check if current request asked for a JSON response
+    pub fn wants_json_request() -> bool {
+        Self::get().map(|c| c.wants_json).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inertia::InertiaResponse;
+    use serde_json::json;
+
+    #[test]
+    fn shared_props_are_merged_into_pages() {
+        InertiaContext::share("auth.user", json!({ "name": "Ada" }));
+        InertiaContext::share("auth.can_edit", true);
+        InertiaContext::share("flash", "Saved");
+
+        let page = InertiaResponse::new(
+            "Posts/Index",
+            json!({ "posts": [], "flash": null }),
+            "/posts".to_string(),
+        );
+        let page: Value = serde_json::from_slice(page.to_json_response().body()).unwrap();
+        assert_eq!(
+            page["props"],
+            json!({
+                "posts": [],
+                "flash": null,
+                "auth": { "user": { "name": "Ada" }, "can_edit": true },
+            })
+        );
+
+        InertiaContext::clear();
+        assert!(InertiaContext::shared().is_empty());
+    }
+
+    #[test]
+    fn partial_reloads_are_read_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(PartialReload::from_headers(&headers), None);
+
+        headers.insert("X-Inertia-Partial-Component", "Dashboard".parse().unwrap());
+        headers.insert("X-Inertia-Partial-Data", "stats, activity".parse().unwrap());
+        headers.insert("X-Inertia-Partial-Except", "activity".parse().unwrap());
+        let partial = PartialReload::from_headers(&headers).unwrap();
+        assert_eq!(partial.only, ["stats", "activity"]);
+        assert!(partial.includes("stats"));
+        assert!(!partial.includes("activity"));
+        assert!(!partial.includes("user"));
+    }
 }
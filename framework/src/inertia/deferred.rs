@@ -0,0 +1,139 @@
+//! Deferred props (Inertia v2)
+//!
+//! A deferred prop is left out of the first render. The page object lists it
+//! under `deferredProps`, and the client fetches it right after with a
+//! partial reload, so slow props don't hold up the page.
+
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+
+use crate::error::FrameworkError;
+
+/// A prop resolved after the page has rendered
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Inertia, InertiaProp};
+///
+/// Inertia::render("Dashboard", json!({ "user": user }))
+///     .defer("stats", InertiaProp::defer("default", || async { Stats::load().await }))
+///     .defer("activity", InertiaProp::defer("sidebar", move || async move {
+///         Activity::recent(user_id).await
+///     }))
+///     .render()
+///     .await
+/// ```
+pub struct InertiaProp {
+    pub(crate) group: String,
+    resolve: Box<dyn FnOnce() -> BoxFuture<'static, Result<Value, FrameworkError>> + Send>,
+}
+
+impl InertiaProp {
+    /// Defer a prop to the follow-up request for `group`
+    ///
+    /// Props in the same group are fetched together; separate groups load
+    /// in parallel.
+    pub fn defer<F, Fut, T>(group: impl Into<String>, resolve: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, FrameworkError>> + Send + 'static,
+        T: Serialize,
+    {
+        Self {
+            group: group.into(),
+            resolve: Box::new(move || {
+                resolve()
+                    .map(|value| {
+                        serde_json::to_value(value?).map_err(|e| {
+                            FrameworkError::internal(format!(
+                                "Deferred prop serialize error: {}",
+                                e
+                            ))
+                        })
+                    })
+                    .boxed()
+            }),
+        }
+    }
+
+    pub(crate) async fn resolve(self) -> Result<Value, FrameworkError> {
+        (self.resolve)().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inertia::{Inertia, InertiaContext, PartialReload};
+    use crate::testing::TestResponse;
+    use serde_json::json;
+
+    fn dashboard() -> crate::InertiaResponse {
+        Inertia::render("Dashboard", json!({ "user": "Ada", "team": "Kit" }))
+            .defer("stats", InertiaProp::defer("default", || async { Ok(42) }))
+            .defer(
+                "activity",
+                InertiaProp::defer("sidebar", || async { Ok(vec!["signed in"]) }),
+            )
+            .defer(
+                "broken",
+                InertiaProp::defer("sidebar", || async {
+                    Err::<(), _>(FrameworkError::internal("unreachable"))
+                }),
+            )
+    }
+
+    fn visit(partial: Option<PartialReload>) {
+        InertiaContext::set(InertiaContext {
+            path: "/dashboard".to_string(),
+            is_inertia: true,
+            partial,
+            ..Default::default()
+        });
+    }
+
+    #[tokio::test]
+    async fn deferred_props_are_listed_then_served_on_partial_reload() {
+        visit(None);
+        let page: Value = TestResponse::from(dashboard().resolve().await).json();
+        assert_eq!(page["props"], json!({ "user": "Ada", "team": "Kit" }));
+        assert_eq!(
+            page["deferredProps"],
+            json!({ "default": ["stats"], "sidebar": ["activity", "broken"] })
+        );
+
+        visit(Some(PartialReload {
+            component: "Dashboard".to_string(),
+            only: vec!["stats".to_string(), "activity".to_string()],
+            except: Vec::new(),
+        }));
+        let page: Value = TestResponse::from(dashboard().resolve().await).json();
+        assert_eq!(
+            page["props"],
+            json!({ "stats": 42, "activity": ["signed in"] })
+        );
+        assert!(page.get("deferredProps").is_none());
+
+        visit(Some(PartialReload {
+            component: "Dashboard".to_string(),
+            only: Vec::new(),
+            except: vec!["team".to_string(), "activity".to_string()],
+        }));
+        TestResponse::from(dashboard().resolve().await).assert_status(500);
+
+        // A reload of another page is a full visit of this one
+        visit(Some(PartialReload {
+            component: "Settings".to_string(),
+            only: vec!["stats".to_string()],
+            except: Vec::new(),
+        }));
+        let page: Value = TestResponse::from(dashboard().resolve().await).json();
+        assert_eq!(page["props"]["user"], "Ada");
+        assert!(page["deferredProps"].is_object());
+        InertiaContext::clear();
+    }
+}
@@ -0,0 +1,76 @@
+//! Inertia facade
+
+use serde::Serialize;
+
+use super::{InertiaContext, InertiaResponse};
+use crate::http::HttpResponse;
+
+/// Inertia facade
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Inertia, Request, Response};
+///
+/// pub async fn billing_portal(req: Request) -> Response {
+///     let url = billing::portal_url(&req).await?;
+///     Ok(Inertia::location(url))
+/// }
+/// ```
+pub struct Inertia;
+
+impl Inertia {
+    /// A page response for the current request
+    ///
+    /// Unlike `inertia_response!`, the component isn't checked at compile
+    /// time. Useful for adding deferred props with `InertiaResponse::defer`.
+    pub fn render(component: impl Into<String>, props: impl Serialize) -> InertiaResponse {
+        let props = serde_json::to_value(props).expect("Failed to serialize InertiaProps");
+        InertiaResponse::new(component, props, InertiaContext::current_path())
+    }
+
+    /// Redirect to a URL outside the Inertia app, e.g. an OAuth provider
+    ///
+    /// A plain 302 would be followed by the Inertia XHR and break the visit,
+    /// so Inertia requests get a 409 with `X-Inertia-Location` and the client
+    /// does a full page visit. Other requests get a normal 302.
+    pub fn location(url: impl Into<String>) -> HttpResponse {
+        let url = url.into();
+        if InertiaContext::is_inertia_request() {
+            InertiaResponse::version_conflict(&url)
+        } else {
+            HttpResponse::new().status(302).header("Location", url)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        InertiaContext::set(InertiaContext {
+            path: "/settings".to_string(),
+            query: None,
+            is_inertia: true,
+            wants_json: false,
+            version: None,
+            partial: None,
+        });
+        let response = Inertia::location("https://billing.example.com/portal");
+        assert_eq!(response.status_code(), 409);
+        assert!(response.headers().contains(&(
+            "X-Inertia-Location".to_string(),
+            "https://billing.example.com/portal".to_string()
+        )));
+
+        InertiaContext::clear();
+        let response = Inertia::location("https://billing.example.com/portal");
+        assert_eq!(response.status_code(), 302);
+        assert!(response.headers().contains(&(
+            "Location".to_string(),
+            "https://billing.example.com/portal".to_string()
+        )));
+    }
+}
@@ -1,7 +1,17 @@
 mod config;
 mod context;
+mod deferred;
+mod facade;
+mod paginated;
+pub mod props;
 mod response;
+pub(crate) mod ssr;
 
 pub use config::InertiaConfig;
-pub use context::InertiaContext;
+pub use context::{InertiaContext, PartialReload};
+pub use deferred::InertiaProp;
+pub use facade::Inertia;
+pub use paginated::{InertiaPaginated, PaginationLinks, PaginationMeta};
+pub use props::Resolved;
 pub use response::InertiaResponse;
+pub use ssr::{InertiaSsrConfig, InertiaSsrConfigBuilder};
@@ -0,0 +1,145 @@
+use serde::Serialize;
+
+use crate::database::Paginator;
+
+/// Paginated props for Inertia tables: `{ data, links, meta }`
+///
+/// Built with `Paginator::into_inertia()`; `kit generate-types` emits the
+/// matching `InertiaPaginated<T>` TypeScript interface.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InertiaPaginated<T> {
+    pub data: Vec<T>,
+    pub links: PaginationLinks,
+    pub meta: PaginationMeta,
+}
+
+/// URLs of neighbouring pages; `prev`/`next` are `null` at either end
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaginationLinks {
+    pub first: String,
+    pub last: String,
+    pub prev: Option<String>,
+    pub next: Option<String>,
+}
+
+/// Page numbers and counts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaginationMeta {
+    pub current_page: u64,
+    pub last_page: u64,
+    pub per_page: u64,
+    pub total: u64,
+    /// 1-based position of the first row on this page, `null` when empty
+    pub from: Option<u64>,
+    /// 1-based position of the last row on this page, `null` when empty
+    pub to: Option<u64>,
+    /// The page URL without a query string
+    pub path: String,
+}
+
+impl<T> InertiaPaginated<T> {
+    /// Build the props for `page`, linking to `path` with `query` kept
+    pub fn new(page: Paginator<T>, path: &str, query: Option<&str>) -> Self {
+        let last_page = page.last_page();
+        let current_page = page.current_page;
+        let (from, to) = if page.data.is_empty() {
+            (None, None)
+        } else {
+            let from = (current_page - 1) * page.per_page + 1;
+            (Some(from), Some(from + page.data.len() as u64 - 1))
+        };
+
+        let url = page_url(path, query);
+        let links = PaginationLinks {
+            first: url(1),
+            last: url(last_page),
+            prev: (current_page > 1).then(|| url(current_page - 1)),
+            next: (current_page < last_page).then(|| url(current_page + 1)),
+        };
+        let meta = PaginationMeta {
+            current_page,
+            last_page,
+            per_page: page.per_page,
+            total: page.total,
+            from,
+            to,
+            path: path.to_string(),
+        };
+
+        Self {
+            data: page.data,
+            links,
+            meta,
+        }
+    }
+}
+
+/// A function from page number to `path?<query>&page=N`
+fn page_url<'a>(path: &'a str, query: Option<&str>) -> impl Fn(u64) -> String + 'a {
+    let mut params: Vec<(String, String)> =
+        serde_urlencoded::from_str(query.unwrap_or_default()).unwrap_or_default();
+    params.retain(|(key, _)| key != "page");
+    move |page| {
+        let mut params = params.clone();
+        params.push(("page".to_string(), page.to_string()));
+        let query = serde_urlencoded::to_string(&params).unwrap_or_default();
+        format!("{}?{}", path, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn page(data: Vec<i32>, total: u64, current_page: u64) -> Paginator<i32> {
+        Paginator {
+            data,
+            total,
+            per_page: 2,
+            current_page,
+        }
+    }
+
+    #[test]
+    fn links_keep_the_query_string() {
+        let props = InertiaPaginated::new(
+            page(vec![3, 4], 5, 2),
+            "/users",
+            Some("search=ada+l&page=2&sort=name"),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&props).unwrap(),
+            json!({
+                "data": [3, 4],
+                "links": {
+                    "first": "/users?search=ada+l&sort=name&page=1",
+                    "last": "/users?search=ada+l&sort=name&page=3",
+                    "prev": "/users?search=ada+l&sort=name&page=1",
+                    "next": "/users?search=ada+l&sort=name&page=3",
+                },
+                "meta": {
+                    "current_page": 2,
+                    "last_page": 3,
+                    "per_page": 2,
+                    "total": 5,
+                    "from": 3,
+                    "to": 4,
+                    "path": "/users",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn empty_and_single_pages_have_no_neighbours() {
+        let props = InertiaPaginated::new(page(vec![], 0, 1), "/users", None);
+        assert_eq!(props.links.first, "/users?page=1");
+        assert_eq!(props.links.last, "/users?page=1");
+        assert_eq!(props.links.prev, None);
+        assert_eq!(props.links.next, None);
+        assert_eq!((props.meta.from, props.meta.to), (None, None));
+        assert_eq!(props.meta.last_page, 1);
+    }
+}
@@ -0,0 +1,186 @@
+//! Support code for `#[derive(InertiaProps)]` and `#[computed_props]`
+//!
+//! Computed props are methods marked `#[prop(computed)]` in a
+//! `#[computed_props]` impl block. Sync methods are called whenever the
+//! props are serialized; async ones are awaited by `resolve()`:
+//!
+//! ```rust,ignore
+//! #[derive(InertiaProps)]
+//! #[inertia(rename_all = "camelCase")]
+//! pub struct UserProps {
+//!     first_name: String,
+//!     last_name: String,
+//!     #[serde(skip)]
+//!     user_id: i64,
+//! }
+//!
+//! #[computed_props]
+//! impl UserProps {
+//!     #[prop(computed)]
+//!     fn full_name(&self) -> String {
+//!         format!("{} {}", self.first_name, self.last_name)
+//!     }
+//!
+//!     #[prop(computed)]
+//!     async fn unread_count(&self) -> u64 {
+//!         Notification::unread_for(self.user_id).await.unwrap_or(0)
+//!     }
+//! }
+//!
+//! // { "firstName": .., "lastName": .., "fullName": .., "unreadCount": .. }
+//! inertia_response!("Profile", props.resolve().await)
+//! ```
+//!
+//! Serializing props without `resolve()` leaves out the async props.
+
+use serde::ser::Error as _;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+/// Computed prop values, keyed by method name
+#[doc(hidden)]
+pub type ComputedValues = Result<Vec<(&'static str, Value)>, serde_json::Error>;
+
+/// Fallback for props without a `#[computed_props]` impl
+///
+/// The inherent method `#[computed_props]` generates takes precedence over
+/// this blanket one, so the derive can call it either way.
+#[doc(hidden)]
+pub trait __NoComputedProps {
+    fn __kit_computed_props(&self) -> ComputedValues {
+        Ok(Vec::new())
+    }
+}
+
+impl<T: ?Sized> __NoComputedProps for T {}
+
+/// Implemented by `#[derive(InertiaProps)]`
+#[doc(hidden)]
+pub trait __PropKey {
+    /// The serialized name of a computed prop, after `rename_all`
+    fn __prop_key(name: &'static str) -> String;
+}
+
+/// Props with their async computed values, from `resolve()`
+pub struct Resolved<P> {
+    props: P,
+    computed: ComputedValues,
+}
+
+impl<P> Resolved<P> {
+    #[doc(hidden)]
+    pub fn new(props: P, computed: ComputedValues) -> Self {
+        Self { props, computed }
+    }
+
+    /// The props themselves
+    pub fn into_inner(self) -> P {
+        self.props
+    }
+}
+
+impl<P: Serialize + __PropKey> Serialize for Resolved<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let computed = self.computed.as_ref().map_err(S::Error::custom)?;
+        let mut value = serde_json::to_value(&self.props).map_err(S::Error::custom)?;
+        if let Value::Object(object) = &mut value {
+            for (name, computed) in computed {
+                object.insert(P::__prop_key(name), computed.clone());
+            }
+        }
+        value.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{computed_props, InertiaProps};
+    use serde_json::json;
+
+    fn shorten(name: &str) -> String {
+        name.chars().take(3).collect()
+    }
+
+    #[derive(InertiaProps)]
+    #[inertia(rename_all = "camelCase")]
+    struct UserProps {
+        first_name: String,
+        last_name: String,
+        #[serde(skip)]
+        user_id: i64,
+        #[serde(rename = "handle", serialize_with = "serialize_short")]
+        screen_name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bio: Option<String>,
+    }
+
+    fn serialize_short<S: serde::Serializer>(name: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&shorten(name))
+    }
+
+    #[computed_props]
+    impl UserProps {
+        #[prop(computed)]
+        fn full_name(&self) -> String {
+            format!("{} {}", self.first_name, self.last_name)
+        }
+
+        #[prop(computed)]
+        async fn unread_count(&self) -> i64 {
+            tokio::task::yield_now().await;
+            self.user_id * 2
+        }
+
+        fn not_a_prop(&self) -> bool {
+            true
+        }
+    }
+
+    fn props() -> UserProps {
+        UserProps {
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+            user_id: 21,
+            screen_name: "adalovelace".to_string(),
+            bio: None,
+        }
+    }
+
+    #[test]
+    fn honors_serde_attributes_and_sync_computed_props() {
+        let props = props();
+        assert!(props.not_a_prop());
+        assert_eq!(
+            serde_json::to_value(&props).unwrap(),
+            json!({
+                "firstName": "Ada",
+                "lastName": "Lovelace",
+                "handle": "ada",
+                "fullName": "Ada Lovelace",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_awaits_async_computed_props() {
+        let resolved = props().resolve().await;
+        let value = serde_json::to_value(&resolved).unwrap();
+        assert_eq!(value["unreadCount"], 42);
+        assert_eq!(value["fullName"], "Ada Lovelace");
+        assert_eq!(resolved.into_inner().user_id, 21);
+    }
+
+    #[derive(InertiaProps)]
+    struct PageProps<T> {
+        items: Vec<T>,
+    }
+
+    #[test]
+    fn generic_props_without_computed_props() {
+        let props = PageProps { items: vec![1, 2] };
+        assert_eq!(
+            serde_json::to_value(&props).unwrap(),
+            json!({ "items": [1, 2] })
+        );
+    }
+}
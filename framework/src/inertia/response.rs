@@ -1,6 +1,18 @@
+use bytes::Bytes;
+use futures_util::future::try_join_all;
+use futures_util::stream;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
 use super::config::InertiaConfig;
+use super::context::{InertiaContext, PartialReload};
+use super::deferred::InertiaProp;
+use super::ssr::{self, InertiaSsrConfig};
+use crate::config::Config;
 use crate::csrf::csrf_token;
-use crate::http::HttpResponse;
+use crate::error::FrameworkError;
+use crate::http::{HttpResponse, IntoResponse, Response};
+use crate::session::Session;
 
 /// Builds Inertia responses based on request type
 pub struct InertiaResponse {
@@ -8,15 +20,29 @@ pub struct InertiaResponse {
     props: serde_json::Value,
     url: String,
     config: InertiaConfig,
+    deferred: Vec<(String, InertiaProp)>,
 }
 
 impl InertiaResponse {
-    pub fn new(component: impl Into<String>, props: serde_json::Value, url: String) -> Self {
+    /// Create a page response, adding props shared with
+    /// `InertiaContext::share` and, with a session, the `flash` prop
+    pub fn new(component: impl Into<String>, mut props: serde_json::Value, url: String) -> Self {
+        if let Some(page_props) = props.as_object_mut() {
+            for (key, value) in InertiaContext::shared() {
+                page_props.entry(key).or_insert(value);
+            }
+            if let Some(session) = Session::current() {
+                page_props
+                    .entry("flash")
+                    .or_insert_with(|| Value::Object(session.flashed()));
+            }
+        }
         Self {
             component: component.into(),
             props,
             url,
             config: InertiaConfig::default(),
+            deferred: Vec::new(),
         }
     }
 
@@ -25,31 +51,89 @@ impl InertiaResponse {
         self
     }
 
-    /// Build JSON response for XHR requests (X-Inertia: true)
-    pub fn to_json_response(&self) -> HttpResponse {
-        let page = serde_json::json!({
+    /// Add a prop that is loaded after the page renders, see `InertiaProp`
+    ///
+    /// Deferred props are resolved by `resolve()`, so return the response
+    /// with `.resolve().await`.
+    pub fn defer(mut self, key: impl Into<String>, prop: InertiaProp) -> Self {
+        self.deferred.push((key.into(), prop));
+        self
+    }
+
+    /// Resolve the deferred props the client asked for and build the response
+    ///
+    /// The first visit only lists deferred props under `deferredProps`; the
+    /// client's follow-up partial reload gets them resolved.
+    pub async fn resolve(mut self) -> Response {
+        if let Some(partial) = self.partial_reload() {
+            let requested: Vec<_> = std::mem::take(&mut self.deferred)
+                .into_iter()
+                .filter(|(key, _)| partial.includes(key))
+                .collect();
+            let values = try_join_all(requested.into_iter().map(|(key, prop)| async move {
+                Ok::<_, FrameworkError>((key, prop.resolve().await?))
+            }))
+            .await?;
+            if let Some(props) = self.props.as_object_mut() {
+                props.extend(values);
+            }
+        }
+        self.into_response()
+    }
+
+    /// The partial reload this response answers, if the client asked for
+    /// one of this component
+    fn partial_reload(&self) -> Option<PartialReload> {
+        InertiaContext::partial_reload().filter(|partial| partial.component == self.component)
+    }
+
+    /// The page object
+    ///
+    /// A partial reload gets only the props it asked for; a full visit gets
+    /// every prop but the deferred ones, which are listed by group.
+    fn page(&self) -> Value {
+        let partial = self.partial_reload();
+        let mut props = self.props.clone();
+        if let (Some(partial), Some(props)) = (&partial, props.as_object_mut()) {
+            props.retain(|key, _| partial.includes(key));
+        }
+
+        let mut page = serde_json::json!({
             "component": self.component,
-            "props": self.props,
+            "props": props,
             "url": self.url,
             "version": self.config.version,
         });
+        if partial.is_none() && !self.deferred.is_empty() {
+            let mut groups: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+            for (key, prop) in &self.deferred {
+                groups.entry(&prop.group).or_default().push(key);
+            }
+            let groups: Map<String, Value> = groups
+                .into_iter()
+                .map(|(group, keys)| (group.to_string(), keys.into()))
+                .collect();
+            page["deferredProps"] = groups.into();
+        }
+        page
+    }
 
-        HttpResponse::json(page)
+    /// Build JSON response for XHR requests (X-Inertia: true)
+    pub fn to_json_response(&self) -> HttpResponse {
+        HttpResponse::json(self.page())
             .header("X-Inertia", "true")
             .header("Vary", "X-Inertia")
     }
 
     /// Build HTML response for initial page loads
+    ///
+    /// With `InertiaSsrConfig::enabled` the body is rendered by the SSR
+    /// server while it is sent, falling back to the client-side page.
     pub fn to_html_response(&self) -> HttpResponse {
-        let page_data = serde_json::json!({
-            "component": self.component,
-            "props": self.props,
-            "url": self.url,
-            "version": self.config.version,
-        });
+        let page = self.page();
 
         // Escape JSON for HTML attribute
-        let page_json = serde_json::to_string(&page_data)
+        let page_json = serde_json::to_string(&page)
             .unwrap_or_default()
             .replace('&', "&amp;")
             .replace('<', "&lt;")
@@ -59,6 +143,7 @@ impl InertiaResponse {
 
         // Get CSRF token for meta tag (empty string if no session)
         let csrf = csrf_token().unwrap_or_default();
+        let app = format!(r#"<div id="app" data-page="{}"></div>"#, page_json);
 
         let html = if self.config.development {
             format!(
@@ -80,7 +165,7 @@ impl InertiaResponse {
     <script type="module" src="{}/{}"></script>
 </head>
 <body>
-    <div id="app" data-page="{}"></div>
+    {}
 </body>
 </html>"#,
                 csrf,
@@ -88,7 +173,7 @@ impl InertiaResponse {
                 self.config.vite_dev_server,
                 self.config.vite_dev_server,
                 self.config.entry_point,
-                page_json
+                app
             )
         } else {
             format!(
@@ -103,14 +188,28 @@ impl InertiaResponse {
     <link rel="stylesheet" href="/assets/main.css">
 </head>
 <body>
-    <div id="app" data-page="{}"></div>
+    {}
 </body>
 </html>"#,
                 csrf,
-                page_json
+                app
             )
         };
 
+        let ssr = Config::get::<InertiaSsrConfig>().unwrap_or_default();
+        if ssr.enabled {
+            let body = stream::once(async move {
+                let html = match ssr::render(&ssr, &page).await {
+                    Some(rendered) => rendered.inject(&html, &app),
+                    None => html,
+                };
+                Bytes::from(html)
+            });
+            return HttpResponse::stream(body)
+                .content_type("text/html; charset=utf-8")
+                .header("Vary", "X-Inertia");
+        }
+
         HttpResponse::text(html)
             .header("Content-Type", "text/html; charset=utf-8")
             .header("Vary", "X-Inertia")
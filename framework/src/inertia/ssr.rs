@@ -0,0 +1,211 @@
+//! Server-side rendering through a Node render server
+//!
+//! With `INERTIA_SSR_ENABLED=true`, the page object of every full page load
+//! is POSTed to the render server started from the app's SSR bundle (Inertia's
+//! `createServer`, listening on port 13714 by default). The HTML it returns
+//! is put into the page so it shows before the JavaScript loads.
+//!
+//! If the server is down, slow or answers with an error, the page is sent
+//! without it and renders on the client as usual.
+
+use bytes::Bytes;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::config::env;
+
+/// Inertia SSR configuration
+///
+/// # Environment Variables
+///
+/// - `INERTIA_SSR_ENABLED` - Render pages on the server (default: false)
+/// - `INERTIA_SSR_URL` - URL of the render server (default: "http://127.0.0.1:13714")
+/// - `INERTIA_SSR_TIMEOUT` - Seconds to wait for a render before falling
+///   back to client-side rendering (default: 5)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Config, InertiaSsrConfig};
+///
+/// // Register from environment
+/// Config::register(InertiaSsrConfig::from_env());
+///
+/// // Or build manually
+/// Config::register(InertiaSsrConfig::builder()
+///     .enabled(true)
+///     .url("http://ssr:13714")
+///     .build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct InertiaSsrConfig {
+    /// Render pages on the server
+    pub enabled: bool,
+    /// URL of the render server, without the `/render` path
+    pub url: String,
+    /// Seconds to wait for a render
+    pub timeout: u64,
+}
+
+impl InertiaSsrConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            enabled: env("INERTIA_SSR_ENABLED", false),
+            url: env("INERTIA_SSR_URL", "http://127.0.0.1:13714".to_string()),
+            timeout: env("INERTIA_SSR_TIMEOUT", 5),
+        }
+    }
+
+    /// Create a builder for manual configuration
+    pub fn builder() -> InertiaSsrConfigBuilder {
+        InertiaSsrConfigBuilder::default()
+    }
+}
+
+impl Default for InertiaSsrConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Builder for InertiaSsrConfig
+#[derive(Debug, Default)]
+pub struct InertiaSsrConfigBuilder {
+    enabled: Option<bool>,
+    url: Option<String>,
+    timeout: Option<u64>,
+}
+
+impl InertiaSsrConfigBuilder {
+    /// Render pages on the server
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    /// Set the URL of the render server
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Set the seconds to wait for a render
+    pub fn timeout(mut self, seconds: u64) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> InertiaSsrConfig {
+        let defaults = InertiaSsrConfig::from_env();
+        InertiaSsrConfig {
+            enabled: self.enabled.unwrap_or(defaults.enabled),
+            url: self.url.unwrap_or(defaults.url),
+            timeout: self.timeout.unwrap_or(defaults.timeout),
+        }
+    }
+}
+
+/// What the render server returns for a page
+#[derive(Debug, Deserialize)]
+pub(crate) struct SsrPage {
+    /// Tags for `<head>`, e.g. from `<Head>`
+    #[serde(default)]
+    pub head: Vec<String>,
+    /// The rendered app, including its `<div id="app">`
+    pub body: String,
+}
+
+impl SsrPage {
+    /// Put the rendered page into the client-side `html`, replacing `app`
+    pub fn inject(&self, html: &str, app: &str) -> String {
+        html.replacen("</head>", &format!("{}\n</head>", self.head.join("\n")), 1)
+            .replacen(app, &self.body, 1)
+    }
+}
+
+/// Render a page object, or `None` if the render server can't
+pub(crate) async fn render(config: &InertiaSsrConfig, page: &Value) -> Option<SsrPage> {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    let client = CLIENT.get_or_init(reqwest::Client::new);
+
+    let url = format!("{}/render", config.url.trim_end_matches('/'));
+    let response = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_vec(page).ok()?)
+        .timeout(Duration::from_secs(config.timeout))
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let body: Bytes = response.bytes().await.ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// A render server answering one request with `body`
+    async fn render_server(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).contains("\"component\"") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    return;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            assert!(String::from_utf8_lossy(&request).starts_with("POST /render "));
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn renders_pages_on_the_render_server() {
+        let url = render_server(
+            r#"{"head":["<title>Posts</title>"],"body":"<div id=\"app\"><h1>Posts</h1></div>"}"#,
+        )
+        .await;
+        let config = InertiaSsrConfig::builder().enabled(true).url(url).build();
+        let page = json!({ "component": "Posts/Index", "props": {} });
+
+        let rendered = render(&config, &page).await.unwrap();
+        let html = "<head>\n</head>\n<body>\n<div id=\"app\"></div>\n</body>";
+        assert_eq!(
+            rendered.inject(html, "<div id=\"app\"></div>"),
+            "<head>\n<title>Posts</title>\n</head>\n<body>\n<div id=\"app\"><h1>Posts</h1></div>\n</body>"
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_render_server_falls_back() {
+        let config = InertiaSsrConfig::builder()
+            .enabled(true)
+            .url("http://127.0.0.1:1")
+            .build();
+        assert!(render(&config, &json!({ "component": "Home" }))
+            .await
+            .is_none());
+    }
+}
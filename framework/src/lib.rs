@@ -1,60 +1,122 @@
+pub mod action;
+pub mod activity;
 pub mod app;
 pub mod auth;
+pub mod batch;
+pub mod bench;
+pub mod billing;
+pub mod blocking;
 pub mod cache;
 pub mod config;
+pub mod console;
 pub mod container;
+pub mod crypt;
 pub mod csrf;
+pub mod daemon;
 pub mod database;
 pub mod error;
+pub mod events;
+#[cfg(feature = "geo")]
+pub mod geo;
 pub mod hashing;
 pub mod http;
+pub mod import;
 pub mod inertia;
+pub mod logging;
+pub mod metrics;
 pub mod middleware;
+pub mod money;
+pub mod profile;
+pub mod queue;
+pub mod reports;
 pub mod routing;
 pub mod schedule;
 pub mod workflow;
 pub mod server;
 pub mod session;
+pub(crate) mod startup;
+pub mod slug;
+pub mod storage;
+pub mod strict;
+pub mod supervisor;
+pub mod telemetry;
 pub mod testing;
+pub mod websocket;
 
 extern crate self as kit;
 
+pub use action::Action;
+pub use activity::{Activity, ActivityConfig, ActivityItem, PruneActivities};
 pub use app::Application;
-pub use auth::{Auth, Authenticatable, AuthMiddleware, GuestMiddleware, UserProvider};
-pub use cache::{Cache, CacheConfig, CacheStore, InMemoryCache, RedisCache};
+pub use auth::{
+    Ability, Auth, Authenticatable, AuthMiddleware, Gate, GuestMiddleware, Policy, UserProvider,
+};
+pub use batch::BatchEndpoint;
+pub use billing::{Billable, BillingConfig, Stripe, StripeEvent};
+pub use blocking::blocking;
+pub use cache::{
+    Cache, CacheConfig, CacheHealth, CacheStore, EncryptedCache, FallbackCache, InMemoryCache,
+    RedisCache,
+};
 pub use config::{env, env_optional, env_required, AppConfig, Config, Environment, ServerConfig};
+pub use console::ConsoleCommand;
 pub use container::{App, Container};
+pub use crypt::{Crypt, Encrypter};
 pub use csrf::{csrf_field, csrf_meta_tag, csrf_token, CsrfMiddleware};
+pub use daemon::{Daemon, DaemonOptions, StopReason};
 pub use database::{
-    AutoRouteBinding, Database, DatabaseConfig, DatabaseType, DbConnection, Model, ModelMut,
-    RouteBinding, DB,
+    AutoRouteBinding, CursorPage, Database, DatabaseConfig, DatabaseType, DbConnection, Model,
+    ModelMut, Paginator, RouteBinding, Seeder, DB,
 };
 pub use error::{AppError, FrameworkError, HttpError, ValidationErrors};
+pub use events::{Event, EventFake};
+#[cfg(feature = "geo")]
+pub use geo::{Geo, GeoConfig, GeoDriver, Location};
 pub use hashing::{hash, needs_rehash, verify, DEFAULT_COST as HASH_DEFAULT_COST};
 pub use http::{
-    json, text, Cookie, CookieOptions, FormRequest, FromParam, FromRequest, HttpResponse, Redirect,
-    Request, Response, ResponseExt, SameSite,
+    json, sanitize_html, text, CamelCaseJson, Cookie, CookieConfig, CookieOptions, ETag,
+    ErrorFormat, ExceptionHandler, Feed, FeedEntry, FormRequest, ForwardedHeader, FromParam,
+    FromRequest, FromRequestRef, HtmlPolicy, HttpResponse, IntoResponse, Json, MultipartForm,
+    Query, Redirect, Request, Response, ResponseExt, Rule, RuleCheck, Rules, SameSite,
+    SanitizeHtml, SseEvent, SseResponse, TrustedProxies, TrustedProxyConfig, UploadRules,
+    UploadedFile, ValidateUpload,
 };
 pub use session::{
-    session, session_mut, SessionConfig, SessionData, SessionMiddleware, SessionStore,
+    session, session_mut, Session, SessionConfig, SessionData, SessionMiddleware, SessionStore,
+};
+pub use slug::Slug;
+pub use strict::StrictMode;
+pub use storage::{Disk, FakeDisk, LocalDisk, S3Config, S3Disk, Storage, StorageConfig};
+pub use websocket::{Channel, WebSocket};
+pub use import::{Import, ImportStatus, ImportSummary};
+pub use inertia::{
+    Inertia, InertiaConfig, InertiaContext, InertiaPaginated, InertiaProp, InertiaResponse,
+    InertiaSsrConfig, PartialReload,
 };
-pub use inertia::{InertiaConfig, InertiaContext, InertiaResponse};
+pub use logging::{Redaction, RequestLogMiddleware};
+pub use money::{Currency, Money, MoneyError, Rounding};
+pub use queue::{Job, Queue, QueueConfig, QueueWorker};
 pub use middleware::{
     register_global_middleware, Middleware, MiddlewareFuture, MiddlewareRegistry, Next,
 };
+pub use reports::{Report, ReportConfig, ReportFailed, ReportReady};
 pub use routing::{
     route, validate_route_path,
     // Internal functions used by macros (hidden from docs)
-    __delete_impl, __fallback_impl, __get_impl, __post_impl, __put_impl,
+    __delete_impl, __fallback_impl, __get_impl, __patch_impl, __post_impl, __put_impl,
     FallbackDefBuilder, GroupBuilder, GroupDef, GroupItem, GroupRoute, GroupRouter,
-    IntoGroupItem, RouteBuilder, RouteDefBuilder, Router,
+    IntoGroupItem, RouteBuilder, RouteDefBuilder, RouteError, RouteErrors, RouteInfo, Router,
+};
+pub use schedule::{
+    CronExpression, DayOfWeek, Schedule, ScheduleError, Task, TaskBuilder, TaskEntry, TaskResult,
 };
-pub use schedule::{CronExpression, DayOfWeek, Schedule, Task, TaskBuilder, TaskEntry, TaskResult};
 pub use workflow::{
     start_named, StepStatus, WorkflowConfig, WorkflowContext, WorkflowHandle, WorkflowStatus,
     WorkflowWorker,
 };
 pub use server::Server;
+pub use supervisor::{Supervisor, SupervisorConfig};
+pub use telemetry::{TelemetryConfig, TelemetryConfigBuilder};
 
 // Re-export async_trait for middleware implementations
 pub use async_trait::async_trait;
@@ -67,6 +129,10 @@ pub use inventory;
 #[doc(hidden)]
 pub use serde_json;
 
+// Re-export clap for #[console_command] argument parsing
+#[doc(hidden)]
+pub use clap;
+
 // Re-export serde for InertiaProps derive macro
 pub use serde;
 
@@ -75,22 +141,33 @@ pub use validator;
 pub use validator::Validate;
 
 // Re-export the proc-macros for compile-time component validation and type safety
+pub use kit_macros::authorize;
+pub use kit_macros::blocking;
+pub use kit_macros::computed_props;
+pub use kit_macros::console_command;
 pub use kit_macros::domain_error;
 pub use kit_macros::handler;
 pub use kit_macros::inertia_response;
+pub use kit_macros::respond_with;
 pub use kit_macros::injectable;
+pub use kit_macros::job;
+pub use kit_macros::listener;
 pub use kit_macros::redirect;
+pub use kit_macros::report;
 pub use kit_macros::request;
 pub use kit_macros::service;
+pub use kit_macros::sluggable;
 pub use kit_macros::workflow;
 pub use kit_macros::workflow_step;
 pub use kit_macros::FormRequest as FormRequestDerive;
 pub use kit_macros::InertiaProps;
+pub use kit_macros::MapFrom;
 pub use kit_macros::kit_test;
 
 // Re-export Jest-like testing macros
 pub use kit_macros::describe;
 pub use kit_macros::test;
+pub use kit_macros::test_each;
 
 #[macro_export]
 macro_rules! json_response {
@@ -99,6 +176,13 @@ macro_rules! json_response {
     };
 }
 
+#[macro_export]
+macro_rules! sse_response {
+    ($stream:expr) => {
+        Ok($crate::HttpResponse::from($crate::SseResponse::new($stream)))
+    };
+}
+
 #[macro_export]
 macro_rules! text_response {
     ($text:expr) => {
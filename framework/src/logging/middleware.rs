@@ -0,0 +1,210 @@
+//! Request logging middleware
+
+use super::Redaction;
+use crate::http::{HttpResponse, Request, Response};
+use crate::middleware::{Middleware, Next};
+use async_trait::async_trait;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Logs every request, and the request details of server errors
+///
+/// Each request is logged as `METHOD /path?query STATUS DURATION from IP`,
+/// with the client IP from `Request::client_ip()`. For
+/// server errors (5xx) the log also includes the error message and the
+/// request headers, so the failure can be reproduced; `.with_body()` adds
+/// the request input. Sensitive values are redacted with the registered
+/// `Redaction` rules (see `Redaction::from_env`).
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// use kit::{global_middleware, RequestLogMiddleware};
+///
+/// global_middleware!(RequestLogMiddleware::new().with_headers().with_body());
+/// ```
+pub struct RequestLogMiddleware {
+    headers: bool,
+    body: bool,
+    redaction: Option<Redaction>,
+}
+
+impl RequestLogMiddleware {
+    /// Log the request line, plus headers for server errors
+    pub fn new() -> Self {
+        Self {
+            headers: false,
+            body: false,
+            redaction: None,
+        }
+    }
+
+    /// Log the headers of every request, not only failed ones
+    pub fn with_headers(mut self) -> Self {
+        self.headers = true;
+        self
+    }
+
+    /// Log the request input (JSON and form bodies)
+    ///
+    /// Bodies are read into memory before the handler runs.
+    pub fn with_body(mut self) -> Self {
+        self.body = true;
+        self
+    }
+
+    /// Use these rules instead of the registered `Redaction`
+    pub fn redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = Some(redaction);
+        self
+    }
+}
+
+impl Default for RequestLogMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Middleware for RequestLogMiddleware {
+    async fn handle(&self, request: Request, next: Next) -> Response {
+        let redaction = self.redaction.clone().unwrap_or_else(Redaction::current);
+        let started = Instant::now();
+
+        let uri = request.inner().uri();
+        let mut line = format!("{} {}", request.method(), uri.path());
+        if let Some(query) = uri.query() {
+            line.push('?');
+            line.push_str(&redaction.redact_query(query));
+        }
+        let headers = redaction.redact_headers(request.inner().headers());
+        let client = request.client_ip();
+
+        let (request, input) = if self.body {
+            let content_type = request.content_type().map(str::to_string);
+            match request.buffer_body().await {
+                Ok((request, body)) => {
+                    let input = redaction.redact_body(content_type.as_deref(), &body);
+                    (request, Some(input))
+                }
+                Err(err) => return Err(HttpResponse::from(err)),
+            }
+        } else {
+            (request, None)
+        };
+
+        let response = next(request).await;
+
+        let (Ok(http) | Err(http)) = &response;
+        let entry = LogEntry {
+            line,
+            status: http.status_code(),
+            duration: started.elapsed(),
+            client,
+            error: http.error(),
+            headers: &headers,
+            input: input.as_deref(),
+        };
+        eprintln!("{}", entry.format(self.headers));
+
+        response
+    }
+}
+
+/// One request's log output
+struct LogEntry<'a> {
+    /// Method, path and redacted query
+    line: String,
+    status: u16,
+    duration: Duration,
+    /// `None` for requests that didn't come through the server
+    client: Option<IpAddr>,
+    error: Option<&'a str>,
+    headers: &'a serde_json::Value,
+    input: Option<&'a str>,
+}
+
+impl LogEntry<'_> {
+    /// The log text; details are always included for server errors
+    fn format(&self, headers: bool) -> String {
+        let mut text = format!(
+            "{} {} {}ms",
+            self.line,
+            self.status,
+            self.duration.as_millis()
+        );
+        if let Some(client) = self.client {
+            text.push_str(&format!(" from {}", client));
+        }
+        let failed = self.status >= 500;
+        if failed {
+            if let Some(error) = self.error {
+                text.push_str(&format!("\n  error: {}", error));
+            }
+        }
+        if failed || headers {
+            text.push_str(&format!("\n  headers: {}", self.headers));
+        }
+        if let Some(input) = self.input.filter(|input| !input.is_empty()) {
+            text.push_str(&format!("\n  input: {}", input));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FrameworkError;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_reads_body_without_consuming_it() {
+        let middleware = RequestLogMiddleware::new().with_body();
+        let next: Next = Arc::new(|request: Request| {
+            Box::pin(async move {
+                let input: serde_json::Value = request.json().await?;
+                Ok(HttpResponse::json(input))
+            })
+        });
+
+        let request = Request::fake()
+            .method("POST")
+            .json(serde_json::json!({"password": "hunter2"}))
+            .build();
+        let Ok(response) = middleware.handle(request, next).await else {
+            panic!("expected the handler's response");
+        };
+
+        // Only the log is redacted, not what the handler sees
+        assert_eq!(response.body().as_ref(), br#"{"password":"hunter2"}"#);
+    }
+
+    #[test]
+    fn test_format() {
+        let headers = serde_json::json!({"cookie": "[REDACTED]"});
+        let error = HttpResponse::from(FrameworkError::internal("boom"));
+        let mut entry = LogEntry {
+            line: "POST /login?token=[REDACTED]".to_string(),
+            status: 200,
+            duration: Duration::from_millis(12),
+            client: Some("203.0.113.9".parse().unwrap()),
+            error: error.error(),
+            headers: &headers,
+            input: Some(r#"{"password":"[REDACTED]"}"#),
+        };
+
+        assert_eq!(
+            entry.format(false),
+            "POST /login?token=[REDACTED] 200 12ms from 203.0.113.9\n  input: {\"password\":\"[REDACTED]\"}"
+        );
+
+        entry.status = 500;
+        assert_eq!(
+            entry.format(false),
+            "POST /login?token=[REDACTED] 500 12ms from 203.0.113.9\n  error: Internal server error: boom\n  \
+             headers: {\"cookie\":\"[REDACTED]\"}\n  input: {\"password\":\"[REDACTED]\"}"
+        );
+    }
+}
@@ -0,0 +1,30 @@
+//! Request logging with credential redaction
+//!
+//! `RequestLogMiddleware` logs one line per request, and for server errors
+//! the error with the request's headers and input. Values of sensitive
+//! fields (`password`, `token`, ...) and headers (`Authorization`,
+//! `Cookie`, ...) are replaced with `[REDACTED]` before anything is written,
+//! following the `Redaction` rules.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{global_middleware, RequestLogMiddleware};
+//!
+//! pub async fn register() {
+//!     global_middleware!(RequestLogMiddleware::new());
+//! }
+//! ```
+//!
+//! ```text
+//! POST /login?redirect=%2Fhome 500 12ms
+//!   error: Database error: connection refused
+//!   headers: {"content-type":"application/json","cookie":"[REDACTED]"}
+//!   input: {"email":"ada@example.com","password":"[REDACTED]"}
+//! ```
+
+mod middleware;
+mod redaction;
+
+pub use middleware::RequestLogMiddleware;
+pub use redaction::{Redaction, REDACTED};
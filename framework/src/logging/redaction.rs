@@ -0,0 +1,292 @@
+//! Rules for keeping credentials out of logs
+
+use crate::config::{env_optional, Config};
+use hyper::HeaderMap;
+use serde_json::Value;
+
+/// Replacement for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Field names redacted by default, matched anywhere in the name
+const DEFAULT_FIELDS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "credit_card",
+    "card_number",
+    "cvv",
+    "ssn",
+];
+
+/// Header names redacted by default
+const DEFAULT_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "x-csrf-token",
+    "x-xsrf-token",
+];
+
+/// Which request fields and headers are redacted in logs
+///
+/// A field is sensitive when its name contains one of the field rules,
+/// ignoring case and treating `-` as `_`: the `password` rule covers
+/// `password_confirmation` and `currentPassword`. Header rules match the
+/// whole header name, ignoring case.
+///
+/// # Environment Variables
+///
+/// - `LOG_REDACT_FIELDS` - Comma-separated field names to redact in
+///   addition to the defaults (`password`, `token`, `secret`, ...)
+/// - `LOG_REDACT_HEADERS` - Comma-separated header names to redact in
+///   addition to the defaults (`Authorization`, `Cookie`, ...)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Config, Redaction};
+///
+/// Config::register(Redaction::defaults().field("iban").header("X-Signature"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Redaction {
+    fields: Vec<String>,
+    headers: Vec<String>,
+}
+
+impl Redaction {
+    /// The defaults plus the rules from environment variables
+    pub fn from_env() -> Self {
+        let mut redaction = Self::defaults();
+        for field in env_list("LOG_REDACT_FIELDS") {
+            redaction = redaction.field(&field);
+        }
+        for header in env_list("LOG_REDACT_HEADERS") {
+            redaction = redaction.header(&header);
+        }
+        redaction
+    }
+
+    /// Redact common credential fields and headers
+    pub fn defaults() -> Self {
+        let redaction = DEFAULT_FIELDS
+            .iter()
+            .fold(Self::none(), |redaction, field| redaction.field(field));
+        DEFAULT_HEADERS
+            .iter()
+            .fold(redaction, |redaction, header| redaction.header(header))
+    }
+
+    /// Redact nothing
+    pub fn none() -> Self {
+        Self {
+            fields: Vec::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Also redact fields whose name contains `name`
+    pub fn field(mut self, name: &str) -> Self {
+        self.fields.push(normalize_field(name));
+        self
+    }
+
+    /// Also redact the header `name`
+    pub fn header(mut self, name: &str) -> Self {
+        self.headers.push(name.to_ascii_lowercase());
+        self
+    }
+
+    /// Whether values of the field `name` are redacted
+    pub fn is_sensitive_field(&self, name: &str) -> bool {
+        let name = normalize_field(name);
+        self.fields.iter().any(|field| name.contains(field.as_str()))
+    }
+
+    /// Whether values of the header `name` are redacted
+    pub fn is_sensitive_header(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|header| header.eq_ignore_ascii_case(name))
+    }
+
+    /// The headers as a JSON object, with sensitive values redacted
+    pub fn redact_headers(&self, headers: &HeaderMap) -> Value {
+        let mut object = serde_json::Map::new();
+        for (name, value) in headers {
+            let value = if self.is_sensitive_header(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            object.insert(name.as_str().to_string(), Value::String(value));
+        }
+        Value::Object(object)
+    }
+
+    /// Redact sensitive fields anywhere in a JSON value
+    pub fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    if self.is_sensitive_field(key) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_json(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_json(item)),
+            _ => {}
+        }
+    }
+
+    /// Redact sensitive parameters in a query string or form body
+    ///
+    /// Other parameters are kept as they were encoded.
+    pub fn redact_query(&self, query: &str) -> String {
+        query
+            .split('&')
+            .map(|pair| {
+                let key = pair.split('=').next().unwrap_or_default();
+                let decoded = serde_urlencoded::from_str::<Vec<(String, String)>>(key)
+                    .ok()
+                    .and_then(|pairs| pairs.into_iter().next())
+                    .map_or_else(|| key.to_string(), |(key, _)| key);
+                if self.is_sensitive_field(&decoded) {
+                    format!("{}={}", key, REDACTED)
+                } else {
+                    pair.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// A request body for logging, with sensitive fields redacted
+    ///
+    /// JSON and form bodies are shown; other content types only by size,
+    /// since they can't be redacted.
+    pub fn redact_body(&self, content_type: Option<&str>, body: &[u8]) -> String {
+        if body.is_empty() {
+            return String::new();
+        }
+        let content_type = content_type.unwrap_or_default();
+        if content_type.starts_with("application/x-www-form-urlencoded") {
+            return self.redact_query(&String::from_utf8_lossy(body));
+        }
+        if content_type.contains("json") {
+            if let Ok(mut value) = serde_json::from_slice::<Value>(body) {
+                self.redact_json(&mut value);
+                return value.to_string();
+            }
+        }
+        format!("<{} bytes>", body.len())
+    }
+
+    /// The registered rules, or the ones from the environment
+    pub(crate) fn current() -> Self {
+        Config::get::<Redaction>().unwrap_or_else(Self::from_env)
+    }
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self::defaults()
+    }
+}
+
+fn normalize_field(name: &str) -> String {
+    name.to_ascii_lowercase().replace('-', "_")
+}
+
+fn env_list(name: &str) -> Vec<String> {
+    env_optional::<String>(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensitive_names() {
+        let redaction = Redaction::defaults().field("iban").header("X-Signature");
+
+        assert!(redaction.is_sensitive_field("password_confirmation"));
+        assert!(redaction.is_sensitive_field("currentPassword"));
+        assert!(redaction.is_sensitive_field("_token"));
+        assert!(redaction.is_sensitive_field("api-key"));
+        assert!(redaction.is_sensitive_field("IBAN"));
+        assert!(!redaction.is_sensitive_field("email"));
+        assert!(redaction.is_sensitive_header("Cookie"));
+        assert!(redaction.is_sensitive_header("x-signature"));
+        assert!(!redaction.is_sensitive_header("Accept"));
+        assert!(!Redaction::none().is_sensitive_field("password"));
+    }
+
+    #[test]
+    fn test_json_is_redacted_at_any_depth() {
+        let mut value = serde_json::json!({
+            "email": "ada@example.com",
+            "password": "hunter2",
+            "cards": [{"card_number": "4242", "brand": "visa"}],
+            "auth": {"refresh_token": {"value": "abc"}},
+        });
+        Redaction::defaults().redact_json(&mut value);
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "email": "ada@example.com",
+                "password": REDACTED,
+                "cards": [{"card_number": REDACTED, "brand": "visa"}],
+                "auth": {"refresh_token": REDACTED},
+            })
+        );
+    }
+
+    #[test]
+    fn test_query_and_body() {
+        let redaction = Redaction::defaults();
+
+        assert_eq!(
+            redaction.redact_query("page=2&access%5Ftoken=abc&q=a%20b&flag"),
+            "page=2&access%5Ftoken=[REDACTED]&q=a%20b&flag"
+        );
+        assert_eq!(
+            redaction.redact_body(
+                Some("application/x-www-form-urlencoded"),
+                b"email=ada%40example.com&password=hunter2"
+            ),
+            "email=ada%40example.com&password=[REDACTED]"
+        );
+        assert_eq!(
+            redaction.redact_body(Some("application/json"), br#"{"token":"abc"}"#),
+            r#"{"token":"[REDACTED]"}"#
+        );
+        assert_eq!(
+            redaction.redact_body(Some("image/png"), &[0x89, b'P']),
+            "<2 bytes>"
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer abc".parse().unwrap());
+        headers.insert("accept", "text/html".parse().unwrap());
+        assert_eq!(
+            redaction.redact_headers(&headers),
+            serde_json::json!({"authorization": REDACTED, "accept": "text/html"})
+        );
+    }
+}
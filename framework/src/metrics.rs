@@ -0,0 +1,251 @@
+//! Per-request metrics and the slow request log
+//!
+//! The server measures every request and logs a warning for requests slower
+//! than `SERVER_SLOW_REQUEST_MS` (default 1000, `0` disables), including the
+//! route name, status, authenticated user, and number of database queries.
+//!
+//! In development the slowest requests are also summarized every
+//! `SERVER_SLOW_SUMMARY_MINUTES` (default 5):
+//!
+//! ```text
+//! Warning: slow request took 1532ms: GET /users/5 (users.show) 200, user 42, 17 queries
+//!
+//! Slowest requests in the last 5 minutes:
+//!   1532ms  GET /users/5 (users.show) 200, user 42, 17 queries
+//!    840ms  GET /reports (reports.index) 200, 3 queries
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How many requests the periodic summary keeps
+const SUMMARY_SIZE: usize = 10;
+
+tokio::task_local! {
+    static REQUEST_METRICS: Arc<RequestMetrics>;
+}
+
+/// Slowest requests since the last summary, slowest first
+static SLOWEST: Mutex<Vec<SlowRequest>> = Mutex::new(Vec::new());
+
+/// Dispatch timings per action type
+static ACTIONS: Mutex<Vec<ActionTiming>> = Mutex::new(Vec::new());
+
+/// Counters collected while a request is being handled
+#[derive(Debug)]
+pub struct RequestMetrics {
+    queries: AtomicU64,
+    user_id: AtomicI64,
+}
+
+impl Default for RequestMetrics {
+    fn default() -> Self {
+        Self {
+            queries: AtomicU64::new(0),
+            user_id: AtomicI64::new(i64::MIN),
+        }
+    }
+}
+
+impl RequestMetrics {
+    /// Number of database queries executed
+    pub fn queries(&self) -> u64 {
+        self.queries.load(Ordering::Relaxed)
+    }
+
+    /// Authenticated user, if any
+    pub fn user_id(&self) -> Option<i64> {
+        match self.user_id.load(Ordering::Relaxed) {
+            i64::MIN => None,
+            id => Some(id),
+        }
+    }
+}
+
+/// Run `future` with fresh metrics for one request
+pub async fn scope<F: Future>(metrics: Arc<RequestMetrics>, future: F) -> F::Output {
+    REQUEST_METRICS.scope(metrics, future).await
+}
+
+/// Count a database query against the current request
+///
+/// Does nothing outside of a request.
+pub fn record_query() {
+    let _ = REQUEST_METRICS.try_with(|metrics| metrics.queries.fetch_add(1, Ordering::Relaxed));
+}
+
+/// Record the authenticated user for the current request
+pub fn record_user(user_id: i64) {
+    let _ = REQUEST_METRICS.try_with(|metrics| metrics.user_id.store(user_id, Ordering::Relaxed));
+}
+
+/// Calls and time spent in one `Action` type
+#[derive(Debug, Clone)]
+pub struct ActionTiming {
+    /// Type name of the action
+    pub name: &'static str,
+    pub calls: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+/// Record one dispatch of an action
+pub fn record_action(name: &'static str, duration: Duration) {
+    let mut actions = ACTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    match actions.iter_mut().find(|timing| timing.name == name) {
+        Some(timing) => {
+            timing.calls += 1;
+            timing.total += duration;
+            timing.max = timing.max.max(duration);
+        }
+        None => actions.push(ActionTiming {
+            name,
+            calls: 1,
+            total: duration,
+            max: duration,
+        }),
+    }
+}
+
+/// Timings of every action dispatched since startup
+pub fn action_timings() -> Vec<ActionTiming> {
+    ACTIONS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// A request that exceeded the slow request threshold
+#[derive(Debug, Clone)]
+pub struct SlowRequest {
+    pub method: String,
+    pub path: String,
+    /// Route name, if the matched route is named
+    pub route: Option<String>,
+    pub status: u16,
+    pub duration: Duration,
+    pub user_id: Option<i64>,
+    pub queries: u64,
+}
+
+impl fmt::Display for SlowRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)?;
+        if let Some(route) = &self.route {
+            write!(f, " ({})", route)?;
+        }
+        write!(f, " {}", self.status)?;
+        if let Some(user_id) = self.user_id {
+            write!(f, ", user {}", user_id)?;
+        }
+        write!(f, ", {} queries", self.queries)
+    }
+}
+
+/// Log a slow request and keep it for the next summary
+pub fn report_slow(request: SlowRequest) {
+    eprintln!(
+        "Warning: slow request took {}ms: {}",
+        request.duration.as_millis(),
+        request
+    );
+
+    let mut slowest = SLOWEST.lock().unwrap_or_else(|e| e.into_inner());
+    let position = slowest
+        .iter()
+        .position(|r| r.duration < request.duration)
+        .unwrap_or(slowest.len());
+    if position < SUMMARY_SIZE {
+        slowest.insert(position, request);
+        slowest.truncate(SUMMARY_SIZE);
+    }
+}
+
+/// Take the slowest requests collected since the last call
+pub fn take_slowest() -> Vec<SlowRequest> {
+    std::mem::take(&mut *SLOWEST.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Print the slowest requests every `interval`
+pub fn spawn_summary(interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let slowest = take_slowest();
+            if slowest.is_empty() {
+                continue;
+            }
+            eprintln!();
+            eprintln!(
+                "Slowest requests in the last {} minutes:",
+                interval.as_secs() / 60
+            );
+            for request in slowest {
+                eprintln!("  {:>6}ms  {}", request.duration.as_millis(), request);
+            }
+            eprintln!();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slow(path: &str, millis: u64) -> SlowRequest {
+        SlowRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            route: None,
+            status: 200,
+            duration: Duration::from_millis(millis),
+            user_id: None,
+            queries: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scope_collects_queries_and_user() {
+        let metrics = Arc::new(RequestMetrics::default());
+        scope(metrics.clone(), async {
+            record_query();
+            record_query();
+            record_user(42);
+        })
+        .await;
+
+        // Outside of a request nothing is recorded
+        record_query();
+
+        assert_eq!(metrics.queries(), 2);
+        assert_eq!(metrics.user_id(), Some(42));
+    }
+
+    #[test]
+    fn test_slowest_are_kept_in_order() {
+        take_slowest();
+        for (i, millis) in [1200, 3000, 1500].into_iter().enumerate() {
+            report_slow(slow(&format!("/{}", i), millis));
+        }
+
+        let slowest = take_slowest();
+        let paths: Vec<&str> = slowest.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/1", "/2", "/0"]);
+        assert!(take_slowest().is_empty());
+    }
+
+    #[test]
+    fn test_slow_request_display() {
+        let mut request = slow("/users/5", 1532);
+        request.route = Some("users.show".to_string());
+        request.user_id = Some(42);
+        request.queries = 17;
+
+        assert_eq!(
+            request.to_string(),
+            "GET /users/5 (users.show) 200, user 42, 17 queries"
+        );
+    }
+}
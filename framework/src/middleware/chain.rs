@@ -48,10 +48,17 @@ impl MiddlewareChain {
             return handler(request).await;
         }
 
+        self.compose(handler)(request).await
+    }
+
+    /// Wrap `handler` in the chain, returning a function that runs both
+    ///
+    /// The result can be stored and called for many requests, see
+    /// `Router::finalize`.
+    pub fn compose(self, handler: Arc<BoxedHandler>) -> Next {
         // Build the chain from inside-out
         // Start with the actual handler as the innermost "next"
-        let handler_clone = handler.clone();
-        let mut next: Next = Arc::new(move |req| handler_clone(req));
+        let mut next: Next = Arc::new(move |req| handler(req));
 
         // Wrap each middleware around the next, from last to first
         // This creates the correct execution order: first middleware runs first
@@ -65,8 +72,8 @@ impl MiddlewareChain {
             });
         }
 
-        // Execute the outermost middleware (which was the first added)
-        next(request).await
+        // The outermost middleware (which was the first added) runs first
+        next
     }
 }
 
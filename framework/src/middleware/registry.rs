@@ -4,10 +4,21 @@
 //! or use `Server::middleware()` for manual configuration.
 
 use super::{into_boxed, BoxedMiddleware, Middleware};
+use std::any::TypeId;
 use std::sync::{OnceLock, RwLock};
 
 /// Global middleware registry (populated via `global_middleware!` macro in bootstrap.rs)
-static GLOBAL_MIDDLEWARE: OnceLock<RwLock<Vec<BoxedMiddleware>>> = OnceLock::new();
+///
+/// The middleware type is kept so route groups can opt out with `without_middleware`.
+static GLOBAL_MIDDLEWARE: OnceLock<RwLock<Vec<GlobalEntry>>> = OnceLock::new();
+
+/// A registered global middleware with its type
+#[derive(Clone)]
+struct GlobalEntry {
+    type_id: TypeId,
+    name: &'static str,
+    middleware: BoxedMiddleware,
+}
 
 /// Register a global middleware that runs on every request
 ///
@@ -23,14 +34,18 @@ static GLOBAL_MIDDLEWARE: OnceLock<RwLock<Vec<BoxedMiddleware>>> = OnceLock::new
 pub fn register_global_middleware<M: Middleware + 'static>(middleware: M) {
     let registry = GLOBAL_MIDDLEWARE.get_or_init(|| RwLock::new(Vec::new()));
     if let Ok(mut vec) = registry.write() {
-        vec.push(into_boxed(middleware));
+        vec.push(GlobalEntry {
+            type_id: TypeId::of::<M>(),
+            name: type_name::<M>(),
+            middleware: into_boxed(middleware),
+        });
     }
 }
 
-/// Get all registered global middleware
+/// Get all registered global middleware, with the type of each
 ///
 /// Used internally by `Server::from_config()` to apply middleware.
-pub fn get_global_middleware() -> Vec<BoxedMiddleware> {
+fn global_entries() -> Vec<GlobalEntry> {
     GLOBAL_MIDDLEWARE
         .get()
         .and_then(|lock| lock.read().ok())
@@ -38,6 +53,16 @@ pub fn get_global_middleware() -> Vec<BoxedMiddleware> {
         .unwrap_or_default()
 }
 
+/// The type name of `M` without its module path, e.g. `CorsMiddleware`
+fn type_name<M>() -> &'static str {
+    let name = std::any::type_name::<M>();
+    let path = name.split('<').next().unwrap_or(name);
+    match path.rfind("::") {
+        Some(i) => &name[i + 2..],
+        None => name,
+    }
+}
+
 /// Registry for global middleware that runs on every request
 ///
 /// # Example
@@ -52,20 +77,31 @@ pub fn get_global_middleware() -> Vec<BoxedMiddleware> {
 pub struct MiddlewareRegistry {
     /// Middleware that runs on every request (in order)
     global: Vec<BoxedMiddleware>,
+    /// Type of each global middleware, in the same order
+    global_types: Vec<TypeId>,
+    /// Type name of each global middleware, in the same order
+    global_names: Vec<&'static str>,
 }
 
 impl MiddlewareRegistry {
     /// Create a new empty middleware registry
     pub fn new() -> Self {
-        Self { global: Vec::new() }
+        Self {
+            global: Vec::new(),
+            global_types: Vec::new(),
+            global_names: Vec::new(),
+        }
     }
 
     /// Create a registry pre-populated with globally registered middleware
     ///
     /// This pulls middleware registered via `global_middleware!` in bootstrap.rs.
     pub fn from_global() -> Self {
+        let entries = global_entries();
         Self {
-            global: get_global_middleware(),
+            global: entries.iter().map(|e| e.middleware.clone()).collect(),
+            global_types: entries.iter().map(|e| e.type_id).collect(),
+            global_names: entries.iter().map(|e| e.name).collect(),
         }
     }
 
@@ -82,6 +118,8 @@ impl MiddlewareRegistry {
     /// ```
     pub fn append<M: Middleware + 'static>(mut self, middleware: M) -> Self {
         self.global.push(into_boxed(middleware));
+        self.global_types.push(TypeId::of::<M>());
+        self.global_names.push(type_name::<M>());
         self
     }
 
@@ -89,6 +127,23 @@ impl MiddlewareRegistry {
     pub fn global_middleware(&self) -> &[BoxedMiddleware] {
         &self.global
     }
+
+    /// Type names of the global middleware, e.g. `["CorsMiddleware"]`
+    pub fn global_middleware_names(&self) -> &[&'static str] {
+        &self.global_names
+    }
+
+    /// Get the global middleware, skipping the given middleware types
+    ///
+    /// Used for routes in groups that opted out with `without_middleware`.
+    pub fn global_middleware_except(&self, excluded: &[TypeId]) -> Vec<BoxedMiddleware> {
+        self.global
+            .iter()
+            .zip(&self.global_types)
+            .filter(|(_, type_id)| !excluded.contains(type_id))
+            .map(|(middleware, _)| middleware.clone())
+            .collect()
+    }
 }
 
 impl Default for MiddlewareRegistry {
@@ -0,0 +1,114 @@
+//! ISO 4217 currencies
+
+use std::fmt;
+
+/// A currency and the number of decimals its amounts use
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::money::Currency;
+///
+/// assert_eq!(Currency::USD.decimals(), 2);
+/// assert_eq!(Currency::from_code("jpy"), Some(Currency::JPY));
+///
+/// // Currencies that aren't built in
+/// const POINTS: Currency = Currency::new("PTS", 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency {
+    code: &'static str,
+    decimals: u32,
+}
+
+impl Currency {
+    pub const USD: Currency = Currency::new("USD", 2);
+    pub const EUR: Currency = Currency::new("EUR", 2);
+    pub const GBP: Currency = Currency::new("GBP", 2);
+    pub const CHF: Currency = Currency::new("CHF", 2);
+    pub const CAD: Currency = Currency::new("CAD", 2);
+    pub const AUD: Currency = Currency::new("AUD", 2);
+    pub const NZD: Currency = Currency::new("NZD", 2);
+    pub const SEK: Currency = Currency::new("SEK", 2);
+    pub const NOK: Currency = Currency::new("NOK", 2);
+    pub const DKK: Currency = Currency::new("DKK", 2);
+    pub const PLN: Currency = Currency::new("PLN", 2);
+    pub const CZK: Currency = Currency::new("CZK", 2);
+    pub const CNY: Currency = Currency::new("CNY", 2);
+    pub const HKD: Currency = Currency::new("HKD", 2);
+    pub const SGD: Currency = Currency::new("SGD", 2);
+    pub const INR: Currency = Currency::new("INR", 2);
+    pub const PKR: Currency = Currency::new("PKR", 2);
+    pub const AED: Currency = Currency::new("AED", 2);
+    pub const SAR: Currency = Currency::new("SAR", 2);
+    pub const TRY: Currency = Currency::new("TRY", 2);
+    pub const BRL: Currency = Currency::new("BRL", 2);
+    pub const MXN: Currency = Currency::new("MXN", 2);
+    pub const ZAR: Currency = Currency::new("ZAR", 2);
+    pub const JPY: Currency = Currency::new("JPY", 0);
+    pub const KRW: Currency = Currency::new("KRW", 0);
+    pub const HUF: Currency = Currency::new("HUF", 2);
+    pub const KWD: Currency = Currency::new("KWD", 3);
+    pub const BHD: Currency = Currency::new("BHD", 3);
+
+    /// Currencies `from_code` knows
+    const ALL: &'static [Currency] = &[
+        Self::USD,
+        Self::EUR,
+        Self::GBP,
+        Self::CHF,
+        Self::CAD,
+        Self::AUD,
+        Self::NZD,
+        Self::SEK,
+        Self::NOK,
+        Self::DKK,
+        Self::PLN,
+        Self::CZK,
+        Self::CNY,
+        Self::HKD,
+        Self::SGD,
+        Self::INR,
+        Self::PKR,
+        Self::AED,
+        Self::SAR,
+        Self::TRY,
+        Self::BRL,
+        Self::MXN,
+        Self::ZAR,
+        Self::JPY,
+        Self::KRW,
+        Self::HUF,
+        Self::KWD,
+        Self::BHD,
+    ];
+
+    /// A currency with `code` whose amounts have `decimals` decimal places
+    pub const fn new(code: &'static str, decimals: u32) -> Self {
+        Self { code, decimals }
+    }
+
+    /// Look up a built-in currency by its ISO 4217 code, ignoring case
+    pub fn from_code(code: &str) -> Option<Currency> {
+        Self::ALL
+            .iter()
+            .find(|currency| currency.code.eq_ignore_ascii_case(code.trim()))
+            .copied()
+    }
+
+    /// The ISO 4217 code, e.g. `USD`
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Decimal places of the minor unit, e.g. 2 for cents
+    pub fn decimals(&self) -> u32 {
+        self.decimals
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
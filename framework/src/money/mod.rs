@@ -0,0 +1,477 @@
+//! Money for Kit framework
+//!
+//! Amounts are decimals, never floats, and always carry their currency.
+//! Results with more decimals than the currency allows are rounded with an
+//! explicit `Rounding` policy.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::money::{Currency, Money, Rounding};
+//!
+//! let price = Money::parse("19.99", Currency::USD)?;
+//! let shipping = Money::from_minor(499, Currency::USD);
+//!
+//! let subtotal = price * 3 + shipping; // 64.96 USD
+//! let tax = subtotal.multiply("0.0825".parse()?, Rounding::HalfUp)?; // 5.36 USD
+//!
+//! // Split without losing a cent: [21.66, 21.65, 21.65]
+//! let shares = subtotal.split(3)?;
+//! ```
+//!
+//! # Serialization
+//!
+//! `Money` serializes as `{ "amount": "64.96", "currency": "USD" }`. The
+//! amount is a string so JavaScript never sees it as a float; `kit
+//! generate-types` emits the matching `Money` interface.
+//!
+//! # Database
+//!
+//! A `Money` field in a SeaORM entity is stored in a JSON column with the
+//! same shape. To store minor units in an integer column instead, convert
+//! with `minor_units()` and `Money::from_minor`.
+
+mod currency;
+mod rounding;
+pub mod rules;
+
+pub use currency::Currency;
+pub use rounding::Rounding;
+pub use rust_decimal::Decimal;
+
+use crate::error::FrameworkError;
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use thiserror::Error;
+
+/// Errors from creating or combining `Money`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MoneyError {
+    /// Two amounts in different currencies were combined
+    #[error("Cannot combine {left} with {right}")]
+    CurrencyMismatch { left: Currency, right: Currency },
+
+    /// The amount isn't a decimal number
+    #[error("Invalid amount: {0:?}")]
+    InvalidAmount(String),
+
+    /// The amount has more decimals than the currency allows
+    #[error("{amount} has more decimals than {currency} allows")]
+    TooPrecise { amount: Decimal, currency: Currency },
+
+    /// The currency code isn't known
+    #[error("Unknown currency: {0:?}")]
+    UnknownCurrency(String),
+
+    /// Division by zero
+    #[error("Cannot divide money by zero")]
+    DivisionByZero,
+
+    /// Allocation ratios were empty or all zero
+    #[error("Allocation ratios must contain a non-zero ratio")]
+    InvalidRatios,
+
+    /// The result doesn't fit in a decimal
+    #[error("Money amount overflowed")]
+    Overflow,
+}
+
+impl From<MoneyError> for FrameworkError {
+    fn from(e: MoneyError) -> Self {
+        FrameworkError::internal(e.to_string())
+    }
+}
+
+/// An amount in a currency
+///
+/// The amount always has exactly the currency's decimals, so `12.3 USD`
+/// is stored and shown as `12.30 USD`.
+///
+/// Adding or subtracting amounts in different currencies with `+`/`-`
+/// panics; use `checked_add`/`checked_sub` for values you don't control.
+/// Comparing them with `<`/`>` is always false.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromJsonQueryResult)]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl Money {
+    /// `amount` in `currency`
+    ///
+    /// Fails if `amount` has more decimals than the currency allows; use
+    /// `Money::rounded` to round it instead.
+    pub fn new(amount: Decimal, currency: Currency) -> Result<Self, MoneyError> {
+        if amount.normalize().scale() > currency.decimals() {
+            return Err(MoneyError::TooPrecise { amount, currency });
+        }
+        Ok(Self::rounded(amount, currency, Rounding::default()))
+    }
+
+    /// `amount` in `currency`, rounded to the currency's decimals
+    pub fn rounded(amount: Decimal, currency: Currency, rounding: Rounding) -> Self {
+        let mut amount = amount.round_dp_with_strategy(currency.decimals(), rounding.strategy());
+        amount.rescale(currency.decimals());
+        Self { amount, currency }
+    }
+
+    /// Parse a decimal string such as `"19.99"`
+    pub fn parse(amount: &str, currency: Currency) -> Result<Self, MoneyError> {
+        let decimal = Decimal::from_str_exact(amount.trim())
+            .map_err(|_| MoneyError::InvalidAmount(amount.to_string()))?;
+        Self::new(decimal, currency)
+    }
+
+    /// An amount given in the currency's minor unit, e.g. cents
+    pub fn from_minor(units: i64, currency: Currency) -> Self {
+        Self {
+            amount: Decimal::new(units, currency.decimals()),
+            currency,
+        }
+    }
+
+    /// Zero in `currency`
+    pub fn zero(currency: Currency) -> Self {
+        Self::from_minor(0, currency)
+    }
+
+    /// The amount as a decimal
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    /// The currency
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// The amount in the currency's minor unit, e.g. 1999 for 19.99 USD
+    pub fn minor_units(&self) -> i128 {
+        self.amount.mantissa()
+    }
+
+    /// Whether the amount is zero
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    /// Whether the amount is greater than zero
+    pub fn is_positive(&self) -> bool {
+        self.amount.is_sign_positive() && !self.amount.is_zero()
+    }
+
+    /// Whether the amount is less than zero
+    pub fn is_negative(&self) -> bool {
+        self.amount.is_sign_negative() && !self.amount.is_zero()
+    }
+
+    /// The amount without its sign
+    pub fn abs(&self) -> Self {
+        Self {
+            amount: self.amount.abs(),
+            currency: self.currency,
+        }
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch {
+                left: self.currency,
+                right: other.currency,
+            });
+        }
+        Ok(())
+    }
+
+    /// Add `other`, failing for another currency or on overflow
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let amount = self
+            .amount
+            .checked_add(other.amount)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Self::rounded(amount, self.currency, Rounding::default()))
+    }
+
+    /// Subtract `other`, failing for another currency or on overflow
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let amount = self
+            .amount
+            .checked_sub(other.amount)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Self::rounded(amount, self.currency, Rounding::default()))
+    }
+
+    /// Multiply by a decimal factor such as a tax rate, rounding the result
+    pub fn multiply(&self, factor: Decimal, rounding: Rounding) -> Result<Money, MoneyError> {
+        let amount = self
+            .amount
+            .checked_mul(factor)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Self::rounded(amount, self.currency, rounding))
+    }
+
+    /// Divide by a decimal, rounding the result
+    ///
+    /// To share an amount out without losing minor units, use `split` or
+    /// `allocate`.
+    pub fn divide(&self, divisor: Decimal, rounding: Rounding) -> Result<Money, MoneyError> {
+        if divisor.is_zero() {
+            return Err(MoneyError::DivisionByZero);
+        }
+        let amount = self
+            .amount
+            .checked_div(divisor)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Self::rounded(amount, self.currency, rounding))
+    }
+
+    /// Share the amount out by `ratios`
+    ///
+    /// The parts always add up to the original amount: minor units left
+    /// over after rounding go to the first parts, one each.
+    ///
+    /// ```rust,ignore
+    /// // 10.00 USD by 1:1:1 is [3.34, 3.33, 3.33]
+    /// let parts = total.allocate(&[1, 1, 1])?;
+    /// ```
+    pub fn allocate(&self, ratios: &[u32]) -> Result<Vec<Money>, MoneyError> {
+        let sum: i128 = ratios.iter().map(|ratio| *ratio as i128).sum();
+        if sum == 0 {
+            return Err(MoneyError::InvalidRatios);
+        }
+
+        let total = self.minor_units();
+        let mut parts: Vec<i128> = ratios
+            .iter()
+            .map(|ratio| total * *ratio as i128 / sum)
+            .collect();
+        let mut remainder = total - parts.iter().sum::<i128>();
+        let step = remainder.signum();
+        for (part, ratio) in parts.iter_mut().zip(ratios) {
+            if remainder == 0 {
+                break;
+            }
+            if *ratio > 0 {
+                *part += step;
+                remainder -= step;
+            }
+        }
+
+        parts
+            .into_iter()
+            .map(|units| {
+                Decimal::try_from_i128_with_scale(units, self.currency.decimals())
+                    .map(|amount| Money {
+                        amount,
+                        currency: self.currency,
+                    })
+                    .map_err(|_| MoneyError::Overflow)
+            })
+            .collect()
+    }
+
+    /// Share the amount out into `parts` equal parts, see `allocate`
+    pub fn split(&self, parts: u32) -> Result<Vec<Money>, MoneyError> {
+        self.allocate(&vec![1; parts as usize])
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        self.checked_add(&other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        self.checked_sub(&other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+
+    fn mul(self, quantity: i64) -> Money {
+        self.multiply(Decimal::from(quantity), Rounding::default())
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        if self.is_zero() {
+            return self;
+        }
+        Money {
+            amount: -self.amount,
+            currency: self.currency,
+        }
+    }
+}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Money) -> Option<Ordering> {
+        if self.currency != other.currency {
+            return None;
+        }
+        self.amount.partial_cmp(&other.amount)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// Wire format shared by serde and the database column
+#[derive(Serialize, Deserialize)]
+struct MoneyJson<'a> {
+    amount: String,
+    #[serde(borrow)]
+    currency: std::borrow::Cow<'a, str>,
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MoneyJson {
+            amount: self.amount.to_string(),
+            currency: self.currency.code().into(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = MoneyJson::deserialize(deserializer)?;
+        let currency = Currency::from_code(&json.currency)
+            .ok_or_else(|| MoneyError::UnknownCurrency(json.currency.to_string()))
+            .map_err(serde::de::Error::custom)?;
+        Money::parse(&json.amount, currency).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(amount: &str) -> Money {
+        Money::parse(amount, Currency::USD).unwrap()
+    }
+
+    #[test]
+    fn test_construction_and_arithmetic() {
+        assert_eq!(usd("12.3").to_string(), "12.30 USD");
+        assert_eq!(Money::from_minor(1999, Currency::USD), usd("19.99"));
+        assert_eq!(usd("19.99").minor_units(), 1999);
+        assert!(matches!(
+            Money::parse("1.999", Currency::USD),
+            Err(MoneyError::TooPrecise { .. })
+        ));
+        assert!(Money::parse("12,00", Currency::USD).is_err());
+        assert!(Money::parse("1.5", Currency::JPY).is_err());
+
+        assert_eq!(usd("19.99") * 3 + usd("4.99"), usd("64.96"));
+        assert_eq!(-(usd("1") - usd("3")), usd("2"));
+        assert_eq!((-usd("0")).to_string(), "0.00 USD");
+        assert!(usd("1") < usd("2"));
+
+        let eur = Money::parse("1", Currency::EUR).unwrap();
+        assert!(usd("1").checked_add(&eur).is_err());
+        assert_eq!(usd("1").partial_cmp(&eur), None);
+    }
+
+    #[test]
+    fn test_rounding_policies() {
+        let price = usd("10.05");
+        let half = Decimal::new(5, 1);
+        assert_eq!(
+            price.multiply(half, Rounding::HalfEven).unwrap(),
+            usd("5.02")
+        );
+        assert_eq!(price.multiply(half, Rounding::HalfUp).unwrap(), usd("5.03"));
+        assert_eq!(price.multiply(half, Rounding::Down).unwrap(), usd("5.02"));
+        assert_eq!(
+            (-price).multiply(half, Rounding::Floor).unwrap(),
+            usd("-5.03")
+        );
+        assert_eq!(
+            usd("10").divide(Decimal::from(3), Rounding::Up).unwrap(),
+            usd("3.34")
+        );
+        assert_eq!(
+            usd("1").divide(Decimal::ZERO, Rounding::HalfUp),
+            Err(MoneyError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn test_allocate_keeps_every_minor_unit() {
+        assert_eq!(
+            usd("10").split(3).unwrap(),
+            vec![usd("3.34"), usd("3.33"), usd("3.33")]
+        );
+        assert_eq!(
+            usd("-0.05").allocate(&[3, 0, 7]).unwrap(),
+            vec![usd("-0.02"), usd("0"), usd("-0.03")]
+        );
+        assert_eq!(usd("1").allocate(&[0, 0]), Err(MoneyError::InvalidRatios));
+    }
+
+    #[test]
+    fn test_serializes_amount_as_string() {
+        let json = serde_json::to_value(usd("64.5")).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "amount": "64.50", "currency": "USD" })
+        );
+        assert_eq!(serde_json::from_value::<Money>(json).unwrap(), usd("64.50"));
+
+        let err = serde_json::from_value::<Money>(
+            serde_json::json!({ "amount": "1", "currency": "XXX" }),
+        );
+        assert!(err.is_err());
+
+        let value: sea_orm::Value = usd("1").into();
+        assert!(matches!(value, sea_orm::Value::Json(Some(_))));
+    }
+
+    #[test]
+    fn test_validation_rules() {
+        use validator::Validate;
+
+        #[derive(Validate)]
+        struct Product {
+            #[validate(custom(function = "rules::positive"))]
+            price: Money,
+            #[validate(custom(function = "rules::non_negative"))]
+            discount: Option<Money>,
+        }
+
+        let product = Product {
+            price: usd("0"),
+            discount: Some(usd("-1")),
+        };
+        let errors = product.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("price"));
+        assert!(errors.field_errors().contains_key("discount"));
+
+        let product = Product {
+            price: usd("1"),
+            discount: None,
+        };
+        assert!(product.validate().is_ok());
+    }
+}
@@ -0,0 +1,47 @@
+//! Rounding policies for money arithmetic
+
+use rust_decimal::RoundingStrategy;
+
+/// How to round results that have more decimals than the currency allows
+///
+/// | Policy     | 1.005 → | -1.005 → | 1.015 → |
+/// |------------|---------|----------|---------|
+/// | `HalfEven` | 1.00    | -1.00    | 1.02    |
+/// | `HalfUp`   | 1.01    | -1.01    | 1.02    |
+/// | `HalfDown` | 1.00    | -1.00    | 1.01    |
+/// | `Up`       | 1.01    | -1.01    | 1.02    |
+/// | `Down`     | 1.00    | -1.00    | 1.01    |
+/// | `Ceiling`  | 1.01    | -1.00    | 1.02    |
+/// | `Floor`    | 1.00    | -1.01    | 1.01    |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Halves go to the even neighbour (banker's rounding)
+    #[default]
+    HalfEven,
+    /// Halves go away from zero
+    HalfUp,
+    /// Halves go towards zero
+    HalfDown,
+    /// Always away from zero
+    Up,
+    /// Always towards zero (truncate)
+    Down,
+    /// Always towards positive infinity
+    Ceiling,
+    /// Always towards negative infinity
+    Floor,
+}
+
+impl Rounding {
+    pub(crate) fn strategy(self) -> RoundingStrategy {
+        match self {
+            Self::HalfEven => RoundingStrategy::MidpointNearestEven,
+            Self::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Self::HalfDown => RoundingStrategy::MidpointTowardZero,
+            Self::Up => RoundingStrategy::AwayFromZero,
+            Self::Down => RoundingStrategy::ToZero,
+            Self::Ceiling => RoundingStrategy::ToPositiveInfinity,
+            Self::Floor => RoundingStrategy::ToNegativeInfinity,
+        }
+    }
+}
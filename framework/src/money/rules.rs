@@ -0,0 +1,63 @@
+//! Validation rules for `Money` fields
+//!
+//! For use with `#[validate(custom(function = ...))]` in `#[request]`
+//! structs. `Option<Money>` fields are only checked when present.
+//!
+//! ```rust,ignore
+//! use kit::money::Money;
+//!
+//! #[request]
+//! pub struct CreateProductRequest {
+//!     pub name: String,
+//!
+//!     #[validate(custom(function = "kit::money::rules::positive"))]
+//!     pub price: Money,
+//!
+//!     #[validate(custom(function = "kit::money::rules::non_negative"))]
+//!     pub discount: Option<Money>,
+//! }
+//! ```
+
+use std::borrow::Cow;
+use validator::ValidationError;
+
+use super::Money;
+
+fn error(code: &'static str, message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(Cow::Borrowed(message));
+    error
+}
+
+/// The amount must be greater than zero
+pub fn positive(money: &Money) -> Result<(), ValidationError> {
+    if money.is_positive() {
+        Ok(())
+    } else {
+        Err(error(
+            "money_positive",
+            "The amount must be greater than zero.",
+        ))
+    }
+}
+
+/// The amount must be zero or more
+pub fn non_negative(money: &Money) -> Result<(), ValidationError> {
+    if money.is_negative() {
+        Err(error(
+            "money_non_negative",
+            "The amount may not be negative.",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// The amount must not be zero
+pub fn non_zero(money: &Money) -> Result<(), ValidationError> {
+    if money.is_zero() {
+        Err(error("money_non_zero", "The amount may not be zero."))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+//! Per-request profiling and `Server-Timing` headers
+//!
+//! In development every response gets a `Server-Timing` header, which
+//! browser devtools show in the network panel. In other environments a
+//! fraction of requests can be sampled with `SERVER_TIMING_SAMPLE_RATE`,
+//! e.g. `0.01` for one in a hundred.
+//!
+//! With `APP_DEBUG` on in development, adding `_profile=1` to a request's
+//! query string also reports the timings and allocations as JSON:
+//!
+//! ```text
+//! Server-Timing: middleware;dur=0.42, extract;dur=1.10, handler;dur=12.73, db;dur=8.05, cache;dur=0.31, blocking;dur=0.00, serialize;dur=0.88, total;dur=15.13
+//! X-Kit-Profile: {"total_ms":15.13,"middleware_ms":0.42,"extraction_ms":1.1,"handler_ms":12.73,"database_ms":8.05,"cache_ms":0.31,"blocking_ms":0.0,"serialization_ms":0.88,"allocations":1893,"allocated_bytes":210544}
+//! ```
+//!
+//! Extraction is the time `#[handler]` spends extracting parameters (model
+//! binding, form validation), serialization the time converting a handler's
+//! return value into a response, and middleware everything else. Database
+//! and cache time, and time waiting on `kit::blocking`, is spent during the
+//! other phases, so it overlaps them.
+//! Allocation counts need `bench::CountingAllocator` installed and include
+//! allocations made by other requests running at the same time.
+//!
+//! The flag is ignored outside development so it can't be used to probe a
+//! production server.
+
+use crate::bench::AllocSnapshot;
+use crate::config::{Config, ServerConfig};
+use crate::http::{HttpResponse, Response};
+use rand::Rng;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    static PROFILE: Arc<RequestProfile>;
+}
+
+/// A timed part of handling a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Extracting `#[handler]` parameters
+    Extraction,
+    /// Running the handler body
+    Handler,
+    /// Converting the handler's value into a response
+    Serialization,
+    /// Running database queries
+    Database,
+    /// Cache reads and writes through `Cache`
+    Cache,
+    /// Work run on the blocking pool with `kit::blocking`
+    Blocking,
+}
+
+/// How much a profiled request reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Detail {
+    /// `Server-Timing` only
+    Timing,
+    /// `Server-Timing` and the `X-Kit-Profile` report, for `?_profile=1`
+    Full,
+}
+
+/// Time spent in each phase of the current request, in nanoseconds
+#[derive(Debug, Default)]
+pub struct RequestProfile {
+    extraction: AtomicU64,
+    handler: AtomicU64,
+    serialization: AtomicU64,
+    database: AtomicU64,
+    cache: AtomicU64,
+    blocking: AtomicU64,
+}
+
+impl RequestProfile {
+    fn phase(&self, phase: Phase) -> &AtomicU64 {
+        match phase {
+            Phase::Extraction => &self.extraction,
+            Phase::Handler => &self.handler,
+            Phase::Serialization => &self.serialization,
+            Phase::Database => &self.database,
+            Phase::Cache => &self.cache,
+            Phase::Blocking => &self.blocking,
+        }
+    }
+
+    /// Time recorded for `phase`
+    pub fn duration(&self, phase: Phase) -> Duration {
+        Duration::from_nanos(self.phase(phase).load(Ordering::Relaxed))
+    }
+}
+
+/// Start timing a phase; `None` unless the current request is profiled
+pub fn start() -> Option<Instant> {
+    PROFILE.try_with(|_| Instant::now()).ok()
+}
+
+/// Record the time since `started` against `phase`
+pub fn record(phase: Phase, started: Option<Instant>) {
+    if let Some(started) = started {
+        record_duration(phase, started.elapsed());
+    }
+}
+
+/// Record `elapsed` against `phase`, for times measured elsewhere
+pub fn record_duration(phase: Phase, elapsed: Duration) {
+    let elapsed = elapsed.as_nanos() as u64;
+    let _ = PROFILE.try_with(|profile| profile.phase(phase).fetch_add(elapsed, Ordering::Relaxed));
+}
+
+/// Run `future`, recording its time against `phase`
+pub async fn timed<F: Future>(phase: Phase, future: F) -> F::Output {
+    let started = start();
+    let output = future.await;
+    record(phase, started);
+    output
+}
+
+/// How much to profile the request, if at all
+pub(crate) fn requested(query: &str) -> Option<Detail> {
+    if Config::is_development() {
+        let full = has_profile_flag(query) && Config::is_debug();
+        return Some(if full { Detail::Full } else { Detail::Timing });
+    }
+    let rate = Config::get::<ServerConfig>()
+        .unwrap_or_default()
+        .timing_sample_rate;
+    (rate > 0.0 && rand::thread_rng().gen::<f64>() < rate).then_some(Detail::Timing)
+}
+
+fn has_profile_flag(query: &str) -> bool {
+    serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+        .unwrap_or_default()
+        .iter()
+        .any(|(key, value)| key == "_profile" && matches!(value.as_str(), "1" | "true"))
+}
+
+/// Run `future` with profiling and attach the report to its response
+pub(crate) async fn profiled<F>(detail: Detail, future: F) -> Response
+where
+    F: Future<Output = Response>,
+{
+    let profile = Arc::new(RequestProfile::default());
+    let allocs_before = AllocSnapshot::now();
+    let started = Instant::now();
+
+    let response = PROFILE.scope(profile.clone(), future).await;
+
+    let total = started.elapsed();
+    let allocs = AllocSnapshot::now()
+        .zip(allocs_before)
+        .map(|(after, before)| after.since(&before));
+    let report = |response: HttpResponse| attach(response, detail, &profile, total, allocs);
+    response.map(report).map_err(report)
+}
+
+fn attach(
+    response: HttpResponse,
+    detail: Detail,
+    profile: &RequestProfile,
+    total: Duration,
+    allocs: Option<AllocSnapshot>,
+) -> HttpResponse {
+    let extraction = profile.duration(Phase::Extraction);
+    let handler = profile.duration(Phase::Handler);
+    let serialization = profile.duration(Phase::Serialization);
+    let database = profile.duration(Phase::Database);
+    let cache = profile.duration(Phase::Cache);
+    let blocking = profile.duration(Phase::Blocking);
+    let middleware = total.saturating_sub(extraction + handler + serialization);
+
+    let timing = format!(
+        "middleware;dur={:.2}, extract;dur={:.2}, handler;dur={:.2}, db;dur={:.2}, cache;dur={:.2}, blocking;dur={:.2}, serialize;dur={:.2}, total;dur={:.2}",
+        ms(middleware),
+        ms(extraction),
+        ms(handler),
+        ms(database),
+        ms(cache),
+        ms(blocking),
+        ms(serialization),
+        ms(total),
+    );
+    let report = serde_json::json!({
+        "total_ms": round(ms(total)),
+        "middleware_ms": round(ms(middleware)),
+        "extraction_ms": round(ms(extraction)),
+        "handler_ms": round(ms(handler)),
+        "database_ms": round(ms(database)),
+        "cache_ms": round(ms(cache)),
+        "blocking_ms": round(ms(blocking)),
+        "serialization_ms": round(ms(serialization)),
+        "allocations": allocs.map(|allocs| allocs.allocations),
+        "allocated_bytes": allocs.map(|allocs| allocs.bytes),
+    });
+
+    let response = response.header("Server-Timing", timing);
+    match detail {
+        Detail::Timing => response,
+        Detail::Full => response.header("X-Kit-Profile", report.to_string()),
+    }
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn round(ms: f64) -> f64 {
+    (ms * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FrameworkError;
+    use crate::http::__handler_response;
+
+    fn header<'a>(response: &'a HttpResponse, name: &str) -> &'a str {
+        response
+            .headers()
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_the_profile_flag() {
+        assert!(has_profile_flag("_profile=1"));
+        assert!(has_profile_flag("page=2&_profile=true"));
+        assert!(!has_profile_flag("_profile=0"));
+        assert!(!has_profile_flag("profile=1"));
+        assert!(!has_profile_flag(""));
+    }
+
+    #[tokio::test]
+    async fn reports_phase_timings_on_the_response() {
+        let response = profiled(Detail::Full, async {
+            record(Phase::Extraction, start());
+            record_duration(Phase::Database, Duration::from_millis(5));
+            __handler_response(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok::<_, FrameworkError>(vec![1, 2, 3])
+            })
+            .await
+        })
+        .await;
+        let Ok(response) = response else {
+            panic!("expected a response");
+        };
+
+        let timing = header(&response, "Server-Timing");
+        for phase in [
+            "middleware",
+            "extract",
+            "handler",
+            "db",
+            "cache",
+            "blocking",
+            "serialize",
+            "total",
+        ] {
+            assert!(timing.contains(&format!("{};dur=", phase)), "{}", timing);
+        }
+        let report: serde_json::Value =
+            serde_json::from_str(header(&response, "X-Kit-Profile")).unwrap();
+        assert!(report["handler_ms"].as_f64().unwrap() >= 20.0);
+        assert_eq!(report["database_ms"], 5.0);
+        assert!(report["total_ms"].as_f64().unwrap() >= report["handler_ms"].as_f64().unwrap());
+    }
+
+    #[tokio::test]
+    async fn sampled_requests_only_get_server_timing() {
+        let response = profiled(Detail::Timing, async {
+            timed(Phase::Cache, tokio::time::sleep(Duration::from_millis(2))).await;
+            Ok(HttpResponse::text("ok"))
+        })
+        .await;
+        let Ok(response) = response else {
+            panic!("expected a response");
+        };
+
+        let timing = header(&response, "Server-Timing");
+        let cache = timing
+            .split(", ")
+            .find_map(|segment| segment.strip_prefix("cache;dur="))
+            .unwrap();
+        assert!(cache.parse::<f64>().unwrap() >= 2.0, "{}", timing);
+        assert!(!response
+            .headers()
+            .iter()
+            .any(|(name, _)| name == "X-Kit-Profile"));
+    }
+
+    #[test]
+    fn phases_are_not_timed_outside_a_profiled_request() {
+        assert!(start().is_none());
+    }
+}
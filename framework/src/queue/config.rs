@@ -0,0 +1,62 @@
+//! Queue configuration
+
+use crate::config::{env, env_optional};
+
+/// Queue configuration
+///
+/// # Environment Variables
+///
+/// - `QUEUE_CONNECTION` - Backend: "database", "redis" or "sync" (default: "database")
+/// - `QUEUE_NAME` - Queue jobs are pushed to and worked by default (default: "default")
+/// - `QUEUE_POLL_INTERVAL_MS` - Worker poll interval in milliseconds (default: 1000)
+/// - `QUEUE_CONCURRENCY` - Number of jobs a worker runs at once (default: 4)
+/// - `QUEUE_RETRY_AFTER_SECS` - Seconds before a job reserved by a crashed worker is retried (default: 90)
+/// - `QUEUE_MAX_ATTEMPTS` - Attempts per job unless the job sets `tries` (default: 3)
+/// - `QUEUE_RETRY_BACKOFF_SECS` - Linear backoff seconds between attempts (default: 5)
+/// - `REDIS_URL` - Redis connection URL for the redis backend (default: redis://127.0.0.1:6379)
+/// - `QUEUE_REDIS_PREFIX` - Key prefix for the redis backend (default: "kit_queue:")
+#[derive(Debug, Clone)]
+pub struct QueueConfig {
+    /// Backend name
+    pub connection: String,
+    /// Default queue name
+    pub default_queue: String,
+    /// Worker poll interval in milliseconds
+    pub poll_interval_ms: u64,
+    /// Max concurrent jobs run by a worker
+    pub concurrency: usize,
+    /// Seconds a reserved job stays hidden from other workers
+    pub retry_after_secs: u64,
+    /// Default max attempts per job
+    pub max_attempts: u32,
+    /// Linear backoff seconds per attempt
+    pub retry_backoff_secs: u64,
+    /// Redis connection URL
+    pub redis_url: String,
+    /// Redis key prefix
+    pub redis_prefix: String,
+}
+
+impl QueueConfig {
+    /// Build config from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            connection: env("QUEUE_CONNECTION", "database".to_string()),
+            default_queue: env("QUEUE_NAME", "default".to_string()),
+            poll_interval_ms: env("QUEUE_POLL_INTERVAL_MS", 1000u64),
+            concurrency: env("QUEUE_CONCURRENCY", 4usize),
+            retry_after_secs: env("QUEUE_RETRY_AFTER_SECS", 90u64),
+            max_attempts: env("QUEUE_MAX_ATTEMPTS", 3u32),
+            retry_backoff_secs: env("QUEUE_RETRY_BACKOFF_SECS", 5u64),
+            redis_url: env_optional("REDIS_URL")
+                .unwrap_or_else(|| "redis://127.0.0.1:6379".to_string()),
+            redis_prefix: env("QUEUE_REDIS_PREFIX", "kit_queue:".to_string()),
+        }
+    }
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
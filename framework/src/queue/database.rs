@@ -0,0 +1,194 @@
+//! Database-backed queue
+//!
+//! Stores jobs in a `jobs` table, created by the migration from
+//! `kit queue:install`. Works with Postgres and SQLite.
+
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use sea_orm::sea_query::{Condition, Expr};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use std::time::Duration;
+
+use super::driver::QueueDriver;
+use super::job::QueuedJob;
+use crate::database::DB;
+use crate::error::FrameworkError;
+
+/// Times `pop` retries when another worker reserves its job first
+const RESERVE_RETRIES: usize = 5;
+
+/// SeaORM entity for the `jobs` table
+pub mod jobs {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "jobs")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        pub queue: String,
+        pub name: String,
+        #[sea_orm(column_type = "Text")]
+        pub payload: String,
+        pub attempts: i32,
+        pub max_attempts: i32,
+        pub available_at: chrono::NaiveDateTime,
+        pub reserved_until: Option<chrono::NaiveDateTime>,
+        pub failed_at: Option<chrono::NaiveDateTime>,
+        #[sea_orm(column_type = "Text", nullable)]
+        pub error: Option<String>,
+        pub created_at: chrono::NaiveDateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Queue backend storing jobs in the application database
+///
+/// Failed jobs stay in the table with `failed_at` and `error` set.
+#[derive(Debug, Default)]
+pub struct DatabaseQueue;
+
+impl DatabaseQueue {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+fn after(delay: Duration) -> NaiveDateTime {
+    now() + ChronoDuration::milliseconds(delay.as_millis() as i64)
+}
+
+fn db_error(e: sea_orm::DbErr) -> FrameworkError {
+    FrameworkError::database(e.to_string())
+}
+
+fn job_id(job: &QueuedJob) -> Result<i64, FrameworkError> {
+    job.id
+        .parse()
+        .map_err(|_| FrameworkError::internal(format!("Invalid job id: {}", job.id)))
+}
+
+#[async_trait]
+impl QueueDriver for DatabaseQueue {
+    async fn push(&self, job: QueuedJob, delay: Duration) -> Result<String, FrameworkError> {
+        let db = DB::connection()?;
+        let model = jobs::ActiveModel {
+            queue: Set(job.queue),
+            name: Set(job.name),
+            payload: Set(job.payload),
+            attempts: Set(0),
+            max_attempts: Set(job.max_attempts as i32),
+            available_at: Set(after(delay)),
+            reserved_until: Set(None),
+            failed_at: Set(None),
+            error: Set(None),
+            created_at: Set(now()),
+            ..Default::default()
+        };
+        let inserted = model.insert(db.inner()).await.map_err(db_error)?;
+        Ok(inserted.id.to_string())
+    }
+
+    async fn pop(
+        &self,
+        queue: &str,
+        retry_after: Duration,
+    ) -> Result<Option<QueuedJob>, FrameworkError> {
+        let db = DB::connection()?;
+
+        for _ in 0..RESERVE_RETRIES {
+            let now = now();
+            let unreserved = Condition::any()
+                .add(jobs::Column::ReservedUntil.is_null())
+                .add(jobs::Column::ReservedUntil.lte(now));
+
+            let Some(candidate) = jobs::Entity::find()
+                .filter(jobs::Column::Queue.eq(queue))
+                .filter(jobs::Column::FailedAt.is_null())
+                .filter(jobs::Column::AvailableAt.lte(now))
+                .filter(unreserved.clone())
+                .order_by_asc(jobs::Column::Id)
+                .one(db.inner())
+                .await
+                .map_err(db_error)?
+            else {
+                return Ok(None);
+            };
+
+            // Only one worker can win the update; the others try the next job
+            let reserved = jobs::Entity::update_many()
+                .col_expr(
+                    jobs::Column::Attempts,
+                    Expr::col(jobs::Column::Attempts).add(1),
+                )
+                .col_expr(jobs::Column::ReservedUntil, Expr::value(after(retry_after)))
+                .filter(jobs::Column::Id.eq(candidate.id))
+                .filter(jobs::Column::Attempts.eq(candidate.attempts))
+                .filter(unreserved)
+                .exec(db.inner())
+                .await
+                .map_err(db_error)?;
+
+            if reserved.rows_affected == 1 {
+                return Ok(Some(QueuedJob {
+                    id: candidate.id.to_string(),
+                    queue: candidate.queue,
+                    name: candidate.name,
+                    payload: candidate.payload,
+                    attempts: candidate.attempts as u32 + 1,
+                    max_attempts: candidate.max_attempts as u32,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn delete(&self, job: &QueuedJob) -> Result<(), FrameworkError> {
+        let db = DB::connection()?;
+        jobs::Entity::delete_by_id(job_id(job)?)
+            .exec(db.inner())
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn release(&self, job: &QueuedJob, delay: Duration) -> Result<(), FrameworkError> {
+        let db = DB::connection()?;
+        jobs::Entity::update_many()
+            .col_expr(jobs::Column::AvailableAt, Expr::value(after(delay)))
+            .col_expr(
+                jobs::Column::ReservedUntil,
+                Expr::value(Option::<NaiveDateTime>::None),
+            )
+            .filter(jobs::Column::Id.eq(job_id(job)?))
+            .exec(db.inner())
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn fail(&self, job: &QueuedJob, error: &str) -> Result<(), FrameworkError> {
+        let db = DB::connection()?;
+        jobs::Entity::update_many()
+            .col_expr(jobs::Column::FailedAt, Expr::value(now()))
+            .col_expr(jobs::Column::Error, Expr::value(error))
+            .col_expr(
+                jobs::Column::ReservedUntil,
+                Expr::value(Option::<NaiveDateTime>::None),
+            )
+            .filter(jobs::Column::Id.eq(job_id(job)?))
+            .exec(db.inner())
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+//! QueueDriver trait definition
+//!
+//! Defines the contract for queue backends (database, redis, sync, ...)
+
+use async_trait::async_trait;
+use std::time::Duration;
+
+use super::job::QueuedJob;
+use crate::error::FrameworkError;
+
+/// QueueDriver trait - all queue backends must implement this
+#[async_trait]
+pub trait QueueDriver: Send + Sync {
+    /// Push a job that becomes available after `delay`
+    ///
+    /// `job.id` and `job.attempts` are ignored. Returns the new job's ID.
+    async fn push(&self, job: QueuedJob, delay: Duration) -> Result<String, FrameworkError>;
+
+    /// Reserve the next available job on `queue`
+    ///
+    /// The job's attempts are incremented. If it isn't deleted, released or
+    /// failed within `retry_after`, another worker may reserve it again.
+    async fn pop(
+        &self,
+        queue: &str,
+        retry_after: Duration,
+    ) -> Result<Option<QueuedJob>, FrameworkError>;
+
+    /// Remove a finished job
+    async fn delete(&self, job: &QueuedJob) -> Result<(), FrameworkError>;
+
+    /// Put a reserved job back, available again after `delay`
+    async fn release(&self, job: &QueuedJob, delay: Duration) -> Result<(), FrameworkError>;
+
+    /// Record a job whose attempts are used up
+    async fn fail(&self, job: &QueuedJob, error: &str) -> Result<(), FrameworkError>;
+}
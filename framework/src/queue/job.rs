@@ -0,0 +1,80 @@
+//! Job trait and queued job records
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::FrameworkError;
+
+/// A background job
+///
+/// Register the struct with `#[job]`, which also derives `Serialize` and
+/// `Deserialize` so the fields can be stored with the queued job.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{async_trait, job, FrameworkError};
+/// use kit::queue::Job;
+///
+/// #[job]
+/// pub struct SendWelcomeEmail {
+///     pub user_id: i64,
+/// }
+///
+/// #[async_trait]
+/// impl Job for SendWelcomeEmail {
+///     async fn handle(&self) -> Result<(), FrameworkError> {
+///         mailer::welcome(self.user_id).await
+///     }
+///
+///     fn queue(&self) -> Option<&str> {
+///         Some("emails")
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Job: JobName + Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Run the job
+    ///
+    /// Returning an error releases the job for another attempt until its
+    /// tries are used up.
+    async fn handle(&self) -> Result<(), FrameworkError>;
+
+    /// Queue to push the job to (default: `QUEUE_NAME`)
+    fn queue(&self) -> Option<&str> {
+        None
+    }
+
+    /// Times to attempt the job (default: `QUEUE_MAX_ATTEMPTS`)
+    fn tries(&self) -> Option<u32> {
+        None
+    }
+
+    /// Called once the last attempt has failed
+    async fn failed(&self, _error: &FrameworkError) {}
+}
+
+/// Name jobs are stored under, implemented by `#[job]`
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(message = "`{Self}` is not registered as a job, add `#[job]` to it")]
+pub trait JobName {
+    const NAME: &'static str;
+}
+
+/// A job as stored by a queue backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedJob {
+    /// ID assigned by the backend
+    pub id: String,
+    /// Queue the job is on
+    pub queue: String,
+    /// Registered job name, `module::path::Struct`
+    pub name: String,
+    /// The job's fields as JSON
+    pub payload: String,
+    /// Attempts so far, including the current one
+    pub attempts: u32,
+    /// Attempts allowed before the job fails
+    pub max_attempts: u32,
+}
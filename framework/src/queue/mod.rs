@@ -0,0 +1,396 @@
+//! Background job queue
+//!
+//! Fire-and-forget jobs, lighter than workflows: a job is a struct whose
+//! fields are stored as JSON and run once by a worker, with retries on
+//! failure. Jobs are stored in the database (`jobs` table, created by
+//! `kit queue:install`), in Redis, or run immediately with the `sync`
+//! backend, selected by `QUEUE_CONNECTION`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{async_trait, dispatch, job, FrameworkError};
+//! use kit::queue::Job;
+//! use std::time::Duration;
+//!
+//! #[job]
+//! pub struct SendWelcomeEmail {
+//!     pub user_id: i64,
+//! }
+//!
+//! #[async_trait]
+//! impl Job for SendWelcomeEmail {
+//!     async fn handle(&self) -> Result<(), FrameworkError> {
+//!         println!("Welcome, user {}", self.user_id);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! // Push a job
+//! dispatch!(SendWelcomeEmail { user_id }).await?;
+//!
+//! // Push a job that runs in ten minutes, on the "emails" queue
+//! dispatch!(
+//!     SendWelcomeEmail { user_id },
+//!     delay = Duration::from_secs(600),
+//!     queue = "emails",
+//! )
+//! .await?;
+//!
+//! // Run worker (separate process):
+//! // kit queue:work --queue emails,default
+//! ```
+
+pub mod config;
+pub mod database;
+pub mod driver;
+pub mod job;
+pub mod redis;
+#[doc(hidden)]
+pub mod registry;
+pub mod sync;
+pub mod worker;
+
+pub use config::QueueConfig;
+pub use database::DatabaseQueue;
+pub use driver::QueueDriver;
+#[doc(hidden)]
+pub use job::JobName;
+pub use job::{Job, QueuedJob};
+pub use redis::RedisQueue;
+pub use sync::SyncQueue;
+pub use worker::QueueWorker;
+
+use crate::config::Config;
+use crate::container::App;
+use crate::error::FrameworkError;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Queue facade - main entry point for dispatching jobs
+pub struct Queue;
+
+impl Queue {
+    /// Start dispatching a job, see `dispatch!`
+    pub fn dispatch<J: Job>(job: J) -> PendingDispatch<J> {
+        PendingDispatch {
+            job,
+            delay: Duration::ZERO,
+            queue: None,
+        }
+    }
+
+    /// Get the queue backend
+    ///
+    /// Uses the `dyn QueueDriver` bound in the container if there is one,
+    /// otherwise connects the backend named by `QUEUE_CONNECTION` and binds it.
+    pub async fn driver() -> Result<Arc<dyn QueueDriver>, FrameworkError> {
+        if let Some(driver) = App::make::<dyn QueueDriver>() {
+            return Ok(driver);
+        }
+
+        let config = Config::get::<QueueConfig>().unwrap_or_default();
+        let driver: Arc<dyn QueueDriver> = match config.connection.as_str() {
+            "database" => Arc::new(DatabaseQueue::new()),
+            "redis" => Arc::new(RedisQueue::connect(&config).await?),
+            "sync" => Arc::new(SyncQueue::new()),
+            other => {
+                return Err(FrameworkError::internal(format!(
+                    "Unknown queue connection '{}'",
+                    other
+                )))
+            }
+        };
+        App::bind::<dyn QueueDriver>(driver.clone());
+        Ok(driver)
+    }
+}
+
+/// A job about to be dispatched
+///
+/// Await it (or call `send()`) to push the job; resolves to the job's ID.
+#[must_use = "jobs are only dispatched when awaited"]
+pub struct PendingDispatch<J: Job> {
+    job: J,
+    delay: Duration,
+    queue: Option<String>,
+}
+
+impl<J: Job> PendingDispatch<J> {
+    /// Make the job available only after `delay`
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Push to this queue instead of the job's own
+    pub fn queue(mut self, queue: impl Into<String>) -> Self {
+        self.queue = Some(queue.into());
+        self
+    }
+
+    /// Push the job to the queue backend
+    pub async fn send(self) -> Result<String, FrameworkError> {
+        let config = Config::get::<QueueConfig>().unwrap_or_default();
+        let payload = serde_json::to_string(&self.job)
+            .map_err(|e| FrameworkError::internal(format!("Job serialize error: {}", e)))?;
+        let queue = self
+            .queue
+            .or_else(|| self.job.queue().map(str::to_string))
+            .unwrap_or(config.default_queue);
+
+        let job = QueuedJob {
+            id: String::new(),
+            queue,
+            name: J::NAME.to_string(),
+            payload,
+            attempts: 0,
+            max_attempts: self.job.tries().unwrap_or(config.max_attempts).max(1),
+        };
+
+        Queue::driver().await?.push(job, self.delay).await
+    }
+}
+
+impl<J: Job> IntoFuture for PendingDispatch<J> {
+    type Output = Result<String, FrameworkError>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.send())
+    }
+}
+
+/// Dispatch a job onto the queue
+///
+/// Options are applied in order as `PendingDispatch` builder calls.
+///
+/// Example:
+/// ```rust,ignore
+/// dispatch!(SendWelcomeEmail { user_id }).await?;
+/// dispatch!(SendWelcomeEmail { user_id }, delay = Duration::from_secs(60)).await?;
+/// ```
+#[macro_export]
+macro_rules! dispatch {
+    ($job:expr $(, $option:ident = $value:expr)* $(,)?) => {
+        $crate::queue::Queue::dispatch($job) $(.$option($value))*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TestContainer, TestDatabase};
+    use sea_orm::{ConnectionTrait, EntityTrait};
+    use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static GREETED: AtomicUsize = AtomicUsize::new(0);
+    static FAILED_HOOKS: AtomicUsize = AtomicUsize::new(0);
+
+    #[crate::job]
+    struct Greet {
+        times: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl Job for Greet {
+        async fn handle(&self) -> Result<(), FrameworkError> {
+            GREETED.fetch_add(self.times, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[crate::job]
+    struct AlwaysFails;
+
+    #[async_trait::async_trait]
+    impl Job for AlwaysFails {
+        async fn handle(&self) -> Result<(), FrameworkError> {
+            Err(FrameworkError::internal("boom"))
+        }
+
+        fn tries(&self) -> Option<u32> {
+            Some(2)
+        }
+
+        async fn failed(&self, _error: &FrameworkError) {
+            FAILED_HOOKS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreateJobs)]
+        }
+    }
+
+    struct CreateJobs;
+
+    impl MigrationName for CreateJobs {
+        fn name(&self) -> &str {
+            "create_jobs"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreateJobs {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), sea_orm::DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "CREATE TABLE jobs (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        queue TEXT NOT NULL,
+                        name TEXT NOT NULL,
+                        payload TEXT NOT NULL,
+                        attempts INTEGER NOT NULL,
+                        max_attempts INTEGER NOT NULL,
+                        available_at TIMESTAMP NOT NULL,
+                        reserved_until TIMESTAMP NULL,
+                        failed_at TIMESTAMP NULL,
+                        error TEXT NULL,
+                        created_at TIMESTAMP NOT NULL
+                    )",
+                )
+                .await
+                .map(|_| ())
+        }
+    }
+
+    fn test_config() -> QueueConfig {
+        QueueConfig {
+            connection: "database".to_string(),
+            default_queue: "default".to_string(),
+            poll_interval_ms: 10,
+            concurrency: 1,
+            retry_after_secs: 90,
+            max_attempts: 3,
+            retry_backoff_secs: 0,
+            redis_url: String::new(),
+            redis_prefix: String::new(),
+        }
+    }
+
+    #[test]
+    fn job_names_include_the_module_path() {
+        assert!(Greet::NAME.ends_with("::queue::tests::Greet"));
+        assert!(registry::find(Greet::NAME).is_some());
+    }
+
+    #[tokio::test]
+    async fn sync_queue_runs_jobs_on_dispatch() {
+        let _guard = TestContainer::fake();
+        TestContainer::bind::<dyn QueueDriver>(Arc::new(SyncQueue::new()));
+
+        let before = GREETED.load(Ordering::SeqCst);
+        crate::dispatch!(Greet { times: 2 }).await.unwrap();
+
+        assert_eq!(GREETED.load(Ordering::SeqCst), before + 2);
+    }
+
+    #[tokio::test]
+    async fn database_queue_runs_and_deletes_jobs() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        TestContainer::bind::<dyn QueueDriver>(Arc::new(DatabaseQueue::new()));
+        let driver = Queue::driver().await.unwrap();
+        let config = test_config();
+
+        crate::dispatch!(Greet { times: 1 }, queue = "greetings")
+            .await
+            .unwrap();
+        assert!(driver
+            .pop("default", Duration::from_secs(90))
+            .await
+            .unwrap()
+            .is_none());
+
+        let job = driver
+            .pop("greetings", Duration::from_secs(90))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.attempts, 1);
+        assert_eq!(job.max_attempts, 3);
+
+        // Reserved jobs are hidden from other workers
+        assert!(driver
+            .pop("greetings", Duration::from_secs(90))
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(worker::process_job(job, driver.as_ref(), &config)
+            .await
+            .unwrap());
+        let remaining = database::jobs::Entity::find().all(db.conn()).await.unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[tokio::test]
+    async fn delayed_jobs_are_not_available_yet() {
+        let _db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        TestContainer::bind::<dyn QueueDriver>(Arc::new(DatabaseQueue::new()));
+        let driver = Queue::driver().await.unwrap();
+
+        crate::dispatch!(Greet { times: 1 }, delay = Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        assert!(driver
+            .pop("default", Duration::from_secs(90))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn failing_jobs_are_retried_then_marked_failed() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        TestContainer::bind::<dyn QueueDriver>(Arc::new(DatabaseQueue::new()));
+        let driver = Queue::driver().await.unwrap();
+        let config = test_config();
+        let hooks_before = FAILED_HOOKS.load(Ordering::SeqCst);
+
+        crate::dispatch!(AlwaysFails).await.unwrap();
+
+        let first = driver
+            .pop("default", Duration::from_secs(90))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!worker::process_job(first, driver.as_ref(), &config)
+            .await
+            .unwrap());
+        assert_eq!(FAILED_HOOKS.load(Ordering::SeqCst), hooks_before);
+
+        let second = driver
+            .pop("default", Duration::from_secs(90))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.attempts, 2);
+        assert!(!worker::process_job(second, driver.as_ref(), &config)
+            .await
+            .unwrap());
+        assert_eq!(FAILED_HOOKS.load(Ordering::SeqCst), hooks_before + 1);
+
+        assert!(driver
+            .pop("default", Duration::from_secs(90))
+            .await
+            .unwrap()
+            .is_none());
+        let failed = database::jobs::Entity::find()
+            .one(db.conn())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(failed.failed_at.is_some());
+        assert!(failed.error.unwrap().contains("boom"));
+    }
+}
@@ -0,0 +1,206 @@
+//! Redis-backed queue
+
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, AsyncCommands, Client, Script};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::config::QueueConfig;
+use super::driver::QueueDriver;
+use super::job::QueuedJob;
+use crate::error::FrameworkError;
+
+/// Moves due delayed and expired reserved jobs onto the ready list, then
+/// reserves the first ready job.
+///
+/// KEYS: ready list, delayed zset, reserved zset
+/// ARGV: now (ms), reserved until (ms), job hash key prefix
+const POP_SCRIPT: &str = r#"
+local due = redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', ARGV[1])
+for _, id in ipairs(due) do
+    redis.call('ZREM', KEYS[2], id)
+    redis.call('RPUSH', KEYS[1], id)
+end
+local expired = redis.call('ZRANGEBYSCORE', KEYS[3], '-inf', ARGV[1])
+for _, id in ipairs(expired) do
+    redis.call('ZREM', KEYS[3], id)
+    redis.call('RPUSH', KEYS[1], id)
+end
+while true do
+    local id = redis.call('LPOP', KEYS[1])
+    if not id then
+        return nil
+    end
+    local key = ARGV[3] .. id
+    if redis.call('EXISTS', key) == 1 then
+        redis.call('HINCRBY', key, 'attempts', 1)
+        redis.call('ZADD', KEYS[3], ARGV[2], id)
+        local fields = redis.call('HGETALL', key)
+        table.insert(fields, 'id')
+        table.insert(fields, id)
+        return fields
+    end
+end
+"#;
+
+/// Queue backend storing jobs in Redis
+///
+/// Each queue uses a ready list plus `:delayed` and `:reserved` sorted sets
+/// of job IDs; job data lives in a hash per job. Failed jobs are appended to
+/// the `failed` list as JSON.
+pub struct RedisQueue {
+    conn: ConnectionManager,
+    prefix: String,
+}
+
+impl RedisQueue {
+    /// Create a new Redis queue connection
+    pub async fn connect(config: &QueueConfig) -> Result<Self, FrameworkError> {
+        let client = Client::open(config.redis_url.as_str())
+            .map_err(|e| FrameworkError::internal(format!("Redis connection error: {}", e)))?;
+
+        let conn = ConnectionManager::new(client).await.map_err(|e| {
+            FrameworkError::internal(format!("Redis connection manager error: {}", e))
+        })?;
+
+        Ok(Self {
+            conn,
+            prefix: config.redis_prefix.clone(),
+        })
+    }
+
+    fn queue_key(&self, queue: &str) -> String {
+        format!("{}{}", self.prefix, queue)
+    }
+
+    fn job_key(&self, id: &str) -> String {
+        format!("{}job:{}", self.prefix, id)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn redis_error(e: redis::RedisError) -> FrameworkError {
+    FrameworkError::internal(format!("Queue redis error: {}", e))
+}
+
+#[async_trait]
+impl QueueDriver for RedisQueue {
+    async fn push(&self, job: QueuedJob, delay: Duration) -> Result<String, FrameworkError> {
+        let mut conn = self.conn.clone();
+        let id: u64 = conn
+            .incr(format!("{}ids", self.prefix), 1)
+            .await
+            .map_err(redis_error)?;
+        let id = id.to_string();
+        let queue_key = self.queue_key(&job.queue);
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().hset_multiple(
+            self.job_key(&id),
+            &[
+                ("queue", job.queue.as_str()),
+                ("name", job.name.as_str()),
+                ("payload", job.payload.as_str()),
+                ("attempts", "0"),
+                ("max_attempts", &job.max_attempts.to_string()),
+            ],
+        );
+        if delay.is_zero() {
+            pipe.rpush(&queue_key, &id);
+        } else {
+            let available_at = now_ms() + delay.as_millis() as u64;
+            pipe.zadd(format!("{}:delayed", queue_key), &id, available_at);
+        }
+        pipe.query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(redis_error)?;
+
+        Ok(id)
+    }
+
+    async fn pop(
+        &self,
+        queue: &str,
+        retry_after: Duration,
+    ) -> Result<Option<QueuedJob>, FrameworkError> {
+        let mut conn = self.conn.clone();
+        let queue_key = self.queue_key(queue);
+        let now = now_ms();
+
+        let fields: Option<HashMap<String, String>> = Script::new(POP_SCRIPT)
+            .key(&queue_key)
+            .key(format!("{}:delayed", queue_key))
+            .key(format!("{}:reserved", queue_key))
+            .arg(now)
+            .arg(now + retry_after.as_millis() as u64)
+            .arg(format!("{}job:", self.prefix))
+            .invoke_async(&mut conn)
+            .await
+            .map_err(redis_error)?;
+
+        let Some(mut fields) = fields else {
+            return Ok(None);
+        };
+        let mut take = |field: &str| fields.remove(field).unwrap_or_default();
+
+        Ok(Some(QueuedJob {
+            id: take("id"),
+            queue: take("queue"),
+            name: take("name"),
+            payload: take("payload"),
+            attempts: take("attempts").parse().unwrap_or(1),
+            max_attempts: take("max_attempts").parse().unwrap_or(1),
+        }))
+    }
+
+    async fn delete(&self, job: &QueuedJob) -> Result<(), FrameworkError> {
+        let mut conn = self.conn.clone();
+        redis::pipe()
+            .atomic()
+            .zrem(format!("{}:reserved", self.queue_key(&job.queue)), &job.id)
+            .del(self.job_key(&job.id))
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn release(&self, job: &QueuedJob, delay: Duration) -> Result<(), FrameworkError> {
+        let mut conn = self.conn.clone();
+        let queue_key = self.queue_key(&job.queue);
+        let available_at = now_ms() + delay.as_millis() as u64;
+        redis::pipe()
+            .atomic()
+            .zrem(format!("{}:reserved", queue_key), &job.id)
+            .zadd(format!("{}:delayed", queue_key), &job.id, available_at)
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn fail(&self, job: &QueuedJob, error: &str) -> Result<(), FrameworkError> {
+        let mut conn = self.conn.clone();
+        let record = serde_json::json!({
+            "id": job.id,
+            "queue": job.queue,
+            "name": job.name,
+            "payload": job.payload,
+            "attempts": job.attempts,
+            "error": error,
+            "failed_at": now_ms(),
+        });
+        redis::pipe()
+            .atomic()
+            .zrem(format!("{}:reserved", self.queue_key(&job.queue)), &job.id)
+            .del(self.job_key(&job.id))
+            .rpush(format!("{}failed", self.prefix), record.to_string())
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(redis_error)
+    }
+}
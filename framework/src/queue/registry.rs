@@ -0,0 +1,28 @@
+//! Job registry via inventory
+
+use crate::error::FrameworkError;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Boxed job runner, given the JSON payload
+pub type JobRunner = fn(&str) -> Pin<Box<dyn Future<Output = Result<(), FrameworkError>> + Send>>;
+
+/// Boxed failure hook, given the JSON payload and the last error
+pub type JobFailedHook =
+    fn(&str, FrameworkError) -> Pin<Box<dyn Future<Output = Result<(), FrameworkError>> + Send>>;
+
+/// Inventory entry for a job
+pub struct JobEntry {
+    pub name: &'static str,
+    pub run: JobRunner,
+    pub failed: JobFailedHook,
+}
+
+inventory::collect!(JobEntry);
+
+/// Find a job entry by name
+pub fn find(name: &str) -> Option<&'static JobEntry> {
+    inventory::iter::<JobEntry>
+        .into_iter()
+        .find(|entry| entry.name == name)
+}
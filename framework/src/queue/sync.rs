@@ -0,0 +1,57 @@
+//! Queue backend that runs jobs immediately
+
+use async_trait::async_trait;
+use std::time::Duration;
+
+use super::driver::QueueDriver;
+use super::job::QueuedJob;
+use super::registry;
+use crate::error::FrameworkError;
+
+/// Runs jobs as soon as they are dispatched, ignoring delays
+///
+/// Useful in development and tests: no worker is needed and job errors are
+/// returned from `dispatch!`. Jobs are attempted once.
+#[derive(Debug, Default)]
+pub struct SyncQueue;
+
+impl SyncQueue {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl QueueDriver for SyncQueue {
+    async fn push(&self, job: QueuedJob, _delay: Duration) -> Result<String, FrameworkError> {
+        let entry = registry::find(&job.name).ok_or_else(|| {
+            FrameworkError::internal(format!("Job '{}' is not registered", job.name))
+        })?;
+
+        if let Err(err) = (entry.run)(&job.payload).await {
+            (entry.failed)(&job.payload, err.clone()).await?;
+            return Err(err);
+        }
+        Ok(String::new())
+    }
+
+    async fn pop(
+        &self,
+        _queue: &str,
+        _retry_after: Duration,
+    ) -> Result<Option<QueuedJob>, FrameworkError> {
+        Ok(None)
+    }
+
+    async fn delete(&self, _job: &QueuedJob) -> Result<(), FrameworkError> {
+        Ok(())
+    }
+
+    async fn release(&self, _job: &QueuedJob, _delay: Duration) -> Result<(), FrameworkError> {
+        Ok(())
+    }
+
+    async fn fail(&self, _job: &QueuedJob, _error: &str) -> Result<(), FrameworkError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+//! Queue worker daemon
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+use super::config::QueueConfig;
+use super::driver::QueueDriver;
+use super::job::QueuedJob;
+use super::{registry, Queue};
+use crate::config::Config;
+use crate::daemon::{Daemon, DaemonOptions, StopReason, WorkerStats};
+use crate::error::FrameworkError;
+
+/// Queue worker daemon
+///
+/// Reserves jobs from one or more queues, checked in the given order, and
+/// runs up to `QUEUE_CONCURRENCY` of them at once.
+pub struct QueueWorker {
+    config: Arc<QueueConfig>,
+    queues: Vec<String>,
+    stats: Arc<WorkerStats>,
+}
+
+impl QueueWorker {
+    /// Create a worker with config from environment
+    pub fn new() -> Self {
+        let config = Config::get::<QueueConfig>().unwrap_or_default();
+        Self::with_config(config)
+    }
+
+    /// Create a worker with a custom config
+    pub fn with_config(config: QueueConfig) -> Self {
+        Self {
+            queues: vec![config.default_queue.clone()],
+            config: Arc::new(config),
+            stats: Arc::new(WorkerStats::default()),
+        }
+    }
+
+    /// Work these queues instead of the default one, highest priority first
+    pub fn queues(mut self, queues: Vec<String>) -> Self {
+        if !queues.is_empty() {
+            self.queues = queues;
+        }
+        self
+    }
+
+    /// Record processed and failed jobs into shared stats
+    pub fn with_stats(mut self, stats: Arc<WorkerStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Run the worker loop until one of the daemon stop conditions is met
+    ///
+    /// In-flight jobs are allowed to finish before returning.
+    pub async fn work_loop_with(
+        options: DaemonOptions,
+        queues: Vec<String>,
+    ) -> Result<StopReason, FrameworkError> {
+        Self::new()
+            .queues(queues)
+            .run(Daemon::new("Queue worker", options))
+            .await
+    }
+
+    /// Run this worker under the given daemon
+    ///
+    /// In-flight jobs are allowed to finish before returning.
+    pub async fn run(self, daemon: Daemon) -> Result<StopReason, FrameworkError> {
+        let driver = Queue::driver().await?;
+        let poll = Duration::from_millis(self.config.poll_interval_ms);
+        let semaphore = Arc::new(Semaphore::new(self.config.concurrency));
+        let signal = daemon.signal();
+        let daemon = daemon.stats(self.stats.clone());
+        let queues = Arc::new(self.queues);
+
+        let reason = daemon
+            .run(|| {
+                let semaphore = semaphore.clone();
+                let signal = signal.clone();
+                let config = self.config.clone();
+                let driver = driver.clone();
+                let queues = queues.clone();
+                let stats = self.stats.clone();
+                async move {
+                    let permit = semaphore.acquire_owned().await.unwrap();
+
+                    match pop_next(driver.as_ref(), &queues, &config).await {
+                        Ok(Some(job)) => {
+                            tokio::spawn(async move {
+                                match process_job(job, driver.as_ref(), &config).await {
+                                    Ok(true) => {}
+                                    Ok(false) => stats.record_failure(),
+                                    Err(err) => {
+                                        stats.record_failure();
+                                        eprintln!("Queue job error: {}", err);
+                                    }
+                                }
+                                drop(permit);
+                            });
+                            Ok(1)
+                        }
+                        Ok(None) => {
+                            drop(permit);
+                            signal.sleep(poll).await;
+                            Ok(0)
+                        }
+                        Err(err) => {
+                            drop(permit);
+                            signal.sleep(poll).await;
+                            Err(FrameworkError::internal(format!(
+                                "Queue pop error: {}",
+                                err
+                            )))
+                        }
+                    }
+                }
+            })
+            .await;
+
+        // Wait for in-flight jobs to finish
+        let _ = semaphore.acquire_many(self.config.concurrency as u32).await;
+
+        Ok(reason)
+    }
+}
+
+impl Default for QueueWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reserve the next job from the first queue that has one
+async fn pop_next(
+    driver: &dyn QueueDriver,
+    queues: &[String],
+    config: &QueueConfig,
+) -> Result<Option<QueuedJob>, FrameworkError> {
+    let retry_after = Duration::from_secs(config.retry_after_secs);
+    for queue in queues {
+        if let Some(job) = driver.pop(queue, retry_after).await? {
+            return Ok(Some(job));
+        }
+    }
+    Ok(None)
+}
+
+/// Run a reserved job, returning whether it succeeded
+///
+/// Failed jobs are released with a linear backoff until their attempts are
+/// used up, then the job's `failed` hook runs and the job is marked failed.
+pub(crate) async fn process_job(
+    job: QueuedJob,
+    driver: &dyn QueueDriver,
+    config: &QueueConfig,
+) -> Result<bool, FrameworkError> {
+    let entry = match registry::find(&job.name) {
+        Some(entry) => entry,
+        None => {
+            driver.fail(&job, "Job not registered").await?;
+            return Ok(false);
+        }
+    };
+
+    match (entry.run)(&job.payload).await {
+        Ok(()) => {
+            driver.delete(&job).await?;
+            Ok(true)
+        }
+        Err(_) if job.attempts < job.max_attempts => {
+            let backoff = config.retry_backoff_secs * job.attempts as u64;
+            driver.release(&job, Duration::from_secs(backoff)).await?;
+            Ok(false)
+        }
+        Err(err) => {
+            let message = err.to_string();
+            if let Err(hook_err) = (entry.failed)(&job.payload, err).await {
+                eprintln!("Queue job failed hook error: {}", hook_err);
+            }
+            driver.fail(&job, &message).await?;
+            Ok(false)
+        }
+    }
+}
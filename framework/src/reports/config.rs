@@ -0,0 +1,129 @@
+//! Report generation configuration
+
+use crate::config::{env, env_optional};
+
+/// Report generation configuration
+///
+/// # Environment Variables
+///
+/// - `REPORT_PDF_DRIVER` - HTML to PDF converter: "chromium" or "weasyprint"
+///   (default: "chromium")
+/// - `REPORT_PDF_BINARY` - Path or name of the converter's executable
+///   (default: the driver name)
+/// - `REPORT_PDF_TIMEOUT` - Seconds a conversion may take (default: 60)
+/// - `REPORT_DISK` - Disk queued reports are stored on (default: `FILESYSTEM_DISK`)
+/// - `REPORT_DIRECTORY` - Directory queued reports are stored in (default: "reports")
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Config, ReportConfig};
+///
+/// // Register from environment
+/// Config::register(ReportConfig::from_env());
+///
+/// // Or build manually
+/// Config::register(ReportConfig::builder()
+///     .driver("weasyprint")
+///     .binary("/usr/local/bin/weasyprint")
+///     .build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReportConfig {
+    /// Converter name
+    pub driver: String,
+    /// Converter executable
+    pub binary: String,
+    /// Seconds a conversion may take
+    pub timeout: u64,
+    /// Disk for queued reports, `None` for the default disk
+    pub disk: Option<String>,
+    /// Directory for queued reports
+    pub directory: String,
+}
+
+impl ReportConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        let driver = env("REPORT_PDF_DRIVER", "chromium".to_string());
+        Self {
+            binary: env("REPORT_PDF_BINARY", driver.clone()),
+            driver,
+            timeout: env("REPORT_PDF_TIMEOUT", 60),
+            disk: env_optional("REPORT_DISK"),
+            directory: env("REPORT_DIRECTORY", "reports".to_string()),
+        }
+    }
+
+    /// Create a builder for manual configuration
+    pub fn builder() -> ReportConfigBuilder {
+        ReportConfigBuilder::default()
+    }
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Builder for ReportConfig
+#[derive(Debug, Default)]
+pub struct ReportConfigBuilder {
+    driver: Option<String>,
+    binary: Option<String>,
+    timeout: Option<u64>,
+    disk: Option<String>,
+    directory: Option<String>,
+}
+
+impl ReportConfigBuilder {
+    /// Set the converter, "chromium" or "weasyprint"
+    pub fn driver(mut self, driver: impl Into<String>) -> Self {
+        self.driver = Some(driver.into());
+        self
+    }
+
+    /// Set the converter executable
+    pub fn binary(mut self, binary: impl Into<String>) -> Self {
+        self.binary = Some(binary.into());
+        self
+    }
+
+    /// Set the seconds a conversion may take
+    pub fn timeout(mut self, seconds: u64) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Set the disk queued reports are stored on
+    pub fn disk(mut self, disk: impl Into<String>) -> Self {
+        self.disk = Some(disk.into());
+        self
+    }
+
+    /// Set the directory queued reports are stored in
+    pub fn directory(mut self, directory: impl Into<String>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Build the configuration
+    ///
+    /// The executable defaults to the driver name when only the driver is set.
+    pub fn build(self) -> ReportConfig {
+        let defaults = ReportConfig::from_env();
+        let binary = match (self.binary, &self.driver) {
+            (Some(binary), _) => binary,
+            (None, Some(driver)) => driver.clone(),
+            (None, None) => defaults.binary,
+        };
+        ReportConfig {
+            driver: self.driver.unwrap_or(defaults.driver),
+            binary,
+            timeout: self.timeout.unwrap_or(defaults.timeout),
+            disk: self.disk.or(defaults.disk),
+            directory: self.directory.unwrap_or(defaults.directory),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! PDF reports
+//!
+//! A report is a struct that renders itself to HTML; the configured driver
+//! (headless Chromium or WeasyPrint, see `ReportConfig`) turns the HTML into
+//! a PDF. Small reports are generated while the request waits and streamed
+//! back; large ones are queued, stored on a disk and announced with the
+//! `ReportReady` event.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::{async_trait, report, reports, FrameworkError, Report};
+//!
+//! #[report]
+//! pub struct SalesReport {
+//!     pub month: u32,
+//! }
+//!
+//! #[async_trait]
+//! impl Report for SalesReport {
+//!     async fn html(&self) -> Result<String, FrameworkError> {
+//!         let orders = Order::for_month(self.month).await?;
+//!         // Or build the HTML yourself
+//!         reports::view("Reports/Sales", json!({ "orders": orders })).await
+//!     }
+//!
+//!     fn filename(&self) -> String {
+//!         format!("sales-{}.pdf", self.month)
+//!     }
+//! }
+//!
+//! // Generate now and download
+//! #[handler]
+//! pub async fn download(req: Request) -> Response {
+//!     Ok(SalesReport { month: 5 }.download().await?)
+//! }
+//!
+//! // Generate on the queue; the logged in user is named in `ReportReady`
+//! SalesReport { month: 5 }.queue().await?;
+//! ```
+
+pub mod config;
+pub mod pdf;
+pub mod queued;
+#[doc(hidden)]
+pub mod registry;
+
+pub use config::{ReportConfig, ReportConfigBuilder};
+pub use pdf::view;
+pub use queued::{GenerateReport, PendingReport, ReportFailed, ReportReady};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::stream;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::FrameworkError;
+use crate::http::download::content_disposition;
+use crate::http::HttpResponse;
+
+/// Size of the chunks a download is streamed in
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// A PDF report
+///
+/// Register the struct with `#[report]`, which also derives `Serialize` and
+/// `Deserialize` so queued reports can be rebuilt by the worker.
+#[async_trait]
+pub trait Report: ReportName + Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// The report as a complete HTML document
+    async fn html(&self) -> Result<String, FrameworkError>;
+
+    /// Name of the PDF file, e.g. "sales-2024-05.pdf"
+    fn filename(&self) -> String;
+
+    /// Render the report to PDF
+    async fn pdf(&self) -> Result<Bytes, FrameworkError> {
+        pdf::render(&self.html().await?).await
+    }
+
+    /// Render the report and send it as a download
+    async fn download(&self) -> Result<HttpResponse, FrameworkError> {
+        let pdf = self.pdf().await?;
+        let len = pdf.len();
+        let chunks: Vec<Bytes> = (0..len)
+            .step_by(DOWNLOAD_CHUNK_SIZE)
+            .map(|start| pdf.slice(start..(start + DOWNLOAD_CHUNK_SIZE).min(len)))
+            .collect();
+        Ok(HttpResponse::stream(stream::iter(chunks))
+            .content_type("application/pdf")
+            .header(
+                "Content-Disposition",
+                content_disposition("attachment", &self.filename()),
+            )
+            .header("Content-Length", len.to_string()))
+    }
+
+    /// Generate the report on the queue instead
+    fn queue(self) -> PendingReport<Self> {
+        PendingReport::new(self)
+    }
+}
+
+/// Name reports are stored under, implemented by `#[report]`
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not registered as a report, add `#[report]` to it"
+)]
+pub trait ReportName {
+    const NAME: &'static str;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::events::Event;
+    use crate::queue::{QueueDriver, SyncQueue};
+    use crate::storage::Storage;
+    use crate::testing::TestContainer;
+    use http_body_util::BodyExt;
+    use std::sync::Arc;
+
+    #[crate::report]
+    struct Invoice {
+        number: u32,
+    }
+
+    #[async_trait]
+    impl Report for Invoice {
+        async fn html(&self) -> Result<String, FrameworkError> {
+            Ok(format!("<h1>Invoice {}</h1>", self.number))
+        }
+
+        fn filename(&self) -> String {
+            format!("invoice-{}.pdf", self.number)
+        }
+    }
+
+    /// A "driver" copying the HTML to the PDF path, like WeasyPrint's
+    /// `weasyprint <input> <output>`
+    fn copying_driver() {
+        Config::register(
+            ReportConfig::builder()
+                .driver("weasyprint")
+                .binary("cp")
+                .disk("reports")
+                .build(),
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_are_converted_and_downloaded() {
+        copying_driver();
+
+        let response = Invoice { number: 7 }.download().await.unwrap().into_hyper();
+        assert_eq!(response.headers()["Content-Type"], "application/pdf");
+        assert_eq!(
+            response.headers()["Content-Disposition"],
+            "attachment; filename=\"invoice-7.pdf\""
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "<h1>Invoice 7</h1>");
+    }
+
+    #[tokio::test]
+    async fn queued_reports_are_stored_and_announced() {
+        copying_driver();
+        let _container = TestContainer::fake();
+        TestContainer::bind::<dyn QueueDriver>(Arc::new(SyncQueue::new()));
+        let disk = Storage::fake_disk("reports");
+        let events = Event::fake();
+
+        Invoice { number: 8 }.queue().notify(3).await.unwrap();
+
+        let ready = events.dispatched::<ReportReady>();
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].report, Invoice::NAME);
+        assert_eq!(ready[0].user_id, Some(3));
+        assert!(ready[0].path.starts_with("reports/"));
+        assert!(ready[0].path.ends_with("/invoice-8.pdf"));
+        disk.assert_exists(&ready[0].path);
+        assert_eq!(
+            Storage::disk("reports")
+                .unwrap()
+                .get(&ready[0].path)
+                .await
+                .unwrap(),
+            "<h1>Invoice 8</h1>"
+        );
+    }
+}
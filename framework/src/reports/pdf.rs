@@ -0,0 +1,132 @@
+//! HTML to PDF conversion and view rendering
+
+use bytes::Bytes;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+use super::config::ReportConfig;
+use crate::config::Config;
+use crate::error::FrameworkError;
+use crate::inertia::{ssr, InertiaSsrConfig};
+
+/// Convert an HTML document to PDF with the configured driver
+///
+/// The HTML is written to a temporary directory so relative links resolve
+/// nowhere; inline styles and images or use absolute URLs.
+pub async fn render(html: &str) -> Result<Bytes, FrameworkError> {
+    let config = Config::get::<ReportConfig>().unwrap_or_default();
+    let suffix: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect();
+    let dir = std::env::temp_dir().join(format!("kit-report-{}", suffix));
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| io_error("create", &dir, e))?;
+
+    let result = convert(&config, html, &dir).await;
+    let _ = tokio::fs::remove_dir_all(&dir).await;
+    result
+}
+
+async fn convert(config: &ReportConfig, html: &str, dir: &Path) -> Result<Bytes, FrameworkError> {
+    let input = dir.join("report.html");
+    let output = dir.join("report.pdf");
+    tokio::fs::write(&input, html)
+        .await
+        .map_err(|e| io_error("write", &input, e))?;
+
+    let mut command = Command::new(&config.binary);
+    match config.driver.as_str() {
+        "chromium" => command
+            .arg("--headless")
+            .arg("--disable-gpu")
+            .arg("--no-pdf-header-footer")
+            .arg(format!("--print-to-pdf={}", output.display()))
+            .arg(format!("file://{}", input.display())),
+        "weasyprint" => command.arg(&input).arg(&output),
+        other => {
+            return Err(FrameworkError::internal(format!(
+                "Unknown PDF driver '{}'",
+                other
+            )))
+        }
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let child = command.spawn().map_err(|e| {
+        FrameworkError::internal(format!("Could not start {}: {}", config.binary, e))
+    })?;
+    let finished = tokio::time::timeout(
+        Duration::from_secs(config.timeout),
+        child.wait_with_output(),
+    )
+    .await
+    .map_err(|_| {
+        FrameworkError::internal(format!(
+            "{} did not finish within {} seconds",
+            config.binary, config.timeout
+        ))
+    })?
+    .map_err(|e| FrameworkError::internal(format!("{} failed: {}", config.binary, e)))?;
+    if !finished.status.success() {
+        return Err(FrameworkError::internal(format!(
+            "{} failed: {}",
+            config.binary,
+            String::from_utf8_lossy(&finished.stderr).trim()
+        )));
+    }
+
+    let pdf = tokio::fs::read(&output)
+        .await
+        .map_err(|e| io_error("read", &output, e))?;
+    Ok(Bytes::from(pdf))
+}
+
+fn io_error(action: &str, path: &Path, err: std::io::Error) -> FrameworkError {
+    FrameworkError::internal(format!("Failed to {} {}: {}", action, path.display(), err))
+}
+
+/// Render an Inertia page to an HTML document on the SSR render server
+///
+/// Lets a report reuse the app's components for its layout. Unlike pages,
+/// there is no client-side fallback, so the render server must be running
+/// (see `InertiaSsrConfig`; it is used even when SSR is disabled for pages).
+///
+/// ```rust,ignore
+/// async fn html(&self) -> Result<String, FrameworkError> {
+///     let orders = Order::for_month(self.month).await?;
+///     reports::view("Reports/Sales", json!({ "orders": orders })).await
+/// }
+/// ```
+pub async fn view(component: &str, props: impl Serialize) -> Result<String, FrameworkError> {
+    let config = Config::get::<InertiaSsrConfig>().unwrap_or_default();
+    let page = serde_json::json!({
+        "component": component,
+        "props": props,
+        "url": "",
+        "version": null,
+    });
+    let rendered = ssr::render(&config, &page).await.ok_or_else(|| {
+        FrameworkError::internal(format!(
+            "The SSR render server at {} could not render {}",
+            config.url, component
+        ))
+    })?;
+
+    Ok(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"UTF-8\">\n{}\n</head>\n<body>\n{}\n</body>\n</html>",
+        rendered.head.join("\n"),
+        rendered.body
+    ))
+}
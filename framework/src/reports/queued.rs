@@ -0,0 +1,177 @@
+//! Generating reports on the queue
+
+use async_trait::async_trait;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::time::Duration;
+
+use super::config::ReportConfig;
+use super::{registry, Report};
+use crate::auth::Auth;
+use crate::config::Config;
+use crate::error::FrameworkError;
+use crate::events::Event;
+use crate::queue::{Job, Queue};
+use crate::storage::Storage;
+
+/// A report about to be queued
+///
+/// Await it (or call `send()`) to push the job; resolves to the job's ID.
+#[must_use = "reports are only queued when awaited"]
+pub struct PendingReport<R: Report> {
+    report: R,
+    user_id: Option<i64>,
+    queue: Option<String>,
+}
+
+impl<R: Report> PendingReport<R> {
+    pub(crate) fn new(report: R) -> Self {
+        Self {
+            report,
+            user_id: Auth::id(),
+            queue: None,
+        }
+    }
+
+    /// Name `user_id` in `ReportReady` instead of the logged in user
+    pub fn notify(mut self, user_id: i64) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    /// Push to this queue instead of `QUEUE_NAME`
+    pub fn queue(mut self, queue: impl Into<String>) -> Self {
+        self.queue = Some(queue.into());
+        self
+    }
+
+    /// Push the job generating the report
+    pub async fn send(self) -> Result<String, FrameworkError> {
+        let payload = serde_json::to_string(&self.report)
+            .map_err(|e| FrameworkError::internal(format!("Report serialize error: {}", e)))?;
+        let job = GenerateReport {
+            report: R::NAME.to_string(),
+            payload,
+            user_id: self.user_id,
+        };
+        match self.queue {
+            Some(queue) => Queue::dispatch(job).queue(queue).await,
+            None => Queue::dispatch(job).await,
+        }
+    }
+}
+
+impl<R: Report> IntoFuture for PendingReport<R> {
+    type Output = Result<String, FrameworkError>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.send())
+    }
+}
+
+/// Job rendering a queued report and storing the PDF
+///
+/// The file goes to `REPORT_DISK` under `REPORT_DIRECTORY`, then
+/// `ReportReady` is dispatched; `ReportFailed` is dispatched once the last
+/// attempt fails.
+#[crate::job]
+pub struct GenerateReport {
+    /// Registered report name
+    pub report: String,
+    /// The report's fields as JSON
+    pub payload: String,
+    /// User to notify
+    pub user_id: Option<i64>,
+}
+
+#[async_trait]
+impl Job for GenerateReport {
+    async fn handle(&self) -> Result<(), FrameworkError> {
+        let entry = registry::find(&self.report).ok_or_else(|| {
+            FrameworkError::internal(format!("Report '{}' is not registered", self.report))
+        })?;
+        let (filename, pdf) = (entry.generate)(&self.payload).await?;
+
+        let config = Config::get::<ReportConfig>().unwrap_or_default();
+        let disk = config.disk.unwrap_or_else(Storage::default_disk_name);
+        let folder: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(16)
+            .map(char::from)
+            .collect();
+        let path = format!(
+            "{}/{}/{}",
+            config.directory.trim_end_matches('/'),
+            folder,
+            filename
+        );
+        Storage::disk(&disk)?.put(&path, pdf).await?;
+
+        Event::dispatch(ReportReady {
+            report: self.report.clone(),
+            filename,
+            disk,
+            path,
+            user_id: self.user_id,
+        })
+        .await
+    }
+
+    async fn failed(&self, error: &FrameworkError) {
+        let event = ReportFailed {
+            report: self.report.clone(),
+            user_id: self.user_id,
+            error: error.to_string(),
+        };
+        if let Err(e) = Event::dispatch(event).await {
+            eprintln!("ReportFailed listener for {} failed: {}", self.report, e);
+        }
+    }
+}
+
+/// Dispatched when a queued report has been stored
+///
+/// Listen for it to tell the user, e.g. by mail or over a WebSocket channel.
+///
+/// ```rust,ignore
+/// #[listener]
+/// async fn send_report_link(event: &ReportReady) -> Result<(), FrameworkError> {
+///     let url = event.temporary_url(Duration::from_secs(24 * 3600))?;
+///     // ...
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportReady {
+    /// Registered report name
+    pub report: String,
+    /// File name given by `Report::filename`
+    pub filename: String,
+    /// Disk the PDF is stored on
+    pub disk: String,
+    /// Path of the PDF on the disk
+    pub path: String,
+    /// User who asked for the report, if any
+    pub user_id: Option<i64>,
+}
+
+impl ReportReady {
+    /// Expiring link to the PDF
+    pub fn temporary_url(&self, expires_in: Duration) -> Result<String, FrameworkError> {
+        Storage::disk(&self.disk)?.temporary_url(&self.path, expires_in)
+    }
+}
+
+/// Dispatched when a queued report failed on its last attempt
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportFailed {
+    /// Registered report name
+    pub report: String,
+    /// User who asked for the report, if any
+    pub user_id: Option<i64>,
+    /// Why it failed
+    pub error: String,
+}
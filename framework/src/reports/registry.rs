@@ -0,0 +1,35 @@
+//! Report registry via inventory
+
+use bytes::Bytes;
+use std::future::Future;
+use std::pin::Pin;
+
+use super::Report;
+use crate::error::FrameworkError;
+
+/// A rendered report: its file name and the PDF
+pub type Generated = Result<(String, Bytes), FrameworkError>;
+
+/// Boxed generator, given the JSON payload
+pub type ReportGenerator = fn(&str) -> Pin<Box<dyn Future<Output = Generated> + Send>>;
+
+/// Inventory entry for a report
+pub struct ReportEntry {
+    pub name: &'static str,
+    pub generate: ReportGenerator,
+}
+
+inventory::collect!(ReportEntry);
+
+/// Find a report entry by name
+pub fn find(name: &str) -> Option<&'static ReportEntry> {
+    inventory::iter::<ReportEntry>
+        .into_iter()
+        .find(|entry| entry.name == name)
+}
+
+/// Render a report rebuilt from its payload, used by `#[report]`
+pub async fn generate<R: Report>(report: R) -> Generated {
+    let pdf = report.pdf().await?;
+    Ok((report.filename(), pdf))
+}
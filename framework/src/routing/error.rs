@@ -0,0 +1,67 @@
+//! Problems found while registering routes
+
+use std::fmt;
+use thiserror::Error;
+
+/// A route that couldn't be registered
+///
+/// `route` is the method and path as registered, e.g. `GET /users/{id}`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RouteError {
+    /// The path is malformed, e.g. no leading `/` or a repeated parameter
+    #[error("{route}: {reason}")]
+    InvalidPath { route: String, reason: String },
+
+    /// The same method and path were registered twice
+    #[error("{route} is registered more than once")]
+    Duplicate { route: String },
+
+    /// The path overlaps a route registered earlier, e.g. `/{id}` and `/{slug}`
+    #[error("{route} conflicts with {existing}")]
+    Conflict { route: String, existing: String },
+
+    /// One name was given to routes with different paths
+    #[error("Route name {name:?} is used for both {first} and {second}")]
+    DuplicateName {
+        name: String,
+        first: String,
+        second: String,
+    },
+}
+
+/// Every problem found while registering a router's routes
+///
+/// Returned by `Router::try_build`; displays as one report listing them all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteErrors {
+    errors: Vec<RouteError>,
+}
+
+impl RouteErrors {
+    pub(crate) fn new(errors: Vec<RouteError>) -> Self {
+        Self { errors }
+    }
+
+    /// The individual problems, in registration order
+    pub fn errors(&self) -> &[RouteError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for RouteErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.errors.len();
+        write!(
+            f,
+            "Found {} invalid route{}:",
+            count,
+            if count == 1 { "" } else { "s" }
+        )?;
+        for error in &self.errors {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RouteErrors {}
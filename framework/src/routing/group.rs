@@ -1,10 +1,16 @@
 //! Route grouping with shared prefix and middleware
+//!
+//! The builder API for groups. Groups are registered through `GroupDef`, the
+//! same type `group!` builds, so prefixes, name prefixes, nesting and
+//! middleware inheritance behave exactly as in `routes!`.
 
-use super::{BoxedHandler, RouteBuilder, Router};
-use crate::http::{Request, Response};
-use crate::middleware::{into_boxed, BoxedMiddleware, Middleware};
+use super::intern::intern;
+use super::macros::{GroupDef, GroupItem, GroupRoute, HttpMethod, RouteDefBuilder};
+use super::{RouteBuilder, RouteErrors, Router};
+use crate::config::Environment;
+use crate::http::{ErrorFormat, Request, Response};
+use crate::middleware::{into_boxed, Middleware};
 use std::future::Future;
-use std::sync::Arc;
 
 /// Builder for route groups with shared prefix and middleware
 ///
@@ -20,27 +26,8 @@ use std::sync::Arc;
 pub struct GroupBuilder {
     /// The outer router we're building into
     outer_router: Router,
-    /// Routes registered within this group (stored as full paths)
-    group_routes: Vec<GroupRoute>,
-    /// The prefix for this group
-    prefix: String,
-    /// Middleware to apply to all routes in this group
-    middleware: Vec<BoxedMiddleware>,
-}
-
-/// A route registered within a group
-struct GroupRoute {
-    method: GroupMethod,
-    path: String,
-    handler: Arc<BoxedHandler>,
-}
-
-#[derive(Clone, Copy)]
-enum GroupMethod {
-    Get,
-    Post,
-    Put,
-    Delete,
+    /// The group being built
+    group: GroupDef,
 }
 
 impl GroupBuilder {
@@ -54,113 +41,260 @@ impl GroupBuilder {
     ///     .middleware(ApiMiddleware)
     /// ```
     pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
-        self.middleware.push(into_boxed(middleware));
+        self.group = self.group.middleware(middleware);
         self
     }
 
-    /// Finalize the group and merge routes into the outer router
-    fn finalize(mut self) -> Router {
-        // Insert all group routes into the outer router with the prefix
-        for route in self.group_routes {
-            let full_path = format!("{}{}", self.prefix, route.path);
-
-            // Insert into the appropriate method router using public(crate) methods
-            match route.method {
-                GroupMethod::Get => {
-                    self.outer_router.insert_get(&full_path, route.handler);
-                }
-                GroupMethod::Post => {
-                    self.outer_router.insert_post(&full_path, route.handler);
-                }
-                GroupMethod::Put => {
-                    self.outer_router.insert_put(&full_path, route.handler);
-                }
-                GroupMethod::Delete => {
-                    self.outer_router.insert_delete(&full_path, route.handler);
-                }
-            }
-
-            // Apply group middleware to each route
-            for mw in &self.middleware {
-                self.outer_router.add_middleware(&full_path, mw.clone());
-            }
-        }
+    /// Prefix the names of all routes in this group (see `GroupDef::name_prefix`)
+    pub fn name_prefix(mut self, prefix: &str) -> Self {
+        self.group = self.group.name_prefix(intern(prefix));
+        self
+    }
 
-        self.outer_router
+    /// Skip an inherited middleware (see `GroupDef::without_middleware`)
+    pub fn without_middleware<M: Middleware + 'static>(mut self) -> Self {
+        self.group = self.group.without_middleware::<M>();
+        self
+    }
+
+    /// Choose how errors are rendered (see `GroupDef::error_format`)
+    pub fn error_format(mut self, format: ErrorFormat) -> Self {
+        self.group = self.group.error_format(format);
+        self
+    }
+
+    /// Only register this group in an environment (see `GroupDef::only_in`)
+    pub fn only_in(mut self, environment: Environment) -> Self {
+        self.group = self.group.only_in(environment);
+        self
+    }
+
+    /// Register a GET route after the group
+    pub fn get<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.finalize().get(path, handler)
+    }
+
+    /// Register a POST route after the group
+    pub fn post<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.finalize().post(path, handler)
+    }
+
+    /// Register a PUT route after the group
+    pub fn put<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.finalize().put(path, handler)
+    }
+
+    /// Register a PATCH route after the group
+    pub fn patch<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.finalize().patch(path, handler)
+    }
+
+    /// Register a DELETE route after the group
+    pub fn delete<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.finalize().delete(path, handler)
+    }
+
+    /// Start another group after this one
+    pub fn group<F>(self, prefix: &str, builder_fn: F) -> GroupBuilder
+    where
+        F: FnOnce(GroupRouter) -> GroupRouter,
+    {
+        self.finalize().group(prefix, builder_fn)
+    }
+
+    /// Finish the router, reporting every route that couldn't be registered
+    ///
+    /// See `Router::try_build`.
+    pub fn try_build(self) -> Result<Router, RouteErrors> {
+        self.finalize().try_build()
+    }
+
+    /// Finalize the group and merge routes into the outer router
+    fn finalize(self) -> Router {
+        self.group.register(self.outer_router)
     }
 }
 
 /// Inner router used within a group closure
 ///
 /// This captures routes without a prefix, which are later merged with the group's prefix.
+/// `.name()`, `.middleware()` and `.only_in()` apply to the most recently
+/// added route.
 pub struct GroupRouter {
-    routes: Vec<GroupRoute>,
+    items: Vec<GroupItem>,
 }
 
 impl GroupRouter {
     fn new() -> Self {
-        Self { routes: Vec::new() }
+        Self { items: Vec::new() }
     }
 
-    /// Register a GET route within the group
-    pub fn get<H, Fut>(mut self, path: &str, handler: H) -> Self
+    fn route<H, Fut>(mut self, method: HttpMethod, path: &str, handler: H) -> Self
     where
         H: Fn(Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Response> + Send + 'static,
     {
-        let boxed: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
-        self.routes.push(GroupRoute {
-            method: GroupMethod::Get,
-            path: path.to_string(),
-            handler: Arc::new(boxed),
-        });
+        let route = RouteDefBuilder::new(method, intern(path), handler).into_group_route();
+        self.items.push(GroupItem::Route(route));
         self
     }
 
+    /// Register a GET route within the group
+    pub fn get<H, Fut>(self, path: &str, handler: H) -> Self
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.route(HttpMethod::Get, path, handler)
+    }
+
     /// Register a POST route within the group
-    pub fn post<H, Fut>(mut self, path: &str, handler: H) -> Self
+    pub fn post<H, Fut>(self, path: &str, handler: H) -> Self
     where
         H: Fn(Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Response> + Send + 'static,
     {
-        let boxed: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
-        self.routes.push(GroupRoute {
-            method: GroupMethod::Post,
-            path: path.to_string(),
-            handler: Arc::new(boxed),
-        });
-        self
+        self.route(HttpMethod::Post, path, handler)
     }
 
     /// Register a PUT route within the group
-    pub fn put<H, Fut>(mut self, path: &str, handler: H) -> Self
+    pub fn put<H, Fut>(self, path: &str, handler: H) -> Self
     where
         H: Fn(Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Response> + Send + 'static,
     {
-        let boxed: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
-        self.routes.push(GroupRoute {
-            method: GroupMethod::Put,
-            path: path.to_string(),
-            handler: Arc::new(boxed),
-        });
-        self
+        self.route(HttpMethod::Put, path, handler)
+    }
+
+    /// Register a PATCH route within the group
+    pub fn patch<H, Fut>(self, path: &str, handler: H) -> Self
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.route(HttpMethod::Patch, path, handler)
     }
 
     /// Register a DELETE route within the group
-    pub fn delete<H, Fut>(mut self, path: &str, handler: H) -> Self
+    pub fn delete<H, Fut>(self, path: &str, handler: H) -> Self
     where
         H: Fn(Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Response> + Send + 'static,
     {
-        let boxed: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
-        self.routes.push(GroupRoute {
-            method: GroupMethod::Delete,
-            path: path.to_string(),
-            handler: Arc::new(boxed),
-        });
+        self.route(HttpMethod::Delete, path, handler)
+    }
+
+    /// Name the most recently added route
+    ///
+    /// The name is combined with the name prefixes of the enclosing groups.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no route was added yet.
+    pub fn name(mut self, name: &str) -> Self {
+        self.last_route("name").name = Some(intern(name));
+        self
+    }
+
+    /// Apply middleware to the most recently added route
+    ///
+    /// # Panics
+    ///
+    /// Panics if no route was added yet.
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.last_route("middleware")
+            .middlewares
+            .push(into_boxed(middleware));
         self
     }
+
+    /// Describe the most recently added route, see `RouteDefBuilder::describe`
+    ///
+    /// # Panics
+    ///
+    /// Panics if no route was added yet.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.last_route("describe").docs.description = Some(description.to_string());
+        self
+    }
+
+    /// Tag the most recently added route
+    ///
+    /// # Panics
+    ///
+    /// Panics if no route was added yet.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.last_route("tag").docs.tags.push(tag.to_string());
+        self
+    }
+
+    /// Mark the most recently added route as deprecated
+    ///
+    /// # Panics
+    ///
+    /// Panics if no route was added yet.
+    pub fn deprecated(mut self) -> Self {
+        self.last_route("deprecated").docs.deprecated = true;
+        self
+    }
+
+    /// Add a nested group
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// Router::new().group("/admin", |r| {
+    ///     r.get("/dashboard", dashboard)
+    ///      .group("/users", |r| r.get("/", list_users).name("admin.users.index"))
+    /// })
+    /// ```
+    pub fn group<F>(mut self, prefix: &str, builder_fn: F) -> Self
+    where
+        F: FnOnce(GroupRouter) -> GroupRouter,
+    {
+        let group = group_def(prefix, builder_fn);
+        self.items.push(GroupItem::NestedGroup(Box::new(group)));
+        self
+    }
+
+    /// Only register the most recently added route in an environment
+    ///
+    /// Call it again to allow more environments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no route was added yet.
+    pub fn only_in(mut self, environment: Environment) -> Self {
+        self.last_route("only_in").environments.push(environment);
+        self
+    }
+
+    fn last_route(&mut self, method: &str) -> &mut GroupRoute {
+        match self.items.last_mut() {
+            Some(GroupItem::Route(route)) => route,
+            _ => panic!("GroupRouter::{}() must follow a route", method),
+        }
+    }
 }
 
 impl Router {
@@ -184,14 +318,9 @@ impl Router {
     where
         F: FnOnce(GroupRouter) -> GroupRouter,
     {
-        let inner = GroupRouter::new();
-        let built = builder_fn(inner);
-
         GroupBuilder {
             outer_router: self,
-            group_routes: built.routes,
-            prefix: prefix.to_string(),
-            middleware: Vec::new(),
+            group: group_def(prefix, builder_fn),
         }
     }
 }
@@ -212,3 +341,12 @@ impl RouteBuilder {
         self.router.group(prefix, builder_fn)
     }
 }
+
+fn group_def<F>(prefix: &str, builder_fn: F) -> GroupDef
+where
+    F: FnOnce(GroupRouter) -> GroupRouter,
+{
+    let mut group = GroupDef::__new_unchecked(intern(prefix));
+    group.items = builder_fn(GroupRouter::new()).items;
+    group
+}
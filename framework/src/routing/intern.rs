@@ -0,0 +1,36 @@
+//! Interned strings for route tables
+//!
+//! Route patterns, prefixes and names are `&'static str` so matched routes
+//! can hand them out without copying. Strings built at runtime are leaked
+//! once per distinct value, so rebuilding routers (in tests, or on reload)
+//! reuses them instead of leaking a copy each time.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+static STRINGS: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// A `'static` copy of `s`, shared by every call with the same contents
+pub(crate) fn intern(s: &str) -> &'static str {
+    let mut strings = STRINGS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(interned) = strings.get(s) {
+        return interned;
+    }
+    let interned: &'static str = Box::leak(s.to_string().into_boxed_str());
+    strings.insert(interned);
+    interned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_strings_share_one_allocation() {
+        let first = intern(&format!("/users/{}", "{id}"));
+        let second = intern(&String::from("/users/{id}"));
+        assert_eq!(first, "/users/{id}");
+        assert!(std::ptr::eq(first, second));
+        assert!(!std::ptr::eq(first, intern("/users/{user}")));
+    }
+}
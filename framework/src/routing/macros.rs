@@ -36,8 +36,12 @@ pub const fn validate_route_path(path: &'static str) -> &'static str {
     }
     path
 }
+use crate::config::{Config, Environment};
+use crate::http::{ErrorFormat, ErrorFormatMiddleware};
 use crate::middleware::{into_boxed, BoxedMiddleware, Middleware};
-use crate::routing::router::{register_route_name, BoxedHandler, Router};
+use crate::routing::intern::intern;
+use crate::routing::router::{BoxedHandler, RouteDocs, Router};
+use std::any::TypeId;
 use std::future::Future;
 use std::sync::Arc;
 
@@ -52,12 +56,17 @@ use std::sync::Arc;
 /// - `/users/:id` → `/users/{id}`
 /// - `/posts/:post_id/comments/:id` → `/posts/{post_id}/comments/{id}`
 /// - `/users/{id}` → `/users/{id}` (already correct syntax, unchanged)
-fn convert_route_params(path: &str) -> String {
+/// - `/posts/{post:slug}` → `/posts/{post:slug}` (binding column, unchanged)
+pub(crate) fn convert_route_params(path: &str) -> String {
     let mut result = String::with_capacity(path.len() + 4); // Extra space for braces
     let mut chars = path.chars().peekable();
+    let mut in_braces = false;
 
     while let Some(ch) = chars.next() {
-        if ch == ':' {
+        if ch == '{' || ch == '}' {
+            in_braces = ch == '{';
+            result.push(ch);
+        } else if ch == ':' && !in_braces {
             // Start of parameter - collect until '/' or end
             result.push('{');
             while let Some(&next) = chars.peek() {
@@ -80,6 +89,7 @@ pub enum HttpMethod {
     Get,
     Post,
     Put,
+    Patch,
     Delete,
 }
 
@@ -90,6 +100,8 @@ pub struct RouteDefBuilder<H> {
     handler: H,
     name: Option<&'static str>,
     middlewares: Vec<BoxedMiddleware>,
+    environments: Vec<Environment>,
+    docs: RouteDocs,
 }
 
 impl<H, Fut> RouteDefBuilder<H>
@@ -105,6 +117,8 @@ where
             handler,
             name: None,
             middlewares: Vec::new(),
+            environments: Vec::new(),
+            docs: RouteDocs::default(),
         }
     }
 
@@ -120,8 +134,63 @@ where
         self
     }
 
+    /// Only register this route in the given environment
+    ///
+    /// Call it again to allow more environments. The environment is checked
+    /// when the routes are registered, using `Config::environment()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// get!("/mail/preview", controllers::mail::preview).only_in(Environment::Local)
+    /// ```
+    pub fn only_in(mut self, environment: Environment) -> Self {
+        self.environments.push(environment);
+        self
+    }
+
+    /// Describe what this route does
+    ///
+    /// The description is shown by `routes:list` and becomes the doc comment
+    /// of the route's generated TypeScript helper.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// post!("/users", controllers::user::store)
+    ///     .describe("Create a user")
+    ///     .tag("users")
+    /// ```
+    pub fn describe(mut self, description: &str) -> Self {
+        self.docs.description = Some(description.to_string());
+        self
+    }
+
+    /// Tag this route, e.g. with the resource it belongs to
+    ///
+    /// Call it again to add more tags.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.docs.tags.push(tag.to_string());
+        self
+    }
+
+    /// Mark this route as deprecated
+    ///
+    /// The route keeps working; `routes:list` and the generated TypeScript
+    /// helper flag it.
+    pub fn deprecated(mut self) -> Self {
+        self.docs.deprecated = true;
+        self
+    }
+
     /// Register this route definition with a router
+    ///
+    /// Routes limited with `only_in` to other environments are skipped.
     pub fn register(self, router: Router) -> Router {
+        if !enabled_in_current_environment(&self.environments) {
+            return router;
+        }
+
         // Convert :param to {param} for matchit compatibility
         let converted_path = convert_route_params(self.path);
 
@@ -130,6 +199,7 @@ where
             HttpMethod::Get => router.get(&converted_path, self.handler),
             HttpMethod::Post => router.post(&converted_path, self.handler),
             HttpMethod::Put => router.put(&converted_path, self.handler),
+            HttpMethod::Patch => router.patch(&converted_path, self.handler),
             HttpMethod::Delete => router.delete(&converted_path, self.handler),
         };
 
@@ -140,11 +210,13 @@ where
             .fold(builder, |b, m| b.middleware_boxed(m));
 
         // Apply name if present, otherwise convert to Router
-        if let Some(name) = self.name {
-            builder.name(name)
+        let mut router: Router = if let Some(name) = self.name {
+            builder.name(name).into()
         } else {
             builder.into()
-        }
+        };
+        router.document_route(&converted_path, self.docs);
+        router
     }
 }
 
@@ -232,6 +304,34 @@ where
     RouteDefBuilder::new(HttpMethod::Put, path, handler)
 }
 
+/// Create a PATCH route definition with compile-time path validation
+///
+/// # Example
+/// ```rust,ignore
+/// patch!("/users/{id}", controllers::user::update).name("users.update")
+/// ```
+///
+/// # Compile Error
+///
+/// Fails to compile if path doesn't start with '/'.
+#[macro_export]
+macro_rules! patch {
+    ($path:expr, $handler:expr) => {{
+        const _: &str = $crate::validate_route_path($path);
+        $crate::__patch_impl($path, $handler)
+    }};
+}
+
+/// Internal implementation for PATCH routes (used by the patch! macro)
+#[doc(hidden)]
+pub fn __patch_impl<H, Fut>(path: &'static str, handler: H) -> RouteDefBuilder<H>
+where
+    H: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Response> + Send + 'static,
+{
+    RouteDefBuilder::new(HttpMethod::Patch, path, handler)
+}
+
 /// Create a DELETE route definition with compile-time path validation
 ///
 /// # Example
@@ -260,6 +360,27 @@ where
     RouteDefBuilder::new(HttpMethod::Delete, path, handler)
 }
 
+/// Create a WebSocket route definition with compile-time path validation
+///
+/// The handler is an async function taking a `WebSocket`. Route middleware
+/// runs on the upgrade request, so it can reject the connection.
+///
+/// # Example
+/// ```rust,ignore
+/// ws!("/chat", controllers::chat::connect).middleware(AuthMiddleware)
+/// ```
+///
+/// # Compile Error
+///
+/// Fails to compile if path doesn't start with '/'.
+#[macro_export]
+macro_rules! ws {
+    ($path:expr, $handler:expr) => {{
+        const _: &str = $crate::validate_route_path($path);
+        $crate::__get_impl($path, $crate::websocket::__upgrade_handler($handler))
+    }};
+}
+
 // ============================================================================
 // Fallback Route Support
 // ============================================================================
@@ -356,8 +477,10 @@ pub struct GroupRoute {
     method: HttpMethod,
     path: &'static str,
     handler: Arc<BoxedHandler>,
-    name: Option<&'static str>,
-    middlewares: Vec<BoxedMiddleware>,
+    pub(crate) name: Option<&'static str>,
+    pub(crate) middlewares: Vec<BoxedMiddleware>,
+    pub(crate) environments: Vec<Environment>,
+    pub(crate) docs: RouteDocs,
 }
 
 /// An item that can be added to a route group - either a route or a nested group
@@ -391,8 +514,12 @@ pub trait IntoGroupItem {
 /// ```
 pub struct GroupDef {
     prefix: &'static str,
-    items: Vec<GroupItem>,
-    group_middlewares: Vec<BoxedMiddleware>,
+    name_prefix: &'static str,
+    pub(crate) items: Vec<GroupItem>,
+    group_middlewares: Vec<(TypeId, BoxedMiddleware)>,
+    without_middlewares: Vec<TypeId>,
+    error_format: Option<ErrorFormat>,
+    environments: Vec<Environment>,
 }
 
 impl GroupDef {
@@ -403,8 +530,12 @@ impl GroupDef {
     pub fn __new_unchecked(prefix: &'static str) -> Self {
         Self {
             prefix,
+            name_prefix: "",
             items: Vec::new(),
             group_middlewares: Vec::new(),
+            without_middlewares: Vec::new(),
+            error_format: None,
+            environments: Vec::new(),
         }
     }
 
@@ -441,7 +572,79 @@ impl GroupDef {
     /// }).middleware(AuthMiddleware).middleware(RateLimitMiddleware)
     /// ```
     pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
-        self.group_middlewares.push(into_boxed(middleware));
+        self.group_middlewares
+            .push((TypeId::of::<M>(), into_boxed(middleware)));
+        self
+    }
+
+    /// Skip a middleware inherited from the server or parent groups
+    ///
+    /// Applies to global middleware and to middleware added by parent groups
+    /// with `.middleware()`. Nested groups skip it too, unless they add it
+    /// back themselves.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// group!("/health", {
+    ///     get!("/", controllers::health::show),
+    /// }).without_middleware::<LoggingMiddleware>()
+    /// ```
+    pub fn without_middleware<M: Middleware + 'static>(mut self) -> Self {
+        self.without_middlewares.push(TypeId::of::<M>());
+        self
+    }
+
+    /// Choose how errors returned by routes in this group are rendered
+    ///
+    /// Without it, errors are rendered as JSON. Nested groups inherit the
+    /// format unless they set their own.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// group!("/api", {
+    ///     get!("/users", controllers::api::users::index),
+    /// }).error_format(ErrorFormat::Json)
+    /// ```
+    pub fn error_format(mut self, format: ErrorFormat) -> Self {
+        self.error_format = Some(format);
+        self
+    }
+
+    /// Prefix the names of all routes in this group
+    ///
+    /// Prefixes of nested groups are combined, outermost first.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// group!("/admin", {
+    ///     group!("/users", {
+    ///         get!("/", controllers::admin::users::index).name("index"),
+    ///     }).name_prefix("users."),
+    /// }).name_prefix("admin.")  // -> GET /admin/users named "admin.users.index"
+    /// ```
+    pub fn name_prefix(mut self, prefix: &'static str) -> Self {
+        self.name_prefix = prefix;
+        self
+    }
+
+    /// Only register the routes in this group in the given environment
+    ///
+    /// Call it again to allow more environments. Nested groups and routes
+    /// can narrow it further with their own `only_in`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// group!("/debug", {
+    ///     get!("/config", controllers::debug::config),
+    ///     get!("/session", controllers::debug::session),
+    /// }).only_in(Environment::Local).only_in(Environment::Development)
+    /// ```
+    pub fn only_in(mut self, environment: Environment) -> Self {
+        self.environments.push(environment);
         self
     }
 
@@ -454,54 +657,85 @@ impl GroupDef {
     /// # Path Combination
     ///
     /// - If route path is "/" (root), the full path is just the group prefix
-    /// - Otherwise, prefix and route path are concatenated
+    /// - Otherwise, prefix and route path are concatenated, so `group!("/", ...)`
+    ///   adds no prefix at all
     ///
     /// # Middleware Inheritance
     ///
     /// Parent group middleware is applied before child group middleware,
-    /// which is applied before route-specific middleware.
+    /// which is applied before route-specific middleware. Middleware skipped
+    /// with `without_middleware` is left out of the inherited list and
+    /// recorded on the router so the server skips it among global middleware.
     pub fn register(self, mut router: Router) -> Router {
-        self.register_with_inherited(&mut router, "", &[]);
+        self.register_with_inherited(&mut router, "", "", &[], &[]);
         router
     }
 
-    /// Internal recursive registration with inherited prefix and middleware
+    /// Internal recursive registration with inherited prefixes and middleware
     fn register_with_inherited(
         self,
         router: &mut Router,
         parent_prefix: &str,
-        inherited_middleware: &[BoxedMiddleware],
+        parent_name_prefix: &str,
+        inherited_middleware: &[(TypeId, BoxedMiddleware)],
+        inherited_without: &[TypeId],
     ) {
-        // Build the full prefix for this group
-        let full_prefix = if parent_prefix.is_empty() {
-            self.prefix.to_string()
+        if !enabled_in_current_environment(&self.environments) {
+            return;
+        }
+
+        // Build the full path and name prefixes for this group. A prefix or
+        // path without a leading '/' is kept as written instead of being
+        // glued on, so the router reports it.
+        let full_prefix = if self.prefix.starts_with('/') || self.prefix.is_empty() {
+            join_paths(parent_prefix, self.prefix)
         } else {
-            format!("{}{}", parent_prefix, self.prefix)
+            self.prefix.to_string()
         };
+        let full_name_prefix = format!("{}{}", parent_name_prefix, self.name_prefix);
 
         // Combine inherited middleware with this group's middleware
-        // Parent middleware runs first (outer), then this group's middleware
-        let combined_middleware: Vec<BoxedMiddleware> = inherited_middleware
+        // Parent middleware runs first (outer), then this group's error
+        // format, then this group's middleware
+        let error_format = self.error_format.map(|format| {
+            (
+                TypeId::of::<ErrorFormatMiddleware>(),
+                into_boxed(ErrorFormatMiddleware(format)),
+            )
+        });
+        let combined_middleware: Vec<(TypeId, BoxedMiddleware)> = inherited_middleware
             .iter()
+            .filter(|(type_id, _)| !self.without_middlewares.contains(type_id))
             .cloned()
+            .chain(error_format)
             .chain(self.group_middlewares.iter().cloned())
             .collect();
+        let without: Vec<TypeId> = inherited_without
+            .iter()
+            .chain(&self.without_middlewares)
+            .copied()
+            .collect();
 
         for item in self.items {
             match item {
                 GroupItem::Route(route) => {
+                    if !enabled_in_current_environment(&route.environments) {
+                        continue;
+                    }
+
                     // Convert :param to {param} for matchit compatibility
                     let converted_route_path = convert_route_params(route.path);
 
                     // Build full path with prefix
-                    // If route path is "/" (root), just use the prefix without trailing slash
-                    let full_path = if converted_route_path == "/" {
-                        full_prefix.clone()
+                    let full_path = if converted_route_path.starts_with('/')
+                        || converted_route_path.is_empty()
+                    {
+                        join_paths(&full_prefix, &converted_route_path)
                     } else {
-                        format!("{}{}", full_prefix, converted_route_path)
+                        converted_route_path
                     };
-                    // We need to leak the string to get a 'static str for the router
-                    let full_path: &'static str = Box::leak(full_path.into_boxed_str());
+                    // The router keeps 'static patterns; interning reuses them across rebuilds
+                    let full_path = intern(&full_path);
 
                     // Register the route with the router
                     match route.method {
@@ -514,6 +748,9 @@ impl GroupDef {
                         HttpMethod::Put => {
                             router.insert_put(full_path, route.handler);
                         }
+                        HttpMethod::Patch => {
+                            router.insert_patch(full_path, route.handler);
+                        }
                         HttpMethod::Delete => {
                             router.insert_delete(full_path, route.handler);
                         }
@@ -521,26 +758,60 @@ impl GroupDef {
 
                     // Register route name if present
                     if let Some(name) = route.name {
-                        register_route_name(name, full_path);
+                        router.name_route(full_path, &format!("{}{}", full_name_prefix, name));
                     }
+                    router.document_route(full_path, route.docs);
 
                     // Apply combined middleware (inherited + group), then route-specific
-                    for mw in &combined_middleware {
+                    for (_, mw) in &combined_middleware {
                         router.add_middleware(full_path, mw.clone());
                     }
+                    for type_id in &without {
+                        router.exclude_middleware(full_path, *type_id);
+                    }
                     for mw in route.middlewares {
                         router.add_middleware(full_path, mw);
                     }
                 }
                 GroupItem::NestedGroup(nested) => {
                     // Recursively register the nested group with accumulated prefix and middleware
-                    nested.register_with_inherited(router, &full_prefix, &combined_middleware);
+                    nested.register_with_inherited(
+                        router,
+                        &full_prefix,
+                        &full_name_prefix,
+                        &combined_middleware,
+                        &without,
+                    );
                 }
             }
         }
     }
 }
 
+/// Whether a route or group limited to `environments` is registered
+///
+/// An empty list means every environment.
+fn enabled_in_current_environment(environments: &[Environment]) -> bool {
+    environments.is_empty() || environments.contains(&Config::environment())
+}
+
+/// Join a group prefix and a route path
+///
+/// A route path of "/" maps to the prefix itself, and a trailing slash on the
+/// prefix is dropped so that `/` + `/login` is `/login` rather than `//login`.
+fn join_paths(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    if path == "/" || path.is_empty() {
+        if prefix.is_empty() {
+            "/".to_string()
+        } else {
+            prefix.to_string()
+        }
+    } else {
+        format!("{}{}", prefix, path)
+    }
+}
+
 impl<H, Fut> RouteDefBuilder<H>
 where
     H: Fn(Request) -> Fut + Send + Sync + 'static,
@@ -558,6 +829,8 @@ where
             handler: Arc::new(boxed),
             name: self.name,
             middlewares: self.middlewares,
+            environments: self.environments,
+            docs: self.docs,
         }
     }
 }
@@ -614,6 +887,13 @@ impl IntoGroupItem for GroupDef {
 /// Middleware applied to a parent group is automatically inherited by all nested groups.
 /// The execution order is: parent middleware -> child middleware -> route middleware.
 ///
+/// # Name Prefixes
+///
+/// `.name_prefix("admin.")` prefixes the names of all routes in the group,
+/// including nested groups, so `.name("index")` inside
+/// `group!("/users", { ... }).name_prefix("users.")` nested in an `admin.`
+/// group is registered as `admin.users.index`.
+///
 /// # Compile Error
 ///
 /// Fails to compile if prefix doesn't start with '/'.
@@ -636,7 +916,7 @@ macro_rules! group {
 ///
 /// # Example
 /// ```rust,ignore
-/// use kit::{routes, get, post, put, delete};
+/// use kit::{routes, get, post, put, patch, delete};
 /// use crate::controllers;
 /// use crate::middleware::AuthMiddleware;
 ///
@@ -646,6 +926,7 @@ macro_rules! group {
 ///     get!("/users/{id}", controllers::user::show).name("users.show"),
 ///     post!("/users", controllers::user::store).name("users.store"),
 ///     put!("/users/{id}", controllers::user::update).name("users.update"),
+///     patch!("/users/{id}/email", controllers::user::update_email).name("users.email.update"),
 ///     delete!("/users/{id}", controllers::user::destroy).name("users.destroy"),
 ///     get!("/protected", controllers::home::index).middleware(AuthMiddleware),
 /// }
@@ -693,6 +974,12 @@ mod tests {
 
         // Parameter at the end
         assert_eq!(convert_route_params("/api/v1/:version"), "/api/v1/{version}");
+
+        // Binding columns inside braces are kept
+        assert_eq!(
+            convert_route_params("/posts/{post:slug}/comments/:id"),
+            "/posts/{post:slug}/comments/{id}"
+        );
     }
 
     // Helper for creating test handlers
@@ -783,4 +1070,185 @@ mod tests {
         assert_eq!(group.items.len(), 1);
         matches!(&group.items[0], GroupItem::Route(_));
     }
+
+    struct Noop;
+
+    #[async_trait::async_trait]
+    impl Middleware for Noop {
+        async fn handle(&self, request: Request, next: crate::middleware::Next) -> Response {
+            next(request).await
+        }
+    }
+
+    #[test]
+    fn test_join_paths() {
+        assert_eq!(join_paths("", "/"), "/");
+        assert_eq!(join_paths("/", "/"), "/");
+        assert_eq!(join_paths("/", "/login"), "/login");
+        assert_eq!(join_paths("/users", "/"), "/users");
+        assert_eq!(join_paths("/admin/", "/users"), "/admin/users");
+    }
+
+    #[test]
+    fn test_nested_group_paths_and_name_prefixes() {
+        let users = GroupDef::__new_unchecked("/users")
+            .add(RouteDefBuilder::new(HttpMethod::Get, "/", test_handler).name("index"))
+            .add(RouteDefBuilder::new(HttpMethod::Get, "/{id}", test_handler).name("show"))
+            .name_prefix("users.");
+        let admin = GroupDef::__new_unchecked("/nested-admin")
+            .add(users)
+            .add(RouteDefBuilder::new(
+                HttpMethod::Post,
+                "/logout",
+                test_handler,
+            ))
+            .name_prefix("nested_admin.")
+            .middleware(Noop);
+
+        let router = admin.register(Router::new());
+        let routes: Vec<(&str, &str, Option<String>, usize)> = router
+            .routes()
+            .into_iter()
+            .map(|r| (r.method, r.pattern, r.name, r.middleware))
+            .collect();
+
+        assert_eq!(
+            routes,
+            [
+                (
+                    "GET",
+                    "/nested-admin/users",
+                    Some("nested_admin.users.index".to_string()),
+                    1
+                ),
+                (
+                    "GET",
+                    "/nested-admin/users/{id}",
+                    Some("nested_admin.users.show".to_string()),
+                    1
+                ),
+                ("POST", "/nested-admin/logout", None, 1),
+            ]
+        );
+        assert_eq!(
+            crate::routing::route("nested_admin.users.show", &[("id", "5")]),
+            Some("/nested-admin/users/5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_route_docs_are_listed() {
+        let router = RouteDefBuilder::new(HttpMethod::Post, "/docs-users", test_handler)
+            .describe("Create a user")
+            .tag("users")
+            .tag("admin")
+            .register(Router::new());
+        let router = GroupDef::__new_unchecked("/docs-v1")
+            .add(RouteDefBuilder::new(HttpMethod::Get, "/users", test_handler).deprecated())
+            .register(router);
+
+        let routes = router.routes();
+        assert_eq!(routes[0].description.as_deref(), Some("Create a user"));
+        assert_eq!(routes[0].tags, ["users", "admin"]);
+        assert!(!routes[0].deprecated);
+        assert_eq!(routes[1].pattern, "/docs-v1/users");
+        assert_eq!(routes[1].description, None);
+        assert!(routes[1].deprecated);
+    }
+
+    #[test]
+    fn test_patch_routes() {
+        let router = crate::patch!("/patch-test/{id}", test_handler).register(Router::new());
+        let router = GroupDef::__new_unchecked("/patch-group")
+            .add(crate::patch!("/{id}", test_handler))
+            .register(router);
+
+        for path in ["/patch-test/5", "/patch-group/5"] {
+            assert!(router.find(&hyper::Method::PATCH, path).is_some());
+            assert!(router.find(&hyper::Method::PUT, path).is_none());
+        }
+        assert_eq!(router.routes()[0].method, "PATCH");
+    }
+
+    #[test]
+    fn test_only_in_skips_routes_for_other_environments() {
+        let current = Config::environment();
+        let other = if current == Environment::Production {
+            Environment::Local
+        } else {
+            Environment::Production
+        };
+
+        let debug = GroupDef::__new_unchecked("/only-in-debug")
+            .add(RouteDefBuilder::new(
+                HttpMethod::Get,
+                "/config",
+                test_handler,
+            ))
+            .only_in(other.clone());
+        let tools = GroupDef::__new_unchecked("/only-in-tools")
+            .add(
+                RouteDefBuilder::new(HttpMethod::Get, "/mail", test_handler).only_in(other.clone()),
+            )
+            .add(
+                RouteDefBuilder::new(HttpMethod::Get, "/jobs", test_handler)
+                    .only_in(other.clone())
+                    .only_in(current.clone()),
+            );
+        let router = RouteDefBuilder::new(HttpMethod::Get, "/only-in-preview", test_handler)
+            .only_in(other)
+            .register(Router::new());
+        let router = debug.register(router);
+        let router = tools.register(router);
+
+        let patterns: Vec<&str> = router.routes().into_iter().map(|r| r.pattern).collect();
+        assert_eq!(patterns, ["/only-in-tools/jobs"]);
+    }
+
+    struct Other;
+
+    #[async_trait::async_trait]
+    impl Middleware for Other {
+        async fn handle(&self, request: Request, next: crate::middleware::Next) -> Response {
+            next(request).await
+        }
+    }
+
+    #[test]
+    fn test_without_middleware_skips_inherited_middleware() {
+        let health = GroupDef::__new_unchecked("/health")
+            .add(RouteDefBuilder::new(HttpMethod::Get, "/", test_handler))
+            .without_middleware::<Noop>();
+        let api = GroupDef::__new_unchecked("/skip-api")
+            .add(health)
+            .add(RouteDefBuilder::new(HttpMethod::Get, "/users", test_handler))
+            .middleware(Noop)
+            .middleware(Other);
+
+        let router = api.register(Router::new());
+        let middleware: Vec<(&str, usize)> = router
+            .routes()
+            .into_iter()
+            .map(|r| (r.pattern, r.middleware))
+            .collect();
+
+        assert_eq!(middleware, [("/skip-api/health", 1), ("/skip-api/users", 2)]);
+        assert_eq!(
+            router.get_excluded_middleware("/skip-api/health"),
+            [TypeId::of::<Noop>()]
+        );
+        assert!(router.get_excluded_middleware("/skip-api/users").is_empty());
+    }
+
+    #[test]
+    fn test_error_format_runs_outside_group_middleware() {
+        let group = GroupDef::__new_unchecked("/format-web")
+            .add(RouteDefBuilder::new(HttpMethod::Get, "/", test_handler))
+            .middleware(Noop)
+            .error_format(ErrorFormat::Inertia);
+
+        let router = group.register(Router::new());
+
+        assert_eq!(router.get_route_middleware("/format-web").len(), 2);
+    }
 }
@@ -1,14 +1,19 @@
+mod error;
 mod group;
+pub(crate) mod intern;
 mod macros;
 mod router;
+pub(crate) mod static_files;
 
+pub use error::{RouteError, RouteErrors};
 pub use group::{GroupBuilder, GroupRouter};
 pub use macros::{
     // Internal functions used by macros (hidden from docs)
-    __delete_impl, __fallback_impl, __get_impl, __post_impl, __put_impl, validate_route_path,
-    FallbackDefBuilder, GroupDef, GroupItem, GroupRoute, HttpMethod, IntoGroupItem,
-    RouteDefBuilder,
+    __delete_impl, __fallback_impl, __get_impl, __patch_impl, __post_impl, __put_impl,
+    validate_route_path, FallbackDefBuilder, GroupDef, GroupItem, GroupRoute, HttpMethod,
+    IntoGroupItem, RouteDefBuilder,
 };
 pub use router::{
-    register_route_name, route, route_with_params, BoxedHandler, RouteBuilder, Router,
+    register_route_name, route, route_name, route_with_params, BoxedHandler, RouteBuilder,
+    RouteInfo, RouteMatch, Router,
 };
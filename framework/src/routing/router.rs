@@ -1,9 +1,19 @@
 use crate::http::{Request, Response};
-use crate::middleware::{into_boxed, BoxedMiddleware, Middleware};
+use crate::middleware::{
+    into_boxed, BoxedMiddleware, Middleware, MiddlewareChain, MiddlewareRegistry, Next,
+};
+use crate::routing::error::{RouteError, RouteErrors};
+use crate::routing::intern::intern;
+use crate::routing::macros::convert_route_params;
+use crate::routing::static_files;
+use arc_swap::ArcSwapOption;
 use matchit::Router as MatchitRouter;
+use std::any::TypeId;
 use std::collections::HashMap;
 use std::future::Future;
+use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock, RwLock};
 
 /// Global registry mapping route names to path patterns
@@ -38,11 +48,38 @@ pub fn route(name: &str, params: &[(&str, &str)]) -> Option<String> {
 
     let mut url = path_pattern.clone();
     for (key, value) in params {
-        url = url.replace(&format!("{{{}}}", key), value);
+        url = fill_route_param(&url, key, value);
     }
     Some(url)
 }
 
+/// Replace `{key}` and `{key:column}` placeholders with `value`
+fn fill_route_param(pattern: &str, key: &str, value: &str) -> String {
+    let url = pattern.replace(&format!("{{{}}}", key), value);
+    let keyed = format!("{{{}:", key);
+    let mut rest = url.as_str();
+    let mut filled = String::with_capacity(url.len());
+    while let Some(start) = rest.find(&keyed) {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        filled.push_str(&rest[..start]);
+        filled.push_str(value);
+        rest = &rest[start + len + 1..];
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Find the name of a route by its path pattern
+pub fn route_name(pattern: &str) -> Option<String> {
+    let registry = ROUTE_REGISTRY.get()?.read().ok()?;
+    registry
+        .iter()
+        .find(|(_, path)| path.as_str() == pattern)
+        .map(|(name, _)| name.clone())
+}
+
 /// Generate URL with HashMap parameters (used internally by Redirect)
 pub fn route_with_params(name: &str, params: &HashMap<String, String>) -> Option<String> {
     let registry = ROUTE_REGISTRY.get()?.read().ok()?;
@@ -50,7 +87,7 @@ pub fn route_with_params(name: &str, params: &HashMap<String, String>) -> Option
 
     let mut url = path_pattern.clone();
     for (key, value) in params {
-        url = url.replace(&format!("{{{}}}", key), value);
+        url = fill_route_param(&url, key, value);
     }
     Some(url)
 }
@@ -61,25 +98,192 @@ enum Method {
     Get,
     Post,
     Put,
+    Patch,
     Delete,
 }
 
+impl Method {
+    fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+        }
+    }
+}
+
+/// A registered route, as shown by `routes:list`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteInfo {
+    pub method: &'static str,
+    /// Full path pattern, including group prefixes
+    pub pattern: &'static str,
+    /// Route name, including group name prefixes
+    pub name: Option<String>,
+    /// Number of route and group middleware (global middleware not included)
+    pub middleware: usize,
+    /// Summary given with `.describe()`
+    pub description: Option<String>,
+    /// Tags given with `.tag()`, in order
+    pub tags: Vec<String>,
+    /// Whether the route was marked with `.deprecated()`
+    pub deprecated: bool,
+}
+
+/// Documentation annotations collected by route builders
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RouteDocs {
+    pub(crate) description: Option<String>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) deprecated: bool,
+}
+
 /// Type alias for route handlers
 pub type BoxedHandler =
     Box<dyn Fn(Request) -> Pin<Box<dyn Future<Output = Response> + Send>> + Send + Sync>;
 
+/// A composed chain, empty until finalized and cleared when its middleware change
+type ChainCell = Arc<ArcSwapOption<Next>>;
+
+/// A route stored in the radix tree
+///
+/// The pattern and its parameter names are computed once at registration,
+/// so matching a request only allocates for the parameter values.
+struct RouteEntry {
+    handler: Arc<BoxedHandler>,
+    pattern: &'static str,
+    param_names: Box<[&'static str]>,
+    binding_keys: Arc<[(&'static str, &'static str)]>,
+    /// The handler wrapped in all of its middleware, set by `Router::finalize`
+    chain: ChainCell,
+}
+
+impl RouteEntry {
+    fn new(pattern: &str, handler: Arc<BoxedHandler>) -> Self {
+        let pattern = intern(pattern);
+        Self {
+            handler,
+            pattern,
+            param_names: param_names(pattern),
+            binding_keys: binding_keys(pattern).into(),
+            chain: Arc::new(ArcSwapOption::empty()),
+        }
+    }
+}
+
+/// A registered route's handler and the slot for its composed chain
+struct ChainSlot {
+    pattern: &'static str,
+    handler: Arc<BoxedHandler>,
+    chain: ChainCell,
+}
+
+/// Extract parameter names from a route pattern, e.g. `/users/{id}` -> `["id"]`
+fn param_names(pattern: &'static str) -> Box<[&'static str]> {
+    let mut names = Vec::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[start + 1..start + len];
+        let name = placeholder.split(':').next().unwrap_or(placeholder);
+        names.push(name.trim_start_matches('*'));
+        rest = &rest[start + len + 1..];
+    }
+    names.into_boxed_slice()
+}
+
+/// Extract route binding columns, e.g. `/posts/{post:slug}` -> `[("post", "slug")]`
+fn binding_keys(pattern: &'static str) -> Box<[(&'static str, &'static str)]> {
+    let mut keys = Vec::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        if let Some((name, column)) = rest[start + 1..start + len].split_once(':') {
+            keys.push((name, column));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    keys.into_boxed_slice()
+}
+
+/// Why `path` can't be registered, for problems matchit doesn't catch
+fn invalid_path(path: &str) -> Option<String> {
+    if !path.starts_with('/') {
+        return Some("path must start with '/'".to_string());
+    }
+    let names = param_names(intern(path));
+    names
+        .iter()
+        .enumerate()
+        .find(|(i, name)| names[..*i].contains(name))
+        .map(|(_, name)| format!("parameter {{{}}} appears more than once", name))
+}
+
+/// The pattern handed to matchit, without binding columns
+fn matcher_path(pattern: &str) -> String {
+    let mut path = String::with_capacity(pattern.len());
+    let mut in_param = false;
+    let mut skipping = false;
+    for c in pattern.chars() {
+        match c {
+            '{' => in_param = true,
+            '}' => {
+                in_param = false;
+                skipping = false;
+            }
+            ':' if in_param => skipping = true,
+            _ => {}
+        }
+        if !skipping {
+            path.push(c);
+        }
+    }
+    path
+}
+
+/// A matched route with its extracted parameters
+pub struct RouteMatch {
+    pub handler: Arc<BoxedHandler>,
+    /// The registered pattern that matched, e.g. `/users/{id}`
+    pub pattern: &'static str,
+    pub params: HashMap<String, String>,
+    /// Columns route parameters bind by, from `{post:slug}` placeholders
+    pub binding_keys: Arc<[(&'static str, &'static str)]>,
+    /// The handler wrapped in all of its middleware, once the router is finalized
+    pub(crate) chain: Option<Next>,
+}
+
 /// HTTP Router with Laravel-like route registration
 pub struct Router {
-    get_routes: MatchitRouter<Arc<BoxedHandler>>,
-    post_routes: MatchitRouter<Arc<BoxedHandler>>,
-    put_routes: MatchitRouter<Arc<BoxedHandler>>,
-    delete_routes: MatchitRouter<Arc<BoxedHandler>>,
-    /// Middleware assignments: path -> boxed middleware instances
+    get_routes: MatchitRouter<RouteEntry>,
+    post_routes: MatchitRouter<RouteEntry>,
+    put_routes: MatchitRouter<RouteEntry>,
+    patch_routes: MatchitRouter<RouteEntry>,
+    delete_routes: MatchitRouter<RouteEntry>,
+    /// Middleware assignments: route pattern -> boxed middleware instances
     route_middleware: HashMap<String, Vec<BoxedMiddleware>>,
+    /// Global middleware types skipped per route pattern (see `GroupDef::without_middleware`)
+    excluded_middleware: HashMap<String, Vec<TypeId>>,
     /// Fallback handler for when no routes match (overrides default 404)
     fallback_handler: Option<Arc<BoxedHandler>>,
     /// Middleware for the fallback route
     fallback_middleware: Vec<BoxedMiddleware>,
+    /// The fallback handler wrapped in all of its middleware
+    fallback_chain: ArcSwapOption<Next>,
+    /// Registered routes in registration order
+    routes: Vec<RouteInfo>,
+    /// Every registered handler, for `finalize` to compose
+    chains: Vec<ChainSlot>,
+    /// Whether `finalize` has run, so middleware changes clear stale chains
+    finalized: AtomicBool,
+    /// Routes that couldn't be registered, reported by `try_build`
+    errors: Vec<RouteError>,
 }
 
 impl Router {
@@ -88,14 +292,137 @@ impl Router {
             get_routes: MatchitRouter::new(),
             post_routes: MatchitRouter::new(),
             put_routes: MatchitRouter::new(),
+            patch_routes: MatchitRouter::new(),
             delete_routes: MatchitRouter::new(),
             route_middleware: HashMap::new(),
+            excluded_middleware: HashMap::new(),
             fallback_handler: None,
             fallback_middleware: Vec::new(),
+            fallback_chain: ArcSwapOption::empty(),
+            routes: Vec::new(),
+            chains: Vec::new(),
+            finalized: AtomicBool::new(false),
+            errors: Vec::new(),
+        }
+    }
+
+    /// All registered routes in registration order
+    pub fn routes(&self) -> Vec<RouteInfo> {
+        self.routes
+            .iter()
+            .map(|route| RouteInfo {
+                middleware: self.route_middleware.get(route.pattern).map_or(0, Vec::len),
+                ..route.clone()
+            })
+            .collect()
+    }
+
+    fn insert(&mut self, method: Method, path: &str, handler: Arc<BoxedHandler>) {
+        let route = format!("{} {}", method.as_str(), path);
+        if let Some(reason) = invalid_path(path) {
+            self.errors.push(RouteError::InvalidPath { route, reason });
+            return;
+        }
+
+        let entry = RouteEntry::new(path, handler);
+        let pattern = entry.pattern;
+        let slot = ChainSlot {
+            pattern,
+            handler: entry.handler.clone(),
+            chain: entry.chain.clone(),
+        };
+        let routes = match method {
+            Method::Get => &mut self.get_routes,
+            Method::Post => &mut self.post_routes,
+            Method::Put => &mut self.put_routes,
+            Method::Patch => &mut self.patch_routes,
+            Method::Delete => &mut self.delete_routes,
+        };
+        let matcher = matcher_path(path);
+        match routes.insert(matcher.clone(), entry) {
+            Ok(()) => {
+                self.chains.push(slot);
+                self.routes.push(RouteInfo {
+                    method: method.as_str(),
+                    pattern,
+                    name: None,
+                    middleware: 0,
+                    description: None,
+                    tags: Vec::new(),
+                    deprecated: false,
+                });
+            }
+            Err(matchit::InsertError::Conflict { with }) if with == matcher => {
+                self.errors.push(RouteError::Duplicate { route });
+            }
+            Err(matchit::InsertError::Conflict { with }) => {
+                let existing = format!("{} {}", method.as_str(), with);
+                self.errors.push(RouteError::Conflict { route, existing });
+            }
+            Err(err) => {
+                let reason = err.to_string();
+                self.errors.push(RouteError::InvalidPath { route, reason });
+            }
+        }
+    }
+
+    /// Check that every route was registered, reporting all problems at once
+    ///
+    /// Registering a malformed path, the same route twice, overlapping
+    /// patterns or one name for different paths doesn't panic; the route is
+    /// left out and the problem kept for this report. The server refuses to
+    /// start when it isn't empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let router = routes::register().try_build().unwrap_or_else(|errors| {
+    ///     eprintln!("{}", errors);
+    ///     std::process::exit(1);
+    /// });
+    /// ```
+    pub fn try_build(self) -> Result<Router, RouteErrors> {
+        if self.errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(RouteErrors::new(self.errors))
+        }
+    }
+
+    /// Name the most recently registered route for `path` (internal use)
+    pub(crate) fn name_route(&mut self, path: &str, name: &str) {
+        let taken = self
+            .routes
+            .iter()
+            .find(|r| r.name.as_deref() == Some(name) && r.pattern != path);
+        if let Some(first) = taken {
+            let second = self.routes.iter().rev().find(|r| r.pattern == path);
+            self.errors.push(RouteError::DuplicateName {
+                name: name.to_string(),
+                first: format!("{} {}", first.method, first.pattern),
+                second: second.map_or_else(
+                    || path.to_string(),
+                    |second| format!("{} {}", second.method, second.pattern),
+                ),
+            });
+            return;
+        }
+        register_route_name(name, path);
+        if let Some(route) = self.routes.iter_mut().rev().find(|r| r.pattern == path) {
+            route.name = Some(name.to_string());
         }
     }
 
-    /// Get middleware for a specific route path
+    /// Attach documentation to the most recently registered route for `path` (internal use)
+    pub(crate) fn document_route(&mut self, path: &str, docs: RouteDocs) {
+        if let Some(route) = self.routes.iter_mut().rev().find(|r| r.pattern == path) {
+            route.description = docs.description;
+            route.tags = docs.tags;
+            route.deprecated = docs.deprecated;
+        }
+    }
+
+    /// Get middleware for a route pattern (see `RouteMatch::pattern`)
     pub fn get_route_middleware(&self, path: &str) -> Vec<BoxedMiddleware> {
         self.route_middleware.get(path).cloned().unwrap_or_default()
     }
@@ -106,16 +433,121 @@ impl Router {
             .entry(path.to_string())
             .or_default()
             .push(middleware);
+        self.clear_chains(path);
+    }
+
+    /// Global middleware types that don't run for a route pattern
+    pub fn get_excluded_middleware(&self, path: &str) -> &[TypeId] {
+        self.excluded_middleware.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    /// Skip a global middleware type for a path (internal use)
+    pub(crate) fn exclude_middleware(&mut self, path: &str, type_id: TypeId) {
+        let excluded = self
+            .excluded_middleware
+            .entry(path.to_string())
+            .or_default();
+        if !excluded.contains(&type_id) {
+            excluded.push(type_id);
+        }
+        self.clear_chains(path);
+    }
+
+    /// Drop the composed chains for a pattern, so its requests compose
+    /// their middleware until the router is finalized again
+    fn clear_chains(&mut self, path: &str) {
+        if !*self.finalized.get_mut() {
+            return;
+        }
+        for slot in self.chains.iter().filter(|slot| slot.pattern == path) {
+            slot.chain.store(None);
+        }
     }
 
     /// Set the fallback handler for when no routes match
     pub(crate) fn set_fallback(&mut self, handler: Arc<BoxedHandler>) {
         self.fallback_handler = Some(handler);
+        self.fallback_chain.store(None);
     }
 
     /// Add middleware to the fallback route
     pub(crate) fn add_fallback_middleware(&mut self, middleware: BoxedMiddleware) {
         self.fallback_middleware.push(middleware);
+        self.fallback_chain.store(None);
+    }
+
+    /// Handle requests that match no route (instead of the default 404)
+    ///
+    /// The builder equivalent of `fallback!`.
+    pub fn fallback<H, Fut>(mut self, handler: H) -> Router
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        let handler: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
+        self.set_fallback(Arc::new(handler));
+        self
+    }
+
+    /// Serve the files under `dir` at `prefix`
+    ///
+    /// `static_dir("/assets", "frontend/dist/assets")` answers
+    /// `GET /assets/app.js` from `frontend/dist/assets/app.js`, with
+    /// `ETag`/`Last-Modified` revalidation and pre-compressed `.br`/`.gz`
+    /// variants.
+    pub fn static_dir(self, prefix: &str, dir: impl Into<PathBuf>) -> Router {
+        let root = Arc::new(dir.into());
+        let path = format!("{}/{{*path}}", prefix.trim_end_matches('/'));
+        self.get(&path, move |req: Request| {
+            let root = root.clone();
+            async move {
+                let path = req.param("path").unwrap_or_default().to_string();
+                static_files::serve_dir(&req, &root, &path).await
+            }
+        })
+        .into()
+    }
+
+    /// Serve `index` for page requests that match no route
+    ///
+    /// Lets a client-side router own every URL the server doesn't. Requests
+    /// that don't accept HTML still get a 404.
+    pub fn spa_fallback(self, index: impl Into<PathBuf>) -> Router {
+        let index = Arc::new(index.into());
+        self.fallback(move |req: Request| {
+            let index = index.clone();
+            async move { static_files::serve_index(&req, &index).await }
+        })
+    }
+
+    /// Compose every route's handler with its middleware ahead of time
+    ///
+    /// Without this, each request collects the global, group and route
+    /// middleware for its route and wraps the handler in them. Finalizing
+    /// does that once per route, so requests only call the composed chain.
+    /// The server finalizes its router when it starts; routes and middleware
+    /// added afterwards fall back to composing per request. Finalizing again
+    /// recomposes every chain, with the given registry's global middleware.
+    pub fn finalize(&self, global: &MiddlewareRegistry) {
+        for slot in &self.chains {
+            let mut chain = MiddlewareChain::new();
+            let excluded = self.get_excluded_middleware(slot.pattern);
+            chain.extend(global.global_middleware_except(excluded));
+            chain.extend(self.get_route_middleware(slot.pattern));
+            slot.chain.store(Some(Arc::new(chain.compose(slot.handler.clone()))));
+        }
+        if let Some((handler, middleware)) = self.get_fallback() {
+            let mut chain = MiddlewareChain::new();
+            chain.extend(global.global_middleware().iter().cloned());
+            chain.extend(middleware);
+            self.fallback_chain.store(Some(Arc::new(chain.compose(handler))));
+        }
+        self.finalized.store(true, Ordering::Relaxed);
+    }
+
+    /// The fallback handler wrapped in its middleware, once finalized
+    pub(crate) fn fallback_chain(&self) -> Option<Next> {
+        self.fallback_chain.load_full().map(|chain| Next::clone(&chain))
     }
 
     /// Get the fallback handler and its middleware
@@ -127,82 +559,92 @@ impl Router {
 
     /// Insert a GET route with a pre-boxed handler (internal use for groups)
     pub(crate) fn insert_get(&mut self, path: &str, handler: Arc<BoxedHandler>) {
-        self.get_routes.insert(path, handler).ok();
+        self.insert(Method::Get, path, handler);
     }
 
     /// Insert a POST route with a pre-boxed handler (internal use for groups)
     pub(crate) fn insert_post(&mut self, path: &str, handler: Arc<BoxedHandler>) {
-        self.post_routes.insert(path, handler).ok();
+        self.insert(Method::Post, path, handler);
     }
 
     /// Insert a PUT route with a pre-boxed handler (internal use for groups)
     pub(crate) fn insert_put(&mut self, path: &str, handler: Arc<BoxedHandler>) {
-        self.put_routes.insert(path, handler).ok();
+        self.insert(Method::Put, path, handler);
+    }
+
+    /// Insert a PATCH route with a pre-boxed handler (internal use for groups)
+    pub(crate) fn insert_patch(&mut self, path: &str, handler: Arc<BoxedHandler>) {
+        self.insert(Method::Patch, path, handler);
     }
 
     /// Insert a DELETE route with a pre-boxed handler (internal use for groups)
     pub(crate) fn insert_delete(&mut self, path: &str, handler: Arc<BoxedHandler>) {
-        self.delete_routes.insert(path, handler).ok();
+        self.insert(Method::Delete, path, handler);
+    }
+
+    /// Register a route from the builder API
+    ///
+    /// Paths use the same syntax as the route macros (`/users/:id` or
+    /// `/users/{id}`). The macros reject paths without a leading `/` at
+    /// compile time; here they are reported by `try_build`.
+    fn add_route(mut self, method: Method, path: &str, handler: Arc<BoxedHandler>) -> RouteBuilder {
+        let path = convert_route_params(path);
+        self.insert(method, &path, handler);
+        RouteBuilder {
+            router: self,
+            last_path: path,
+            _last_method: method,
+        }
     }
 
     /// Register a GET route
-    pub fn get<H, Fut>(mut self, path: &str, handler: H) -> RouteBuilder
+    pub fn get<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
     where
         H: Fn(Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Response> + Send + 'static,
     {
         let handler: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
-        self.get_routes.insert(path, Arc::new(handler)).ok();
-        RouteBuilder {
-            router: self,
-            last_path: path.to_string(),
-            _last_method: Method::Get,
-        }
+        self.add_route(Method::Get, path, Arc::new(handler))
     }
 
     /// Register a POST route
-    pub fn post<H, Fut>(mut self, path: &str, handler: H) -> RouteBuilder
+    pub fn post<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
     where
         H: Fn(Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Response> + Send + 'static,
     {
         let handler: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
-        self.post_routes.insert(path, Arc::new(handler)).ok();
-        RouteBuilder {
-            router: self,
-            last_path: path.to_string(),
-            _last_method: Method::Post,
-        }
+        self.add_route(Method::Post, path, Arc::new(handler))
     }
 
     /// Register a PUT route
-    pub fn put<H, Fut>(mut self, path: &str, handler: H) -> RouteBuilder
+    pub fn put<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
     where
         H: Fn(Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Response> + Send + 'static,
     {
         let handler: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
-        self.put_routes.insert(path, Arc::new(handler)).ok();
-        RouteBuilder {
-            router: self,
-            last_path: path.to_string(),
-            _last_method: Method::Put,
-        }
+        self.add_route(Method::Put, path, Arc::new(handler))
+    }
+
+    /// Register a PATCH route
+    pub fn patch<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        let handler: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
+        self.add_route(Method::Patch, path, Arc::new(handler))
     }
 
     /// Register a DELETE route
-    pub fn delete<H, Fut>(mut self, path: &str, handler: H) -> RouteBuilder
+    pub fn delete<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
     where
         H: Fn(Request) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Response> + Send + 'static,
     {
         let handler: BoxedHandler = Box::new(move |req| Box::pin(handler(req)));
-        self.delete_routes.insert(path, Arc::new(handler)).ok();
-        RouteBuilder {
-            router: self,
-            last_path: path.to_string(),
-            _last_method: Method::Delete,
-        }
+        self.add_route(Method::Delete, path, Arc::new(handler))
     }
 
     /// Match a request and return the handler with extracted params
@@ -211,21 +653,38 @@ impl Router {
         method: &hyper::Method,
         path: &str,
     ) -> Option<(Arc<BoxedHandler>, HashMap<String, String>)> {
+        self.find(method, path)
+            .map(|matched| (matched.handler, matched.params))
+    }
+
+    /// Match a request against the registered routes
+    pub fn find(&self, method: &hyper::Method, path: &str) -> Option<RouteMatch> {
         let router = match *method {
             hyper::Method::GET => &self.get_routes,
             hyper::Method::POST => &self.post_routes,
             hyper::Method::PUT => &self.put_routes,
+            hyper::Method::PATCH => &self.patch_routes,
             hyper::Method::DELETE => &self.delete_routes,
             _ => return None,
         };
 
-        router.at(path).ok().map(|matched| {
-            let params: HashMap<String, String> = matched
-                .params
-                .iter()
-                .map(|(k, v)| (k.to_string(), v.to_string()))
-                .collect();
-            (matched.value.clone(), params)
+        let matched = router.at(path).ok()?;
+        let entry = matched.value;
+
+        let mut params = HashMap::new();
+        if !entry.param_names.is_empty() {
+            params.reserve(entry.param_names.len());
+            for (name, (_, value)) in entry.param_names.iter().zip(matched.params.iter()) {
+                params.insert((*name).to_string(), value.to_string());
+            }
+        }
+
+        Some(RouteMatch {
+            handler: entry.handler.clone(),
+            pattern: entry.pattern,
+            params,
+            binding_keys: entry.binding_keys.clone(),
+            chain: entry.chain.load_full().map(|chain| Next::clone(&chain)),
         })
     }
 }
@@ -246,9 +705,19 @@ pub struct RouteBuilder {
 
 impl RouteBuilder {
     /// Name the most recently registered route
-    pub fn name(self, name: &str) -> Router {
-        register_route_name(name, &self.last_path);
-        self.router
+    ///
+    /// Names work with `route()` and `redirect!` like names given with the
+    /// route macros.
+    pub fn name(mut self, name: &str) -> RouteBuilder {
+        self.router.name_route(&self.last_path, name);
+        self
+    }
+
+    /// Finish the router, reporting every route that couldn't be registered
+    ///
+    /// See `Router::try_build`.
+    pub fn try_build(self) -> Result<Router, RouteErrors> {
+        self.router.try_build()
     }
 
     /// Apply middleware to the most recently registered route
@@ -304,6 +773,15 @@ impl RouteBuilder {
         self.router.put(path, handler)
     }
 
+    /// Register a PATCH route (for chaining without .name())
+    pub fn patch<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.router.patch(path, handler)
+    }
+
     /// Register a DELETE route (for chaining without .name())
     pub fn delete<H, Fut>(self, path: &str, handler: H) -> RouteBuilder
     where
@@ -312,6 +790,25 @@ impl RouteBuilder {
     {
         self.router.delete(path, handler)
     }
+
+    /// Set the fallback handler (for chaining without .name())
+    pub fn fallback<H, Fut>(self, handler: H) -> Router
+    where
+        H: Fn(Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Response> + Send + 'static,
+    {
+        self.router.fallback(handler)
+    }
+
+    /// Serve a directory of files (for chaining without .name())
+    pub fn static_dir(self, prefix: &str, dir: impl Into<PathBuf>) -> Router {
+        self.router.static_dir(prefix, dir)
+    }
+
+    /// Serve an SPA entry point as the fallback (for chaining without .name())
+    pub fn spa_fallback(self, index: impl Into<PathBuf>) -> Router {
+        self.router.spa_fallback(index)
+    }
 }
 
 impl From<RouteBuilder> for Router {
@@ -319,3 +816,239 @@ impl From<RouteBuilder> for Router {
         builder.router
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::text;
+    use crate::middleware::Next;
+    use async_trait::async_trait;
+    use std::time::Instant;
+
+    async fn ok(_req: Request) -> Response {
+        text("ok")
+    }
+
+    struct Noop;
+
+    #[async_trait]
+    impl Middleware for Noop {
+        async fn handle(&self, request: Request, next: Next) -> Response {
+            next(request).await
+        }
+    }
+
+    fn large_router(resources: usize) -> Router {
+        let mut router = Router::new();
+        for i in 0..resources {
+            router = router
+                .get(&format!("/r{}", i), ok)
+                .get(&format!("/r{}/{{id}}", i), ok)
+                .get(&format!("/r{}/{{id}}/items/{{item}}", i), ok)
+                .into();
+        }
+        router
+    }
+
+    #[test]
+    fn test_param_names_are_extracted_from_pattern() {
+        assert!(param_names("/users").is_empty());
+        assert_eq!(&*param_names("/users/{id}"), &["id"]);
+        assert_eq!(
+            &*param_names("/posts/{post_id}/comments/{id}"),
+            &["post_id", "id"]
+        );
+        assert_eq!(&*param_names("/files/{*path}"), &["path"]);
+    }
+
+    #[test]
+    fn test_find_matches_large_route_table() {
+        let router = large_router(500);
+
+        let matched = router
+            .find(&hyper::Method::GET, "/r499/42/items/7")
+            .unwrap();
+        assert_eq!(matched.pattern, "/r499/{id}/items/{item}");
+        assert_eq!(matched.params.get("id").map(String::as_str), Some("42"));
+        assert_eq!(matched.params.get("item").map(String::as_str), Some("7"));
+
+        let matched = router.find(&hyper::Method::GET, "/r0").unwrap();
+        assert_eq!(matched.pattern, "/r0");
+        assert!(matched.params.is_empty());
+
+        assert!(router.find(&hyper::Method::GET, "/r500").is_none());
+        assert!(router.find(&hyper::Method::POST, "/r0").is_none());
+    }
+
+    #[test]
+    fn test_binding_columns_are_matched_and_reported() {
+        let router: Router = Router::new()
+            .get("/keyed-posts/{post:slug}/comments/{comment}", ok)
+            .name("keyed-posts.comments.show")
+            .into();
+
+        let matched = router
+            .find(&hyper::Method::GET, "/keyed-posts/hello-world/comments/3")
+            .unwrap();
+        assert_eq!(matched.pattern, "/keyed-posts/{post:slug}/comments/{comment}");
+        assert_eq!(matched.params.get("post").map(String::as_str), Some("hello-world"));
+        assert_eq!(&*matched.binding_keys, &[("post", "slug")]);
+        assert_eq!(
+            route("keyed-posts.comments.show", &[("post", "hello-world"), ("comment", "3")]),
+            Some("/keyed-posts/hello-world/comments/3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_route_middleware_is_keyed_by_pattern() {
+        let router: Router = Router::new().get("/users/{id}", ok).middleware(Noop).into();
+
+        let matched = router.find(&hyper::Method::GET, "/users/5").unwrap();
+        assert_eq!(router.get_route_middleware(matched.pattern).len(), 1);
+    }
+
+    struct Trail(&'static str, Arc<std::sync::Mutex<Vec<&'static str>>>);
+
+    #[async_trait]
+    impl Middleware for Trail {
+        async fn handle(&self, request: Request, next: Next) -> Response {
+            self.1.lock().unwrap().push(self.0);
+            next(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_finalize_composes_global_and_route_middleware() {
+        let trail = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let router: Router = Router::new()
+            .get("/users/{id}", ok)
+            .middleware(Trail("route", trail.clone()))
+            .into();
+        let global = MiddlewareRegistry::new().append(Trail("global", trail.clone()));
+
+        let matched = router.find(&hyper::Method::GET, "/users/5").unwrap();
+        assert!(matched.chain.is_none());
+
+        router.finalize(&global);
+        let matched = router.find(&hyper::Method::GET, "/users/5").unwrap();
+        let chain = matched.chain.expect("route chain is composed");
+        assert!(chain(Request::fake().build()).await.is_ok());
+        assert_eq!(*trail.lock().unwrap(), ["global", "route"]);
+    }
+
+    #[tokio::test]
+    async fn test_finalize_again_after_middleware_changes() {
+        let trail = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut router: Router = Router::new().get("/orders", ok).into();
+        router.finalize(&MiddlewareRegistry::new().append(Trail("first", trail.clone())));
+
+        // New route middleware drops the stale chain until the next finalize
+        let route = crate::middleware::into_boxed(Trail("route", trail.clone()));
+        router.add_middleware("/orders", route);
+        assert!(router.find(&hyper::Method::GET, "/orders").unwrap().chain.is_none());
+
+        router.finalize(&MiddlewareRegistry::new().append(Trail("second", trail.clone())));
+        let chain = router.find(&hyper::Method::GET, "/orders").unwrap().chain.unwrap();
+        assert!(chain(Request::fake().build()).await.is_ok());
+        assert_eq!(*trail.lock().unwrap(), ["second", "route"]);
+    }
+
+    #[test]
+    fn test_builder_routes_match_macro_routes() {
+        let router: Router = Router::new()
+            .get("/builder/:id", ok)
+            .name("builder.show")
+            .middleware(Noop)
+            .into();
+
+        let matched = router.find(&hyper::Method::GET, "/builder/5").unwrap();
+        assert_eq!(matched.pattern, "/builder/{id}");
+        assert_eq!(router.get_route_middleware(matched.pattern).len(), 1);
+        assert_eq!(
+            route("builder.show", &[("id", "5")]),
+            Some("/builder/5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_builder_groups_match_macro_groups() {
+        let router: Router = Router::new()
+            .group("/builder-admin", |r| {
+                r.get("/", ok)
+                    .name("index")
+                    .group("/users", |r| r.get("/:id", ok).name("users.show").middleware(Noop))
+            })
+            .name_prefix("builder_admin.")
+            .middleware(Noop)
+            .into();
+        let routes: Vec<(&str, Option<String>, usize)> = router
+            .routes()
+            .into_iter()
+            .map(|r| (r.pattern, r.name, r.middleware))
+            .collect();
+
+        assert_eq!(
+            routes,
+            [
+                ("/builder-admin", Some("builder_admin.index".to_string()), 1),
+                (
+                    "/builder-admin/users/{id}",
+                    Some("builder_admin.users.show".to_string()),
+                    2
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_build_reports_every_problem() {
+        let errors = Router::new()
+            .get("users", ok)
+            .get("/try-build/{id}/posts/{id}", ok)
+            .get("/try-build/{id}", ok)
+            .name("try_build.show")
+            .get("/try-build/{id}", ok)
+            .get("/try-build/{slug}", ok)
+            .post("/try-build", ok)
+            .name("try_build.show")
+            .group("/try-build-admin", |r| r.get("settings", ok))
+            .try_build()
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            errors.to_string(),
+            "Found 6 invalid routes:\n  \
+             - GET users: path must start with '/'\n  \
+             - GET /try-build/{id}/posts/{id}: parameter {id} appears more than once\n  \
+             - GET /try-build/{id} is registered more than once\n  \
+             - GET /try-build/{slug} conflicts with GET /try-build/{id}\n  \
+             - Route name \"try_build.show\" is used for both GET /try-build/{id} and POST /try-build\n  \
+             - GET settings: path must start with '/'"
+        );
+        assert_eq!(route("try_build.show", &[("id", "1")]).unwrap(), "/try-build/1");
+        assert!(Router::new().get("/try-build-ok", ok).try_build().is_ok());
+    }
+
+    /// Run with `cargo test -- --ignored` in release mode
+    #[test]
+    #[ignore]
+    fn bench_find_large_route_table() {
+        let router = large_router(2000);
+        let paths: Vec<String> = (0..2000)
+            .map(|i| format!("/r{}/{}/items/9", i, i))
+            .collect();
+
+        let started = Instant::now();
+        for path in &paths {
+            assert!(router.find(&hyper::Method::GET, path).is_some());
+        }
+        let per_lookup = started.elapsed() / paths.len() as u32;
+
+        assert!(
+            per_lookup.as_micros() < 20,
+            "route lookup took {:?} per request",
+            per_lookup
+        );
+    }
+}
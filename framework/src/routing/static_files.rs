@@ -0,0 +1,315 @@
+//! Serving built frontend files
+//!
+//! `Router::static_dir` and `Router::spa_fallback` let a production build
+//! serve the Vite output without a separate web server:
+//!
+//! ```rust,ignore
+//! let router = routes::register()
+//!     .static_dir("/assets", "frontend/dist/assets")
+//!     .spa_fallback("frontend/dist/index.html");
+//! ```
+//!
+//! Responses carry `ETag` and `Last-Modified` and answer conditional
+//! requests with `304 Not Modified`. When the client accepts it, a
+//! pre-compressed `app.js.br` or `app.js.gz` next to `app.js` is sent
+//! instead, with `Content-Encoding` set; nothing is compressed on the fly.
+
+use crate::http::{ETag, HttpResponse, Request, Response};
+use chrono::{DateTime, Utc};
+use percent_encoding::percent_decode_str;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Pre-compressed siblings, in order of preference
+const ENCODINGS: [(&str, &str); 2] = [("br", "br"), ("gzip", "gz")];
+
+/// Format of `Last-Modified` and `If-Modified-Since`
+const HTTP_DATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Serve `path` (the rest of the URL after the prefix) from `root`
+pub(crate) async fn serve_dir(req: &Request, root: &Path, path: &str) -> Response {
+    let Some(file) = resolve(root, path) else {
+        return Err(not_found());
+    };
+    serve_file(req, &file, None).await.ok_or_else(not_found)?
+}
+
+/// Serve the SPA entry point for page navigations
+///
+/// Only `GET` and `HEAD` requests that accept HTML get the index page, so a
+/// mistyped API call or a missing image still gets a 404.
+pub(crate) async fn serve_index(req: &Request, index: &Path) -> Response {
+    let navigation = matches!(*req.method(), hyper::Method::GET | hyper::Method::HEAD)
+        && req
+            .header("Accept")
+            .is_some_and(|accept| accept.contains("text/html") || accept.contains("*/*"));
+    if !navigation {
+        return Err(not_found());
+    }
+    serve_file(req, index, Some("no-cache"))
+        .await
+        .ok_or_else(not_found)?
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse::text("404 Not Found").status(404)
+}
+
+/// Join a URL path onto `root`, refusing anything that could escape it
+fn resolve(root: &Path, path: &str) -> Option<PathBuf> {
+    let decoded = percent_decode_str(path).decode_utf8().ok()?;
+    let mut file = root.to_path_buf();
+    for segment in decoded.split('/') {
+        if segment.is_empty() || segment == "." || segment == ".." || segment.contains(['\\', '\0'])
+        {
+            return None;
+        }
+        file.push(segment);
+    }
+    Some(file)
+}
+
+/// Respond with `file`, or `None` when it doesn't exist
+async fn serve_file(req: &Request, file: &Path, cache_control: Option<&str>) -> Option<Response> {
+    let metadata = tokio::fs::metadata(file).await.ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+
+    let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+    let mtime = modified
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let etag = ETag::from_parts([metadata.len().to_string(), mtime.to_string()]);
+    let last_modified = DateTime::<Utc>::from(modified)
+        .format(HTTP_DATE)
+        .to_string();
+
+    let fresh = match req.header("If-None-Match") {
+        Some(_) => etag.matches(req),
+        None => req
+            .header("If-Modified-Since")
+            .is_some_and(|since| not_modified_since(since, modified)),
+    };
+    let response = if fresh {
+        etag.not_modified()
+    } else {
+        let (body, encoding) = read_encoded(req, file).await?;
+        let mut response =
+            HttpResponse::from_bytes(content_type(file), body).header("ETag", etag.as_str());
+        if let Some(encoding) = encoding {
+            response = response.header("Content-Encoding", encoding);
+        }
+        response
+    };
+
+    let mut response = response
+        .header("Last-Modified", last_modified)
+        .header("Vary", "Accept-Encoding");
+    if let Some(cache_control) = cache_control {
+        response = response.header("Cache-Control", cache_control);
+    }
+    Some(Ok(response))
+}
+
+/// Read the best pre-compressed variant the client accepts, or the file itself
+async fn read_encoded(req: &Request, file: &Path) -> Option<(Vec<u8>, Option<&'static str>)> {
+    let accepted = req.header("Accept-Encoding").unwrap_or_default();
+    for (encoding, extension) in ENCODINGS {
+        if !accepts_encoding(accepted, encoding) {
+            continue;
+        }
+        let mut compressed = file.as_os_str().to_owned();
+        compressed.push(".");
+        compressed.push(extension);
+        if let Ok(body) = tokio::fs::read(&compressed).await {
+            return Some((body, Some(encoding)));
+        }
+    }
+    tokio::fs::read(file).await.ok().map(|body| (body, None))
+}
+
+/// Whether an `Accept-Encoding` header allows `encoding` (`q=0` refuses it)
+fn accepts_encoding(header: &str, encoding: &str) -> bool {
+    header.split(',').any(|item| {
+        let mut parts = item.split(';').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let refused = parts.any(|param| {
+            param
+                .strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+        (name.eq_ignore_ascii_case(encoding) || name == "*") && !refused
+    })
+}
+
+/// Whether the file is no newer than an `If-Modified-Since` date
+fn not_modified_since(since: &str, modified: SystemTime) -> bool {
+    let Ok(since) = DateTime::parse_from_rfc2822(since) else {
+        return false;
+    };
+    let modified = DateTime::<Utc>::from(modified).timestamp();
+    modified <= since.timestamp()
+}
+
+/// The `Content-Type` for a file, from its extension
+pub(crate) fn content_type(file: &Path) -> &'static str {
+    let extension = file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" | "map" => "application/json",
+        "webmanifest" => "application/manifest+json",
+        "txt" => "text/plain; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "wasm" => "application/wasm",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routing::Router;
+
+    fn fixture(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kit-static-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("index.html"), "<div id=\"app\"></div>").unwrap();
+        std::fs::write(dir.join("assets/app.js"), "console.log(1)").unwrap();
+        std::fs::write(dir.join("assets/app.js.br"), "brotli").unwrap();
+        std::fs::write(dir.join("secret.txt"), "secret").unwrap();
+        dir
+    }
+
+    async fn get(router: &Router, path: &str, headers: &[(&str, &str)]) -> HttpResponse {
+        let mut fake = Request::fake().path(path);
+        for (name, value) in headers {
+            fake = fake.header(*name, *value);
+        }
+        let req = fake.build();
+        let response = match router.find(&hyper::Method::GET, path) {
+            Some(matched) => (matched.handler)(req.with_params(matched.params)).await,
+            None => {
+                let (fallback, _) = router.get_fallback().unwrap();
+                fallback(req).await
+            }
+        };
+        response.unwrap_or_else(|response| response)
+    }
+
+    fn header<'a>(response: &'a HttpResponse, name: &str) -> Option<&'a str> {
+        response
+            .headers()
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[tokio::test]
+    async fn serves_files_with_validators_and_precompressed_variants() {
+        let dir = fixture("assets");
+        let router = Router::new().static_dir("/assets", dir.join("assets"));
+
+        let plain = get(&router, "/assets/app.js", &[]).await;
+        assert_eq!(plain.status_code(), 200);
+        assert_eq!(plain.body().as_ref(), b"console.log(1)");
+        assert_eq!(
+            header(&plain, "Content-Type"),
+            Some("text/javascript; charset=utf-8")
+        );
+        assert_eq!(header(&plain, "Content-Encoding"), None);
+        assert!(header(&plain, "Last-Modified").is_some_and(|date| date.ends_with("GMT")));
+
+        let brotli = get(
+            &router,
+            "/assets/app.js",
+            &[("Accept-Encoding", "gzip, br")],
+        )
+        .await;
+        assert_eq!(brotli.body().as_ref(), b"brotli");
+        assert_eq!(header(&brotli, "Content-Encoding"), Some("br"));
+        assert_eq!(header(&brotli, "Vary"), Some("Accept-Encoding"));
+
+        let etag = header(&plain, "ETag").unwrap();
+        let cached = get(&router, "/assets/app.js", &[("If-None-Match", etag)]).await;
+        assert_eq!(cached.status_code(), 304);
+        assert!(cached.body().is_empty());
+
+        let since = header(&plain, "Last-Modified").unwrap();
+        let cached = get(&router, "/assets/app.js", &[("If-Modified-Since", since)]).await;
+        assert_eq!(cached.status_code(), 304);
+
+        let stale = get(&router, "/assets/app.js", &[("If-None-Match", "W/\"old\"")]).await;
+        assert_eq!(stale.status_code(), 200);
+    }
+
+    #[tokio::test]
+    async fn refuses_paths_outside_the_directory() {
+        let dir = fixture("traversal");
+        let router = Router::new().static_dir("/assets", dir.join("assets"));
+
+        for path in [
+            "/assets/../secret.txt",
+            "/assets/%2e%2e/secret.txt",
+            "/assets/missing.js",
+        ] {
+            assert_eq!(get(&router, path, &[]).await.status_code(), 404, "{}", path);
+        }
+        assert!(resolve(&dir, "a/b.js").is_some());
+        assert!(resolve(&dir, "a//b.js").is_none());
+    }
+
+    #[tokio::test]
+    async fn spa_fallback_serves_the_index_for_navigations() {
+        let dir = fixture("spa");
+        let router = Router::new()
+            .static_dir("/assets", dir.join("assets"))
+            .spa_fallback(dir.join("index.html"));
+
+        let page = get(&router, "/todos/5", &[("Accept", "text/html,*/*;q=0.8")]).await;
+        assert_eq!(page.status_code(), 200);
+        assert_eq!(page.body().as_ref(), b"<div id=\"app\"></div>");
+        assert_eq!(
+            header(&page, "Content-Type"),
+            Some("text/html; charset=utf-8")
+        );
+        assert_eq!(header(&page, "Cache-Control"), Some("no-cache"));
+
+        let api = get(&router, "/api/missing", &[("Accept", "application/json")]).await;
+        assert_eq!(api.status_code(), 404);
+    }
+
+    #[test]
+    fn parses_accept_encoding() {
+        assert!(accepts_encoding("gzip, deflate, br", "br"));
+        assert!(accepts_encoding("*", "gzip"));
+        assert!(!accepts_encoding("gzip;q=0, br", "gzip"));
+        assert!(!accepts_encoding("", "br"));
+    }
+}
@@ -30,6 +30,8 @@ pub struct TaskBuilder {
     pub(crate) description: Option<String>,
     pub(crate) without_overlapping: bool,
     pub(crate) run_in_background: bool,
+    /// The first invalid schedule setting, reported when the task is added
+    pub(crate) error: Option<String>,
 }
 
 impl TaskBuilder {
@@ -47,6 +49,7 @@ impl TaskBuilder {
             description: None,
             without_overlapping: false,
             run_in_background: false,
+            error: None,
         }
     }
 
@@ -82,6 +85,7 @@ impl TaskBuilder {
             description: None,
             without_overlapping: false,
             run_in_background: false,
+            error: None,
         }
     }
 
@@ -96,11 +100,13 @@ impl TaskBuilder {
     /// .cron("0 */5 * * *") // Every 5 hours at minute 0
     /// ```
     ///
-    /// # Panics
-    /// Panics if the cron expression is invalid.
+    /// An invalid expression is reported when the task is added, see
+    /// `Schedule::try_add`.
     pub fn cron(mut self, expression: &str) -> Self {
-        self.expression =
-            CronExpression::parse(expression).expect("Invalid cron expression");
+        match CronExpression::parse(expression) {
+            Ok(parsed) => self.expression = parsed,
+            Err(e) => self.invalid(format!("Invalid cron expression {:?}: {}", expression, e)),
+        }
         self
     }
 
@@ -200,6 +206,7 @@ impl TaskBuilder {
     /// .daily_at("13:00") // Daily at 1:00 PM
     /// ```
     pub fn daily_at(mut self, time: &str) -> Self {
+        self.check_time(time);
         self.expression = CronExpression::daily_at(time);
         self
     }
@@ -226,6 +233,7 @@ impl TaskBuilder {
     /// .weekly().at("09:00") // Weekly at 9:00 AM
     /// ```
     pub fn at(mut self, time: &str) -> Self {
+        self.check_time(time);
         self.expression = self.expression.at(time);
         self
     }
@@ -379,6 +387,22 @@ impl TaskBuilder {
         self
     }
 
+    /// Record an invalid setting, keeping the first one
+    fn invalid(&mut self, reason: String) {
+        self.error.get_or_insert(reason);
+    }
+
+    /// Record `time` as invalid unless it is `HH:MM`
+    fn check_time(&mut self, time: &str) {
+        let in_range = |value: &str, max: u32| value.parse::<u32>().is_ok_and(|n| n <= max);
+        let valid = time
+            .split_once(':')
+            .is_some_and(|(hour, minute)| in_range(hour, 23) && in_range(minute, 59));
+        if !valid {
+            self.invalid(format!("Invalid time {:?}, expected HH:MM", time));
+        }
+    }
+
     /// Build the task entry
     ///
     /// This is called internally when adding the task to the schedule.
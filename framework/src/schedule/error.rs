@@ -0,0 +1,15 @@
+//! Problems found while registering scheduled tasks
+
+use thiserror::Error;
+
+/// A task that couldn't be added to the schedule
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ScheduleError {
+    /// The cron expression or time of day is invalid
+    #[error("Task {task:?}: {reason}")]
+    InvalidSchedule { task: String, reason: String },
+
+    /// Another task was already added under this name
+    #[error("Task name {0:?} is used more than once")]
+    DuplicateName(String),
+}
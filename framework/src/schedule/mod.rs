@@ -78,14 +78,19 @@
 //! ```
 
 pub mod builder;
+mod error;
 pub mod expression;
 pub mod task;
 
 pub use builder::TaskBuilder;
+pub use error::ScheduleError;
 pub use expression::{CronExpression, DayOfWeek};
 pub use task::{BoxedFuture, BoxedTask, Task, TaskEntry, TaskHandler, TaskResult};
 
+use crate::daemon::{Daemon, StopReason, WorkerStats};
 use crate::error::FrameworkError;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Schedule - main entry point for scheduling tasks
 ///
@@ -117,12 +122,17 @@ use crate::error::FrameworkError;
 /// ```
 pub struct Schedule {
     tasks: Vec<TaskEntry>,
+    /// Tasks `add` left out, see `errors`
+    errors: Vec<ScheduleError>,
 }
 
 impl Schedule {
     /// Create a new empty schedule
     pub fn new() -> Self {
-        Self { tasks: Vec::new() }
+        Self {
+            tasks: Vec::new(),
+            errors: Vec::new(),
+        }
     }
 
     /// Register a trait-based scheduled task
@@ -173,12 +183,46 @@ impl Schedule {
     /// let builder = schedule.call(|| async { Ok(()) }).daily();
     /// schedule.add(builder);
     /// ```
+    ///
+    /// A task with an invalid schedule or a name that is already taken is
+    /// left out and kept in `errors()`, so every problem is reported together
+    /// when the scheduler starts.
     pub fn add(&mut self, builder: TaskBuilder) -> &mut Self {
-        let task_index = self.tasks.len();
-        self.tasks.push(builder.build(task_index));
+        if let Some(error) = self.try_add(builder).err() {
+            self.errors.push(error);
+        }
         self
     }
 
+    /// Add a configured task builder, returning why it can't be added
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// schedule.try_add(schedule.task(Backup).cron("0 3 * *").name("backup"))?;
+    /// // Err: Task "backup": Invalid cron expression "0 3 * *": Cron expression must have 5 fields, got 4
+    /// ```
+    pub fn try_add(&mut self, mut builder: TaskBuilder) -> Result<&mut Self, ScheduleError> {
+        let error = builder.error.take();
+        let task = builder.build(self.tasks.len());
+        if let Some(reason) = error {
+            return Err(ScheduleError::InvalidSchedule {
+                task: task.name,
+                reason,
+            });
+        }
+        if self.find(&task.name).is_some() {
+            return Err(ScheduleError::DuplicateName(task.name));
+        }
+        self.tasks.push(task);
+        Ok(self)
+    }
+
+    /// Tasks that `add` left out, in the order they were added
+    pub fn errors(&self) -> &[ScheduleError] {
+        &self.errors
+    }
+
     /// Get all registered tasks
     pub fn tasks(&self) -> &[TaskEntry] {
         &self.tasks
@@ -228,6 +272,40 @@ impl Schedule {
         results
     }
 
+    /// Run due tasks at the start of every minute until the daemon stops
+    ///
+    /// Task results are printed as they complete and recorded in `stats`.
+    pub async fn work(&self, daemon: Daemon, stats: Arc<WorkerStats>) -> StopReason {
+        let signal = daemon.signal();
+        let daemon = daemon.stats(stats.clone());
+
+        daemon
+            .run(|| {
+                let signal = signal.clone();
+                let stats = stats.clone();
+                async move {
+                    let results = self.run_due_tasks().await;
+                    for (name, result) in &results {
+                        match result {
+                            Ok(()) => println!("  ✓ {}", name),
+                            Err(e) => {
+                                stats.record_failure();
+                                eprintln!("  ✗ {}: {}", name, e);
+                            }
+                        }
+                    }
+
+                    // Sleep until the start of the next minute
+                    let now = chrono::Local::now().timestamp();
+                    let wait = 60 - now.rem_euclid(60) as u64;
+                    signal.sleep(Duration::from_secs(wait)).await;
+
+                    Ok(results.len() as u64)
+                }
+            })
+            .await
+    }
+
     /// Find a task by name
     pub fn find(&self, name: &str) -> Option<&TaskEntry> {
         self.tasks.iter().find(|t| t.name == name)
@@ -345,6 +423,42 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[test]
+    fn test_schedule_reports_invalid_tasks() {
+        let mut schedule = Schedule::new();
+        schedule.add(schedule.task(TestTask).cron("0 3 * *").name("backup"));
+        schedule.add(schedule.task(TestTask).daily().at("25:00").name("report"));
+        schedule.add(schedule.task(TestTask).every_minute().name("sync"));
+        schedule.add(schedule.task(TestTask).hourly().name("sync"));
+
+        assert_eq!(schedule.len(), 1);
+        let errors: Vec<String> = schedule.errors().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "Task \"backup\": Invalid cron expression \"0 3 * *\": \
+                 Cron expression must have 5 fields, got 4",
+                "Task \"report\": Invalid time \"25:00\", expected HH:MM",
+                "Task name \"sync\" is used more than once",
+            ]
+        );
+
+        let err = schedule
+            .try_add(schedule.task(TestTask).cron("*/x * * * *"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            ScheduleError::InvalidSchedule {
+                task: "closure-task-1".to_string(),
+                reason: "Invalid cron expression \"*/x * * * *\": \
+                         Invalid step value in '*/x'"
+                    .to_string(),
+            }
+        );
+        assert_eq!(schedule.errors().len(), 3);
+    }
+
     #[tokio::test]
     async fn test_schedule_run_all_tasks() {
         let mut schedule = Schedule::new();
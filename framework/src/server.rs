@@ -1,10 +1,19 @@
+use crate::batch::BatchEndpoint;
 use crate::cache::Cache;
 use crate::config::{Config, ServerConfig};
 use crate::container::App;
-use crate::http::{HttpResponse, Request};
-use crate::inertia::InertiaContext;
+use crate::http::{
+    error_page, tls, BodyLimits, ConnectionScheme, Disconnect, ErrorContext, HttpResponse,
+    RemoteAddr, Request, RequestBody, ResponseBody,
+};
+use crate::inertia::{InertiaContext, PartialReload};
+use crate::metrics::{self, RequestMetrics, SlowRequest};
 use crate::middleware::{Middleware, MiddlewareChain, MiddlewareRegistry};
-use crate::routing::Router;
+use crate::profile;
+use crate::routing::{route_name, Router};
+use crate::startup::{Boot, StartupSummary};
+use crate::strict;
+use crate::telemetry;
 use bytes::Bytes;
 use http_body_util::Full;
 use hyper::server::conn::http1;
@@ -12,14 +21,38 @@ use hyper::service::service_fn;
 use hyper_util::rt::TokioIo;
 use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
 
 pub struct Server {
     router: Arc<Router>,
     middleware: MiddlewareRegistry,
     host: String,
     port: u16,
+    slow_request: Option<Duration>,
+    slow_summary: Option<Duration>,
+    batch: Option<BatchEndpoint>,
+    cancel_on_disconnect: bool,
+    /// Certificate chain and key files to serve HTTPS with
+    tls: Option<(PathBuf, PathBuf)>,
+    /// Port redirecting plain HTTP to HTTPS
+    https_redirect_port: Option<u16>,
+    /// What the application booted, for the startup summary
+    boot: Option<Boot>,
+}
+
+/// What each connection needs to handle its requests
+#[derive(Clone)]
+struct Connection {
+    router: Arc<Router>,
+    middleware: Arc<MiddlewareRegistry>,
+    batch: Option<Arc<BatchEndpoint>>,
+    slow_request: Option<Duration>,
+    cancel_on_disconnect: bool,
 }
 
 impl Server {
@@ -29,6 +62,13 @@ impl Server {
             middleware: MiddlewareRegistry::new(),
             host: "127.0.0.1".to_string(),
             port: 8000,
+            slow_request: Some(Duration::from_secs(1)),
+            slow_summary: None,
+            batch: None,
+            cancel_on_disconnect: true,
+            tls: None,
+            https_redirect_port: None,
+            boot: None,
         }
     }
 
@@ -46,6 +86,17 @@ impl Server {
             middleware: MiddlewareRegistry::from_global(),
             host: config.host,
             port: config.port,
+            slow_request: (config.slow_request_ms > 0)
+                .then(|| Duration::from_millis(config.slow_request_ms)),
+            slow_summary: (config.slow_summary_minutes > 0 && Config::is_development())
+                .then(|| Duration::from_secs(config.slow_summary_minutes * 60)),
+            batch: None,
+            cancel_on_disconnect: true,
+            tls: (!config.tls_cert.is_empty())
+                .then(|| (config.tls_cert.into(), config.tls_key.into())),
+            https_redirect_port: (config.https_redirect_port > 0)
+                .then_some(config.https_redirect_port),
+            boot: None,
         }
     }
 
@@ -77,48 +128,305 @@ impl Server {
         self
     }
 
+    /// Log a warning for requests slower than `threshold` (`None` disables)
+    pub fn slow_request_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.slow_request = threshold;
+        self
+    }
+
+    /// Print the slowest requests every `interval` (`None` disables)
+    pub fn slow_request_summary(mut self, interval: Option<Duration>) -> Self {
+        self.slow_summary = interval;
+        self
+    }
+
+    /// Accept batches of sub-requests at the endpoint's path
+    ///
+    /// Off by default. See the `batch` module for the request format.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// Server::from_config(router)
+    ///     .batch("/batch")
+    ///     .run()
+    ///     .await;
+    /// ```
+    pub fn batch(mut self, endpoint: impl Into<BatchEndpoint>) -> Self {
+        self.batch = Some(endpoint.into());
+        self
+    }
+
+    /// Drop a request's handler when its client disconnects (default `true`)
+    ///
+    /// With `false` handlers run to completion and their response is
+    /// discarded, for handlers whose side effects must finish. Either way
+    /// `Request::on_disconnect` completes when the client goes away.
+    pub fn cancel_on_disconnect(mut self, cancel: bool) -> Self {
+        self.cancel_on_disconnect = cancel;
+        self
+    }
+
+    /// Serve HTTPS with a PEM certificate chain and private key
+    ///
+    /// For small deployments without a reverse proxy in front. The files are
+    /// read when the server starts; a missing or invalid file fails `run()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// Server::from_config(router)
+    ///     .with_tls("/etc/letsencrypt/live/shop.test/fullchain.pem",
+    ///               "/etc/letsencrypt/live/shop.test/privkey.pem")
+    ///     .port(443)
+    ///     .redirect_http(80)
+    ///     .run()
+    ///     .await;
+    /// ```
+    pub fn with_tls(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.tls = Some((cert.into(), key.into()));
+        self
+    }
+
+    /// Listen for plain HTTP on `port` and redirect it to HTTPS
+    ///
+    /// Only used together with `with_tls`.
+    pub fn redirect_http(mut self, port: u16) -> Self {
+        self.https_redirect_port = Some(port);
+        self
+    }
+
+    /// Print the startup summary for `boot` instead of just the address
+    pub(crate) fn startup(mut self, boot: Boot) -> Self {
+        self.boot = Some(boot);
+        self
+    }
+
+    /// Split the server into its router and middleware for in-process dispatch
+    pub(crate) fn into_parts(self) -> (Arc<Router>, Arc<MiddlewareRegistry>) {
+        self.router.finalize(&self.middleware);
+        (self.router, Arc::new(self.middleware))
+    }
+
     fn get_addr(&self) -> SocketAddr {
         SocketAddr::new(self.host.parse().unwrap(), self.port)
     }
 
-    pub async fn run(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn run(mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut boot = self.boot.take();
+
         // Bootstrap cache (Redis with in-memory fallback)
+        let started = Instant::now();
         Cache::bootstrap().await;
+        if let Some(boot) = &mut boot {
+            boot.phases.record("cache", started);
+        }
 
         let addr: SocketAddr = self.get_addr();
+        let started = Instant::now();
         let listener = TcpListener::bind(addr).await?;
 
-        println!("Kit server running on http://{}", addr);
+        let mut redirect = None;
+        if self.tls.is_some() {
+            if let Some(port) = self.https_redirect_port {
+                let redirect_addr = SocketAddr::new(addr.ip(), port);
+                let listener = TcpListener::bind(redirect_addr).await?;
+                tokio::spawn(tls::redirect_http(listener, addr.port()));
+                redirect = Some(format!("http://{}", redirect_addr));
+            }
+        }
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        let address = format!("{}://{}", scheme, addr);
+
+        match boot {
+            Some(mut boot) => {
+                boot.phases.record("bind", started);
+                let summary = StartupSummary {
+                    address,
+                    redirect,
+                    environment: Config::environment(),
+                    routes: self.router.routes().len(),
+                    middleware: self.middleware.global_middleware_names().to_vec(),
+                    database: StartupSummary::database(),
+                    scheduled_tasks: boot.scheduled_tasks,
+                    workers: StartupSummary::workers(),
+                    phases: boot.phases,
+                };
+                println!("{}", summary);
+            }
+            None => {
+                if let Some(redirect) = redirect {
+                    println!("Redirecting {} to HTTPS", redirect);
+                }
+                println!("Kit server running on {}", address);
+            }
+        }
 
-        let router = self.router;
-        let middleware = Arc::new(self.middleware);
+        self.serve(listener).await
+    }
+
+    /// Accept and serve connections from `listener`
+    pub(crate) async fn serve(
+        self,
+        listener: TcpListener,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let (Some(_), Some(interval)) = (self.slow_request, self.slow_summary) {
+            metrics::spawn_summary(interval);
+        }
+
+        let acceptor = match &self.tls {
+            Some((cert, key)) => Some(tls::acceptor(cert, key)?),
+            None => None,
+        };
+
+        self.router.finalize(&self.middleware);
+        let connection = Connection {
+            router: self.router,
+            middleware: Arc::new(self.middleware),
+            batch: self.batch.map(Arc::new),
+            slow_request: self.slow_request,
+            cancel_on_disconnect: self.cancel_on_disconnect,
+        };
 
         loop {
-            let (stream, _) = listener.accept().await?;
-            let io = TokioIo::new(stream);
-            let router = router.clone();
-            let middleware = middleware.clone();
+            let (stream, remote_addr) = listener.accept().await?;
+            let connection = connection.clone();
+            let acceptor = acceptor.clone();
 
             tokio::spawn(async move {
-                let service = service_fn(move |req: hyper::Request<hyper::body::Incoming>| {
-                    let router = router.clone();
-                    let middleware = middleware.clone();
-                    async move { Ok::<_, Infallible>(handle_request(router, middleware, req).await) }
-                });
-
-                if let Err(err) = http1::Builder::new().serve_connection(io, service).await {
-                    eprintln!("Error serving connection: {:?}", err);
+                match acceptor {
+                    // Failed or stalled handshakes (scanners, clients not
+                    // trusting the certificate) never reach the application
+                    Some(acceptor) => {
+                        let handshake = tokio::time::timeout(
+                            tls::HANDSHAKE_TIMEOUT,
+                            acceptor.accept(stream),
+                        );
+                        if let Ok(Ok(stream)) = handshake.await {
+                            connection
+                                .serve(stream, remote_addr, ConnectionScheme::Https)
+                                .await;
+                        }
+                    }
+                    None => {
+                        connection
+                            .serve(stream, remote_addr, ConnectionScheme::Http)
+                            .await
+                    }
                 }
             });
         }
     }
 }
 
-async fn handle_request(
+impl Connection {
+    /// Serve the requests sent over `stream`
+    async fn serve<S>(self, stream: S, remote_addr: SocketAddr, scheme: ConnectionScheme)
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let Connection {
+            router,
+            middleware,
+            batch,
+            slow_request,
+            cancel_on_disconnect,
+        } = self;
+        let disconnect = CancellationToken::new();
+        let connection = disconnect.clone();
+        let service = service_fn(move |mut req: hyper::Request<hyper::body::Incoming>| {
+            req.extensions_mut().insert(RemoteAddr(remote_addr));
+            req.extensions_mut().insert(scheme);
+            req.extensions_mut().insert(Disconnect(connection.clone()));
+            let req = req.map(RequestBody::Incoming);
+            let router = router.clone();
+            let middleware = middleware.clone();
+            let batch = batch.clone();
+            let handle = async move {
+                match batch {
+                    Some(batch) if batch.matches(&req) => {
+                        batch.handle(router, middleware, req).await
+                    }
+                    _ => handle_measured_request(router, middleware, slow_request, req).await,
+                }
+            };
+            async move {
+                // hyper drops this future when the client goes away;
+                // a spawned task keeps running to completion instead
+                let response = if cancel_on_disconnect {
+                    handle.await
+                } else {
+                    tokio::spawn(handle).await.unwrap_or_else(|_| {
+                        HttpResponse::text("Internal Server Error")
+                            .status(500)
+                            .into_hyper()
+                    })
+                };
+                Ok::<_, Infallible>(response)
+            }
+        });
+
+        // Upgrades let WebSocket routes take over the connection
+        let result = http1::Builder::new()
+            .serve_connection(TokioIo::new(stream), service)
+            .with_upgrades()
+            .await;
+        disconnect.cancel();
+
+        // A client closing the connection mid-request isn't an error
+        if let Err(err) = result {
+            if !err.is_incomplete_message() {
+                eprintln!("Error serving connection: {:?}", err);
+            }
+        }
+    }
+}
+
+/// Handle a request while collecting metrics, logging it if it was slow
+async fn handle_measured_request(
+    router: Arc<Router>,
+    middleware_registry: Arc<MiddlewareRegistry>,
+    slow_request: Option<Duration>,
+    req: hyper::Request<RequestBody>,
+) -> hyper::Response<ResponseBody> {
+    let Some(threshold) = slow_request else {
+        return handle_request(router, middleware_registry, req).await;
+    };
+
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let started = Instant::now();
+    let request_metrics = Arc::new(RequestMetrics::default());
+
+    let response = metrics::scope(
+        request_metrics.clone(),
+        handle_request(router.clone(), middleware_registry, req),
+    )
+    .await;
+
+    let duration = started.elapsed();
+    if duration >= threshold {
+        metrics::report_slow(SlowRequest {
+            route: router
+                .find(&method, &path)
+                .and_then(|matched| route_name(matched.pattern)),
+            method: method.to_string(),
+            path,
+            status: response.status().as_u16(),
+            duration,
+            user_id: request_metrics.user_id(),
+            queries: request_metrics.queries(),
+        });
+    }
+
+    response
+}
+
+pub(crate) async fn handle_request(
     router: Arc<Router>,
     middleware_registry: Arc<MiddlewareRegistry>,
-    req: hyper::Request<hyper::body::Incoming>,
-) -> hyper::Response<Full<Bytes>> {
+    req: hyper::Request<RequestBody>,
+) -> hyper::Response<ResponseBody> {
     let method = req.method().clone();
     let path = req.uri().path().to_string();
     let query = req.uri().query().unwrap_or("");
@@ -129,6 +437,13 @@ async fn handle_request(
         return health_response(query).await;
     }
 
+    let profiling = profile::requested(query);
+
+    // Reject bodies over the size limit before reading any of them
+    if let Err(err) = BodyLimits::from_config().check_content_length(req.headers()) {
+        return HttpResponse::from(err).into_hyper();
+    }
+
     // Set up Inertia context from request headers
     let is_inertia = req
         .headers()
@@ -145,37 +460,64 @@ async fn handle_request(
 
     InertiaContext::set(InertiaContext {
         path: path.clone(),
+        query: req.uri().query().map(str::to_string),
         is_inertia,
+        wants_json: crate::http::wants_json(req.headers()),
         version: inertia_version,
+        partial: PartialReload::from_headers(req.headers()).filter(|_| is_inertia),
     });
 
-    let response = match router.match_route(&method, &path) {
-        Some((handler, params)) => {
-            let request = Request::new(req).with_params(params);
+    // Continue the caller's trace, if it sent one
+    let trace_parent = telemetry::remote_parent(req.headers());
+
+    // File responses answer byte ranges, e.g. to resume a download
+    let range = req
+        .headers()
+        .get(hyper::header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let response = match router.find(&method, &path) {
+        Some(matched) => {
+            let request = Request::from_hyper(req)
+                .with_params(matched.params)
+                .with_binding_keys(matched.binding_keys);
 
-            // Build middleware chain
-            let mut chain = MiddlewareChain::new();
+            // Use the chain composed by `Router::finalize`, or build it
+            let chain = matched.chain.unwrap_or_else(|| {
+                let mut chain = MiddlewareChain::new();
 
-            // 1. Add global middleware
-            chain.extend(middleware_registry.global_middleware().iter().cloned());
+                // 1. Add global middleware, minus any the route's groups opted out of
+                let excluded = router.get_excluded_middleware(matched.pattern);
+                chain.extend(middleware_registry.global_middleware_except(excluded));
 
-            // 2. Add route-level middleware (already boxed)
-            let route_middleware = router.get_route_middleware(&path);
-            chain.extend(route_middleware);
+                // 2. Add route-level middleware (already boxed)
+                chain.extend(router.get_route_middleware(matched.pattern));
+                chain.compose(matched.handler)
+            });
 
-            // 3. Execute chain with handler
-            let response = chain.execute(request, handler).await;
+            // 3. Execute chain with handler, timing it for `Server-Timing`
+            let context = ErrorContext::new(request.inner().headers(), Some(matched.pattern));
+            let execute = telemetry::request(
+                method.as_str(),
+                Some(matched.pattern),
+                &path,
+                trace_parent,
+                context.scope(strict::scope(chain(request))),
+            );
+            let response = match profiling {
+                Some(detail) => profile::profiled(detail, execute).await,
+                None => execute.await,
+            };
 
             // Unwrap the Result - both Ok and Err contain HttpResponse
-            let http_response = response.unwrap_or_else(|e| e);
-            http_response.into_hyper()
+            let http_response = error_page::render(response.unwrap_or_else(|e| e));
+            http_response.with_range(range.as_deref()).into_hyper()
         }
         None => {
-            // Check for fallback handler
-            if let Some((fallback_handler, fallback_middleware)) = router.get_fallback() {
-                let request = Request::new(req).with_params(std::collections::HashMap::new());
-
-                // Build middleware chain for fallback
+            // Check for a fallback handler, using its composed chain if finalized
+            let fallback = router.fallback_chain().or_else(|| {
+                let (fallback_handler, fallback_middleware) = router.get_fallback()?;
                 let mut chain = MiddlewareChain::new();
 
                 // 1. Add global middleware
@@ -183,16 +525,25 @@ async fn handle_request(
 
                 // 2. Add fallback-specific middleware
                 chain.extend(fallback_middleware);
+                Some(chain.compose(fallback_handler))
+            });
+
+            if let Some(chain) = fallback {
+                let request =
+                    Request::from_hyper(req).with_params(std::collections::HashMap::new());
 
                 // 3. Execute chain with fallback handler
-                let response = chain.execute(request, fallback_handler).await;
+                let context = ErrorContext::new(request.inner().headers(), None);
+                let execute = context.scope(strict::scope(chain(request)));
+                let response =
+                    telemetry::request(method.as_str(), None, &path, trace_parent, execute).await;
 
                 // Unwrap the Result - both Ok and Err contain HttpResponse
-                let http_response = response.unwrap_or_else(|e| e);
-                http_response.into_hyper()
+                let http_response = error_page::render(response.unwrap_or_else(|e| e));
+                http_response.with_range(range.as_deref()).into_hyper()
             } else {
                 // No fallback defined, return default 404
-                HttpResponse::text("404 Not Found").status(404).into_hyper()
+                error_page::not_found().into_hyper()
             }
         }
     };
@@ -206,7 +557,7 @@ async fn handle_request(
 /// Built-in health check endpoint at /_kit/health
 /// Returns {"status": "ok", "timestamp": "..."} by default
 /// Add ?db=true to also check database connectivity (/_kit/health?db=true)
-async fn health_response(query: &str) -> hyper::Response<Full<Bytes>> {
+async fn health_response(query: &str) -> hyper::Response<ResponseBody> {
     use chrono::Utc;
     use serde_json::json;
 
@@ -236,7 +587,7 @@ async fn health_response(query: &str) -> hyper::Response<Full<Bytes>> {
     hyper::Response::builder()
         .status(200)
         .header("Content-Type", "application/json")
-        .body(Full::new(Bytes::from(body)))
+        .body(Full::new(Bytes::from(body)).into())
         .unwrap()
 }
 
@@ -259,3 +610,116 @@ async fn check_database_health() -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Response;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpStream;
+
+    static NOTIFIED: AtomicBool = AtomicBool::new(false);
+    static CANCELLED: AtomicBool = AtomicBool::new(false);
+    static FINISHED: AtomicBool = AtomicBool::new(false);
+
+    struct SetOnDrop(&'static AtomicBool);
+
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    async fn watch(req: Request) -> Response {
+        let disconnected = req.on_disconnect();
+        tokio::spawn(async move {
+            disconnected.await;
+            NOTIFIED.store(true, Ordering::SeqCst);
+        });
+        let _guard = SetOnDrop(&CANCELLED);
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        Ok(HttpResponse::text("too late"))
+    }
+
+    async fn finish(_req: Request) -> Response {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        FINISHED.store(true, Ordering::SeqCst);
+        Ok(HttpResponse::text("done"))
+    }
+
+    async fn serve(server: Server) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(server.serve(listener));
+        addr
+    }
+
+    /// Send a request and hang up before the response arrives
+    async fn abandon(addr: SocketAddr, path: &str) {
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+        client.write_all(request.as_bytes()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    async fn eventually(flag: &AtomicBool) -> bool {
+        for _ in 0..100 {
+            if flag.load(Ordering::SeqCst) {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn cancels_handlers_and_signals_on_disconnect() {
+        let router = crate::get!("/watch", watch).register(Router::new());
+        let addr = serve(Server::new(router)).await;
+
+        abandon(addr, "/watch").await;
+
+        assert!(eventually(&CANCELLED).await);
+        assert!(eventually(&NOTIFIED).await);
+    }
+
+    #[tokio::test]
+    async fn handlers_can_run_to_completion_after_disconnect() {
+        let router = crate::get!("/finish", finish).register(Router::new());
+        let addr = serve(Server::new(router).cancel_on_disconnect(false)).await;
+
+        abandon(addr, "/finish").await;
+
+        assert!(eventually(&FINISHED).await);
+    }
+
+    #[test]
+    fn fake_requests_never_disconnect() {
+        let req = Request::fake().build();
+        assert!(!req.is_disconnected());
+    }
+
+    async fn secure(req: Request) -> Response {
+        Ok(HttpResponse::text(req.url()))
+    }
+
+    #[tokio::test]
+    async fn serves_https_with_tls() {
+        let (cert, key) = crate::http::tls::tests::self_signed("server-tls");
+        let router = crate::get!("/secure", secure).register(Router::new());
+        let addr = serve(Server::new(router).with_tls(&cert, &key)).await;
+
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .build()
+            .unwrap();
+        let url = format!("https://localhost:{}/secure", addr.port());
+        let body = client.get(&url).send().await.unwrap().text().await.unwrap();
+        // The request knows it came in over TLS, so its URLs are https://
+        assert_eq!(body, url);
+
+        std::fs::remove_file(cert).ok();
+        std::fs::remove_file(key).ok();
+    }
+}
@@ -1,5 +1,6 @@
 //! Session configuration
 
+use crate::http::cookie::CookieConfig;
 use std::time::Duration;
 
 /// Session configuration
@@ -19,18 +20,34 @@ pub struct SessionConfig {
     pub cookie_same_site: String,
     /// Database table name for sessions
     pub table_name: String,
+    /// Storage driver: `database`, `cache` or `memory`
+    pub driver: String,
+    /// Encrypt stored session data with `APP_KEY`
+    pub encrypt: bool,
+    /// Still read unencrypted sessions while `encrypt` is on
+    ///
+    /// Only for the switch to encryption: plaintext sessions can be planted
+    /// by anyone with write access to the store. Turn it off once sessions
+    /// from before the switch have expired (see `lifetime`).
+    pub encrypt_allow_plaintext: bool,
 }
 
 impl Default for SessionConfig {
+    /// Cookie security comes from `CookieConfig`, so it depends on the
+    /// environment
     fn default() -> Self {
+        let cookies = CookieConfig::default();
         Self {
             lifetime: Duration::from_secs(120 * 60), // 2 hours (120 minutes)
             cookie_name: "kit_session".to_string(),
             cookie_path: "/".to_string(),
-            cookie_secure: true,
+            cookie_secure: cookies.secure,
             cookie_http_only: true,
-            cookie_same_site: "Lax".to_string(),
+            cookie_same_site: cookies.same_site.as_str().to_string(),
             table_name: "sessions".to_string(),
+            driver: "database".to_string(),
+            encrypt: false,
+            encrypt_allow_plaintext: false,
         }
     }
 }
@@ -46,17 +63,30 @@ impl SessionConfig {
     /// Environment variables:
     /// - `SESSION_LIFETIME`: Session lifetime in minutes (default: 120)
     /// - `SESSION_COOKIE`: Cookie name (default: kit_session)
-    /// - `SESSION_SECURE`: Set Secure flag (default: true)
+    /// - `SESSION_SECURE`: Set Secure flag (default: from `CookieConfig`)
     /// - `SESSION_PATH`: Cookie path (default: /)
-    /// - `SESSION_SAME_SITE`: SameSite attribute (default: Lax)
+    /// - `SESSION_SAME_SITE`: SameSite attribute (default: from `CookieConfig`)
+    /// - `SESSION_DRIVER`: `database`, `cache` or `memory` (default: database)
+    /// - `SESSION_ENCRYPT`: Encrypt stored session data with `APP_KEY` (default: false)
+    /// - `SESSION_ENCRYPT_ALLOW_PLAINTEXT`: Still read unencrypted sessions
+    ///   while switching to encryption (default: false)
     pub fn from_env() -> Self {
         let lifetime_minutes: u64 = crate::env_optional("SESSION_LIFETIME")
             .and_then(|s: String| s.parse().ok())
             .unwrap_or(120);
 
+        let cookies = CookieConfig::default();
         let cookie_secure = crate::env_optional("SESSION_SECURE")
+            .filter(|s: &String| !s.is_empty())
+            .map(|s: String| s.to_lowercase() == "true" || s == "1")
+            .unwrap_or(cookies.secure);
+
+        let encrypt = crate::env_optional("SESSION_ENCRYPT")
+            .map(|s: String| s.to_lowercase() == "true" || s == "1")
+            .unwrap_or(false);
+        let encrypt_allow_plaintext = crate::env_optional("SESSION_ENCRYPT_ALLOW_PLAINTEXT")
             .map(|s: String| s.to_lowercase() == "true" || s == "1")
-            .unwrap_or(true);
+            .unwrap_or(false);
 
         Self {
             lifetime: Duration::from_secs(lifetime_minutes * 60),
@@ -67,8 +97,12 @@ impl SessionConfig {
             cookie_secure,
             cookie_http_only: true, // Always true for security
             cookie_same_site: crate::env_optional("SESSION_SAME_SITE")
-                .unwrap_or_else(|| "Lax".to_string()),
+                .unwrap_or_else(|| cookies.same_site.as_str().to_string()),
             table_name: "sessions".to_string(),
+            driver: crate::env_optional("SESSION_DRIVER")
+                .unwrap_or_else(|| "database".to_string()),
+            encrypt,
+            encrypt_allow_plaintext,
         }
     }
 
@@ -89,4 +123,22 @@ impl SessionConfig {
         self.cookie_secure = secure;
         self
     }
+
+    /// Set the storage driver: `database`, `cache` or `memory`
+    pub fn driver(mut self, driver: impl Into<String>) -> Self {
+        self.driver = driver.into();
+        self
+    }
+
+    /// Set whether stored session data is encrypted with `APP_KEY`
+    pub fn encrypt(mut self, encrypt: bool) -> Self {
+        self.encrypt = encrypt;
+        self
+    }
+
+    /// Set whether unencrypted sessions are still read while encrypting
+    pub fn encrypt_allow_plaintext(mut self, allow: bool) -> Self {
+        self.encrypt_allow_plaintext = allow;
+        self
+    }
 }
@@ -0,0 +1,200 @@
+//! Cache-backed session storage driver
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cache::{Cache, CacheStore};
+use crate::crypt::Encrypter;
+use crate::error::FrameworkError;
+use crate::session::store::{SessionData, SessionStore};
+
+/// Session driver storing sessions in a cache store
+///
+/// Uses the application's `Cache` store (Redis when available, otherwise
+/// in-memory) unless given a store. Sessions are stored under
+/// `session:{id}` and expire through the cache TTL, so `gc()` has nothing
+/// to do.
+pub struct CacheSessionDriver {
+    lifetime: Duration,
+    store: Option<Arc<dyn CacheStore>>,
+    encrypter: Option<Encrypter>,
+    allow_plaintext: bool,
+}
+
+/// What is stored for each session
+#[derive(Serialize, Deserialize)]
+struct CachedSession {
+    data: HashMap<String, serde_json::Value>,
+    user_id: Option<i64>,
+    csrf_token: String,
+}
+
+impl CacheSessionDriver {
+    /// Create a driver using the application's cache store
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            lifetime,
+            store: None,
+            encrypter: None,
+            allow_plaintext: false,
+        }
+    }
+
+    /// Create a driver using the given cache store
+    pub fn with_store(lifetime: Duration, store: Arc<dyn CacheStore>) -> Self {
+        Self {
+            lifetime,
+            store: Some(store),
+            encrypter: None,
+            allow_plaintext: false,
+        }
+    }
+
+    /// Encrypt stored sessions, see `SessionConfig::encrypt`
+    ///
+    /// Unencrypted sessions are rejected unless `allow_plaintext` is set.
+    pub fn encrypted(mut self, encrypter: Option<Encrypter>) -> Self {
+        self.encrypter = encrypter;
+        self
+    }
+
+    /// Still read sessions stored before encryption was turned on, see
+    /// `SessionConfig::encrypt_allow_plaintext`
+    pub fn allow_plaintext(mut self, allow: bool) -> Self {
+        self.allow_plaintext = allow;
+        self
+    }
+
+    /// The cache store, resolved per call since the cache is bootstrapped
+    /// after middleware is registered
+    fn store(&self) -> Result<Arc<dyn CacheStore>, FrameworkError> {
+        match &self.store {
+            Some(store) => Ok(store.clone()),
+            None => Cache::store(),
+        }
+    }
+}
+
+fn key(id: &str) -> String {
+    format!("session:{}", id)
+}
+
+#[async_trait]
+impl SessionStore for CacheSessionDriver {
+    async fn read(&self, id: &str) -> Result<Option<SessionData>, FrameworkError> {
+        let Some(mut raw) = self.store()?.get_raw(&key(id)).await? else {
+            return Ok(None);
+        };
+        if let Some(encrypter) = &self.encrypter {
+            // Sessions from another APP_KEY can't be read; start a new one
+            let Ok(json) = encrypter.open_json(raw, self.allow_plaintext) else {
+                return Ok(None);
+            };
+            raw = json;
+        }
+        let cached: CachedSession = serde_json::from_str(&raw)
+            .map_err(|e| FrameworkError::internal(format!("Session deserialize error: {}", e)))?;
+
+        Ok(Some(SessionData {
+            id: id.to_string(),
+            data: cached.data,
+            user_id: cached.user_id,
+            csrf_token: cached.csrf_token,
+            dirty: false,
+        }))
+    }
+
+    async fn write(&self, session: &SessionData) -> Result<(), FrameworkError> {
+        let cached = CachedSession {
+            data: session.data.clone(),
+            user_id: session.user_id,
+            csrf_token: session.csrf_token.clone(),
+        };
+        let mut raw = serde_json::to_string(&cached)
+            .map_err(|e| FrameworkError::internal(format!("Session serialize error: {}", e)))?;
+        if let Some(encrypter) = &self.encrypter {
+            raw = encrypter.encrypt_string(&raw)?;
+        }
+
+        self.store()?
+            .put_raw(&key(&session.id), &raw, Some(self.lifetime))
+            .await
+    }
+
+    async fn destroy(&self, id: &str) -> Result<(), FrameworkError> {
+        self.store()?.forget(&key(id)).await?;
+        Ok(())
+    }
+
+    async fn gc(&self) -> Result<u64, FrameworkError> {
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::InMemoryCache;
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let driver =
+            CacheSessionDriver::with_store(Duration::from_secs(60), Arc::new(InMemoryCache::new()));
+        let mut session = SessionData::new("abc".to_string(), "token".to_string());
+        session.put("name", "Ada");
+        session.user_id = Some(7);
+
+        driver.write(&session).await.unwrap();
+        let read = driver.read("abc").await.unwrap().unwrap();
+        assert_eq!(read.get::<String>("name").as_deref(), Some("Ada"));
+        assert_eq!((read.user_id, read.csrf_token.as_str()), (Some(7), "token"));
+
+        driver.destroy("abc").await.unwrap();
+        assert!(driver.read("abc").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_sessions() {
+        let store = Arc::new(InMemoryCache::new());
+        let mut session = SessionData::new("abc".to_string(), "token".to_string());
+        session.put("email", "ada@example.com");
+
+        let driver = CacheSessionDriver::with_store(Duration::from_secs(60), store.clone())
+            .encrypted(Some(Encrypter::new("secret")));
+        driver.write(&session).await.unwrap();
+        let stored = store.get_raw("session:abc").await.unwrap().unwrap();
+        assert!(!stored.contains("ada@example.com"));
+        let read = driver.read("abc").await.unwrap().unwrap();
+        assert_eq!(
+            read.get::<String>("email").as_deref(),
+            Some("ada@example.com")
+        );
+
+        // A rotated APP_KEY starts a new session instead of failing
+        let rotated = CacheSessionDriver::with_store(Duration::from_secs(60), store.clone())
+            .encrypted(Some(Encrypter::new("rotated")));
+        assert!(rotated.read("abc").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_sessions_are_rejected_when_encrypted() {
+        let store = Arc::new(InMemoryCache::new());
+        let planted = r#"{"data":{},"user_id":1,"csrf_token":"token"}"#;
+        store.put_raw("session:abc", planted, None).await.unwrap();
+
+        let driver = CacheSessionDriver::with_store(Duration::from_secs(60), store.clone())
+            .encrypted(Some(Encrypter::new("secret")));
+        assert!(driver.read("abc").await.unwrap().is_none());
+
+        // Read while migrating to encryption, and encrypted once written back
+        let migrating = driver.allow_plaintext(true);
+        let session = migrating.read("abc").await.unwrap().unwrap();
+        assert_eq!(session.user_id, Some(1));
+        migrating.write(&session).await.unwrap();
+        let stored = store.get_raw("session:abc").await.unwrap().unwrap();
+        assert!(!stored.contains("user_id"));
+    }
+}
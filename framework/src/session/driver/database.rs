@@ -6,6 +6,7 @@ use sea_orm::{QueryFilter, Set};
 use std::collections::HashMap;
 use std::time::Duration;
 
+use crate::crypt::Encrypter;
 use crate::database::DB;
 use crate::error::FrameworkError;
 use crate::session::store::{SessionData, SessionStore};
@@ -15,17 +16,39 @@ use crate::session::store::{SessionData, SessionStore};
 /// Stores sessions in a `sessions` table with the following schema:
 /// - id: VARCHAR (primary key) - session ID
 /// - user_id: BIGINT (nullable) - authenticated user ID
-/// - payload: TEXT - JSON serialized session data
+/// - payload: TEXT - JSON serialized session data, encrypted with
+///   `SESSION_ENCRYPT`
 /// - csrf_token: VARCHAR - CSRF protection token
 /// - last_activity: TIMESTAMP - last access time
 pub struct DatabaseSessionDriver {
     lifetime: Duration,
+    encrypter: Option<Encrypter>,
+    allow_plaintext: bool,
 }
 
 impl DatabaseSessionDriver {
     /// Create a new database session driver
     pub fn new(lifetime: Duration) -> Self {
-        Self { lifetime }
+        Self {
+            lifetime,
+            encrypter: None,
+            allow_plaintext: false,
+        }
+    }
+
+    /// Encrypt session payloads, see `SessionConfig::encrypt`
+    ///
+    /// Unencrypted payloads are rejected unless `allow_plaintext` is set.
+    pub fn encrypted(mut self, encrypter: Option<Encrypter>) -> Self {
+        self.encrypter = encrypter;
+        self
+    }
+
+    /// Still read payloads stored before encryption was turned on, see
+    /// `SessionConfig::encrypt_allow_plaintext`
+    pub fn allow_plaintext(mut self, allow: bool) -> Self {
+        self.allow_plaintext = allow;
+        self
     }
 }
 
@@ -52,8 +75,19 @@ impl SessionStore for DatabaseSessionDriver {
             }
 
             // Parse the payload
+            let payload = match &self.encrypter {
+                // Payloads from another APP_KEY, or plaintext ones that
+                // aren't allowed, can't be trusted; start a new session
+                Some(encrypter) => {
+                    match encrypter.open_json(session.payload, self.allow_plaintext) {
+                        Ok(payload) => payload,
+                        Err(_) => return Ok(None),
+                    }
+                }
+                None => session.payload,
+            };
             let data: HashMap<String, serde_json::Value> =
-                serde_json::from_str(&session.payload).unwrap_or_default();
+                serde_json::from_str(&payload).unwrap_or_default();
 
             Ok(Some(SessionData {
                 id: session.id,
@@ -70,8 +104,11 @@ impl SessionStore for DatabaseSessionDriver {
     async fn write(&self, session: &SessionData) -> Result<(), FrameworkError> {
         let db = DB::connection()?;
 
-        let payload = serde_json::to_string(&session.data)
+        let mut payload = serde_json::to_string(&session.data)
             .map_err(|e| FrameworkError::internal(format!("Session serialize error: {}", e)))?;
+        if let Some(encrypter) = &self.encrypter {
+            payload = encrypter.encrypt_string(&payload)?;
+        }
 
         let now = chrono::Utc::now().naive_utc();
 
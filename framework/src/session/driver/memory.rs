@@ -0,0 +1,74 @@
+//! In-memory session storage driver
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::FrameworkError;
+use crate::session::store::{SessionData, SessionStore};
+
+/// Session driver keeping sessions in process memory
+///
+/// Sessions are lost on restart and aren't shared between processes, so
+/// this is meant for tests and local development.
+pub struct MemorySessionDriver {
+    lifetime: Duration,
+    sessions: Mutex<HashMap<String, (SessionData, Instant)>>,
+}
+
+impl MemorySessionDriver {
+    /// Create an empty in-memory driver
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            lifetime,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_expired(&self, last_activity: Instant) -> bool {
+        last_activity.elapsed() > self.lifetime
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionDriver {
+    async fn read(&self, id: &str) -> Result<Option<SessionData>, FrameworkError> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        match sessions.get(id) {
+            Some((_, last_activity)) if self.is_expired(*last_activity) => {
+                sessions.remove(id);
+                Ok(None)
+            }
+            Some((session, _)) => {
+                let mut session = session.clone();
+                session.mark_clean();
+                Ok(Some(session))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn write(&self, session: &SessionData) -> Result<(), FrameworkError> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(session.id.clone(), (session.clone(), Instant::now()));
+        Ok(())
+    }
+
+    async fn destroy(&self, id: &str) -> Result<(), FrameworkError> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(id);
+        Ok(())
+    }
+
+    async fn gc(&self) -> Result<u64, FrameworkError> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let before = sessions.len();
+        sessions.retain(|_, (_, last_activity)| !self.is_expired(*last_activity));
+        Ok((before - sessions.len()) as u64)
+    }
+}
@@ -1,5 +1,9 @@
 //! Session storage drivers
 
+pub mod cache;
 pub mod database;
+pub mod memory;
 
+pub use cache::CacheSessionDriver;
 pub use database::DatabaseSessionDriver;
+pub use memory::MemorySessionDriver;
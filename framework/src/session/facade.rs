@@ -0,0 +1,188 @@
+//! The `Session` handle for the current request
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::middleware::{generate_csrf_token, generate_session_id};
+use super::store::SessionData;
+
+tokio::task_local! {
+    static CURRENT_SESSION: Session;
+}
+
+/// The session of the current request
+///
+/// `SessionMiddleware` loads the session before the handler runs and saves
+/// it afterwards. Get it from the request with `req.session()`, or anywhere
+/// in the request with `Session::current()`. Clones share the same data.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Request, Response, Redirect};
+///
+/// pub async fn store(req: Request) -> Response {
+///     let session = req.session();
+///     session.put("theme", "dark");
+///     session.flash("success", "Preferences saved!");
+///
+///     let visits: i64 = session.get("visits").unwrap_or(0);
+///     session.put("visits", visits + 1);
+///
+///     Redirect::to("/settings").into()
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Session {
+    data: Arc<Mutex<SessionData>>,
+}
+
+impl Session {
+    /// Wrap session data
+    pub fn new(data: SessionData) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(data)),
+        }
+    }
+
+    /// The session of the current request, if `SessionMiddleware` runs
+    pub fn current() -> Option<Session> {
+        CURRENT_SESSION.try_with(Session::clone).ok()
+    }
+
+    /// Run `future` with this session as `Session::current()`
+    ///
+    /// Used by `SessionMiddleware`; in tests it sets up a session without
+    /// the middleware.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_SESSION.scope(self, future).await
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SessionData> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The session ID, as stored in the session cookie
+    pub fn id(&self) -> String {
+        self.lock().id.clone()
+    }
+
+    /// The CSRF token for this session
+    pub fn csrf_token(&self) -> String {
+        self.lock().csrf_token.clone()
+    }
+
+    /// The authenticated user ID, if any
+    pub fn user_id(&self) -> Option<i64> {
+        self.lock().user_id
+    }
+
+    /// Get a value from the session
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.lock().get(key)
+    }
+
+    /// Put a value into the session
+    pub fn put<T: Serialize>(&self, key: &str, value: T) {
+        self.lock().put(key, value);
+    }
+
+    /// Remove a value from the session, returning it
+    pub fn forget(&self, key: &str) -> Option<serde_json::Value> {
+        self.lock().forget(key)
+    }
+
+    /// Check if the session has a key
+    pub fn has(&self, key: &str) -> bool {
+        self.lock().has(key)
+    }
+
+    /// Get a value and remove it from the session
+    pub fn pull<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let mut data = self.lock();
+        let value = data.get(key);
+        data.forget(key);
+        value
+    }
+
+    /// Flash a value for the next request
+    pub fn flash<T: Serialize>(&self, key: &str, value: T) {
+        self.lock().flash(key, value);
+    }
+
+    /// Get a value flashed by the previous request
+    pub fn get_flash<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.lock().get_flash(key)
+    }
+
+    /// All values flashed by the previous request, by key
+    pub fn flashed(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.lock().flashed()
+    }
+
+    /// Remove all data, keeping the session ID
+    pub fn flush(&self) {
+        self.lock().flush();
+    }
+
+    /// Give the session a new ID, keeping its data
+    ///
+    /// Call this after login to prevent session fixation. The old session is
+    /// removed from the store when the request finishes.
+    pub fn regenerate(&self) {
+        let mut data = self.lock();
+        data.id = generate_session_id();
+        data.dirty = true;
+    }
+
+    /// Remove all data and start over with a new ID and CSRF token
+    pub fn invalidate(&self) {
+        let mut data = self.lock();
+        data.flush();
+        data.id = generate_session_id();
+        data.csrf_token = generate_csrf_token();
+    }
+
+    /// Read or change the session data directly
+    pub fn update<R>(&self, f: impl FnOnce(&mut SessionData) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    /// A copy of the session data
+    pub fn data(&self) -> SessionData {
+        self.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scope_shares_the_session() {
+        let session = Session::new(SessionData::new("abc".to_string(), "token".to_string()));
+        assert!(Session::current().is_none());
+
+        session
+            .clone()
+            .scope(async {
+                tokio::task::yield_now().await;
+                Session::current().unwrap().put("name", "Ada");
+                super::super::session_mut(|data| data.put("count", 2));
+            })
+            .await;
+
+        assert_eq!(session.get::<String>("name").as_deref(), Some("Ada"));
+        assert_eq!(session.pull::<i64>("count"), Some(2));
+        assert!(!session.has("count"));
+
+        session.regenerate();
+        assert_ne!(session.id(), "abc");
+        assert!(session.has("name"));
+
+        session.invalidate();
+        assert!(!session.has("name"));
+        assert_ne!(session.csrf_token(), "token");
+    }
+}
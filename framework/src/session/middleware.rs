@@ -1,26 +1,26 @@
 //! Session middleware for Kit framework
 
+use crate::crypt::Crypt;
 use crate::http::cookie::{Cookie, SameSite};
 use crate::http::Response;
 use crate::middleware::{Middleware, Next};
 use crate::Request;
 use async_trait::async_trait;
 use rand::Rng;
-use std::cell::RefCell;
 use std::sync::Arc;
 
 use super::config::SessionConfig;
-use super::driver::DatabaseSessionDriver;
+use super::driver::{CacheSessionDriver, DatabaseSessionDriver, MemorySessionDriver};
+use super::facade::Session;
 use super::store::{SessionData, SessionStore};
 
-// Thread-local session context for storing the current request's session data
-thread_local! {
-    static SESSION_CONTEXT: RefCell<Option<SessionData>> = const { RefCell::new(None) };
-}
+/// Length of generated session IDs
+const SESSION_ID_LENGTH: usize = 40;
 
 /// Get the current session (read-only)
 ///
-/// Returns a clone of the current session data if available.
+/// Returns a copy of the current request's session data if available.
+/// See also `Session::current()`.
 ///
 /// # Example
 ///
@@ -32,7 +32,7 @@ thread_local! {
 /// }
 /// ```
 pub fn session() -> Option<SessionData> {
-    SESSION_CONTEXT.with(|ctx| ctx.borrow().clone())
+    Session::current().map(|session| session.data())
 }
 
 /// Get the current session and modify it
@@ -50,29 +50,7 @@ pub fn session_mut<F, R>(f: F) -> Option<R>
 where
     F: FnOnce(&mut SessionData) -> R,
 {
-    SESSION_CONTEXT.with(|ctx| {
-        let mut session_opt = ctx.borrow_mut();
-        session_opt.as_mut().map(f)
-    })
-}
-
-/// Set the session context for the current request
-pub fn set_session(session: SessionData) {
-    SESSION_CONTEXT.with(|ctx| {
-        *ctx.borrow_mut() = Some(session);
-    });
-}
-
-/// Clear the session context
-pub fn clear_session() {
-    SESSION_CONTEXT.with(|ctx| {
-        *ctx.borrow_mut() = None;
-    });
-}
-
-/// Take the session out of the context (for saving)
-pub fn take_session() -> Option<SessionData> {
-    SESSION_CONTEXT.with(|ctx| ctx.borrow_mut().take())
+    Session::current().map(|session| session.update(f))
 }
 
 /// Generate a cryptographically secure session ID
@@ -82,7 +60,7 @@ pub fn generate_session_id() -> String {
     let mut rng = rand::thread_rng();
     const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
 
-    (0..40)
+    (0..SESSION_ID_LENGTH)
         .map(|_| {
             let idx = rng.gen_range(0..CHARSET.len());
             CHARSET[idx] as char
@@ -97,12 +75,21 @@ pub fn generate_csrf_token() -> String {
     generate_session_id()
 }
 
+/// Whether a cookie value looks like an ID from `generate_session_id`
+fn is_valid_session_id(id: &str) -> bool {
+    id.len() == SESSION_ID_LENGTH
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+}
+
 /// Session middleware
 ///
 /// Handles session lifecycle:
 /// 1. Reads session ID from cookie
 /// 2. Loads session data from storage
-/// 3. Makes session available during request
+/// 3. Makes session available during request (`req.session()`,
+///    `Session::current()`)
 /// 4. Saves session after request
 /// 5. Sets session cookie on response
 pub struct SessionMiddleware {
@@ -112,8 +99,37 @@ pub struct SessionMiddleware {
 
 impl SessionMiddleware {
     /// Create a new session middleware with the given configuration
+    ///
+    /// The store is picked by `config.driver`: `database` (default),
+    /// `cache` (the `Cache` store, i.e. Redis when available) or `memory`.
+    ///
+    /// # Panics
+    ///
+    /// If `config.encrypt` is set without an `APP_KEY`.
     pub fn new(config: SessionConfig) -> Self {
-        let store = Arc::new(DatabaseSessionDriver::new(config.lifetime));
+        let encrypter = config
+            .encrypt
+            .then(|| Crypt::encrypter().expect("SESSION_ENCRYPT needs APP_KEY to be set"));
+        let allow_plaintext = config.encrypt && config.encrypt_allow_plaintext;
+        if allow_plaintext {
+            eprintln!(
+                "Warning: SESSION_ENCRYPT_ALLOW_PLAINTEXT accepts unencrypted sessions; \
+                 turn it off once sessions from before encryption have expired"
+            );
+        }
+        let store: Arc<dyn SessionStore> = match config.driver.to_lowercase().as_str() {
+            "cache" | "redis" => Arc::new(
+                CacheSessionDriver::new(config.lifetime)
+                    .encrypted(encrypter)
+                    .allow_plaintext(allow_plaintext),
+            ),
+            "memory" => Arc::new(MemorySessionDriver::new(config.lifetime)),
+            _ => Arc::new(
+                DatabaseSessionDriver::new(config.lifetime)
+                    .encrypted(encrypter)
+                    .allow_plaintext(allow_plaintext),
+            ),
+        };
         Self { config, store }
     }
 
@@ -123,71 +139,66 @@ impl SessionMiddleware {
     }
 
     fn create_session_cookie(&self, session_id: &str) -> Cookie {
-        let mut cookie = Cookie::new(&self.config.cookie_name, session_id)
+        Cookie::new(&self.config.cookie_name, session_id)
             .http_only(self.config.cookie_http_only)
             .secure(self.config.cookie_secure)
+            .same_site(SameSite::parse(&self.config.cookie_same_site))
             .path(&self.config.cookie_path)
-            .max_age(self.config.lifetime);
-
-        cookie = match self.config.cookie_same_site.to_lowercase().as_str() {
-            "strict" => cookie.same_site(SameSite::Strict),
-            "none" => cookie.same_site(SameSite::None),
-            _ => cookie.same_site(SameSite::Lax),
-        };
-
-        cookie
+            .max_age(self.config.lifetime)
     }
 }
 
 #[async_trait]
 impl Middleware for SessionMiddleware {
-    async fn handle(&self, request: Request, next: Next) -> Response {
-        // Get session ID from cookie or generate new one
-        let session_id = request
+    async fn handle(&self, mut request: Request, next: Next) -> Response {
+        // Load the session named by the cookie. Unknown IDs get a fresh
+        // session with a new ID, so clients can't choose their session ID.
+        let stored = match request
             .cookie(&self.config.cookie_name)
-            .unwrap_or_else(generate_session_id);
-
-        // Load session from store
-        let mut session = match self.store.read(&session_id).await {
-            Ok(Some(s)) => s,
-            Ok(None) => {
-                // Create new session
-                SessionData::new(session_id.clone(), generate_csrf_token())
-            }
-            Err(e) => {
+            .filter(|id| is_valid_session_id(id))
+        {
+            Some(id) => self.store.read(&id).await.unwrap_or_else(|e| {
                 eprintln!("Session read error: {}", e);
-                SessionData::new(session_id.clone(), generate_csrf_token())
-            }
+                None
+            }),
+            None => None,
         };
+        let mut data = stored
+            .unwrap_or_else(|| SessionData::new(generate_session_id(), generate_csrf_token()));
 
         // Age flash data from previous request
-        session.age_flash_data();
+        data.age_flash_data();
 
-        // Store session in thread-local context
-        set_session(session);
+        let loaded_id = data.id.clone();
+        let session = Session::new(data);
+        request.insert_extension(session.clone());
 
         // Process the request
-        let response = next(request).await;
+        let response = session.clone().scope(next(request)).await;
 
-        // Get the potentially modified session
-        let session = take_session();
+        let session = session.data();
+        if let Some(user_id) = session.user_id {
+            crate::metrics::record_user(user_id);
+        }
 
-        // Save session and add cookie to response
-        if let Some(session) = session {
-            // Always save to update last_activity
-            if let Err(e) = self.store.write(&session).await {
-                eprintln!("Session write error: {}", e);
+        // A regenerated session replaces the one it was loaded as
+        if session.id != loaded_id {
+            if let Err(e) = self.store.destroy(&loaded_id).await {
+                eprintln!("Session destroy error: {}", e);
             }
+        }
 
-            // Add session cookie to response
-            let cookie = self.create_session_cookie(&session.id);
+        // Always save to update last_activity
+        if let Err(e) = self.store.write(&session).await {
+            eprintln!("Session write error: {}", e);
+        }
 
-            match response {
-                Ok(res) => Ok(res.cookie(cookie)),
-                Err(res) => Err(res.cookie(cookie)),
-            }
-        } else {
-            response
+        // Add session cookie to response
+        let cookie = self.create_session_cookie(&session.id);
+
+        match response {
+            Ok(res) => Ok(res.cookie(cookie)),
+            Err(res) => Err(res.cookie(cookie)),
         }
     }
 }
@@ -197,18 +208,16 @@ impl Middleware for SessionMiddleware {
 /// This creates a new session ID while preserving session data,
 /// which helps prevent session fixation attacks.
 pub fn regenerate_session_id() {
-    session_mut(|session| {
-        session.id = generate_session_id();
-        session.dirty = true;
-    });
+    if let Some(session) = Session::current() {
+        session.regenerate();
+    }
 }
 
 /// Invalidate the current session (clear all data)
 pub fn invalidate_session() {
-    session_mut(|session| {
-        session.flush();
-        session.csrf_token = generate_csrf_token();
-    });
+    if let Some(session) = Session::current() {
+        session.invalidate();
+    }
 }
 
 /// Helper to get the CSRF token from current session
@@ -241,3 +250,52 @@ pub fn clear_auth_user() {
         session.dirty = true;
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::HttpResponse;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_session_persists_between_requests() {
+        let store = Arc::new(MemorySessionDriver::new(Duration::from_secs(60)));
+        let middleware = SessionMiddleware::with_store(SessionConfig::default(), store);
+        let next: Next = Arc::new(|request: Request| {
+            Box::pin(async move {
+                let session = request.session();
+                let visits = session.get::<i64>("visits").unwrap_or(0) + 1;
+                session.put("visits", visits);
+                Ok(HttpResponse::text(visits.to_string()))
+            })
+        });
+
+        let Ok(first) = middleware
+            .handle(Request::fake().build(), next.clone())
+            .await
+        else {
+            panic!("expected the handler's response");
+        };
+        let cookie = first
+            .headers()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("set-cookie"))
+            .map(|(_, value)| value.split(';').next().unwrap().to_string())
+            .expect("session cookie");
+
+        let request = Request::fake().header("Cookie", cookie).build();
+        let Ok(second) = middleware.handle(request, next.clone()).await else {
+            panic!("expected the handler's response");
+        };
+        assert_eq!(second.body().as_ref(), b"2");
+
+        // An unknown session ID is not adopted
+        let request = Request::fake()
+            .header("Cookie", format!("kit_session={}", "a".repeat(40)))
+            .build();
+        let Ok(third) = middleware.handle(request, next).await else {
+            panic!("expected the handler's response");
+        };
+        assert_eq!(third.body().as_ref(), b"1");
+    }
+}
@@ -1,18 +1,27 @@
 //! Session management for Kit framework
 //!
-//! Provides Laravel-like session handling with database storage.
+//! Provides Laravel-like session handling with pluggable storage.
 //!
 //! # Features
 //!
 //! - Secure session cookies (HttpOnly, Secure, SameSite)
-//! - Database-backed storage for scalability
+//! - Database, cache (Redis) or in-memory storage (`SESSION_DRIVER`)
 //! - CSRF token generation per session
 //! - Flash messages for one-time notifications
-//! - Session data stored as JSON
+//! - Session data stored as JSON, optionally encrypted (`SESSION_ENCRYPT`)
 //!
 //! # Example
 //!
 //! ```rust,ignore
+//! // From a handler
+//! let session = req.session();
+//! session.put("name", "John");
+//! let name: Option<String> = session.get("name");
+//! ```
+//!
+//! Or without the request:
+//!
+//! ```rust,ignore
 //! use kit::session::{session, session_mut};
 //!
 //! // Read from session
@@ -42,14 +51,16 @@
 
 pub mod config;
 pub mod driver;
+mod facade;
 pub mod middleware;
 pub mod store;
 
 pub use config::SessionConfig;
-pub use driver::DatabaseSessionDriver;
+pub use driver::{CacheSessionDriver, DatabaseSessionDriver, MemorySessionDriver};
+pub use facade::Session;
 pub use middleware::{
-    auth_user_id, clear_auth_user, clear_session, generate_csrf_token, generate_session_id,
-    get_csrf_token, invalidate_session, is_authenticated, regenerate_session_id, session,
-    session_mut, set_auth_user, set_session, take_session, SessionMiddleware,
+    auth_user_id, clear_auth_user, generate_csrf_token, generate_session_id, get_csrf_token,
+    invalidate_session, is_authenticated, regenerate_session_id, session, session_mut,
+    set_auth_user, SessionMiddleware,
 };
 pub use store::{SessionData, SessionStore};
@@ -97,6 +97,17 @@ impl SessionData {
         value
     }
 
+    /// All values flashed by the previous request, by key
+    pub fn flashed(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.data
+            .iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix("_flash.old.")
+                    .map(|key| (key.to_string(), value.clone()))
+            })
+            .collect()
+    }
+
     /// Age flash data (move new flash to old, clear old)
     pub fn age_flash_data(&mut self) {
         // Remove old flash data
@@ -0,0 +1,387 @@
+//! URL slugs for Kit framework
+//!
+//! `Slug::from` turns any text into a lowercase, hyphen-separated slug.
+//! Accents are stripped (`Crème Brûlée` -> `creme-brulee`) and anything that
+//! isn't a letter or digit becomes a single hyphen.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::Slug;
+//!
+//! let slug = Slug::from("Hello, World!");
+//! assert_eq!(slug, "hello-world");
+//! ```
+//!
+//! # Models
+//!
+//! `#[sluggable]` on a model's `ActiveModelBehavior` impl fills the slug
+//! column on insert, adding `-2`, `-3`, ... when the slug is taken:
+//!
+//! ```rust,ignore
+//! #[kit::sluggable(source = "title")]
+//! impl ActiveModelBehavior for ActiveModel {}
+//! ```
+//!
+//! Bind routes by slug with a `{param:column}` placeholder:
+//!
+//! ```rust,ignore
+//! routes! {
+//!     get!("/posts/{post:slug}", controllers::post::show),
+//! }
+//!
+//! #[handler]
+//! pub async fn show(post: post::Model) -> Response { ... }
+//! ```
+
+use crate::error::FrameworkError;
+use crate::http::FromParam;
+use sea_orm::sea_query::Condition;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter,
+    QuerySelect, Value,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// A URL-safe slug: lowercase letters, digits and single hyphens
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
+pub struct Slug(String);
+
+impl Slug {
+    /// Whether `value` is already a slug
+    pub fn is_valid(value: &str) -> bool {
+        !value.is_empty()
+            && !value.starts_with('-')
+            && !value.ends_with('-')
+            && !value.contains("--")
+            && value
+                .chars()
+                .all(|c| c == '-' || c.is_lowercase() || c.is_numeric())
+    }
+
+    /// The slug as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert into the underlying `String`
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<&str> for Slug {
+    fn from(text: &str) -> Self {
+        let mut slug = String::with_capacity(text.len());
+        let mut pending_hyphen = false;
+
+        for c in text.nfkd().filter(|c| !is_combining_mark(*c)) {
+            if c.is_alphanumeric() {
+                if pending_hyphen && !slug.is_empty() {
+                    slug.push('-');
+                }
+                pending_hyphen = false;
+                slug.extend(c.to_lowercase());
+            } else if c != '\'' {
+                pending_hyphen = true;
+            }
+        }
+
+        Slug(slug)
+    }
+}
+
+impl From<String> for Slug {
+    fn from(text: String) -> Self {
+        Slug::from(text.as_str())
+    }
+}
+
+impl From<Slug> for String {
+    fn from(slug: Slug) -> Self {
+        slug.0
+    }
+}
+
+impl Deref for Slug {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Slug {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Slug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for Slug {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Slug {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+/// Route parameters must already be slugs, otherwise 400 Bad Request
+impl FromParam for Slug {
+    fn from_param(value: &str) -> Result<Self, FrameworkError> {
+        if Slug::is_valid(value) {
+            Ok(Slug(value.to_string()))
+        } else {
+            Err(FrameworkError::param_parse(value, "Slug"))
+        }
+    }
+}
+
+/// Find a slug for `text` that isn't used in `column` yet
+///
+/// Returns the plain slug when it is free, otherwise the first free one of
+/// `slug-2`, `slug-3`, ...
+pub async fn unique_slug<E, C>(db: &C, column: E::Column, text: &str) -> Result<String, DbErr>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let base = Slug::from(text).into_string();
+
+    let taken: HashSet<String> = E::find()
+        .select_only()
+        .column(column)
+        .filter(
+            Condition::any()
+                .add(column.eq(base.as_str()))
+                .add(column.like(format!("{}-%", base))),
+        )
+        .into_tuple::<String>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    if !taken.contains(&base) {
+        return Ok(base);
+    }
+    Ok((2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("a free suffix always exists"))
+}
+
+/// Set `target` to a unique slug of `source` unless it already has a value
+///
+/// Used by `#[sluggable]` before inserts.
+#[doc(hidden)]
+pub async fn fill_unique_slug<A, C>(
+    model: &mut A,
+    source: <A::Entity as EntityTrait>::Column,
+    target: <A::Entity as EntityTrait>::Column,
+    db: &C,
+) -> Result<(), DbErr>
+where
+    A: ActiveModelTrait + Send,
+    C: ConnectionTrait,
+{
+    if string_value(model.get(target)).is_some_and(|slug| !slug.is_empty()) {
+        return Ok(());
+    }
+    let Some(text) = string_value(model.get(source)) else {
+        return Ok(());
+    };
+
+    let slug = unique_slug::<A::Entity, C>(db, target, &text).await?;
+    model.set(target, slug.into());
+    Ok(())
+}
+
+fn string_value(value: ActiveValue<Value>) -> Option<String> {
+    match value.into_value()? {
+        Value::String(Some(text)) => Some(*text),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::AutoRouteBinding;
+    use crate::testing::TestDatabase;
+    use sea_orm::{ActiveModelTrait, Set};
+    use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager};
+
+    mod posts {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "posts")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub title: String,
+            pub slug: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        #[crate::sluggable(source = "title")]
+        impl ActiveModelBehavior for ActiveModel {}
+
+        impl crate::database::Model for Entity {}
+    }
+
+    struct Migrator;
+
+    #[async_trait::async_trait]
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreatePosts)]
+        }
+    }
+
+    struct CreatePosts;
+
+    impl MigrationName for CreatePosts {
+        fn name(&self) -> &str {
+            "create_posts"
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreatePosts {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "CREATE TABLE posts (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        title TEXT NOT NULL,
+                        slug TEXT NOT NULL UNIQUE
+                    )",
+                )
+                .await
+                .map(|_| ())
+        }
+    }
+
+    async fn create_post(db: &TestDatabase, title: &str) -> posts::Model {
+        posts::ActiveModel {
+            title: Set(title.to_string()),
+            ..Default::default()
+        }
+        .insert(db.conn())
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn slugs_are_lowercase_and_hyphenated() {
+        assert_eq!(Slug::from("Hello World"), "hello-world");
+        assert_eq!(Slug::from("  Rust -- 2024 edition!  "), "rust-2024-edition");
+        assert_eq!(Slug::from("Crème Brûlée"), "creme-brulee");
+        assert_eq!(Slug::from("Don't panic"), "dont-panic");
+        assert_eq!(Slug::from("!!!"), "");
+    }
+
+    #[test]
+    fn only_slugs_are_accepted_as_route_params() {
+        assert_eq!(Slug::from_param("hello-world").unwrap(), "hello-world");
+        assert!(Slug::from_param("Hello-World").is_err());
+        assert!(Slug::from_param("hello--world").is_err());
+        assert!(Slug::from_param("-hello").is_err());
+        assert!(Slug::from_param("").is_err());
+    }
+
+    #[tokio::test]
+    async fn sluggable_models_get_unique_slugs_on_insert() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+
+        assert_eq!(create_post(&db, "Hello World").await.slug, "hello-world");
+        assert_eq!(
+            create_post(&db, "Hello, world!").await.slug,
+            "hello-world-2"
+        );
+        assert_eq!(create_post(&db, "Hello World").await.slug, "hello-world-3");
+        assert_eq!(
+            create_post(&db, "Hello World Tour").await.slug,
+            "hello-world-tour"
+        );
+
+        let custom = posts::ActiveModel {
+            title: Set("Anything".to_string()),
+            slug: Set("custom".to_string()),
+            ..Default::default()
+        }
+        .insert(db.conn())
+        .await
+        .unwrap();
+        assert_eq!(custom.slug, "custom");
+    }
+
+    #[tokio::test]
+    async fn models_bind_by_slug_column() {
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        let post = create_post(&db, "Hello World").await;
+
+        let found = posts::Model::from_route_key("slug", "hello-world")
+            .await
+            .unwrap();
+        assert_eq!(found, post);
+
+        let missing = posts::Model::from_route_key("slug", "nope")
+            .await
+            .unwrap_err();
+        assert_eq!(missing.status_code(), 404);
+        assert!(posts::Model::from_route_key("nope", "hello-world")
+            .await
+            .is_err());
+    }
+
+    type Post = posts::Model;
+
+    #[crate::handler]
+    async fn show(post: Post) -> crate::Response {
+        crate::text(post.title)
+    }
+
+    #[tokio::test]
+    async fn handlers_bind_model_aliases_from_the_signature() {
+        use crate::testing::TestResponse;
+
+        let db = TestDatabase::fresh::<Migrator>().await.unwrap();
+        let post = create_post(&db, "Hello World").await;
+        let request = |name: &str, value: &str| crate::Request::fake().param(name, value).build();
+
+        let by_name = TestResponse::from(show(request("post", &post.id.to_string())).await);
+        by_name.assert_status(200);
+        assert_eq!(by_name.text(), "Hello World");
+
+        let by_id = TestResponse::from(show(request("id", &post.id.to_string())).await);
+        assert_eq!(by_id.text(), "Hello World");
+
+        let keyed = request("post", "hello-world").with_binding_keys([("post", "slug")]);
+        let by_slug = show(keyed).await;
+        assert_eq!(TestResponse::from(by_slug).text(), "Hello World");
+
+        TestResponse::from(show(request("post", "999")).await).assert_status(404);
+        TestResponse::from(show(request("post", "abc")).await).assert_status(400);
+    }
+}
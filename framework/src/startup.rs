@@ -0,0 +1,217 @@
+//! The startup summary printed by `serve`
+//!
+//! Shows what the server booted with and how long each boot phase took, so
+//! a wrong environment, a missing database or middleware that never
+//! registered is visible before the first request. `serve --quiet` prints
+//! only the address.
+//!
+//! ```text
+//! Kit server running on http://127.0.0.1:8000
+//!
+//!   Environment  development
+//!   Routes       24
+//!   Middleware   LoggingMiddleware, CorsMiddleware
+//!   Database     postgres, pool of 10
+//!   Scheduler    3 tasks, run by `schedule:work` or `work`
+//!   Workers      emails: 2 workflow, scheduler: 1 scheduler (supervisor.toml), run by `work`
+//!
+//!   Booted in 412ms: config 3ms, migrations 120ms, bootstrap 40ms, ...
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::config::{Config, Environment};
+use crate::database::DatabaseConfig;
+use crate::supervisor::{SupervisorConfig, DEFAULT_CONFIG_FILE};
+
+/// How long each boot phase took, in the order they ran
+#[derive(Debug, Default)]
+pub(crate) struct BootPhases {
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl BootPhases {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a phase that started at `started`
+    pub(crate) fn record(&mut self, name: &'static str, started: Instant) {
+        self.phases.push((name, started.elapsed()));
+    }
+
+    /// Run `f` as the phase `name`
+    pub(crate) fn time<T>(&mut self, name: &'static str, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let output = f();
+        self.record(name, started);
+        output
+    }
+
+    /// Run `future` as the phase `name`
+    pub(crate) async fn time_async<F: Future>(
+        &mut self,
+        name: &'static str,
+        future: F,
+    ) -> F::Output {
+        let started = Instant::now();
+        let output = future.await;
+        self.record(name, started);
+        output
+    }
+}
+
+/// What the application booted before the server starts, see `Server::startup`
+#[derive(Debug)]
+pub(crate) struct Boot {
+    pub(crate) phases: BootPhases,
+    /// Registered scheduled tasks and how many of them are invalid
+    pub(crate) scheduled_tasks: (usize, usize),
+}
+
+/// Everything `serve` reports once it is listening
+#[derive(Debug)]
+pub(crate) struct StartupSummary {
+    pub(crate) address: String,
+    pub(crate) redirect: Option<String>,
+    pub(crate) environment: Environment,
+    pub(crate) routes: usize,
+    pub(crate) middleware: Vec<&'static str>,
+    /// Driver and pool size, `None` without a database connection
+    pub(crate) database: Option<(String, u32)>,
+    pub(crate) scheduled_tasks: (usize, usize),
+    pub(crate) workers: String,
+    pub(crate) phases: BootPhases,
+}
+
+impl StartupSummary {
+    /// The database driver and pool size, if the application connected
+    pub(crate) fn database() -> Option<(String, u32)> {
+        if !crate::DB::is_connected() {
+            return None;
+        }
+        let config = Config::get::<DatabaseConfig>().unwrap_or_default();
+        let driver = config.url.split(':').next().unwrap_or_default().to_string();
+        Some((driver, config.max_connections))
+    }
+
+    /// The worker pools `work` runs, from `supervisor.toml`
+    pub(crate) fn workers() -> String {
+        let path = Path::new(DEFAULT_CONFIG_FILE);
+        let source = if path.exists() {
+            DEFAULT_CONFIG_FILE
+        } else {
+            "default, no supervisor.toml"
+        };
+        match SupervisorConfig::load(path) {
+            Ok(config) => {
+                let pools: Vec<String> = config
+                    .pools
+                    .iter()
+                    .map(|(name, pool)| {
+                        format!("{}: {} {}", name, pool.workers, pool.kind.as_str())
+                    })
+                    .collect();
+                format!("{} ({}), run by `work`", pools.join(", "), source)
+            }
+            Err(e) => e.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for StartupSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(redirect) = &self.redirect {
+            writeln!(f, "Redirecting {} to HTTPS", redirect)?;
+        }
+        writeln!(f, "Kit server running on {}", self.address)?;
+        writeln!(f)?;
+
+        let middleware = if self.middleware.is_empty() {
+            "none".to_string()
+        } else {
+            self.middleware.join(", ")
+        };
+        let database = match &self.database {
+            Some((driver, pool)) => format!("{}, pool of {}", driver, pool),
+            None => "not connected".to_string(),
+        };
+        let scheduler = match self.scheduled_tasks {
+            (0, 0) => "no tasks".to_string(),
+            (tasks, 0) => format!("{} tasks, run by `schedule:work` or `work`", tasks),
+            (tasks, invalid) => {
+                format!("{} tasks, {} invalid (see `schedule:list`)", tasks, invalid)
+            }
+        };
+        let rows = [
+            ("Environment", self.environment.to_string()),
+            ("Routes", self.routes.to_string()),
+            ("Middleware", middleware),
+            ("Database", database),
+            ("Scheduler", scheduler),
+            ("Workers", self.workers.clone()),
+        ];
+        for (label, value) in rows {
+            writeln!(f, "  {:<12} {}", label, value)?;
+        }
+
+        let total: Duration = self.phases.phases.iter().map(|(_, elapsed)| *elapsed).sum();
+        let phases: Vec<String> = self
+            .phases
+            .phases
+            .iter()
+            .map(|(name, elapsed)| format!("{} {}ms", name, elapsed.as_millis()))
+            .collect();
+        writeln!(f)?;
+        write!(
+            f,
+            "  Booted in {}ms: {}",
+            total.as_millis(),
+            phases.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_lists_every_row_and_phase() {
+        let mut phases = BootPhases::new();
+        phases.phases.push(("config", Duration::from_millis(3)));
+        phases
+            .phases
+            .push(("migrations", Duration::from_millis(120)));
+        phases.phases.push(("bind", Duration::from_micros(400)));
+        let summary = StartupSummary {
+            address: "https://127.0.0.1:8443".to_string(),
+            redirect: Some("http://127.0.0.1:8080".to_string()),
+            environment: Environment::Production,
+            routes: 24,
+            middleware: vec!["LoggingMiddleware", "CorsMiddleware"],
+            database: Some(("postgres".to_string(), 10)),
+            scheduled_tasks: (3, 1),
+            workers: "emails: 2 workflow (supervisor.toml), run by `work`".to_string(),
+            phases,
+        };
+
+        assert_eq!(
+            summary.to_string(),
+            "Redirecting http://127.0.0.1:8080 to HTTPS\n\
+             Kit server running on https://127.0.0.1:8443\n\
+             \n  \
+             Environment  production\n  \
+             Routes       24\n  \
+             Middleware   LoggingMiddleware, CorsMiddleware\n  \
+             Database     postgres, pool of 10\n  \
+             Scheduler    3 tasks, 1 invalid (see `schedule:list`)\n  \
+             Workers      emails: 2 workflow (supervisor.toml), run by `work`\n\
+             \n  \
+             Booted in 123ms: config 3ms, migrations 120ms, bind 0ms"
+        );
+    }
+}
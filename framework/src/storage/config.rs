@@ -0,0 +1,164 @@
+//! Storage configuration for Kit framework
+
+use crate::config::{env, env_optional};
+
+/// Storage configuration
+///
+/// # Environment Variables
+///
+/// - `FILESYSTEM_DISK` - Disk used by the `Storage` shortcuts: "local" or "s3" (default: "local")
+/// - `STORAGE_ROOT` - Directory of the local disk (default: "storage/app")
+/// - `STORAGE_URL` - Public URL the local disk is served under (default: "/storage")
+/// - `APP_KEY` - Key that signs temporary URLs of the local disk
+/// - `S3_*` - The S3 disk, see `S3Config`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Config, StorageConfig};
+///
+/// // Register from environment
+/// Config::register(StorageConfig::from_env());
+///
+/// // Or build manually
+/// Config::register(StorageConfig::builder()
+///     .default_disk("s3")
+///     .root("/var/lib/myapp/files")
+///     .build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    /// Name of the disk used by the `Storage` shortcuts
+    pub default_disk: String,
+    /// Directory of the local disk
+    pub root: String,
+    /// Public URL of the local disk
+    pub url: String,
+    /// Key that signs temporary URLs of the local disk
+    pub signing_key: Option<String>,
+    /// The S3 disk
+    pub s3: S3Config,
+}
+
+impl StorageConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            default_disk: env("FILESYSTEM_DISK", "local".to_string()),
+            root: env("STORAGE_ROOT", "storage/app".to_string()),
+            url: env("STORAGE_URL", "/storage".to_string()),
+            signing_key: env_optional("APP_KEY"),
+            s3: S3Config::from_env(),
+        }
+    }
+
+    /// Create a builder for manual configuration
+    pub fn builder() -> StorageConfigBuilder {
+        StorageConfigBuilder::default()
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Builder for StorageConfig
+#[derive(Debug, Default)]
+pub struct StorageConfigBuilder {
+    default_disk: Option<String>,
+    root: Option<String>,
+    url: Option<String>,
+    signing_key: Option<String>,
+    s3: Option<S3Config>,
+}
+
+impl StorageConfigBuilder {
+    /// Set the disk used by the `Storage` shortcuts
+    pub fn default_disk(mut self, disk: impl Into<String>) -> Self {
+        self.default_disk = Some(disk.into());
+        self
+    }
+
+    /// Set the directory of the local disk
+    pub fn root(mut self, root: impl Into<String>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Set the public URL of the local disk
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Set the key that signs temporary URLs of the local disk
+    pub fn signing_key(mut self, key: impl Into<String>) -> Self {
+        self.signing_key = Some(key.into());
+        self
+    }
+
+    /// Configure the S3 disk
+    pub fn s3(mut self, s3: S3Config) -> Self {
+        self.s3 = Some(s3);
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> StorageConfig {
+        let defaults = StorageConfig::from_env();
+        StorageConfig {
+            default_disk: self.default_disk.unwrap_or(defaults.default_disk),
+            root: self.root.unwrap_or(defaults.root),
+            url: self.url.unwrap_or(defaults.url),
+            signing_key: self.signing_key.or(defaults.signing_key),
+            s3: self.s3.unwrap_or(defaults.s3),
+        }
+    }
+}
+
+/// Configuration of an S3-compatible disk (AWS S3, MinIO, R2, ...)
+///
+/// # Environment Variables
+///
+/// - `S3_ENDPOINT` - Endpoint for S3-compatible services, e.g. `http://localhost:9000`
+///   (default: AWS)
+/// - `S3_REGION` - Region (default: "us-east-1")
+/// - `S3_BUCKET` - Bucket name
+/// - `S3_ACCESS_KEY` / `S3_SECRET_KEY` - Credentials
+/// - `S3_PATH_STYLE` - Address buckets as `endpoint/bucket` instead of
+///   `bucket.endpoint` (default: true when `S3_ENDPOINT` is set)
+/// - `S3_URL` - Public URL of the bucket, e.g. a CDN (default: the bucket URL)
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub path_style: bool,
+    pub url: Option<String>,
+}
+
+impl S3Config {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        let endpoint = env_optional("S3_ENDPOINT");
+        Self {
+            path_style: env("S3_PATH_STYLE", endpoint.is_some()),
+            endpoint,
+            region: env("S3_REGION", "us-east-1".to_string()),
+            bucket: env("S3_BUCKET", String::new()),
+            access_key: env("S3_ACCESS_KEY", String::new()),
+            secret_key: env("S3_SECRET_KEY", String::new()),
+            url: env_optional("S3_URL"),
+        }
+    }
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
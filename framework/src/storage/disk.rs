@@ -0,0 +1,119 @@
+//! Disk trait definition
+//!
+//! Defines the contract for storage backends (local, S3, fake, ...)
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::io::AsyncRead;
+
+use crate::error::FrameworkError;
+
+/// File contents read or written piece by piece
+pub type FileStream = Pin<Box<dyn AsyncRead + Send>>;
+
+/// Disk trait - all storage backends must implement this
+///
+/// Paths are relative to the disk, use `/` as separator and may not leave
+/// the disk (`../secrets` is rejected).
+#[async_trait]
+pub trait Disk: Send + Sync {
+    /// Write a file, replacing any existing one
+    async fn put(&self, path: &str, contents: Bytes) -> Result<(), FrameworkError>;
+
+    /// Read a whole file
+    ///
+    /// Fails with a 404 error if the file doesn't exist.
+    async fn get(&self, path: &str) -> Result<Bytes, FrameworkError>;
+
+    /// Check if a file exists
+    async fn exists(&self, path: &str) -> Result<bool, FrameworkError>;
+
+    /// Delete a file
+    ///
+    /// Returns `true` if the file existed.
+    async fn delete(&self, path: &str) -> Result<bool, FrameworkError>;
+
+    /// Size of a file in bytes
+    async fn size(&self, path: &str) -> Result<u64, FrameworkError>;
+
+    /// Paths of the files directly inside `dir`, sorted
+    async fn files(&self, dir: &str) -> Result<Vec<String>, FrameworkError>;
+
+    /// Open a file for reading without loading it into memory
+    async fn read_stream(&self, path: &str) -> Result<FileStream, FrameworkError>;
+
+    /// Write a file from a stream, replacing any existing one
+    ///
+    /// Returns the number of bytes written.
+    async fn write_stream(&self, path: &str, stream: FileStream) -> Result<u64, FrameworkError>;
+
+    /// Public URL of a file
+    fn url(&self, path: &str) -> Result<String, FrameworkError>;
+
+    /// URL that grants access to a file until it expires
+    fn temporary_url(&self, path: &str, expires_in: Duration) -> Result<String, FrameworkError>;
+}
+
+/// Check a disk path and bring it into `dir/file` form
+///
+/// Leading slashes and `.` segments are dropped; empty paths and `..`
+/// segments are rejected.
+pub fn normalize_path(path: &str) -> Result<String, FrameworkError> {
+    let mut segments = Vec::new();
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                return Err(FrameworkError::internal(format!(
+                    "Invalid storage path: {}",
+                    path
+                )))
+            }
+            segment => segments.push(segment),
+        }
+    }
+    if segments.is_empty() {
+        return Err(FrameworkError::internal(format!(
+            "Invalid storage path: {:?}",
+            path
+        )));
+    }
+    Ok(segments.join("/"))
+}
+
+/// Like `normalize_path`, but the empty path stands for the disk root
+pub(crate) fn normalize_dir(dir: &str) -> Result<String, FrameworkError> {
+    if dir
+        .split(['/', '\\'])
+        .all(|segment| matches!(segment, "" | "."))
+    {
+        return Ok(String::new());
+    }
+    normalize_path(dir)
+}
+
+/// The error for a file that doesn't exist
+pub(crate) fn not_found(path: &str) -> FrameworkError {
+    FrameworkError::domain(format!("File not found: {}", path), 404)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path("/avatars//./me.png").unwrap(),
+            "avatars/me.png"
+        );
+        assert_eq!(normalize_path("docs\\a.txt").unwrap(), "docs/a.txt");
+        assert!(normalize_path("../config/app.toml").is_err());
+        assert!(normalize_path("avatars/../../x").is_err());
+        assert!(normalize_path("/").is_err());
+        assert_eq!(normalize_dir("/").unwrap(), "");
+        assert!(normalize_dir("..").is_err());
+    }
+}
@@ -0,0 +1,143 @@
+//! In-memory disk for tests
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+
+use super::disk::{normalize_dir, normalize_path, not_found, Disk, FileStream};
+use crate::error::FrameworkError;
+
+/// Disk that keeps files in memory, returned by `Storage::fake()`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let disk = Storage::fake();
+///
+/// upload_avatar(file).await?;
+///
+/// disk.assert_exists("avatars/1.png");
+/// disk.assert_missing("avatars/0.png");
+/// ```
+#[derive(Default)]
+pub struct FakeDisk {
+    files: Mutex<BTreeMap<String, Bytes>>,
+}
+
+impl FakeDisk {
+    /// An empty disk
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Bytes>> {
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Paths of all stored files, sorted
+    pub fn all_files(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+
+    /// Panic unless `path` was stored
+    #[track_caller]
+    pub fn assert_exists(&self, path: &str) {
+        let path = normalize_path(path).expect("valid storage path");
+        assert!(
+            self.lock().contains_key(&path),
+            "Expected {} to exist on the fake disk, found: {:?}",
+            path,
+            self.all_files()
+        );
+    }
+
+    /// Panic if `path` is stored
+    #[track_caller]
+    pub fn assert_missing(&self, path: &str) {
+        let path = normalize_path(path).expect("valid storage path");
+        assert!(
+            !self.lock().contains_key(&path),
+            "Expected {} to be missing from the fake disk",
+            path
+        );
+    }
+}
+
+#[async_trait]
+impl Disk for FakeDisk {
+    async fn put(&self, path: &str, contents: Bytes) -> Result<(), FrameworkError> {
+        self.lock().insert(normalize_path(path)?, contents);
+        Ok(())
+    }
+
+    async fn get(&self, path: &str) -> Result<Bytes, FrameworkError> {
+        let path = normalize_path(path)?;
+        self.lock()
+            .get(&path)
+            .cloned()
+            .ok_or_else(|| not_found(&path))
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool, FrameworkError> {
+        Ok(self.lock().contains_key(&normalize_path(path)?))
+    }
+
+    async fn delete(&self, path: &str) -> Result<bool, FrameworkError> {
+        Ok(self.lock().remove(&normalize_path(path)?).is_some())
+    }
+
+    async fn size(&self, path: &str) -> Result<u64, FrameworkError> {
+        Ok(self.get(path).await?.len() as u64)
+    }
+
+    async fn files(&self, dir: &str) -> Result<Vec<String>, FrameworkError> {
+        let prefix = match normalize_dir(dir)?.as_str() {
+            "" => String::new(),
+            dir => format!("{}/", dir),
+        };
+        Ok(self
+            .lock()
+            .keys()
+            .filter(|path| {
+                path.strip_prefix(&prefix)
+                    .is_some_and(|name| !name.contains('/'))
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn read_stream(&self, path: &str) -> Result<FileStream, FrameworkError> {
+        let contents = self.get(path).await?;
+        Ok(Box::pin(std::io::Cursor::new(contents)))
+    }
+
+    async fn write_stream(
+        &self,
+        path: &str,
+        mut stream: FileStream,
+    ) -> Result<u64, FrameworkError> {
+        let mut contents = Vec::new();
+        stream
+            .read_to_end(&mut contents)
+            .await
+            .map_err(|e| FrameworkError::internal(format!("Failed to read {}: {}", path, e)))?;
+        let size = contents.len() as u64;
+        self.put(path, Bytes::from(contents)).await?;
+        Ok(size)
+    }
+
+    fn url(&self, path: &str) -> Result<String, FrameworkError> {
+        Ok(format!("/storage/{}", normalize_path(path)?))
+    }
+
+    fn temporary_url(&self, path: &str, expires_in: Duration) -> Result<String, FrameworkError> {
+        Ok(format!(
+            "{}?expires_in={}",
+            self.url(path)?,
+            expires_in.as_secs()
+        ))
+    }
+}
@@ -0,0 +1,263 @@
+//! Local filesystem disk
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::disk::{normalize_dir, normalize_path, not_found, Disk, FileStream};
+use crate::error::FrameworkError;
+
+/// Disk that keeps files in a local directory
+///
+/// Temporary URLs carry an `expires` timestamp and a `signature` made with
+/// the signing key; check them with `verify_temporary_url` in the route
+/// that serves the files.
+pub struct LocalDisk {
+    root: PathBuf,
+    url: String,
+    signing_key: Option<String>,
+}
+
+impl LocalDisk {
+    /// Disk for the files under `root`, served under `url`
+    pub fn new(root: impl Into<PathBuf>, url: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            url: url.into().trim_end_matches('/').to_string(),
+            signing_key: None,
+        }
+    }
+
+    /// Sign temporary URLs with `key`
+    pub fn with_signing_key(mut self, key: impl Into<String>) -> Self {
+        self.signing_key = Some(key.into());
+        self
+    }
+
+    /// The directory files are stored in
+    pub fn root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    /// Absolute location of a disk path
+    pub fn path(&self, path: &str) -> Result<PathBuf, FrameworkError> {
+        Ok(self.root.join(normalize_path(path)?))
+    }
+
+    /// Check the `expires` and `signature` query values of a temporary URL
+    pub fn verify_temporary_url(&self, path: &str, expires: u64, signature: &str) -> bool {
+        let (Some(key), Ok(path), Ok(signature)) = (
+            &self.signing_key,
+            normalize_path(path),
+            hex::decode(signature),
+        ) else {
+            return false;
+        };
+        if expires < unix_now() {
+            return false;
+        }
+        signer(key, &path, expires).verify_slice(&signature).is_ok()
+    }
+
+    async fn create_parent(&self, path: &std::path::Path) -> Result<(), FrameworkError> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                FrameworkError::internal(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn signer(key: &str, path: &str, expires: u64) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}\n{}", path, expires).as_bytes());
+    mac
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn io_error(action: &str, path: &str, err: std::io::Error) -> FrameworkError {
+    if err.kind() == ErrorKind::NotFound {
+        return not_found(path);
+    }
+    FrameworkError::internal(format!("Failed to {} {}: {}", action, path, err))
+}
+
+#[async_trait]
+impl Disk for LocalDisk {
+    async fn put(&self, path: &str, contents: Bytes) -> Result<(), FrameworkError> {
+        let full = self.path(path)?;
+        self.create_parent(&full).await?;
+        tokio::fs::write(&full, &contents)
+            .await
+            .map_err(|e| io_error("write", path, e))
+    }
+
+    async fn get(&self, path: &str) -> Result<Bytes, FrameworkError> {
+        let contents = tokio::fs::read(self.path(path)?)
+            .await
+            .map_err(|e| io_error("read", path, e))?;
+        Ok(Bytes::from(contents))
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool, FrameworkError> {
+        Ok(tokio::fs::metadata(self.path(path)?)
+            .await
+            .map(|meta| meta.is_file())
+            .unwrap_or(false))
+    }
+
+    async fn delete(&self, path: &str) -> Result<bool, FrameworkError> {
+        match tokio::fs::remove_file(self.path(path)?).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(io_error("delete", path, e)),
+        }
+    }
+
+    async fn size(&self, path: &str) -> Result<u64, FrameworkError> {
+        let meta = tokio::fs::metadata(self.path(path)?)
+            .await
+            .map_err(|e| io_error("read", path, e))?;
+        Ok(meta.len())
+    }
+
+    async fn files(&self, dir: &str) -> Result<Vec<String>, FrameworkError> {
+        let dir = normalize_dir(dir)?;
+        let mut entries = match tokio::fs::read_dir(self.root.join(&dir)).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error("list", &dir, e)),
+        };
+
+        let mut files = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| io_error("list", &dir, e))?
+        {
+            let is_file = entry
+                .file_type()
+                .await
+                .map(|t| t.is_file())
+                .unwrap_or(false);
+            if let (true, Some(name)) = (is_file, entry.file_name().to_str()) {
+                files.push(match dir.as_str() {
+                    "" => name.to_string(),
+                    dir => format!("{}/{}", dir, name),
+                });
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    async fn read_stream(&self, path: &str) -> Result<FileStream, FrameworkError> {
+        let file = tokio::fs::File::open(self.path(path)?)
+            .await
+            .map_err(|e| io_error("read", path, e))?;
+        Ok(Box::pin(file))
+    }
+
+    async fn write_stream(
+        &self,
+        path: &str,
+        mut stream: FileStream,
+    ) -> Result<u64, FrameworkError> {
+        let full = self.path(path)?;
+        self.create_parent(&full).await?;
+        let mut file = tokio::fs::File::create(&full)
+            .await
+            .map_err(|e| io_error("write", path, e))?;
+        tokio::io::copy(&mut stream, &mut file)
+            .await
+            .map_err(|e| io_error("write", path, e))
+    }
+
+    fn url(&self, path: &str) -> Result<String, FrameworkError> {
+        Ok(format!("{}/{}", self.url, normalize_path(path)?))
+    }
+
+    fn temporary_url(&self, path: &str, expires_in: Duration) -> Result<String, FrameworkError> {
+        let key = self.signing_key.as_deref().ok_or_else(|| {
+            FrameworkError::internal("Temporary URLs of the local disk need APP_KEY to be set")
+        })?;
+        let path = normalize_path(path)?;
+        let expires = unix_now() + expires_in.as_secs();
+        let signature = hex::encode(signer(key, &path, expires).finalize().into_bytes());
+        Ok(format!(
+            "{}/{}?expires={}&signature={}",
+            self.url, path, expires, signature
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk(name: &str) -> LocalDisk {
+        let root = std::env::temp_dir().join(format!("kit-storage-{}-{}", name, unix_now()));
+        LocalDisk::new(root, "/storage/").with_signing_key("secret")
+    }
+
+    #[tokio::test]
+    async fn test_put_get_and_stream() {
+        let disk = disk("local");
+        disk.put("docs/a.txt", Bytes::from("hello")).await.unwrap();
+        disk.write_stream("docs/b.txt", Box::pin(&b"streamed"[..]))
+            .await
+            .unwrap();
+
+        assert_eq!(disk.get("docs/a.txt").await.unwrap(), "hello");
+        assert_eq!(disk.size("docs/b.txt").await.unwrap(), 8);
+        assert_eq!(
+            disk.files("docs").await.unwrap(),
+            vec!["docs/a.txt", "docs/b.txt"]
+        );
+
+        let mut contents = String::new();
+        let mut stream = disk.read_stream("docs/b.txt").await.unwrap();
+        tokio::io::AsyncReadExt::read_to_string(&mut stream, &mut contents)
+            .await
+            .unwrap();
+        assert_eq!(contents, "streamed");
+
+        assert!(disk.delete("docs/a.txt").await.unwrap());
+        assert!(!disk.exists("docs/a.txt").await.unwrap());
+        assert_eq!(disk.get("docs/a.txt").await.unwrap_err().status_code(), 404);
+        assert!(disk.put("../escape.txt", Bytes::new()).await.is_err());
+
+        tokio::fs::remove_dir_all(disk.root()).await.unwrap();
+    }
+
+    #[test]
+    fn test_temporary_url() {
+        let disk = disk("signed");
+        assert_eq!(disk.url("/docs/a.txt").unwrap(), "/storage/docs/a.txt");
+
+        let url = disk
+            .temporary_url("docs/a.txt", Duration::from_secs(60))
+            .unwrap();
+        let query = url.split_once('?').unwrap().1;
+        let params: std::collections::HashMap<String, String> =
+            serde_urlencoded::from_str(query).unwrap();
+        let expires: u64 = params["expires"].parse().unwrap();
+
+        assert!(disk.verify_temporary_url("docs/a.txt", expires, &params["signature"]));
+        assert!(!disk.verify_temporary_url("docs/b.txt", expires, &params["signature"]));
+        assert!(!disk.verify_temporary_url("docs/a.txt", expires + 1, &params["signature"]));
+        assert!(!disk.verify_temporary_url("docs/a.txt", 1, &params["signature"]));
+    }
+}
@@ -0,0 +1,243 @@
+//! File storage for Kit framework
+//!
+//! Stores files on named disks: `local` (a directory, `storage/app` by
+//! default) and `s3` (AWS S3 or a compatible service such as MinIO). The
+//! `Storage` shortcuts use the disk named by `FILESYSTEM_DISK`.
+//!
+//! # Quick Start
+//!
+//! ```rust,ignore
+//! use kit::Storage;
+//! use std::time::Duration;
+//!
+//! // Write and read on the default disk
+//! Storage::put("reports/2024.csv", csv).await?;
+//! let report = Storage::get("reports/2024.csv").await?;
+//!
+//! // Stream large files instead of loading them into memory
+//! let file = tokio::fs::File::open("backup.tar").await?;
+//! Storage::disk("s3")?.write_stream("backups/latest.tar", Box::pin(file)).await?;
+//!
+//! // Links
+//! let url = Storage::url("avatars/1.png")?;
+//! let link = Storage::temporary_url("invoices/7.pdf", Duration::from_secs(300))?;
+//! ```
+//!
+//! # Testing
+//!
+//! ```rust,ignore
+//! let disk = Storage::fake();
+//!
+//! store_avatar(file).await?;
+//!
+//! disk.assert_exists("avatars/1.png");
+//! ```
+
+pub mod config;
+pub mod disk;
+pub mod fake;
+pub mod local;
+pub mod s3;
+
+pub use config::{S3Config, StorageConfig, StorageConfigBuilder};
+pub use disk::{Disk, FileStream};
+pub use fake::FakeDisk;
+pub use local::LocalDisk;
+pub use s3::S3Disk;
+
+use crate::config::Config;
+use crate::error::FrameworkError;
+use bytes::Bytes;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+
+/// The configured disks, created on first use
+static DISKS: OnceLock<RwLock<Disks>> = OnceLock::new();
+
+thread_local! {
+    /// Disks replaced by `Storage::fake()` on this thread
+    static FAKE_DISKS: RefCell<HashMap<String, Arc<FakeDisk>>> = RefCell::new(HashMap::new());
+}
+
+struct Disks {
+    default: String,
+    disks: HashMap<String, Arc<dyn Disk>>,
+}
+
+impl Disks {
+    fn from_config(config: StorageConfig) -> Self {
+        let mut local = LocalDisk::new(&config.root, &config.url);
+        if let Some(key) = config.signing_key {
+            local = local.with_signing_key(key);
+        }
+
+        let mut disks: HashMap<String, Arc<dyn Disk>> = HashMap::new();
+        disks.insert("local".to_string(), Arc::new(local));
+        disks.insert("s3".to_string(), Arc::new(S3Disk::new(config.s3)));
+        Self {
+            default: config.default_disk,
+            disks,
+        }
+    }
+}
+
+fn disks() -> &'static RwLock<Disks> {
+    DISKS.get_or_init(|| {
+        let config = Config::get::<StorageConfig>().unwrap_or_default();
+        RwLock::new(Disks::from_config(config))
+    })
+}
+
+/// Storage facade - main entry point for file storage
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::Storage;
+///
+/// // Default disk
+/// Storage::put("notes/todo.txt", "Buy milk").await?;
+///
+/// // A specific disk
+/// let s3 = Storage::disk("s3")?;
+/// let size = s3.size("backups/latest.tar").await?;
+/// ```
+pub struct Storage;
+
+impl Storage {
+    /// Get a disk by name
+    ///
+    /// `local` and `s3` are always available; add more with `Storage::extend`.
+    pub fn disk(name: &str) -> Result<Arc<dyn Disk>, FrameworkError> {
+        if let Some(fake) = FAKE_DISKS.with(|fakes| fakes.borrow().get(name).cloned()) {
+            return Ok(fake);
+        }
+        let disks = disks().read().unwrap_or_else(|e| e.into_inner());
+        disks
+            .disks
+            .get(name)
+            .cloned()
+            .ok_or_else(|| FrameworkError::internal(format!("Disk [{}] is not configured", name)))
+    }
+
+    /// Name of the disk the shortcuts use (`FILESYSTEM_DISK`)
+    pub fn default_disk_name() -> String {
+        disks()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .default
+            .clone()
+    }
+
+    /// Get the default disk
+    pub fn default_disk() -> Result<Arc<dyn Disk>, FrameworkError> {
+        Self::disk(&Self::default_disk_name())
+    }
+
+    /// Register a disk under `name`, replacing any disk of that name
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// Storage::extend("backups", Arc::new(LocalDisk::new("/mnt/backups", "/backups")));
+    /// ```
+    pub fn extend(name: impl Into<String>, disk: Arc<dyn Disk>) {
+        let mut disks = disks().write().unwrap_or_else(|e| e.into_inner());
+        disks.disks.insert(name.into(), disk);
+    }
+
+    /// Replace the default disk with an in-memory one for this thread
+    ///
+    /// Returns the fake to assert on. Fakes last until the thread ends, so
+    /// each `#[test]`/`#[tokio::test]` starts with real disks.
+    pub fn fake() -> Arc<FakeDisk> {
+        Self::fake_disk(&Self::default_disk_name())
+    }
+
+    /// Replace the disk `name` with an in-memory one for this thread
+    pub fn fake_disk(name: &str) -> Arc<FakeDisk> {
+        let fake = Arc::new(FakeDisk::new());
+        FAKE_DISKS.with(|fakes| {
+            fakes
+                .borrow_mut()
+                .insert(name.to_string(), Arc::clone(&fake))
+        });
+        fake
+    }
+
+    // =========================================================================
+    // Default disk shortcuts
+    // =========================================================================
+
+    /// Write a file on the default disk, replacing any existing one
+    pub async fn put(path: &str, contents: impl Into<Bytes>) -> Result<(), FrameworkError> {
+        Self::default_disk()?.put(path, contents.into()).await
+    }
+
+    /// Read a file from the default disk
+    pub async fn get(path: &str) -> Result<Bytes, FrameworkError> {
+        Self::default_disk()?.get(path).await
+    }
+
+    /// Check if a file exists on the default disk
+    pub async fn exists(path: &str) -> Result<bool, FrameworkError> {
+        Self::default_disk()?.exists(path).await
+    }
+
+    /// Delete a file from the default disk
+    ///
+    /// Returns `true` if the file existed.
+    pub async fn delete(path: &str) -> Result<bool, FrameworkError> {
+        Self::default_disk()?.delete(path).await
+    }
+
+    /// Size of a file on the default disk in bytes
+    pub async fn size(path: &str) -> Result<u64, FrameworkError> {
+        Self::default_disk()?.size(path).await
+    }
+
+    /// Paths of the files directly inside `dir` on the default disk
+    pub async fn files(dir: &str) -> Result<Vec<String>, FrameworkError> {
+        Self::default_disk()?.files(dir).await
+    }
+
+    /// Open a file on the default disk for reading
+    pub async fn read_stream(path: &str) -> Result<FileStream, FrameworkError> {
+        Self::default_disk()?.read_stream(path).await
+    }
+
+    /// Write a file on the default disk from a stream
+    pub async fn write_stream(path: &str, stream: FileStream) -> Result<u64, FrameworkError> {
+        Self::default_disk()?.write_stream(path, stream).await
+    }
+
+    /// Public URL of a file on the default disk
+    pub fn url(path: &str) -> Result<String, FrameworkError> {
+        Self::default_disk()?.url(path)
+    }
+
+    /// Expiring URL of a file on the default disk
+    pub fn temporary_url(path: &str, expires_in: Duration) -> Result<String, FrameworkError> {
+        Self::default_disk()?.temporary_url(path, expires_in)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fake_replaces_the_disk() {
+        let disk = Storage::fake_disk("s3");
+        let s3 = Storage::disk("s3").unwrap();
+
+        s3.put("avatars/1.png", Bytes::from("png")).await.unwrap();
+        disk.assert_exists("/avatars/1.png");
+        disk.assert_missing("avatars/2.png");
+        assert_eq!(s3.files("avatars").await.unwrap(), vec!["avatars/1.png"]);
+
+        assert!(Storage::disk("ftp").is_err());
+    }
+}
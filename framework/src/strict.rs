@@ -0,0 +1,217 @@
+//! Strict mode - catches common mistakes while developing
+//!
+//! With `APP_STRICT=log`, the default in the local environment, every
+//! request is checked for:
+//!
+//! - **N+1 queries**: the same statement running `N_PLUS_ONE_THRESHOLD`
+//!   times in one request, usually a query inside a loop that should be a
+//!   single `IN (...)` query or a join. Literals are ignored when comparing
+//!   statements.
+//! - **Blocking the runtime**: a single poll of the request taking longer
+//!   than `BLOCKING_POLL`, usually `std::fs`, `std::thread::sleep`, a
+//!   blocking HTTP client or heavy hashing on the async runtime. Move such
+//!   work to `kit::blocking` or an async API.
+//!
+//! Violations are printed to stderr. `APP_STRICT=panic` panics instead, so
+//! tests that trip a check fail; `APP_STRICT=off` disables the checks.
+//! Forgetting to `.await` a response or query is already caught by the
+//! compiler's `unused_must_use` lint.
+
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::{AppConfig, Config};
+
+/// Times one statement runs in a request before it is reported as N+1
+pub const N_PLUS_ONE_THRESHOLD: usize = 5;
+
+/// Longest a single poll of a request may block the runtime
+pub const BLOCKING_POLL: Duration = Duration::from_millis(100);
+
+tokio::task_local! {
+    static CHECKS: Arc<RequestChecks>;
+}
+
+/// What strict mode does when a check fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StrictMode {
+    /// No checks
+    #[default]
+    Off,
+    /// Print violations to stderr
+    Log,
+    /// Panic on the first violation
+    Panic,
+}
+
+impl StrictMode {
+    /// Whether requests are checked
+    pub fn is_enabled(self) -> bool {
+        self != Self::Off
+    }
+}
+
+impl FromStr for StrictMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" | "0" => Ok(Self::Off),
+            "log" | "on" | "true" | "1" => Ok(Self::Log),
+            "panic" => Ok(Self::Panic),
+            other => Err(format!("Unknown strict mode '{}'", other)),
+        }
+    }
+}
+
+/// Checks collected for one request
+struct RequestChecks {
+    mode: StrictMode,
+    /// Times each normalized statement ran
+    queries: Mutex<HashMap<String, usize>>,
+}
+
+/// Run a request's future with the checks of `AppConfig::strict_mode`
+pub(crate) async fn scope<F: Future>(future: F) -> F::Output {
+    let mode = Config::get::<AppConfig>().unwrap_or_default().strict_mode;
+    scope_with(mode, future).await
+}
+
+async fn scope_with<F: Future>(mode: StrictMode, future: F) -> F::Output {
+    if !mode.is_enabled() {
+        return future.await;
+    }
+    let checks = Arc::new(RequestChecks {
+        mode,
+        queries: Mutex::new(HashMap::new()),
+    });
+
+    let mut future = Box::pin(future);
+    let timed = poll_fn(move |cx| {
+        let started = Instant::now();
+        let poll = future.as_mut().poll(cx);
+        let blocked = started.elapsed();
+        if blocked >= BLOCKING_POLL {
+            violation(
+                mode,
+                format!(
+                    "request blocked the async runtime for {}ms without yielding; \
+                     move blocking work to kit::blocking",
+                    blocked.as_millis()
+                ),
+            );
+        }
+        poll
+    });
+    CHECKS.scope(checks, timed).await
+}
+
+/// Count a query against the current request, reporting likely N+1 queries
+///
+/// Does nothing outside of a strict request.
+pub(crate) fn record_query(sql: &str) {
+    let _ = CHECKS.try_with(|checks| {
+        let runs = {
+            let mut queries = checks.queries.lock().unwrap_or_else(|e| e.into_inner());
+            let runs = queries.entry(normalize(sql)).or_insert(0);
+            *runs += 1;
+            *runs
+        };
+        if runs == N_PLUS_ONE_THRESHOLD {
+            violation(
+                checks.mode,
+                format!(
+                    "possible N+1 query, run {} times in one request: {}",
+                    runs, sql
+                ),
+            );
+        }
+    });
+}
+
+fn violation(mode: StrictMode, message: String) {
+    match mode {
+        StrictMode::Off => {}
+        StrictMode::Log => eprintln!("Strict mode: {}", message),
+        StrictMode::Panic => panic!("Strict mode: {}", message),
+    }
+}
+
+/// `sql` with string and number literals replaced by `?`
+fn normalize(sql: &str) -> String {
+    let mut normalized = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut previous = ' ';
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            // Quotes inside strings are doubled, which reads as two strings
+            for c in chars.by_ref() {
+                if c == '\'' {
+                    break;
+                }
+            }
+            normalized.push('?');
+        } else if c.is_ascii_digit()
+            && !(previous.is_alphanumeric() || matches!(previous, '_' | '$'))
+        {
+            while chars
+                .peek()
+                .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+            {
+                chars.next();
+            }
+            normalized.push('?');
+        } else {
+            normalized.push(c);
+        }
+        previous = c;
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statements_differing_in_literals_match() {
+        assert_eq!(
+            normalize("SELECT * FROM posts WHERE user_id = 12 AND title = 'it''s'"),
+            "SELECT * FROM posts WHERE user_id = ? AND title = ??"
+        );
+        assert_eq!(
+            normalize("SELECT * FROM t2 WHERE id = $1 LIMIT 3"),
+            "SELECT * FROM t2 WHERE id = $1 LIMIT ?"
+        );
+        assert_eq!("panic".parse(), Ok(StrictMode::Panic));
+        assert_eq!("false".parse(), Ok(StrictMode::Off));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "possible N+1 query, run 5 times")]
+    async fn repeated_queries_are_reported() {
+        scope_with(StrictMode::Panic, async {
+            record_query("SELECT * FROM users WHERE id = 1");
+            for id in 0..N_PLUS_ONE_THRESHOLD {
+                record_query(&format!("SELECT * FROM posts WHERE user_id = {}", id));
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "blocked the async runtime")]
+    async fn blocking_polls_are_reported() {
+        scope_with(StrictMode::Panic, async {
+            // Yielding in between keeps each poll short
+            for _ in 0..3 {
+                tokio::time::sleep(BLOCKING_POLL / 2).await;
+            }
+            std::thread::sleep(BLOCKING_POLL + Duration::from_millis(20));
+        })
+        .await;
+    }
+}
@@ -0,0 +1,174 @@
+//! Supervisor configuration loaded from `supervisor.toml`
+
+use crate::error::FrameworkError;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Default supervisor configuration file
+pub const DEFAULT_CONFIG_FILE: &str = "supervisor.toml";
+
+/// Kind of worker a pool runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolKind {
+    /// The task scheduler (`schedule:work`)
+    Scheduler,
+    /// Durable workflow workers (`workflow:work`)
+    Workflow,
+}
+
+impl PoolKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Scheduler => "scheduler",
+            Self::Workflow => "workflow",
+        }
+    }
+}
+
+/// A named pool of identical workers
+#[derive(Debug, Clone, Deserialize)]
+pub struct PoolConfig {
+    /// Worker kind
+    pub kind: PoolKind,
+    /// Number of workers in the pool (the scheduler always runs one)
+    #[serde(default = "default_workers")]
+    pub workers: usize,
+    /// Concurrent jobs per worker (defaults to `WORKFLOW_CONCURRENCY`)
+    pub concurrency: Option<usize>,
+}
+
+/// Supervisor configuration
+///
+/// # Example
+///
+/// ```toml
+/// status_interval_secs = 2
+/// status_port = 9090
+///
+/// [pools.scheduler]
+/// kind = "scheduler"
+///
+/// [pools.emails]
+/// kind = "workflow"
+/// workers = 2
+/// concurrency = 8
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct SupervisorConfig {
+    /// Seconds between dashboard refreshes
+    #[serde(default = "default_status_interval")]
+    pub status_interval_secs: u64,
+    /// Serve JSON status on `127.0.0.1:<port>` when set
+    pub status_port: Option<u16>,
+    /// Worker pools keyed by name
+    #[serde(default)]
+    pub pools: BTreeMap<String, PoolConfig>,
+}
+
+fn default_workers() -> usize {
+    1
+}
+
+fn default_status_interval() -> u64 {
+    2
+}
+
+impl Default for SupervisorConfig {
+    /// One scheduler and one workflow worker
+    fn default() -> Self {
+        let mut pools = BTreeMap::new();
+        pools.insert(
+            "scheduler".to_string(),
+            PoolConfig {
+                kind: PoolKind::Scheduler,
+                workers: 1,
+                concurrency: None,
+            },
+        );
+        pools.insert(
+            "workflows".to_string(),
+            PoolConfig {
+                kind: PoolKind::Workflow,
+                workers: 1,
+                concurrency: None,
+            },
+        );
+        Self {
+            status_interval_secs: default_status_interval(),
+            status_port: None,
+            pools,
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Parse configuration from TOML
+    pub fn from_toml(contents: &str) -> Result<Self, FrameworkError> {
+        toml::from_str(contents)
+            .map_err(|e| FrameworkError::internal(format!("Invalid supervisor config: {}", e)))
+    }
+
+    /// Load configuration from a file, falling back to defaults if it doesn't exist
+    pub fn load(path: &Path) -> Result<Self, FrameworkError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            FrameworkError::internal(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Self::from_toml(&contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pools() {
+        let config = SupervisorConfig::from_toml(
+            r#"
+            status_port = 9090
+
+            [pools.scheduler]
+            kind = "scheduler"
+
+            [pools.emails]
+            kind = "workflow"
+            workers = 2
+            concurrency = 8
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.status_port, Some(9090));
+        assert_eq!(config.status_interval_secs, 2);
+        assert_eq!(config.pools.len(), 2);
+
+        let emails = &config.pools["emails"];
+        assert_eq!(emails.kind, PoolKind::Workflow);
+        assert_eq!(emails.workers, 2);
+        assert_eq!(emails.concurrency, Some(8));
+        assert_eq!(config.pools["scheduler"].workers, 1);
+    }
+
+    #[test]
+    fn test_rejects_unknown_kind() {
+        let result = SupervisorConfig::from_toml(
+            r#"
+            [pools.mystery]
+            kind = "mystery"
+            "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = SupervisorConfig::default();
+        assert_eq!(config.pools["scheduler"].kind, PoolKind::Scheduler);
+        assert_eq!(config.pools["workflows"].kind, PoolKind::Workflow);
+    }
+}
@@ -0,0 +1,382 @@
+//! Worker supervisor for `work --supervise`
+//!
+//! Runs the scheduler and workflow worker pools side by side in one process,
+//! restarts workers that crash, and reports throughput, failures, and memory
+//! usage on a live terminal dashboard (or as JSON).
+//!
+//! Pools are configured in `supervisor.toml`:
+//!
+//! ```toml
+//! [pools.scheduler]
+//! kind = "scheduler"
+//!
+//! [pools.emails]
+//! kind = "workflow"
+//! workers = 2
+//! concurrency = 8
+//! ```
+//!
+//! ```bash
+//! kit work --supervise
+//! kit work --supervise --json
+//! ```
+
+pub mod config;
+
+pub use config::{PoolConfig, PoolKind, SupervisorConfig, DEFAULT_CONFIG_FILE};
+
+use crate::daemon::{self, Daemon, DaemonOptions, WorkerStats};
+use crate::error::FrameworkError;
+use crate::schedule::Schedule;
+use crate::workflow::{WorkflowConfig, WorkflowWorker};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::io::IsTerminal;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::task::JoinSet;
+
+/// Runtime state of a pool shared between its workers and the dashboard
+struct PoolState {
+    name: String,
+    config: PoolConfig,
+    stats: Arc<WorkerStats>,
+    restarts: AtomicU64,
+}
+
+/// Point-in-time view of a pool
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolSnapshot {
+    pub name: String,
+    pub kind: &'static str,
+    pub workers: usize,
+    pub processed: u64,
+    pub failed: u64,
+    pub restarts: u64,
+    /// Jobs processed per minute since the supervisor started
+    pub per_minute: f64,
+}
+
+/// Point-in-time view of the supervisor
+#[derive(Debug, Clone, Serialize)]
+pub struct SupervisorSnapshot {
+    pub uptime_secs: u64,
+    pub memory_mb: Option<u64>,
+    pub pools: Vec<PoolSnapshot>,
+}
+
+/// Runs worker pools and reports on them
+pub struct Supervisor {
+    config: SupervisorConfig,
+    schedule: Arc<Schedule>,
+    options: DaemonOptions,
+    supervise: bool,
+    json: bool,
+}
+
+impl Supervisor {
+    /// Create a supervisor for the given pools and schedule
+    pub fn new(config: SupervisorConfig, schedule: Schedule) -> Self {
+        Self {
+            config,
+            schedule: Arc::new(schedule),
+            options: DaemonOptions::default(),
+            supervise: true,
+            json: false,
+        }
+    }
+
+    /// Daemon options applied to every worker
+    pub fn daemon_options(mut self, options: DaemonOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Restart workers that crash and report status (default: true)
+    ///
+    /// Without supervision the pools simply run until they stop.
+    pub fn supervise(mut self, supervise: bool) -> Self {
+        self.supervise = supervise;
+        self
+    }
+
+    /// Print JSON status lines instead of the terminal dashboard
+    pub fn json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    /// Run all pools until every worker has stopped
+    pub async fn run(self) -> Result<(), FrameworkError> {
+        if self.config.pools.is_empty() {
+            return Err(FrameworkError::internal(
+                "No worker pools configured in supervisor.toml",
+            ));
+        }
+
+        let started = Instant::now();
+        let pools: Arc<Vec<PoolState>> = Arc::new(
+            self.config
+                .pools
+                .iter()
+                .map(|(name, config)| PoolState {
+                    name: name.clone(),
+                    config: config.clone(),
+                    stats: Arc::new(WorkerStats::default()),
+                    restarts: AtomicU64::new(0),
+                })
+                .collect(),
+        );
+
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        tokio::spawn(async move {
+            daemon::shutdown_signal().await;
+            let _ = shutdown_tx.send(true);
+        });
+
+        let mut workers = JoinSet::new();
+        for (index, pool) in pools.iter().enumerate() {
+            let count = match pool.config.kind {
+                PoolKind::Scheduler => 1,
+                PoolKind::Workflow => pool.config.workers.max(1),
+            };
+            for _ in 0..count {
+                workers.spawn(supervise_worker(
+                    pools.clone(),
+                    index,
+                    self.schedule.clone(),
+                    self.options.clone(),
+                    self.supervise,
+                    shutdown_rx.clone(),
+                ));
+            }
+        }
+
+        if let Some(port) = self.config.status_port {
+            let pools = pools.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve_status(port, pools, started).await {
+                    eprintln!("Supervisor status endpoint error: {}", e);
+                }
+            });
+        }
+
+        let interval = Duration::from_secs(self.config.status_interval_secs.max(1));
+        let dashboard = !self.json && std::io::stdout().is_terminal();
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                joined = workers.join_next() => {
+                    if joined.is_none() {
+                        break;
+                    }
+                }
+                _ = ticker.tick(), if self.supervise => {
+                    let status = snapshot(&pools, started);
+                    if self.json {
+                        println!("{}", serde_json::to_string(&status).unwrap_or_default());
+                    } else {
+                        print_dashboard(&status, dashboard);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Run one worker, restarting it if it panics
+async fn supervise_worker(
+    pools: Arc<Vec<PoolState>>,
+    index: usize,
+    schedule: Arc<Schedule>,
+    options: DaemonOptions,
+    restart: bool,
+    shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        let pool = &pools[index];
+        let kind = pool.config.kind;
+        let name = pool.name.clone();
+        let stats = pool.stats.clone();
+        let concurrency = pool.config.concurrency;
+        let schedule = schedule.clone();
+        let options = options.clone();
+
+        let handle = tokio::spawn(async move {
+            let daemon = Daemon::new(&name, options);
+            match kind {
+                PoolKind::Scheduler => {
+                    schedule.work(daemon, stats).await;
+                }
+                PoolKind::Workflow => {
+                    let mut config = WorkflowConfig::from_env();
+                    if let Some(concurrency) = concurrency {
+                        config.concurrency = concurrency;
+                    }
+                    let worker = WorkflowWorker::with_config(config).with_stats(stats);
+                    if let Err(e) = worker.run(daemon).await {
+                        eprintln!("{} worker error: {}", name, e);
+                    }
+                }
+            }
+        });
+
+        match handle.await {
+            Err(e) if e.is_panic() && restart && !*shutdown.borrow() => {
+                pool.restarts.fetch_add(1, Ordering::Relaxed);
+                eprintln!("{} worker crashed, restarting", pool.name);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            _ => break,
+        }
+    }
+}
+
+fn snapshot(pools: &[PoolState], started: Instant) -> SupervisorSnapshot {
+    let elapsed = started.elapsed();
+    let minutes = (elapsed.as_secs_f64() / 60.0).max(1.0 / 60.0);
+
+    SupervisorSnapshot {
+        uptime_secs: elapsed.as_secs(),
+        memory_mb: daemon::resident_memory_mb(),
+        pools: pools
+            .iter()
+            .map(|pool| {
+                let processed = pool.stats.processed();
+                PoolSnapshot {
+                    name: pool.name.clone(),
+                    kind: pool.config.kind.as_str(),
+                    workers: match pool.config.kind {
+                        PoolKind::Scheduler => 1,
+                        PoolKind::Workflow => pool.config.workers.max(1),
+                    },
+                    processed,
+                    failed: pool.stats.failed(),
+                    restarts: pool.restarts.load(Ordering::Relaxed),
+                    per_minute: processed as f64 / minutes,
+                }
+            })
+            .collect(),
+    }
+}
+
+fn print_dashboard(status: &SupervisorSnapshot, clear: bool) {
+    if clear {
+        // Clear screen and move cursor to the top-left corner
+        print!("\x1b[2J\x1b[H");
+    }
+
+    let uptime = status.uptime_secs;
+    let memory = status
+        .memory_mb
+        .map(|mb| format!("{} MB", mb))
+        .unwrap_or_else(|| "n/a".to_string());
+
+    println!(
+        "Kit Supervisor  uptime {:02}:{:02}:{:02}  memory {}",
+        uptime / 3600,
+        (uptime % 3600) / 60,
+        uptime % 60,
+        memory
+    );
+    println!();
+    println!(
+        "{:<16} {:<10} {:>7} {:>10} {:>8} {:>9} {:>9}",
+        "POOL", "KIND", "WORKERS", "PROCESSED", "FAILED", "PER MIN", "RESTARTS"
+    );
+    for pool in &status.pools {
+        println!(
+            "{:<16} {:<10} {:>7} {:>10} {:>8} {:>9.1} {:>9}",
+            pool.name,
+            pool.kind,
+            pool.workers,
+            pool.processed,
+            pool.failed,
+            pool.per_minute,
+            pool.restarts
+        );
+    }
+    println!();
+    println!("Press Ctrl+C to stop");
+}
+
+/// Serve the supervisor snapshot as JSON on `127.0.0.1:<port>`
+async fn serve_status(
+    port: u16,
+    pools: Arc<Vec<PoolState>>,
+    started: Instant,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let listener = TcpListener::bind(addr).await?;
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let io = TokioIo::new(stream);
+        let pools = pools.clone();
+
+        tokio::spawn(async move {
+            let service = service_fn(move |_req: hyper::Request<hyper::body::Incoming>| {
+                let body = serde_json::to_string(&snapshot(&pools, started)).unwrap_or_default();
+                async move {
+                    Ok::<_, Infallible>(
+                        hyper::Response::builder()
+                            .status(200)
+                            .header("Content-Type", "application/json")
+                            .body(Full::new(Bytes::from(body)))
+                            .unwrap(),
+                    )
+                }
+            });
+
+            if let Err(err) = http1::Builder::new().serve_connection(io, service).await {
+                eprintln!("Error serving supervisor status: {:?}", err);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_reports_pool_stats() {
+        let config = SupervisorConfig::default();
+        let pools: Vec<PoolState> = config
+            .pools
+            .iter()
+            .map(|(name, config)| PoolState {
+                name: name.clone(),
+                config: config.clone(),
+                stats: Arc::new(WorkerStats::default()),
+                restarts: AtomicU64::new(0),
+            })
+            .collect();
+
+        pools[1].stats.record_processed(10);
+        pools[1].stats.record_failure();
+        pools[1].restarts.fetch_add(1, Ordering::Relaxed);
+
+        let status = snapshot(&pools, Instant::now());
+        assert_eq!(status.pools.len(), 2);
+
+        let workflows = &status.pools[1];
+        assert_eq!(workflows.name, "workflows");
+        assert_eq!(workflows.kind, "workflow");
+        assert_eq!(workflows.processed, 10);
+        assert_eq!(workflows.failed, 1);
+        assert_eq!(workflows.restarts, 1);
+        assert!(workflows.per_minute > 0.0);
+    }
+}
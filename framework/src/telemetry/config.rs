@@ -0,0 +1,103 @@
+//! Trace export configuration for Kit framework
+
+use crate::config::env;
+
+/// OpenTelemetry trace export configuration
+///
+/// # Environment Variables
+///
+/// - `OTEL_EXPORTER_OTLP_ENDPOINT` - Base URL of the OTLP/HTTP collector,
+///   e.g. "http://localhost:4318" (default: "", export disabled)
+/// - `OTEL_SERVICE_NAME` - `service.name` of exported spans (default: `APP_NAME`)
+/// - `OTEL_EXPORTER_OTLP_TIMEOUT` - Milliseconds to wait for the collector
+///   (default: 10000)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{Config, TelemetryConfig};
+///
+/// // Register from environment
+/// Config::register(TelemetryConfig::from_env());
+///
+/// // Or build manually
+/// Config::register(TelemetryConfig::builder()
+///     .endpoint("http://otel-collector:4318")
+///     .service_name("shop")
+///     .build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TelemetryConfig {
+    /// Base URL of the collector, empty to disable export
+    pub endpoint: String,
+    /// `service.name` resource attribute
+    pub service_name: String,
+    /// Milliseconds to wait for the collector
+    pub timeout: u64,
+}
+
+impl TelemetryConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Self {
+        let app_name = env("APP_NAME", "Kit Application".to_string());
+        Self {
+            endpoint: env("OTEL_EXPORTER_OTLP_ENDPOINT", String::new()),
+            service_name: env("OTEL_SERVICE_NAME", app_name),
+            timeout: env("OTEL_EXPORTER_OTLP_TIMEOUT", 10_000),
+        }
+    }
+
+    /// Create a builder for manual configuration
+    pub fn builder() -> TelemetryConfigBuilder {
+        TelemetryConfigBuilder::default()
+    }
+
+    /// Whether spans are exported
+    pub fn is_enabled(&self) -> bool {
+        !self.endpoint.is_empty()
+    }
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Builder for TelemetryConfig
+#[derive(Debug, Default)]
+pub struct TelemetryConfigBuilder {
+    endpoint: Option<String>,
+    service_name: Option<String>,
+    timeout: Option<u64>,
+}
+
+impl TelemetryConfigBuilder {
+    /// Set the base URL of the collector
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Set the `service.name` of exported spans
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = Some(name.into());
+        self
+    }
+
+    /// Set the milliseconds to wait for the collector
+    pub fn timeout(mut self, millis: u64) -> Self {
+        self.timeout = Some(millis);
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> TelemetryConfig {
+        let defaults = TelemetryConfig::from_env();
+        TelemetryConfig {
+            endpoint: self.endpoint.unwrap_or(defaults.endpoint),
+            service_name: self.service_name.unwrap_or(defaults.service_name),
+            timeout: self.timeout.unwrap_or(defaults.timeout),
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! OpenTelemetry trace export
+//!
+//! With `OTEL_EXPORTER_OTLP_ENDPOINT` set (see `TelemetryConfig`), every
+//! request becomes a server span with a child span for each database query
+//! it runs. Workflow runs get a span with one child per executed step. Spans
+//! are sent in batches to `{endpoint}/v1/traces` as OTLP/HTTP JSON, which
+//! the OpenTelemetry Collector, Jaeger, Tempo and most vendors accept.
+//!
+//! A request with a W3C `traceparent` header continues the caller's trace.
+//!
+//! ```bash
+//! OTEL_EXPORTER_OTLP_ENDPOINT=http://localhost:4318
+//! OTEL_SERVICE_NAME=shop
+//! ```
+//!
+//! Spans are exported every few seconds, so call `telemetry::flush()` before
+//! a short-lived process exits.
+
+pub mod config;
+mod otlp;
+
+pub use config::{TelemetryConfig, TelemetryConfigBuilder};
+
+use hyper::header::HeaderMap;
+use rand::Rng;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::config::Config;
+use crate::http::Response;
+use otlp::{Attribute, Exporter, SpanData, SpanKind};
+
+/// The exporter, or `None` when export is disabled
+static EXPORTER: OnceLock<Option<&'static Exporter>> = OnceLock::new();
+
+tokio::task_local! {
+    static CURRENT: SpanContext;
+}
+
+/// The span children of the running future belong to
+#[derive(Clone, Copy)]
+struct SpanContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    exporter: &'static Exporter,
+}
+
+/// A span in another service, from a `traceparent` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RemoteParent {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+}
+
+fn exporter() -> Option<&'static Exporter> {
+    *EXPORTER.get_or_init(|| {
+        let config = Config::get::<TelemetryConfig>().unwrap_or_default();
+        if !config.is_enabled() {
+            return None;
+        }
+        let exporter: &'static Exporter = Box::leak(Box::new(Exporter::new(&config)));
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let mut interval = tokio::time::interval(otlp::EXPORT_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    exporter.flush().await;
+                }
+            });
+        }
+        Some(exporter)
+    })
+}
+
+/// Export the spans that haven't been sent yet
+pub async fn flush() {
+    if let Some(exporter) = EXPORTER.get().copied().flatten() {
+        exporter.flush().await;
+    }
+}
+
+/// The caller's span from a request's `traceparent` header
+pub(crate) fn remote_parent(headers: &HeaderMap) -> Option<RemoteParent> {
+    parse_traceparent(headers.get("traceparent")?.to_str().ok()?)
+}
+
+/// Parse `{version}-{trace id}-{parent id}-{flags}`
+fn parse_traceparent(value: &str) -> Option<RemoteParent> {
+    let mut parts = value.trim().split('-');
+    let version = parts.next()?;
+    if version.len() != 2 || version == "ff" {
+        return None;
+    }
+    let trace_id: [u8; 16] = hex::decode(parts.next()?).ok()?.try_into().ok()?;
+    let span_id: [u8; 8] = hex::decode(parts.next()?).ok()?.try_into().ok()?;
+    if trace_id == [0; 16] || span_id == [0; 8] {
+        return None;
+    }
+    Some(RemoteParent { trace_id, span_id })
+}
+
+/// Run a request's future as a server span
+///
+/// `route` is the matched route pattern, `None` for the fallback.
+pub(crate) async fn request<F>(
+    method: &str,
+    route: Option<&str>,
+    path: &str,
+    parent: Option<RemoteParent>,
+    future: F,
+) -> Response
+where
+    F: Future<Output = Response>,
+{
+    match exporter() {
+        Some(exporter) => request_with(exporter, method, route, path, parent, future).await,
+        None => future.await,
+    }
+}
+
+async fn request_with<F>(
+    exporter: &'static Exporter,
+    method: &str,
+    route: Option<&str>,
+    path: &str,
+    parent: Option<RemoteParent>,
+    future: F,
+) -> Response
+where
+    F: Future<Output = Response>,
+{
+    let name = match route {
+        Some(route) => format!("{} {}", method, route),
+        None => method.to_string(),
+    };
+    let mut attributes = vec![
+        ("http.request.method", Attribute::from(method)),
+        ("url.path", Attribute::from(path)),
+    ];
+    if let Some(route) = route {
+        attributes.push(("http.route", Attribute::from(route)));
+    }
+    let parent = parent.map(|parent| (parent.trace_id, parent.span_id));
+
+    let (response, mut span) =
+        run(exporter, parent, name, SpanKind::Server, attributes, future).await;
+
+    let status = match &response {
+        Ok(response) | Err(response) => response.status_code(),
+    };
+    span.attributes
+        .push(("http.response.status_code", i64::from(status).into()));
+    if status >= 500 {
+        let reason = hyper::StatusCode::from_u16(status)
+            .ok()
+            .and_then(|status| status.canonical_reason());
+        span.error = Some(reason.unwrap_or("Server error").to_string());
+    }
+    exporter.record(span);
+    response
+}
+
+/// Run `future` as a span inside the current one, or as a new trace
+///
+/// An `Err` marks the span as failed.
+pub(crate) async fn span<T, E, F>(name: impl Into<String>, future: F) -> Result<T, E>
+where
+    E: Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let current = CURRENT.try_with(|current| *current).ok();
+    let Some(exporter) = current.map(|current| current.exporter).or_else(exporter) else {
+        return future.await;
+    };
+    let parent = current.map(|current| (current.trace_id, current.span_id));
+
+    let (result, mut span) = run(
+        exporter,
+        parent,
+        name.into(),
+        SpanKind::Internal,
+        Vec::new(),
+        future,
+    )
+    .await;
+    if let Err(err) = &result {
+        span.error = Some(err.to_string());
+    }
+    exporter.record(span);
+    result
+}
+
+/// Run `future` with a new span as the current one
+async fn run<F: Future>(
+    exporter: &'static Exporter,
+    parent: Option<([u8; 16], [u8; 8])>,
+    name: String,
+    kind: SpanKind,
+    attributes: Vec<(&'static str, Attribute)>,
+    future: F,
+) -> (F::Output, SpanData) {
+    let trace_id = parent.map_or_else(|| rand::thread_rng().gen(), |(trace_id, _)| trace_id);
+    let context = SpanContext {
+        trace_id,
+        span_id: rand::thread_rng().gen(),
+        exporter,
+    };
+
+    let start = SystemTime::now();
+    let started = Instant::now();
+    let output = CURRENT.scope(context, future).await;
+
+    let span = SpanData {
+        trace_id,
+        span_id: context.span_id,
+        parent_id: parent.map(|(_, span_id)| span_id),
+        name,
+        kind,
+        start,
+        end: start + started.elapsed(),
+        attributes,
+        error: None,
+    };
+    (output, span)
+}
+
+/// Record a finished database query as a span in the current one
+///
+/// Does nothing outside of a span.
+pub(crate) fn record_query(sql: &str, elapsed: Duration, failed: bool) {
+    let _ = CURRENT.try_with(|current| {
+        let operation = sql
+            .split_whitespace()
+            .next()
+            .map_or_else(|| "QUERY".to_string(), str::to_ascii_uppercase);
+        let end = SystemTime::now();
+        current.exporter.record(SpanData {
+            trace_id: current.trace_id,
+            span_id: rand::thread_rng().gen(),
+            parent_id: Some(current.span_id),
+            name: operation.clone(),
+            kind: SpanKind::Client,
+            start: end.checked_sub(elapsed).unwrap_or(end),
+            end,
+            attributes: vec![
+                ("db.operation.name", operation.into()),
+                ("db.query.text", sql.into()),
+            ],
+            error: failed.then(|| "Query failed".to_string()),
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FrameworkError;
+    use crate::http::HttpResponse;
+
+    #[tokio::test]
+    async fn requests_queries_and_steps_are_nested_spans() {
+        let config = TelemetryConfig::builder()
+            .endpoint("http://127.0.0.1:4318")
+            .build();
+        let exporter: &'static Exporter = Box::leak(Box::new(Exporter::new(&config)));
+        let caller = parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01");
+        assert!(caller.is_some());
+        assert_eq!(
+            parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01"),
+            None
+        );
+
+        let response = request_with(
+            exporter,
+            "GET",
+            Some("/users/{id}"),
+            "/users/5",
+            caller,
+            async {
+                record_query(
+                    "select * from users where id = $1",
+                    Duration::from_millis(3),
+                    false,
+                );
+                span("welcome email", async {
+                    Err::<(), _>(FrameworkError::internal("smtp down"))
+                })
+                .await
+                .unwrap_err();
+                Err(HttpResponse::text("boom").status(500))
+            },
+        )
+        .await;
+        assert!(response.is_err());
+
+        let spans = exporter.pending();
+        let [query, step, request] = &spans[..] else {
+            panic!("expected 3 spans, got {}", spans.len());
+        };
+        assert_eq!(
+            hex::encode(request.trace_id),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert_eq!(
+            request.parent_id.map(hex::encode).as_deref(),
+            Some("00f067aa0ba902b7")
+        );
+        assert_eq!(request.name, "GET /users/{id}");
+        assert_eq!(request.error.as_deref(), Some("Internal Server Error"));
+        for child in [query, step] {
+            assert_eq!(child.trace_id, request.trace_id);
+            assert_eq!(child.parent_id, Some(request.span_id));
+        }
+        assert_eq!(query.name, "SELECT");
+        assert_eq!(
+            step.error.as_deref(),
+            Some("Internal server error: smtp down")
+        );
+
+        let body = otlp::encode("shop", &spans);
+        let resource = &body["resourceSpans"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "shop"
+        );
+        let exported = &resource["scopeSpans"][0]["spans"][2];
+        assert_eq!(exported["kind"], 2);
+        assert_eq!(exported["parentSpanId"], "00f067aa0ba902b7");
+        assert_eq!(exported["status"]["code"], 2);
+        assert_eq!(
+            exported["attributes"][3],
+            serde_json::json!({
+                "key": "http.response.status_code",
+                "value": { "intValue": "500" },
+            })
+        );
+    }
+}
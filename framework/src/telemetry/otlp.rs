@@ -0,0 +1,197 @@
+//! Batching OTLP/HTTP exporter with JSON encoding
+
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::TelemetryConfig;
+
+/// Spans buffered before an export is started early
+const MAX_BATCH: usize = 512;
+
+/// How often buffered spans are exported
+pub(crate) const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Role of a span in its trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SpanKind {
+    /// Work inside the application, e.g. a workflow step
+    Internal = 1,
+    /// An incoming request
+    Server = 2,
+    /// A call out of the application, e.g. a database query
+    Client = 3,
+}
+
+/// A span attribute value
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Attribute {
+    String(String),
+    Int(i64),
+}
+
+impl From<&str> for Attribute {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Attribute {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<i64> for Attribute {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+/// A finished span
+#[derive(Debug, Clone)]
+pub(crate) struct SpanData {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub parent_id: Option<[u8; 8]>,
+    pub name: String,
+    pub kind: SpanKind,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub attributes: Vec<(&'static str, Attribute)>,
+    /// Error message, if the span failed
+    pub error: Option<String>,
+}
+
+impl SpanData {
+    fn to_json(&self) -> Value {
+        let mut span = json!({
+            "traceId": hex::encode(self.trace_id),
+            "spanId": hex::encode(self.span_id),
+            "name": self.name,
+            "kind": self.kind as u8,
+            "startTimeUnixNano": unix_nanos(self.start),
+            "endTimeUnixNano": unix_nanos(self.end),
+            "attributes": self
+                .attributes
+                .iter()
+                .map(|(key, value)| attribute(key, value))
+                .collect::<Vec<_>>(),
+            "status": match &self.error {
+                Some(message) => json!({ "code": 2, "message": message }),
+                None => json!({}),
+            },
+        });
+        if let Some(parent_id) = self.parent_id {
+            span["parentSpanId"] = hex::encode(parent_id).into();
+        }
+        span
+    }
+}
+
+/// Sends finished spans to a collector in batches
+pub(crate) struct Exporter {
+    url: String,
+    service_name: String,
+    timeout: Duration,
+    client: reqwest::Client,
+    pending: Mutex<Vec<SpanData>>,
+    /// Whether the last export failed, so failures are logged once
+    failing: AtomicBool,
+}
+
+impl Exporter {
+    pub(crate) fn new(config: &TelemetryConfig) -> Self {
+        Self {
+            url: format!("{}/v1/traces", config.endpoint.trim_end_matches('/')),
+            service_name: config.service_name.clone(),
+            timeout: Duration::from_millis(config.timeout),
+            client: reqwest::Client::new(),
+            pending: Mutex::new(Vec::new()),
+            failing: AtomicBool::new(false),
+        }
+    }
+
+    /// Buffer `span`, exporting the buffer once it is full
+    pub(crate) fn record(&'static self, span: SpanData) {
+        let full = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.push(span);
+            pending.len() >= MAX_BATCH
+        };
+        if full {
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                runtime.spawn(self.flush());
+            }
+        }
+    }
+
+    /// Export all buffered spans
+    pub(crate) async fn flush(&self) {
+        let spans = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        if spans.is_empty() {
+            return;
+        }
+
+        let result = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .body(encode(&self.service_name, &spans).to_string())
+            .timeout(self.timeout)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match result {
+            Ok(_) => self.failing.store(false, Ordering::Relaxed),
+            Err(err) => {
+                if !self.failing.swap(true, Ordering::Relaxed) {
+                    eprintln!(
+                        "Warning: failed to export {} spans to {}: {}",
+                        spans.len(),
+                        self.url,
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    /// Spans waiting to be exported
+    #[cfg(test)]
+    pub(crate) fn pending(&self) -> Vec<SpanData> {
+        self.pending.lock().unwrap().clone()
+    }
+}
+
+/// The `ExportTraceServiceRequest` body for `spans`
+pub(crate) fn encode(service_name: &str, spans: &[SpanData]) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [attribute("service.name", &Attribute::from(service_name))],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "kit", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans.iter().map(SpanData::to_json).collect::<Vec<_>>(),
+            }],
+        }],
+    })
+}
+
+fn attribute(key: &str, value: &Attribute) -> Value {
+    let value = match value {
+        Attribute::String(s) => json!({ "stringValue": s }),
+        // 64-bit integers are strings in OTLP/JSON
+        Attribute::Int(i) => json!({ "intValue": i.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
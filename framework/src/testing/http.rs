@@ -0,0 +1,421 @@
+//! Fake requests and response assertions for testing handlers directly
+//!
+//! Handlers are plain async functions taking a `Request`, so they can be
+//! called in a unit test without starting a server or building a router.
+
+use crate::http::{HttpResponse, RemoteAddr, Request, RequestBody, Response, UploadedFile};
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+/// Boundary of multipart bodies built by `FakeRequest::multipart`
+const MULTIPART_BOUNDARY: &str = "kit-fake-request-boundary";
+
+/// Builder for requests used in tests, created with `Request::fake()`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::testing::TestResponse;
+/// use kit::Request;
+///
+/// #[tokio::test]
+/// async fn stores_a_user() {
+///     let request = Request::fake()
+///         .method("POST")
+///         .path("/users")
+///         .json(serde_json::json!({ "name": "Ada" }))
+///         .build();
+///
+///     let response = TestResponse::from(controllers::user::store(request).await);
+///
+///     response.assert_status(201);
+///     assert_eq!(response.json::<serde_json::Value>()["name"], "Ada");
+/// }
+/// ```
+pub struct FakeRequest {
+    method: hyper::Method,
+    path: String,
+    headers: Vec<(String, String)>,
+    params: HashMap<String, String>,
+    body: Bytes,
+    remote_addr: Option<SocketAddr>,
+}
+
+impl Request {
+    /// Start building a fake `GET /` request for tests
+    pub fn fake() -> FakeRequest {
+        FakeRequest {
+            method: hyper::Method::GET,
+            path: "/".to_string(),
+            headers: Vec::new(),
+            params: HashMap::new(),
+            body: Bytes::new(),
+            remote_addr: None,
+        }
+    }
+}
+
+impl FakeRequest {
+    /// Set the HTTP method
+    ///
+    /// # Panics
+    ///
+    /// Panics if `method` is not a valid HTTP method.
+    pub fn method(mut self, method: &str) -> Self {
+        self.method = hyper::Method::from_bytes(method.to_uppercase().as_bytes())
+            .unwrap_or_else(|_| panic!("Invalid HTTP method: {}", method));
+        self
+    }
+
+    /// Set the request path, optionally with a query string
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Add a request header
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set a route parameter, as the router would for `/users/{id}`
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+
+    /// Send a JSON body (sets `Content-Type: application/json`)
+    pub fn json(self, body: serde_json::Value) -> Self {
+        self.header("Content-Type", "application/json")
+            .body(body.to_string())
+    }
+
+    /// Send a form-urlencoded body (sets the matching `Content-Type`)
+    pub fn form(self, fields: &[(&str, &str)]) -> Self {
+        let body = serde_urlencoded::to_string(fields).expect("Failed to encode form body");
+        self.header("Content-Type", "application/x-www-form-urlencoded")
+            .body(body)
+    }
+
+    /// Send a `multipart/form-data` body with text fields and files
+    ///
+    /// ```rust,ignore
+    /// let request = Request::fake()
+    ///     .method("POST")
+    ///     .multipart(
+    ///         &[("name", "Ada")],
+    ///         &[("avatar", UploadedFile::new("me.png", "image/png", png_bytes))],
+    ///     )
+    ///     .build();
+    /// ```
+    pub fn multipart(self, fields: &[(&str, &str)], files: &[(&str, UploadedFile)]) -> Self {
+        let mut body = Vec::new();
+        for (name, value) in fields {
+            body.extend_from_slice(
+                format!(
+                    "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                    MULTIPART_BOUNDARY, name, value
+                )
+                .as_bytes(),
+            );
+        }
+        for (name, file) in files {
+            body.extend_from_slice(
+                format!(
+                    "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: {}\r\n\r\n",
+                    MULTIPART_BOUNDARY,
+                    name,
+                    file.client_name().unwrap_or_default(),
+                    file.content_type()
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(file.bytes());
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+
+        self.header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        )
+        .body(body)
+    }
+
+    /// Send a raw body
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Set the address the connection came from, e.g. `"10.0.0.1:5000"`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `addr` is not an `ip:port` socket address.
+    pub fn remote_addr(mut self, addr: &str) -> Self {
+        self.remote_addr = Some(
+            addr.parse()
+                .unwrap_or_else(|_| panic!("Invalid socket address: {}", addr)),
+        );
+        self
+    }
+
+    /// Mark the request as an Inertia XHR request
+    pub fn inertia(self) -> Self {
+        self.header("X-Inertia", "true")
+    }
+
+    /// Build the `Request`
+    pub fn build(self) -> Request {
+        let mut builder = hyper::Request::builder()
+            .method(self.method)
+            .uri(self.path);
+        for (name, value) in self.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(addr) = self.remote_addr {
+            builder = builder.extension(RemoteAddr(addr));
+        }
+        let inner = builder
+            .body(RequestBody::Full(self.body))
+            .expect("Invalid fake request");
+
+        Request::from_hyper(inner).with_params(self.params)
+    }
+}
+
+impl From<FakeRequest> for Request {
+    fn from(fake: FakeRequest) -> Request {
+        fake.build()
+    }
+}
+
+/// A handler's `Response` with helpers for assertions
+///
+/// Both `Ok` and `Err` responses are kept, since handlers return errors as
+/// responses too.
+pub struct TestResponse {
+    response: HttpResponse,
+    is_err: bool,
+}
+
+impl From<Response> for TestResponse {
+    fn from(response: Response) -> Self {
+        match response {
+            Ok(response) => Self {
+                response,
+                is_err: false,
+            },
+            Err(response) => Self {
+                response,
+                is_err: true,
+            },
+        }
+    }
+}
+
+impl TestResponse {
+    /// The HTTP status code
+    pub fn status(&self) -> u16 {
+        self.response.status_code()
+    }
+
+    /// Whether the handler returned `Err`
+    pub fn is_err(&self) -> bool {
+        self.is_err
+    }
+
+    /// The first value of a response header (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.response
+            .headers()
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The body as text
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(self.response.body()).into_owned()
+    }
+
+    /// The body parsed as JSON
+    ///
+    /// # Panics
+    ///
+    /// Panics if the body is not valid JSON for `T`.
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(self.response.body()).unwrap_or_else(|e| {
+            panic!(
+                "Response body is not the expected JSON: {}\nBody: {}",
+                e,
+                self.text()
+            )
+        })
+    }
+
+    /// Assert the status code
+    #[track_caller]
+    pub fn assert_status(&self, expected: u16) -> &Self {
+        assert_eq!(
+            self.status(),
+            expected,
+            "Expected status {}, got {}. Body: {}",
+            expected,
+            self.status(),
+            self.text()
+        );
+        self
+    }
+
+    /// Assert a response header value
+    #[track_caller]
+    pub fn assert_header(&self, name: &str, expected: &str) -> &Self {
+        assert_eq!(
+            self.header(name),
+            Some(expected),
+            "Unexpected value for header {}",
+            name
+        );
+        self
+    }
+
+    /// Assert the handler redirected to `location`
+    #[track_caller]
+    pub fn assert_redirect(&self, location: &str) -> &Self {
+        assert!(
+            (300..400).contains(&self.status()),
+            "Expected a redirect, got status {}",
+            self.status()
+        );
+        self.assert_header("Location", location)
+    }
+
+    /// The underlying `HttpResponse`
+    pub fn into_inner(self) -> HttpResponse {
+        self.response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FrameworkError;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct NewUser {
+        name: String,
+    }
+
+    async fn store(req: Request) -> Response {
+        let id = req.param("team")?.to_string();
+        let auth = req.header("Authorization").unwrap_or_default().to_string();
+        let user: NewUser = req.json().await?;
+        Ok(HttpResponse::json(serde_json::json!({
+            "team": id,
+            "auth": auth,
+            "name": user.name,
+        }))
+        .status(201))
+    }
+
+    #[tokio::test]
+    async fn test_fake_request_reaches_handler() {
+        let request = Request::fake()
+            .method("post")
+            .path("/teams/7/users")
+            .param("team", "7")
+            .header("Authorization", "Bearer token")
+            .json(serde_json::json!({ "name": "Ada" }))
+            .build();
+        assert_eq!(request.method(), hyper::Method::POST);
+        assert_eq!(request.path(), "/teams/7/users");
+
+        let response = TestResponse::from(store(request).await);
+
+        response
+            .assert_status(201)
+            .assert_header("content-type", "application/json");
+        let body: serde_json::Value = response.json();
+        assert_eq!(
+            body,
+            serde_json::json!({ "team": "7", "auth": "Bearer token", "name": "Ada" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_kept_as_responses() {
+        let response = TestResponse::from(store(Request::fake().build()).await);
+
+        assert!(response.is_err());
+        response.assert_status(FrameworkError::param("team").status_code());
+    }
+
+    #[test]
+    fn test_forwarded_headers_need_a_trusted_proxy() {
+        use crate::{Config, Redirect, TrustedProxies};
+
+        Config::register(TrustedProxies::none().trust("10.0.0.0/8"));
+        let forwarded = |addr: &str| {
+            Request::fake()
+                .path("/users?page=2")
+                .header("Host", "internal:8080")
+                .header("X-Forwarded-For", "203.0.113.9, 10.0.0.2")
+                .header("X-Forwarded-Proto", "https")
+                .header("X-Forwarded-Host", "example.com")
+                .remote_addr(addr)
+                .build()
+        };
+
+        let request = forwarded("10.0.0.1:4000");
+        assert_eq!(request.ip(), Some("203.0.113.9".parse().unwrap()));
+        assert_eq!(request.scheme(), "https");
+        assert_eq!(request.host(), Some("example.com"));
+        assert_eq!(request.url(), "https://example.com/users?page=2");
+        let response = TestResponse::from(Response::from(
+            Redirect::to("/login").absolute(&request),
+        ));
+        response.assert_redirect("https://example.com/login");
+
+        let request = forwarded("198.51.100.1:4000");
+        assert_eq!(request.ip(), Some("198.51.100.1".parse().unwrap()));
+        assert_eq!(request.scheme(), "http");
+        assert_eq!(request.url(), "http://internal:8080/users?page=2");
+
+        // A client-sent Forwarded header passed through by the proxy is
+        // ignored while the proxy's X-Forwarded-For is the configured header
+        let spoofed = || {
+            Request::fake()
+                .header("Forwarded", "for=1.2.3.4")
+                .header("X-Forwarded-For", "203.0.113.9")
+                .remote_addr("10.0.0.1:4000")
+                .build()
+        };
+        assert_eq!(spoofed().client_ip(), Some("203.0.113.9".parse().unwrap()));
+        Config::register(
+            TrustedProxies::none()
+                .trust("10.0.0.0/8")
+                .header(crate::ForwardedHeader::Forwarded),
+        );
+        assert_eq!(spoofed().client_ip(), Some("1.2.3.4".parse().unwrap()));
+        Config::register(TrustedProxies::none().trust("10.0.0.0/8"));
+
+        assert_eq!(Request::fake().build().ip(), None);
+    }
+
+    #[tokio::test]
+    async fn test_form_body() {
+        let request = Request::fake()
+            .form(&[("name", "Ada Lovelace")])
+            .build();
+
+        let user: NewUser = request.input().await.unwrap();
+        assert_eq!(user.name, "Ada Lovelace");
+    }
+}
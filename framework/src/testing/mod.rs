@@ -2,9 +2,10 @@
 //!
 //! Provides Jest-like testing helpers including:
 //! - `expect!` macro for fluent assertions with clear expected/received output
-//! - `describe!` and `test!` macros for test organization
-//! - `TestDatabase` for isolated database tests
-//! - `TestContainer` for dependency injection in tests
+//! - `describe!`, `test!` and `test_each!` macros for test organization
+//! - `TestDatabase` for isolated database tests, with `fixtures!` and seeders
+//! - `TestContainer` for dependency injection in tests, with call-recording spies
+//! - `Request::fake()` and `TestResponse` for calling handlers directly
 //!
 //! # Example
 //!
@@ -23,7 +24,95 @@
 //! ```
 
 mod expect;
+mod http;
 
-pub use crate::container::testing::{TestContainer, TestContainerGuard};
+pub use crate::container::testing::{Spy, SpyCall, TestContainer, TestContainerGuard};
+pub use crate::database::fixtures::Fixtures;
 pub use crate::database::testing::TestDatabase;
 pub use expect::{set_current_test_name, Expect};
+pub use http::{FakeRequest, TestResponse};
+
+use futures_util::FutureExt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+
+/// Run a `test!` body, catching a panic so `after_each!` hooks still run
+#[doc(hidden)]
+pub async fn __catch_unwind<F: Future>(body: F) -> std::thread::Result<F::Output> {
+    AssertUnwindSafe(body).catch_unwind().await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{describe, test, test_each};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static BEFORE_ALL_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    describe!("lifecycle hooks", {
+        before_all!({
+            BEFORE_ALL_RUNS.fetch_add(1, Ordering::SeqCst);
+        });
+
+        before_each!({
+            let mut calls = vec!["outer before"];
+        });
+
+        after_each!({
+            calls.push("outer after");
+            if calls.contains(&"nested test") {
+                assert_eq!(
+                    calls,
+                    ["outer before", "inner before", "nested test", "inner after", "outer after"]
+                );
+            }
+        });
+
+        test!("runs before_all once", fn() {
+            assert_eq!(BEFORE_ALL_RUNS.load(Ordering::SeqCst), 1);
+            assert_eq!(calls, ["outer before"]);
+        });
+
+        test_each!([(1, 2, 3), (2, 2, 4)], "adds {0} and {1}", fn(a, b, expected) {
+            assert_eq!(calls, ["outer before"]);
+            assert_eq!(a + b, expected);
+        });
+
+        test_each!(["Ada", "Grace"], "greets {0}", fn(name: &str) {
+            assert_eq!(format!("Hello {}", name).len(), 6 + name.len());
+        });
+
+        describe!("nested", {
+            before_each!({
+                calls.push("inner before");
+            });
+
+            after_each! {
+                calls.push("inner after");
+            }
+
+            test!("runs the outer hooks around its own", fn() {
+                assert_eq!(BEFORE_ALL_RUNS.load(Ordering::SeqCst), 1);
+                calls.push("nested test");
+            });
+        });
+    });
+
+    describe!("a panicking test", {
+        before_each!({
+            let finished = false;
+        });
+
+        after_each!({
+            assert!(finished, "after_each ran for a failed test");
+        });
+
+        test!(
+            "still runs after_each",
+            #[should_panic(expected = "after_each ran for a failed test")]
+            fn() {
+                panic!("the test failed");
+            }
+        );
+    });
+}
@@ -0,0 +1,117 @@
+//! Named groups of WebSocket connections
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use tokio::sync::mpsc::error::TrySendError;
+
+use super::{Message, Outbox};
+
+/// Connections per channel name, by connection ID
+type Subscribers = HashMap<String, HashMap<u64, Outbox>>;
+
+static SUBSCRIBERS: OnceLock<RwLock<Subscribers>> = OnceLock::new();
+
+fn subscribers() -> &'static RwLock<Subscribers> {
+    SUBSCRIBERS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// A named group of WebSocket connections to broadcast to
+///
+/// Connections join with `WebSocket::join` and leave when they close.
+/// Broadcasting works from anywhere in the app, e.g. a controller that just
+/// saved a chat message. Channels live in this process only. A connection
+/// whose outbox is full when a broadcast arrives is closed rather than left
+/// to queue messages without bound.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::websocket::{Channel, Message};
+///
+/// let room = Channel::new(format!("rooms.{}", room_id));
+/// room.broadcast(Message::text("someone joined"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Channel {
+    name: String,
+}
+
+impl Channel {
+    /// The channel called `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// The channel's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Send `message` to every connection in the channel
+    ///
+    /// Returns the number of connections it was sent to. Connections too
+    /// far behind to take it are closed and removed from the channel.
+    pub fn broadcast(&self, message: impl Into<Message>) -> usize {
+        self.send(message.into(), None)
+    }
+
+    /// Send `message` to every connection in the channel except `connection`
+    ///
+    /// Use it to relay a message to everyone but its sender.
+    pub fn broadcast_except(&self, message: impl Into<Message>, connection: u64) -> usize {
+        self.send(message.into(), Some(connection))
+    }
+
+    /// Number of connections in the channel
+    pub fn connections(&self) -> usize {
+        let subscribers = subscribers().read().unwrap_or_else(|e| e.into_inner());
+        subscribers.get(&self.name).map_or(0, HashMap::len)
+    }
+
+    fn send(&self, message: Message, except: Option<u64>) -> usize {
+        let mut sent = 0;
+        let mut slow = Vec::new();
+        {
+            let subscribers = subscribers().read().unwrap_or_else(|e| e.into_inner());
+            let Some(connections) = subscribers.get(&self.name) else {
+                return 0;
+            };
+            for (id, outbox) in connections.iter().filter(|(id, _)| Some(**id) != except) {
+                match outbox.try_send(message.clone()) {
+                    Ok(()) => sent += 1,
+                    Err(TrySendError::Full(_)) => {
+                        outbox.close();
+                        slow.push(*id);
+                    }
+                    Err(TrySendError::Closed(_)) => {}
+                }
+            }
+        }
+        for connection in slow {
+            eprintln!(
+                "Warning: closing WebSocket connection {} on channel {}: it is not reading",
+                connection, self.name
+            );
+            self.unsubscribe(connection);
+        }
+        sent
+    }
+
+    pub(crate) fn subscribe(&self, connection: u64, outbox: Outbox) {
+        let mut subscribers = subscribers().write().unwrap_or_else(|e| e.into_inner());
+        subscribers
+            .entry(self.name.clone())
+            .or_default()
+            .insert(connection, outbox);
+    }
+
+    pub(crate) fn unsubscribe(&self, connection: u64) {
+        let mut subscribers = subscribers().write().unwrap_or_else(|e| e.into_inner());
+        if let Some(connections) = subscribers.get_mut(&self.name) {
+            connections.remove(&connection);
+            if connections.is_empty() {
+                subscribers.remove(&self.name);
+            }
+        }
+    }
+}
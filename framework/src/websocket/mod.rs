@@ -0,0 +1,487 @@
+//! WebSockets
+//!
+//! `ws!` registers a route that upgrades the connection and hands it to an
+//! async handler. The upgrade request goes through the usual middleware
+//! first, so `AuthMiddleware` on the route (or its group) rejects guests
+//! before a socket is opened. Connections `join` a `Channel` to receive its
+//! broadcasts.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kit::websocket::{Channel, Message, WebSocket};
+//!
+//! routes! {
+//!     ws!("/rooms/{room}", controllers::chat::connect).middleware(AuthMiddleware),
+//! }
+//!
+//! pub async fn connect(mut socket: WebSocket) {
+//!     let room = Channel::new(format!("rooms.{}", socket.param("room").unwrap()));
+//!     socket.join(&room);
+//!
+//!     while let Some(message) = socket.recv().await {
+//!         room.broadcast_except(message, socket.id());
+//!     }
+//! }
+//! ```
+
+pub mod channel;
+
+pub use channel::Channel;
+
+use crate::error::FrameworkError;
+use crate::http::{HttpResponse, Request, Response};
+use crate::session::Session;
+use futures_util::stream::{SplitStream, StreamExt};
+use futures_util::SinkExt;
+use hyper::upgrade::Upgraded;
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::{Role, WebSocketConfig};
+use tokio_tungstenite::tungstenite::Message as Frame;
+use tokio_tungstenite::WebSocketStream;
+use tokio_util::sync::CancellationToken;
+
+type Stream = WebSocketStream<TokioIo<Upgraded>>;
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Messages that can wait for a client before sends to it fail
+const OUTBOX_CAPACITY: usize = 64;
+
+/// Largest message a client may send, in bytes
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// A text or binary WebSocket message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Message {
+    /// A text message
+    pub fn text(text: impl Into<String>) -> Self {
+        Message::Text(text.into())
+    }
+
+    /// A text message holding `value` as JSON
+    pub fn json<T: Serialize>(value: &T) -> Result<Self, FrameworkError> {
+        serde_json::to_string(value)
+            .map(Message::Text)
+            .map_err(|e| FrameworkError::internal(format!("WebSocket message error: {}", e)))
+    }
+
+    /// The text of a text message
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Message::Text(text) => Some(text),
+            Message::Binary(_) => None,
+        }
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Message::Text(text)
+    }
+}
+
+impl From<&str> for Message {
+    fn from(text: &str) -> Self {
+        Message::Text(text.to_string())
+    }
+}
+
+impl From<Vec<u8>> for Message {
+    fn from(bytes: Vec<u8>) -> Self {
+        Message::Binary(bytes)
+    }
+}
+
+/// The queue of messages waiting to be written to a connection
+#[derive(Clone)]
+pub(crate) struct Outbox {
+    sender: Sender<Message>,
+    closed: CancellationToken,
+}
+
+impl Outbox {
+    /// An outbox holding up to `capacity` messages, and its receiving end
+    fn new(capacity: usize) -> (Self, Receiver<Message>) {
+        let (sender, pending) = mpsc::channel(capacity);
+        let outbox = Self {
+            sender,
+            closed: CancellationToken::new(),
+        };
+        (outbox, pending)
+    }
+
+    /// Queue `message` without waiting for room
+    pub(crate) fn try_send(&self, message: Message) -> Result<(), TrySendError<Message>> {
+        self.sender.try_send(message)
+    }
+
+    /// Close the connection, e.g. because it fell behind on a channel
+    pub(crate) fn close(&self) {
+        self.closed.cancel();
+    }
+}
+
+/// An open WebSocket connection, given to `ws!` handlers
+///
+/// The connection is closed when the handler returns.
+pub struct WebSocket {
+    id: u64,
+    params: HashMap<String, String>,
+    session: Option<Session>,
+    incoming: SplitStream<Stream>,
+    outbox: Outbox,
+    channels: Vec<Channel>,
+}
+
+impl WebSocket {
+    fn new(stream: Stream, params: HashMap<String, String>, session: Option<Session>) -> Self {
+        let (mut sink, incoming) = stream.split();
+        let (outbox, mut pending) = Outbox::new(OUTBOX_CAPACITY);
+
+        // Messages from the handler and from channels go out through one
+        // writer task, which closes the socket once every sender is gone.
+        // A closed outbox stops it at once, even mid-write to a stalled client.
+        let writer = async move {
+            while let Some(message) = pending.recv().await {
+                let frame = match message {
+                    Message::Text(text) => Frame::Text(text),
+                    Message::Binary(bytes) => Frame::Binary(bytes),
+                };
+                if sink.send(frame).await.is_err() {
+                    return;
+                }
+            }
+            let _ = sink.close().await;
+        };
+        let closed = outbox.closed.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = writer => {}
+                _ = closed.cancelled() => {}
+            }
+        });
+
+        Self {
+            id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            params,
+            session,
+            incoming,
+            outbox,
+            channels: Vec::new(),
+        }
+    }
+
+    /// ID of this connection, unique within the process
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// A route parameter of the upgrade request
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    /// The session of the upgrade request, if `SessionMiddleware` ran
+    pub fn session(&self) -> Option<&Session> {
+        self.session.as_ref()
+    }
+
+    /// ID of the user authenticated when the connection was opened
+    pub fn user_id(&self) -> Option<i64> {
+        self.session.as_ref().and_then(Session::user_id)
+    }
+
+    /// Wait for the next message from the client
+    ///
+    /// Returns `None` once the client closes the connection, sends a
+    /// message over 1 MiB, or is closed for falling behind on a channel's
+    /// broadcasts. Pings are answered automatically.
+    pub async fn recv(&mut self) -> Option<Message> {
+        let closed = self.outbox.closed.clone();
+        tokio::select! {
+            message = next_message(&mut self.incoming) => message,
+            _ = closed.cancelled() => None,
+        }
+    }
+
+    /// Send a message to the client
+    ///
+    /// Fails if the connection is closed, or if the client has stopped
+    /// reading and its outbox is full.
+    pub fn send(&self, message: impl Into<Message>) -> Result<(), FrameworkError> {
+        self.outbox.try_send(message.into()).map_err(|e| match e {
+            TrySendError::Full(_) => FrameworkError::internal("WebSocket client is not reading"),
+            TrySendError::Closed(_) => FrameworkError::internal("WebSocket connection is closed"),
+        })
+    }
+
+    /// Receive the channel's broadcasts until the connection closes
+    pub fn join(&mut self, channel: &Channel) {
+        channel.subscribe(self.id, self.outbox.clone());
+        if !self.channels.contains(channel) {
+            self.channels.push(channel.clone());
+        }
+    }
+
+    /// Stop receiving the channel's broadcasts
+    pub fn leave(&mut self, channel: &Channel) {
+        channel.unsubscribe(self.id);
+        self.channels.retain(|joined| joined != channel);
+    }
+}
+
+impl Drop for WebSocket {
+    fn drop(&mut self) {
+        for channel in &self.channels {
+            channel.unsubscribe(self.id);
+        }
+    }
+}
+
+/// The next text or binary message read from `incoming`
+async fn next_message(incoming: &mut SplitStream<Stream>) -> Option<Message> {
+    while let Some(frame) = incoming.next().await {
+        match frame.ok()? {
+            Frame::Text(text) => return Some(Message::Text(text)),
+            Frame::Binary(bytes) => return Some(Message::Binary(bytes)),
+            Frame::Close(_) => return None,
+            Frame::Ping(_) | Frame::Pong(_) | Frame::Frame(_) => {}
+        }
+    }
+    None
+}
+
+/// Wrap a `ws!` handler as a route handler that performs the upgrade
+#[doc(hidden)]
+pub fn __upgrade_handler<H, Fut>(
+    handler: H,
+) -> impl Fn(Request) -> Pin<Box<dyn Future<Output = Response> + Send>> + Send + Sync + 'static
+where
+    H: Fn(WebSocket) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let handler = Arc::new(handler);
+    move |req| {
+        let handler = Arc::clone(&handler);
+        Box::pin(async move { upgrade(req, handler) })
+    }
+}
+
+fn upgrade<H, Fut>(mut req: Request, handler: Arc<H>) -> Response
+where
+    H: Fn(WebSocket) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let accept = accept_key(&req).ok_or_else(|| {
+        HttpResponse::text("Expected a WebSocket upgrade request")
+            .status(400)
+            .header("Sec-WebSocket-Version", "13")
+    })?;
+
+    let on_upgrade = req.on_upgrade();
+    let params = req.params().clone();
+    let session = req.try_session();
+
+    tokio::spawn(async move {
+        match on_upgrade.await {
+            Ok(upgraded) => {
+                let stream = WebSocketStream::from_raw_socket(
+                    TokioIo::new(upgraded),
+                    Role::Server,
+                    Some(config()),
+                )
+                .await;
+                handler(WebSocket::new(stream, params, session)).await;
+            }
+            Err(err) => eprintln!("WebSocket upgrade failed: {}", err),
+        }
+    });
+
+    Ok(HttpResponse::new()
+        .status(101)
+        .header("Upgrade", "websocket")
+        .header("Connection", "Upgrade")
+        .header("Sec-WebSocket-Accept", accept))
+}
+
+/// Limits on what clients send, so one connection can't exhaust memory
+fn config() -> WebSocketConfig {
+    WebSocketConfig {
+        max_message_size: Some(MAX_MESSAGE_SIZE),
+        max_frame_size: Some(MAX_MESSAGE_SIZE),
+        ..WebSocketConfig::default()
+    }
+}
+
+/// `Sec-WebSocket-Accept` for a valid upgrade request
+fn accept_key(req: &Request) -> Option<String> {
+    let has_token = |name: &str, token: &str| {
+        req.header(name).is_some_and(|value| {
+            value
+                .split(',')
+                .any(|part| part.trim().eq_ignore_ascii_case(token))
+        })
+    };
+
+    if req.method() != hyper::Method::GET
+        || !has_token("Connection", "upgrade")
+        || !has_token("Upgrade", "websocket")
+        || req.header("Sec-WebSocket-Version") != Some("13")
+    {
+        return None;
+    }
+    let key = req.header("Sec-WebSocket-Key")?;
+    Some(derive_accept_key(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::{Middleware, Next};
+    use crate::routing::Router;
+    use crate::server::Server;
+    use std::net::SocketAddr;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_tungstenite::{connect_async, MaybeTlsStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn echo(mut socket: WebSocket) {
+        let room = socket.param("room").unwrap_or_default().to_string();
+        while let Some(message) = socket.recv().await {
+            let text = message.as_text().unwrap_or_default();
+            socket.send(format!("{}: {}", room, text)).unwrap();
+        }
+    }
+
+    async fn chat(mut socket: WebSocket) {
+        let lobby = Channel::new("websocket-tests.lobby");
+        socket.join(&lobby);
+        socket.send("joined").unwrap();
+        while let Some(message) = socket.recv().await {
+            lobby.broadcast_except(message, socket.id());
+        }
+    }
+
+    struct Deny;
+
+    #[async_trait::async_trait]
+    impl Middleware for Deny {
+        async fn handle(&self, _request: Request, _next: Next) -> Response {
+            Err(HttpResponse::text("Unauthorized").status(401))
+        }
+    }
+
+    async fn serve() -> SocketAddr {
+        let mut router = Router::new();
+        router = crate::ws!("/echo/{room}", echo).register(router);
+        router = crate::ws!("/chat", chat).register(router);
+        router = crate::ws!("/private", echo)
+            .middleware(Deny)
+            .register(router);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(Server::new(router).serve(listener));
+        addr
+    }
+
+    async fn connect(addr: SocketAddr, path: &str) -> Client {
+        connect_async(format!("ws://{}{}", addr, path))
+            .await
+            .unwrap()
+            .0
+    }
+
+    async fn next_text(client: &mut Client) -> String {
+        match client.next().await.unwrap().unwrap() {
+            Frame::Text(text) => text,
+            other => panic!("expected a text message, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn upgrades_and_exchanges_messages() {
+        let addr = serve().await;
+        let mut client = connect(addr, "/echo/lobby").await;
+
+        client.send(Frame::Text("hello".into())).await.unwrap();
+        assert_eq!(next_text(&mut client).await, "lobby: hello");
+    }
+
+    #[tokio::test]
+    async fn channels_broadcast_to_other_connections() {
+        let addr = serve().await;
+        let mut alice = connect(addr, "/chat").await;
+        let mut bob = connect(addr, "/chat").await;
+        assert_eq!(next_text(&mut alice).await, "joined");
+        assert_eq!(next_text(&mut bob).await, "joined");
+
+        alice.send(Frame::Text("hi bob".into())).await.unwrap();
+        assert_eq!(next_text(&mut bob).await, "hi bob");
+
+        let lobby = Channel::new("websocket-tests.lobby");
+        assert_eq!(lobby.broadcast("from the server"), 2);
+        assert_eq!(next_text(&mut alice).await, "from the server");
+        assert_eq!(next_text(&mut bob).await, "from the server");
+    }
+
+    #[tokio::test]
+    async fn oversized_messages_close_the_connection() {
+        let addr = serve().await;
+        let mut client = connect(addr, "/echo/lobby").await;
+
+        let huge = "x".repeat(MAX_MESSAGE_SIZE + 1);
+        client.send(Frame::Text(huge)).await.unwrap();
+        assert!(!matches!(client.next().await, Some(Ok(Frame::Text(_)))));
+    }
+
+    #[test]
+    fn slow_subscribers_are_dropped_and_closed() {
+        let channel = Channel::new("websocket-tests.slow");
+        let (outbox, _pending) = Outbox::new(1);
+        channel.subscribe(42, outbox.clone());
+
+        assert_eq!(channel.broadcast("first"), 1);
+        assert_eq!(channel.broadcast("second"), 0);
+        assert_eq!(channel.connections(), 0);
+        assert!(outbox.closed.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn middleware_can_reject_the_upgrade() {
+        let addr = serve().await;
+
+        let error = connect_async(format!("ws://{}/private", addr))
+            .await
+            .unwrap_err();
+        let tokio_tungstenite::tungstenite::Error::Http(response) = error else {
+            panic!("expected an HTTP error, got {:?}", error);
+        };
+        assert_eq!(response.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn plain_requests_are_rejected() {
+        let handler = __upgrade_handler(echo);
+        let Err(response) = handler(Request::fake().path("/echo/lobby").build()).await else {
+            panic!("expected the upgrade to be refused");
+        };
+        assert_eq!(response.status_code(), 400);
+    }
+}
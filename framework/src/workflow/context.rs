@@ -1,6 +1,7 @@
 //! Workflow execution context
 
 use crate::error::FrameworkError;
+use crate::telemetry;
 use crate::workflow::store;
 use crate::workflow::types::StepStatus;
 use serde::de::DeserializeOwned;
@@ -102,7 +103,7 @@ impl WorkflowContext {
 
         store::refresh_lock(workflow_id, self.inner.lock_timeout).await?;
 
-        let result = f().await;
+        let result = telemetry::span(format!("workflow step {}", step_name), f()).await;
 
         match result {
             Ok(value) => {
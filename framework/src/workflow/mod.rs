@@ -47,7 +47,9 @@ pub use context::WorkflowContext;
 pub use types::{StepStatus, WorkflowHandle, WorkflowStatus};
 
 use crate::config::Config;
+use crate::daemon::{Daemon, DaemonOptions, StopReason, WorkerStats};
 use crate::error::FrameworkError;
+use crate::telemetry;
 use crate::workflow::types::ClaimedWorkflow;
 use chrono::{Duration as ChronoDuration, Utc};
 use rand::Rng;
@@ -82,6 +84,7 @@ pub fn normalize_workflow_name(name: &str) -> String {
 pub struct WorkflowWorker {
     config: Arc<WorkflowConfig>,
     worker_id: String,
+    stats: Arc<WorkerStats>,
 }
 
 impl WorkflowWorker {
@@ -98,57 +101,103 @@ impl WorkflowWorker {
         Self {
             config: Arc::new(config),
             worker_id,
+            stats: Arc::new(WorkerStats::default()),
         }
     }
 
+    /// Record processed and failed workflows into shared stats
+    pub fn with_stats(mut self, stats: Arc<WorkerStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
     /// Run the worker loop indefinitely
     pub async fn work_loop() -> Result<(), FrameworkError> {
-        Self::new().run().await
+        Self::work_loop_with(DaemonOptions::default()).await.map(|_| ())
     }
 
-    async fn run(self) -> Result<(), FrameworkError> {
+    /// Run the worker loop until one of the daemon stop conditions is met
+    ///
+    /// In-flight workflows are allowed to finish before returning.
+    pub async fn work_loop_with(options: DaemonOptions) -> Result<StopReason, FrameworkError> {
+        Self::new().run(Daemon::new("Workflow worker", options)).await
+    }
+
+    /// Run this worker under the given daemon
+    ///
+    /// In-flight workflows are allowed to finish before returning.
+    pub async fn run(self, daemon: Daemon) -> Result<StopReason, FrameworkError> {
         let poll = Duration::from_millis(self.config.poll_interval_ms);
         let semaphore = Arc::new(Semaphore::new(self.config.concurrency));
-
-        loop {
-            let permit = semaphore.clone().acquire_owned().await.unwrap();
-            let claim = store::claim_next_workflow(&self.worker_id, &self.config).await;
-
-            match claim {
-                Ok(Some(claimed)) => {
-                    let config = self.config.clone();
-                    let worker_id = self.worker_id.clone();
-                    tokio::spawn(async move {
-                        if let Err(err) = process_claimed_workflow(claimed, config, &worker_id).await {
-                            eprintln!("Workflow execution error: {}", err);
+        let signal = daemon.signal();
+        let daemon = daemon.stats(self.stats.clone());
+
+        let reason = daemon
+            .run(|| {
+                let semaphore = semaphore.clone();
+                let signal = signal.clone();
+                let config = self.config.clone();
+                let worker_id = self.worker_id.clone();
+                let stats = self.stats.clone();
+                async move {
+                    let permit = semaphore.acquire_owned().await.unwrap();
+                    let claim = store::claim_next_workflow(&worker_id, &config).await;
+
+                    match claim {
+                        Ok(Some(claimed)) => {
+                            tokio::spawn(async move {
+                                match process_claimed_workflow(claimed, config, &worker_id).await {
+                                    Ok(WorkflowStatus::Succeeded) => {}
+                                    Ok(_) => stats.record_failure(),
+                                    Err(err) => {
+                                        stats.record_failure();
+                                        eprintln!("Workflow execution error: {}", err);
+                                    }
+                                }
+                                drop(permit);
+                            });
+                            Ok(1)
                         }
-                        drop(permit);
-                    });
-                }
-                Ok(None) => {
-                    drop(permit);
-                    tokio::time::sleep(poll).await;
-                }
-                Err(err) => {
-                    eprintln!("Workflow claim error: {}", err);
-                    drop(permit);
-                    tokio::time::sleep(poll).await;
+                        Ok(None) => {
+                            drop(permit);
+                            signal.sleep(poll).await;
+                            Ok(0)
+                        }
+                        Err(err) => {
+                            drop(permit);
+                            signal.sleep(poll).await;
+                            Err(FrameworkError::internal(format!(
+                                "Workflow claim error: {}",
+                                err
+                            )))
+                        }
+                    }
                 }
-            }
-        }
+            })
+            .await;
+
+        // Wait for in-flight workflows to finish
+        let _ = semaphore
+            .acquire_many(self.config.concurrency as u32)
+            .await;
+
+        Ok(reason)
     }
 }
 
+/// Execute a claimed workflow and return its resulting status
+///
+/// `Pending` means the workflow failed and was requeued for another attempt.
 async fn process_claimed_workflow(
     claimed: ClaimedWorkflow,
     config: Arc<WorkflowConfig>,
     _worker_id: &str,
-) -> Result<(), FrameworkError> {
+) -> Result<WorkflowStatus, FrameworkError> {
     let entry = match registry::find(&claimed.name) {
         Some(entry) => entry,
         None => {
             store::mark_failed(claimed.id, "Workflow not registered").await?;
-            return Ok(());
+            return Ok(WorkflowStatus::Failed);
         }
     };
 
@@ -158,25 +207,28 @@ async fn process_claimed_workflow(
     );
 
     let result = ctx
-        .enter(async { (entry.run)(&claimed.input).await })
+        .enter(telemetry::span(format!("workflow {}", claimed.name), async {
+            (entry.run)(&claimed.input).await
+        }))
         .await;
 
     match result {
         Ok(output) => {
             store::mark_succeeded(claimed.id, &output).await?;
+            Ok(WorkflowStatus::Succeeded)
         }
         Err(err) => {
             if claimed.attempts < claimed.max_attempts {
                 let backoff = config.retry_backoff_secs * claimed.attempts as i64;
                 let next_run_at = Utc::now().naive_utc() + ChronoDuration::seconds(backoff);
                 store::requeue(claimed.id, &err.to_string(), next_run_at).await?;
+                Ok(WorkflowStatus::Pending)
             } else {
                 store::mark_failed(claimed.id, &err.to_string()).await?;
+                Ok(WorkflowStatus::Failed)
             }
         }
     }
-
-    Ok(())
 }
 
 /// Enqueue a workflow by function name with serialized args
@@ -6,7 +6,9 @@ use crate::workflow::config::WorkflowConfig;
 use crate::workflow::entities::{workflow_steps, workflows};
 use crate::workflow::types::{ClaimedWorkflow, StepStatus, WorkflowHandle, WorkflowStatus};
 use chrono::{Duration as ChronoDuration, Utc};
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseBackend, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseBackend, EntityTrait, QueryFilter, QueryOrder, Set,
+};
 use sea_orm::{ConnectionTrait, Statement};
 use std::time::Duration;
 
@@ -309,6 +311,17 @@ pub async fn load_step(
         .map_err(|e| FrameworkError::database(e.to_string()))
 }
 
+/// Load all steps of a workflow, in order
+pub async fn list_steps(workflow_id: i64) -> Result<Vec<workflow_steps::Model>, FrameworkError> {
+    let db = DB::connection()?;
+    workflow_steps::Entity::find()
+        .filter(workflow_steps::Column::WorkflowId.eq(workflow_id))
+        .order_by_asc(workflow_steps::Column::StepIndex)
+        .all(db.inner())
+        .await
+        .map_err(|e| FrameworkError::database(e.to_string()))
+}
+
 /// Load any step by workflow + index (used to detect mismatches)
 pub async fn load_step_by_index(
     workflow_id: i64,
@@ -0,0 +1,64 @@
+//! activity:install command - Install the activities table migration
+
+use console::style;
+use std::fs;
+use std::path::Path;
+
+use super::make_migration::{migrator_mod_template, update_mod_file};
+use crate::templates;
+
+const ACTIVITIES_MIGRATION: &str = "m20240101_000007_create_activities_table";
+
+pub fn run() {
+    if !Path::new("src").exists() {
+        eprintln!(
+            "{} Not in a Kit project root directory",
+            style("Error:").red().bold()
+        );
+        std::process::exit(1);
+    }
+
+    if let Err(e) = install_migration() {
+        eprintln!("{} {}", style("Error:").red().bold(), e);
+        std::process::exit(1);
+    }
+
+    println!();
+    println!("Activity feed installed.");
+    println!("Run `kit migrate` to apply it, then record activities with");
+    println!("`Activity::log(\"created\", &post).await?`.");
+    println!("Schedule `kit::PruneActivities` daily to delete old activities.");
+}
+
+fn install_migration() -> Result<(), String> {
+    let migrations_dir = Path::new("src/migrations");
+    fs::create_dir_all(migrations_dir)
+        .map_err(|e| format!("Failed to create migrations directory: {}", e))?;
+
+    let migration_file = migrations_dir.join(format!("{}.rs", ACTIVITIES_MIGRATION));
+    if migration_file.exists() {
+        println!(
+            "{} {} already exists",
+            style("Info:").yellow().bold(),
+            migration_file.display()
+        );
+    } else {
+        fs::write(&migration_file, templates::create_activities_migration())
+            .map_err(|e| format!("Failed to write activities migration: {}", e))?;
+        println!(
+            "{} Created {}",
+            style("✓").green(),
+            migration_file.display()
+        );
+    }
+
+    let mod_file = migrations_dir.join("mod.rs");
+    if mod_file.exists() {
+        update_mod_file(&mod_file, ACTIVITIES_MIGRATION)?;
+    } else {
+        fs::write(&mod_file, migrator_mod_template(ACTIVITIES_MIGRATION))
+            .map_err(|e| format!("Failed to create mod.rs: {}", e))?;
+    }
+    println!("{} Updated src/migrations/mod.rs", style("✓").green());
+    Ok(())
+}
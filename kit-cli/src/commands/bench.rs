@@ -0,0 +1,34 @@
+//! bench command - Benchmark a route in-process through the app's router
+
+use console::style;
+use std::process::Command;
+
+pub fn run(bench_args: Vec<String>, release: bool) {
+    println!("{} Building and benchmarking...", style("->").cyan());
+    if !release {
+        println!(
+            "{}",
+            style("Debug build: numbers are only useful for relative comparisons").dim()
+        );
+    }
+    println!();
+
+    let mut args = vec!["run".to_string(), "--quiet".to_string()];
+    if release {
+        args.push("--release".to_string());
+    }
+    args.push("--".to_string());
+    args.push("bench".to_string());
+    args.extend(bench_args);
+
+    let status = Command::new("cargo")
+        .args(&args)
+        .status()
+        .expect("Failed to execute cargo command");
+
+    if !status.success() {
+        eprintln!();
+        eprintln!("{} Benchmark failed", style("Error:").red().bold());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,42 @@
+//! daemon:stop command - Ask running worker daemons to stop gracefully
+
+use console::style;
+use std::fs;
+use std::path::Path;
+
+/// Must match `kit::daemon::DEFAULT_STOP_FILE`
+const STOP_FILE: &str = ".kit/daemon.stop";
+
+pub fn run() {
+    let stop_file = Path::new(STOP_FILE);
+
+    if let Some(parent) = stop_file.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            eprintln!(
+                "{} Failed to create {}: {}",
+                style("Error:").red().bold(),
+                parent.display(),
+                e
+            );
+            std::process::exit(1);
+        }
+    }
+
+    // Daemons compare this timestamp against their own start time, so only
+    // workers that are already running will stop.
+    let now = chrono::Utc::now().timestamp_millis();
+    if let Err(e) = fs::write(stop_file, now.to_string()) {
+        eprintln!(
+            "{} Failed to write {}: {}",
+            style("Error:").red().bold(),
+            stop_file.display(),
+            e
+        );
+        std::process::exit(1);
+    }
+
+    println!(
+        "{} Stop signal sent. Running daemons will exit after their current job.",
+        style("✓").green()
+    );
+}
@@ -0,0 +1,701 @@
+//! db:diff command - Generate a migration from model changes
+//!
+//! The `Model` structs in `src/models` (any `#[sea_orm(table_name = "..")]`
+//! entity) are the desired schema. They are compared with the database, or
+//! with the snapshot the previous diff saved, and the difference is written
+//! as a migration:
+//!
+//! - tables without a model are created, with indexes for `unique` and
+//!   `indexed` columns
+//! - new fields become `add_column`
+//! - columns whose field was removed are dropped
+//!
+//! Column type changes and tables without a model are left alone. The
+//! migration is a starting point: review it before running `kit migrate`.
+
+use chrono::Local;
+use console::style;
+use sea_orm::Database;
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::Path;
+use syn::{Fields, GenericArgument, Item, LitStr, PathArguments, Type};
+use walkdir::WalkDir;
+
+use super::db_sync::discover_tables;
+use super::make_migration::{migrator_mod_template, update_mod_file};
+
+/// Where the schema is recorded for `--from-snapshot`
+const SNAPSHOT_FILE: &str = "src/migrations/schema_snapshot.json";
+
+#[derive(Debug, Clone, PartialEq)]
+struct Column {
+    name: String,
+    /// The `ColumnDef` method for the type, e.g. `big_integer`
+    kind: String,
+    nullable: bool,
+    primary_key: bool,
+    unique: bool,
+    indexed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Table {
+    name: String,
+    columns: Vec<Column>,
+}
+
+enum Change {
+    CreateTable(Table),
+    AddColumn(String, Column),
+    DropColumn(String, Column),
+}
+
+pub fn run(name: Option<String>, from_snapshot: bool, dry_run: bool) {
+    let models_dir = Path::new("src/models");
+    if !models_dir.exists() {
+        eprintln!(
+            "{} No src/models directory found",
+            style("Error:").red().bold()
+        );
+        std::process::exit(1);
+    }
+
+    let desired = desired_schema(models_dir);
+    let current = if from_snapshot {
+        read_snapshot()
+    } else {
+        database_schema()
+    };
+
+    let changes = diff(&current, &desired);
+    if changes.is_empty() {
+        println!("{} Schema is up to date", style("✓").green());
+        if !dry_run {
+            write_snapshot(&desired);
+        }
+        return;
+    }
+
+    println!("Changes:");
+    for change in &changes {
+        match change {
+            Change::CreateTable(table) => {
+                println!("  {} create table {}", style("+").green(), table.name)
+            }
+            Change::AddColumn(table, column) => {
+                println!(
+                    "  {} add column {}.{}",
+                    style("+").green(),
+                    table,
+                    column.name
+                )
+            }
+            Change::DropColumn(table, column) => {
+                println!(
+                    "  {} drop column {}.{}",
+                    style("-").red(),
+                    table,
+                    column.name
+                )
+            }
+        }
+    }
+
+    if dry_run {
+        return;
+    }
+
+    let file_name = name.unwrap_or_else(|| "schema_diff".to_string());
+    if !file_name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        eprintln!(
+            "{} '{}' is not a valid migration name",
+            style("Error:").red().bold(),
+            file_name
+        );
+        std::process::exit(1);
+    }
+
+    if let Err(e) = write_migration(&file_name, &migration_template(&changes)) {
+        eprintln!("{} {}", style("Error:").red().bold(), e);
+        std::process::exit(1);
+    }
+    write_snapshot(&desired);
+
+    println!();
+    println!(
+        "Review the migration, then run {} to apply it",
+        style("kit migrate").cyan()
+    );
+}
+
+/// Tables declared by the `Model` structs under `models_dir`
+fn desired_schema(models_dir: &Path) -> Vec<Table> {
+    let mut tables: Vec<Table> = Vec::new();
+    let files = WalkDir::new(models_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "rs"));
+
+    for entry in files {
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let Ok(file) = syn::parse_file(&content) else {
+            eprintln!(
+                "{} Skipping {}: failed to parse",
+                style("Warning:").yellow(),
+                entry.path().display()
+            );
+            continue;
+        };
+        collect_tables(&file.items, &mut tables);
+    }
+
+    tables
+}
+
+fn collect_tables(items: &[Item], tables: &mut Vec<Table>) {
+    for item in items {
+        match item {
+            Item::Struct(item) if item.ident == "Model" => {
+                let Some(table) = parse_model(item) else {
+                    continue;
+                };
+                if !tables.iter().any(|t| t.name == table.name) {
+                    tables.push(table);
+                }
+            }
+            Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    collect_tables(items, tables);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parse_model(item: &syn::ItemStruct) -> Option<Table> {
+    let mut table_name = None;
+    for attr in item.attrs.iter().filter(|a| a.path().is_ident("sea_orm")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table_name") {
+                table_name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        });
+    }
+
+    let Fields::Named(fields) = &item.fields else {
+        return None;
+    };
+    let columns = fields
+        .named
+        .iter()
+        .filter_map(|field| {
+            let (kind, optional) = column_kind(&field.ty);
+            let mut column = Column {
+                name: field.ident.as_ref()?.to_string(),
+                kind: kind.to_string(),
+                nullable: optional,
+                primary_key: false,
+                unique: false,
+                indexed: false,
+            };
+            let mut ignored = false;
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("sea_orm")) {
+                let _ = attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("column_name") {
+                        column.name = meta.value()?.parse::<LitStr>()?.value();
+                    } else if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse::<syn::Expr>()?;
+                    } else if meta.path.is_ident("primary_key") {
+                        column.primary_key = true;
+                    } else if meta.path.is_ident("unique") {
+                        column.unique = true;
+                    } else if meta.path.is_ident("indexed") {
+                        column.indexed = true;
+                    } else if meta.path.is_ident("nullable") {
+                        column.nullable = true;
+                    } else if meta.path.is_ident("ignore") {
+                        ignored = true;
+                    }
+                    Ok(())
+                });
+            }
+            (!ignored).then_some(column)
+        })
+        .collect();
+
+    Some(Table {
+        name: table_name?,
+        columns,
+    })
+}
+
+/// The `ColumnDef` method for a field type, and whether it's an `Option`
+fn column_kind(ty: &Type) -> (&'static str, bool) {
+    let Type::Path(path) = ty else {
+        return ("string", false);
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return ("string", false);
+    };
+    let inner = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+
+    let kind = match segment.ident.to_string().as_str() {
+        "Option" => {
+            return match inner {
+                Some(inner) => (column_kind(inner).0, true),
+                None => ("string", true),
+            }
+        }
+        "Vec" => match inner {
+            Some(Type::Path(inner)) if inner.path.is_ident("u8") => "binary",
+            _ => "json",
+        },
+        "i8" | "i16" | "u8" | "u16" => "small_integer",
+        "i32" | "u32" => "integer",
+        "i64" | "u64" => "big_integer",
+        "f32" => "float",
+        "f64" => "double",
+        "bool" => "boolean",
+        "DateTimeUtc" | "DateTimeWithTimeZone" | "DateTimeLocal" => "timestamp_with_time_zone",
+        "DateTime" | "NaiveDateTime" => "timestamp",
+        "Date" | "NaiveDate" => "date",
+        "Time" | "NaiveTime" => "time",
+        "Uuid" => "uuid",
+        "Json" | "Value" => "json",
+        "Decimal" => "decimal",
+        _ => "string",
+    };
+    (kind, false)
+}
+
+/// The `ColumnDef` method for a column type reported by the database
+fn sql_kind(col_type: &str) -> &'static str {
+    let col_type = col_type.to_uppercase();
+    if col_type.contains("BIGINT") || col_type.contains("INT8") {
+        "big_integer"
+    } else if col_type.contains("SMALLINT") || col_type.contains("INT2") {
+        "small_integer"
+    } else if col_type.contains("INT") {
+        "integer"
+    } else if col_type.contains("BOOL") {
+        "boolean"
+    } else if col_type.contains("REAL") || col_type.contains("FLOAT4") {
+        "float"
+    } else if col_type.contains("DOUBLE") || col_type.contains("FLOAT8") {
+        "double"
+    } else if col_type.contains("TIMESTAMP") && col_type.contains("ZONE") {
+        "timestamp_with_time_zone"
+    } else if col_type.contains("TIMESTAMP") || col_type.contains("DATETIME") {
+        "timestamp"
+    } else if col_type.contains("DATE") {
+        "date"
+    } else if col_type.contains("TIME") {
+        "time"
+    } else if col_type.contains("UUID") {
+        "uuid"
+    } else if col_type.contains("JSON") {
+        "json"
+    } else if col_type.contains("BYTEA") || col_type.contains("BLOB") {
+        "binary"
+    } else if col_type.contains("DECIMAL") || col_type.contains("NUMERIC") {
+        "decimal"
+    } else {
+        "string"
+    }
+}
+
+/// The schema of the database in `DATABASE_URL`
+fn database_schema() -> Vec<Table> {
+    dotenvy::dotenv().ok();
+    let database_url = match env::var("DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            eprintln!(
+                "{} DATABASE_URL not set in .env",
+                style("Error:").red().bold()
+            );
+            std::process::exit(1);
+        }
+    };
+
+    println!("{} Discovering database schema...", style("→").cyan());
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let tables = rt.block_on(async {
+        let db = match Database::connect(&database_url).await {
+            Ok(db) => db,
+            Err(e) => {
+                eprintln!(
+                    "{} Failed to connect to database: {}",
+                    style("Error:").red().bold(),
+                    e
+                );
+                std::process::exit(1);
+            }
+        };
+        discover_tables(&db, database_url.starts_with("sqlite")).await
+    });
+
+    tables
+        .into_iter()
+        .map(|table| Table {
+            name: table.name,
+            columns: table
+                .columns
+                .into_iter()
+                .map(|column| Column {
+                    kind: sql_kind(&column.col_type).to_string(),
+                    name: column.name,
+                    nullable: column.is_nullable,
+                    primary_key: column.is_primary_key,
+                    unique: false,
+                    indexed: false,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// The schema recorded by the previous diff, empty if there is none
+fn read_snapshot() -> Vec<Table> {
+    let Ok(content) = fs::read_to_string(SNAPSHOT_FILE) else {
+        println!(
+            "{} No {} yet, comparing with an empty schema",
+            style("Info:").yellow(),
+            SNAPSHOT_FILE
+        );
+        return Vec::new();
+    };
+    let snapshot: Value = match serde_json::from_str(&content) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            eprintln!(
+                "{} Failed to parse {}: {}",
+                style("Error:").red().bold(),
+                SNAPSHOT_FILE,
+                e
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let tables = snapshot["tables"].as_array().cloned().unwrap_or_default();
+    tables
+        .iter()
+        .map(|table| Table {
+            name: table["name"].as_str().unwrap_or_default().to_string(),
+            columns: table["columns"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|column| {
+                    let flag = |name: &str| column[name].as_bool().unwrap_or(false);
+                    Column {
+                        name: column["name"].as_str().unwrap_or_default().to_string(),
+                        kind: column["kind"].as_str().unwrap_or("string").to_string(),
+                        nullable: flag("nullable"),
+                        primary_key: flag("primary_key"),
+                        unique: flag("unique"),
+                        indexed: flag("indexed"),
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn write_snapshot(tables: &[Table]) {
+    if !Path::new("src/migrations").exists() {
+        return;
+    }
+    let tables: Vec<Value> = tables
+        .iter()
+        .map(|table| {
+            let columns: Vec<Value> = table
+                .columns
+                .iter()
+                .map(|column| {
+                    json!({
+                        "name": column.name,
+                        "kind": column.kind,
+                        "nullable": column.nullable,
+                        "primary_key": column.primary_key,
+                        "unique": column.unique,
+                        "indexed": column.indexed,
+                    })
+                })
+                .collect();
+            json!({ "name": table.name, "columns": columns })
+        })
+        .collect();
+
+    let content = serde_json::to_string_pretty(&json!({ "tables": tables })).unwrap();
+    if let Err(e) = fs::write(SNAPSHOT_FILE, content + "\n") {
+        eprintln!(
+            "{} Failed to write {}: {}",
+            style("Warning:").yellow(),
+            SNAPSHOT_FILE,
+            e
+        );
+    }
+}
+
+/// What turns `current` into `desired`
+///
+/// Tables that only exist in `current` are not dropped: they may belong to
+/// the framework (sessions, jobs) rather than to a model.
+fn diff(current: &[Table], desired: &[Table]) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for table in desired {
+        let Some(existing) = current.iter().find(|t| t.name == table.name) else {
+            changes.push(Change::CreateTable(table.clone()));
+            continue;
+        };
+        for column in &table.columns {
+            if !existing.columns.iter().any(|c| c.name == column.name) {
+                changes.push(Change::AddColumn(table.name.clone(), column.clone()));
+            }
+        }
+        for column in &existing.columns {
+            if !table.columns.iter().any(|c| c.name == column.name) {
+                changes.push(Change::DropColumn(table.name.clone(), column.clone()));
+            }
+        }
+    }
+    changes
+}
+
+/// `ColumnDef::new(..)` with its type and constraints
+///
+/// Uniqueness is left to a separate index, since SQLite can't add a column
+/// with a `UNIQUE` constraint.
+fn column_def(column: &Column, single_primary_key: bool) -> String {
+    let mut def = format!(
+        "ColumnDef::new(Alias::new(\"{}\")).{}()",
+        column.name, column.kind
+    );
+    def.push_str(if column.nullable {
+        ".null()"
+    } else {
+        ".not_null()"
+    });
+    if column.primary_key && single_primary_key {
+        if column.kind.ends_with("integer") {
+            def.push_str(".auto_increment()");
+        }
+        def.push_str(".primary_key()");
+    }
+    def
+}
+
+/// `create_index`/`drop_index` statements for a column, if it needs one
+fn index_statements(table: &str, column: &Column) -> Option<(String, String)> {
+    if !column.unique && !column.indexed {
+        return None;
+    }
+    let name = format!("idx_{}_{}", table, column.name);
+    let unique = if column.unique {
+        "\n                    .unique()"
+    } else {
+        ""
+    };
+    let up = format!(
+        r#"        manager
+            .create_index(
+                Index::create()
+                    .name("{name}")
+                    .table(Alias::new("{table}"))
+                    .col(Alias::new("{column}")){unique}
+                    .to_owned(),
+            )
+            .await?;
+"#,
+        column = column.name
+    );
+    let down = format!(
+        r#"        manager
+            .drop_index(Index::drop().name("{name}").table(Alias::new("{table}")).to_owned())
+            .await?;
+"#
+    );
+    Some((up, down))
+}
+
+fn alter_statement(table: &str, operation: &str) -> String {
+    format!(
+        r#"        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("{table}"))
+                    .{operation}
+                    .to_owned(),
+            )
+            .await?;
+"#
+    )
+}
+
+/// The up and down statements of each change; down runs in reverse
+fn statements(change: &Change) -> Vec<(String, String)> {
+    match change {
+        Change::CreateTable(table) => {
+            let primary_keys: Vec<&Column> =
+                table.columns.iter().filter(|c| c.primary_key).collect();
+            let single = primary_keys.len() == 1;
+            let mut body = String::new();
+            for column in &table.columns {
+                body.push_str(&format!(
+                    "\n                    .col({})",
+                    column_def(column, single)
+                ));
+            }
+            if primary_keys.len() > 1 {
+                let cols: String = primary_keys
+                    .iter()
+                    .map(|c| format!(".col(Alias::new(\"{}\"))", c.name))
+                    .collect();
+                body.push_str(&format!(
+                    "\n                    .primary_key(Index::create(){})",
+                    cols
+                ));
+            }
+            let up = format!(
+                r#"        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("{name}"))
+                    .if_not_exists(){body}
+                    .to_owned(),
+            )
+            .await?;
+"#,
+                name = table.name
+            );
+            let down = format!(
+                r#"        manager
+            .drop_table(Table::drop().table(Alias::new("{}")).to_owned())
+            .await?;
+"#,
+                table.name
+            );
+
+            let mut statements = vec![(up, down)];
+            statements.extend(
+                table
+                    .columns
+                    .iter()
+                    .filter_map(|column| index_statements(&table.name, column)),
+            );
+            statements
+        }
+        Change::AddColumn(table, column) => {
+            let mut up = String::new();
+            if !column.nullable {
+                up.push_str(
+                    "        // Existing rows need a value: add .default(..) if the table has data\n",
+                );
+            }
+            up.push_str(&alter_statement(
+                table,
+                &format!("add_column({})", column_def(column, false)),
+            ));
+            let down = alter_statement(
+                table,
+                &format!("drop_column(Alias::new(\"{}\"))", column.name),
+            );
+
+            let mut statements = vec![(up, down)];
+            statements.extend(index_statements(table, column));
+            statements
+        }
+        Change::DropColumn(table, column) => {
+            let up = alter_statement(
+                table,
+                &format!("drop_column(Alias::new(\"{}\"))", column.name),
+            );
+            let down =
+                alter_statement(table, &format!("add_column({})", column_def(column, false)));
+            vec![(up, down)]
+        }
+    }
+}
+
+fn migration_template(changes: &[Change]) -> String {
+    let statements: Vec<(String, String)> = changes.iter().flat_map(statements).collect();
+    let up: Vec<&str> = statements.iter().map(|(up, _)| up.as_str()).collect();
+    let down: Vec<&str> = statements
+        .iter()
+        .rev()
+        .map(|(_, down)| down.as_str())
+        .collect();
+
+    format!(
+        r#"// Generated by `kit db:diff` - review before running
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {{
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {{
+{up}
+        Ok(())
+    }}
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {{
+{down}
+        Ok(())
+    }}
+}}
+"#,
+        up = up.join("\n"),
+        down = down.join("\n"),
+    )
+}
+
+/// Write `src/migrations/m{timestamp}_{name}.rs` and register it
+fn write_migration(name: &str, content: &str) -> Result<(), String> {
+    let migrations_dir = Path::new("src/migrations");
+    fs::create_dir_all(migrations_dir)
+        .map_err(|e| format!("Failed to create migrations directory: {}", e))?;
+
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let migration_name = format!("m{}_{}", timestamp, name);
+    let migration_file = migrations_dir.join(format!("{}.rs", migration_name));
+    fs::write(&migration_file, content)
+        .map_err(|e| format!("Failed to write migration file: {}", e))?;
+    println!(
+        "{} Created {}",
+        style("✓").green(),
+        migration_file.display()
+    );
+
+    let mod_file = migrations_dir.join("mod.rs");
+    if mod_file.exists() {
+        update_mod_file(&mod_file, &migration_name)?;
+    } else {
+        fs::write(&mod_file, migrator_mod_template(&migration_name))
+            .map_err(|e| format!("Failed to create mod.rs: {}", e))?;
+    }
+    println!("{} Updated src/migrations/mod.rs", style("✓").green());
+    Ok(())
+}
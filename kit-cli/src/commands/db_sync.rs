@@ -100,18 +100,7 @@ async fn discover_and_generate(database_url: &str, regenerate_models: bool) {
         }
     };
 
-    // Discover tables based on database type
-    let tables = if is_sqlite {
-        discover_sqlite_tables(&db).await
-    } else {
-        discover_postgres_tables(&db).await
-    };
-
-    // Filter out migration tables
-    let tables: Vec<_> = tables
-        .into_iter()
-        .filter(|t| t.name != "seaql_migrations" && !t.name.starts_with("_"))
-        .collect();
+    let tables = discover_tables(&db, is_sqlite).await;
 
     if tables.is_empty() {
         println!("{} No tables found in database", style("Info:").yellow());
@@ -181,6 +170,22 @@ async fn discover_and_generate(database_url: &str, regenerate_models: bool) {
     }
 }
 
+/// The application's tables, without the migrations bookkeeping table
+pub async fn discover_tables(db: &sea_orm::DatabaseConnection, is_sqlite: bool) -> Vec<TableInfo> {
+    // Discover tables based on database type
+    let tables = if is_sqlite {
+        discover_sqlite_tables(db).await
+    } else {
+        discover_postgres_tables(db).await
+    };
+
+    // Filter out migration tables
+    tables
+        .into_iter()
+        .filter(|t| t.name != "seaql_migrations" && !t.name.starts_with("_"))
+        .collect()
+}
+
 async fn discover_sqlite_tables(db: &sea_orm::DatabaseConnection) -> Vec<TableInfo> {
     let mut tables = Vec::new();
 
@@ -308,6 +313,112 @@ async fn discover_postgres_columns(
         .collect()
 }
 
+/// A secondary index, as reported by the database
+pub struct IndexInfo {
+    pub name: String,
+    pub is_unique: bool,
+    /// Indexed columns, in index order
+    pub columns: Vec<String>,
+}
+
+/// The indexes of a table, sorted by name, without the primary key
+pub async fn discover_indexes(
+    db: &sea_orm::DatabaseConnection,
+    is_sqlite: bool,
+    table_name: &str,
+) -> Vec<IndexInfo> {
+    let mut indexes = if is_sqlite {
+        discover_sqlite_indexes(db, table_name).await
+    } else {
+        discover_postgres_indexes(db, table_name).await
+    };
+    indexes.sort_by(|a, b| a.name.cmp(&b.name));
+    indexes
+}
+
+async fn discover_sqlite_indexes(
+    db: &sea_orm::DatabaseConnection,
+    table_name: &str,
+) -> Vec<IndexInfo> {
+    let query = format!("PRAGMA index_list({})", table_name);
+    let rows = db
+        .query_all(Statement::from_string(DbBackend::Sqlite, query))
+        .await
+        .unwrap_or_default();
+
+    let mut indexes = Vec::new();
+    for row in rows {
+        let (Ok(name), Ok(unique), Ok(origin)) = (
+            row.try_get_by_index::<String>(1),
+            row.try_get_by_index::<i32>(2),
+            row.try_get_by_index::<String>(3),
+        ) else {
+            continue;
+        };
+        if origin == "pk" {
+            continue;
+        }
+
+        let query = format!("PRAGMA index_info({})", name);
+        let columns = db
+            .query_all(Statement::from_string(DbBackend::Sqlite, query))
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|row| row.try_get_by_index::<String>(2).ok())
+            .collect();
+        indexes.push(IndexInfo {
+            name,
+            is_unique: unique != 0,
+            columns,
+        });
+    }
+    indexes
+}
+
+async fn discover_postgres_indexes(
+    db: &sea_orm::DatabaseConnection,
+    table_name: &str,
+) -> Vec<IndexInfo> {
+    let query = format!(
+        r#"
+        SELECT i.relname, ix.indisunique, a.attname
+        FROM pg_class t
+        JOIN pg_index ix ON t.oid = ix.indrelid
+        JOIN pg_class i ON i.oid = ix.indexrelid
+        JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(ix.indkey)
+        JOIN pg_namespace n ON n.oid = t.relnamespace
+        WHERE n.nspname = 'public' AND t.relname = '{}' AND NOT ix.indisprimary
+        ORDER BY i.relname, array_position(ix.indkey::int2[], a.attnum)
+        "#,
+        table_name
+    );
+    let rows = db
+        .query_all(Statement::from_string(DbBackend::Postgres, query))
+        .await
+        .unwrap_or_default();
+
+    let mut indexes: Vec<IndexInfo> = Vec::new();
+    for row in rows {
+        let (Ok(name), Ok(is_unique), Ok(column)) = (
+            row.try_get_by_index::<String>(0),
+            row.try_get_by_index::<bool>(1),
+            row.try_get_by_index::<String>(2),
+        ) else {
+            continue;
+        };
+        match indexes.last_mut() {
+            Some(index) if index.name == name => index.columns.push(column),
+            _ => indexes.push(IndexInfo {
+                name,
+                is_unique,
+                columns: vec![column],
+            }),
+        }
+    }
+    indexes
+}
+
 fn generate_entity_file(table: &TableInfo, entities_dir: &Path) {
     let entity_file = entities_dir.join(format!("{}.rs", table.name));
     let content = templates::entity_template(&table.name, &table.columns);
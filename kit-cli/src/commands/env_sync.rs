@@ -0,0 +1,242 @@
+//! env:sync command - Keep .env.example in step with the code
+//!
+//! Every `env("KEY", default)`, `env_required("KEY")`, `env_optional("KEY")`
+//! and `std::env::var("KEY")` call under `src/` is collected. Keys missing
+//! from `.env.example` are appended with their default, leaving existing
+//! lines and comments untouched. Keys set in `.env` that neither the code nor
+//! the framework reads are reported, as they are usually typos or leftovers.
+
+use console::style;
+use proc_macro2::Span;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Expr, ExprCall, Lit};
+use walkdir::WalkDir;
+
+use crate::templates;
+
+const EXAMPLE_FILE: &str = ".env.example";
+const ENV_FILE: &str = ".env";
+
+/// Where a key is first read, and its default if it has a literal one
+struct EnvRead {
+    file: PathBuf,
+    line: usize,
+    default: Option<String>,
+}
+
+pub fn run(dry_run: bool) {
+    if !Path::new("src").is_dir() {
+        eprintln!(
+            "{} No src/ directory found. Run this from a Kit project.",
+            style("Error:").red().bold()
+        );
+        std::process::exit(1);
+    }
+
+    let reads = scan("src");
+    println!(
+        "{} Found {} environment variable(s) read in src/",
+        style("->").cyan(),
+        reads.len()
+    );
+
+    let example = fs::read_to_string(EXAMPLE_FILE).unwrap_or_default();
+    let documented = keys(&example);
+    let missing: Vec<(&String, &EnvRead)> = reads
+        .iter()
+        .filter(|(key, _)| !documented.contains(key.as_str()))
+        .collect();
+
+    if missing.is_empty() {
+        println!("{} {} is up to date", style("✓").green(), EXAMPLE_FILE);
+    } else {
+        let mut added = String::from("\n# Added by `kit env:sync`\n");
+        for (key, read) in &missing {
+            println!(
+                "  {} {} {}",
+                style("+").green(),
+                key,
+                style(format!("({}:{})", read.file.display(), read.line)).dim()
+            );
+            added.push_str(&format!(
+                "{}={}\n",
+                key,
+                read.default.as_deref().unwrap_or_default()
+            ));
+        }
+
+        if dry_run {
+            println!(
+                "{} Dry run, {} was not changed",
+                style("->").cyan(),
+                EXAMPLE_FILE
+            );
+        } else {
+            let mut contents = example;
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(&added);
+            if let Err(e) = fs::write(EXAMPLE_FILE, contents) {
+                eprintln!(
+                    "{} Failed to write {}: {}",
+                    style("Error:").red().bold(),
+                    EXAMPLE_FILE,
+                    e
+                );
+                std::process::exit(1);
+            }
+            println!(
+                "{} Added {} key(s) to {}",
+                style("✓").green(),
+                missing.len(),
+                EXAMPLE_FILE
+            );
+        }
+    }
+
+    // Keys the framework reads are documented in the project template
+    let framework = keys(templates::env_example());
+    let env = fs::read_to_string(ENV_FILE).unwrap_or_default();
+    let mut unread: Vec<&str> = keys(&env)
+        .into_iter()
+        .filter(|key| !reads.contains_key(*key) && !framework.contains(key))
+        .collect();
+    unread.sort_unstable();
+    if !unread.is_empty() {
+        println!();
+        println!(
+            "{} {} key(s) in {} are never read:",
+            style("Warning:").yellow().bold(),
+            unread.len(),
+            ENV_FILE
+        );
+        for key in unread {
+            println!("  {}", key);
+        }
+    }
+}
+
+/// Environment variables read by the Rust files under `dir`, by key
+fn scan(dir: &str) -> BTreeMap<String, EnvRead> {
+    let mut reads = BTreeMap::new();
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    files.sort();
+
+    for file in files {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let Ok(syntax) = syn::parse_file(&content) else {
+            continue;
+        };
+        let mut visitor = EnvVisitor { found: Vec::new() };
+        visitor.visit_file(&syntax);
+        for (key, span, default) in visitor.found {
+            reads.entry(key).or_insert_with(|| EnvRead {
+                file: file.clone(),
+                line: span.start().line,
+                default,
+            });
+        }
+    }
+    reads
+}
+
+/// Finds `env("KEY", ...)`-style calls with a string literal key
+struct EnvVisitor {
+    found: Vec<(String, Span, Option<String>)>,
+}
+
+impl<'ast> Visit<'ast> for EnvVisitor {
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        if let Some((key, default)) = env_read(call) {
+            self.found.push((key, call.span(), default));
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+}
+
+/// The key and literal default of an environment variable read
+fn env_read(call: &ExprCall) -> Option<(String, Option<String>)> {
+    let Expr::Path(func) = &*call.func else {
+        return None;
+    };
+    let segments: Vec<String> = func
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let reads_env = match segments.as_slice() {
+        [.., name] if ["env", "env_required", "env_optional"].contains(&name.as_str()) => true,
+        [.., module, name] => module == "env" && (name == "var" || name == "var_os"),
+        _ => false,
+    };
+    if !reads_env {
+        return None;
+    }
+
+    let mut args = call.args.iter();
+    let key = match args.next()? {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Str(key) => key.value(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some((key, args.next().and_then(literal)))
+}
+
+/// The value of a literal default, including `"text".to_string()`
+fn literal(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Str(value) => Some(quote_value(&value.value())),
+            Lit::Int(value) => Some(value.base10_digits().to_string()),
+            Lit::Float(value) => Some(value.base10_digits().to_string()),
+            Lit::Bool(value) => Some(value.value.to_string()),
+            _ => None,
+        },
+        Expr::MethodCall(call)
+            if call.args.is_empty()
+                && ["to_string", "to_owned", "into"]
+                    .contains(&call.method.to_string().as_str()) =>
+        {
+            literal(&call.receiver)
+        }
+        _ => None,
+    }
+}
+
+/// A value as written in a dotenv file, quoted if it contains spaces or `#`
+fn quote_value(value: &str) -> String {
+    if value.contains([' ', '#', '"']) {
+        format!("\"{}\"", value.replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The keys assigned in a dotenv file
+fn keys(contents: &str) -> HashSet<&str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, _) = line.split_once('=')?;
+            Some(key.trim())
+        })
+        .collect()
+}
@@ -11,8 +11,10 @@ use regex::Regex;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
 use syn::visit::Visit;
-use syn::{Attribute, Fields, FnArg, ItemFn, ItemStruct, Type};
+use syn::{Attribute, Expr, Fields, FnArg, ItemFn, ItemStruct, LitStr, Token, Type};
 use walkdir::WalkDir;
 
 /// HTTP methods for routes
@@ -63,6 +65,9 @@ pub struct RouteDefinition {
     pub handler_fn: String,     // e.g., "show"
     pub name: Option<String>,   // e.g., "users.show"
     pub path_params: Vec<PathParam>,
+    pub description: Option<String>, // from `.describe()`
+    pub tags: Vec<String>,           // from `.tag()`
+    pub deprecated: bool,            // from `.deprecated()`
 }
 
 /// Information about a handler function
@@ -108,7 +113,166 @@ pub struct GeneratedRoute {
 }
 
 /// Parse routes.rs file content and extract route definitions
+///
+/// Routes inside `group!` get their full path and name, including prefixes
+/// from nested groups and `.name_prefix()`. If the file has no `routes!`
+/// block that syn can parse, route macros are matched by pattern instead.
 pub fn parse_routes_file(content: &str) -> Vec<RouteDefinition> {
+    parse_routes_macro(content).unwrap_or_else(|| parse_routes_with_regex(content))
+}
+
+fn parse_routes_macro(content: &str) -> Option<Vec<RouteDefinition>> {
+    let file = syn::parse_file(content).ok()?;
+    let routes_macro = file.items.iter().find_map(|item| match item {
+        syn::Item::Macro(m) if m.mac.path.is_ident("routes") => Some(&m.mac),
+        _ => None,
+    })?;
+    let items = routes_macro
+        .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        .ok()?;
+
+    let mut routes = Vec::new();
+    for item in &items {
+        collect_routes(item, "", "", &mut routes);
+    }
+    Some(routes)
+}
+
+/// Collect the routes defined by one `routes!` or `group!` entry
+fn collect_routes(expr: &Expr, prefix: &str, name_prefix: &str, routes: &mut Vec<RouteDefinition>) {
+    // Peel off chained calls; the outermost `.name()` wins, as at runtime
+    let mut expr = expr;
+    let mut name = None;
+    let mut group_name_prefix = None;
+    let mut description = None;
+    let mut tags = Vec::new();
+    let mut deprecated = false;
+    while let Expr::MethodCall(call) = expr {
+        let arg = call.args.first().and_then(string_literal);
+        match call.method.to_string().as_str() {
+            "name" => name = name.or(arg),
+            "name_prefix" => group_name_prefix = group_name_prefix.or(arg),
+            "describe" => description = description.or(arg),
+            "tag" => tags.extend(arg),
+            "deprecated" => deprecated = true,
+            _ => {}
+        }
+        expr = &call.receiver;
+    }
+    // Tags were collected outermost first
+    tags.reverse();
+
+    let Expr::Macro(mac) = expr else {
+        return;
+    };
+    let Some(macro_name) = mac.mac.path.segments.last().map(|s| s.ident.to_string()) else {
+        return;
+    };
+
+    if macro_name == "group" {
+        let Ok((group_prefix, items)) = mac.mac.parse_body_with(parse_group_body) else {
+            return;
+        };
+        let full_prefix = join_paths(prefix, &group_prefix);
+        let full_name_prefix = format!("{}{}", name_prefix, group_name_prefix.unwrap_or_default());
+        for item in &items {
+            collect_routes(item, &full_prefix, &full_name_prefix, routes);
+        }
+        return;
+    }
+
+    let Some(method) = HttpMethod::from_str(&macro_name) else {
+        return;
+    };
+    let Ok((path, handler)) = mac.mac.parse_body_with(parse_route_body) else {
+        return;
+    };
+    let Some((handler_module, handler_fn)) = handler.rsplit_once("::") else {
+        return;
+    };
+
+    let path = join_paths(prefix, &convert_route_params(&path));
+    let path_params = path_params(&path);
+    routes.push(RouteDefinition {
+        method,
+        path,
+        handler_module: handler_module.to_string(),
+        handler_fn: handler_fn.to_string(),
+        name: name.map(|name| format!("{}{}", name_prefix, name)),
+        path_params,
+        description,
+        tags,
+        deprecated,
+    });
+}
+
+/// `"/prefix", { items... }`
+fn parse_group_body(input: ParseStream) -> syn::Result<(String, Punctuated<Expr, Token![,]>)> {
+    let prefix: LitStr = input.parse()?;
+    input.parse::<Token![,]>()?;
+    let content;
+    syn::braced!(content in input);
+    let items = Punctuated::parse_terminated(&content)?;
+    Ok((prefix.value(), items))
+}
+
+/// `"/path", controllers::module::handler`
+fn parse_route_body(input: ParseStream) -> syn::Result<(String, String)> {
+    let path: LitStr = input.parse()?;
+    input.parse::<Token![,]>()?;
+    let handler: syn::Path = input.parse()?;
+    let _ = input.parse::<Option<Token![,]>>()?;
+    let handler = handler
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    Ok((path.value(), handler))
+}
+
+fn string_literal(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(s),
+            ..
+        }) => Some(s.value()),
+        _ => None,
+    }
+}
+
+/// Join a group prefix and a route path the same way the router does
+fn join_paths(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    if path == "/" || path.is_empty() {
+        if prefix.is_empty() {
+            "/".to_string()
+        } else {
+            prefix.to_string()
+        }
+    } else {
+        format!("{}{}", prefix, path)
+    }
+}
+
+/// Convert `:param` segments to `{param}`
+fn convert_route_params(path: &str) -> String {
+    let param = Regex::new(r":(\w+)").unwrap();
+    param.replace_all(path, "{$1}").into_owned()
+}
+
+fn path_params(path: &str) -> Vec<PathParam> {
+    let param_pattern = Regex::new(r#"\{(\w+)\}"#).unwrap();
+    param_pattern
+        .captures_iter(path)
+        .map(|cap| PathParam {
+            name: cap[1].to_string(),
+        })
+        .collect()
+}
+
+/// Match route macros by pattern, for files without a parseable `routes!` block
+fn parse_routes_with_regex(content: &str) -> Vec<RouteDefinition> {
     let mut routes = Vec::new();
 
     // Pattern to match route definitions like:
@@ -157,6 +321,9 @@ pub fn parse_routes_file(content: &str) -> Vec<RouteDefinition> {
             handler_fn,
             name,
             path_params,
+            description: None,
+            tags: Vec::new(),
+            deprecated: false,
         });
     }
 
@@ -275,6 +442,8 @@ impl FormRequestVisitor {
                     "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32"
                     | "u64" | "u128" | "usize" | "f32" | "f64" => RustType::Number,
                     "bool" => RustType::Bool,
+                    // kit::Money's wire format
+                    "Money" => RustType::Custom("{ amount: string; currency: string }".to_string()),
                     "Option" => {
                         if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
                             if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
@@ -464,6 +633,33 @@ fn rust_type_to_ts(ty: &RustType) -> String {
     }
 }
 
+/// JSDoc for a route helper from its `.describe()`, `.tag()` and `.deprecated()`
+fn doc_comment(route: &RouteDefinition, indent: &str) -> String {
+    let mut lines: Vec<String> = route
+        .description
+        .iter()
+        .flat_map(|description| description.lines())
+        .map(|line| line.replace("*/", "*\\/"))
+        .collect();
+    // TypeDoc groups helpers by `@category`
+    lines.extend(route.tags.iter().map(|tag| format!("@category {}", tag)));
+    if route.deprecated {
+        lines.push("@deprecated".to_string());
+    }
+    match lines.as_slice() {
+        [] => String::new(),
+        [line] => format!("{}/** {} */\n", indent, line),
+        _ => {
+            let mut comment = format!("{}/**\n", indent);
+            for line in &lines {
+                comment.push_str(&format!("{} * {}\n", indent, line));
+            }
+            comment.push_str(&format!("{} */\n", indent));
+            comment
+        }
+    }
+}
+
 /// Generate TypeScript routes file
 pub fn generate_typescript(routes: &[GeneratedRoute]) -> String {
     let mut output = String::new();
@@ -607,6 +803,7 @@ pub fn generate_typescript(routes: &[GeneratedRoute]) -> String {
             let data_prop = if has_data { ", data" } else { "" };
 
             let comma = if j < module_routes.len() - 1 { "," } else { "" };
+            output.push_str(&doc_comment(&route.definition, "    "));
             output.push_str(&format!(
                 "    {}: ({}): {} => ({{ url: {}, method: '{}'{} }}){}\n",
                 fn_name, params_signature, return_type, url, method, data_prop, comma
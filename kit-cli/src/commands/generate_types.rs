@@ -3,7 +3,10 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use syn::visit::Visit;
-use syn::{Attribute, Fields, GenericArgument, ItemStruct, PathArguments, Type};
+use syn::{
+    Attribute, Fields, GenericArgument, ImplItem, ItemImpl, ItemStruct, PathArguments, ReturnType,
+    Type,
+};
 use walkdir::WalkDir;
 
 /// Represents a parsed InertiaProps struct
@@ -24,21 +27,43 @@ pub enum RustType {
     String,
     Number,
     Bool,
+    /// `kit::Money`, sent as `{ amount: string, currency: string }`
+    Money,
+    /// `kit::ActivityItem`, an entry of an activity feed
+    ActivityItem,
+    /// `kit::ImportStatus`, the progress of a CSV import
+    ImportStatus,
+    /// `kit::CursorPage<T>`, the cursor pagination envelope
+    CursorPage(Box<RustType>),
+    /// `kit::InertiaPaginated<T>`, offset pagination props for tables
+    InertiaPaginated(Box<RustType>),
     Option(Box<RustType>),
     Vec(Box<RustType>),
     HashMap(Box<RustType>, Box<RustType>),
     Custom(String),
 }
 
+/// A `#[prop(computed)]` method, before it's attached to its struct
+struct ComputedProp {
+    struct_name: String,
+    method: String,
+    ty: RustType,
+}
+
 /// Visitor that collects structs with #[derive(InertiaProps)]
 struct InertiaPropsVisitor {
     structs: Vec<InertiaPropsStruct>,
+    /// Whether each collected struct renames its props to camelCase
+    camel_case: HashMap<String, bool>,
+    computed: Vec<ComputedProp>,
 }
 
 impl InertiaPropsVisitor {
     fn new() -> Self {
         Self {
             structs: Vec::new(),
+            camel_case: HashMap::new(),
+            computed: Vec::new(),
         }
     }
 
@@ -78,6 +103,29 @@ impl InertiaPropsVisitor {
                     "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32"
                     | "u64" | "u128" | "usize" | "f32" | "f64" => RustType::Number,
                     "bool" => RustType::Bool,
+                    "Money" => RustType::Money,
+                    "ActivityItem" => RustType::ActivityItem,
+                    "ImportStatus" => RustType::ImportStatus,
+                    "CursorPage" => {
+                        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                            if let Some(GenericArgument::Type(inner_ty)) = args.args.first() {
+                                return RustType::CursorPage(Box::new(self.parse_type(inner_ty)));
+                            }
+                        }
+                        RustType::CursorPage(Box::new(RustType::Custom("unknown".to_string())))
+                    }
+                    "InertiaPaginated" => {
+                        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                            if let Some(GenericArgument::Type(inner_ty)) = args.args.first() {
+                                return RustType::InertiaPaginated(Box::new(
+                                    self.parse_type(inner_ty),
+                                ));
+                            }
+                        }
+                        RustType::InertiaPaginated(Box::new(RustType::Custom(
+                            "unknown".to_string(),
+                        )))
+                    }
                     "Option" => {
                         if let PathArguments::AngleBracketed(args) = &segment.arguments {
                             if let Some(GenericArgument::Type(inner_ty)) = args.args.first() {
@@ -140,14 +188,25 @@ impl<'ast> Visit<'ast> for InertiaPropsVisitor {
     fn visit_item_struct(&mut self, node: &'ast ItemStruct) {
         if self.has_inertia_props_derive(&node.attrs) {
             let name = node.ident.to_string();
+            let camel_case = has_camel_case_rename(&node.attrs);
 
             let fields = match &node.fields {
                 Fields::Named(named) => named
                     .named
                     .iter()
                     .filter_map(|f| {
-                        f.ident.as_ref().map(|ident| StructField {
-                            name: ident.to_string(),
+                        let ident = f.ident.as_ref()?;
+                        let serde = serde_field_options(&f.attrs);
+                        if serde.skip {
+                            return None;
+                        }
+                        let name = match serde.rename {
+                            Some(rename) => rename,
+                            None if camel_case => to_camel_case(&ident.to_string()),
+                            None => ident.to_string(),
+                        };
+                        Some(StructField {
+                            name,
                             ty: self.parse_type(&f.ty),
                         })
                     })
@@ -155,18 +214,122 @@ impl<'ast> Visit<'ast> for InertiaPropsVisitor {
                 _ => Vec::new(),
             };
 
+            self.camel_case.insert(name.clone(), camel_case);
             self.structs.push(InertiaPropsStruct { name, fields });
         }
 
         // Continue visiting nested items
         syn::visit::visit_item_struct(self, node);
     }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let is_computed_props = node.attrs.iter().any(|attr| {
+            attr.path()
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "computed_props")
+        });
+        if let (true, Type::Path(self_ty)) = (is_computed_props, &*node.self_ty) {
+            let struct_name = self_ty.path.segments.last().unwrap().ident.to_string();
+            for item in &node.items {
+                let ImplItem::Fn(method) = item else {
+                    continue;
+                };
+                if !method.attrs.iter().any(is_computed_attr) {
+                    continue;
+                }
+                let ty = match &method.sig.output {
+                    ReturnType::Type(_, ty) => self.parse_type(ty),
+                    ReturnType::Default => RustType::Custom("unknown".to_string()),
+                };
+                self.computed.push(ComputedProp {
+                    struct_name: struct_name.clone(),
+                    method: method.sig.ident.to_string(),
+                    ty,
+                });
+            }
+        }
+
+        syn::visit::visit_item_impl(self, node);
+    }
+}
+
+/// Whether a method is marked `#[prop(computed)]`
+fn is_computed_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("prop")
+        && attr
+            .parse_args::<syn::Ident>()
+            .is_ok_and(|ident| ident == "computed")
+}
+
+/// The `#[serde(...)]` field options that change a field's TypeScript shape
+#[derive(Default)]
+struct SerdeFieldOptions {
+    rename: Option<String>,
+    skip: bool,
+}
+
+fn serde_field_options(attrs: &[Attribute]) -> SerdeFieldOptions {
+    let mut options = SerdeFieldOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                options.skip = true;
+            } else if meta.path.is_ident("rename") && meta.input.peek(syn::Token![=]) {
+                let rename: syn::LitStr = meta.value()?.parse()?;
+                options.rename = Some(rename.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|_| Ok(()))?;
+            }
+            Ok(())
+        });
+    }
+    options
+}
+
+/// Whether a props struct has `#[inertia(rename_all = "camelCase")]`
+fn has_camel_case_rename(attrs: &[Attribute]) -> bool {
+    let mut camel_case = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("inertia")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let rule: syn::LitStr = meta.value()?.parse()?;
+                camel_case = rule.value() == "camelCase";
+            }
+            Ok(())
+        });
+    }
+    camel_case
+}
+
+/// `snake_case` to `camelCase`, the same conversion `#[derive(InertiaProps)]`
+/// applies when serializing
+fn to_camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut capitalize = false;
+    for ch in name.chars() {
+        if ch == '_' {
+            capitalize = !camel.is_empty();
+        } else if capitalize {
+            camel.push(ch.to_ascii_uppercase());
+            capitalize = false;
+        } else if camel.is_empty() {
+            camel.push(ch.to_ascii_lowercase());
+        } else {
+            camel.push(ch);
+        }
+    }
+    camel
 }
 
 /// Scan all Rust files in the src directory for InertiaProps structs
 pub fn scan_inertia_props(project_path: &Path) -> Vec<InertiaPropsStruct> {
     let src_path = project_path.join("src");
     let mut all_structs = Vec::new();
+    let mut camel_case = HashMap::new();
+    let mut computed = Vec::new();
 
     for entry in WalkDir::new(&src_path)
         .into_iter()
@@ -178,10 +341,25 @@ pub fn scan_inertia_props(project_path: &Path) -> Vec<InertiaPropsStruct> {
                 let mut visitor = InertiaPropsVisitor::new();
                 visitor.visit_file(&syntax);
                 all_structs.extend(visitor.structs);
+                camel_case.extend(visitor.camel_case);
+                computed.extend(visitor.computed);
             }
         }
     }
 
+    // Computed props may be declared in a different file than their struct
+    for prop in computed {
+        let Some(props) = all_structs.iter_mut().find(|s| s.name == prop.struct_name) else {
+            continue;
+        };
+        let name = if camel_case.get(&prop.struct_name).copied().unwrap_or(false) {
+            to_camel_case(&prop.method)
+        } else {
+            prop.method
+        };
+        props.fields.push(StructField { name, ty: prop.ty });
+    }
+
     all_structs
 }
 
@@ -191,6 +369,13 @@ fn rust_type_to_ts(ty: &RustType) -> String {
         RustType::String => "string".to_string(),
         RustType::Number => "number".to_string(),
         RustType::Bool => "boolean".to_string(),
+        RustType::Money => "Money".to_string(),
+        RustType::ActivityItem => "ActivityItem".to_string(),
+        RustType::ImportStatus => "ImportStatus".to_string(),
+        RustType::CursorPage(inner) => format!("CursorPage<{}>", rust_type_to_ts(inner)),
+        RustType::InertiaPaginated(inner) => {
+            format!("InertiaPaginated<{}>", rust_type_to_ts(inner))
+        }
         RustType::Option(inner) => format!("{} | null", rust_type_to_ts(inner)),
         RustType::Vec(inner) => format!("{}[]", rust_type_to_ts(inner)),
         RustType::HashMap(key, val) => {
@@ -257,7 +442,10 @@ fn collect_type_deps(ty: &RustType, deps: &mut HashSet<String>, known: &HashSet<
         RustType::Custom(name) if known.contains(name) => {
             deps.insert(name.clone());
         }
-        RustType::Option(inner) | RustType::Vec(inner) => {
+        RustType::Option(inner)
+        | RustType::Vec(inner)
+        | RustType::CursorPage(inner)
+        | RustType::InertiaPaginated(inner) => {
             collect_type_deps(inner, deps, known);
         }
         RustType::HashMap(key, val) => {
@@ -268,6 +456,62 @@ fn collect_type_deps(ty: &RustType, deps: &mut HashSet<String>, known: &HashSet<
     }
 }
 
+fn uses_money(ty: &RustType) -> bool {
+    match ty {
+        RustType::Money => true,
+        RustType::Option(inner)
+        | RustType::Vec(inner)
+        | RustType::CursorPage(inner)
+        | RustType::InertiaPaginated(inner) => uses_money(inner),
+        RustType::HashMap(key, val) => uses_money(key) || uses_money(val),
+        _ => false,
+    }
+}
+
+fn uses_activity_item(ty: &RustType) -> bool {
+    match ty {
+        RustType::ActivityItem => true,
+        RustType::Option(inner)
+        | RustType::Vec(inner)
+        | RustType::CursorPage(inner)
+        | RustType::InertiaPaginated(inner) => uses_activity_item(inner),
+        RustType::HashMap(key, val) => uses_activity_item(key) || uses_activity_item(val),
+        _ => false,
+    }
+}
+
+fn uses_import_status(ty: &RustType) -> bool {
+    match ty {
+        RustType::ImportStatus => true,
+        RustType::Option(inner)
+        | RustType::Vec(inner)
+        | RustType::CursorPage(inner)
+        | RustType::InertiaPaginated(inner) => uses_import_status(inner),
+        RustType::HashMap(key, val) => uses_import_status(key) || uses_import_status(val),
+        _ => false,
+    }
+}
+
+fn uses_cursor_page(ty: &RustType) -> bool {
+    match ty {
+        RustType::CursorPage(_) => true,
+        RustType::Option(inner) | RustType::Vec(inner) => uses_cursor_page(inner),
+        RustType::HashMap(key, val) => uses_cursor_page(key) || uses_cursor_page(val),
+        _ => false,
+    }
+}
+
+fn uses_inertia_paginated(ty: &RustType) -> bool {
+    match ty {
+        RustType::InertiaPaginated(_) => true,
+        RustType::Option(inner) | RustType::Vec(inner) | RustType::CursorPage(inner) => {
+            uses_inertia_paginated(inner)
+        }
+        RustType::HashMap(key, val) => uses_inertia_paginated(key) || uses_inertia_paginated(val),
+        _ => false,
+    }
+}
+
 /// Generate TypeScript interfaces from the structs
 pub fn generate_typescript(structs: &[InertiaPropsStruct]) -> String {
     let sorted = topological_sort(structs);
@@ -276,6 +520,88 @@ pub fn generate_typescript(structs: &[InertiaPropsStruct]) -> String {
     output.push_str("// This file is auto-generated by Kit. Do not edit manually.\n");
     output.push_str("// Run `kit generate-types` to regenerate.\n\n");
 
+    if structs
+        .iter()
+        .flat_map(|s| &s.fields)
+        .any(|field| uses_money(&field.ty))
+    {
+        output.push_str("export interface Money {\n  amount: string;\n  currency: string;\n}\n\n");
+    }
+
+    if structs
+        .iter()
+        .flat_map(|s| &s.fields)
+        .any(|field| uses_activity_item(&field.ty))
+    {
+        output.push_str(concat!(
+            "export interface ActivityItem {\n",
+            "  id: number;\n",
+            "  actor_id: number | null;\n",
+            "  action: string;\n",
+            "  subject_type: string;\n",
+            "  subject_id: string;\n",
+            "  properties: Record<string, unknown>;\n",
+            "  created_at: string;\n",
+            "}\n\n",
+        ));
+    }
+
+    if structs
+        .iter()
+        .flat_map(|s| &s.fields)
+        .any(|field| uses_import_status(&field.ty))
+    {
+        output.push_str(concat!(
+            "export interface ImportStatus {\n",
+            "  status: 'pending' | 'running' | 'succeeded' | 'failed';\n",
+            "  total: number;\n",
+            "  processed: number;\n",
+            "  imported: number;\n",
+            "  failed: number;\n",
+            "  progress: number;\n",
+            "  report_url: string | null;\n",
+            "  error: string | null;\n",
+            "}\n\n",
+        ));
+    }
+
+    if structs
+        .iter()
+        .flat_map(|s| &s.fields)
+        .any(|field| uses_cursor_page(&field.ty))
+    {
+        output.push_str(
+            "export interface CursorPage<T> {\n  data: T[];\n  next_cursor: string | null;\n  prev_cursor: string | null;\n}\n\n",
+        );
+    }
+
+    if structs
+        .iter()
+        .flat_map(|s| &s.fields)
+        .any(|field| uses_inertia_paginated(&field.ty))
+    {
+        output.push_str(concat!(
+            "export interface InertiaPaginated<T> {\n",
+            "  data: T[];\n",
+            "  links: {\n",
+            "    first: string;\n",
+            "    last: string;\n",
+            "    prev: string | null;\n",
+            "    next: string | null;\n",
+            "  };\n",
+            "  meta: {\n",
+            "    current_page: number;\n",
+            "    last_page: number;\n",
+            "    per_page: number;\n",
+            "    total: number;\n",
+            "    from: number | null;\n",
+            "    to: number | null;\n",
+            "    path: string;\n",
+            "  };\n",
+            "}\n\n",
+        ));
+    }
+
     for s in sorted {
         output.push_str(&format!("export interface {} {{\n", s.name));
         for field in &s.fields {
@@ -0,0 +1,220 @@
+//! make:billing command - Scaffold Stripe subscription billing
+//!
+//! Adds the `billing_customers` and `subscriptions` migration, a webhook
+//! controller routed at `/stripe/webhook` and exempt from CSRF, and
+//! `impl Billable` on the User model. Running it again only adds what is
+//! missing.
+
+use console::style;
+use std::fs;
+use std::path::Path;
+
+use super::make_controller;
+use super::make_migration::{migrator_mod_template, update_mod_file};
+use crate::templates;
+use crate::wiring::{self, Registration};
+
+const BILLING_MIGRATION: &str = "m20240101_000006_create_billing_tables";
+
+const WEBHOOK_ROUTE: &str = "/stripe/webhook";
+
+pub fn run() {
+    if !Path::new("src").exists() {
+        eprintln!(
+            "{} Not in a Kit project root directory",
+            style("Error:").red().bold()
+        );
+        std::process::exit(1);
+    }
+
+    if let Err(e) = install_migration() {
+        fail(&e);
+    }
+    if let Err(e) = install_controller() {
+        fail(&e);
+    }
+    register_route();
+    exempt_from_csrf();
+    if let Err(e) = make_user_billable() {
+        fail(&e);
+    }
+
+    println!();
+    println!("Billing installed. Next steps:");
+    println!(
+        "  {} Set STRIPE_SECRET and STRIPE_WEBHOOK_SECRET in .env",
+        style("1.").dim()
+    );
+    println!(
+        "  {} Point a Stripe webhook at {} with the customer.subscription.* events",
+        style("2.").dim(),
+        style(WEBHOOK_ROUTE).cyan()
+    );
+    println!("  {} Run `kit migrate`", style("3.").dim());
+    println!();
+    println!("Then send users to Stripe Checkout with `user.checkout(\"pro\", price, ...)`");
+    println!("and check access with `user.subscribed(\"pro\").await?`.");
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{} {}", style("Error:").red().bold(), message);
+    std::process::exit(1);
+}
+
+fn install_migration() -> Result<(), String> {
+    let migrations_dir = Path::new("src/migrations");
+    fs::create_dir_all(migrations_dir)
+        .map_err(|e| format!("Failed to create migrations directory: {}", e))?;
+
+    let migration_file = migrations_dir.join(format!("{}.rs", BILLING_MIGRATION));
+    if migration_file.exists() {
+        println!(
+            "{} {} already exists",
+            style("Info:").yellow().bold(),
+            migration_file.display()
+        );
+    } else {
+        fs::write(&migration_file, templates::create_billing_migration())
+            .map_err(|e| format!("Failed to write billing migration: {}", e))?;
+        println!(
+            "{} Created {}",
+            style("✓").green(),
+            migration_file.display()
+        );
+    }
+
+    let mod_file = migrations_dir.join("mod.rs");
+    if mod_file.exists() {
+        update_mod_file(&mod_file, BILLING_MIGRATION)?;
+    } else {
+        fs::write(&mod_file, migrator_mod_template(BILLING_MIGRATION))
+            .map_err(|e| format!("Failed to create mod.rs: {}", e))?;
+    }
+    println!("{} Updated src/migrations/mod.rs", style("✓").green());
+    Ok(())
+}
+
+fn install_controller() -> Result<(), String> {
+    let controllers_dir = Path::new("src/controllers");
+    fs::create_dir_all(controllers_dir)
+        .map_err(|e| format!("Failed to create controllers directory: {}", e))?;
+
+    let controller_file = controllers_dir.join("billing.rs");
+    if controller_file.exists() {
+        println!(
+            "{} {} already exists",
+            style("Info:").yellow().bold(),
+            controller_file.display()
+        );
+    } else {
+        fs::write(&controller_file, templates::billing_controller_template())
+            .map_err(|e| format!("Failed to write billing controller: {}", e))?;
+        println!(
+            "{} Created {}",
+            style("✓").green(),
+            controller_file.display()
+        );
+    }
+
+    let mod_file = controllers_dir.join("mod.rs");
+    let mod_content = fs::read_to_string(&mod_file).unwrap_or_default();
+    if !mod_content.contains("pub mod billing;") {
+        if mod_file.exists() {
+            make_controller::update_mod_file(&mod_file, "billing")?;
+        } else {
+            fs::write(&mod_file, "pub mod billing;\n")
+                .map_err(|e| format!("Failed to create mod.rs: {}", e))?;
+        }
+        println!("{} Updated src/controllers/mod.rs", style("✓").green());
+    }
+    Ok(())
+}
+
+fn register_route() {
+    let routes = Path::new("src/routes.rs");
+    match wiring::register_route(
+        routes,
+        "post",
+        WEBHOOK_ROUTE,
+        "controllers::billing::webhook",
+    ) {
+        Ok(Registration::Added) => {
+            println!(
+                "{} Added POST {} to src/routes.rs",
+                style("✓").green(),
+                WEBHOOK_ROUTE
+            );
+        }
+        Ok(Registration::AlreadyRegistered) => {
+            println!(
+                "{} POST {} is already in src/routes.rs",
+                style("Info:").yellow().bold(),
+                WEBHOOK_ROUTE
+            );
+        }
+        Err(e) => fail(&e),
+    }
+}
+
+/// Stripe can't send a CSRF token, so the webhook must be exempt
+fn exempt_from_csrf() {
+    let bootstrap = Path::new("src/bootstrap.rs");
+    let content = fs::read_to_string(bootstrap).unwrap_or_default();
+    let default_csrf = "CsrfMiddleware::new())";
+
+    if content.contains(WEBHOOK_ROUTE) {
+        return;
+    }
+    if content.matches(default_csrf).count() != 1 {
+        println!(
+            "{} Exempt {} from CSRF protection in src/bootstrap.rs:",
+            style("Info:").yellow().bold(),
+            WEBHOOK_ROUTE
+        );
+        println!(
+            "     CsrfMiddleware::new().except(vec![\"{}\"])",
+            WEBHOOK_ROUTE
+        );
+        return;
+    }
+
+    let updated = content.replace(
+        default_csrf,
+        &format!("CsrfMiddleware::new().except(vec![\"{}\"]))", WEBHOOK_ROUTE),
+    );
+    if let Err(e) = fs::write(bootstrap, updated) {
+        fail(&format!("Failed to update src/bootstrap.rs: {}", e));
+    }
+    println!(
+        "{} Exempted {} from CSRF protection in src/bootstrap.rs",
+        style("✓").green(),
+        WEBHOOK_ROUTE
+    );
+}
+
+fn make_user_billable() -> Result<(), String> {
+    let user_model = Path::new("src/models/user.rs");
+    let Ok(content) = fs::read_to_string(user_model) else {
+        println!(
+            "{} No src/models/user.rs; implement kit::Billable on your user model",
+            style("Info:").yellow().bold()
+        );
+        return Ok(());
+    };
+    if content.contains("Billable for") {
+        return Ok(());
+    }
+
+    let updated = format!(
+        "{}\n{}",
+        content.trim_end(),
+        templates::billable_impl_template()
+    );
+    fs::write(user_model, updated)
+        .map_err(|e| format!("Failed to update src/models/user.rs: {}", e))?;
+    println!(
+        "{} Implemented kit::Billable for the User model",
+        style("✓").green()
+    );
+    Ok(())
+}
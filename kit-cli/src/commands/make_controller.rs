@@ -3,8 +3,9 @@ use std::fs;
 use std::path::Path;
 
 use crate::templates;
+use crate::wiring::{self, Registration};
 
-pub fn run(name: String) {
+pub fn run(name: String, route: Option<String>) {
     // Convert to snake_case for file name
     let file_name = to_snake_case(&name);
 
@@ -18,6 +19,17 @@ pub fn run(name: String) {
         std::process::exit(1);
     }
 
+    if let Some(route) = &route {
+        if !route.starts_with('/') {
+            eprintln!(
+                "{} Route '{}' must start with '/'",
+                style("Error:").red().bold(),
+                route
+            );
+            std::process::exit(1);
+        }
+    }
+
     let controllers_dir = Path::new("src/controllers");
     let controller_file = controllers_dir.join(format!("{}.rs", file_name));
     let mod_file = controllers_dir.join("mod.rs");
@@ -43,6 +55,9 @@ pub fn run(name: String) {
             file_name,
             controller_file.display()
         );
+        if let Some(route) = &route {
+            register_route(route, &file_name);
+        }
         std::process::exit(0);
     }
 
@@ -57,6 +72,9 @@ pub fn run(name: String) {
                 style("Info:").yellow().bold(),
                 file_name
             );
+            if let Some(route) = &route {
+                register_route(route, &file_name);
+            }
             std::process::exit(0);
         }
     }
@@ -104,12 +122,21 @@ pub fn run(name: String) {
         println!("{} Created src/controllers/mod.rs", style("✓").green());
     }
 
+    if let Some(route) = &route {
+        register_route(route, &file_name);
+    }
+
     println!();
     println!(
         "Controller {} created successfully!",
         style(&file_name).cyan().bold()
     );
     println!();
+    if let Some(route) = &route {
+        println!("Visit {} to try it out.", style(route).cyan());
+        println!();
+        return;
+    }
     println!("Usage:");
     println!("  {} Add a route in src/routes.rs:", style("1.").dim());
     println!(
@@ -119,6 +146,33 @@ pub fn run(name: String) {
     println!();
 }
 
+/// Add a GET route for the controller to src/routes.rs
+fn register_route(route: &str, file_name: &str) {
+    let routes = Path::new("src/routes.rs");
+    let handler = format!("controllers::{}::invoke", file_name);
+
+    match wiring::register_route(routes, "get", route, &handler) {
+        Ok(Registration::Added) => {
+            println!(
+                "{} Added GET {} to src/routes.rs",
+                style("✓").green(),
+                route
+            );
+        }
+        Ok(Registration::AlreadyRegistered) => {
+            println!(
+                "{} GET {} is already in src/routes.rs",
+                style("Info:").yellow().bold(),
+                route
+            );
+        }
+        Err(e) => {
+            eprintln!("{} {}", style("Error:").red().bold(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn is_valid_identifier(name: &str) -> bool {
     if name.is_empty() {
         return false;
@@ -151,7 +205,7 @@ fn to_snake_case(s: &str) -> String {
     result
 }
 
-fn update_mod_file(mod_file: &Path, file_name: &str) -> Result<(), String> {
+pub(crate) fn update_mod_file(mod_file: &Path, file_name: &str) -> Result<(), String> {
     let content =
         fs::read_to_string(mod_file).map_err(|e| format!("Failed to read mod.rs: {}", e))?;
 
@@ -3,8 +3,9 @@ use std::fs;
 use std::path::Path;
 
 use crate::templates;
+use crate::wiring::{self, Registration};
 
-pub fn run(name: String) {
+pub fn run(name: String, global: bool) {
     // Validate name is a valid Rust identifier
     if !is_valid_identifier(&name) {
         eprintln!(
@@ -44,6 +45,10 @@ pub fn run(name: String) {
 
     // Check if middleware file already exists
     if middleware_file.exists() {
+        if global {
+            register_globally(&struct_name);
+            return;
+        }
         eprintln!(
             "{} Middleware '{}' already exists at {}",
             style("Error:").red().bold(),
@@ -99,12 +104,21 @@ pub fn run(name: String) {
         println!("{} Created src/middleware/mod.rs", style("✓").green());
     }
 
+    if global {
+        register_globally(&struct_name);
+    }
+
     println!();
     println!(
         "Middleware {} created successfully!",
         style(&struct_name).cyan().bold()
     );
     println!();
+    if global {
+        println!("It now runs on every request.");
+        println!();
+        return;
+    }
     println!("Usage:");
     println!("  {} Import and use in routes:", style("1.").dim());
     println!("     use crate::middleware::{};", struct_name);
@@ -115,6 +129,33 @@ pub fn run(name: String) {
     println!();
 }
 
+/// Add the middleware to `register()` in src/bootstrap.rs
+fn register_globally(struct_name: &str) {
+    let bootstrap = Path::new("src/bootstrap.rs");
+    let middleware = format!("middleware::{}", struct_name);
+
+    match wiring::register_global_middleware(bootstrap, &middleware) {
+        Ok(Registration::Added) => {
+            println!(
+                "{} Registered {} in src/bootstrap.rs",
+                style("✓").green(),
+                struct_name
+            );
+        }
+        Ok(Registration::AlreadyRegistered) => {
+            println!(
+                "{} {} is already registered in src/bootstrap.rs",
+                style("Info:").yellow().bold(),
+                struct_name
+            );
+        }
+        Err(e) => {
+            eprintln!("{} {}", style("Error:").red().bold(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn is_valid_identifier(name: &str) -> bool {
     if name.is_empty() {
         return false;
@@ -255,7 +255,7 @@ enum {table_enum_name} {{
     )
 }
 
-fn migrator_mod_template(migration_name: &str) -> String {
+pub fn migrator_mod_template(migration_name: &str) -> String {
     format!(
         r#"pub use sea_orm_migration::prelude::*;
 
@@ -276,7 +276,7 @@ impl MigratorTrait for Migrator {{
     )
 }
 
-fn update_mod_file(mod_file: &Path, migration_name: &str) -> Result<(), String> {
+pub fn update_mod_file(mod_file: &Path, migration_name: &str) -> Result<(), String> {
     let content =
         fs::read_to_string(mod_file).map_err(|e| format!("Failed to read mod.rs: {}", e))?;
 
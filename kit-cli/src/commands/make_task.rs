@@ -168,9 +168,18 @@ pub fn run(name: String) {
     );
     println!();
     println!(
-        "  {} Run the scheduler:",
+        "  {} Make sure the schedule is registered in cmd/main.rs:",
         style("3.").dim()
     );
+    println!(
+        "     {}",
+        style("Application::new().schedule(schedule::register)").cyan()
+    );
+    println!();
+    println!(
+        "  {} Run the scheduler:",
+        style("4.").dim()
+    );
     println!("     kit schedule:work  {} Daemon mode", style("#").dim());
     println!("     kit schedule:run   {} Run once", style("#").dim());
     println!("     kit schedule:list  {} List tasks", style("#").dim());
@@ -2,7 +2,7 @@ use console::style;
 use std::path::Path;
 use std::process::Command;
 
-pub fn run() {
+pub fn run(check_safety: bool) {
     // Check we're in a Kit project
     if !Path::new("src/migrations").exists() {
         eprintln!(
@@ -16,6 +16,10 @@ pub fn run() {
         std::process::exit(1);
     }
 
+    if check_safety {
+        super::migrate_safety::check();
+    }
+
     println!("{} Running migrations...", style("->").cyan());
 
     // Run cargo run -- migrate (unified binary)
@@ -0,0 +1,314 @@
+//! Safety linter for `kit migrate --check-safety`
+//!
+//! Pending migrations are parsed and checked for operations that fail or
+//! lock tables on a database that already has data:
+//!
+//! - `add_column` with a NOT NULL column and no default, which fails on a
+//!   table with rows (or rewrites it on databases that backfill)
+//! - `create_index` on an existing table on Postgres, which blocks writes
+//!   until the index is built; `create_index_concurrently` from
+//!   `kit::database::migration::prelude` doesn't
+//!
+//! A `// kit:allow-unsafe` comment on or above the line silences a finding.
+
+use console::style;
+use proc_macro2::Span;
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, Statement};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Expr, ExprMethodCall};
+
+const ALLOW_MARKER: &str = "kit:allow-unsafe";
+
+struct Finding {
+    file: PathBuf,
+    line: usize,
+    message: String,
+    hint: &'static str,
+}
+
+/// Lint the pending migrations, exiting with an error if any are unsafe
+pub fn check() {
+    dotenvy::dotenv().ok();
+    let database_url = env::var("DATABASE_URL").unwrap_or_default();
+    let postgres = database_url.starts_with("postgres");
+
+    let applied = applied_versions(&database_url);
+    let mut files: Vec<PathBuf> = fs::read_dir("src/migrations")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                    path.extension().is_some_and(|ext| ext == "rs")
+                        && stem != "mod"
+                        && !applied.contains(stem.as_ref())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    println!(
+        "{} Checking {} pending migration(s) for unsafe operations...",
+        style("->").cyan(),
+        files.len()
+    );
+
+    let findings: Vec<Finding> = files
+        .iter()
+        .flat_map(|file| lint_file(file, postgres))
+        .collect();
+    if findings.is_empty() {
+        println!("{} No unsafe operations found", style("✓").green());
+        return;
+    }
+
+    eprintln!(
+        "{} {} unsafe operation(s) in pending migrations:",
+        style("Error:").red().bold(),
+        findings.len()
+    );
+    for finding in &findings {
+        eprintln!(
+            "  {}:{} {}",
+            finding.file.display(),
+            finding.line,
+            finding.message
+        );
+        eprintln!("      {}", style(finding.hint).dim());
+    }
+    eprintln!(
+        "{}",
+        style(format!(
+            "Add a `// {}` comment to the line if it is safe for your data.",
+            ALLOW_MARKER
+        ))
+        .dim()
+    );
+    std::process::exit(1);
+}
+
+/// Migrations already recorded in the database, empty if it can't be read
+fn applied_versions(database_url: &str) -> HashSet<String> {
+    if database_url.is_empty() {
+        return HashSet::new();
+    }
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let mut options = ConnectOptions::new(database_url);
+        options
+            .connect_timeout(Duration::from_secs(5))
+            .acquire_timeout(Duration::from_secs(5))
+            .sqlx_logging(false);
+        let Ok(db) = Database::connect(options).await else {
+            return HashSet::new();
+        };
+        let statement = Statement::from_string(
+            db.get_database_backend(),
+            "SELECT version FROM seaql_migrations",
+        );
+        match db.query_all(statement).await {
+            Ok(rows) => rows
+                .iter()
+                .filter_map(|row| row.try_get::<String>("", "version").ok())
+                .collect(),
+            Err(_) => HashSet::new(),
+        }
+    })
+}
+
+fn lint_file(path: &Path, postgres: bool) -> Vec<Finding> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let Ok(file) = syn::parse_file(&content) else {
+        return Vec::new();
+    };
+
+    let mut created = CreatedTables::default();
+    created.visit_file(&file);
+    let mut linter = Linter {
+        postgres,
+        created_tables: created.tables,
+        findings: Vec::new(),
+    };
+    linter.visit_file(&file);
+
+    let lines: Vec<&str> = content.lines().collect();
+    // From the line above the statement through the flagged line
+    let allowed = |start: usize, line: usize| {
+        lines[start.saturating_sub(2)..line.min(lines.len())]
+            .iter()
+            .any(|l| l.contains(ALLOW_MARKER))
+    };
+    linter
+        .findings
+        .into_iter()
+        .filter(|(start, line, _, _)| !allowed(*start, *line))
+        .map(|(_, line, message, hint)| Finding {
+            file: path.to_path_buf(),
+            line,
+            message,
+            hint,
+        })
+        .collect()
+}
+
+/// The method calls of a builder chain, outermost first
+fn chain(expr: &Expr) -> Vec<&ExprMethodCall> {
+    let mut calls = Vec::new();
+    let mut expr = expr;
+    loop {
+        match expr {
+            Expr::MethodCall(call) => {
+                calls.push(call);
+                expr = &call.receiver;
+            }
+            Expr::Reference(reference) => expr = &reference.expr,
+            Expr::Paren(paren) => expr = &paren.expr,
+            _ => return calls,
+        }
+    }
+}
+
+/// The innermost receiver of a builder chain, e.g. `Table::create()`
+fn chain_root(expr: &Expr) -> &Expr {
+    match chain(expr).last() {
+        Some(call) => chain_root(&call.receiver),
+        None => expr,
+    }
+}
+
+/// The first argument of the `.table(...)` call in a builder chain
+fn chain_table(expr: &Expr) -> Option<String> {
+    chain(expr)
+        .into_iter()
+        .find(|call| call.method == "table")
+        .and_then(|call| call.args.first())
+        .map(source)
+}
+
+fn source(expr: &Expr) -> String {
+    expr.span()
+        .source_text()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect()
+}
+
+fn line(span: Span) -> usize {
+    span.start().line
+}
+
+fn is_path_call(expr: &Expr, ty: &str, function: &str) -> bool {
+    let Expr::Call(call) = expr else {
+        return false;
+    };
+    let Expr::Path(path) = &*call.func else {
+        return false;
+    };
+    let segments: Vec<String> = path
+        .path
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect();
+    segments.ends_with(&[ty.to_string(), function.to_string()])
+}
+
+/// Tables created in the file, where new indexes can't block anyone
+#[derive(Default)]
+struct CreatedTables {
+    tables: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for CreatedTables {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        if call.method == "create_table" {
+            if let Some(table) = call.args.first() {
+                if is_path_call(chain_root(table), "Table", "create") {
+                    self.tables.extend(chain_table(table));
+                }
+            }
+        }
+        syn::visit::visit_expr_method_call(self, call);
+    }
+}
+
+struct Linter {
+    postgres: bool,
+    created_tables: HashSet<String>,
+    findings: Vec<(usize, usize, String, &'static str)>,
+}
+
+impl Linter {
+    fn check_add_column(&mut self, call: &ExprMethodCall, column: &Expr) {
+        let calls = chain(column);
+        let has = |method: &str| calls.iter().any(|call| call.method == method);
+        if !has("not_null") || has("default") {
+            return;
+        }
+        let root = chain_root(column);
+        let name = match root {
+            Expr::Call(call) if is_path_call(root, "ColumnDef", "new") => {
+                call.args.first().map(source)
+            }
+            _ => None,
+        };
+        self.findings.push((
+            line(call.span()),
+            line(column.span()),
+            format!(
+                "adds NOT NULL column {}without a default",
+                name.map(|name| format!("`{}` ", name)).unwrap_or_default()
+            ),
+            "Add a .default(...), or add the column as nullable, backfill it and \
+             make it NOT NULL in a later migration.",
+        ));
+    }
+
+    fn check_create_index(&mut self, call: &ExprMethodCall) {
+        let Some(index) = call.args.first() else {
+            return;
+        };
+        let table = chain_table(index);
+        if table
+            .as_ref()
+            .is_some_and(|table| self.created_tables.contains(table))
+        {
+            return;
+        }
+        self.findings.push((
+            line(call.span()),
+            line(call.method.span()),
+            format!(
+                "creates an index on {} without CONCURRENTLY",
+                table
+                    .map(|table| format!("`{}`", table))
+                    .unwrap_or_else(|| "an existing table".to_string())
+            ),
+            "Postgres blocks writes to the table until the index is built. Use \
+             manager.create_index_concurrently(...) from kit::database::migration::prelude.",
+        ));
+    }
+}
+
+impl<'ast> Visit<'ast> for Linter {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        let method = call.method.to_string();
+        if method == "add_column" || method == "add_column_if_not_exists" {
+            if let Some(column) = call.args.first() {
+                self.check_add_column(call, column);
+            }
+        } else if method == "create_index" && self.postgres {
+            self.check_create_index(call);
+        }
+        syn::visit::visit_expr_method_call(self, call);
+    }
+}
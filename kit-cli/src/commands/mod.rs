@@ -1,9 +1,15 @@
+pub mod activity_install;
+pub mod bench;
+pub mod daemon_stop;
+pub mod db_diff;
 pub mod db_sync;
 pub mod docker_compose;
 pub mod docker_init;
+pub mod env_sync;
 pub mod generate_routes;
 pub mod generate_types;
 pub mod make_action;
+pub mod make_billing;
 pub mod make_controller;
 pub mod make_error;
 pub mod make_inertia;
@@ -13,12 +19,20 @@ pub mod make_task;
 pub mod migrate;
 pub mod migrate_fresh;
 pub mod migrate_rollback;
+pub mod migrate_safety;
 pub mod migrate_status;
 pub mod new;
+pub mod queue_install;
+pub mod queue_work;
+pub mod route_cache;
+pub mod routes_list;
 pub mod schedule_list;
 pub mod schedule_run;
 pub mod schedule_work;
+pub mod schema_snapshot;
+pub mod schema_verify;
 pub mod serve;
 pub mod web_run;
+pub mod work;
 pub mod workflow_install;
 pub mod workflow_work;
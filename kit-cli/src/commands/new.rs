@@ -4,13 +4,51 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+use crate::project;
 use crate::templates;
 
-pub fn run(name: Option<String>, no_interaction: bool, no_git: bool) {
+/// How `kit new` pins and installs dependencies
+#[derive(Debug, Default)]
+pub struct InstallOptions {
+    /// Pin exact versions from the bundled lockfile and keep Cargo offline
+    pub offline: bool,
+    /// Run npm install in frontend/ once the project is created
+    pub npm_install: bool,
+    /// crates.io replacement registry for Cargo
+    pub registry: Option<String>,
+    /// Registry for npm, written to frontend/.npmrc
+    pub npm_registry: Option<String>,
+}
+
+pub fn run(
+    name: Option<String>,
+    no_interaction: bool,
+    no_git: bool,
+    workspace: bool,
+    install: InstallOptions,
+) {
     println!();
     println!("{}", style("Welcome to Kit!").cyan().bold());
     println!();
 
+    // Workspace members live in the workspace's git repository
+    let workspace_root = if workspace {
+        let root = std::env::current_dir()
+            .ok()
+            .and_then(|dir| project::find_workspace_root(&dir));
+        if root.is_none() {
+            eprintln!(
+                "{} No Cargo workspace found in this directory or its parents",
+                style("Error:").red().bold()
+            );
+            std::process::exit(1);
+        }
+        root
+    } else {
+        None
+    };
+    let no_git = no_git || workspace_root.is_some();
+
     let project_name = get_project_name(name, no_interaction);
     let description = get_description(no_interaction);
     let author = get_author(no_interaction);
@@ -23,23 +61,53 @@ pub fn run(name: Option<String>, no_interaction: bool, no_git: bool) {
         style(format!("Creating project '{}'...", project_name)).dim()
     );
 
-    if let Err(e) = create_project(&project_name, &package_name, &description, &author, no_git) {
+    if let Err(e) = create_project(
+        &project_name,
+        &package_name,
+        &description,
+        &author,
+        no_git,
+        &install,
+    ) {
         eprintln!("{} {}", style("Error:").red().bold(), e);
         std::process::exit(1);
     }
 
     println!("{} Generated project structure", style("✓").green());
 
+    if let Some(root) = &workspace_root {
+        match add_to_workspace(root, &project_name) {
+            Ok(member) => println!(
+                "{} Added {} to the workspace members",
+                style("✓").green(),
+                member
+            ),
+            Err(e) => {
+                eprintln!("{} {}", style("Error:").red().bold(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     if !no_git {
         println!("{} Initialized git repository", style("✓").green());
     }
 
+    if install.offline {
+        println!("{} Pinned dependency versions", style("✓").green());
+    } else if install.npm_install {
+        install_npm_dependencies(Path::new(&project_name).join("frontend").as_path());
+    }
+
     println!("{} Ready to go!", style("✓").green());
     println!();
     println!("Next steps:");
     println!("  {} {}", style("cd").cyan(), project_name);
     println!("  {}", style("kit serve").cyan());
     println!();
+    if install.offline {
+        print_offline_instructions();
+    }
     println!(
         "Backend will be at {}",
         style("http://localhost:8000").underlined()
@@ -51,6 +119,70 @@ pub fn run(name: Option<String>, no_interaction: bool, no_git: bool) {
     println!();
 }
 
+/// Register the new project with the workspace, returning its member path
+fn add_to_workspace(workspace_root: &Path, project_name: &str) -> Result<String, String> {
+    let project_path = Path::new(project_name)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve project path: {}", e))?;
+    let workspace_root = workspace_root
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve workspace path: {}", e))?;
+    let member = project_path
+        .strip_prefix(&workspace_root)
+        .map_err(|_| "The project is outside of the workspace".to_string())?
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    project::add_workspace_member(&workspace_root, &member)?;
+    Ok(member)
+}
+
+/// Run `npm install` for the new project, warning instead of failing
+fn install_npm_dependencies(frontend_path: &Path) {
+    println!("{}", style("Installing frontend dependencies...").dim());
+    let status = Command::new("npm")
+        .args(["install"])
+        .current_dir(frontend_path)
+        .status();
+
+    match status {
+        Ok(status) if status.success() => {
+            println!("{} Installed frontend dependencies", style("✓").green());
+        }
+        Ok(_) => {
+            eprintln!(
+                "{} npm install failed; run it again in frontend/ or use --offline",
+                style("Warning:").yellow().bold()
+            );
+        }
+        Err(_) => {
+            eprintln!(
+                "{} npm not found; run npm install in frontend/ once Node.js is installed",
+                style("Warning:").yellow().bold()
+            );
+        }
+    }
+}
+
+fn print_offline_instructions() {
+    println!(
+        "{}",
+        style("Offline mode: no dependencies were downloaded.").yellow()
+    );
+    println!("  Cargo.toml and frontend/package.json are pinned to exact versions,");
+    println!("  and .cargo/config.toml keeps Cargo offline. Before building:");
+    println!(
+        "  - make the crates available in your Cargo cache, or vendor them with {}",
+        style("cargo vendor").cyan()
+    );
+    println!(
+        "  - install frontend packages from your npm cache with {}",
+        style("npm install --offline").cyan()
+    );
+    println!("  Remove [net] from .cargo/config.toml to go back online.");
+    println!();
+}
+
 fn get_project_name(name: Option<String>, no_interaction: bool) -> String {
     if let Some(n) = name {
         return n;
@@ -139,6 +271,7 @@ fn create_project(
     description: &str,
     author: &str,
     no_git: bool,
+    install: &InstallOptions,
 ) -> Result<(), String> {
     let project_path = Path::new(project_name);
 
@@ -168,6 +301,8 @@ fn create_project(
         .map_err(|e| format!("Failed to create directories: {}", e))?;
     fs::create_dir_all(project_path.join("frontend/src/pages/auth"))
         .map_err(|e| format!("Failed to create directories: {}", e))?;
+    fs::create_dir_all(project_path.join("frontend/src/components"))
+        .map_err(|e| format!("Failed to create directories: {}", e))?;
     fs::create_dir_all(project_path.join("frontend/src/types"))
         .map_err(|e| format!("Failed to create directories: {}", e))?;
 
@@ -178,10 +313,24 @@ fn create_project(
     // === Backend files ===
 
     // Write Cargo.toml
-    let cargo_toml = templates::cargo_toml(package_name, description, author);
+    let mut cargo_toml = templates::cargo_toml(package_name, description, author);
+    if install.offline {
+        cargo_toml = templates::pin_cargo_toml(&cargo_toml);
+    }
     fs::write(project_path.join("Cargo.toml"), cargo_toml)
         .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
 
+    // Write .cargo/config.toml for offline builds or a crates.io mirror
+    if install.offline || install.registry.is_some() {
+        fs::create_dir_all(project_path.join(".cargo"))
+            .map_err(|e| format!("Failed to create directories: {}", e))?;
+        fs::write(
+            project_path.join(".cargo/config.toml"),
+            templates::cargo_config(install.offline, install.registry.as_deref()),
+        )
+        .map_err(|e| format!("Failed to write .cargo/config.toml: {}", e))?;
+    }
+
     // Write .gitignore
     fs::write(project_path.join(".gitignore"), templates::gitignore())
         .map_err(|e| format!("Failed to write .gitignore: {}", e))?;
@@ -340,10 +489,22 @@ fn create_project(
     // === Frontend files ===
 
     // Write frontend/package.json
-    let package_json = templates::package_json(project_name);
+    let mut package_json = templates::package_json(project_name);
+    if install.offline {
+        package_json = templates::pin_package_json(&package_json);
+    }
     fs::write(project_path.join("frontend/package.json"), package_json)
         .map_err(|e| format!("Failed to write frontend/package.json: {}", e))?;
 
+    // Write frontend/.npmrc
+    if let Some(registry) = &install.npm_registry {
+        fs::write(
+            project_path.join("frontend/.npmrc"),
+            templates::npmrc(registry),
+        )
+        .map_err(|e| format!("Failed to write frontend/.npmrc: {}", e))?;
+    }
+
     // Write frontend/vite.config.ts
     fs::write(
         project_path.join("frontend/vite.config.ts"),
@@ -399,6 +560,18 @@ fn create_project(
     )
     .map_err(|e| format!("Failed to write frontend/src/pages/Dashboard.tsx: {}", e))?;
 
+    // Write frontend/src/components/FlashMessages.tsx
+    fs::write(
+        project_path.join("frontend/src/components/FlashMessages.tsx"),
+        templates::flash_messages_component(),
+    )
+    .map_err(|e| {
+        format!(
+            "Failed to write frontend/src/components/FlashMessages.tsx: {}",
+            e
+        )
+    })?;
+
     // Write frontend/src/types/inertia-props.ts
     fs::write(
         project_path.join("frontend/src/types/inertia-props.ts"),
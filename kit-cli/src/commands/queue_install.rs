@@ -0,0 +1,95 @@
+//! queue:install command - Install the jobs table migration
+
+use console::style;
+use std::fs;
+use std::path::Path;
+
+use super::workflow_install::update_mod_file;
+use crate::templates;
+
+const JOBS_MIGRATION: &str = "m20240101_000005_create_jobs_table";
+
+pub fn run() {
+    let migrations_dir = Path::new("src/migrations");
+    let mod_file = migrations_dir.join("mod.rs");
+
+    if !Path::new("src").exists() {
+        eprintln!(
+            "{} Not in a Kit project root directory",
+            style("Error:").red().bold()
+        );
+        std::process::exit(1);
+    }
+
+    if !migrations_dir.exists() {
+        if let Err(e) = fs::create_dir_all(migrations_dir) {
+            eprintln!(
+                "{} Failed to create migrations directory: {}",
+                style("Error:").red().bold(),
+                e
+            );
+            std::process::exit(1);
+        }
+        println!("{} Created src/migrations/", style("✓").green());
+    }
+
+    let jobs_file = migrations_dir.join(format!("{}.rs", JOBS_MIGRATION));
+
+    if !jobs_file.exists() {
+        if let Err(e) = fs::write(&jobs_file, templates::create_jobs_migration()) {
+            eprintln!(
+                "{} Failed to write jobs migration: {}",
+                style("Error:").red().bold(),
+                e
+            );
+            std::process::exit(1);
+        }
+        println!("{} Created {}", style("✓").green(), jobs_file.display());
+    } else {
+        println!(
+            "{} {} already exists",
+            style("Info:").yellow().bold(),
+            jobs_file.display()
+        );
+    }
+
+    if mod_file.exists() {
+        if let Err(e) = update_mod_file(&mod_file, JOBS_MIGRATION) {
+            eprintln!(
+                "{} Failed to update mod.rs: {}",
+                style("Error:").red().bold(),
+                e
+            );
+            std::process::exit(1);
+        }
+        println!("{} Updated src/migrations/mod.rs", style("✓").green());
+    } else {
+        let mod_content = format!(
+            "pub use sea_orm_migration::prelude::*;\n\nmod {};\n\n\
+            pub struct Migrator;\n\n\
+            #[async_trait::async_trait]\n\
+            impl MigratorTrait for Migrator {{\n\
+                fn migrations() -> Vec<Box<dyn MigrationTrait>> {{\n\
+                    vec![\n\
+                        Box::new({}::Migration),\n\
+                    ]\n\
+                }}\n\
+            }}\n",
+            JOBS_MIGRATION, JOBS_MIGRATION
+        );
+
+        if let Err(e) = fs::write(&mod_file, mod_content) {
+            eprintln!(
+                "{} Failed to create mod.rs: {}",
+                style("Error:").red().bold(),
+                e
+            );
+            std::process::exit(1);
+        }
+        println!("{} Created src/migrations/mod.rs", style("✓").green());
+    }
+
+    println!();
+    println!("Queue migration installed.");
+    println!("Run `kit migrate` to apply it, then `kit queue:work` to process jobs.");
+}
@@ -0,0 +1,33 @@
+//! queue:work command - Run the queue worker daemon
+
+use console::style;
+use std::process::Command;
+
+pub fn run(worker_args: Vec<String>) {
+    println!("{} Starting queue worker...", style("->").cyan());
+    println!("{}", style("Press Ctrl+C to stop").dim());
+    println!();
+
+    let status = Command::new("cargo")
+        .args(["run", "--quiet", "--", "queue:work"])
+        .args(&worker_args)
+        .status()
+        .expect("Failed to execute cargo command");
+
+    if !status.success() {
+        if let Some(code) = status.code() {
+            if code != 130 {
+                eprintln!();
+                eprintln!(
+                    "{} Queue worker exited with error (code: {})",
+                    style("Error:").red().bold(),
+                    code
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
+    println!();
+    println!("{} Queue worker stopped.", style("->").cyan());
+}
@@ -0,0 +1,20 @@
+//! route:cache command - Write the route manifest used by redirect!
+
+use console::style;
+use std::process::Command;
+
+pub fn run(path: Option<String>) {
+    // Run cargo run -- route:cache (unified binary)
+    let mut command = Command::new("cargo");
+    command.args(["run", "--quiet", "--", "route:cache"]);
+    if let Some(path) = path {
+        command.args(["--path", &path]);
+    }
+    let status = command.status().expect("Failed to execute cargo command");
+
+    if !status.success() {
+        eprintln!();
+        eprintln!("{} Failed to cache routes", style("Error:").red().bold());
+        std::process::exit(1);
+    }
+}
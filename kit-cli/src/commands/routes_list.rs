@@ -0,0 +1,18 @@
+//! routes:list command - Display all registered routes
+
+use console::style;
+use std::process::Command;
+
+pub fn run() {
+    // Run cargo run -- routes:list (unified binary)
+    let status = Command::new("cargo")
+        .args(["run", "--quiet", "--", "routes:list"])
+        .status()
+        .expect("Failed to execute cargo command");
+
+    if !status.success() {
+        eprintln!();
+        eprintln!("{} Failed to list routes", style("Error:").red().bold());
+        std::process::exit(1);
+    }
+}
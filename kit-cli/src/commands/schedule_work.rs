@@ -3,7 +3,7 @@
 use console::style;
 use std::process::Command;
 
-pub fn run() {
+pub fn run(daemon_args: Vec<String>) {
     println!("{} Starting scheduler daemon...", style("->").cyan());
     println!(
         "{}",
@@ -14,6 +14,7 @@ pub fn run() {
     // Run cargo run -- schedule:work (unified binary)
     let status = Command::new("cargo")
         .args(["run", "--quiet", "--", "schedule:work"])
+        .args(&daemon_args)
         .status()
         .expect("Failed to execute cargo command");
 
@@ -0,0 +1,142 @@
+//! schema:snapshot command - Record the schema the migrations produce
+//!
+//! The migrations are run against a fresh, temporary SQLite database (or
+//! the empty database given with `--fresh-url`) and the resulting tables,
+//! columns and indexes are written to `schema.snapshot` in a canonical text
+//! form. Commit the file: `kit schema:verify` compares against it in CI, and
+//! schema changes show up in code review.
+
+use console::style;
+use sea_orm::Database;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use super::db_sync::{discover_indexes, discover_tables};
+
+/// The default snapshot file, relative to the project root
+pub const DEFAULT_PATH: &str = "schema.snapshot";
+
+const HEADER: &str =
+    "# Generated by `kit schema:snapshot` - checked by `kit schema:verify`, do not edit\n";
+
+pub fn run(path: Option<String>, fresh_url: Option<String>, database: bool) {
+    let path = path.unwrap_or_else(|| DEFAULT_PATH.to_string());
+    let schema = load_schema(fresh_url, database);
+
+    if let Err(e) = fs::write(&path, &schema) {
+        eprintln!(
+            "{} Failed to write {}: {}",
+            style("Error:").red().bold(),
+            path,
+            e
+        );
+        std::process::exit(1);
+    }
+    println!("{} Wrote {}", style("✓").green(), path);
+}
+
+/// The canonical schema, from the configured database or freshly migrated
+pub fn load_schema(fresh_url: Option<String>, database: bool) -> String {
+    if database {
+        dotenvy::dotenv().ok();
+        let Ok(database_url) = env::var("DATABASE_URL") else {
+            eprintln!(
+                "{} DATABASE_URL not set in .env",
+                style("Error:").red().bold()
+            );
+            std::process::exit(1);
+        };
+        return introspect(&database_url);
+    }
+
+    if !Path::new("src/migrations").exists() {
+        eprintln!(
+            "{} No migrations directory found at src/migrations",
+            style("Error:").red().bold()
+        );
+        std::process::exit(1);
+    }
+
+    let temp_file = env::temp_dir().join(format!("kit-schema-{}.db", std::process::id()));
+    let database_url = match &fresh_url {
+        Some(url) => url.clone(),
+        None => {
+            let _ = fs::remove_file(&temp_file);
+            format!("sqlite://{}", temp_file.display())
+        }
+    };
+
+    println!(
+        "{} Running migrations against a fresh database...",
+        style("→").cyan()
+    );
+    let status = Command::new("cargo")
+        .args(["run", "--quiet", "--", "migrate"])
+        .env("DATABASE_URL", &database_url)
+        .status()
+        .expect("Failed to execute cargo command");
+    if !status.success() {
+        eprintln!("{} Migration failed", style("Error:").red().bold());
+        std::process::exit(1);
+    }
+
+    let schema = introspect(&database_url);
+    if fresh_url.is_none() {
+        let _ = fs::remove_file(&temp_file);
+    }
+    schema
+}
+
+/// Read the schema of `database_url` in the canonical snapshot form
+///
+/// Tables are sorted by name and indexes by name; columns keep their
+/// position, so a column added in a different order is a difference too.
+fn introspect(database_url: &str) -> String {
+    let is_sqlite = database_url.starts_with("sqlite");
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let db = match Database::connect(database_url).await {
+            Ok(db) => db,
+            Err(e) => {
+                eprintln!(
+                    "{} Failed to connect to database: {}",
+                    style("Error:").red().bold(),
+                    e
+                );
+                std::process::exit(1);
+            }
+        };
+
+        let mut tables = discover_tables(&db, is_sqlite).await;
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut schema = HEADER.to_string();
+        for table in tables {
+            schema.push_str(&format!("\ntable {}\n", table.name));
+            for column in &table.columns {
+                schema.push_str(&format!(
+                    "  column {} {}{}{}\n",
+                    column.name,
+                    column.col_type.to_uppercase(),
+                    if column.is_nullable { "" } else { " not null" },
+                    if column.is_primary_key {
+                        " primary key"
+                    } else {
+                        ""
+                    },
+                ));
+            }
+            for index in discover_indexes(&db, is_sqlite, &table.name).await {
+                schema.push_str(&format!(
+                    "  index {}{} ({})\n",
+                    index.name,
+                    if index.is_unique { " unique" } else { "" },
+                    index.columns.join(", ")
+                ));
+            }
+        }
+        schema
+    })
+}
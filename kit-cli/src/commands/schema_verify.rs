@@ -0,0 +1,77 @@
+//! schema:verify command - Fail when the schema drifts from the snapshot
+//!
+//! By default the migrations are run against a fresh database, which
+//! catches migrations that were edited after the snapshot was taken or that
+//! only work in the order they happened to run locally. With `--database`
+//! the configured database (e.g. production) is checked instead, catching
+//! manual changes and migrations that were never run.
+
+use console::style;
+use std::fs;
+
+use super::schema_snapshot::{load_schema, DEFAULT_PATH};
+
+pub fn run(path: Option<String>, fresh_url: Option<String>, database: bool) {
+    let path = path.unwrap_or_else(|| DEFAULT_PATH.to_string());
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(e) => {
+            eprintln!(
+                "{} Failed to read {}: {}",
+                style("Error:").red().bold(),
+                path,
+                e
+            );
+            eprintln!("{}", style("Run 'kit schema:snapshot' to create it.").dim());
+            std::process::exit(1);
+        }
+    };
+
+    let actual = load_schema(fresh_url, database);
+    if actual == expected {
+        println!("{} Schema matches {}", style("✓").green(), path);
+        return;
+    }
+
+    let expected_lines = qualified_lines(&expected);
+    let actual_lines = qualified_lines(&actual);
+    eprintln!(
+        "{} Schema differs from {}:",
+        style("Error:").red().bold(),
+        path
+    );
+    for line in expected_lines.iter().filter(|l| !actual_lines.contains(l)) {
+        eprintln!("  {} {}", style("-").red(), line);
+    }
+    for line in actual_lines.iter().filter(|l| !expected_lines.contains(l)) {
+        eprintln!("  {} {}", style("+").green(), line);
+    }
+    if expected_lines.iter().all(|l| actual_lines.contains(l))
+        && actual_lines.iter().all(|l| expected_lines.contains(l))
+    {
+        eprintln!("  Columns are in a different order");
+    }
+    eprintln!(
+        "{}",
+        style("If the change is intended, run 'kit schema:snapshot' and commit the result.").dim()
+    );
+    std::process::exit(1);
+}
+
+/// Snapshot lines prefixed with their table, e.g. `posts: column id INTEGER`
+fn qualified_lines(schema: &str) -> Vec<String> {
+    let mut table = "";
+    let mut lines = Vec::new();
+    for line in schema.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix("table ") {
+            table = name;
+            lines.push(line.to_string());
+        } else {
+            lines.push(format!("{}: {}", table, line.trim()));
+        }
+    }
+    lines
+}
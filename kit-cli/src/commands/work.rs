@@ -0,0 +1,53 @@
+//! work command - Run the scheduler and worker pools from supervisor.toml
+
+use console::style;
+use std::process::Command;
+
+pub fn run(supervise: bool, json: bool, config: Option<String>, daemon_args: Vec<String>) {
+    if supervise {
+        println!("{} Starting supervised workers...", style("->").cyan());
+    } else {
+        println!("{} Starting workers...", style("->").cyan());
+    }
+    println!("{}", style("Press Ctrl+C to stop").dim());
+    println!();
+
+    let mut args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--".to_string(),
+        "work".to_string(),
+    ];
+    if supervise {
+        args.push("--supervise".to_string());
+    }
+    if json {
+        args.push("--json".to_string());
+    }
+    if let Some(config) = config {
+        args.push(format!("--config={}", config));
+    }
+    args.extend(daemon_args);
+
+    let status = Command::new("cargo")
+        .args(&args)
+        .status()
+        .expect("Failed to execute cargo command");
+
+    if !status.success() {
+        if let Some(code) = status.code() {
+            if code != 130 {
+                eprintln!();
+                eprintln!(
+                    "{} Workers exited with error (code: {})",
+                    style("Error:").red().bold(),
+                    code
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
+    println!();
+    println!("{} Workers stopped.", style("->").cyan());
+}
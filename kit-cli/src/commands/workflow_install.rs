@@ -117,7 +117,7 @@ pub fn run() {
     println!("Run `kit migrate` to apply them.");
 }
 
-fn update_mod_file(mod_file: &Path, module_name: &str) -> Result<(), String> {
+pub(crate) fn update_mod_file(mod_file: &Path, module_name: &str) -> Result<(), String> {
     let content = fs::read_to_string(mod_file).map_err(|e| format!("Failed to read mod.rs: {}", e))?;
 
     let mod_decl = format!("mod {};", module_name);
@@ -3,13 +3,14 @@
 use console::style;
 use std::process::Command;
 
-pub fn run() {
+pub fn run(daemon_args: Vec<String>) {
     println!("{} Starting workflow worker...", style("->").cyan());
     println!("{}", style("Press Ctrl+C to stop").dim());
     println!();
 
     let status = Command::new("cargo")
         .args(["run", "--quiet", "--", "workflow:work"])
+        .args(&daemon_args)
         .status()
         .expect("Failed to execute cargo command");
 
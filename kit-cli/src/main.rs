@@ -1,5 +1,7 @@
 mod commands;
+mod project;
 mod templates;
+mod wiring;
 
 use clap::{Parser, Subcommand};
 
@@ -25,6 +27,26 @@ enum Commands {
         /// Skip git initialization
         #[arg(long)]
         no_git: bool,
+
+        /// Pin exact dependency versions from the bundled lockfile and keep Cargo offline
+        #[arg(long)]
+        offline: bool,
+
+        /// Run npm install in frontend/ after creating the project (needs Node.js and network)
+        #[arg(long, conflicts_with = "offline")]
+        npm_install: bool,
+
+        /// Use this crates.io mirror (e.g. sparse+https://crates.example.com/index/)
+        #[arg(long, value_name = "URL")]
+        registry: Option<String>,
+
+        /// Use this npm registry (written to frontend/.npmrc)
+        #[arg(long, value_name = "URL")]
+        npm_registry: Option<String>,
+
+        /// Add the project as a member of the enclosing Cargo workspace
+        #[arg(long)]
+        workspace: bool,
     },
     /// Start the development servers (backend + frontend)
     Serve {
@@ -66,12 +88,20 @@ enum Commands {
     MakeMiddleware {
         /// Name of the middleware (e.g., Auth, RateLimit)
         name: String,
+
+        /// Register the middleware globally in src/bootstrap.rs
+        #[arg(long)]
+        global: bool,
     },
     /// Generate a new controller
     #[command(name = "make:controller")]
     MakeController {
         /// Name of the controller (e.g., users, user_profile)
         name: String,
+
+        /// Add a GET route for the controller to src/routes.rs (e.g., /users)
+        #[arg(long)]
+        route: Option<String>,
     },
     /// Generate a new action
     #[command(name = "make:action")]
@@ -103,8 +133,15 @@ enum Commands {
         /// Name of the task (e.g., CleanupLogs, SendReminders)
         name: String,
     },
+    /// Scaffold Stripe subscription billing
+    #[command(name = "make:billing")]
+    MakeBilling,
     /// Run all pending database migrations
-    Migrate,
+    Migrate {
+        /// Check pending migrations for operations unsafe on a live database first
+        #[arg(long)]
+        check_safety: bool,
+    },
     /// Rollback the last database migration(s)
     #[command(name = "migrate:rollback")]
     MigrateRollback {
@@ -128,6 +165,51 @@ enum Commands {
         #[arg(long)]
         regenerate_models: bool,
     },
+    /// Generate a migration from the difference between the models and the database
+    #[command(name = "db:diff")]
+    DbDiff {
+        /// Name of the migration (default: schema_diff)
+        name: Option<String>,
+        /// Compare with the schema saved by the previous diff instead of the database
+        #[arg(long)]
+        from_snapshot: bool,
+        /// Print the changes without writing a migration
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write the schema the migrations produce to schema.snapshot
+    #[command(name = "schema:snapshot")]
+    SchemaSnapshot {
+        /// Snapshot file (default: schema.snapshot)
+        #[arg(long)]
+        path: Option<String>,
+        /// Empty database to migrate (default: a temporary SQLite database)
+        #[arg(long)]
+        fresh_url: Option<String>,
+        /// Read the configured DATABASE_URL instead of migrating a fresh database
+        #[arg(long)]
+        database: bool,
+    },
+    /// Fail if the schema the migrations produce differs from schema.snapshot
+    #[command(name = "schema:verify")]
+    SchemaVerify {
+        /// Snapshot file (default: schema.snapshot)
+        #[arg(long)]
+        path: Option<String>,
+        /// Empty database to migrate (default: a temporary SQLite database)
+        #[arg(long)]
+        fresh_url: Option<String>,
+        /// Check the configured DATABASE_URL instead of migrating a fresh database
+        #[arg(long)]
+        database: bool,
+    },
+    /// Add keys read by the code to .env.example and report unread keys in .env
+    #[command(name = "env:sync")]
+    EnvSync {
+        /// Print the missing keys without changing .env.example
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Generate a production-ready Dockerfile
     #[command(name = "docker:init")]
     DockerInit,
@@ -146,28 +228,192 @@ enum Commands {
     ScheduleRun,
     /// Start the scheduler daemon (runs continuously, checks every minute)
     #[command(name = "schedule:work")]
-    ScheduleWork,
+    ScheduleWork {
+        /// Stop after processing this many jobs
+        #[arg(long)]
+        max_jobs: Option<u64>,
+        /// Stop after running for this many seconds
+        #[arg(long)]
+        max_time: Option<u64>,
+        /// Stop when resident memory exceeds this many megabytes
+        #[arg(long)]
+        memory: Option<u64>,
+    },
     /// List all registered scheduled tasks
     #[command(name = "schedule:list")]
     ScheduleList,
+    /// List all registered routes with their names and middleware
+    #[command(name = "routes:list")]
+    RoutesList,
+    /// Write .kit/routes.json so redirect! can check every registered route
+    #[command(name = "route:cache")]
+    RouteCache {
+        /// Where to write the manifest (defaults to .kit/routes.json)
+        #[arg(long)]
+        path: Option<String>,
+    },
     /// Start the workflow worker daemon
     #[command(name = "workflow:work")]
-    WorkflowWork,
+    WorkflowWork {
+        /// Stop after processing this many jobs
+        #[arg(long)]
+        max_jobs: Option<u64>,
+        /// Stop after running for this many seconds
+        #[arg(long)]
+        max_time: Option<u64>,
+        /// Stop when resident memory exceeds this many megabytes
+        #[arg(long)]
+        memory: Option<u64>,
+    },
+    /// Start the queue worker daemon
+    #[command(name = "queue:work")]
+    QueueWork {
+        /// Queues to work, highest priority first (comma separated)
+        #[arg(long)]
+        queue: Option<String>,
+        /// Stop after processing this many jobs
+        #[arg(long)]
+        max_jobs: Option<u64>,
+        /// Stop after running for this many seconds
+        #[arg(long)]
+        max_time: Option<u64>,
+        /// Stop when resident memory exceeds this many megabytes
+        #[arg(long)]
+        memory: Option<u64>,
+    },
+    /// Run the scheduler and worker pools configured in supervisor.toml
+    Work {
+        /// Restart crashed workers and show a live status dashboard
+        #[arg(long)]
+        supervise: bool,
+        /// Print status as JSON lines instead of the dashboard
+        #[arg(long)]
+        json: bool,
+        /// Path to the supervisor configuration file (default: supervisor.toml)
+        #[arg(long)]
+        config: Option<String>,
+        /// Stop after processing this many jobs
+        #[arg(long)]
+        max_jobs: Option<u64>,
+        /// Stop after running for this many seconds
+        #[arg(long)]
+        max_time: Option<u64>,
+        /// Stop when resident memory exceeds this many megabytes
+        #[arg(long)]
+        memory: Option<u64>,
+    },
+    /// Benchmark a route in-process and report latency percentiles
+    Bench {
+        /// Route path (e.g. /users/1) or route name (e.g. users.index)
+        route: String,
+        /// HTTP method
+        #[arg(long, default_value = "GET")]
+        method: String,
+        /// Number of measured requests
+        #[arg(long, short = 'n', default_value_t = 1000)]
+        requests: usize,
+        /// Number of requests in flight at once
+        #[arg(long, short = 'c', default_value_t = 10)]
+        concurrency: usize,
+        /// Request header as "Name: value" (repeatable)
+        #[arg(long = "header", short = 'H')]
+        headers: Vec<String>,
+        /// Request body
+        #[arg(long)]
+        body: Option<String>,
+        /// Use the configured DATABASE_URL instead of an in-memory SQLite database
+        #[arg(long)]
+        env_db: bool,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+        /// Build without optimizations (faster to compile, slower to run)
+        #[arg(long)]
+        debug: bool,
+    },
+    /// Ask running worker daemons to stop after their current job
+    #[command(name = "daemon:stop")]
+    DaemonStop,
     /// Install workflow migrations
     #[command(name = "workflow:install")]
     WorkflowInstall,
+    /// Install the jobs table migration for the database queue
+    #[command(name = "queue:install")]
+    QueueInstall,
+    /// Install the activities table migration for activity feeds
+    #[command(name = "activity:install")]
+    ActivityInstall,
+}
+
+/// Run the command from the Kit project root, which may be a workspace member
+fn enter_project_root() {
+    let Ok(current_dir) = std::env::current_dir() else {
+        return;
+    };
+    // Commands report a missing project themselves
+    let Ok(root) = project::find_root(&current_dir) else {
+        return;
+    };
+    if root == current_dir {
+        return;
+    }
+
+    let display = root.strip_prefix(&current_dir).unwrap_or(&root);
+    println!(
+        "{}",
+        console::style(format!("Using Kit project in {}", display.display())).dim()
+    );
+    if let Err(e) = std::env::set_current_dir(&root) {
+        eprintln!(
+            "{} Failed to enter {}: {}",
+            console::style("Error:").red().bold(),
+            root.display(),
+            e
+        );
+        std::process::exit(1);
+    }
 }
 
 fn main() {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+
+    if !matches!(cli.command, Commands::New { .. }) {
+        // Paths given on the command line stay relative to where kit was run
+        if let Commands::GenerateTypes {
+            output: Some(output),
+            ..
+        } = &mut cli.command
+        {
+            if let Ok(absolute) = std::path::absolute(&*output) {
+                *output = absolute.to_string_lossy().into_owned();
+            }
+        }
+        enter_project_root();
+    }
 
     match cli.command {
         Commands::New {
             name,
             no_interaction,
             no_git,
+            offline,
+            npm_install,
+            registry,
+            npm_registry,
+            workspace,
         } => {
-            commands::new::run(name, no_interaction, no_git);
+            commands::new::run(
+                name,
+                no_interaction,
+                no_git,
+                workspace,
+                commands::new::InstallOptions {
+                    offline,
+                    npm_install,
+                    registry,
+                    npm_registry,
+                },
+            );
         }
         Commands::Serve {
             port,
@@ -184,11 +430,11 @@ fn main() {
         Commands::GenerateTypes { output, watch } => {
             commands::generate_types::run(output, watch);
         }
-        Commands::MakeMiddleware { name } => {
-            commands::make_middleware::run(name);
+        Commands::MakeMiddleware { name, global } => {
+            commands::make_middleware::run(name, global);
         }
-        Commands::MakeController { name } => {
-            commands::make_controller::run(name);
+        Commands::MakeController { name, route } => {
+            commands::make_controller::run(name, route);
         }
         Commands::MakeAction { name } => {
             commands::make_action::run(name);
@@ -205,8 +451,11 @@ fn main() {
         Commands::MakeTask { name } => {
             commands::make_task::run(name);
         }
-        Commands::Migrate => {
-            commands::migrate::run();
+        Commands::MakeBilling => {
+            commands::make_billing::run();
+        }
+        Commands::Migrate { check_safety } => {
+            commands::migrate::run(check_safety);
         }
         Commands::MigrateRollback { step } => {
             commands::migrate_rollback::run(step);
@@ -223,6 +472,30 @@ fn main() {
         } => {
             commands::db_sync::run(skip_migrations, regenerate_models);
         }
+        Commands::DbDiff {
+            name,
+            from_snapshot,
+            dry_run,
+        } => {
+            commands::db_diff::run(name, from_snapshot, dry_run);
+        }
+        Commands::SchemaSnapshot {
+            path,
+            fresh_url,
+            database,
+        } => {
+            commands::schema_snapshot::run(path, fresh_url, database);
+        }
+        Commands::SchemaVerify {
+            path,
+            fresh_url,
+            database,
+        } => {
+            commands::schema_verify::run(path, fresh_url, database);
+        }
+        Commands::EnvSync { dry_run } => {
+            commands::env_sync::run(dry_run);
+        }
         Commands::DockerInit => {
             commands::docker_init::run();
         }
@@ -235,17 +508,111 @@ fn main() {
         Commands::ScheduleRun => {
             commands::schedule_run::run();
         }
-        Commands::ScheduleWork => {
-            commands::schedule_work::run();
+        Commands::ScheduleWork {
+            max_jobs,
+            max_time,
+            memory,
+        } => {
+            commands::schedule_work::run(daemon_args(max_jobs, max_time, memory));
         }
         Commands::ScheduleList => {
             commands::schedule_list::run();
         }
-        Commands::WorkflowWork => {
-            commands::workflow_work::run();
+        Commands::RoutesList => {
+            commands::routes_list::run();
+        }
+        Commands::RouteCache { path } => {
+            commands::route_cache::run(path);
+        }
+        Commands::WorkflowWork {
+            max_jobs,
+            max_time,
+            memory,
+        } => {
+            commands::workflow_work::run(daemon_args(max_jobs, max_time, memory));
+        }
+        Commands::QueueWork {
+            queue,
+            max_jobs,
+            max_time,
+            memory,
+        } => {
+            let mut args = daemon_args(max_jobs, max_time, memory);
+            if let Some(queue) = queue {
+                args.push(format!("--queue={}", queue));
+            }
+            commands::queue_work::run(args);
+        }
+        Commands::Work {
+            supervise,
+            json,
+            config,
+            max_jobs,
+            max_time,
+            memory,
+        } => {
+            commands::work::run(
+                supervise,
+                json,
+                config,
+                daemon_args(max_jobs, max_time, memory),
+            );
+        }
+        Commands::Bench {
+            route,
+            method,
+            requests,
+            concurrency,
+            headers,
+            body,
+            env_db,
+            json,
+            debug,
+        } => {
+            let mut args = vec![
+                route,
+                format!("--method={}", method),
+                format!("--requests={}", requests),
+                format!("--concurrency={}", concurrency),
+            ];
+            args.extend(headers.into_iter().map(|h| format!("--header={}", h)));
+            if let Some(body) = body {
+                args.push(format!("--body={}", body));
+            }
+            if env_db {
+                args.push("--env-db".to_string());
+            }
+            if json {
+                args.push("--json".to_string());
+            }
+            commands::bench::run(args, !debug);
+        }
+        Commands::DaemonStop => {
+            commands::daemon_stop::run();
         }
         Commands::WorkflowInstall => {
             commands::workflow_install::run();
         }
+        Commands::QueueInstall => {
+            commands::queue_install::run();
+        }
+        Commands::ActivityInstall => {
+            commands::activity_install::run();
+        }
+    }
+}
+
+/// Build the daemon flags forwarded to the app binary
+fn daemon_args(max_jobs: Option<u64>, max_time: Option<u64>, memory: Option<u64>) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(max_jobs) = max_jobs {
+        args.push(format!("--max-jobs={}", max_jobs));
+    }
+    if let Some(max_time) = max_time {
+        args.push(format!("--max-time={}", max_time));
+    }
+    if let Some(memory) = memory {
+        args.push(format!("--memory={}", memory));
     }
+    args
 }
@@ -0,0 +1,170 @@
+//! Locating the Kit project and Cargo workspace roots
+//!
+//! Commands work relative to the project root (the crate that depends on
+//! `kit-rs`). They can be run from any subdirectory of the project, or from
+//! the root of a Cargo workspace that contains it, in which case the project
+//! is found through `cargo metadata`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Find the Kit project that `start` belongs to
+pub fn find_root(start: &Path) -> Result<PathBuf, String> {
+    for dir in start.ancestors() {
+        if let Some(manifest) = read_manifest(&dir.join("Cargo.toml")) {
+            if depends_on_kit(&manifest) {
+                return Ok(dir.to_path_buf());
+            }
+        }
+    }
+
+    let roots = workspace_kit_packages(start)?;
+    match roots.as_slice() {
+        [root] => Ok(root.clone()),
+        [] => Err("No Kit project found (no crate depends on kit-rs)".into()),
+        _ => Err(format!(
+            "Found several Kit projects in this workspace, run the command from one of them:\n{}",
+            roots
+                .iter()
+                .map(|root| format!("  {}", root.display()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+    }
+}
+
+/// Find the root of the Cargo workspace that `start` is in
+pub fn find_workspace_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| {
+            read_manifest(&dir.join("Cargo.toml"))
+                .is_some_and(|manifest| manifest.contains_key("workspace"))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Add `member` (relative to the workspace root) to `workspace.members`
+///
+/// Does nothing if an existing entry, including a `dir/*` glob, already
+/// covers the member.
+pub fn add_workspace_member(workspace_root: &Path, member: &str) -> Result<(), String> {
+    let manifest_path = workspace_root.join("Cargo.toml");
+    let content = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read workspace Cargo.toml: {}", e))?;
+    let manifest: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse workspace Cargo.toml: {}", e))?;
+
+    let members = manifest
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array());
+    let covered = members.is_some_and(|members| {
+        members
+            .iter()
+            .filter_map(|m| m.as_str())
+            .any(|pattern| member_matches(pattern, member))
+    });
+    if covered {
+        return Ok(());
+    }
+
+    fs::write(&manifest_path, insert_member(&content, member)?)
+        .map_err(|e| format!("Failed to write workspace Cargo.toml: {}", e))
+}
+
+fn read_manifest(path: &Path) -> Option<toml::Table> {
+    fs::read_to_string(path).ok()?.parse().ok()
+}
+
+fn depends_on_kit(manifest: &toml::Table) -> bool {
+    let Some(dependencies) = manifest.get("dependencies").and_then(|d| d.as_table()) else {
+        return false;
+    };
+    dependencies.iter().any(|(name, spec)| {
+        name == "kit-rs" || spec.get("package").and_then(|p| p.as_str()) == Some("kit-rs")
+    })
+}
+
+/// Directories of all workspace members that depend on kit-rs
+fn workspace_kit_packages(start: &Path) -> Result<Vec<PathBuf>, String> {
+    let output = Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(start)
+        .output()
+        .map_err(|e| format!("Failed to run cargo metadata: {}", e))?;
+    if !output.status.success() {
+        return Err("No Kit project found (no Cargo.toml in this directory or its parents)".into());
+    }
+
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse cargo metadata: {}", e))?;
+    let packages = metadata["packages"].as_array().cloned().unwrap_or_default();
+
+    Ok(packages
+        .iter()
+        .filter(|package| {
+            package["dependencies"]
+                .as_array()
+                .is_some_and(|deps| deps.iter().any(|dep| dep["name"] == "kit-rs"))
+        })
+        .filter_map(|package| package["manifest_path"].as_str())
+        .filter_map(|manifest| Path::new(manifest).parent().map(Path::to_path_buf))
+        .collect())
+}
+
+fn member_matches(pattern: &str, member: &str) -> bool {
+    let pattern = pattern.trim_end_matches('/');
+    match pattern.strip_suffix("/*") {
+        Some(dir) => Path::new(member).parent() == Some(Path::new(dir)),
+        None => pattern == member,
+    }
+}
+
+/// Insert `member` into the `members` array of the `[workspace]` table,
+/// keeping the existing formatting
+fn insert_member(content: &str, member: &str) -> Result<String, String> {
+    let entry = format!("\"{}\"", member);
+    let workspace = content
+        .find("[workspace]\n")
+        .ok_or("Could not find [workspace] in the workspace Cargo.toml")?;
+    let section_end = content[workspace + 1..]
+        .find("\n[")
+        .map(|i| workspace + 1 + i)
+        .unwrap_or(content.len());
+
+    let members = regex::Regex::new(r"(?m)^members\s*=\s*\[").unwrap();
+    let Some(found) = members.find(&content[workspace..section_end]) else {
+        let insert_at = workspace + "[workspace]\n".len();
+        return Ok(format!(
+            "{}members = [{}]\n{}",
+            &content[..insert_at],
+            entry,
+            &content[insert_at..]
+        ));
+    };
+
+    let open = workspace + found.end();
+    let close = open
+        + content[open..]
+            .find(']')
+            .ok_or("Unterminated workspace.members in Cargo.toml")?;
+    let existing = content[open..close].trim_end();
+
+    let list = if existing.trim().is_empty() {
+        entry
+    } else if existing.contains('\n') {
+        // One member per line: match the trailing comma style
+        let (existing, comma) = match existing.strip_suffix(',') {
+            Some(existing) => (existing, ","),
+            None => (existing, ""),
+        };
+        format!("{},\n    {}{}\n", existing, entry, comma)
+    } else {
+        format!("{}, {}", existing.trim_end_matches(','), entry)
+    };
+
+    Ok(format!("{}{}{}", &content[..open], list, &content[close..]))
+}
@@ -1,14 +1,18 @@
 //! example_action action
 
-use kit::injectable;
+use kit::{async_trait, injectable, Action, FrameworkError};
 
 #[injectable]
 pub struct ExampleAction {
     // Dependencies injected via container
 }
 
-impl ExampleAction {
-    pub fn execute(&self) -> String {
-        "Hello from ExampleAction!".to_string()
+#[async_trait]
+impl Action for ExampleAction {
+    type Input = String;
+    type Output = String;
+
+    async fn handle(&self, name: String) -> Result<String, FrameworkError> {
+        Ok(format!("Hello from ExampleAction, {}!", name))
     }
 }
@@ -0,0 +1,10 @@
+
+impl kit::Billable for Model {
+    fn billable_id(&self) -> i64 {
+        self.id
+    }
+
+    fn billing_email(&self) -> Option<String> {
+        Some(self.email.clone())
+    }
+}
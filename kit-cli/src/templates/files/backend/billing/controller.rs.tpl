@@ -0,0 +1,12 @@
+//! Billing controller
+
+use kit::{billing, handler, Request, Response};
+
+/// Stripe webhook
+///
+/// Keeps the subscriptions table in sync. Every event is also dispatched as
+/// a `kit::StripeEvent`; handle the others with a `#[listener]`.
+#[handler]
+pub async fn webhook(req: Request) -> Response {
+    billing::handle_webhook(req).await
+}
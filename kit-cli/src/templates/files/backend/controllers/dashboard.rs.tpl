@@ -3,7 +3,7 @@
 use kit::{handler, inertia_response, Auth, InertiaProps, Model, Request, Response};
 use serde::Serialize;
 
-use crate::models::user::{Entity as UserEntity, User};
+use crate::models::user::Entity as UserEntity;
 
 #[derive(Serialize)]
 pub struct UserInfo {
@@ -8,7 +8,10 @@ pub struct LoggingMiddleware;
 #[async_trait]
 impl Middleware for LoggingMiddleware {
     async fn handle(&self, request: Request, next: Next) -> Response {
-        println!("--> {} {}", request.method(), request.path());
+        let client = request
+            .client_ip()
+            .map_or_else(|| "-".to_string(), |ip| ip.to_string());
+        println!("--> {} {} from {}", request.method(), request.path(), client);
         let response = next(request).await;
         println!("<-- Request complete");
         response
@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Activities::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Activities::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Activities::ActorId).big_integer().null())
+                    .col(ColumnDef::new(Activities::Action).string().not_null())
+                    .col(ColumnDef::new(Activities::SubjectType).string().not_null())
+                    .col(ColumnDef::new(Activities::SubjectId).string().not_null())
+                    .col(ColumnDef::new(Activities::Properties).text().not_null())
+                    .col(
+                        ColumnDef::new(Activities::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_activities_actor_id")
+                    .table(Activities::Table)
+                    .col(Activities::ActorId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_activities_subject")
+                    .table(Activities::Table)
+                    .col(Activities::SubjectType)
+                    .col(Activities::SubjectId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_activities_created_at")
+                    .table(Activities::Table)
+                    .col(Activities::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Activities::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Activities {
+    Table,
+    Id,
+    ActorId,
+    Action,
+    SubjectType,
+    SubjectId,
+    Properties,
+    CreatedAt,
+}
@@ -0,0 +1,127 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BillingCustomers::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BillingCustomers::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BillingCustomers::UserId)
+                            .big_integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BillingCustomers::StripeId)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BillingCustomers::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Subscriptions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Subscriptions::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Subscriptions::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(Subscriptions::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(Subscriptions::StripeId)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Subscriptions::StripeStatus).string().not_null())
+                    .col(ColumnDef::new(Subscriptions::StripePrice).string().null())
+                    .col(ColumnDef::new(Subscriptions::TrialEndsAt).timestamp().null())
+                    .col(ColumnDef::new(Subscriptions::EndsAt).timestamp().null())
+                    .col(
+                        ColumnDef::new(Subscriptions::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Subscriptions::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_subscriptions_user_id_name")
+                    .table(Subscriptions::Table)
+                    .col(Subscriptions::UserId)
+                    .col(Subscriptions::Name)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Subscriptions::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(BillingCustomers::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BillingCustomers {
+    Table,
+    Id,
+    UserId,
+    StripeId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Subscriptions {
+    Table,
+    Id,
+    UserId,
+    Name,
+    StripeId,
+    StripeStatus,
+    StripePrice,
+    TrialEndsAt,
+    EndsAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Jobs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Jobs::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Jobs::Queue).string().not_null())
+                    .col(ColumnDef::new(Jobs::Name).string().not_null())
+                    .col(ColumnDef::new(Jobs::Payload).text().not_null())
+                    .col(ColumnDef::new(Jobs::Attempts).integer().not_null())
+                    .col(ColumnDef::new(Jobs::MaxAttempts).integer().not_null())
+                    .col(ColumnDef::new(Jobs::AvailableAt).timestamp().not_null())
+                    .col(ColumnDef::new(Jobs::ReservedUntil).timestamp().null())
+                    .col(ColumnDef::new(Jobs::FailedAt).timestamp().null())
+                    .col(ColumnDef::new(Jobs::Error).text().null())
+                    .col(
+                        ColumnDef::new(Jobs::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_jobs_queue_available_at")
+                    .table(Jobs::Table)
+                    .col(Jobs::Queue)
+                    .col(Jobs::AvailableAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Jobs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Jobs {
+    Table,
+    Id,
+    Queue,
+    Name,
+    Payload,
+    Attempts,
+    MaxAttempts,
+    AvailableAt,
+    ReservedUntil,
+    FailedAt,
+    Error,
+    CreatedAt,
+}
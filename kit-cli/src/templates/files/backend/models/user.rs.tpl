@@ -1,9 +1,12 @@
 //! User model
 
+use kit::auth::Authenticatable;
 use kit::database::{Model as DatabaseModel, ModelMut, QueryBuilder};
+use kit::Auth;
 use sea_orm::entity::prelude::*;
 use sea_orm::Set;
 use serde::Serialize;
+use std::any::Any;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
 #[sea_orm(table_name = "users")]
@@ -34,7 +37,7 @@ pub type User = Model;
 impl Model {
     /// Start a query builder
     pub fn query() -> QueryBuilder<Entity> {
-        QueryBuilder::new()
+        QueryBuilder::new().on_connection(Entity::connection_name())
     }
 
     /// Find a user by their email address
@@ -47,7 +50,7 @@ impl Model {
 
     /// Verify the user's password
     pub fn verify_password(&self, password: &str) -> Result<bool, kit::FrameworkError> {
-        kit::hashing::verify(password, &self.password)
+        Auth::verify_password(self, password)
     }
 
     /// Create a new user with a hashed password
@@ -56,7 +59,7 @@ impl Model {
         email: impl Into<String>,
         password: &str,
     ) -> Result<Self, kit::FrameworkError> {
-        let hashed = kit::hashing::hash(password)?;
+        let hashed = Auth::hash_password(password)?;
 
         let model = ActiveModel {
             name: Set(name.into()),
@@ -79,3 +82,17 @@ impl Model {
         Ok(())
     }
 }
+
+impl Authenticatable for Model {
+    fn auth_identifier(&self) -> i64 {
+        self.id
+    }
+
+    fn auth_password(&self) -> Option<&str> {
+        Some(&self.password)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
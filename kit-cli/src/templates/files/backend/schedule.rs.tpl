@@ -64,7 +64,7 @@ use kit::Schedule;
 
 /// Register all scheduled tasks
 ///
-/// Called by the schedule binary when starting the scheduler.
+/// Called by the `schedule:*` commands via `Application::schedule()`.
 pub fn register(schedule: &mut Schedule) {
     // Example: Register a trait-based task
     // schedule.add(
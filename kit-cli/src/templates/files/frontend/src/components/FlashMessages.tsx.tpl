@@ -0,0 +1,39 @@
+import { usePage } from '@inertiajs/react'
+import { useEffect, useState } from 'react'
+
+// Messages flashed with `redirect!(...).with_flash("success", "...")`
+type Flash = Record<string, string | undefined>
+
+const styles: Record<string, string> = {
+  success: 'bg-green-50 text-green-800 border-green-200',
+  error: 'bg-red-50 text-red-800 border-red-200',
+}
+
+export default function FlashMessages() {
+  const { flash = {} } = usePage<{ flash?: Flash }>().props
+  const [visible, setVisible] = useState<Flash>(flash)
+
+  useEffect(() => {
+    setVisible(flash)
+    const timer = setTimeout(() => setVisible({}), 5000)
+    return () => clearTimeout(timer)
+  }, [flash])
+
+  const messages = Object.entries(visible).filter(([, message]) => message)
+  if (messages.length === 0) {
+    return null
+  }
+
+  return (
+    <div className="fixed top-4 right-4 z-50 space-y-2">
+      {messages.map(([type, message]) => (
+        <div
+          key={type}
+          className={`border rounded-md px-4 py-3 shadow ${styles[type] ?? 'bg-white text-gray-800 border-gray-200'}`}
+        >
+          {message}
+        </div>
+      ))}
+    </div>
+  )
+}
@@ -1,4 +1,5 @@
 import { router } from '@inertiajs/react'
+import FlashMessages from '../components/FlashMessages'
 import type { DashboardProps } from '../types/inertia-props'
 
 export default function Dashboard({ user }: DashboardProps) {
@@ -8,6 +9,7 @@ export default function Dashboard({ user }: DashboardProps) {
 
   return (
     <div className="min-h-screen bg-gray-100">
+      <FlashMessages />
       <nav className="bg-white shadow">
         <div className="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8">
           <div className="flex justify-between h-16">
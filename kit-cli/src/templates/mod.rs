@@ -60,6 +60,28 @@ pub fn create_workflow_steps_migration() -> &'static str {
     include_str!("files/backend/migrations/create_workflow_steps_table.rs.tpl")
 }
 
+pub fn create_jobs_migration() -> &'static str {
+    include_str!("files/backend/migrations/create_jobs_table.rs.tpl")
+}
+
+pub fn create_billing_migration() -> &'static str {
+    include_str!("files/backend/migrations/create_billing_tables.rs.tpl")
+}
+
+pub fn create_activities_migration() -> &'static str {
+    include_str!("files/backend/migrations/create_activities_table.rs.tpl")
+}
+
+// Billing templates
+
+pub fn billing_controller_template() -> &'static str {
+    include_str!("files/backend/billing/controller.rs.tpl")
+}
+
+pub fn billable_impl_template() -> &'static str {
+    include_str!("files/backend/billing/billable.rs.tpl")
+}
+
 // Middleware templates
 
 pub fn middleware_mod() -> &'static str {
@@ -116,16 +138,21 @@ pub fn action_template(name: &str, struct_name: &str) -> String {
     format!(
         r#"//! {name} action
 
-use kit::injectable;
+use kit::{{async_trait, injectable, Action, FrameworkError}};
 
 #[injectable]
 pub struct {struct_name} {{
     // Dependencies injected via container
 }}
 
-impl {struct_name} {{
-    pub fn execute(&self) {{
+#[async_trait]
+impl Action for {struct_name} {{
+    type Input = ();
+    type Output = ();
+
+    async fn handle(&self, _input: ()) -> Result<(), FrameworkError> {{
         // TODO: Implement action logic
+        Ok(())
     }}
 }}
 "#,
@@ -265,6 +292,10 @@ pub fn dashboard_page() -> &'static str {
     include_str!("files/frontend/src/pages/Dashboard.tsx.tpl")
 }
 
+pub fn flash_messages_component() -> &'static str {
+    include_str!("files/frontend/src/components/FlashMessages.tsx.tpl")
+}
+
 // Auth backend templates
 
 pub fn auth_controller() -> &'static str {
@@ -307,6 +338,102 @@ pub fn env_example() -> &'static str {
     include_str!("files/root/env.example.tpl")
 }
 
+// Dependency pinning and registry templates (kit new --offline/--registry)
+
+/// Exact versions bundled with the CLI, as `(crates, npm)` tables
+fn pinned_versions() -> (toml::Table, toml::Table) {
+    let lock: toml::Table = include_str!("files/versions.lock")
+        .parse()
+        .expect("bundled versions.lock is valid TOML");
+    let table = |name: &str| {
+        lock.get(name)
+            .and_then(|v| v.as_table())
+            .cloned()
+            .unwrap_or_default()
+    };
+    (table("crates"), table("npm"))
+}
+
+/// Pin every dependency in a generated Cargo.toml to the bundled versions
+pub fn pin_cargo_toml(manifest: &str) -> String {
+    let (crates, _) = pinned_versions();
+    let simple = regex::Regex::new(r#"^([\w-]+) = "[^"]*"$"#).unwrap();
+    let detailed = regex::Regex::new(r#"version = "[^"]*""#).unwrap();
+
+    let mut pinned = String::new();
+    for line in manifest.lines() {
+        let key = line.split(" = ").next().unwrap_or_default();
+        let version = if key == "kit" {
+            Some(env!("CARGO_PKG_VERSION"))
+        } else {
+            crates.get(key).and_then(|v| v.as_str())
+        };
+
+        match version {
+            Some(version) if simple.is_match(line) => {
+                pinned.push_str(&format!("{} = \"={}\"", key, version));
+            }
+            Some(version) => {
+                let replacement = format!("version = \"={}\"", version);
+                pinned.push_str(&detailed.replace(line, replacement.as_str()));
+            }
+            None => pinned.push_str(line),
+        }
+        pinned.push('\n');
+    }
+    pinned
+}
+
+/// Pin every dependency in a generated package.json to the bundled versions
+pub fn pin_package_json(package_json: &str) -> String {
+    let (_, npm) = pinned_versions();
+    let dependency = regex::Regex::new(r#"^(\s*)"([^"]+)": "[^"]*"(,?)$"#).unwrap();
+
+    let mut pinned = String::new();
+    for line in package_json.lines() {
+        let caps = dependency.captures(line);
+        let version = caps
+            .as_ref()
+            .and_then(|caps| npm.get(&caps[2]))
+            .and_then(|v| v.as_str());
+        match (caps, version) {
+            (Some(caps), Some(version)) => pinned.push_str(&format!(
+                "{}\"{}\": \"{}\"{}",
+                &caps[1], &caps[2], version, &caps[3]
+            )),
+            _ => pinned.push_str(line),
+        }
+        pinned.push('\n');
+    }
+    pinned
+}
+
+/// Project-local `.cargo/config.toml` for offline builds and/or a crates.io mirror
+///
+/// `registry` accepts anything Cargo does for a registry source, such as a
+/// sparse index (`sparse+https://crates.example.com/index/`) or a git index URL.
+pub fn cargo_config(offline: bool, registry: Option<&str>) -> String {
+    let mut config = String::new();
+    if let Some(registry) = registry {
+        config.push_str(&format!(
+            "[source.crates-io]\nreplace-with = \"mirror\"\n\n[source.mirror]\nregistry = \"{}\"\n",
+            registry
+        ));
+    }
+    if offline {
+        if !config.is_empty() {
+            config.push('\n');
+        }
+        config.push_str("[net]\noffline = true\n");
+    }
+    config
+}
+
+/// `frontend/.npmrc` pointing npm at a custom registry
+pub fn npmrc(registry: &str) -> String {
+    format!("registry={}\n", registry)
+}
+
 // Entity generation templates for db:sync command
 
 /// Generate auto-generated entity file (regenerated on every sync)
@@ -396,6 +523,12 @@ pub type {struct_name} = Model;
 
 impl ActiveModelBehavior for ActiveModel {{}}
 
+// To store {struct_name} in a named connection (see DB_CONNECTIONS), use:
+// impl kit::database::Model for Entity {{
+//     fn connection_name() -> &'static str {{
+//         "analytics"
+//     }}
+// }}
 impl kit::database::Model for Entity {{}}
 impl kit::database::ModelMut for Entity {{}}
 
@@ -413,7 +546,7 @@ impl Model {{
     /// let record = {struct_name}::query().filter(Column::Id.eq(1)).first().await?;
     /// ```
     pub fn query() -> QueryBuilder<Entity> {{
-        QueryBuilder::new()
+        QueryBuilder::new().on_connection(<Entity as kit::database::Model>::connection_name())
     }}
 
     /// Create a new record builder
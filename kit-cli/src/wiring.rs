@@ -0,0 +1,177 @@
+//! Registering generated code in the application
+//!
+//! Generators can wire what they create into `src/bootstrap.rs` and
+//! `src/routes.rs`. The files are parsed with syn to find where the new line
+//! belongs and whether it is already there, then edited as text so the rest
+//! of the file keeps its formatting and comments.
+
+use proc_macro2::LineColumn;
+use std::fs;
+use std::path::Path;
+use syn::spanned::Spanned;
+use syn::{Expr, Item, Macro, MacroDelimiter, Stmt};
+
+/// Outcome of a registration
+#[derive(Debug, PartialEq, Eq)]
+pub enum Registration {
+    Added,
+    AlreadyRegistered,
+}
+
+/// Add `global_middleware!(<middleware>);` to `register()` in bootstrap.rs
+///
+/// The call goes after the last existing `global_middleware!`, or at the end
+/// of `register()` if there is none.
+pub fn register_global_middleware(
+    bootstrap: &Path,
+    middleware: &str,
+) -> Result<Registration, String> {
+    let content = read(bootstrap)?;
+    let file = parse(bootstrap, &content)?;
+
+    let register = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            Item::Fn(f) if f.sig.ident == "register" => Some(f),
+            _ => None,
+        })
+        .ok_or_else(|| format!("No `register` function found in {}", bootstrap.display()))?;
+
+    let mut last = None;
+    for stmt in &register.block.stmts {
+        let mac = match stmt {
+            Stmt::Macro(m) => &m.mac,
+            Stmt::Expr(Expr::Macro(m), _) => &m.mac,
+            _ => continue,
+        };
+        if !mac.path.is_ident("global_middleware") {
+            continue;
+        }
+        if normalize(&mac.tokens.to_string()) == normalize(middleware) {
+            return Ok(Registration::AlreadyRegistered);
+        }
+        last = Some(stmt);
+    }
+
+    let (line, indent) = match last {
+        Some(stmt) => (
+            stmt.span().end().line,
+            indentation(&content, stmt.span().start().line),
+        ),
+        None => (
+            register.block.brace_token.span.close().start().line - 1,
+            "    ".to_string(),
+        ),
+    };
+
+    let call = format!("{}global_middleware!({});\n", indent, middleware);
+    write(bootstrap, &insert_line(&content, line, &call))?;
+    Ok(Registration::Added)
+}
+
+/// Add `<method>!("<path>", <handler>),` to the end of `routes! { ... }`
+pub fn register_route(
+    routes: &Path,
+    method: &str,
+    path: &str,
+    handler: &str,
+) -> Result<Registration, String> {
+    let content = read(routes)?;
+    let file = parse(routes, &content)?;
+
+    let routes_macro: &Macro = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            Item::Macro(m) if m.mac.path.is_ident("routes") => Some(&m.mac),
+            _ => None,
+        })
+        .ok_or_else(|| format!("No `routes!` block found in {}", routes.display()))?;
+
+    let entry = format!("{}!(\"{}\", {})", method, path, handler);
+    if normalize(&routes_macro.tokens.to_string()).contains(&normalize(&entry)) {
+        return Ok(Registration::AlreadyRegistered);
+    }
+
+    let MacroDelimiter::Brace(brace) = &routes_macro.delimiter else {
+        return Err(format!(
+            "Expected `routes! {{ ... }}` in {}",
+            routes.display()
+        ));
+    };
+    let close = offset(&content, brace.span.close().start());
+
+    // Make sure the previous entry is terminated
+    let before = content[..close].trim_end();
+    let comma = if before.ends_with(',') || before.ends_with('{') {
+        ""
+    } else {
+        ","
+    };
+
+    let line_start = content[..close].rfind('\n').map_or(0, |i| i + 1);
+    let updated = if content[line_start..close].trim().is_empty() {
+        format!(
+            "{}{}{}    {},\n{}",
+            before,
+            comma,
+            &content[before.len()..line_start],
+            entry,
+            &content[line_start..]
+        )
+    } else {
+        format!("{}{}\n    {},\n{}", before, comma, entry, &content[close..])
+    };
+
+    write(routes, &updated)?;
+    Ok(Registration::Added)
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn parse(path: &Path, content: &str) -> Result<syn::File, String> {
+    syn::parse_file(content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Token text without whitespace, for comparing code regardless of formatting
+fn normalize(tokens: &str) -> String {
+    tokens.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Leading whitespace of a 1-based line
+fn indentation(content: &str, line: usize) -> String {
+    content
+        .lines()
+        .nth(line - 1)
+        .unwrap_or_default()
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect()
+}
+
+/// Insert `text` after the 1-based `line`
+fn insert_line(content: &str, line: usize, text: &str) -> String {
+    let mut lines: Vec<&str> = content.split_inclusive('\n').collect();
+    lines.insert(line.min(lines.len()), text);
+    lines.concat()
+}
+
+/// Byte offset of a span position (1-based line, 0-based character column)
+fn offset(content: &str, position: LineColumn) -> usize {
+    let line_start: usize = content
+        .split_inclusive('\n')
+        .take(position.line - 1)
+        .map(str::len)
+        .sum();
+    content[line_start..]
+        .char_indices()
+        .nth(position.column)
+        .map_or(content.len(), |(i, _)| line_start + i)
+}
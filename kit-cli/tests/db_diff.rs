@@ -0,0 +1,140 @@
+//! Tests for the migrations written by `kit db:diff`
+
+use sea_orm::{ConnectionTrait, Database};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const POST_MODEL: &str = r#"use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "posts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub title: String,
+    #[sea_orm(unique)]
+    pub slug: String,
+    pub published_at: Option<DateTimeUtc>,
+    #[sea_orm(ignore)]
+    pub excerpt: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+"#;
+
+fn kit(dir: &Path, args: &[&str], database_url: &str) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_kit"))
+        .args(args)
+        .current_dir(dir)
+        .env("DATABASE_URL", database_url)
+        .output()
+        .expect("Failed to run kit");
+    assert!(
+        output.status.success(),
+        "kit {} failed:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn new_project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("kit-cli-tests").join(format!(
+        "db-diff-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    kit(
+        &dir,
+        &["new", "demo", "--no-interaction", "--no-git", "--offline"],
+        "",
+    );
+    dir.join("demo")
+}
+
+fn migrations(project: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(project.join("src/migrations"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .ends_with("_add_posts.rs")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn diffs_models_against_the_database_then_the_snapshot() {
+    let project = new_project("posts");
+    fs::write(project.join("src/models/post.rs"), POST_MODEL).unwrap();
+
+    // The users table is missing `remember_token` and has a stale column
+    let database_url = format!("sqlite://{}?mode=rwc", project.join("diff.db").display());
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let db = Database::connect(&database_url).await.unwrap();
+        db.execute_unprepared(
+            "CREATE TABLE users (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                email TEXT NOT NULL,
+                password TEXT NOT NULL,
+                nickname TEXT,
+                created_at TIMESTAMP NOT NULL,
+                updated_at TIMESTAMP NOT NULL
+            )",
+        )
+        .await
+        .unwrap();
+    });
+
+    let output = kit(&project, &["db:diff", "add_posts"], &database_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for expected in [
+        "create table posts",
+        "add column users.remember_token",
+        "drop column users.nickname",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "missing {:?} in:\n{}",
+            expected,
+            stdout
+        );
+    }
+
+    let files = migrations(&project);
+    assert_eq!(files.len(), 1);
+    let migration = fs::read_to_string(&files[0]).unwrap();
+    for expected in [
+        ".table(Alias::new(\"posts\"))",
+        "ColumnDef::new(Alias::new(\"id\")).big_integer().not_null().auto_increment().primary_key()",
+        "ColumnDef::new(Alias::new(\"published_at\")).timestamp_with_time_zone().null()",
+        ".name(\"idx_posts_slug\")",
+        "add_column(ColumnDef::new(Alias::new(\"remember_token\")).string().null())",
+        "drop_column(Alias::new(\"nickname\"))",
+        "add_column(ColumnDef::new(Alias::new(\"nickname\")).string().null())",
+        ".drop_table(Table::drop().table(Alias::new(\"posts\")).to_owned())",
+    ] {
+        assert!(migration.contains(expected), "missing {:?} in:\n{}", expected, migration);
+    }
+    assert!(!migration.contains("excerpt"));
+
+    let name = files[0].file_stem().unwrap().to_string_lossy().into_owned();
+    let mod_rs = fs::read_to_string(project.join("src/migrations/mod.rs")).unwrap();
+    assert!(mod_rs.contains(&format!("mod {};", name)));
+    assert!(mod_rs.contains(&format!("Box::new({}::Migration)", name)));
+
+    // The snapshot now matches the models, so nothing is written
+    let output = kit(&project, &["db:diff", "add_posts", "--from-snapshot"], "");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Schema is up to date"));
+    assert_eq!(migrations(&project).len(), 1);
+}
@@ -0,0 +1,170 @@
+//! Tests for the TypeScript route helpers and props types written by
+//! `kit generate-types`
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ROUTES: &str = r#"use kit::{get, group, patch, post, routes};
+
+use crate::controllers;
+use crate::middleware;
+
+routes! {
+    get!("/", controllers::home::index).name("home"),
+
+    group!("/", {
+        get!("/login", controllers::auth::show_login).name("login"),
+    }).middleware(middleware::authenticate::guest()),
+
+    group!("/admin", {
+        get!("/dashboard", controllers::dashboard::index).name("dashboard"),
+        group!("/users", {
+            get!("/", controllers::user::index).name("index"),
+            get!("/:id", controllers::user::show).name("show"),
+            post!("/", controllers::user::store).describe("Create a user").tag("users"),
+            patch!("/:id", controllers::user::update).name("update").deprecated(),
+        }).name_prefix("users."),
+    }).name_prefix("admin.").middleware(middleware::authenticate::auth()),
+}
+"#;
+
+fn kit(dir: &Path, args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_kit"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("Failed to run kit");
+    assert!(
+        output.status.success(),
+        "kit {} failed:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn new_project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("kit-cli-tests").join(format!(
+        "generate-types-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    kit(
+        &dir,
+        &["new", "demo", "--no-interaction", "--no-git", "--offline"],
+    );
+    dir.join("demo")
+}
+
+#[test]
+fn nested_groups_resolve_full_paths_and_names() {
+    let project = new_project("routes");
+    fs::write(project.join("src/routes.rs"), ROUTES).unwrap();
+
+    kit(&project, &["generate-types"]);
+    let routes = fs::read_to_string(project.join("frontend/src/types/routes.ts")).unwrap();
+
+    for expected in [
+        "url: '/login', method: 'get'",
+        "url: '/admin/dashboard', method: 'get'",
+        "url: '/admin/users', method: 'get'",
+        "url: `/admin/users/${params.id}`, method: 'get'",
+        "url: '/admin/users', method: 'post'",
+        "url: `/admin/users/${params.id}`, method: 'patch'",
+        "'login': controllers.auth.show_login",
+        "'admin.dashboard': controllers.dashboard.index",
+        "'admin.users.index': controllers.user.index",
+        "'admin.users.show': controllers.user.show",
+        "'admin.users.update': controllers.user.update",
+        "    /**\n     * Create a user\n     * @category users\n     */\n    store: ",
+        "    /** @deprecated */\n    update: ",
+    ] {
+        assert!(
+            routes.contains(expected),
+            "missing {:?} in:\n{}",
+            expected,
+            routes
+        );
+    }
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
+
+#[test]
+fn camel_case_props_generate_camel_case_fields() {
+    let project = new_project("camel-case");
+    fs::write(
+        project.join("src/profile_props.rs"),
+        r#"use kit::InertiaProps;
+
+#[derive(InertiaProps)]
+#[inertia(rename_all = "camelCase")]
+pub struct ProfileProps {
+    pub current_user: String,
+    pub address_line_1: Option<String>,
+}
+
+#[derive(InertiaProps)]
+pub struct SettingsProps {
+    pub time_zone: String,
+    #[serde(skip)]
+    pub user_id: i64,
+    #[serde(rename = "tz_label")]
+    pub label: String,
+    pub sessions: kit::InertiaPaginated<ProfileProps>,
+    pub activity: Vec<kit::ActivityItem>,
+    pub import: Option<kit::ImportStatus>,
+}
+
+#[kit::computed_props]
+impl ProfileProps {
+    #[prop(computed)]
+    async fn unread_count(&self) -> u32 {
+        0
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    kit(&project, &["generate-types"]);
+    let types = fs::read_to_string(project.join("frontend/src/types/inertia-props.ts")).unwrap();
+
+    assert!(types.contains("  currentUser: string;"), "{}", types);
+    assert!(
+        types.contains("  addressLine1: string | null;"),
+        "{}",
+        types
+    );
+    assert!(types.contains("  time_zone: string;"), "{}", types);
+    assert!(types.contains("  tz_label: string;"), "{}", types);
+    assert!(!types.contains("user_id"), "{}", types);
+    assert!(types.contains("  unreadCount: number;"), "{}", types);
+    assert!(
+        types.contains("  sessions: InertiaPaginated<ProfileProps>;"),
+        "{}",
+        types
+    );
+    assert!(
+        types.contains("export interface InertiaPaginated<T> {"),
+        "{}",
+        types
+    );
+    assert!(types.contains("  activity: ActivityItem[];"), "{}", types);
+    assert!(
+        types.contains("  import: ImportStatus | null;"),
+        "{}",
+        types
+    );
+    assert!(types.contains("  report_url: string | null;"), "{}", types);
+    assert!(
+        types.contains("export interface ActivityItem {"),
+        "{}",
+        types
+    );
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
@@ -0,0 +1,14 @@
+//! Auth middleware
+
+use kit::{async_trait, Middleware, Next, Request, Response};
+
+/// Auth middleware
+pub struct AuthMiddleware;
+
+#[async_trait]
+impl Middleware for AuthMiddleware {
+    async fn handle(&self, request: Request, next: Next) -> Response {
+        // TODO: Implement middleware logic
+        next(request).await
+    }
+}
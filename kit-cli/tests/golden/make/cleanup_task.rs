@@ -0,0 +1,49 @@
+//! CleanupTask scheduled task
+//!
+//! Created with `kit make:task cleanup_task`
+
+use async_trait::async_trait;
+use kit::{Task, TaskResult};
+
+/// CleanupTask - A scheduled task
+///
+/// Implement your task logic in the `handle()` method.
+/// Register this task in `src/schedule.rs` with the fluent API.
+///
+/// # Example Registration
+///
+/// ```rust,ignore
+/// // In src/schedule.rs
+/// use crate::tasks::cleanup_task;
+///
+/// schedule.add(
+///     schedule.task(CleanupTask::new())
+///         .daily()
+///         .at("03:00")
+///         .name("cleanup_task")
+///         .description("TODO: Add task description")
+/// );
+/// ```
+pub struct CleanupTask;
+
+impl CleanupTask {
+    /// Create a new instance of this task
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CleanupTask {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Task for CleanupTask {
+    async fn handle(&self) -> TaskResult {
+        // TODO: Implement your task logic here
+        println!("Running CleanupTask...");
+        Ok(())
+    }
+}
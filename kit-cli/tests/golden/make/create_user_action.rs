@@ -0,0 +1,19 @@
+//! create_user_action action
+
+use kit::{async_trait, injectable, Action, FrameworkError};
+
+#[injectable]
+pub struct CreateUserAction {
+    // Dependencies injected via container
+}
+
+#[async_trait]
+impl Action for CreateUserAction {
+    type Input = ();
+    type Output = ();
+
+    async fn handle(&self, _input: ()) -> Result<(), FrameworkError> {
+        // TODO: Implement action logic
+        Ok(())
+    }
+}
@@ -0,0 +1,6 @@
+//! NotFound error
+
+use kit::domain_error;
+
+#[domain_error(status = 500, message = "Not found")]
+pub struct NotFound;
@@ -0,0 +1,10 @@
+//! user controller
+
+use kit::{handler, json_response, Request, Response};
+
+#[handler]
+pub async fn invoke(_req: Request) -> Response {
+    json_response!({
+        "controller": "user"
+    })
+}
@@ -0,0 +1,88 @@
+//! Tests for `kit schema:snapshot` and `kit schema:verify`
+//!
+//! These read an existing database with `--database`; migrating a fresh
+//! one builds the project, which `templates.rs` already covers.
+
+use sea_orm::{ConnectionTrait, Database};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn kit(dir: &Path, args: &[&str], database_url: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_kit"))
+        .args(args)
+        .current_dir(dir)
+        .env("DATABASE_URL", database_url)
+        .output()
+        .expect("Failed to run kit")
+}
+
+fn new_project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("kit-cli-tests").join(format!(
+        "schema-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let output = kit(
+        &dir,
+        &["new", "demo", "--no-interaction", "--no-git", "--offline"],
+        "",
+    );
+    assert!(output.status.success());
+    dir.join("demo")
+}
+
+fn execute(database_url: &str, sql: &[&str]) {
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let db = Database::connect(database_url).await.unwrap();
+        for statement in sql {
+            db.execute_unprepared(statement).await.unwrap();
+        }
+    });
+}
+
+#[test]
+fn verify_reports_drift_from_the_snapshot() {
+    let project = new_project("drift");
+    let database_url = format!("sqlite://{}?mode=rwc", project.join("app.db").display());
+    execute(
+        &database_url,
+        &[
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL, slug TEXT)",
+            "CREATE UNIQUE INDEX idx_posts_slug ON posts (slug)",
+            "CREATE TABLE authors (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+        ],
+    );
+
+    let output = kit(&project, &["schema:snapshot", "--database"], &database_url);
+    assert!(output.status.success());
+    let snapshot = fs::read_to_string(project.join("schema.snapshot")).unwrap();
+    assert!(snapshot.ends_with(
+        "\ntable authors\n  column id INTEGER primary key\n  column name TEXT not null\n\
+         \ntable posts\n  column id INTEGER primary key\n  column title TEXT not null\n  \
+         column slug TEXT\n  index idx_posts_slug unique (slug)\n"
+    ));
+
+    let output = kit(&project, &["schema:verify", "--database"], &database_url);
+    assert!(output.status.success());
+
+    execute(
+        &database_url,
+        &[
+            "ALTER TABLE posts ADD COLUMN body TEXT",
+            "DROP INDEX idx_posts_slug",
+        ],
+    );
+    let output = kit(&project, &["schema:verify", "--database"], &database_url);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("- posts: index idx_posts_slug unique (slug)"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("+ posts: column body TEXT"), "{}", stderr);
+}
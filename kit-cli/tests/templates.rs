@@ -0,0 +1,393 @@
+//! Template tests for `kit new` and the `make:*` generators
+//!
+//! Generated output is compared against the golden files in `tests/golden`.
+//! After an intentional template change, refresh them with:
+//!
+//! ```bash
+//! KIT_UPDATE_GOLDEN=1 cargo test -p kit-cli --test templates
+//! ```
+//!
+//! `new_project_compiles` builds a fresh project against the framework in
+//! this repository. It is slow, so it only runs on request:
+//!
+//! ```bash
+//! cargo test -p kit-cli --test templates -- --ignored
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Generators exercised by the golden tests: (command, name, created file)
+const GENERATORS: &[(&str, &str, &str)] = &[
+    ("make:controller", "User", "src/controllers/user.rs"),
+    ("make:middleware", "Auth", "src/middleware/auth.rs"),
+    (
+        "make:action",
+        "CreateUser",
+        "src/actions/create_user_action.rs",
+    ),
+    ("make:error", "NotFound", "src/errors/not_found.rs"),
+    ("make:task", "Cleanup", "src/tasks/cleanup_task.rs"),
+];
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn kit(dir: &Path, args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_kit"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("Failed to run kit");
+    assert!(
+        output.status.success(),
+        "kit {} failed:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+/// Generate a fresh project named `demo` in an empty scratch directory
+///
+/// Projects are created with `--offline` so the tests never reach npm.
+fn new_project(test: &str) -> PathBuf {
+    new_project_with(test, &[])
+}
+
+fn new_project_with(test: &str, extra_args: &[&str]) -> PathBuf {
+    let dir = scratch_dir(test);
+
+    let mut args = vec!["new", "demo", "--no-interaction", "--no-git", "--offline"];
+    args.extend_from_slice(extra_args);
+    kit(&dir, &args);
+    dir.join("demo")
+}
+
+/// An empty directory for one test
+fn scratch_dir(test: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir()
+            .join("kit-cli-tests")
+            .join(format!("{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Compare `actual` with a golden file, or rewrite it when updating
+fn assert_golden(name: &str, actual: &str) {
+    let path = golden_dir().join(name);
+
+    if std::env::var_os("KIT_UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing golden file {}. Run with KIT_UPDATE_GOLDEN=1 to create it.",
+            path.display()
+        )
+    });
+    assert_text_eq(name, &expected, actual);
+}
+
+fn assert_text_eq(name: &str, expected: &str, actual: &str) {
+    if expected == actual {
+        return;
+    }
+    let line = expected
+        .lines()
+        .zip(actual.lines())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+    panic!(
+        "{} differs from its golden file at line {}:\n  expected: {:?}\n  actual:   {:?}\n\
+         Run with KIT_UPDATE_GOLDEN=1 if the change is intentional.",
+        name,
+        line + 1,
+        expected.lines().nth(line).unwrap_or("<end of file>"),
+        actual.lines().nth(line).unwrap_or("<end of file>"),
+    );
+}
+
+fn list_files(root: &Path) -> String {
+    let mut files: Vec<String> = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            entry
+                .path()
+                .strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    files.sort();
+    files.join("\n") + "\n"
+}
+
+#[test]
+fn new_project_generates_expected_files() {
+    let project = new_project("files");
+
+    assert_golden("new_project_files.txt", &list_files(&project));
+
+    let cargo_toml = fs::read_to_string(project.join("Cargo.toml")).unwrap();
+    assert!(cargo_toml.contains("name = \"demo\""));
+    let main_rs = fs::read_to_string(project.join("cmd/main.rs")).unwrap();
+    assert!(main_rs.contains("use demo::{"));
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
+
+#[test]
+fn new_project_offline_pins_versions() {
+    let project = new_project("offline");
+
+    let cargo_toml = fs::read_to_string(project.join("Cargo.toml")).unwrap();
+    assert!(cargo_toml.contains(&format!(
+        "kit = {{ package = \"kit-rs\", version = \"={}\" }}",
+        env!("CARGO_PKG_VERSION")
+    )));
+    assert!(cargo_toml.contains("async-trait = \"=0.1.89\""));
+    assert!(cargo_toml.contains("tokio = { version = \"=1.48.0\", features = [\"full\"] }"));
+
+    let package_json = fs::read_to_string(project.join("frontend/package.json")).unwrap();
+    assert!(package_json.contains("\"react\": \"18.2.0\","));
+    assert!(package_json.contains("\"vite\": \"5.0.12\"\n"));
+    assert!(!package_json.contains('^'));
+
+    let config = fs::read_to_string(project.join(".cargo/config.toml")).unwrap();
+    assert_eq!(config, "[net]\noffline = true\n");
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
+
+#[test]
+fn new_project_uses_custom_registries() {
+    let project = new_project_with(
+        "registry",
+        &[
+            "--registry",
+            "sparse+https://crates.example.com/index/",
+            "--npm-registry",
+            "https://npm.example.com/",
+        ],
+    );
+
+    let config = fs::read_to_string(project.join(".cargo/config.toml")).unwrap();
+    assert!(config.contains("[source.crates-io]\nreplace-with = \"mirror\""));
+    assert!(config.contains("registry = \"sparse+https://crates.example.com/index/\""));
+
+    let npmrc = fs::read_to_string(project.join("frontend/.npmrc")).unwrap();
+    assert_eq!(npmrc, "registry=https://npm.example.com/\n");
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
+
+#[test]
+fn new_project_joins_workspace() {
+    let dir = scratch_dir("workspace");
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[workspace]\nresolver = \"2\"\nmembers = [\n    \"crates/*\",\n]\n",
+    )
+    .unwrap();
+    fs::create_dir_all(dir.join("crates/core/src")).unwrap();
+    fs::write(
+        dir.join("crates/core/Cargo.toml"),
+        "[package]\nname = \"core-lib\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("crates/core/src/lib.rs"), "").unwrap();
+    fs::create_dir_all(dir.join("apps")).unwrap();
+
+    kit(
+        &dir.join("apps"),
+        &[
+            "new",
+            "demo",
+            "--no-interaction",
+            "--offline",
+            "--workspace",
+        ],
+    );
+
+    let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+    assert_eq!(
+        manifest,
+        "[workspace]\nresolver = \"2\"\nmembers = [\n    \"crates/*\",\n    \"apps/demo\",\n]\n"
+    );
+    // The workspace owns the git repository
+    assert!(!dir.join("apps/demo/.git").exists());
+
+    // Commands run from the workspace root find the member project
+    kit(&dir, &["make:controller", "Post"]);
+    assert!(dir.join("apps/demo/src/controllers/post.rs").exists());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn make_commands_match_golden_files() {
+    let project = new_project("make");
+
+    for (command, name, file) in GENERATORS {
+        kit(&project, &[command, name]);
+        let generated = fs::read_to_string(project.join(file))
+            .unwrap_or_else(|_| panic!("{} {} did not create {}", command, name, file));
+        let golden = format!(
+            "make/{}",
+            Path::new(file).file_name().unwrap().to_string_lossy()
+        );
+        assert_golden(&golden, &generated);
+    }
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
+
+#[test]
+fn make_commands_register_generated_code() {
+    let project = new_project("register");
+
+    // Running twice must not register twice
+    for _ in 0..2 {
+        kit(&project, &["make:middleware", "Audit", "--global"]);
+        kit(&project, &["make:controller", "Post", "--route", "/posts"]);
+    }
+
+    let bootstrap = fs::read_to_string(project.join("src/bootstrap.rs")).unwrap();
+    assert_eq!(bootstrap.matches("middleware::AuditMiddleware").count(), 1);
+    assert!(bootstrap.contains(
+        "    global_middleware!(CsrfMiddleware::new());\n    global_middleware!(middleware::AuditMiddleware);\n"
+    ));
+
+    let routes = fs::read_to_string(project.join("src/routes.rs")).unwrap();
+    assert_eq!(routes.matches("/posts").count(), 1);
+    assert!(routes.ends_with(
+        "    }).middleware(middleware::authenticate::auth()),\n    get!(\"/posts\", controllers::post::invoke),\n}\n"
+    ));
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
+
+#[test]
+fn make_billing_installs_billing() {
+    let project = new_project("billing");
+
+    // Running twice must not install twice
+    for _ in 0..2 {
+        kit(&project, &["make:billing"]);
+        kit(&project, &["activity:install"]);
+    }
+
+    let migrations = fs::read_to_string(project.join("src/migrations/mod.rs")).unwrap();
+    assert_eq!(
+        migrations
+            .matches("Box::new(m20240101_000006_create_billing_tables::Migration)")
+            .count(),
+        1
+    );
+    assert!(project
+        .join("src/migrations/m20240101_000006_create_billing_tables.rs")
+        .exists());
+
+    let controllers = fs::read_to_string(project.join("src/controllers/mod.rs")).unwrap();
+    assert_eq!(controllers.matches("pub mod billing;").count(), 1);
+
+    let routes = fs::read_to_string(project.join("src/routes.rs")).unwrap();
+    assert_eq!(
+        routes
+            .matches("post!(\"/stripe/webhook\", controllers::billing::webhook)")
+            .count(),
+        1
+    );
+
+    let bootstrap = fs::read_to_string(project.join("src/bootstrap.rs")).unwrap();
+    assert!(bootstrap
+        .contains("global_middleware!(CsrfMiddleware::new().except(vec![\"/stripe/webhook\"]));"));
+
+    let user = fs::read_to_string(project.join("src/models/user.rs")).unwrap();
+    assert_eq!(user.matches("impl kit::Billable for Model").count(), 1);
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
+
+#[test]
+fn activity_install_adds_activities_migration() {
+    let project = new_project("activity");
+
+    for _ in 0..2 {
+        kit(&project, &["activity:install"]);
+    }
+
+    let migrations = fs::read_to_string(project.join("src/migrations/mod.rs")).unwrap();
+    assert_eq!(
+        migrations
+            .matches("Box::new(m20240101_000007_create_activities_table::Migration)")
+            .count(),
+        1
+    );
+    assert!(project
+        .join("src/migrations/m20240101_000007_create_activities_table.rs")
+        .exists());
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
+
+#[test]
+#[ignore]
+fn new_project_compiles() {
+    let project = new_project("compile");
+
+    for (command, name, _) in GENERATORS {
+        kit(&project, &[command, name]);
+    }
+    kit(&project, &["make:middleware", "Audit", "--global"]);
+    kit(&project, &["make:controller", "Post", "--route", "/posts"]);
+    kit(&project, &["make:billing"]);
+
+    // Modules that the generators ask you to add by hand
+    let lib_rs = project.join("src/lib.rs");
+    let mut lib = fs::read_to_string(&lib_rs).unwrap();
+    lib.push_str("pub mod errors;\npub mod schedule;\npub mod tasks;\n");
+    fs::write(&lib_rs, lib).unwrap();
+
+    // Build against the framework in this repository rather than crates.io
+    let framework = Path::new(env!("CARGO_MANIFEST_DIR")).join("../framework");
+    let cargo_toml = project.join("Cargo.toml");
+    let mut manifest = fs::read_to_string(&cargo_toml).unwrap();
+    manifest.push_str(&format!(
+        "\n[patch.crates-io]\nkit-rs = {{ path = {:?} }}\n",
+        framework.canonicalize().unwrap()
+    ));
+    fs::write(&cargo_toml, manifest).unwrap();
+
+    // Share a target directory across runs to keep rebuilds incremental
+    let target_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/kit-new-check");
+    // The project is generated with `--offline`; only stay offline on request
+    let mut check = Command::new(env!("CARGO"));
+    if std::env::var_os("KIT_TEST_OFFLINE").is_none() {
+        check.args(["--config", "net.offline=false"]);
+    }
+    check
+        .arg("check")
+        .current_dir(&project)
+        .env("CARGO_TARGET_DIR", target_dir);
+
+    let output = check.output().expect("Failed to run cargo check");
+    assert!(
+        output.status.success(),
+        "Generated project does not compile:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    fs::remove_dir_all(project.parent().unwrap()).ok();
+}
@@ -0,0 +1,66 @@
+//! `#[blocking]` attribute macro
+//!
+//! Turns a synchronous function into an async one whose body runs on the
+//! blocking thread pool through `kit::blocking`.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, FnArg, ItemFn, ReturnType};
+
+pub fn blocking_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new_spanned(attr, "#[blocking] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let mut input = parse_macro_input!(input as ItemFn);
+    let sig = &input.sig;
+
+    if let Some(asyncness) = &sig.asyncness {
+        return syn::Error::new_spanned(
+            asyncness,
+            "#[blocking] functions must not be async; the body runs on a blocking thread",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if let Some(FnArg::Receiver(receiver)) = sig.inputs.first() {
+        if receiver.reference.is_some() {
+            return syn::Error::new_spanned(
+                receiver,
+                "#[blocking] methods can't borrow self; take `self` or clone what the body needs",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    // The body is moved to another thread, so a panic there becomes an
+    // internal error returned through `?`, or is re-raised for `()` functions
+    let block = &input.block;
+    let body = match &sig.output {
+        ReturnType::Default => quote! {
+            {
+                if let Err(err) = ::kit::blocking(move || #block).await {
+                    panic!("{}", err);
+                }
+            }
+        },
+        ReturnType::Type(_, ty) => quote! {
+            {
+                ::kit::blocking(move || -> #ty #block).await?
+            }
+        },
+    };
+
+    input.sig.asyncness = Some(Default::default());
+    let attrs = &input.attrs;
+    let vis = &input.vis;
+    let sig = &input.sig;
+    TokenStream::from(quote! {
+        #(#attrs)*
+        #vis #sig #body
+    })
+}
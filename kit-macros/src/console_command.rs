@@ -0,0 +1,108 @@
+//! `#[console_command]` attribute macro for application CLI commands
+//!
+//! Registers a struct implementing `ConsoleCommand` so it can be invoked
+//! through the application binary (`cargo run -- <name> [args]`).
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput, LitStr};
+
+/// Parse the macro attributes
+struct ConsoleCommandArgs {
+    name: Option<LitStr>,
+    description: Option<LitStr>,
+}
+
+impl syn::parse::Parse for ConsoleCommandArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut name = None;
+        let mut description = None;
+
+        while !input.is_empty() {
+            let ident: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            let value: LitStr = input.parse()?;
+
+            if ident == "name" {
+                name = Some(value);
+            } else if ident == "description" {
+                description = Some(value);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "Unknown #[console_command] option, expected `name` or `description`",
+                ));
+            }
+
+            if input.peek(syn::Token![,]) {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        Ok(Self { name, description })
+    }
+}
+
+pub fn console_command_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as ConsoleCommandArgs);
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    let name = match args.name {
+        Some(name) => name,
+        None => {
+            return syn::Error::new_spanned(
+                ident,
+                "#[console_command] requires a name, e.g. #[console_command(name = \"reports:send\")]",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    if name.value().is_empty() || name.value().contains(char::is_whitespace) {
+        return syn::Error::new_spanned(&name, "Console command names cannot be empty or contain whitespace")
+            .to_compile_error()
+            .into();
+    }
+
+    let description = args
+        .description
+        .unwrap_or_else(|| LitStr::new("", proc_macro2::Span::call_site()));
+
+    let augment_name = format_ident!("__kit_console_augment_{}", ident);
+    let runner_name = format_ident!("__kit_console_runner_{}", ident);
+
+    let expanded = quote! {
+        #input
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        fn #augment_name(cmd: ::kit::clap::Command) -> ::kit::clap::Command {
+            <#ident as ::kit::clap::Args>::augment_args(cmd)
+        }
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        fn #runner_name(
+            __matches: &::kit::clap::ArgMatches,
+        ) -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = Result<(), ::kit::FrameworkError>> + Send>> {
+            let __parsed = <#ident as ::kit::clap::FromArgMatches>::from_arg_matches(__matches);
+            Box::pin(async move {
+                let __command = __parsed.map_err(|e| ::kit::FrameworkError::internal(e.to_string()))?;
+                ::kit::ConsoleCommand::handle(&__command).await
+            })
+        }
+
+        ::kit::inventory::submit! {
+            ::kit::console::CommandEntry {
+                name: #name,
+                description: #description,
+                augment: #augment_name,
+                run: #runner_name,
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
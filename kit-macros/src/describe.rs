@@ -1,12 +1,16 @@
 //! `describe!` macro for grouping related tests
 //!
 //! Generates a module with properly structured tests, similar to Jest's describe blocks.
+//!
+//! `before_each!`, `after_each!` and `before_all!` blocks inside a describe are
+//! removed from the module and woven into every `test!` and `test_each!` in
+//! it, including the tests of nested describes.
 
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Delimiter, Group, Spacing, TokenStream as TokenStream2, TokenTree};
 use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream};
-use syn::{braced, LitStr, Token};
+use syn::{braced, Ident, LitStr, Token};
 
 /// Convert a string to snake_case for module/function names
 fn to_snake_case(name: &str) -> String {
@@ -41,9 +45,14 @@ fn to_snake_case(name: &str) -> String {
 }
 
 /// Parse describe macro arguments: describe!("Name", { ... })
+///
+/// Nested describes are rewritten by their parent to
+/// `describe!("Name", { ... }, __inherit_before_all)` when the parent has
+/// `before_all!` hooks.
 struct DescribeArgs {
     name: LitStr,
     body: TokenStream2,
+    inherit_before_all: bool,
 }
 
 impl Parse for DescribeArgs {
@@ -55,8 +64,156 @@ impl Parse for DescribeArgs {
         braced!(content in input);
         let body: TokenStream2 = content.parse()?;
 
-        Ok(Self { name, body })
+        let mut inherit_before_all = false;
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if !input.is_empty() {
+                let flag: Ident = input.parse()?;
+                if flag != "__inherit_before_all" {
+                    return Err(syn::Error::new(flag.span(), "unexpected describe! argument"));
+                }
+                inherit_before_all = true;
+            }
+        }
+
+        Ok(Self {
+            name,
+            body,
+            inherit_before_all,
+        })
+    }
+}
+
+/// Lifecycle hooks declared in a describe block, in declaration order
+#[derive(Default)]
+struct Hooks {
+    before_all: Vec<TokenStream2>,
+    before_each: Vec<TokenStream2>,
+    after_each: Vec<TokenStream2>,
+}
+
+/// Split a describe body into its hooks and the remaining tokens
+fn extract_hooks(body: TokenStream2) -> (Hooks, Vec<TokenTree>) {
+    let tokens: Vec<TokenTree> = body.into_iter().collect();
+    let mut hooks = Hooks::default();
+    let mut rest = Vec::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some((name, group)) = macro_call(&tokens, i) {
+            let slot = match name.to_string().as_str() {
+                "before_all" => Some(&mut hooks.before_all),
+                "before_each" => Some(&mut hooks.before_each),
+                "after_each" => Some(&mut hooks.after_each),
+                _ => None,
+            };
+            if let Some(slot) = slot {
+                slot.push(hook_statements(group));
+                i += 3;
+                if matches!(tokens.get(i), Some(TokenTree::Punct(p)) if p.as_char() == ';') {
+                    i += 1;
+                }
+                continue;
+            }
+        }
+        rest.push(tokens[i].clone());
+        i += 1;
+    }
+
+    (hooks, rest)
+}
+
+/// Match `name ! (...)` starting at `tokens[i]`
+fn macro_call(tokens: &[TokenTree], i: usize) -> Option<(&Ident, &Group)> {
+    match (tokens.get(i), tokens.get(i + 1), tokens.get(i + 2)) {
+        (Some(TokenTree::Ident(name)), Some(TokenTree::Punct(bang)), Some(TokenTree::Group(group)))
+            if bang.as_char() == '!' && bang.spacing() == Spacing::Alone =>
+        {
+            Some((name, group))
+        }
+        _ => None,
+    }
+}
+
+/// The statements of a hook, accepting `hook!({ ... })`, `hook!(async { ... })`
+/// and `hook! { ... }`
+///
+/// A trailing expression gets a `;` so hooks can be spliced one after another.
+fn hook_statements(group: &Group) -> TokenStream2 {
+    let mut stream = group.stream();
+    let mut inner = stream.clone().into_iter().peekable();
+    if matches!(inner.peek(), Some(TokenTree::Ident(kw)) if kw == "async") {
+        inner.next();
+    }
+    if let (Some(TokenTree::Group(block)), None) = (inner.next(), inner.next()) {
+        if block.delimiter() == Delimiter::Brace {
+            stream = block.stream();
+        }
+    }
+
+    let needs_semicolon = match stream.clone().into_iter().last() {
+        None => false,
+        Some(TokenTree::Punct(p)) => p.as_char() != ';',
+        Some(TokenTree::Group(g)) => g.delimiter() != Delimiter::Brace,
+        Some(_) => true,
+    };
+    if needs_semicolon {
+        quote! { #stream; }
+    } else {
+        stream
+    }
+}
+
+/// Pass the hooks on to the `test!`, `test_each!` and `describe!` calls of a describe body
+fn weave_hooks(rest: Vec<TokenTree>, hooks: &Hooks, has_before_all: bool) -> TokenStream2 {
+    let Hooks {
+        before_each,
+        after_each,
+        ..
+    } = hooks;
+    let mut output = TokenStream2::new();
+
+    let mut i = 0;
+    while i < rest.len() {
+        if let Some((name, group)) = macro_call(&rest, i) {
+            let rewritten = match name.to_string().as_str() {
+                "test" | "test_each" => {
+                    let args = group.stream();
+                    let before_all = has_before_all.then(|| quote! { before_all });
+                    Some(quote! {
+                        #name!(#args, __hooks {
+                            #before_all
+                            before { #({ #before_each })* }
+                            after { #({ #after_each })* }
+                        })
+                    })
+                }
+                "describe" => syn::parse2::<DescribeArgs>(group.stream()).ok().map(|nested| {
+                    // The parent's before_each hooks run first, its after_each hooks last
+                    let nested_name = nested.name;
+                    let nested_body = nested.body;
+                    let inherit = has_before_all.then(|| quote! { , __inherit_before_all });
+                    quote! {
+                        #name!(#nested_name, {
+                            #(before_each! { #before_each })*
+                            #nested_body
+                            #(after_each! { #after_each })*
+                        } #inherit)
+                    }
+                }),
+                _ => None,
+            };
+            if let Some(rewritten) = rewritten {
+                output.extend(rewritten);
+                i += 3;
+                continue;
+            }
+        }
+        output.extend(std::iter::once(rest[i].clone()));
+        i += 1;
     }
+
+    output
 }
 
 pub fn describe_impl(input: TokenStream) -> TokenStream {
@@ -67,12 +224,41 @@ pub fn describe_impl(input: TokenStream) -> TokenStream {
 
     let name_str = args.name.value();
     let mod_name = format_ident!("{}", to_snake_case(&name_str));
-    let body = args.body;
+
+    let (hooks, rest) = extract_hooks(args.body);
+    let has_before_all = args.inherit_before_all || !hooks.before_all.is_empty();
+    let body = weave_hooks(rest, &hooks, has_before_all);
+
+    // before_all hooks run once per describe, before the first of its tests.
+    // Nested describes run their parent's hooks first.
+    let before_all_fn = has_before_all.then(|| {
+        let parent = args
+            .inherit_before_all
+            .then(|| quote! { super::__kit_before_all().await; });
+        let own = &hooks.before_all;
+        let once = (!own.is_empty()).then(|| {
+            quote! {
+                static __KIT_BEFORE_ALL: ::tokio::sync::OnceCell<()> =
+                    ::tokio::sync::OnceCell::const_new();
+                __KIT_BEFORE_ALL
+                    .get_or_init(|| async { #(#own)* })
+                    .await;
+            }
+        });
+        quote! {
+            async fn __kit_before_all() {
+                #parent
+                #once
+            }
+        }
+    });
 
     let output = quote! {
         mod #mod_name {
             use super::*;
 
+            #before_all_fn
+
             #body
         }
     };
@@ -92,4 +278,34 @@ mod tests {
         assert_eq!(to_snake_case("UserService"), "user_service");
         assert_eq!(to_snake_case("API endpoints"), "api_endpoints");
     }
+
+    #[test]
+    fn test_extract_hooks() {
+        let body: TokenStream2 = quote! {
+            before_each!({ let user = 1 });
+            after_each! { cleanup(); }
+            test!("uses the user", fn() { assert_eq!(user, 1); });
+            before_all!(async { setup().await });
+        };
+
+        let (hooks, rest) = extract_hooks(body);
+
+        assert_eq!(
+            hooks.before_each[0].to_string(),
+            quote! { let user = 1; }.to_string()
+        );
+        assert_eq!(
+            hooks.after_each[0].to_string(),
+            quote! { cleanup(); }.to_string()
+        );
+        assert_eq!(
+            hooks.before_all[0].to_string(),
+            quote! { setup().await; }.to_string()
+        );
+        let rest: TokenStream2 = rest.into_iter().collect();
+        assert_eq!(
+            rest.to_string(),
+            quote! { test!("uses the user", fn() { assert_eq!(user, 1); }); }.to_string()
+        );
+    }
 }
@@ -6,7 +6,8 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, FnArg, ItemFn, Pat, Type};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Attribute, FnArg, Ident, ItemFn, Pat, Path, ReturnType, Token, Type};
 
 /// Parameter classification for extraction strategy
 enum ParamKind {
@@ -14,10 +15,10 @@ enum ParamKind {
     Request,
     /// Primitive type (i32, String, etc.) - extract from path params via FromParam
     Primitive,
-    /// Model type (*::Model) - extract via RouteBinding
-    Model,
-    /// Other types - extract via FromRequest (FormRequest, etc.)
-    FormRequest,
+    /// Other types - a model via `RouteBinding`/`AutoRouteBinding`, otherwise
+    /// `FromRequestRef` (Query) or `FromRequest` (FormRequest, etc.), picked at
+    /// compile time by the type
+    Resolved,
 }
 
 /// Implementation of the `#[handler]` attribute macro
@@ -25,8 +26,11 @@ enum ParamKind {
 /// Supports multiple parameter extraction:
 ///
 /// - `Request` - passes through unchanged
-/// - Primitives (`i32`, `String`, etc.) - extracted from path params via `FromParam`
-/// - Model types (`user::Model`) - extracted via `RouteBinding` (auto 404 if not found)
+/// - Primitives (`i32`, `String`, `Uuid`, etc.) - extracted from path params via `FromParam`
+/// - `#[param]` arguments - any other `FromParam` type, extracted the same way
+/// - Models (`User`, `user::Model`) - resolved from `{user}` (or `{id}`) via
+///   `RouteBinding` or `AutoRouteBinding` (auto 404 if not found)
+/// - `Query<T>` and other `FromRequestRef` types - read from the request
 /// - Other types - extracted via `FromRequest` (FormRequest validation)
 ///
 /// # Examples
@@ -40,31 +44,50 @@ enum ParamKind {
 /// #[handler]
 /// pub async fn show(req: Request) -> Response { ... }
 ///
-/// // Path parameter extraction
+/// // Path parameter extraction (400 if `{id}` isn't a number)
 /// #[handler]
-/// pub async fn show(id: i32) -> Response { ... }
+/// pub async fn show(id: i64, req: Request) -> Response { ... }
 ///
-/// // Route model binding
+/// // Custom `FromParam` types
 /// #[handler]
-/// pub async fn show(user: user::Model) -> Response { ... }
+/// pub async fn index(#[param] status: OrderStatus) -> Response { ... }
+///
+/// // Route model binding, for `/users/{user}` or `/users/{id}`
+/// #[handler]
+/// pub async fn show(user: User) -> Response { ... }
 ///
 /// // FormRequest validation
 /// #[handler]
 /// pub async fn store(form: CreateUserRequest) -> Response { ... }
 ///
+/// // Typed query string
+/// #[handler]
+/// pub async fn index(params: Query<PaginationParams>, req: Request) -> Response { ... }
+///
 /// // Mixed parameters
 /// #[handler]
-/// pub async fn update(user: user::Model, form: UpdateUserRequest) -> Response { ... }
+/// pub async fn update(user: User, form: UpdateUserRequest) -> Response { ... }
 /// ```
+///
+/// Only one parameter can consume the request (`Request` or a `FromRequest`
+/// type); a second one fails with a 500. `FromRequestRef` parameters must be
+/// declared before a `FromRequest` one.
+///
+/// Return types other than `Response` are converted with `IntoResponse`.
+///
+/// `#[authorize(Policy::ability)]` below `#[handler]` checks the policy after
+/// extraction, against the first bound parameter or the one named second:
+/// `#[authorize(PostPolicy::update, post)]`.
 pub fn handler_impl(_attr: TokenStream, input: TokenStream) -> TokenStream {
     let input_fn = parse_macro_input!(input as ItemFn);
 
     let fn_vis = &input_fn.vis;
     let fn_name = &input_fn.sig.ident;
     let fn_generics = &input_fn.sig.generics;
-    let fn_output = &input_fn.sig.output;
-    let fn_block = &input_fn.block;
-    let fn_attrs = &input_fn.attrs;
+    let (authorize, fn_attrs) = match take_authorize(&input_fn.attrs) {
+        Ok(found) => found,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     let is_async = input_fn.sig.asyncness.is_some();
     let async_token = if is_async {
@@ -73,24 +96,44 @@ pub fn handler_impl(_attr: TokenStream, input: TokenStream) -> TokenStream {
         quote! {}
     };
 
+    // Handler values are converted with `IntoResponse` after the body runs
+    let (fn_output, fn_block) = match response_conversion(&input_fn) {
+        Some(block) => (quote! { -> kit::Response }, block),
+        None => {
+            let output = &input_fn.sig.output;
+            let block = &input_fn.block;
+            (quote! { #output }, quote! { #block })
+        }
+    };
+
     // Collect all parameters
     let params: Vec<_> = input_fn.sig.inputs.iter().collect();
 
     // Handle no parameters case
     if params.is_empty() {
+        let check = match &authorize {
+            Some(authorize) => match authorize.check(None) {
+                Ok(check) => Some(check),
+                Err(err) => return err.to_compile_error().into(),
+            },
+            None => None,
+        };
         let output = quote! {
             #(#fn_attrs)*
             #fn_vis #async_token fn #fn_name #fn_generics(_: kit::Request) #fn_output {
+                #check
                 #fn_block
             }
         };
         return output.into();
     }
 
-    // Process parameters and generate extraction code
+    // Process parameters and generate extraction code. `Request` parameters
+    // go last so parameters that only read the request (`Query`) can run first
     let mut extractions = Vec::new();
-    let mut has_request_consumer = false;
-    let mut has_request_param = false;
+    let mut request_extractions = Vec::new();
+    let mut uses_request = false;
+    let mut first_resolved = None;
 
     for param in &params {
         match param {
@@ -99,17 +142,25 @@ pub fn handler_impl(_attr: TokenStream, input: TokenStream) -> TokenStream {
                 let param_type = &pat_type.ty;
                 let param_name = extract_param_name(param_pat);
 
-                let kind = classify_param_type(param_type);
+                let kind = if pat_type
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("param"))
+                {
+                    ParamKind::Primitive
+                } else {
+                    classify_param_type(param_type)
+                };
+                uses_request |= !matches!(kind, ParamKind::Primitive);
+                if matches!(kind, ParamKind::Resolved) && first_resolved.is_none() {
+                    first_resolved = Some(param_name.clone());
+                }
 
-                let extraction = generate_extraction(
-                    param_pat,
-                    param_type,
-                    &param_name,
-                    &kind,
-                    &mut has_request_consumer,
-                    &mut has_request_param,
-                );
-                extractions.push(extraction);
+                let extraction = generate_extraction(param_pat, param_type, &param_name, &kind);
+                match kind {
+                    ParamKind::Request => request_extractions.push(extraction),
+                    _ => extractions.push(extraction),
+                }
             }
             FnArg::Receiver(_) => {
                 return syn::Error::new_spanned(
@@ -122,29 +173,183 @@ pub fn handler_impl(_attr: TokenStream, input: TokenStream) -> TokenStream {
         }
     }
 
+    let check = match &authorize {
+        Some(authorize) => match authorize.check(first_resolved.as_deref()) {
+            Ok(check) => Some(check),
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => None,
+    };
+
+    // Binding columns are read before a parameter takes the request
+    let request_slot = uses_request.then(|| {
+        quote! {
+            let __kit_binding_keys: ::std::collections::HashMap<String, String> = __kit_params
+                .keys()
+                .filter_map(|__name| {
+                    __kit_req.route_key(__name).map(|__column| (__name.clone(), __column.to_string()))
+                })
+                .collect();
+            let mut __kit_req_slot = ::core::option::Option::Some(__kit_req);
+        }
+    });
+
     // Generate the transformed function
-    let output = if has_request_param {
-        // If we have a Request param, we need to handle it specially
+    let output = quote! {
+        #(#fn_attrs)*
+        #fn_vis #async_token fn #fn_name #fn_generics(__kit_req: kit::Request) #fn_output {
+            let __kit_extraction = kit::profile::start();
+            let __kit_params = __kit_req.params().clone();
+            #request_slot
+            #(#extractions)*
+            #(#request_extractions)*
+            kit::profile::record(kit::profile::Phase::Extraction, __kit_extraction);
+            #check
+            #fn_block
+        }
+    };
+
+    output.into()
+}
+
+/// Arguments of `#[authorize(Policy::ability[, param])]`
+struct Authorize {
+    policy: Path,
+    ability: Ident,
+    model: Option<Ident>,
+}
+
+impl Parse for Authorize {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut policy: Path = input.parse()?;
+        let ability = match policy.segments.pop() {
+            Some(ability) if !policy.segments.is_empty() => ability.into_value().ident,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &policy,
+                    "expected `Policy::ability`, e.g. `#[authorize(PostPolicy::update)]`",
+                ))
+            }
+        };
+        // `pop` leaves the trailing `::` behind
+        policy.segments.pop_punct();
+
+        let model = if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self {
+            policy,
+            ability,
+            model,
+        })
+    }
+}
+
+impl Authorize {
+    /// The `Gate::authorize` call, with the model parameter for abilities
+    /// that are about one
+    fn check(&self, first_resolved: Option<&str>) -> syn::Result<TokenStream2> {
+        let policy = &self.policy;
+        let variant = match self.ability.to_string().as_str() {
+            "view_any" => quote! { ViewAny },
+            "create" => quote! { Create },
+            "view" => quote! { View },
+            "update" => quote! { Update },
+            "delete" => quote! { Delete },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &self.ability,
+                    "unknown ability, expected view_any, view, create, update or delete",
+                ))
+            }
+        };
+        let ability = if matches!(self.ability.to_string().as_str(), "view_any" | "create") {
+            quote! { kit::Ability::#variant }
+        } else {
+            let model = match (&self.model, first_resolved) {
+                (Some(model), _) => quote! { #model },
+                (None, Some(name)) => {
+                    let name = Ident::new(name, self.ability.span());
+                    quote! { #name }
+                }
+                (None, None) => {
+                    return Err(syn::Error::new_spanned(
+                        &self.ability,
+                        "this ability needs a model, add a model parameter to the handler",
+                    ))
+                }
+            };
+            quote! { kit::Ability::#variant(&#model) }
+        };
+        Ok(quote! {
+            kit::Gate::authorize::<#policy>(#ability).await?;
+        })
+    }
+}
+
+/// Remove `#[authorize(...)]` from the handler's attributes and parse it,
+/// rejecting a misplaced `#[blocking]`
+fn take_authorize(attrs: &[Attribute]) -> syn::Result<(Option<Authorize>, Vec<Attribute>)> {
+    let mut authorize = None;
+    let mut rest = Vec::new();
+    for attr in attrs {
+        let last = attr.path().segments.last();
+        if last.is_some_and(|segment| segment.ident == "blocking") {
+            // Below #[handler] it would move the extraction to the blocking pool
+            return Err(syn::Error::new_spanned(
+                attr,
+                "#[blocking] must be placed above #[handler]",
+            ));
+        }
+        let is_authorize = last.is_some_and(|segment| segment.ident == "authorize");
+        if !is_authorize {
+            rest.push(attr.clone());
+        } else if authorize.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "only one #[authorize] is allowed per handler",
+            ));
+        } else {
+            authorize = Some(attr.parse_args::<Authorize>()?);
+        }
+    }
+    Ok((authorize, rest))
+}
+
+/// The body wrapped in an `IntoResponse` conversion
+///
+/// `Response` converts to itself; going through the conversion anyway lets
+/// `?_profile=1` time the handler body and serialization separately.
+fn response_conversion(input_fn: &ItemFn) -> Option<TokenStream2> {
+    let ReturnType::Type(_, ty) = &input_fn.sig.output else {
+        return None;
+    };
+
+    // `impl IntoResponse` can't be named, so it's left to inference
+    let named = !matches!(&**ty, Type::ImplTrait(_));
+    let block = &input_fn.block;
+
+    Some(if input_fn.sig.asyncness.is_some() {
+        let ty = if named {
+            quote! { #ty }
+        } else {
+            quote! { _ }
+        };
         quote! {
-            #(#fn_attrs)*
-            #fn_vis #async_token fn #fn_name #fn_generics(__kit_req: kit::Request) #fn_output {
-                let __kit_params = __kit_req.params().clone();
-                #(#extractions)*
-                #fn_block
+            {
+                kit::http::__handler_response::<#ty, _>(async move #block).await
             }
         }
     } else {
+        let closure_output = named.then(|| quote! { -> #ty });
         quote! {
-            #(#fn_attrs)*
-            #fn_vis #async_token fn #fn_name #fn_generics(__kit_req: kit::Request) #fn_output {
-                let __kit_params = __kit_req.params().clone();
-                #(#extractions)*
-                #fn_block
+            {
+                kit::http::__handler_response_sync(move || #closure_output #block)
             }
         }
-    };
-
-    output.into()
+    })
 }
 
 /// Extract the parameter name as a string from the pattern
@@ -166,10 +371,7 @@ fn classify_param_type(ty: &Type) -> ParamKind {
             if segments.len() == 1 && segments[0].ident == "Request" {
                 return ParamKind::Request;
             }
-            if segments.len() == 2
-                && segments[0].ident == "kit"
-                && segments[1].ident == "Request"
-            {
+            if segments.len() == 2 && segments[0].ident == "kit" && segments[1].ident == "Request" {
                 return ParamKind::Request;
             }
 
@@ -181,21 +383,13 @@ fn classify_param_type(ty: &Type) -> ParamKind {
                 }
             }
 
-            // Check for Model type (path ends with ::Model)
-            if let Some(last_segment) = segments.last() {
-                if last_segment.ident == "Model" && segments.len() >= 2 {
-                    return ParamKind::Model;
-                }
-            }
-
-            // Default to FormRequest for other types
-            ParamKind::FormRequest
+            ParamKind::Resolved
         }
-        _ => ParamKind::FormRequest,
+        _ => ParamKind::Resolved,
     }
 }
 
-/// Check if a type name is a primitive that should use FromParam
+/// Check if a type name is a primitive (or `kit::Slug`, `Uuid`) that should use FromParam
 fn is_primitive_type_name(name: &str) -> bool {
     matches!(
         name,
@@ -210,25 +404,19 @@ fn is_primitive_type_name(name: &str) -> bool {
             | "u128"
             | "usize"
             | "isize"
+            | "bool"
             | "String"
+            | "Slug"
+            | "Uuid"
     )
 }
 
 /// Generate extraction code for a parameter based on its classification
-fn generate_extraction(
-    pat: &Pat,
-    ty: &Type,
-    param_name: &str,
-    kind: &ParamKind,
-    has_consumer: &mut bool,
-    has_request: &mut bool,
-) -> TokenStream2 {
+fn generate_extraction(pat: &Pat, ty: &Type, param_name: &str, kind: &ParamKind) -> TokenStream2 {
     match kind {
         ParamKind::Request => {
-            *has_request = true;
-            *has_consumer = true;
             quote! {
-                let #pat: #ty = __kit_req;
+                let #pat: #ty = kit::http::__resolve::take_request(&mut __kit_req_slot, #param_name)?;
             }
         }
         ParamKind::Primitive => {
@@ -241,23 +429,27 @@ fn generate_extraction(
                 };
             }
         }
-        ParamKind::Model => {
-            // Route model binding using AutoRouteBinding trait
-            // The parameter name comes from the function signature
+        ParamKind::Resolved => {
+            // Route model binding if the type supports it, else FromRequest.
+            // The parameter name comes from the function signature; a
+            // `{name:column}` route placeholder binds by that column
             quote! {
                 let #pat: #ty = {
-                    let __value = __kit_params.get(#param_name)
-                        .ok_or_else(|| kit::FrameworkError::param(#param_name))?;
-                    <#ty as kit::AutoRouteBinding>::from_route_param(__value).await?
+                    #[allow(unused_imports)]
+                    use kit::http::__resolve::{
+                        ViaAutoRouteBinding as _, ViaFromRequest as _, ViaFromRequestRef as _,
+                        ViaRouteBinding as _,
+                    };
+                    (&&&&kit::http::__resolve::Resolve::<#ty>::new())
+                        .__kit_resolve(kit::http::__resolve::Source {
+                            name: #param_name,
+                            params: &__kit_params,
+                            binding_keys: &__kit_binding_keys,
+                            request: &mut __kit_req_slot,
+                        })
+                        .await?
                 };
             }
         }
-        ParamKind::FormRequest => {
-            // Use FromRequest trait (consumes request body)
-            *has_consumer = true;
-            quote! {
-                let #pat: #ty = <#ty as kit::FromRequest>::from_request(__kit_req).await?;
-            }
-        }
     }
 }
@@ -95,46 +95,281 @@ pub fn derive_inertia_props_impl(input: TokenStream) -> TokenStream {
         }
     };
 
-    let field_count = fields.len();
-    let field_names: Vec<_> = fields.iter().map(|f| &f.ident).collect();
-    let field_name_strings: Vec<_> = fields
+    let camel_case = match rename_all_camel_case(&input.attrs) {
+        Ok(camel_case) => camel_case,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Serialize through a mirror struct of references deriving `Serialize`,
+    // so `#[serde(...)]` attributes behave exactly as they would with serde
+    let container_attrs = input
+        .attrs
         .iter()
-        .map(|f| f.ident.as_ref().unwrap().to_string())
-        .collect();
+        .filter(|attr| attr.path().is_ident("serde"));
+    let rename_all = camel_case.then(|| quote! { #[serde(rename_all = "camelCase")] });
+    let mirror_params = generics.params.iter();
+    let mut serialize_generics = generics.clone();
+    for param in generics.type_params() {
+        let ident = &param.ident;
+        serialize_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#ident: ::kit::serde::Serialize));
+    }
+    let serialize_where = &serialize_generics.where_clause;
+    let mirror_fields = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let attrs = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("serde"));
+        quote! { #(#attrs)* #ident: &'__kit #ty, }
+    });
+    let field_names = fields.iter().map(|field| &field.ident);
+    let prop_key = if camel_case || serde_rename_all_camel_case(&input.attrs) {
+        quote! { ::kit::http::to_camel_case(name) }
+    } else {
+        quote! { name.to_string() }
+    };
 
     let expanded = quote! {
-        impl #impl_generics ::kit::serde::Serialize for #name #ty_generics #where_clause {
-            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
-            where
-                S: ::kit::serde::Serializer,
-            {
-                use ::kit::serde::ser::SerializeStruct;
-                let mut state = serializer.serialize_struct(stringify!(#name), #field_count)?;
-                #(
-                    state.serialize_field(#field_name_strings, &self.#field_names)?;
-                )*
-                state.end()
+        const _: () = {
+            #[allow(dead_code)]
+            #[derive(::kit::serde::Serialize)]
+            #[serde(crate = "::kit::serde")]
+            #(#container_attrs)*
+            #rename_all
+            struct __KitInertiaProps<'__kit, #(#mirror_params),*> #where_clause {
+                #(#mirror_fields)*
+                #[serde(flatten)]
+                __kit_computed: ::kit::serde_json::Map<::std::string::String, ::kit::serde_json::Value>,
+            }
+
+            impl #impl_generics ::kit::serde::Serialize for #name #ty_generics #serialize_where {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: ::kit::serde::Serializer,
+                {
+                    use ::kit::inertia::props::__NoComputedProps as _;
+                    use ::kit::serde::ser::Error as _;
+                    let computed = self
+                        .__kit_computed_props()
+                        .map_err(S::Error::custom)?
+                        .into_iter()
+                        .map(|(name, value)| {
+                            (<Self as ::kit::inertia::props::__PropKey>::__prop_key(name), value)
+                        })
+                        .collect();
+                    ::kit::serde::Serialize::serialize(
+                        &__KitInertiaProps {
+                            #(#field_names: &self.#field_names,)*
+                            __kit_computed: computed,
+                        },
+                        serializer,
+                    )
+                }
+            }
+
+            impl #impl_generics ::kit::inertia::props::__PropKey for #name #ty_generics #where_clause {
+                fn __prop_key(name: &'static str) -> ::std::string::String {
+                    #prop_key
+                }
+            }
+        };
+    };
+
+    expanded.into()
+}
+
+/// Whether the struct has `#[serde(rename_all = "camelCase")]`
+fn serde_rename_all_camel_case(attrs: &[syn::Attribute]) -> bool {
+    let mut camel_case = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let rule: LitStr = meta.value()?.parse()?;
+                camel_case = rule.value() == "camelCase";
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|_| Ok(()))?;
             }
+            Ok(())
+        });
+    }
+    camel_case
+}
+
+/// Whether the struct has `#[inertia(rename_all = "camelCase")]`
+fn rename_all_camel_case(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut camel_case = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("inertia")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("rename_all") {
+                return Err(meta.error("Unknown #[inertia] option, expected `rename_all`"));
+            }
+            let rule: LitStr = meta.value()?.parse()?;
+            match rule.value().as_str() {
+                "camelCase" => camel_case = true,
+                "snake_case" => camel_case = false,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        rule,
+                        "#[inertia(rename_all)] supports \"camelCase\" and \"snake_case\"",
+                    ))
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(camel_case)
+}
+
+/// Implementation for `#[computed_props]` on a props impl block
+///
+/// Strips `#[prop(computed)]` from the marked methods and adds the hidden
+/// `__kit_computed_props` method the `InertiaProps` serializer calls, plus
+/// `resolve()` when any of them are async.
+pub fn computed_props_impl(input: TokenStream) -> TokenStream {
+    let mut item = parse_macro_input!(input as syn::ItemImpl);
+
+    let mut sync_props = Vec::new();
+    let mut async_props = Vec::new();
+    for impl_item in &mut item.items {
+        let syn::ImplItem::Fn(method) = impl_item else {
+            continue;
+        };
+        let before = method.attrs.len();
+        method.attrs.retain(|attr| !is_computed_attr(attr));
+        if method.attrs.len() == before {
+            continue;
+        }
+
+        let sig = &method.sig;
+        let takes_only_self = matches!(
+            sig.inputs.first(),
+            Some(syn::FnArg::Receiver(receiver)) if receiver.reference.is_some() && receiver.mutability.is_none()
+        ) && sig.inputs.len() == 1;
+        if !takes_only_self || !sig.generics.params.is_empty() {
+            return syn::Error::new_spanned(
+                sig,
+                "#[prop(computed)] methods must take only `&self` and have no generics",
+            )
+            .to_compile_error()
+            .into();
+        }
+        if sig.asyncness.is_some() {
+            async_props.push(sig.ident.clone());
+        } else {
+            sync_props.push(sig.ident.clone());
+        }
+    }
+
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let self_ty = &item.self_ty;
+    let resolve = (!async_props.is_empty()).then(|| {
+        quote! {
+            /// Await the async computed props, for `inertia_response!`
+            pub async fn resolve(self) -> ::kit::inertia::Resolved<Self> {
+                let computed = async {
+                    ::core::result::Result::Ok::<_, ::kit::serde_json::Error>(::std::vec![
+                        #((stringify!(#async_props), ::kit::serde_json::to_value(self.#async_props().await)?),)*
+                    ])
+                }
+                .await;
+                ::kit::inertia::Resolved::new(self, computed)
+            }
+        }
+    });
+
+    let expanded = quote! {
+        #item
+
+        impl #impl_generics #self_ty #where_clause {
+            #[doc(hidden)]
+            pub fn __kit_computed_props(&self) -> ::kit::inertia::props::ComputedValues {
+                ::core::result::Result::Ok(::std::vec![
+                    #((stringify!(#sync_props), ::kit::serde_json::to_value(self.#sync_props())?),)*
+                ])
+            }
+
+            #resolve
         }
     };
 
     expanded.into()
 }
 
+/// Whether an attribute is `#[prop(computed)]`
+fn is_computed_attr(attr: &syn::Attribute) -> bool {
+    attr.path().is_ident("prop")
+        && attr
+            .parse_args::<syn::Ident>()
+            .is_ok_and(|ident| ident == "computed")
+}
+
 /// Implementation for the inertia_response! macro
 pub fn inertia_response_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as InertiaResponseInput);
+    match page_response(&input) {
+        Ok(page) => page.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implementation for the respond_with! macro
+///
+/// Same input as `inertia_response!`; clients that want JSON get the props
+/// as the response body instead of the page.
+pub fn respond_with_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as InertiaResponseInput);
+    let page = match page_response(&input) {
+        Ok(page) => page,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let props_expr = props_expr(&input);
+    let expanded = quote! {{
+        if ::kit::InertiaContext::wants_json_request() {
+            Ok(::kit::HttpResponse::json(#props_expr))
+        } else #page
+    }};
+    expanded.into()
+}
 
+/// The Inertia page response for `inertia_response!` and `respond_with!`
+fn page_response(input: &InertiaResponseInput) -> Result<proc_macro2::TokenStream, syn::Error> {
     let component_name = input.component.value();
     let component_lit = &input.component;
 
     // Validate the component exists at compile time
-    if let Err(err) = validate_component_exists(&component_name, component_lit.span()) {
-        return err.to_compile_error().into();
-    }
+    validate_component_exists(&component_name, component_lit.span())?;
+
+    let props_expr = props_expr(input);
 
-    // Generate props conversion based on props kind
-    let props_expr = match &input.props {
+    // Generate the appropriate expansion based on whether config is provided
+    let with_config = input.config.as_ref().map(|config| {
+        let config_expr = &config.expr;
+        quote! { .with_config(#config_expr) }
+    });
+
+    Ok(quote! {{
+        let props = #props_expr;
+        let url = ::kit::InertiaContext::current_path();
+        let response = ::kit::InertiaResponse::new(#component_lit, props, url)#with_config;
+
+        if ::kit::InertiaContext::is_inertia_request() {
+            Ok(response.to_json_response())
+        } else {
+            Ok(response.to_html_response())
+        }
+    }})
+}
+
+/// The props as a `serde_json::Value`
+fn props_expr(input: &InertiaResponseInput) -> proc_macro2::TokenStream {
+    match &input.props {
         PropsKind::Typed(expr) => {
             // Typed struct: serialize using serde_json::to_value
             quote! {
@@ -148,38 +383,7 @@ pub fn inertia_response_impl(input: TokenStream) -> TokenStream {
                 ::kit::serde_json::json!({#tokens})
             }
         }
-    };
-
-    // Generate the appropriate expansion based on whether config is provided
-    let expanded = if let Some(config) = input.config {
-        let config_expr = config.expr;
-        quote! {{
-            let props = #props_expr;
-            let url = ::kit::InertiaContext::current_path();
-            let response = ::kit::InertiaResponse::new(#component_lit, props, url)
-                .with_config(#config_expr);
-
-            if ::kit::InertiaContext::is_inertia_request() {
-                Ok(response.to_json_response())
-            } else {
-                Ok(response.to_html_response())
-            }
-        }}
-    } else {
-        quote! {{
-            let props = #props_expr;
-            let url = ::kit::InertiaContext::current_path();
-            let response = ::kit::InertiaResponse::new(#component_lit, props, url);
-
-            if ::kit::InertiaContext::is_inertia_request() {
-                Ok(response.to_json_response())
-            } else {
-                Ok(response.to_html_response())
-            }
-        }}
-    };
-
-    expanded.into()
+    }
 }
 
 fn validate_component_exists(component_name: &str, span: Span) -> Result<(), syn::Error> {
@@ -0,0 +1,79 @@
+//! `#[job]` attribute macro for queued background jobs
+//!
+//! Derives `Serialize`/`Deserialize` for the struct and registers it so
+//! queue workers can rebuild and run it from its stored payload.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput};
+
+pub fn job_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new_spanned(attr, "#[job] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(&input.generics, "#[job] structs cannot be generic")
+            .to_compile_error()
+            .into();
+    }
+
+    let runner_name = format_ident!("__kit_job_runner_{}", ident);
+    let failed_name = format_ident!("__kit_job_failed_{}", ident);
+
+    let expanded = quote! {
+        #[derive(::kit::serde::Serialize, ::kit::serde::Deserialize)]
+        #[serde(crate = "::kit::serde")]
+        #input
+
+        impl ::kit::queue::JobName for #ident {
+            const NAME: &'static str = concat!(module_path!(), "::", stringify!(#ident));
+        }
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        fn #runner_name(
+            __payload: &str,
+        ) -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = Result<(), ::kit::FrameworkError>> + Send>> {
+            let __job = ::kit::serde_json::from_str::<#ident>(__payload);
+            Box::pin(async move {
+                let __job = __job.map_err(|e| {
+                    ::kit::FrameworkError::internal(format!("Job payload deserialize error: {}", e))
+                })?;
+                <#ident as ::kit::queue::Job>::handle(&__job).await
+            })
+        }
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        fn #failed_name(
+            __payload: &str,
+            __error: ::kit::FrameworkError,
+        ) -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = Result<(), ::kit::FrameworkError>> + Send>> {
+            let __job = ::kit::serde_json::from_str::<#ident>(__payload);
+            Box::pin(async move {
+                let __job = __job.map_err(|e| {
+                    ::kit::FrameworkError::internal(format!("Job payload deserialize error: {}", e))
+                })?;
+                <#ident as ::kit::queue::Job>::failed(&__job, &__error).await;
+                Ok(())
+            })
+        }
+
+        ::kit::inventory::submit! {
+            ::kit::queue::registry::JobEntry {
+                name: <#ident as ::kit::queue::JobName>::NAME,
+                run: #runner_name,
+                failed: #failed_name,
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
@@ -2,6 +2,10 @@
 //!
 //! This macro simplifies writing tests that need database access by automatically
 //! setting up an in-memory SQLite database with migrations applied.
+//!
+//! `#[kit_test(transactional)]` migrates a shared database once per process
+//! and rolls back each test's transaction instead. `#[kit_test(seed = ...)]`
+//! runs seeders before the test body.
 
 use proc_macro::TokenStream;
 use quote::quote;
@@ -10,17 +14,26 @@ use syn::{parse_macro_input, FnArg, ItemFn, Pat, Type};
 /// Parse the macro attributes
 struct KitTestArgs {
     migrator: Option<syn::Path>,
+    transactional: bool,
+    seeds: Vec<syn::Path>,
 }
 
 impl syn::parse::Parse for KitTestArgs {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut migrator = None;
+        let mut transactional = false;
+        let mut seeds = Vec::new();
 
         while !input.is_empty() {
             let ident: syn::Ident = input.parse()?;
             if ident == "migrator" {
                 input.parse::<syn::Token![=]>()?;
                 migrator = Some(input.parse()?);
+            } else if ident == "transactional" {
+                transactional = true;
+            } else if ident == "seed" {
+                input.parse::<syn::Token![=]>()?;
+                seeds.push(input.parse()?);
             }
 
             if input.peek(syn::Token![,]) {
@@ -28,7 +41,11 @@ impl syn::parse::Parse for KitTestArgs {
             }
         }
 
-        Ok(Self { migrator })
+        Ok(Self {
+            migrator,
+            transactional,
+            seeds,
+        })
     }
 }
 
@@ -77,26 +94,44 @@ pub fn kit_test_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
     // Check if function takes TestDatabase parameter
     let db_param_name = find_db_param_name(&input_fn);
 
-    let setup_and_body = if let Some(param_name) = db_param_name {
-        // Function has TestDatabase parameter - bind it
+    // Bind the database to the test's parameter, or keep it alive unnamed
+    let db_binding = db_param_name.unwrap_or_else(|| syn::parse_quote!(_db));
+
+    let create_db = if args.transactional {
+        quote! { ::kit::testing::TestDatabase::transactional::<#migrator_type>() }
+    } else {
+        quote! { ::kit::testing::TestDatabase::fresh::<#migrator_type>() }
+    };
+
+    // Seeders run in order, after migrations and before the body
+    let seeds = &args.seeds;
+    let setup = quote! {
+        // Bootstrap services so #[injectable] types are available
+        ::kit::App::init();
+        ::kit::App::boot_services();
+        let #db_binding = #create_db
+            .await
+            .expect("Failed to set up test database");
+        #(
+            ::kit::Seeder::run(&#seeds, #db_binding.db())
+                .await
+                .expect(concat!("Seeder ", stringify!(#seeds), " failed"));
+        )*
+    };
+
+    let setup_and_body = if args.transactional {
+        // The body runs in its own block so an early `return` still rolls back
         quote! {
-            // Bootstrap services so #[injectable] types are available
-            ::kit::App::init();
-            ::kit::App::boot_services();
-            let #param_name = ::kit::testing::TestDatabase::fresh::<#migrator_type>()
+            #setup
+            async #fn_block.await;
+            #db_binding
+                .rollback()
                 .await
-                .expect("Failed to set up test database");
-            #fn_block
+                .expect("Failed to roll back test database");
         }
     } else {
-        // No TestDatabase parameter - still set up but don't bind
         quote! {
-            // Bootstrap services so #[injectable] types are available
-            ::kit::App::init();
-            ::kit::App::boot_services();
-            let _db = ::kit::testing::TestDatabase::fresh::<#migrator_type>()
-                .await
-                .expect("Failed to set up test database");
+            #setup
             #fn_block
         }
     };
@@ -6,19 +6,33 @@
 //! - Service auto-registration
 //! - Handler attribute for controller methods
 //! - FormRequest for validated request data
-//! - Jest-like testing with describe! and test! macros
+//! - MapFrom for copying models into props and resources
+//! - Application console commands
+//! - Queued background jobs
+//! - Event listeners
+//! - Unique slugs for models
+//! - Jest-like testing with describe!, test! and test_each! macros
 
 use proc_macro::TokenStream;
 
+mod blocking;
+mod console_command;
 mod describe;
 mod domain_error;
 mod handler;
 mod inertia;
 mod injectable;
+mod job;
 mod kit_test;
+mod listener;
+mod map_from;
 mod redirect;
+mod report;
 mod request;
+mod route_source;
 mod service;
+mod sluggable;
+mod test_each;
 mod test_macro;
 mod utils;
 mod workflow;
@@ -26,20 +40,98 @@ mod workflow_step;
 
 /// Derive macro for generating `Serialize` implementation for Inertia props
 ///
+/// Fields keep their Rust names unless the struct has
+/// `#[inertia(rename_all = "camelCase")]`, which `kit generate-types`
+/// also applies to the TypeScript interface. `#[serde(...)]` attributes
+/// (`rename`, `skip`, `skip_serializing_if`, `serialize_with`, ...) work as
+/// they do with `#[derive(Serialize)]`, and methods marked
+/// `#[prop(computed)]` in a `#[computed_props]` impl are serialized too.
+///
 /// # Example
 ///
 /// ```rust,ignore
 /// #[derive(InertiaProps)]
+/// #[inertia(rename_all = "camelCase")]
 /// struct HomeProps {
 ///     title: String,
-///     user: User,
+///     current_user: User, // sent as `currentUser`
+///     #[serde(skip_serializing_if = "Option::is_none")]
+///     flash: Option<String>,
 /// }
 /// ```
-#[proc_macro_derive(InertiaProps)]
+#[proc_macro_derive(InertiaProps, attributes(inertia, serde))]
 pub fn derive_inertia_props(input: TokenStream) -> TokenStream {
     inertia::derive_inertia_props_impl(input)
 }
 
+/// Add computed props to an `InertiaProps` struct
+///
+/// Methods marked `#[prop(computed)]` take `&self` and return anything
+/// `Serialize`; they're sent under the method name (following the struct's
+/// `rename_all`). Sync methods run whenever the props are serialized. Async
+/// methods need `props.resolve().await`, which awaits them and returns
+/// props ready for `inertia_response!`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[computed_props]
+/// impl UserProps {
+///     #[prop(computed)]
+///     fn full_name(&self) -> String {
+///         format!("{} {}", self.first_name, self.last_name)
+///     }
+///
+///     #[prop(computed)]
+///     async fn unread_count(&self) -> u64 {
+///         Notification::unread_for(self.id).await.unwrap_or(0)
+///     }
+/// }
+///
+/// inertia_response!("Profile", props.resolve().await)
+/// ```
+#[proc_macro_attribute]
+pub fn computed_props(_attr: TokenStream, input: TokenStream) -> TokenStream {
+    inertia::computed_props_impl(input)
+}
+
+/// Derive `From<Source>` for props, API resources and requests
+///
+/// Fields are copied from the source field of the same name through
+/// `Into`, so `i32` to `i64` or `String` to `Option<String>` just work.
+/// List several sources in `#[map_from(...)]` to get an impl for each.
+///
+/// Field options:
+/// - `#[map_from(rename = "name")]` - copy from a differently named field
+/// - `#[map_from(with = path::to::fn)]` - convert the field with `fn(value) -> T`
+/// - `#[map_from(skip)]` - use `Default::default()`
+/// - `#[map_from(compute = path::to::fn)]` - build the value from `&Source`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{InertiaProps, MapFrom};
+///
+/// #[derive(InertiaProps, MapFrom)]
+/// #[map_from(users::Model)]
+/// pub struct UserProps {
+///     pub id: i64,
+///     #[map_from(rename = "name")]
+///     pub display_name: String,
+///     #[map_from(with = format_date)]
+///     pub created_at: String,
+///     #[map_from(skip)]
+///     pub is_admin: bool,
+/// }
+///
+/// let props = UserProps::from(user);
+/// let list: Vec<UserProps> = users.into_iter().map(UserProps::from).collect();
+/// ```
+#[proc_macro_derive(MapFrom, attributes(map_from))]
+pub fn derive_map_from(input: TokenStream) -> TokenStream {
+    map_from::derive_map_from_impl(input)
+}
+
 /// Create an Inertia response with compile-time component validation
 ///
 /// # Examples
@@ -62,11 +154,35 @@ pub fn derive_inertia_props(input: TokenStream) -> TokenStream {
 ///
 /// This macro validates that the component file exists at compile time.
 /// If `frontend/src/pages/Dashboard.tsx` doesn't exist, you'll get a compile error.
+///
+/// Props shared by middleware with `InertiaContext::share` are added to the
+/// page's props.
 #[proc_macro]
 pub fn inertia_response(input: TokenStream) -> TokenStream {
     inertia::inertia_response_impl(input)
 }
 
+/// Respond with an Inertia page to browsers and JSON to API clients
+///
+/// Takes the same arguments as `inertia_response!`. Requests that want JSON
+/// (`Accept: application/json` or a non-Inertia XHR, see
+/// `Request::wants_json`) get the props as the response body; page loads
+/// and Inertia visits get the page.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[handler]
+/// pub async fn index() -> Response {
+///     let users = User::query().all().await?;
+///     respond_with!("Users/Index", UsersProps { users })
+/// }
+/// ```
+#[proc_macro]
+pub fn respond_with(input: TokenStream) -> TokenStream {
+    inertia::respond_with_impl(input)
+}
+
 /// Create a redirect to a named route with compile-time validation
 ///
 /// # Examples
@@ -75,15 +191,32 @@ pub fn inertia_response(input: TokenStream) -> TokenStream {
 /// // Simple redirect
 /// redirect!("users.index").into()
 ///
-/// // Redirect with route parameters
-/// redirect!("users.show").with("id", "42").into()
+/// // Redirect with route parameters, checked against the route's path
+/// redirect!("users.show", id = user.id).into()
 ///
 /// // Redirect with query parameters
 /// redirect!("users.index").query("page", "1").into()
+///
+/// // Status codes
+/// redirect!("users.index").permanent().into() // 301
+/// redirect!("users.index").status(303).into()
+///
+/// // External or computed URLs (not validated)
+/// redirect!(to: "https://example.com/billing").query_map(params).into()
 /// ```
 ///
 /// This macro validates that the route name exists at compile time.
 /// If the route doesn't exist, you'll get a compile error with suggestions.
+/// Parameters passed to the macro must match the `{name}` segments of the
+/// route's path: missing or unknown parameters are compile errors. A route
+/// with parameters and none passed gets a warning, since `.with()` after the
+/// macro is only checked at runtime.
+/// Names are read from `src/routes.rs`, or the files matching
+/// `routes = [...]` globs under `[package.metadata.kit]` in Cargo.toml, plus
+/// the manifest written by `kit route:cache`.
+///
+/// Query parameters are merged into any query the URL already has, replacing
+/// parameters of the same name.
 #[proc_macro]
 pub fn redirect(input: TokenStream) -> TokenStream {
     redirect::redirect_impl(input)
@@ -106,11 +239,20 @@ pub fn redirect(input: TokenStream) -> TokenStream {
 /// }
 ///
 /// // This expands to:
+/// #[async_trait]
 /// pub trait HttpClient: Send + Sync + 'static {
 ///     async fn get(&self, url: &str) -> Result<String, Error>;
 /// }
 /// ```
 ///
+/// `#[async_trait]` is added when the trait has async methods and doesn't
+/// use it already. Default method bodies and generics are kept as written.
+///
+/// Services are resolved as `dyn Trait`, so methods that can't be called
+/// through `dyn` (no `self` receiver, generic type parameters, `Self` or
+/// `impl Trait` in the signature) are reported at the method. Add
+/// `where Self: Sized` to such a method to keep it on the trait anyway.
+///
 /// Then you can use it with the App container:
 ///
 /// ```rust,ignore
@@ -120,6 +262,28 @@ pub fn redirect(input: TokenStream) -> TokenStream {
 /// // Resolve
 /// let client: Arc<dyn HttpClient> = App::make::<dyn HttpClient>().unwrap();
 /// ```
+///
+/// `#[service(mock)]` also generates `MockHttpClient`, whose methods return
+/// whatever the closures passed to `on_get(...)` etc. compute:
+///
+/// ```rust,ignore
+/// #[service(mock)]
+/// pub trait HttpClient {
+///     async fn get(&self, url: &str) -> Result<String, Error>;
+/// }
+///
+/// let _guard = TestContainer::fake();
+/// MockHttpClient::new().on_get(|_url| Ok("{}".to_string())).bind();
+/// ```
+///
+/// In tests, `TestContainer::spy::<dyn HttpClient>()` wraps the binding in a
+/// generated proxy that records every call:
+///
+/// ```rust,ignore
+/// let client = TestContainer::spy::<dyn HttpClient>();
+/// // ... run the code under test ...
+/// client.assert_called("get").times(1).with(&["\"https://example.com\""]);
+/// ```
 #[proc_macro_attribute]
 pub fn service(attr: TokenStream, input: TokenStream) -> TokenStream {
     service::service_impl(attr, input)
@@ -223,11 +387,90 @@ pub fn domain_error(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///     json_response!({ "status": "ok" })
 /// }
 /// ```
+///
+/// ## Other return types:
+///
+/// Handlers may return any `IntoResponse` type; the value is converted after
+/// the body runs, and `?` converts errors into the declared error type.
+///
+/// ```rust,ignore
+/// use kit::{handler, FrameworkError, Json};
+///
+/// #[handler]
+/// pub async fn show(id: i32) -> Result<User, FrameworkError> {
+///     User::find(id).await // serialized as JSON
+/// }
+///
+/// #[handler]
+/// pub async fn store(form: CreateUserRequest) -> (u16, Json<User>) {
+///     (201, Json(User::create(form).await))
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn handler(attr: TokenStream, input: TokenStream) -> TokenStream {
     handler::handler_impl(attr, input)
 }
 
+/// Check a policy before a handler runs, see `kit::Policy`
+///
+/// Read by `#[handler]`, so it must be placed below it. The model is the
+/// handler's first bound parameter unless one is named; guests and denied
+/// users get a 403.
+///
+/// ```rust,ignore
+/// #[handler]
+/// #[authorize(PostPolicy::update)]
+/// pub async fn update(post: Post, form: UpdatePostRequest) -> Response { ... }
+///
+/// #[handler]
+/// #[authorize(CommentPolicy::delete, comment)]
+/// pub async fn destroy(post: Post, comment: Comment) -> Response { ... }
+///
+/// #[handler]
+/// #[authorize(PostPolicy::create)]
+/// pub async fn store(form: CreatePostRequest) -> Response { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn authorize(_attr: TokenStream, input: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+    let error = syn::Error::new(
+        proc_macro2::Span::call_site(),
+        "#[authorize] must be placed below #[handler]",
+    )
+    .to_compile_error();
+    quote::quote! { #error #input }.into()
+}
+
+/// Run a synchronous function on the blocking thread pool, see `kit::blocking`
+///
+/// The function becomes `async`; its arguments are moved to the blocking
+/// thread, so they must be `Send + 'static`, and methods take `self` by
+/// value. A panic in the body is returned through `?` as an internal error,
+/// so the return type must be a `Result` whose error converts from
+/// `FrameworkError` (such as `Response`), or `()`.
+///
+/// On handlers it goes above `#[handler]`, so parameters are extracted on
+/// the runtime before the body moves to the blocking pool.
+///
+/// ```rust,ignore
+/// #[blocking]
+/// #[handler]
+/// pub fn thumbnail(upload: UploadedFile) -> Response {
+///     let image = image::load_from_memory(&upload.bytes())?;
+///     // ...
+/// }
+///
+/// #[blocking]
+/// fn checksum(path: PathBuf) -> Result<String, FrameworkError> {
+///     let bytes = std::fs::read(path)?;
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn blocking(attr: TokenStream, input: TokenStream) -> TokenStream {
+    blocking::blocking_impl(attr, input)
+}
+
 /// Derive macro for FormRequest trait
 ///
 /// Generates the `FormRequest` trait implementation for a struct.
@@ -250,7 +493,7 @@ pub fn handler(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///     pub password: String,
 /// }
 /// ```
-#[proc_macro_derive(FormRequest)]
+#[proc_macro_derive(FormRequest, attributes(transform, file))]
 pub fn derive_form_request(input: TokenStream) -> TokenStream {
     request::derive_request_impl(input)
 }
@@ -285,6 +528,17 @@ pub fn derive_form_request(input: TokenStream) -> TokenStream {
 ///     json_response!({ "email": form.email })
 /// }
 /// ```
+///
+/// Fields marked `#[transform(sanitize_html)]` are run through
+/// `kit::sanitize_html` before validation; pass a policy with
+/// `#[transform(sanitize_html(strict))]` (`strict`, `basic` or `relaxed`).
+///
+/// `#[validate(custom = "unique_email")]` runs the async rule registered
+/// with `kit::Rules::register("unique_email", ...)`, for checks that need
+/// the database.
+///
+/// Mark struct and `Vec` fields `#[validate(nested)]` to validate their
+/// items too; errors are keyed like `items.2.price`.
 #[proc_macro_attribute]
 pub fn request(attr: TokenStream, input: TokenStream) -> TokenStream {
     request::request_attr_impl(attr, input)
@@ -333,11 +587,175 @@ pub fn request(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///     // Uses custom migrator instead of default
 /// }
 /// ```
+///
+/// ## Transactional:
+/// ```rust,ignore
+/// #[kit_test(transactional)]
+/// async fn test_with_shared_database(db: TestDatabase) {
+///     // Migrations ran once for the whole test process; everything this
+///     // test writes is rolled back when it finishes
+/// }
+/// ```
+///
+/// Transactional tests run one at a time and can't open their own
+/// transactions. See `TestDatabase::transactional`.
+///
+/// ## With seeders:
+/// ```rust,ignore
+/// #[kit_test(seed = seeders::BasicUsers, seed = seeders::Posts)]
+/// async fn test_with_seed_data(db: TestDatabase) {
+///     // Both seeders ran, in order, before the body
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn kit_test(attr: TokenStream, input: TokenStream) -> TokenStream {
     kit_test::kit_test_impl(attr, input)
 }
 
+/// Register an application console command
+///
+/// The annotated struct must derive `clap::Args` (its fields become the
+/// command's arguments) and implement `ConsoleCommand`. The command runs
+/// after config and bootstrap, so services in the App container are available.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{async_trait, console_command, ConsoleCommand, FrameworkError};
+///
+/// #[console_command(name = "reports:send", description = "Email the daily reports")]
+/// #[derive(clap::Args)]
+/// pub struct SendReports {
+///     #[arg(long)]
+///     since: Option<String>,
+/// }
+///
+/// #[async_trait]
+/// impl ConsoleCommand for SendReports {
+///     async fn handle(&self) -> Result<(), FrameworkError> {
+///         Ok(())
+///     }
+/// }
+///
+/// // cargo run -- reports:send --since=2024-01-01
+/// ```
+#[proc_macro_attribute]
+pub fn console_command(attr: TokenStream, input: TokenStream) -> TokenStream {
+    console_command::console_command_impl(attr, input)
+}
+
+/// Register a queued background job
+///
+/// Derives `Serialize` and `Deserialize` for the struct, so its fields must
+/// be serializable. The struct must implement `kit::queue::Job`. Dispatch it
+/// with `dispatch!` and run it with `kit queue:work`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{async_trait, dispatch, job, FrameworkError};
+/// use kit::queue::Job;
+///
+/// #[job]
+/// pub struct SendWelcomeEmail {
+///     pub user_id: i64,
+/// }
+///
+/// #[async_trait]
+/// impl Job for SendWelcomeEmail {
+///     async fn handle(&self) -> Result<(), FrameworkError> {
+///         Ok(())
+///     }
+/// }
+///
+/// dispatch!(SendWelcomeEmail { user_id: 1 }).await?;
+/// ```
+#[proc_macro_attribute]
+pub fn job(attr: TokenStream, input: TokenStream) -> TokenStream {
+    job::job_impl(attr, input)
+}
+
+/// Register a PDF report
+///
+/// Derives `Serialize` and `Deserialize` for the struct, so its fields must
+/// be serializable. The struct must implement `kit::Report`; queued reports
+/// are rebuilt from their fields by the queue worker.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{async_trait, report, FrameworkError, Report};
+///
+/// #[report]
+/// pub struct SalesReport {
+///     pub month: u32,
+/// }
+///
+/// #[async_trait]
+/// impl Report for SalesReport {
+///     async fn html(&self) -> Result<String, FrameworkError> {
+///         Ok(format!("<h1>Sales for {}</h1>", self.month))
+///     }
+///
+///     fn filename(&self) -> String {
+///         format!("sales-{}.pdf", self.month)
+///     }
+/// }
+///
+/// SalesReport { month: 5 }.queue().await?;
+/// ```
+#[proc_macro_attribute]
+pub fn report(attr: TokenStream, input: TokenStream) -> TokenStream {
+    report::report_impl(attr, input)
+}
+
+/// Register a function as an event listener
+///
+/// The function takes the event by reference and may be sync or async,
+/// returning `()` or `Result<(), FrameworkError>`. It runs whenever
+/// `Event::dispatch` is called with that event type.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{listener, FrameworkError};
+///
+/// #[listener]
+/// async fn send_welcome_email(event: &UserRegistered) -> Result<(), FrameworkError> {
+///     mailer::welcome(event.user_id).await
+/// }
+///
+/// #[listener]
+/// fn log_registration(event: &UserRegistered) {
+///     println!("user {} registered", event.user_id);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn listener(attr: TokenStream, input: TokenStream) -> TokenStream {
+    listener::listener_impl(attr, input)
+}
+
+/// Fill a model's slug column on insert
+///
+/// Place on the model's `ActiveModelBehavior` impl. Before each insert the
+/// `column` (default `slug`) is set to a slug of the `source` column, with a
+/// `-2`, `-3`, ... suffix if it is already taken. A slug set explicitly is
+/// kept as is.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // src/models/posts.rs
+/// pub use super::entities::posts::*;
+///
+/// #[kit::sluggable(source = "title")]
+/// impl ActiveModelBehavior for ActiveModel {}
+/// ```
+#[proc_macro_attribute]
+pub fn sluggable(attr: TokenStream, input: TokenStream) -> TokenStream {
+    sluggable::sluggable_impl(attr, input)
+}
+
 /// Attribute macro for defining durable workflows
 #[proc_macro_attribute]
 pub fn workflow(attr: TokenStream, input: TokenStream) -> TokenStream {
@@ -376,6 +794,40 @@ pub fn workflow_step(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///     });
 /// });
 /// ```
+///
+/// # Hooks
+///
+/// `before_each!`, `after_each!` and `before_all!` blocks are woven into every
+/// `test!` of the describe, including those in nested describes:
+///
+/// - `before_each!` runs at the start of each test, in the test's own scope:
+///   its bindings, such as a `TestContainer::fake()` guard or the test's
+///   `db`, are visible to the test body.
+/// - `after_each!` runs after the body, even when it panics; the panic is
+///   raised again once the hooks have run, so the test still fails.
+/// - `before_all!` runs once, before the first test of the describe.
+///
+/// Hooks may `.await` in async tests. A parent's `before_*` hooks run before
+/// a nested describe's, and its `after_each!` hooks after.
+///
+/// ```rust,ignore
+/// describe!("CreateTodoAction", {
+///     before_all!(async {
+///         load_test_config().await;
+///     });
+///
+///     before_each!({
+///         let _container = TestContainer::fake();
+///         TestContainer::singleton(FakeMailer::new());
+///         let user = CreateUserAction::new().execute("ada@example.com").await.unwrap();
+///     });
+///
+///     test!("creates a todo", async fn(db: TestDatabase) {
+///         let todo = CreateTodoAction::new().execute(&user, "Write docs").await.unwrap();
+///         expect!(todo.user_id).to_equal(user.id);
+///     });
+/// });
+/// ```
 #[proc_macro]
 pub fn describe(input: TokenStream) -> TokenStream {
     describe::describe_impl(input)
@@ -411,6 +863,14 @@ pub fn describe(input: TokenStream) -> TokenStream {
 /// });
 /// ```
 ///
+/// ## Attributes
+/// Attributes before `fn` go on the generated test function:
+/// ```rust,ignore
+/// test!("rejects a zero divisor", #[should_panic] fn() {
+///     divide(1, 0);
+/// });
+/// ```
+///
 /// On failure, the test name is shown:
 /// ```text
 /// Test: "creates a user"
@@ -425,3 +885,33 @@ pub fn describe(input: TokenStream) -> TokenStream {
 pub fn test(input: TokenStream) -> TokenStream {
     test_macro::test_impl(input)
 }
+
+/// Define one test per case of a table
+///
+/// Each case is a tuple with one value per parameter (or a single value when
+/// there is one parameter). Parameter types are optional and inferred from
+/// the values. `{0}`, `{1}`, ... in the name are replaced with the case's
+/// values, so each case shows up under its own name in the test output and
+/// in `expect!` failures. A `TestDatabase` parameter works as in `test!`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kit::{expect, test_each};
+///
+/// test_each!([(1, 2, 3), (2, 2, 4)], "adds {0} and {1}", async fn(a, b, expected) {
+///     expect!(add(a, b).await).to_equal(expected);
+/// });
+///
+/// test_each!(["ada", "ada@"], "rejects {0}", fn(email: &str) {
+///     expect!(validate_email(email).is_err()).to_be_true();
+/// });
+/// ```
+///
+/// This generates `adds_1_and_2` and `adds_2_and_2` (shown as
+/// `"adds 1 and 2"`, ...). Inside `describe!`, the describe's hooks apply to
+/// every case.
+#[proc_macro]
+pub fn test_each(input: TokenStream) -> TokenStream {
+    test_each::test_each_impl(input)
+}
@@ -0,0 +1,81 @@
+//! `#[listener]` attribute macro for event listeners
+//!
+//! Registers a function taking `&SomeEvent` so `Event::dispatch` calls it
+//! for every dispatched `SomeEvent`.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, FnArg, ItemFn, Type};
+
+pub fn listener_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new_spanned(attr, "#[listener] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let input = parse_macro_input!(input as ItemFn);
+    let sig = &input.sig;
+    let fn_name = &sig.ident;
+
+    if !sig.generics.params.is_empty() {
+        return syn::Error::new_spanned(&sig.generics, "#[listener] functions cannot be generic")
+            .to_compile_error()
+            .into();
+    }
+
+    let event_ty = match (sig.inputs.len(), sig.inputs.first()) {
+        (1, Some(FnArg::Typed(arg))) => match arg.ty.as_ref() {
+            Type::Reference(reference) if reference.mutability.is_none() => {
+                Some(reference.elem.as_ref().clone())
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some(event_ty) = event_ty else {
+        return syn::Error::new_spanned(
+            &sig.inputs,
+            "#[listener] functions take the event by reference, e.g. `fn(event: &UserRegistered)`",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let handler_name = format_ident!("__kit_listener_{}", fn_name);
+    let call = if sig.asyncness.is_some() {
+        quote! { #fn_name(&__event).await }
+    } else {
+        quote! { #fn_name(&__event) }
+    };
+
+    let expanded = quote! {
+        #input
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        fn #handler_name(
+            __event: ::std::sync::Arc<dyn ::std::any::Any + Send + Sync>,
+        ) -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = Result<(), ::kit::FrameworkError>> + Send>> {
+            Box::pin(async move {
+                let __event = __event.downcast::<#event_ty>().map_err(|_| {
+                    ::kit::FrameworkError::internal(concat!(
+                        "Listener ", stringify!(#fn_name), " received the wrong event type"
+                    ))
+                })?;
+                ::kit::events::registry::ListenerResult::into_result(#call)
+            })
+        }
+
+        ::kit::inventory::submit! {
+            ::kit::events::registry::ListenerEntry {
+                event: ::std::any::TypeId::of::<#event_ty>,
+                name: concat!(module_path!(), "::", stringify!(#fn_name)),
+                handle: #handler_name,
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
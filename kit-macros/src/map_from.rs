@@ -0,0 +1,205 @@
+//! MapFrom derive macro implementation
+//!
+//! Generates `From<Source>` impls that copy a source struct (usually a
+//! SeaORM `Model`) into props, resources or requests field by field.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Ident, Lit, Path, Token, Type};
+
+/// How a target field gets its value
+enum FieldSource {
+    /// `source.<name>`, optionally passed through a function
+    Field { name: Ident, with: Option<Path> },
+    /// `Default::default()`
+    Skip,
+    /// A function of the whole source, called before any field is moved
+    Compute(Path),
+}
+
+pub fn derive_map_from_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match map_from_impls(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn map_from_impls(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "MapFrom can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "MapFrom can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut sources: Vec<Type> = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("map_from")) {
+        sources.extend(attr.parse_args_with(Punctuated::<Type, Token![,]>::parse_terminated)?);
+    }
+    if sources.is_empty() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "missing source type, add `#[map_from(Source)]` to the struct",
+        ));
+    }
+
+    let mut computed = Vec::new();
+    let mut inits = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let value = match field_source(field)? {
+            FieldSource::Field { name, with: None } => {
+                quote! { ::std::convert::Into::into(source.#name) }
+            }
+            FieldSource::Field {
+                name,
+                with: Some(with),
+            } => quote! { #with(source.#name) },
+            FieldSource::Skip => quote! { ::std::default::Default::default() },
+            FieldSource::Compute(compute) => {
+                let binding = quote::format_ident!("__computed_{}", ident);
+                computed.push(quote! { let #binding = #compute(&source); });
+                quote! { #binding }
+            }
+        };
+        inits.push(quote! { #ident: #value });
+    }
+
+    let impls = sources.iter().map(|source_ty| {
+        quote! {
+            impl #impl_generics ::std::convert::From<#source_ty> for #name #ty_generics #where_clause {
+                fn from(source: #source_ty) -> Self {
+                    #(#computed)*
+                    Self {
+                        #(#inits,)*
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(quote! { #(#impls)* })
+}
+
+/// Read the `#[map_from(...)]` options of a field
+///
+/// Supported options:
+/// - `rename = "name"` - copy from the source field `name`
+/// - `with = path::to::fn` - convert the source field with `fn(value) -> T`
+/// - `skip` - use `Default::default()`
+/// - `compute = path::to::fn` - build the value with `fn(&Source) -> T`
+fn field_source(field: &syn::Field) -> syn::Result<FieldSource> {
+    let mut name = field.ident.clone().expect("named field");
+    let mut with = None;
+    let mut skip = false;
+    let mut compute = None;
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("map_from")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("rename") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                name = value.parse()?;
+            } else if meta.path.is_ident("with") {
+                with = Some(path_value(meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("compute") {
+                compute = Some(path_value(meta.value()?.parse()?)?);
+            } else {
+                return Err(
+                    meta.error("unknown option, expected `rename`, `with`, `skip` or `compute`")
+                );
+            }
+            Ok(())
+        })?;
+    }
+
+    match (skip, compute) {
+        (true, Some(_)) => Err(syn::Error::new_spanned(
+            field,
+            "`skip` and `compute` can't be combined",
+        )),
+        (true, None) => Ok(FieldSource::Skip),
+        (false, Some(compute)) => Ok(FieldSource::Compute(compute)),
+        (false, None) => Ok(FieldSource::Field { name, with }),
+    }
+}
+
+/// A function path, written bare (`with = fmt::date`) or quoted (`with = "fmt::date"`)
+fn path_value(expr: Expr) -> syn::Result<Path> {
+    match expr {
+        Expr::Path(path) => Ok(path.path),
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(path) => path.parse(),
+            _ => Err(syn::Error::new_spanned(lit, "expected a function path")),
+        },
+        other => Err(syn::Error::new_spanned(other, "expected a function path")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_from_impls() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[map_from(users::Model, admins::Model)]
+            struct UserProps {
+                id: i64,
+                #[map_from(rename = "name")]
+                display_name: String,
+                #[map_from(with = format_date)]
+                created_at: String,
+                #[map_from(skip)]
+                is_admin: bool,
+                #[map_from(compute = "initials")]
+                initials: String,
+            }
+        };
+
+        let generated = map_from_impls(&input).unwrap().to_string();
+        assert!(generated.contains("From < users :: Model > for UserProps"));
+        assert!(generated.contains("From < admins :: Model > for UserProps"));
+        assert!(generated.contains("id : :: std :: convert :: Into :: into (source . id)"));
+        assert!(
+            generated.contains("display_name : :: std :: convert :: Into :: into (source . name)")
+        );
+        assert!(generated.contains("created_at : format_date (source . created_at)"));
+        assert!(generated.contains("is_admin : :: std :: default :: Default :: default ()"));
+        assert!(generated.contains("let __computed_initials = initials (& source) ;"));
+
+        let input: DeriveInput = syn::parse_quote! {
+            #[map_from(users::Model)]
+            struct UserProps {
+                #[map_from(flatten)]
+                id: i64,
+            }
+        };
+        assert!(map_from_impls(&input).is_err());
+
+        let input: DeriveInput = syn::parse_quote! {
+            struct UserProps {
+                id: i64,
+            }
+        };
+        assert!(map_from_impls(&input).is_err());
+    }
+}
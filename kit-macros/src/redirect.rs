@@ -1,100 +1,249 @@
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
-use std::path::PathBuf;
-use syn::{parse::Parse, parse::ParseStream, parse_macro_input, LitStr};
+use quote::{quote, quote_spanned};
+use std::path::Path;
+use syn::punctuated::Punctuated;
+use syn::{parse::Parse, parse::ParseStream, parse_macro_input, Expr, Ident, LitStr, Token};
 
+use crate::route_source::{known_routes, path_params, KnownRoute};
 use crate::utils::levenshtein_distance;
 
 /// Custom parser for redirect! macro
-pub struct RedirectInput {
-    pub route_name: LitStr,
+///
+/// Either a route name with optional parameters,
+/// `redirect!("users.show", id = user.id)`, or a URL,
+/// `redirect!(to: "https://example.com")`.
+pub enum RedirectInput {
+    Route {
+        name: LitStr,
+        params: Vec<RouteParam>,
+    },
+    Url(Expr),
+}
+
+/// A `name = value` route parameter
+pub struct RouteParam {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for RouteParam {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(RouteParam { name, value })
+    }
 }
 
 impl Parse for RedirectInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        Ok(RedirectInput {
-            route_name: input.parse()?,
-        })
+        if input.peek(Ident) && input.peek2(Token![:]) {
+            let key: Ident = input.parse()?;
+            if key != "to" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "expected a route name or `to: url`",
+                ));
+            }
+            input.parse::<Token![:]>()?;
+            return Ok(RedirectInput::Url(input.parse()?));
+        }
+
+        let name = input.parse()?;
+        let mut params = Vec::new();
+        if input.parse::<Option<Token![,]>>()?.is_some() {
+            params = Punctuated::<RouteParam, Token![,]>::parse_terminated(input)?
+                .into_iter()
+                .collect();
+        }
+        Ok(RedirectInput::Route { name, params })
     }
 }
 
 /// Implementation for the redirect! macro
 pub fn redirect_impl(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as RedirectInput);
-    let route_name = input.route_name.value();
-    let route_lit = &input.route_name;
+    let (route_lit, params) = match parse_macro_input!(input as RedirectInput) {
+        RedirectInput::Route { name, params } => (name, params),
+        // URLs aren't validated, so they can be external or computed
+        RedirectInput::Url(url) => {
+            return quote! {
+                ::kit::Redirect::to(#url)
+            }
+            .into();
+        }
+    };
+    let route_name = route_lit.value();
 
-    // Validate the route exists at compile time
-    if let Err(err) = validate_route_exists(&route_name, route_lit.span()) {
-        return err.to_compile_error().into();
-    }
+    // Validate the route and its parameters at compile time
+    let route = match validate_route_exists(&route_name, route_lit.span()) {
+        Ok(route) => route,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let warning = match route
+        .as_ref()
+        .map(|route| check_params(route, &params, route_lit.span()))
+    {
+        Some(Err(err)) => {
+            // Several errors expand to several compile_error! calls
+            let errors = err.to_compile_error();
+            return quote! {{ #errors }}.into();
+        }
+        Some(Ok(warning)) => warning,
+        None => None,
+    };
+
+    let with = params.iter().map(|RouteParam { name, value }| {
+        let key = name.to_string();
+        quote! { .with(#key, ::std::string::ToString::to_string(&(#value))) }
+    });
+
+    // Surface the warning as a deprecation at the route name
+    let warning = warning.map(|note| {
+        quote_spanned! {route_lit.span()=>
+            #[deprecated(note = #note)]
+            #[allow(non_upper_case_globals)]
+            const route_parameters: () = ();
+            let () = route_parameters;
+        }
+    });
 
     // Generate the redirect builder
     let expanded = quote! {
-        ::kit::Redirect::route(#route_lit)
+        {
+            #warning
+            ::kit::Redirect::route(#route_lit) #(#with)*
+        }
     };
 
     expanded.into()
 }
 
-fn validate_route_exists(route_name: &str, span: Span) -> Result<(), syn::Error> {
-    // Get the manifest directory
-    let manifest_dir = match std::env::var("CARGO_MANIFEST_DIR") {
-        Ok(dir) => dir,
-        Err(_) => return Ok(()), // Skip validation if env not available
+/// Check the parameters given to the macro against the route's path
+///
+/// Passing any parameters means passing all of them. A route with parameters
+/// and none given gets a warning, since `.with()` calls after the macro are
+/// only checked at runtime.
+fn check_params(
+    route: &KnownRoute,
+    params: &[RouteParam],
+    span: Span,
+) -> Result<Option<String>, syn::Error> {
+    let Some(path) = &route.path else {
+        return Ok(None);
     };
+    let expected = path_params(path);
 
-    let project_root = PathBuf::from(&manifest_dir);
-
-    // Scan routes.rs (or main entrypoint) for route definitions
-    let available_routes = extract_route_names(&project_root);
+    let mut errors: Option<syn::Error> = None;
+    let mut push = |error: syn::Error| match &mut errors {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error),
+    };
 
-    if available_routes.is_empty() {
-        // No routes found, skip validation (might be running in different context)
-        return Ok(());
+    for (index, param) in params.iter().enumerate() {
+        let name = param.name.to_string();
+        if params[..index].iter().any(|p| p.name == name) {
+            push(syn::Error::new(
+                param.name.span(),
+                format!("parameter `{}` is given more than once", name),
+            ));
+        } else if !expected.contains(&name) {
+            let mut message = format!(
+                "Route '{}' has no parameter `{}` (path: {})",
+                route.name, name, path
+            );
+            if let Some(suggestion) = find_similar_route(&name, &expected) {
+                message.push_str(&format!(". Did you mean `{}`?", suggestion));
+            }
+            push(syn::Error::new(param.name.span(), message));
+        }
     }
 
-    if !available_routes.contains(&route_name.to_string()) {
-        let mut error_msg = format!("Route '{}' not found.", route_name);
-
-        error_msg.push_str("\n\nAvailable routes:");
-        for route in &available_routes {
-            error_msg.push_str(&format!("\n  - {}", route));
+    if params.is_empty() {
+        if expected.is_empty() {
+            return Ok(None);
         }
+        return Ok(Some(format!(
+            "route '{}' takes {}; pass them to redirect!(\"{}\", {} = ...) so they are checked at compile time",
+            route.name,
+            describe_params(&expected),
+            route.name,
+            expected[0]
+        )));
+    }
 
-        // Suggest similar route names
-        if let Some(suggestion) = find_similar_route(route_name, &available_routes) {
-            error_msg.push_str(&format!("\n\nDid you mean '{}'?", suggestion));
-        }
+    let missing: Vec<String> = expected
+        .iter()
+        .filter(|name| !params.iter().any(|p| p.name == name))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        push(syn::Error::new(
+            span,
+            format!(
+                "Route '{}' is missing {} (path: {})",
+                route.name,
+                describe_params(&missing),
+                path
+            ),
+        ));
+    }
 
-        return Err(syn::Error::new(span, error_msg));
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(None),
     }
+}
 
-    Ok(())
+/// "parameter `id`" or "parameters `team`, `id`"
+fn describe_params(names: &[String]) -> String {
+    let list: Vec<String> = names.iter().map(|name| format!("`{}`", name)).collect();
+    match list.len() {
+        1 => format!("parameter {}", list[0]),
+        _ => format!("parameters {}", list.join(", ")),
+    }
 }
 
-fn extract_route_names(project_root: &PathBuf) -> Vec<String> {
-    // Try routes.rs first, fall back to cmd/main.rs or legacy src/main.rs
-    let routes_rs = project_root.join("src").join("routes.rs");
-    let cmd_main_rs = project_root.join("cmd").join("main.rs");
-    let main_rs = project_root.join("src").join("main.rs");
+/// Check the route exists, returning it if the app's routes could be read
+fn validate_route_exists(route_name: &str, span: Span) -> Result<Option<KnownRoute>, syn::Error> {
+    // Get the manifest directory
+    let manifest_dir = match std::env::var("CARGO_MANIFEST_DIR") {
+        Ok(dir) => dir,
+        Err(_) => return Ok(None), // Skip validation if env not available
+    };
+
+    // Read the route manifest and route files for route definitions
+    let known = known_routes(Path::new(&manifest_dir));
+    if let Some(route) = known.get(route_name) {
+        return Ok(Some(route.clone()));
+    }
+
+    let available_routes = known.names();
+    if available_routes.is_empty() {
+        // No routes found, skip validation (might be running in different context)
+        return Ok(None);
+    }
+
+    let mut error_msg = format!("Route '{}' not found.", route_name);
 
-    let content = std::fs::read_to_string(&routes_rs)
-        .or_else(|_| std::fs::read_to_string(&cmd_main_rs))
-        .or_else(|_| std::fs::read_to_string(&main_rs))
-        .unwrap_or_default();
+    error_msg.push_str("\n\nAvailable routes:");
+    for route in &available_routes {
+        error_msg.push_str(&format!("\n  - {}", route));
+    }
 
-    if content.is_empty() {
-        return Vec::new();
+    // Suggest similar route names
+    if let Some(suggestion) = find_similar_route(route_name, &available_routes) {
+        error_msg.push_str(&format!("\n\nDid you mean '{}'?", suggestion));
     }
 
-    // Use regex to find .name("...") patterns
-    let re = regex::Regex::new(r#"\.name\s*\(\s*"([^"]+)"\s*\)"#).unwrap();
+    if let Some(manifest) = known.manifest {
+        error_msg.push_str(&format!(
+            "\n\nRoute names were read from {}. Run `kit route:cache` if it is out of date.",
+            manifest.display()
+        ));
+    }
 
-    re.captures_iter(&content)
-        .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_string()))
-        .collect()
+    Err(syn::Error::new(span, error_msg))
 }
 
 fn find_similar_route(target: &str, available: &[String]) -> Option<String> {
@@ -121,3 +270,43 @@ fn find_similar_route(target: &str, available: &[String]) -> Option<String> {
 
     best_match.map(|(name, _)| name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route() -> KnownRoute {
+        KnownRoute {
+            name: "teams.users.show".to_string(),
+            path: Some("/teams/{team}/users/{id}".to_string()),
+        }
+    }
+
+    fn params(input: RedirectInput) -> Vec<RouteParam> {
+        match input {
+            RedirectInput::Route { params, .. } => params,
+            RedirectInput::Url(_) => panic!("expected a route"),
+        }
+    }
+
+    #[test]
+    fn test_check_params() {
+        let span = Span::call_site();
+        let all = params(syn::parse_quote!("teams.users.show", team = 1, id = user.id));
+        assert!(check_params(&route(), &all, span).unwrap().is_none());
+
+        let none = params(syn::parse_quote!("teams.users.show"));
+        let warning = check_params(&route(), &none, span).unwrap().unwrap();
+        assert!(warning.contains("parameters `team`, `id`"), "{}", warning);
+
+        let wrong = params(syn::parse_quote!("teams.users.show", team = 1, user = 2));
+        let errors: Vec<String> = check_params(&route(), &wrong, span)
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("no parameter `user`"), "{}", errors[0]);
+        assert!(errors[1].contains("missing parameter `id`"), "{}", errors[1]);
+    }
+}
@@ -0,0 +1,61 @@
+//! `#[report]` attribute macro for PDF reports
+//!
+//! Derives `Serialize`/`Deserialize` for the struct and registers it so
+//! queue workers can rebuild and render it from its stored payload.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput};
+
+pub fn report_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new_spanned(attr, "#[report] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(&input.generics, "#[report] structs cannot be generic")
+            .to_compile_error()
+            .into();
+    }
+
+    let generator_name = format_ident!("__kit_report_generator_{}", ident);
+
+    let expanded = quote! {
+        #[derive(::kit::serde::Serialize, ::kit::serde::Deserialize)]
+        #[serde(crate = "::kit::serde")]
+        #input
+
+        impl ::kit::reports::ReportName for #ident {
+            const NAME: &'static str = concat!(module_path!(), "::", stringify!(#ident));
+        }
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        fn #generator_name(
+            __payload: &str,
+        ) -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = ::kit::reports::registry::Generated> + Send>> {
+            let __report = ::kit::serde_json::from_str::<#ident>(__payload);
+            Box::pin(async move {
+                let __report = __report.map_err(|e| {
+                    ::kit::FrameworkError::internal(format!("Report payload deserialize error: {}", e))
+                })?;
+                ::kit::reports::registry::generate(__report).await
+            })
+        }
+
+        ::kit::inventory::submit! {
+            ::kit::reports::registry::ReportEntry {
+                name: <#ident as ::kit::reports::ReportName>::NAME,
+                generate: #generator_name,
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
@@ -4,8 +4,13 @@
 //! Works for both JSON and form-urlencoded request bodies.
 
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_macro_input, Attribute, DeriveInput, Expr, Fields, Ident, Lit, LitStr, Member, Meta,
+    Token,
+};
 
 /// Implementation of the `#[derive(FormRequest)]` derive macro
 ///
@@ -39,8 +44,19 @@ pub fn derive_request_impl(input: TokenStream) -> TokenStream {
     let generics = &input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    let hooks = match &input.data {
+        syn::Data::Struct(data) => hook_fns(&data.fields),
+        _ => Ok(TokenStream2::new()),
+    };
+    let hooks = match hooks {
+        Ok(hooks) => hooks,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
     let output = quote! {
-        impl #impl_generics kit_rs::FormRequest for #name #ty_generics #where_clause {}
+        impl #impl_generics ::kit::FormRequest for #name #ty_generics #where_clause {
+            #hooks
+        }
     };
 
     output.into()
@@ -81,11 +97,20 @@ pub fn derive_request_impl(input: TokenStream) -> TokenStream {
 ///
 /// ## Content Type Support
 ///
-/// The `#[request]` attribute works with both:
+/// The `#[request]` attribute works with:
 /// - `application/json` - JSON request bodies
 /// - `application/x-www-form-urlencoded` - HTML form submissions
+/// - `multipart/form-data` - forms with `UploadedFile` fields, checked with
+///   `#[file(max_size = "2MB", mimes("image/png"))]`
 ///
 /// The content type is automatically detected from the request headers.
+///
+/// ## Async Rules
+///
+/// `#[validate(custom = "unique_email")]` runs the rule registered with
+/// `kit::Rules::register("unique_email", ...)` after the other rules. The
+/// entry is removed before `validator::Validate` is derived, so it only
+/// works with `#[request]`, and the field type must be `Serialize`.
 pub fn request_attr_impl(_attr: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -104,15 +129,404 @@ pub fn request_attr_impl(_attr: TokenStream, input: TokenStream) -> TokenStream
         }
     };
 
-    let fields = &data.fields;
+    let hooks = match hook_fns(&data.fields) {
+        Ok(hooks) => hooks,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // serde and validator don't know #[transform], #[file] and async rules,
+    // so they are removed here
+    let mut fields = data.fields.clone();
+    for field in fields.iter_mut() {
+        field
+            .attrs
+            .retain(|attr| !attr.path().is_ident("transform") && !attr.path().is_ident("file"));
+        strip_async_rules(&mut field.attrs);
+    }
+    let semi = match fields {
+        Fields::Named(_) => None,
+        _ => Some(quote! { ; }),
+    };
 
     let output = quote! {
         #(#attrs)*
         #[derive(serde::Deserialize, validator::Validate)]
-        #vis struct #name #generics #fields
+        #vis struct #name #generics #fields #semi
 
-        impl #impl_generics kit_rs::FormRequest for #name #ty_generics #where_clause {}
+        impl #impl_generics ::kit::FormRequest for #name #ty_generics #where_clause {
+            #hooks
+        }
     };
 
     output.into()
 }
+
+/// The `FormRequest` methods generated from field attributes
+fn hook_fns(fields: &Fields) -> syn::Result<TokenStream2> {
+    let transform = transform_fn(fields)?;
+    let uploads = uploads_fn(fields)?;
+    let rule_checks = rule_checks_fn(fields);
+    Ok(quote! {
+        #transform
+        #uploads
+        #rule_checks
+    })
+}
+
+fn member(index: usize, field: &syn::Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(index.into()),
+    }
+}
+
+/// Generate `FormRequest::validate_uploads` from the `#[file(...)]` field attributes
+///
+/// Supported rules:
+/// - `max_size = 1048576` or `max_size = "1MB"` (`B`, `KB`, `MB` and `GB`)
+/// - `mimes("image/png", "image/*")`
+fn uploads_fn(fields: &Fields) -> syn::Result<TokenStream2> {
+    let mut checks = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = member(index, field);
+        let name = match &member {
+            Member::Named(ident) => ident.to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        };
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("file")) {
+            let mut rules = quote! { ::kit::UploadRules::new() };
+            for rule in attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)? {
+                rules = upload_rule(rules, &rule)?;
+            }
+            checks.push(quote! {
+                if let Some(message) =
+                    ::kit::ValidateUpload::validate_upload(&self.#member, #name, &#rules)
+                {
+                    errors.add(#name, message);
+                }
+            });
+        }
+    }
+
+    if checks.is_empty() {
+        return Ok(TokenStream2::new());
+    }
+    Ok(quote! {
+        fn validate_uploads(&self) -> ::kit::ValidationErrors {
+            let mut errors = ::kit::ValidationErrors::new();
+            #(#checks)*
+            errors
+        }
+    })
+}
+
+/// The `custom = "name"` entries of a `#[validate(...)]` attribute
+///
+/// Attributes that don't parse as a list of metas are left to validator.
+fn async_rules(attr: &Attribute) -> Vec<LitStr> {
+    if !attr.path().is_ident("validate") {
+        return Vec::new();
+    }
+    let Ok(rules) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+        return Vec::new();
+    };
+    rules.iter().filter_map(async_rule_name).collect()
+}
+
+fn async_rule_name(rule: &Meta) -> Option<LitStr> {
+    match rule {
+        Meta::NameValue(nv) if nv.path.is_ident("custom") => match &nv.value {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Str(name) => Some(name.clone()),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Remove `custom = "name"` from `#[validate(...)]` attributes, dropping
+/// attributes left empty
+fn strip_async_rules(attrs: &mut Vec<Attribute>) {
+    let mut kept = Vec::with_capacity(attrs.len());
+    for attr in attrs.drain(..) {
+        if async_rules(&attr).is_empty() {
+            kept.push(attr);
+            continue;
+        }
+        let Ok(rules) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        else {
+            kept.push(attr);
+            continue;
+        };
+        let rules: Vec<&Meta> = rules
+            .iter()
+            .filter(|rule| async_rule_name(rule).is_none())
+            .collect();
+        if !rules.is_empty() {
+            kept.push(syn::parse_quote! { #[validate(#(#rules),*)] });
+        }
+    }
+    *attrs = kept;
+}
+
+/// Generate `FormRequest::rule_checks` from `#[validate(custom = "name")]`
+fn rule_checks_fn(fields: &Fields) -> TokenStream2 {
+    let mut checks = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = member(index, field);
+        let name = match &member {
+            Member::Named(ident) => ident.to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        };
+        for rule in field.attrs.iter().flat_map(async_rules) {
+            checks.push(quote! {
+                ::kit::RuleCheck {
+                    field: #name,
+                    rule: #rule,
+                    value: ::kit::serde_json::to_value(&self.#member)
+                        .unwrap_or(::kit::serde_json::Value::Null),
+                }
+            });
+        }
+    }
+
+    if checks.is_empty() {
+        return TokenStream2::new();
+    }
+    quote! {
+        fn rule_checks(&self) -> ::std::vec::Vec<::kit::RuleCheck> {
+            ::std::vec![#(#checks),*]
+        }
+    }
+}
+
+fn upload_rule(rules: TokenStream2, rule: &Meta) -> syn::Result<TokenStream2> {
+    match rule {
+        Meta::NameValue(nv) if nv.path.is_ident("max_size") => {
+            let bytes = match &nv.value {
+                Expr::Lit(lit) => match &lit.lit {
+                    Lit::Int(int) => int.base10_parse::<usize>()?,
+                    Lit::Str(size) => parse_size(size)?,
+                    _ => return Err(syn::Error::new_spanned(&nv.value, "expected a size")),
+                },
+                _ => return Err(syn::Error::new_spanned(&nv.value, "expected a size")),
+            };
+            Ok(quote! { #rules.max_size(#bytes) })
+        }
+        Meta::List(list) if list.path.is_ident("mimes") => {
+            let mimes = list.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?;
+            let mimes = mimes.iter();
+            Ok(quote! { #rules.mimes(&[#(#mimes),*]) })
+        }
+        _ => Err(syn::Error::new_spanned(
+            rule,
+            "unknown file rule, expected `max_size = ...` or `mimes(...)`",
+        )),
+    }
+}
+
+/// Parse sizes such as `"512KB"` and `"2MB"` into bytes
+fn parse_size(size: &LitStr) -> syn::Result<usize> {
+    let value = size.value();
+    let value = value.trim();
+    let digits = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(digits);
+    let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" => 1024,
+        "MB" => 1024 * 1024,
+        "GB" => 1024 * 1024 * 1024,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                size,
+                "unknown unit, expected B, KB, MB or GB",
+            ))
+        }
+    };
+    number
+        .parse::<usize>()
+        .map(|number| number * multiplier)
+        .map_err(|_| syn::Error::new_spanned(size, "expected a size such as \"2MB\""))
+}
+
+/// Generate `FormRequest::transform` from the `#[transform(...)]` field attributes
+///
+/// Supported transforms:
+/// - `sanitize_html` - `kit::sanitize_html` with the basic policy
+/// - `sanitize_html(strict | basic | relaxed)` - with the named policy
+fn transform_fn(fields: &Fields) -> syn::Result<TokenStream2> {
+    let mut steps = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = member(index, field);
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("transform"))
+        {
+            for transform in transforms(attr)? {
+                steps.push(transform_step(&member, &transform)?);
+            }
+        }
+    }
+
+    if steps.is_empty() {
+        return Ok(TokenStream2::new());
+    }
+    Ok(quote! {
+        fn transform(&mut self) {
+            #(#steps)*
+        }
+    })
+}
+
+fn transforms(attr: &Attribute) -> syn::Result<Punctuated<Meta, Token![,]>> {
+    attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+}
+
+fn transform_step(member: &Member, transform: &Meta) -> syn::Result<TokenStream2> {
+    if !transform.path().is_ident("sanitize_html") {
+        return Err(syn::Error::new_spanned(
+            transform.path(),
+            "unknown transform, expected `sanitize_html`",
+        ));
+    }
+
+    let policy = match transform {
+        Meta::Path(_) => Ident::new("basic", proc_macro2::Span::call_site()),
+        Meta::List(list) => {
+            let policy: Ident = list.parse_args()?;
+            if !["strict", "basic", "relaxed"].contains(&policy.to_string().as_str()) {
+                return Err(syn::Error::new_spanned(
+                    &policy,
+                    "unknown policy, expected `strict`, `basic` or `relaxed`",
+                ));
+            }
+            policy
+        }
+        Meta::NameValue(_) => {
+            return Err(syn::Error::new_spanned(
+                transform,
+                "expected `sanitize_html` or `sanitize_html(policy)`",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        ::kit::SanitizeHtml::sanitize_html(&mut self.#member, &::kit::HtmlPolicy::#policy());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_fn() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Post {
+                title: String,
+                #[transform(sanitize_html)]
+                body: String,
+                #[transform(sanitize_html(strict))]
+                summary: Option<String>,
+            }
+        };
+        let syn::Data::Struct(data) = input.data else {
+            unreachable!()
+        };
+
+        let generated = transform_fn(&data.fields).unwrap().to_string();
+        assert!(generated.contains("self . body , & :: kit :: HtmlPolicy :: basic ()"));
+        assert!(generated.contains("self . summary , & :: kit :: HtmlPolicy :: strict ()"));
+        assert!(!generated.contains("title"));
+
+        let input: DeriveInput = syn::parse_quote! {
+            struct Post {
+                #[transform(sanitize_html(loose))]
+                body: String,
+            }
+        };
+        let syn::Data::Struct(data) = input.data else {
+            unreachable!()
+        };
+        assert!(transform_fn(&data.fields).is_err());
+    }
+
+    #[test]
+    fn test_async_rules_are_moved_out_of_validate() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Register {
+                #[validate(email, custom = "unique_email")]
+                email: String,
+                #[validate(custom = "available_username")]
+                username: String,
+                #[validate(custom(function = "check_name"), length(min = 1))]
+                name: String,
+            }
+        };
+        let syn::Data::Struct(data) = input.data else {
+            unreachable!()
+        };
+
+        let generated = rule_checks_fn(&data.fields).to_string();
+        assert!(generated.contains("field : \"email\" , rule : \"unique_email\""));
+        assert!(generated.contains("field : \"username\" , rule : \"available_username\""));
+        assert!(!generated.contains("check_name"));
+
+        let mut fields = data.fields.clone();
+        for field in fields.iter_mut() {
+            strip_async_rules(&mut field.attrs);
+        }
+        let attrs: Vec<String> = fields
+            .iter()
+            .map(|field| {
+                {
+                    let attrs = &field.attrs;
+                    quote!(#(#attrs)*)
+                }
+                .to_string()
+            })
+            .collect();
+        assert_eq!(attrs[0], "# [validate (email)]");
+        assert_eq!(attrs[1], "");
+        assert!(attrs[2].contains("custom (function = \"check_name\")"));
+    }
+
+    #[test]
+    fn test_uploads_fn() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Upload {
+                title: String,
+                #[file(max_size = "2MB", mimes("image/png", "image/*"))]
+                avatar: UploadedFile,
+                #[file(max_size = 1024)]
+                resume: Option<UploadedFile>,
+            }
+        };
+        let syn::Data::Struct(data) = input.data else {
+            unreachable!()
+        };
+
+        let generated = uploads_fn(&data.fields).unwrap().to_string();
+        assert!(
+            generated.contains("max_size (2097152usize) . mimes (& [\"image/png\" , \"image/*\"])")
+        );
+        assert!(generated.contains("self . resume , \"resume\""));
+        assert!(!generated.contains("title"));
+
+        let input: DeriveInput = syn::parse_quote! {
+            struct Upload {
+                #[file(max_size = "2TB")]
+                avatar: UploadedFile,
+            }
+        };
+        let syn::Data::Struct(data) = input.data else {
+            unreachable!()
+        };
+        assert!(uploads_fn(&data.fields).is_err());
+    }
+}
@@ -0,0 +1,359 @@
+//! Where compile-time route checks find the app's routes
+//!
+//! Route names and paths come from two places, merged:
+//!
+//! - The route manifest written by `kit route:cache` (`.kit/routes.json`),
+//!   which lists every route the app registers, including ones built in code
+//! - The route source files, `src/routes.rs` by default (or `cmd/main.rs`,
+//!   `src/main.rs`), or the globs listed in Cargo.toml:
+//!
+//! ```toml
+//! [package.metadata.kit]
+//! routes = ["src/routes.rs", "src/routes/**/*.rs"]
+//! route-manifest = ".kit/routes.json"
+//! ```
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::{Expr, LitStr, Token};
+
+/// Default location of the manifest written by `kit route:cache`
+const DEFAULT_MANIFEST: &str = ".kit/routes.json";
+
+/// Routes known at compile time
+#[derive(Debug, Default)]
+pub struct KnownRoutes {
+    pub routes: Vec<KnownRoute>,
+    /// The manifest that contributed routes, if any
+    pub manifest: Option<PathBuf>,
+}
+
+/// A named route
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownRoute {
+    pub name: String,
+    /// The full path pattern, when the source shows it
+    pub path: Option<String>,
+}
+
+impl KnownRoutes {
+    /// The route names, in the order they were found
+    pub fn names(&self) -> Vec<String> {
+        self.routes.iter().map(|route| route.name.clone()).collect()
+    }
+
+    /// The route with this name
+    pub fn get(&self, name: &str) -> Option<&KnownRoute> {
+        self.routes.iter().find(|route| route.name == name)
+    }
+}
+
+/// The parameter names in a path pattern, `{id}` or `:id`
+pub fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| {
+            segment
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+                .or_else(|| segment.strip_prefix(':'))
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// `[package.metadata.kit]` settings for route checks
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RouteConfig {
+    #[serde(default)]
+    routes: Vec<String>,
+    route_manifest: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CargoManifest {
+    package: Option<CargoPackage>,
+}
+
+#[derive(Deserialize)]
+struct CargoPackage {
+    metadata: Option<CargoMetadata>,
+}
+
+#[derive(Deserialize)]
+struct CargoMetadata {
+    kit: Option<RouteConfig>,
+}
+
+/// A route entry in `.kit/routes.json`
+#[derive(Deserialize)]
+struct ManifestRoute {
+    name: Option<String>,
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct RouteManifest {
+    routes: Vec<ManifestRoute>,
+}
+
+/// Collect the route names of the crate at `project_root`
+pub fn known_routes(project_root: &Path) -> KnownRoutes {
+    let config = std::fs::read_to_string(project_root.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| parse_config(&content))
+        .unwrap_or_default();
+
+    let mut known = KnownRoutes::default();
+
+    let manifest = project_root.join(config.route_manifest.as_deref().unwrap_or(DEFAULT_MANIFEST));
+    if let Some(routes) = std::fs::read_to_string(&manifest)
+        .ok()
+        .and_then(|content| parse_manifest(&content))
+    {
+        known.routes.extend(routes);
+        known.manifest = Some(manifest);
+    }
+
+    for file in source_files(project_root, &config.routes) {
+        if let Ok(content) = std::fs::read_to_string(&file) {
+            known.routes.extend(routes_in_source(&content));
+        }
+    }
+
+    // The manifest comes first, so its paths win over the scanned ones
+    let mut seen = std::collections::HashSet::new();
+    known.routes.retain(|route| seen.insert(route.name.clone()));
+    known
+}
+
+fn parse_config(cargo_toml: &str) -> Option<RouteConfig> {
+    toml::from_str::<CargoManifest>(cargo_toml)
+        .ok()?
+        .package?
+        .metadata?
+        .kit
+}
+
+fn parse_manifest(content: &str) -> Option<Vec<KnownRoute>> {
+    let manifest: RouteManifest = serde_json::from_str(content).ok()?;
+    Some(
+        manifest
+            .routes
+            .into_iter()
+            .filter_map(|route| {
+                Some(KnownRoute {
+                    name: route.name?,
+                    path: Some(route.path),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// The files matching the configured globs, or the default routes file
+fn source_files(project_root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    if patterns.is_empty() {
+        // Try routes.rs first, fall back to cmd/main.rs or legacy src/main.rs
+        return ["src/routes.rs", "cmd/main.rs", "src/main.rs"]
+            .iter()
+            .map(|path| project_root.join(path))
+            .find(|path| path.is_file())
+            .into_iter()
+            .collect();
+    }
+
+    let mut files: Vec<PathBuf> = patterns
+        .iter()
+        .filter_map(|pattern| glob::glob(&project_root.join(pattern).to_string_lossy()).ok())
+        .flat_map(|paths| paths.filter_map(Result::ok))
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Named routes declared in a source file
+fn routes_in_source(content: &str) -> Vec<KnownRoute> {
+    // Walk the routes! block so group prefixes are applied
+    if let Some(routes) = routes_from_macro(content) {
+        return routes;
+    }
+
+    // Otherwise use regex to find .name("...") patterns
+    let re = regex::Regex::new(r#"\.name\s*\(\s*"([^"]+)"\s*\)"#).unwrap();
+
+    re.captures_iter(content)
+        .filter_map(|cap| {
+            cap.get(1).map(|m| KnownRoute {
+                name: m.as_str().to_string(),
+                path: None,
+            })
+        })
+        .collect()
+}
+
+fn routes_from_macro(content: &str) -> Option<Vec<KnownRoute>> {
+    let file = syn::parse_file(content).ok()?;
+    let routes = file.items.iter().find_map(|item| match item {
+        syn::Item::Macro(m) if m.mac.path.is_ident("routes") => Some(&m.mac),
+        _ => None,
+    })?;
+    let items = routes
+        .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        .ok()?;
+
+    let mut routes = Vec::new();
+    for item in &items {
+        collect_routes(item, "", "", &mut routes);
+    }
+    Some(routes)
+}
+
+/// Collect named routes from a route or group entry, applying the group's
+/// path prefix and `.name_prefix()`
+fn collect_routes(expr: &Expr, path_prefix: &str, name_prefix: &str, routes: &mut Vec<KnownRoute>) {
+    let mut expr = expr;
+    let mut name = None;
+    let mut group_prefix = None;
+    while let Expr::MethodCall(call) = expr {
+        let arg = match call.args.first() {
+            Some(Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            })) => Some(s.value()),
+            _ => None,
+        };
+        match call.method.to_string().as_str() {
+            "name" => name = name.or(arg),
+            "name_prefix" => group_prefix = group_prefix.or(arg),
+            _ => {}
+        }
+        expr = &call.receiver;
+    }
+
+    let Expr::Macro(mac) = expr else {
+        return;
+    };
+    if !mac.mac.path.is_ident("group") {
+        if let Some(name) = name {
+            // get!("/users/{id}", handler)
+            let path = mac
+                .mac
+                .parse_body_with(|input: ParseStream| {
+                    let path = input.parse::<LitStr>()?;
+                    input.parse::<proc_macro2::TokenStream>()?;
+                    Ok(path)
+                })
+                .ok()
+                .map(|path| format!("{}{}", path_prefix, path.value()));
+            routes.push(KnownRoute {
+                name: format!("{}{}", name_prefix, name),
+                path,
+            });
+        }
+        return;
+    }
+
+    let group_items = |input: ParseStream| {
+        let path = input.parse::<LitStr>()?;
+        input.parse::<Token![,]>()?;
+        let content;
+        syn::braced!(content in input);
+        Ok((
+            path,
+            Punctuated::<Expr, Token![,]>::parse_terminated(&content)?,
+        ))
+    };
+    if let Ok((path, items)) = mac.mac.parse_body_with(group_items) {
+        let path_prefix = format!("{}{}", path_prefix, path.value());
+        let name_prefix = format!("{}{}", name_prefix, group_prefix.unwrap_or_default());
+        for item in &items {
+            collect_routes(item, &path_prefix, &name_prefix, routes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse_config(
+            r#"
+            [package]
+            name = "app"
+
+            [package.metadata.kit]
+            routes = ["src/routes/**/*.rs"]
+            route-manifest = "target/routes.json"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.routes, ["src/routes/**/*.rs"]);
+        assert_eq!(config.route_manifest.as_deref(), Some("target/routes.json"));
+        assert!(parse_config("[package]\nname = \"app\"\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_manifest_skips_unnamed_routes() {
+        let routes = parse_manifest(
+            r#"{"routes": [
+                {"method": "GET", "path": "/users", "name": "users.index"},
+                {"method": "GET", "path": "/health", "name": null}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            routes,
+            [KnownRoute {
+                name: "users.index".to_string(),
+                path: Some("/users".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_path_params() {
+        assert_eq!(path_params("/teams/{team}/users/:id"), ["team", "id"]);
+        assert!(path_params("/users").is_empty());
+    }
+
+    #[test]
+    fn test_scans_every_file_matching_the_globs() {
+        let root = std::env::temp_dir().join(format!("kit-route-source-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src/routes")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package.metadata.kit]\nroutes = [\"src/routes/*.rs\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/routes/users.rs"),
+            "pub fn routes() -> Router { Router::new().get(\"/users\", index).name(\"users.index\") }",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/routes/admin.rs"),
+            "routes! { group!(\"/admin\", { get!(\"/\", home).name(\"home\") }).name_prefix(\"admin.\") }",
+        )
+        .unwrap();
+
+        let known = known_routes(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(known.names(), ["admin.home", "users.index"]);
+        assert_eq!(
+            known.get("admin.home").unwrap().path.as_deref(),
+            Some("/admin/")
+        );
+        assert_eq!(known.get("users.index").unwrap().path, None);
+        assert!(known.manifest.is_none());
+    }
+}
@@ -1,72 +1,80 @@
 //! Service trait macro for the Kit framework
 //!
 //! Provides the `#[service]` attribute macro that:
-//! 1. Adds `Send + Sync + 'static` bounds to trait definitions
+//! 1. Adds `Send + Sync + 'static` bounds to trait definitions, and
+//!    `#[async_trait]` when they have async methods
 //! 2. Optionally auto-registers a concrete implementation with the container
-//! 3. Optionally generates a `fake()` method for testing
+//! 3. Optionally generates a `fake()` method or a `Mock{Trait}` for testing
+//! 4. Generates a recording proxy for `TestContainer::spy()`
 
 use proc_macro::TokenStream;
-use quote::quote;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
-use syn::{parse_macro_input, Ident, ItemTrait, Path, Token};
+use syn::visit::{self, Visit};
+use syn::{
+    parse_macro_input, FnArg, Ident, ItemTrait, Path, ReturnType, Token, TraitItem, TraitItemFn,
+    Type, TypeParamBound, WherePredicate,
+};
 
 /// Parsed arguments from the service attribute
+#[derive(Default)]
 struct ServiceArgs {
     impl_type: Option<Path>,
     fake_type: Option<Path>,
+    mock: bool,
 }
 
 impl Parse for ServiceArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut impl_type = None;
-        let mut fake_type = None;
+        let mut args = ServiceArgs::default();
+        let mut first = true;
 
-        if input.is_empty() {
-            return Ok(ServiceArgs {
-                impl_type: None,
-                fake_type: None,
-            });
-        }
-
-        // Check if this is named parameters (contains '=') or positional (old syntax)
-        let fork = input.fork();
-        let is_named = if fork.parse::<Ident>().is_ok() {
-            fork.peek(Token![=])
-        } else {
-            false
-        };
+        while !input.is_empty() {
+            // `impl` is a keyword, so peek with `parse_any`
+            let fork = input.fork();
+            let name = fork.call(Ident::parse_any).ok();
+            let is_named = name.is_some() && fork.peek(Token![=]);
+            let is_flag = name.as_ref().is_some_and(|n| n == "mock")
+                && (fork.is_empty() || fork.peek(Token![,]));
 
-        if is_named {
-            // Parse named parameters: impl = Type, fake = Type
-            while !input.is_empty() {
-                let name: Ident = input.parse()?;
+            if is_named {
+                // Named parameters: impl = Type, fake = Type
+                let name = input.call(Ident::parse_any)?;
                 input.parse::<Token![=]>()?;
                 let path: Path = input.parse()?;
 
                 match name.to_string().as_str() {
-                    "impl" => impl_type = Some(path),
-                    "fake" => fake_type = Some(path),
+                    "impl" => args.impl_type = Some(path),
+                    "fake" => args.fake_type = Some(path),
                     _ => {
                         return Err(syn::Error::new(
                             name.span(),
-                            format!("unknown parameter '{}', expected 'impl' or 'fake'", name),
+                            format!(
+                                "unknown parameter '{}', expected 'impl', 'fake' or 'mock'",
+                                name
+                            ),
                         ))
                     }
                 }
+            } else if is_flag {
+                input.call(Ident::parse_any)?;
+                args.mock = true;
+            } else if first {
+                // Backwards compatible: positional argument is the impl type
+                args.impl_type = Some(input.parse()?);
+            } else {
+                return Err(input.error("expected 'impl = Type', 'fake = Type' or 'mock'"));
+            }
 
-                if input.peek(Token![,]) {
-                    input.parse::<Token![,]>()?;
-                }
+            first = false;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
             }
-        } else {
-            // Backwards compatible: positional argument is the impl type
-            impl_type = Some(input.parse()?);
         }
 
-        Ok(ServiceArgs {
-            impl_type,
-            fake_type,
-        })
+        Ok(args)
     }
 }
 
@@ -104,6 +112,26 @@ impl Parse for ServiceArgs {
 /// // In tests:
 /// let _guard = <dyn CacheStore>::fake();  // Binds FakeCache, returns TestContainerGuard
 /// ```
+///
+/// # With mock (generates a closure-configured Mock{Trait})
+///
+/// ```rust,ignore
+/// #[service(mock)]
+/// pub trait HttpClient {
+///     async fn get(&self, url: &str) -> Result<String, Error>;
+/// }
+///
+/// // In tests:
+/// let _guard = TestContainer::fake();
+/// MockHttpClient::new()
+///     .on_get(|url| Ok(format!("<html>{}</html>", url)))
+///     .bind();
+/// ```
+///
+/// # Spies
+///
+/// Non-generic traits also get a recording proxy, so
+/// `TestContainer::spy::<dyn CacheStore>()` can wrap the current binding.
 pub fn service_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as ServiceArgs);
     let mut item_trait = parse_macro_input!(input as ItemTrait);
@@ -156,6 +184,24 @@ pub fn service_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
         item_trait.supertraits.push(static_bound);
     }
 
+    // Services are used as `dyn Trait`, so report methods that prevent it
+    // here, pointing at the method instead of at every `App::make` call
+    if let Some(error) = dyn_compatibility_errors(&item_trait) {
+        let error = error.to_compile_error();
+        return TokenStream::from(quote! {
+            #error
+            #item_trait
+        });
+    }
+
+    // Async methods need #[async_trait] to be callable through `dyn`
+    let has_async = trait_methods(&item_trait).any(|m| m.sig.asyncness.is_some());
+    if has_async && !uses_async_trait(&item_trait) {
+        item_trait
+            .attrs
+            .push(syn::parse_quote!(#[::kit::async_trait]));
+    }
+
     let trait_name = &item_trait.ident;
     let trait_name_str = trait_name.to_string();
 
@@ -203,11 +249,438 @@ pub fn service_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
         }
     });
 
+    let spy_impl = spy_proxy(&item_trait);
+
+    // Generate Mock{Trait} if requested
+    let mock_impl = if args.mock {
+        match mock_type(&item_trait) {
+            Ok(mock) => Some(mock),
+            Err(e) => return e.to_compile_error().into(),
+        }
+    } else {
+        None
+    };
+
     let expanded = quote! {
         #item_trait
         #impl_registration
         #fake_impl
+        #spy_impl
+        #mock_impl
     };
 
     TokenStream::from(expanded)
 }
+
+fn trait_methods(item_trait: &ItemTrait) -> impl Iterator<Item = &TraitItemFn> {
+    item_trait.items.iter().filter_map(|item| match item {
+        TraitItem::Fn(method) => Some(method),
+        _ => None,
+    })
+}
+
+/// Whether the method takes `&self`, the only receiver generated impls forward
+fn takes_ref_self(method: &TraitItemFn) -> bool {
+    matches!(
+        method.sig.receiver(),
+        Some(receiver) if receiver.reference.is_some()
+            && receiver.mutability.is_none()
+            && receiver.colon_token.is_none()
+    )
+}
+
+fn uses_async_trait(item_trait: &ItemTrait) -> bool {
+    item_trait.attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "async_trait")
+    })
+}
+
+/// `#[async_trait]` for generated impls, when the trait uses it for async methods
+fn async_trait_attr(item_trait: &ItemTrait) -> Option<TokenStream2> {
+    let has_async = trait_methods(item_trait).any(|m| m.sig.asyncness.is_some());
+
+    (uses_async_trait(item_trait) && has_async).then(|| quote! { #[::kit::async_trait] })
+}
+
+/// Whether the method opts out of `dyn` with `where Self: Sized`
+fn requires_sized(method: &TraitItemFn) -> bool {
+    let Some(where_clause) = &method.sig.generics.where_clause else {
+        return false;
+    };
+    where_clause.predicates.iter().any(|predicate| match predicate {
+        WherePredicate::Type(predicate) => {
+            matches!(&predicate.bounded_ty, Type::Path(ty) if ty.path.is_ident("Self"))
+                && predicate.bounds.iter().any(|bound| {
+                    matches!(bound, TypeParamBound::Trait(b) if b.path.is_ident("Sized"))
+                })
+        }
+        _ => false,
+    })
+}
+
+/// Whether a type mentions `Self` or `impl Trait`
+fn find_unsupported_type(ty: &Type) -> Option<&'static str> {
+    struct Finder(Option<&'static str>);
+
+    impl<'ast> Visit<'ast> for Finder {
+        fn visit_type_impl_trait(&mut self, _: &'ast syn::TypeImplTrait) {
+            self.0.get_or_insert("`impl Trait`");
+        }
+
+        fn visit_path(&mut self, path: &'ast syn::Path) {
+            if path.is_ident("Self") {
+                self.0.get_or_insert("`Self`");
+            }
+            visit::visit_path(self, path);
+        }
+    }
+
+    let mut finder = Finder(None);
+    finder.visit_type(ty);
+    finder.0
+}
+
+/// Errors for trait items that make `dyn Trait` impossible, combined
+fn dyn_compatibility_errors(item_trait: &ItemTrait) -> Option<syn::Error> {
+    let mut errors: Vec<syn::Error> = Vec::new();
+    let hint = "add `where Self: Sized` to keep it off `dyn` services";
+
+    for item in &item_trait.items {
+        match item {
+            TraitItem::Const(constant) => errors.push(syn::Error::new_spanned(
+                &constant.ident,
+                "service traits are used as `dyn Trait`, which can't have associated constants",
+            )),
+            TraitItem::Fn(method) if !requires_sized(method) => {
+                let sig = &method.sig;
+                if sig.receiver().is_none() {
+                    errors.push(syn::Error::new_spanned(
+                        &sig.ident,
+                        format!(
+                            "`{}` has no `self` receiver, so it can't be called on a `dyn` service; {}",
+                            sig.ident, hint
+                        ),
+                    ));
+                    continue;
+                }
+                if let Some(param) = sig.generics.type_params().next() {
+                    errors.push(syn::Error::new_spanned(
+                        param,
+                        format!(
+                            "`{}` is generic, so it can't be called on a `dyn` service; {}",
+                            sig.ident, hint
+                        ),
+                    ));
+                }
+                let arg_types = sig.inputs.iter().filter_map(|input| match input {
+                    FnArg::Typed(typed) => Some(&*typed.ty),
+                    FnArg::Receiver(_) => None,
+                });
+                let output = match &sig.output {
+                    ReturnType::Type(_, ty) => Some(&**ty),
+                    ReturnType::Default => None,
+                };
+                for ty in arg_types.chain(output) {
+                    if let Some(what) = find_unsupported_type(ty) {
+                        errors.push(syn::Error::new_spanned(
+                            ty,
+                            format!(
+                                "`{}` uses {} in its signature, so it can't be called on a `dyn` service; {}",
+                                sig.ident, what, hint
+                            ),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    errors.into_iter().reduce(|mut combined, error| {
+        combined.combine(error);
+        combined
+    })
+}
+
+/// Generate the proxy used by `TestContainer::spy::<dyn Trait>()`
+///
+/// Every `&self` method records its name and `Debug`-formatted arguments,
+/// then delegates to the wrapped binding. Generic traits get no proxy.
+fn spy_proxy(item_trait: &ItemTrait) -> Option<TokenStream2> {
+    if !item_trait.generics.params.is_empty() {
+        return None;
+    }
+
+    let trait_name = &item_trait.ident;
+    let proxy_name = format_ident!("__KitSpy{}", trait_name);
+
+    let methods: Vec<TokenStream2> = trait_methods(item_trait).filter_map(spy_method).collect();
+    let async_trait_attr = async_trait_attr(item_trait);
+
+    Some(quote! {
+        const _: () = {
+            struct #proxy_name {
+                inner: ::std::sync::Arc<dyn #trait_name>,
+                recorder: ::kit::container::testing::SpyRecorder,
+            }
+
+            #async_trait_attr
+            impl #trait_name for #proxy_name {
+                #(#methods)*
+            }
+
+            impl ::kit::container::testing::Spyable for dyn #trait_name {
+                fn spy(
+                    inner: ::std::sync::Arc<Self>,
+                    recorder: ::kit::container::testing::SpyRecorder,
+                ) -> ::std::sync::Arc<Self> {
+                    ::std::sync::Arc::new(#proxy_name { inner, recorder })
+                }
+            }
+        };
+    })
+}
+
+/// A recording, delegating implementation of one trait method
+///
+/// Methods that can't be called through `dyn` keep their default body, or
+/// panic if they have none.
+fn spy_method(method: &TraitItemFn) -> Option<TokenStream2> {
+    let mut sig = method.sig.clone();
+    let name = &sig.ident;
+    let name_str = name.to_string();
+
+    if !takes_ref_self(method) {
+        if method.default.is_some() {
+            return None;
+        }
+        return Some(quote! {
+            #sig {
+                unimplemented!(concat!("spies can't forward ", #name_str))
+            }
+        });
+    }
+
+    // Argument patterns may destructure, so rebind them to plain names
+    let mut args = Vec::new();
+    for (i, input) in sig.inputs.iter_mut().enumerate() {
+        if let FnArg::Typed(typed) = input {
+            let arg = format_ident!("__arg{}", i);
+            *typed.pat = syn::parse_quote!(#arg);
+            args.push(arg);
+        }
+    }
+
+    let await_call = sig.asyncness.is_some().then(|| quote! { .await });
+
+    Some(quote! {
+        #sig {
+            use ::kit::container::testing::{SpyDebug as _, SpyOpaque as _};
+            self.recorder.record(
+                #name_str,
+                vec![#((&::kit::container::testing::SpyArg(&#args)).spy_format()),*],
+            );
+            self.inner.#name(#(#args),*) #await_call
+        }
+    })
+}
+
+/// Generate `Mock{Trait}`, whose methods call closures set with `on_{method}`
+///
+/// Methods without a closure panic, or run the trait's default body if it has one.
+fn mock_type(item_trait: &ItemTrait) -> syn::Result<TokenStream2> {
+    if !item_trait.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_trait.generics,
+            "#[service(mock)] doesn't support generic traits",
+        ));
+    }
+
+    let vis = &item_trait.vis;
+    let trait_name = &item_trait.ident;
+    let mock_name = format_ident!("Mock{}", trait_name);
+    let mock_name_str = mock_name.to_string();
+
+    let mut fields = Vec::new();
+    let mut setters = Vec::new();
+    let mut methods = Vec::new();
+
+    for method in trait_methods(item_trait) {
+        let mut sig = method.sig.clone();
+        let name = &method.sig.ident;
+        let name_str = name.to_string();
+
+        // Closures can't be generic, so only lifetimes are supported
+        let generic_types = sig.generics.type_params().next().is_some();
+        if !takes_ref_self(method) || generic_types {
+            if method.default.is_none() {
+                methods.push(quote! {
+                    #sig {
+                        unimplemented!(concat!(#mock_name_str, " can't mock ", #name_str))
+                    }
+                });
+            }
+            continue;
+        }
+
+        let mut args = Vec::new();
+        let mut arg_patterns = Vec::new();
+        let mut arg_types = Vec::new();
+        for (i, input) in sig.inputs.iter_mut().enumerate() {
+            if let FnArg::Typed(typed) = input {
+                let arg = format_ident!("__arg{}", i);
+                arg_patterns.push(std::mem::replace(&mut *typed.pat, syn::parse_quote!(#arg)));
+                args.push(arg);
+                arg_types.push(typed.ty.clone());
+            }
+        }
+        let output = match &sig.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => quote! { #ty },
+        };
+        let lifetimes: Vec<_> = sig.generics.lifetimes().map(|l| &l.lifetime).collect();
+        let for_lifetimes = (!lifetimes.is_empty()).then(|| quote! { for<#(#lifetimes),*> });
+        let closure = quote! { #for_lifetimes Fn(#(#arg_types),*) -> #output + Send + Sync };
+
+        let setter = format_ident!("on_{}", name);
+        let setter_doc = format!("Set what `{}` returns, computed from its arguments", name);
+        let unconfigured = format!(
+            "{}::{} was called, but no return was configured with .{}()",
+            mock_name, name, setter
+        );
+
+        fields.push(quote! {
+            #name: ::std::option::Option<::std::boxed::Box<dyn #closure>>,
+        });
+        setters.push(quote! {
+            #[doc = #setter_doc]
+            pub fn #setter(mut self, f: impl #closure + 'static) -> Self {
+                self.#name = ::std::option::Option::Some(::std::boxed::Box::new(f));
+                self
+            }
+        });
+        // Unconfigured methods fall back to the trait's default body, if any
+        let fallback = match &method.default {
+            Some(default) => {
+                let stmts = &default.stmts;
+                quote! {
+                    {
+                        #(let #arg_patterns = #args;)*
+                        #(#stmts)*
+                    }
+                }
+            }
+            None => quote! { panic!(#unconfigured) },
+        };
+        methods.push(quote! {
+            #sig {
+                match &self.#name {
+                    ::std::option::Option::Some(f) => f(#(#args),*),
+                    ::std::option::Option::None => #fallback,
+                }
+            }
+        });
+    }
+
+    let doc = format!(
+        "Mock implementation of [`{}`], generated by `#[service(mock)]`",
+        trait_name
+    );
+    let async_trait_attr = async_trait_attr(item_trait);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(::std::default::Default)]
+        #vis struct #mock_name {
+            #(#fields)*
+        }
+
+        impl #mock_name {
+            /// A mock with no configured returns
+            pub fn new() -> Self {
+                ::std::default::Default::default()
+            }
+
+            #(#setters)*
+
+            /// Bind this mock in the active test container (see `TestContainer::fake`)
+            pub fn bind(self) {
+                assert!(
+                    ::kit::container::testing::TestContainer::is_active(),
+                    concat!(#mock_name_str, "::bind() needs TestContainer::fake()")
+                );
+                ::kit::container::testing::TestContainer::bind::<dyn #trait_name>(
+                    ::std::sync::Arc::new(self)
+                );
+            }
+        }
+
+        #async_trait_attr
+        impl #trait_name for #mock_name {
+            #(#methods)*
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_service_args() {
+        let args: ServiceArgs = syn::parse_quote!(impl = RealCache, fake = FakeCache, mock);
+        assert!(args.impl_type.unwrap().is_ident("RealCache"));
+        assert!(args.fake_type.unwrap().is_ident("FakeCache"));
+        assert!(args.mock);
+
+        let args: ServiceArgs = syn::parse_quote!(crate::cache::RedisCache);
+        assert!(args.impl_type.is_some());
+        assert!(!args.mock);
+
+        let args: ServiceArgs = syn::parse_quote!(mock);
+        assert!(args.impl_type.is_none() && args.mock);
+    }
+
+    #[test]
+    fn test_dyn_compatibility_errors() {
+        let item_trait: ItemTrait = syn::parse_quote! {
+            trait Repository {
+                const TABLE: &'static str;
+                fn new() -> Self;
+                fn find<T>(&self, id: T);
+                fn merge(&self, other: &Self) -> impl Iterator<Item = u8>;
+                fn create() -> Self where Self: Sized;
+                fn all(&self) -> Vec<Self::Row>;
+            }
+        };
+
+        let messages: Vec<String> = dyn_compatibility_errors(&item_trait)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect();
+
+        assert_eq!(messages.len(), 5, "{:#?}", messages);
+        assert!(messages[0].contains("associated constants"));
+        assert!(messages[1].contains("`new` has no `self` receiver"));
+        assert!(messages[2].contains("`find` is generic"));
+        assert!(messages[3].contains("`merge` uses `Self`"));
+        assert!(messages[4].contains("`merge` uses `impl Trait`"));
+    }
+
+    #[test]
+    fn test_dyn_compatible_trait_has_no_errors() {
+        let item_trait: ItemTrait = syn::parse_quote! {
+            trait HttpClient {
+                async fn get(&self, url: &str) -> Result<String, Error>;
+                fn timeout(&self) -> u64 { 30 }
+                fn with_timeout<T: Into<u64>>(self, timeout: T) -> Self where Self: Sized;
+            }
+        };
+
+        assert!(dyn_compatibility_errors(&item_trait).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+//! `#[sluggable]` attribute macro for SeaORM models
+//!
+//! Adds a `before_save` hook to a model's `ActiveModelBehavior` impl that
+//! fills the slug column from a source column on insert.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Ident, ImplItem, ItemImpl, LitStr};
+
+/// Parse the macro attributes
+struct SluggableArgs {
+    source: Option<LitStr>,
+    column: Option<LitStr>,
+}
+
+impl syn::parse::Parse for SluggableArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut source = None;
+        let mut column = None;
+
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            let value: LitStr = input.parse()?;
+
+            if ident == "source" {
+                source = Some(value);
+            } else if ident == "column" {
+                column = Some(value);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "Unknown #[sluggable] option, expected `source` or `column`",
+                ));
+            }
+
+            if input.peek(syn::Token![,]) {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        Ok(Self { source, column })
+    }
+}
+
+pub fn sluggable_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as SluggableArgs);
+    let mut input = parse_macro_input!(input as ItemImpl);
+
+    let is_behavior = input
+        .trait_
+        .as_ref()
+        .and_then(|(_, path, _)| path.segments.last())
+        .is_some_and(|segment| segment.ident == "ActiveModelBehavior");
+    if !is_behavior {
+        return syn::Error::new_spanned(
+            &input.self_ty,
+            "#[sluggable] must be placed on `impl ActiveModelBehavior for ActiveModel`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let Some(source) = args.source else {
+        return syn::Error::new(
+            Span::call_site(),
+            "#[sluggable] requires a source column, e.g. #[sluggable(source = \"title\")]",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let has_before_save = input
+        .items
+        .iter()
+        .any(|item| matches!(item, ImplItem::Fn(method) if method.sig.ident == "before_save"));
+    if has_before_save {
+        return syn::Error::new_spanned(
+            &input.self_ty,
+            "#[sluggable] defines `before_save`; call `kit::slug::unique_slug` from your own `before_save` instead",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let column = args
+        .column
+        .unwrap_or_else(|| LitStr::new("slug", Span::call_site()));
+    let source_variant = column_variant(&source);
+    let column_variant = column_variant(&column);
+
+    let hook: ImplItem = syn::parse_quote! {
+        async fn before_save<C>(mut self, db: &C, insert: bool) -> ::std::result::Result<Self, ::sea_orm::DbErr>
+        where
+            C: ::sea_orm::ConnectionTrait,
+        {
+            if insert {
+                ::kit::slug::fill_unique_slug(&mut self, Column::#source_variant, Column::#column_variant, db).await?;
+            }
+            Ok(self)
+        }
+    };
+    input.items.push(hook);
+
+    let has_async_trait = input.attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "async_trait")
+    });
+    let async_trait = (!has_async_trait).then(|| quote! { #[::kit::async_trait] });
+
+    TokenStream::from(quote! {
+        #async_trait
+        #input
+    })
+}
+
+/// SeaORM `Column` variant for a column name, e.g. `seo_title` -> `SeoTitle`
+fn column_variant(name: &LitStr) -> Ident {
+    let variant: String = name
+        .value()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    Ident::new(&variant, name.span())
+}
@@ -0,0 +1,206 @@
+//! `test_each!` macro for table-driven tests
+//!
+//! Generates one test per case, named by formatting the case values into
+//! the name template, similar to Jest's test.each.
+
+use crate::test_macro::{expand_test, is_test_database, to_snake_case, TestFn};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, Expr, Lit, LitStr, Token, UnOp};
+
+/// Arguments for the test_each! macro
+/// Supports: test_each!([(1, 2, 3), ...], "adds {0} and {1}", async fn(a, b, expected) { ... })
+///           test_each!(["a", "b"], "accepts {0}", fn(input: &str) { ... })
+struct TestEachArgs {
+    cases: Vec<Expr>,
+    name: LitStr,
+    test_fn: TestFn,
+}
+
+impl Parse for TestEachArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        bracketed!(content in input);
+        let cases = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect();
+        input.parse::<Token![,]>()?;
+
+        let name: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let test_fn: TestFn = input.parse()?;
+
+        Ok(Self {
+            cases,
+            name,
+            test_fn,
+        })
+    }
+}
+
+/// Split a case into one value per parameter
+///
+/// A single parameter takes the whole case; otherwise the case must be a
+/// tuple with one element per parameter.
+fn case_values(case: &Expr, param_count: usize) -> syn::Result<Vec<Expr>> {
+    if param_count == 1 {
+        return Ok(vec![case.clone()]);
+    }
+    match case {
+        Expr::Tuple(tuple) if tuple.elems.len() == param_count => {
+            Ok(tuple.elems.iter().cloned().collect())
+        }
+        _ => Err(syn::Error::new_spanned(
+            case,
+            format!("expected a tuple of {} values", param_count),
+        )),
+    }
+}
+
+/// How a value appears in the test name
+fn display_value(value: &Expr) -> String {
+    match value {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => s.value(),
+            other => other.to_token_stream().to_string(),
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            format!("-{}", display_value(&unary.expr))
+        }
+        other => other.to_token_stream().to_string(),
+    }
+}
+
+/// Replace `{0}`, `{1}`, ... in the template with the case values
+///
+/// `{{` and `}}` produce literal braces.
+fn format_name(template: &str, values: &[String]) -> Result<String, String> {
+    let mut name = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                name.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                name.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => index.push(c),
+                        None => return Err("unclosed `{` in test name".to_string()),
+                    }
+                }
+                let value = index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| values.get(i))
+                    .ok_or_else(|| {
+                        format!(
+                            "`{{{}}}` doesn't match a case value (cases have {} values)",
+                            index,
+                            values.len()
+                        )
+                    })?;
+                name.push_str(value);
+            }
+            c => name.push(c),
+        }
+    }
+
+    Ok(name)
+}
+
+pub fn test_each_impl(input: TokenStream) -> TokenStream {
+    let args = match syn::parse::<TestEachArgs>(input) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // The TestDatabase parameter is provided by the test, the rest by the cases
+    let value_params: Vec<_> = args
+        .test_fn
+        .params
+        .iter()
+        .filter(|p| !is_test_database(&p.ty))
+        .collect();
+
+    let mut fn_names = Vec::new();
+    let mut output = TokenStream2::new();
+
+    for (index, case) in args.cases.iter().enumerate() {
+        let values = match case_values(case, value_params.len()) {
+            Ok(values) => values,
+            Err(e) => return e.to_compile_error().into(),
+        };
+
+        let displayed: Vec<String> = values.iter().map(display_value).collect();
+        let name_str = match format_name(&args.name.value(), &displayed) {
+            Ok(name) => name,
+            Err(e) => return syn::Error::new(args.name.span(), e).to_compile_error().into(),
+        };
+
+        // Function names must be valid and unique within the module
+        let mut fn_name = to_snake_case(&name_str);
+        if fn_name.is_empty() || fn_name.starts_with(|c: char| c.is_ascii_digit()) {
+            fn_name = format!("case_{}", fn_name);
+        }
+        if fn_names.contains(&fn_name) {
+            fn_name = format!("{}_{}", fn_name, index);
+        }
+        fn_names.push(fn_name.clone());
+
+        let bindings = value_params.iter().zip(&values).map(|(param, value)| {
+            let name = &param.name;
+            match &param.ty {
+                Some(ty) => quote! { let #name: #ty = #value; },
+                None => quote! { let #name = #value; },
+            }
+        });
+        let bindings = quote! { #(#bindings)* };
+
+        output.extend(expand_test(
+            &name_str,
+            &format_ident!("{}", fn_name),
+            &args.test_fn,
+            bindings,
+        ));
+    }
+
+    output.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_name() {
+        let values = vec!["1".to_string(), "-2".to_string()];
+
+        assert_eq!(
+            format_name("adds {0} and {1}", &values).unwrap(),
+            "adds 1 and -2"
+        );
+        assert_eq!(format_name("{{{0}}}", &values).unwrap(), "{1}");
+        assert!(format_name("uses {2}", &values).is_err());
+        assert!(format_name("uses {0", &values).is_err());
+    }
+
+    #[test]
+    fn test_display_value() {
+        assert_eq!(display_value(&syn::parse_quote!("Ada")), "Ada");
+        assert_eq!(display_value(&syn::parse_quote!(-1)), "-1");
+        assert_eq!(display_value(&syn::parse_quote!(2.5)), "2.5");
+    }
+}
@@ -7,10 +7,10 @@ use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream};
-use syn::{braced, parenthesized, Ident, LitStr, Token, Type};
+use syn::{braced, parenthesized, Attribute, Ident, LitStr, Token, Type};
 
 /// Convert a string to snake_case for function names
-fn to_snake_case(name: &str) -> String {
+pub(crate) fn to_snake_case(name: &str) -> String {
     let mut result = String::new();
     let mut prev_is_uppercase = false;
 
@@ -42,27 +42,65 @@ fn to_snake_case(name: &str) -> String {
 }
 
 /// Parameter in the function signature
-struct FnParam {
-    name: Ident,
-    ty: Type,
+///
+/// The type is optional so `test_each!` can infer it from the cases.
+pub(crate) struct FnParam {
+    pub(crate) name: Ident,
+    pub(crate) ty: Option<Type>,
 }
 
-/// Arguments for the test! macro
-/// Supports: test!("name", async fn(db: TestDatabase) { ... })
-///           test!("name", async fn() { ... })
-///           test!("name", fn() { ... })
-struct TestArgs {
-    name: LitStr,
-    is_async: bool,
-    params: Vec<FnParam>,
-    body: TokenStream2,
+/// Hooks woven in by an enclosing `describe!`
+///
+/// `describe!` appends them as `, __hooks { before_all before { {..} } after { {..} } }`.
+#[derive(Default)]
+pub(crate) struct TestHooks {
+    before_all: bool,
+    before_each: Vec<TokenStream2>,
+    after_each: Vec<TokenStream2>,
 }
 
-impl Parse for TestArgs {
+impl Parse for TestHooks {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        // Parse the test name string
-        let name: LitStr = input.parse()?;
-        input.parse::<Token![,]>()?;
+        let mut hooks = TestHooks::default();
+        while !input.is_empty() {
+            let section: Ident = input.parse()?;
+            let slot = match section.to_string().as_str() {
+                "before_all" => {
+                    hooks.before_all = true;
+                    continue;
+                }
+                "before" => &mut hooks.before_each,
+                "after" => &mut hooks.after_each,
+                _ => return Err(syn::Error::new(section.span(), "unknown test! hook")),
+            };
+            let content;
+            braced!(content in input);
+            while !content.is_empty() {
+                let hook;
+                braced!(hook in content);
+                slot.push(hook.parse()?);
+            }
+        }
+        Ok(hooks)
+    }
+}
+
+/// The test function: `async fn(db: TestDatabase) { ... }`, `fn() { ... }`, ...
+///
+/// May start with attributes such as `#[should_panic]`, and is followed by
+/// the hooks of an enclosing `describe!`, if any.
+pub(crate) struct TestFn {
+    pub(crate) attrs: Vec<Attribute>,
+    pub(crate) is_async: bool,
+    pub(crate) params: Vec<FnParam>,
+    pub(crate) body: TokenStream2,
+    pub(crate) hooks: TestHooks,
+}
+
+impl Parse for TestFn {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Attributes for the generated test function
+        let attrs = input.call(Attribute::parse_outer)?;
 
         // Check for async keyword
         let is_async = if input.peek(Token![async]) {
@@ -82,8 +120,12 @@ impl Parse for TestArgs {
         let mut params = Vec::new();
         while !content.is_empty() {
             let param_name: Ident = content.parse()?;
-            content.parse::<Token![:]>()?;
-            let param_type: Type = content.parse()?;
+            let param_type = if content.peek(Token![:]) {
+                content.parse::<Token![:]>()?;
+                Some(content.parse::<Type>()?)
+            } else {
+                None
+            };
             params.push(FnParam {
                 name: param_name,
                 ty: param_type,
@@ -99,18 +141,54 @@ impl Parse for TestArgs {
         braced!(body_content in input);
         let body: TokenStream2 = body_content.parse()?;
 
+        // Hooks from an enclosing describe!
+        let mut hooks = TestHooks::default();
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if !input.is_empty() {
+                let marker: Ident = input.parse()?;
+                if marker != "__hooks" {
+                    return Err(syn::Error::new(marker.span(), "unexpected test! argument"));
+                }
+                let content;
+                braced!(content in input);
+                hooks = content.parse()?;
+            }
+        }
+
         Ok(Self {
-            name,
+            attrs,
             is_async,
             params,
             body,
+            hooks,
         })
     }
 }
 
+/// Arguments for the test! macro
+/// Supports: test!("name", async fn(db: TestDatabase) { ... })
+///           test!("name", async fn() { ... })
+///           test!("name", fn() { ... })
+struct TestArgs {
+    name: LitStr,
+    test_fn: TestFn,
+}
+
+impl Parse for TestArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse the test name string
+        let name: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let test_fn: TestFn = input.parse()?;
+
+        Ok(Self { name, test_fn })
+    }
+}
+
 /// Check if a type path ends with "TestDatabase"
-fn is_test_database(ty: &Type) -> bool {
-    if let Type::Path(type_path) = ty {
+pub(crate) fn is_test_database(ty: &Option<Type>) -> bool {
+    if let Some(Type::Path(type_path)) = ty {
         if let Some(segment) = type_path.path.segments.last() {
             return segment.ident == "TestDatabase";
         }
@@ -126,7 +204,40 @@ pub fn test_impl(input: TokenStream) -> TokenStream {
 
     let name_str = args.name.value();
     let fn_name = format_ident!("{}", to_snake_case(&name_str));
-    let body = args.body;
+
+    expand_test(&name_str, &fn_name, &args.test_fn, TokenStream2::new()).into()
+}
+
+/// Generate a test function
+///
+/// `bindings` are statements placed at the start of the body, used by
+/// `test_each!` to bind the case values to the parameters.
+pub(crate) fn expand_test(
+    name_str: &str,
+    fn_name: &Ident,
+    args: &TestFn,
+    bindings: TokenStream2,
+) -> TokenStream2 {
+    let body = &args.body;
+    let attrs = &args.attrs;
+
+    // before_each hooks share the test's scope, so their bindings (and guards
+    // such as `TestContainer::fake()`) stay alive for the body and after_each
+    let before_each = &args.hooks.before_each;
+    let after_each = &args.hooks.after_each;
+    let before_all = if !args.hooks.before_all {
+        quote! {}
+    } else if args.is_async {
+        quote! { __kit_before_all().await; }
+    } else {
+        quote! {
+            ::tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to start a runtime for before_all")
+                .block_on(__kit_before_all());
+        }
+    };
 
     // Check if any parameter is TestDatabase
     let has_db_param = args.params.iter().any(|p| is_test_database(&p.ty));
@@ -140,62 +251,93 @@ pub fn test_impl(input: TokenStream) -> TokenStream {
             let db_param_name = &db_param.unwrap().name;
             let output = quote! {
                 #[::kit::kit_test]
+                #(#attrs)*
                 async fn #fn_name(#db_param_name: ::kit::testing::TestDatabase) {
                     // Set the test name for expect! macro output
                     ::kit::testing::set_current_test_name(Some(#name_str.to_string()));
 
-                    // Run the test body
-                    let __test_result = async {
+                    #before_all
+                    #(#before_each)*
+
+                    // Run the test body, holding a panic until after_each has run
+                    let __test_result = ::kit::testing::__catch_unwind(async {
+                        #bindings
                         #body
-                    }.await;
+                    }).await;
+
+                    #(#after_each)*
 
                     // Clear the test name
                     ::kit::testing::set_current_test_name(None);
 
-                    __test_result
+                    match __test_result {
+                        Ok(result) => result,
+                        Err(panic) => ::std::panic::resume_unwind(panic),
+                    }
                 }
             };
-            output.into()
+            output
         } else {
             // Async without TestDatabase - still use kit_test for consistency
             let output = quote! {
                 #[::kit::kit_test]
+                #(#attrs)*
                 async fn #fn_name() {
                     // Set the test name for expect! macro output
                     ::kit::testing::set_current_test_name(Some(#name_str.to_string()));
 
-                    // Run the test body
-                    let __test_result = async {
+                    #before_all
+                    #(#before_each)*
+
+                    // Run the test body, holding a panic until after_each has run
+                    let __test_result = ::kit::testing::__catch_unwind(async {
+                        #bindings
                         #body
-                    }.await;
+                    }).await;
+
+                    #(#after_each)*
 
                     // Clear the test name
                     ::kit::testing::set_current_test_name(None);
 
-                    __test_result
+                    match __test_result {
+                        Ok(result) => result,
+                        Err(panic) => ::std::panic::resume_unwind(panic),
+                    }
                 }
             };
-            output.into()
+            output
         }
     } else {
         // Sync test - use regular #[test]
         let output = quote! {
-            #[test]
+            // Spelled out, since `use kit::test` shadows the built-in attribute
+            #[::core::prelude::v1::test]
+            #(#attrs)*
             fn #fn_name() {
                 // Set the test name for expect! macro output
                 ::kit::testing::set_current_test_name(Some(#name_str.to_string()));
 
-                // Run the test body
-                let __test_result = {
+                #before_all
+                #(#before_each)*
+
+                // Run the test body, holding a panic until after_each has run
+                let __test_result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+                    #bindings
                     #body
-                };
+                }));
+
+                #(#after_each)*
 
                 // Clear the test name
                 ::kit::testing::set_current_test_name(None);
 
-                __test_result
+                match __test_result {
+                    Ok(result) => result,
+                    Err(panic) => ::std::panic::resume_unwind(panic),
+                }
             }
         };
-        output.into()
+        output
     }
 }